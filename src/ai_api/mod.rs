@@ -301,6 +301,8 @@ impl AiDataApi {
             "get_network_interfaces" => self.tool_get_network_interfaces(params),
             "get_network_bandwidth" => self.tool_get_network_bandwidth(params),
             "get_interface_details" => self.tool_get_interface_details(params),
+            "analyze_network_traffic" => self.tool_analyze_network_traffic(params),
+            "get_nic_details" => self.tool_get_nic_details(params),
 
             // Process tools
             "get_process_list" => self.tool_get_process_list(params),
@@ -371,6 +371,60 @@ pub fn get_all_tool_definitions() -> Vec<ToolDefinition> {
         example: Some("get_interface_details({\"interface_name\": \"eth0\"})".to_string()),
     });
 
+    tools.push(ToolDefinition {
+        name: "analyze_network_traffic".to_string(),
+        description: "Analyze network traffic from a pcap/pcapng file or a short live capture: top talkers, largest flows (5-tuple, bytes, packets, duration), protocol mix, TCP retransmissions/zero windows/resets and DNS latency and failures. Useful for questions like 'What is saturating eth0?'. Live capture requires root or CAP_NET_RAW.".to_string(),
+        parameters: json!({
+            "type": "object",
+            "properties": {
+                "file": {
+                    "type": "string",
+                    "description": "Path of a pcap or pcapng file to analyze. Omit to capture live."
+                },
+                "interface": {
+                    "type": "string",
+                    "description": "Interface for a live capture (e.g., 'eth0'). Default: first active interface"
+                },
+                "duration_secs": {
+                    "type": "integer",
+                    "description": "Live capture duration in seconds (1-60). Default: 5"
+                },
+                "filter": {
+                    "type": "string",
+                    "description": "Optional tcpdump-style filter for a live capture (e.g., 'tcp port 443')"
+                },
+                "top_n": {
+                    "type": "integer",
+                    "description": "Entries per table (talkers, flows, DNS). Default: 10"
+                }
+            },
+            "required": []
+        }),
+        category: ToolCategory::Network,
+        example: Some("analyze_network_traffic({\"interface\": \"eth0\", \"duration_secs\": 5})".to_string()),
+    });
+
+    tools.push(ToolDefinition {
+        name: "get_nic_details".to_string(),
+        description: "Get NIC driver-level details (ethtool): driver/firmware versions, negotiated speed vs supported/advertised/link-partner modes, autoneg, FEC, pause, ring and channel sizes, offloads and normalized error counters (rx_missed, CRC errors, pause frames), plus detected issues such as a link negotiating below what both ends support. Linux only.".to_string(),
+        parameters: json!({
+            "type": "object",
+            "properties": {
+                "interface_name": {
+                    "type": "string",
+                    "description": "Interface name (e.g., 'eth0', 'enp59s0f0')"
+                },
+                "include_stats": {
+                    "type": "boolean",
+                    "description": "Include all vendor statistics and per-queue counters. Default: false"
+                }
+            },
+            "required": ["interface_name"]
+        }),
+        category: ToolCategory::Network,
+        example: Some("get_nic_details({\"interface_name\": \"eth0\"})".to_string()),
+    });
+
     // Process tools
     tools.push(ToolDefinition {
         name: "get_process_list".to_string(),
@@ -1828,6 +1882,71 @@ impl AiDataApi {
         }))
     }
 
+    pub(crate) fn tool_analyze_network_traffic(
+        &mut self,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        use crate::packet_capture::analysis;
+
+        let top_n = params.get("top_n").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+
+        let analysis = match params.get("file").and_then(|v| v.as_str()) {
+            Some(file) => analysis::analyze_file(std::path::Path::new(file), top_n)?,
+            None => {
+                let duration = params
+                    .get("duration_secs")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(5)
+                    .clamp(1, 60) as u32;
+                let config = crate::network_tools::CaptureConfig {
+                    interface: params
+                        .get("interface")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    custom_filter: params
+                        .get("filter")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    // The duration bounds a native capture; the count bounds tcpdump
+                    packet_count: 20_000,
+                    timeout_secs: duration,
+                    ..Default::default()
+                };
+                let result = crate::network_tools::capture_packets(&config)?;
+                analysis::analyze_capture_result(&result, top_n)
+            }
+        };
+
+        Ok(serde_json::to_value(analysis)?)
+    }
+
+    pub(crate) fn tool_get_nic_details(
+        &mut self,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let interface_name = params
+            .get("interface_name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| SimonError::InvalidArgument("interface_name is required".to_string()))?;
+        let include_stats = params
+            .get("include_stats")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let mut details = crate::ethtool::nic_details(interface_name)?;
+        let issues: Vec<String> = details.issues(None).iter().map(|i| i.to_string()).collect();
+        // Drivers expose hundreds of vendor counters; the normalized ones
+        // are enough unless asked for
+        if !include_stats {
+            details.stats.clear();
+            details.queues.clear();
+        }
+
+        let mut value = serde_json::to_value(details)?;
+        value["issues"] = json!(issues);
+        Ok(value)
+    }
+
     // ============== Process Tools ==============
 
     pub(crate) fn tool_get_process_list(
@@ -4,6 +4,10 @@
 //! Can run as client connecting to a remote server, or measure local
 //! network interface throughput.
 //!
+//! These tests stream into a plain TCP sink. To test against (or serve) stock
+//! iperf3 endpoints with result exchange, UDP loss/jitter and reverse mode,
+//! use [`crate::iperf3`].
+//!
 //! # Examples
 //!
 //! ## TCP Bandwidth Test
@@ -65,6 +65,24 @@ enum Commands {
         #[command(subcommand)]
         action: PrivacySubcommand,
     },
+    /// Control GPU power limits, clocks, fans and modes
+    ///
+    /// On Unix, changing settings requires root (effective UID 0); other users
+    /// can only view status.
+    Gpu {
+        #[command(subcommand)]
+        action: GpuSubcommand,
+    },
+    /// Apply CPU tuning profiles (governor, EPP, turbo, limits, C-states)
+    Cpu {
+        #[command(subcommand)]
+        action: CpuSubcommand,
+    },
+    /// Network tools: bandwidth benchmark, NIC details, Wi-Fi links and RDMA ports
+    Net {
+        #[command(subcommand)]
+        action: NetSubcommand,
+    },
 }
 
 
@@ -104,6 +122,14 @@ enum CliSubcommand {
     Memory,
     /// Monitor power statistics
     Power,
+    /// Whole-node power breakdown (measured and estimated components, PSU losses)
+    NodePower,
+    /// Hardware performance counters (IPC, cache/branch miss rates, memory bandwidth)
+    Perf {
+        /// Count one process instead of every CPU (works at perf_event_paranoid 1-2)
+        #[arg(long)]
+        pid: Option<u32>,
+    },
     /// Monitor temperature statistics
     Temperature,
     /// Monitor processes with smart categorization
@@ -118,6 +144,24 @@ enum CliSubcommand {
     Display,
     /// List USB devices
     Usb,
+    /// Analyze network traffic from a pcap/pcapng file or a live capture
+    /// (flows, top talkers, protocol mix, TCP health, DNS)
+    Traffic {
+        /// pcap or pcapng file to analyze (captures live if omitted)
+        file: Option<PathBuf>,
+        /// Interface for a live capture
+        #[arg(long)]
+        interface: Option<String>,
+        /// Live capture duration in seconds
+        #[arg(long, default_value = "5")]
+        duration: u32,
+        /// tcpdump-style filter for a live capture
+        #[arg(long)]
+        filter: Option<String>,
+        /// Entries per table
+        #[arg(long, default_value = "10")]
+        top: usize,
+    },
     /// Show all statistics
     All,
     /// Interactive real-time monitoring mode
@@ -323,6 +367,214 @@ enum PrivacySubcommand {
     Info,
 }
 
+/// GPU control subcommands
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+enum GpuSubcommand {
+    /// Show current control settings and supported ranges
+    Status {
+        /// GPU index (all GPUs if not specified)
+        #[arg(short, long)]
+        gpu: Option<usize>,
+
+        /// Output format (json or text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// Apply control settings (validated against device limits; root on Unix)
+    Set {
+        /// GPU index
+        #[arg(short, long, default_value = "0")]
+        gpu: usize,
+
+        /// Power limit in Watts
+        #[arg(long)]
+        power_limit: Option<f32>,
+
+        /// Lock graphics clocks to a range (e.g., 1200-1800)
+        #[arg(long)]
+        gpu_clocks: Option<simonlib::gpu::ClockRange>,
+
+        /// Lock memory clocks to a range (e.g., 5000-9000)
+        #[arg(long)]
+        mem_clocks: Option<simonlib::gpu::ClockRange>,
+
+        /// Fan speed override in percent
+        #[arg(long)]
+        fan: Option<u32>,
+
+        /// Enable or disable persistence mode (NVIDIA)
+        #[arg(long)]
+        persistence: Option<bool>,
+
+        /// Compute mode: default, exclusive_process, prohibited (NVIDIA)
+        #[arg(long)]
+        compute_mode: Option<simonlib::gpu::ComputeMode>,
+
+        /// Validate and show planned changes without applying them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Keep settings until Ctrl+C, then revert to the previous state
+        #[arg(long)]
+        hold: bool,
+
+        /// Output format (json or text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// Reset clock locks and fan control to driver defaults (root on Unix)
+    Reset {
+        /// GPU index
+        #[arg(short, long, default_value = "0")]
+        gpu: usize,
+
+        /// Release graphics clock lock
+        #[arg(long)]
+        clocks: bool,
+
+        /// Release memory clock lock
+        #[arg(long)]
+        memory_clocks: bool,
+
+        /// Return fan to automatic control
+        #[arg(long)]
+        fan: bool,
+    },
+}
+
+/// CPU tuning profile subcommands
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+enum CpuSubcommand {
+    /// List configured profiles and switching rules
+    Profiles {
+        /// Profile file (default: ~/.config/simon/cpu_profiles.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+    },
+    /// Apply a profile; the previous state is saved for `simon cpu revert`
+    Apply {
+        /// Profile name
+        name: String,
+
+        /// Profile file (default: ~/.config/simon/cpu_profiles.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+
+        /// Show planned changes without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Restore the state saved by the last `simon cpu apply`
+    Revert,
+    /// Switch profiles automatically by rule until Ctrl+C, then restore
+    Auto {
+        /// Profile file (default: ~/.config/simon/cpu_profiles.toml)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+    },
+}
+
+/// Network subcommands: iperf3-compatible benchmark, NIC, Wi-Fi and RDMA diagnostics
+#[cfg(feature = "cli")]
+#[derive(Subcommand)]
+enum NetSubcommand {
+    /// iperf3-compatible bandwidth test against HOST, or serve tests with --server
+    Bench {
+        /// iperf3 server to test against
+        #[arg(required_unless_present = "server")]
+        host: Option<String>,
+
+        /// Run as a server for iperf3 clients
+        #[arg(short, long, conflicts_with = "host")]
+        server: bool,
+
+        /// Server port
+        #[arg(short, long, default_value = "5201")]
+        port: u16,
+
+        /// Use UDP instead of TCP
+        #[arg(short, long)]
+        udp: bool,
+
+        /// Reverse mode: the server sends, the client receives
+        #[arg(short = 'R', long)]
+        reverse: bool,
+
+        /// Number of parallel streams
+        #[arg(short = 'P', long, default_value = "1")]
+        parallel: u32,
+
+        /// Test duration in seconds
+        #[arg(short, long, default_value = "10")]
+        time: u64,
+
+        /// Target bitrate per stream, e.g. 100M (default 1M for UDP, unlimited for TCP)
+        #[arg(short, long)]
+        bitrate: Option<String>,
+
+        /// Interval report period in seconds
+        #[arg(long, default_value = "1.0")]
+        interval: f64,
+
+        /// Read/write size in bytes
+        #[arg(short, long)]
+        length: Option<usize>,
+
+        /// Server: exit after one test
+        #[arg(short = '1', long)]
+        one_off: bool,
+
+        /// Output format (json or text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// NIC driver details: link negotiation, FEC, rings, offloads and error counters
+    Nic {
+        /// Interface (all interfaces if not specified)
+        interface: Option<String>,
+
+        /// Show all vendor statistics and per-queue counters
+        #[arg(short, long)]
+        stats: bool,
+
+        /// Re-check every N seconds and report error counters that grow
+        #[arg(short, long)]
+        watch: Option<u64>,
+
+        /// Output format (json or text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// WiFi link quality: SSID/BSSID, channel, signal and noise, bitrates, retries and roams
+    Wifi {
+        /// Interface (all wireless interfaces if not specified)
+        interface: Option<String>,
+
+        /// Re-sample every N seconds, printing signal, rates, retries and roams
+        #[arg(short, long)]
+        watch: Option<u64>,
+
+        /// Output format (json or text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    /// InfiniBand/RoCE ports: state, link rate, LID/GID, traffic, errors and congestion
+    Rdma {
+        /// RDMA device, e.g. mlx5_0 (all devices if not specified)
+        device: Option<String>,
+
+        /// Re-sample every N seconds, printing rates and port alerts
+        #[arg(short, long)]
+        watch: Option<u64>,
+
+        /// Output format (json or text)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+}
+
 #[cfg(feature = "cli")]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
@@ -397,6 +649,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             handle_privacy_command(action)?;
         }
 
+        // GPU command - validated power/clock/fan control
+        Some(Commands::Gpu { action }) => {
+            handle_gpu_command(action)?;
+        }
+
+        // CPU command - named tuning profiles
+        Some(Commands::Cpu { action }) => {
+            handle_cpu_command(action)?;
+        }
+
+        // Net command - network benchmarks
+        Some(Commands::Net { action }) => {
+            handle_net_command(action)?;
+        }
+
         // Default: launch GUI if available, otherwise TUI
         #[cfg(not(feature = "gui"))]
         None => {
@@ -583,6 +850,66 @@ fn handle_cli_command(
                 print_power_info(&snapshot.power);
             }
         }
+        CliSubcommand::NodePower => {
+            use simonlib::node_power::{NodePowerCollector, PowerCoefficients};
+            let mut collector = NodePowerCollector::new(PowerCoefficients::default());
+            // RAPL and disk busy time need an interval to measure over
+            std::thread::sleep(Duration::from_secs_f64(interval.max(0.5)));
+            let report = collector.sample();
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_node_power(&report);
+            }
+        }
+        CliSubcommand::Perf { pid } => {
+            use simonlib::perf::{CounterSet, PerfConfig, PerfCounter, PerfMonitor, PerfTarget};
+            let window = Duration::from_secs_f64(interval.max(0.1));
+            match *pid {
+                Some(pid) => {
+                    let events: Vec<PerfCounter> = PerfCounter::HARDWARE
+                        .iter()
+                        .chain(PerfCounter::SOFTWARE)
+                        .copied()
+                        .collect();
+                    let mut set = CounterSet::open(PerfTarget::Process(pid), &events)?;
+                    std::thread::sleep(window);
+                    let values = set.read();
+                    let unavailable: Vec<String> = set
+                        .unavailable()
+                        .iter()
+                        .map(|u| format!("{}: {}", u.counter, u.reason))
+                        .collect();
+                    if format == "json" {
+                        println!("{}", serde_json::to_string_pretty(&values)?);
+                    } else {
+                        println!("Process {} over {:.1}s", pid, window.as_secs_f64());
+                        print_perf(&values, None, &unavailable);
+                    }
+                }
+                None => {
+                    let mut monitor = PerfMonitor::new(PerfConfig {
+                        per_cpu: format == "json",
+                        ..Default::default()
+                    })?;
+                    std::thread::sleep(window);
+                    let snapshot = monitor.sample();
+                    if format == "json" {
+                        println!("{}", serde_json::to_string_pretty(&snapshot)?);
+                    } else {
+                        println!(
+                            "All CPUs over {:.1}s (access: {})",
+                            snapshot.elapsed_secs, snapshot.access
+                        );
+                        print_perf(
+                            &snapshot.total,
+                            snapshot.memory_bandwidth.as_ref(),
+                            &snapshot.unavailable,
+                        );
+                    }
+                }
+            }
+        }
         CliSubcommand::Temperature => {
             let mut stats = Simon::with_interval(interval)?;
             let snapshot = stats.snapshot()?;
@@ -732,6 +1059,35 @@ fn handle_cli_command(
                 display_usb()?;
             }
         }
+        CliSubcommand::Traffic {
+            file,
+            interface,
+            duration,
+            filter,
+            top,
+        } => {
+            use simonlib::packet_capture::analysis;
+            let analysis = match file {
+                Some(path) => analysis::analyze_file(path, *top)?,
+                None => {
+                    let config = simonlib::network_tools::CaptureConfig {
+                        interface: interface.clone(),
+                        custom_filter: filter.clone(),
+                        // The duration bounds a native capture; the count bounds tcpdump
+                        packet_count: 20_000,
+                        timeout_secs: *duration,
+                        ..Default::default()
+                    };
+                    let result = simonlib::network_tools::capture_packets(&config)?;
+                    analysis::analyze_capture_result(&result, *top)
+                }
+            };
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&analysis)?);
+            } else {
+                print_capture_analysis(&analysis);
+            }
+        }
         CliSubcommand::Jetson { action } => {
             handle_jetson_command(action)?;
         }
@@ -980,6 +1336,200 @@ fn print_memory_info(memory: &simonlib::core::memory::MemoryStats) {
 }
 
 #[cfg(feature = "cli")]
+fn print_node_power(report: &simonlib::node_power::NodePowerReport) {
+    println!("Node power: {:.1} W", report.node_watts);
+    if let Some(ref wall) = report.wall {
+        println!("  Wall reading: {:.1} W ({:?})", wall.watts, wall.source);
+    } else {
+        println!("  Wall reading: none (modeled from components)");
+    }
+    println!(
+        "  PSU: {:.1}% efficient{}, {:.1} W loss",
+        report.psu_efficiency * 100.0,
+        if report.psu_efficiency_measured { " (measured)" } else { "" },
+        report.psu_loss_watts
+    );
+    println!(
+        "  Components: {:.1} W ({:.1} W measured, {:.1} W estimated)",
+        report.dc_watts, report.measured_watts, report.estimated_watts
+    );
+    if let Some(residual) = report.residual_watts {
+        println!("  Unexplained residual: {:+.1} W", residual);
+    }
+    println!();
+    for c in &report.components {
+        println!(
+            "  {:<28} {:<10} {:>8.1} W  {}",
+            c.name, c.kind, c.watts, c.provenance
+        );
+    }
+}
+
+#[cfg(feature = "cli")]
+fn print_capture_analysis(a: &simonlib::packet_capture::CaptureAnalysis) {
+    let bytes = simonlib::memory_management::format_bytes;
+    let rate = |bps: Option<f64>| match bps {
+        Some(bps) if bps >= 1e6 => format!("{:.1} Mbit/s", bps / 1e6),
+        Some(bps) => format!("{:.1} kbit/s", bps / 1e3),
+        None => "-".to_string(),
+    };
+    let endpoint = |host: &str, port: Option<u16>| match port {
+        Some(p) if host.contains(':') => format!("[{}]:{}", host, p),
+        Some(p) => format!("{}:{}", host, p),
+        None => host.to_string(),
+    };
+
+    println!("{}", "═══ Traffic Analysis ═══".cyan().bold());
+    println!(
+        "  {} {} packets, {} in {:.2}s ({})",
+        format!("{}:", a.source).white().bold(),
+        a.packets,
+        bytes(a.bytes),
+        a.duration_secs,
+        rate(a.bits_per_sec)
+    );
+
+    println!("\n  {}", "Top talkers".white().bold());
+    for t in &a.top_talkers {
+        println!(
+            "    {:<40} {:>8} sent {:>8} received {:>5.1}%",
+            t.address,
+            bytes(t.bytes_sent),
+            bytes(t.bytes_received),
+            t.share_percent
+        );
+    }
+
+    println!("\n  {}", "Protocols".white().bold());
+    for p in &a.protocols {
+        println!(
+            "    {:<10} {:>8} packets {:>8} {:>5.1}%",
+            p.protocol,
+            p.packets,
+            bytes(p.bytes),
+            p.percent_bytes
+        );
+    }
+
+    println!(
+        "\n  {} (top {} of {})",
+        "Flows".white().bold(),
+        a.flows.len(),
+        a.total_flows
+    );
+    for f in &a.flows {
+        let mut line = format!(
+            "    {:<6} {} -> {}  {} / {} packets, {:.2}s, {}",
+            f.protocol,
+            endpoint(&f.src, f.src_port),
+            endpoint(&f.dst, f.dst_port),
+            bytes(f.bytes()),
+            f.packets(),
+            f.duration_secs,
+            rate(f.bits_per_sec)
+        );
+        if f.retransmissions > 0 || f.zero_windows > 0 || f.resets > 0 {
+            line.push_str(&format!(
+                "  [retrans {}, zero-win {}, rst {}]",
+                f.retransmissions, f.zero_windows, f.resets
+            ));
+        }
+        println!("{}", line);
+    }
+
+    let retrans = format!(
+        "{} ({:.2}%)",
+        a.tcp.retransmissions, a.tcp.retransmission_percent
+    );
+    println!(
+        "\n  {} {} segments, {} SYNs, retransmissions {}, zero windows {}, resets {}",
+        "TCP:".white().bold(),
+        a.tcp.segments,
+        a.tcp.syns,
+        if a.tcp.retransmission_percent > 2.0 {
+            retrans.red()
+        } else if a.tcp.retransmission_percent > 0.5 {
+            retrans.yellow()
+        } else {
+            retrans.green()
+        },
+        a.tcp.zero_windows,
+        a.tcp.resets
+    );
+
+    let dns = &a.dns;
+    if dns.queries > 0 || dns.responses > 0 {
+        let latency = match (dns.mean_latency_ms, dns.max_latency_ms) {
+            (Some(mean), Some(max)) => format!(", latency {:.1} ms mean / {:.1} ms max", mean, max),
+            _ => String::new(),
+        };
+        println!(
+            "  {} {} queries, {} responses, {} unanswered, {} errors{}",
+            "DNS:".white().bold(),
+            dns.queries,
+            dns.responses,
+            dns.unanswered,
+            dns.errors,
+            latency
+        );
+        for t in &dns.transactions {
+            println!(
+                "    {:<5} {:<40} {:<10} {} -> {}{}",
+                t.qtype.as_deref().unwrap_or("?"),
+                t.name.as_deref().unwrap_or("?"),
+                t.rcode_name(),
+                t.client,
+                t.server,
+                t.latency_ms
+                    .map(|ms| format!(" {:.1} ms", ms))
+                    .unwrap_or_default()
+            );
+        }
+    }
+
+    for warning in &a.warnings {
+        println!("  {} {}", "Warning:".yellow().bold(), warning);
+    }
+}
+
+#[cfg(feature = "cli")]
+fn print_perf(
+    values: &simonlib::perf::CounterValues,
+    memory: Option<&simonlib::perf::MemoryBandwidth>,
+    unavailable: &[String],
+) {
+    for (counter, value) in &values.counts {
+        println!("  {:<24} {:>18.0}", counter.name(), value);
+    }
+    println!();
+    let ratio = |label: &str, value: Option<f64>, pct: bool| {
+        if let Some(v) = value {
+            if pct {
+                println!("  {:<24} {:>17.2}%", label, v * 100.0);
+            } else {
+                println!("  {:<24} {:>18.2}", label, v);
+            }
+        }
+    };
+    ratio("IPC", values.ipc(), false);
+    ratio("LLC miss rate", values.llc_miss_rate(), true);
+    ratio("LLC MPKI", values.llc_mpki(), false);
+    ratio("Branch miss rate", values.branch_miss_rate(), true);
+    ratio("Frontend stalls", values.frontend_stall_rate(), true);
+    ratio("Backend stalls", values.backend_stall_rate(), true);
+    if let Some(bw) = memory {
+        println!(
+            "  {:<24} {:>12.2} GB/s read, {:.2} GB/s write",
+            "DRAM bandwidth",
+            bw.read_bytes_per_sec / 1e9,
+            bw.write_bytes_per_sec / 1e9
+        );
+    }
+    for entry in unavailable {
+        println!("  unavailable: {}", entry);
+    }
+}
+
 fn print_power_info(power: &simonlib::core::power::PowerStats) {
     println!("{}", "═══ Power Information ═══".cyan().bold());
     let total = power.total_watts();
@@ -2066,6 +2616,9 @@ fn handle_record_command(action: &RecordSubcommand) -> Result<(), Box<dyn std::e
             // Create backend for data collection
             let mut backend = MonitoringBackend::new()?;
 
+            // Per-process TCP rates (Linux sock_diag); disabled if unavailable
+            let mut net_accountant = Some(simonlib::net_accounting::NetAccountant::new());
+
             // Install Ctrl+C handler
             let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
             let r = running.clone();
@@ -2121,6 +2674,22 @@ fn handle_record_command(action: &RecordSubcommand) -> Result<(), Box<dyn std::e
                     gpu_power_mw.push(acc.power_watts.map(|p| (p * 1000.0) as u32).unwrap_or(0));
                 }
 
+                let net_traffic = match net_accountant.as_mut().map(|a| a.refresh()) {
+                    Some(Ok(traffic)) => Some(traffic),
+                    Some(Err(_)) => {
+                        net_accountant = None;
+                        None
+                    }
+                    None => None,
+                };
+                let net_rates = |pid: u32| {
+                    net_traffic
+                        .as_ref()
+                        .and_then(|t| t.process(pid))
+                        .map(|t| (t.rx_bps as u64, t.tx_bps as u64))
+                        .unwrap_or((0, 0))
+                };
+
                 // Collect process snapshots (use top_processes, not processes)
                 let processes: Vec<ProcessSnapshot> = state
                     .top_processes
@@ -2135,8 +2704,8 @@ fn handle_record_command(action: &RecordSubcommand) -> Result<(), Box<dyn std::e
                         gpu_percent: 0.0,  // ProcessState doesn't have gpu_percent
                         disk_read_bps: 0,  // Per-process I/O rates need delta tracking across snapshots
                         disk_write_bps: 0, // Absolute I/O bytes available in ProcessMonitorInfo
-                        net_rx_bps: 0,     // Per-process network rates not tracked by OS
-                        net_tx_bps: 0,     // System-level net rates are in SystemSnapshot
+                        net_rx_bps: net_rates(p.pid).0, // TCP only (sock_diag)
+                        net_tx_bps: net_rates(p.pid).1,
                     })
                     .collect();
 
@@ -2165,6 +2734,10 @@ fn handle_record_command(action: &RecordSubcommand) -> Result<(), Box<dyn std::e
 
                 // Record snapshot
                 recorder.record_snapshot(snapshot)?;
+                recorder.record_filesystems(simonlib::tsdb::FilesystemSnapshot {
+                    timestamp,
+                    filesystems: simonlib::capacity::current_filesystems(),
+                })?;
                 record_count += 1;
 
                 // Print status
@@ -2463,6 +3036,1156 @@ fn handle_record_command(action: &RecordSubcommand) -> Result<(), Box<dyn std::e
     Ok(())
 }
 
+/// Handle GPU control subcommands, authorized as the local operator
+#[cfg(feature = "cli")]
+fn handle_gpu_command(action: &GpuSubcommand) -> Result<(), Box<dyn std::error::Error>> {
+    use simonlib::gpu::control::{self, ControlState, GpuControl, GpuControlRequest};
+
+    let permissions = std::sync::Arc::new(std::sync::RwLock::new(control::local_operator()));
+    let mut devices = control::enumerate_devices();
+    if devices.is_empty() {
+        return Err("No controllable GPUs found".into());
+    }
+
+    let mut take_device = |index: usize| {
+        if index >= devices.len() {
+            return Err(format!(
+                "GPU {} not found ({} controllable GPU(s) detected)",
+                index,
+                devices.len()
+            ));
+        }
+        Ok(devices.remove(index))
+    };
+
+    match action {
+        GpuSubcommand::Status { gpu, format } => {
+            let indices: Vec<usize> = match gpu {
+                Some(i) => vec![*i],
+                None => (0..devices.len()).collect(),
+            };
+            let mut states = Vec::new();
+            for &i in &indices {
+                let device = devices.get(i).ok_or_else(|| format!("GPU {} not found", i))?;
+                states.push((
+                    i,
+                    device.name().unwrap_or_default(),
+                    ControlState::capture(device.as_ref()),
+                ));
+            }
+
+            if format == "json" {
+                let json: Vec<_> = states
+                    .iter()
+                    .map(|(i, name, state)| {
+                        serde_json::json!({ "gpu": i, "name": name, "state": state })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&json)?);
+                return Ok(());
+            }
+
+            let opt = |v: Option<String>| v.unwrap_or_else(|| "N/A".to_string());
+            for (i, name, state) in states {
+                println!("{} {}", format!("GPU {}:", i).cyan().bold(), name.white().bold());
+                println!(
+                    "  Power limit:     {} (range {} - {})",
+                    opt(state.power_limit_watts.map(|w| format!("{:.1}W", w))),
+                    opt(state.min_power_limit_watts.map(|w| format!("{:.1}W", w))),
+                    opt(state.max_power_limit_watts.map(|w| format!("{:.1}W", w)))
+                );
+                println!(
+                    "  Graphics clock:  {} (supported {})",
+                    opt(state.graphics_clock_mhz.map(|c| format!("{} MHz", c))),
+                    opt(state.clock_constraints.graphics.map(|r| r.to_string()))
+                );
+                println!(
+                    "  Memory clock:    {} (supported {})",
+                    opt(state.memory_clock_mhz.map(|c| format!("{} MHz", c))),
+                    opt(state.clock_constraints.memory.map(|r| r.to_string()))
+                );
+                println!(
+                    "  Fan:             {}",
+                    opt(state.fan_speed.map(|f| match f {
+                        simonlib::gpu::FanSpeed::Percent(p) => format!("{}%", p),
+                        simonlib::gpu::FanSpeed::Rpm(r) => format!("{} RPM", r),
+                    }))
+                );
+                println!(
+                    "  Persistence:     {}",
+                    opt(state.persistence_mode.map(|p| p.to_string()))
+                );
+                println!(
+                    "  Compute mode:    {}",
+                    opt(state.compute_mode.map(|m| m.to_string()))
+                );
+                println!();
+            }
+        }
+        GpuSubcommand::Set {
+            gpu,
+            power_limit,
+            gpu_clocks,
+            mem_clocks,
+            fan,
+            persistence,
+            compute_mode,
+            dry_run,
+            hold,
+            format,
+        } => {
+            let request = GpuControlRequest {
+                power_limit_watts: *power_limit,
+                gpu_clocks: *gpu_clocks,
+                memory_clocks: *mem_clocks,
+                fan_speed_percent: *fan,
+                persistence_mode: *persistence,
+                compute_mode: *compute_mode,
+                ..Default::default()
+            };
+            if request.is_empty() {
+                return Err("Nothing to set (see 'simon gpu set --help')".into());
+            }
+
+            let mut ctl = GpuControl::new(take_device(*gpu)?)
+                .dry_run(*dry_run)
+                .revert_on_drop(*hold)
+                .with_permissions(permissions, control::LOCAL_OPERATOR_KEY);
+            let report = ctl.apply(&request)?;
+
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                let header = if report.dry_run { "Planned" } else { "Applied" };
+                println!("{} GPU {}:", header.cyan().bold(), gpu);
+                if report.actions.is_empty() {
+                    println!("  {} no changes needed", "•".dimmed());
+                }
+                for action in &report.actions {
+                    println!("  {} {}", "✓".green(), action);
+                }
+            }
+
+            if *hold && !report.dry_run && ctl.has_pending_revert() {
+                let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+                let r = running.clone();
+                // On failure, dropping `ctl` reverts what was just applied
+                ctrlc::set_handler(move || {
+                    r.store(false, std::sync::atomic::Ordering::SeqCst);
+                })
+                .map_err(|e| format!("Failed to set Ctrl-C handler: {}", e))?;
+
+                println!("{}", "Holding settings, press Ctrl+C to revert...".yellow());
+                while running.load(std::sync::atomic::Ordering::SeqCst) {
+                    std::thread::sleep(Duration::from_millis(200));
+                }
+
+                for action in ctl.revert()? {
+                    println!("  {} {}", "↺".cyan(), action);
+                }
+            }
+        }
+        GpuSubcommand::Reset {
+            gpu,
+            clocks,
+            memory_clocks,
+            fan,
+        } => {
+            // With no flags, reset everything. Each domain is applied separately so
+            // domains the device does not support are skipped.
+            let all = !(*clocks || *memory_clocks || *fan);
+            let mut requests = Vec::new();
+            if all || *clocks {
+                requests.push(GpuControlRequest::new().reset_gpu_clocks());
+            }
+            if all || *memory_clocks {
+                requests.push(GpuControlRequest::new().reset_memory_clocks());
+            }
+            if all || *fan {
+                requests.push(GpuControlRequest::new().reset_fan());
+            }
+
+            let mut ctl = GpuControl::new(take_device(*gpu)?)
+                .with_permissions(permissions, control::LOCAL_OPERATOR_KEY);
+            for request in &requests {
+                match ctl.apply(request) {
+                    Ok(report) => {
+                        for action in report.actions {
+                            println!("  {} {}", "✓".green(), action);
+                        }
+                    }
+                    Err(simonlib::gpu::GpuError::NotSupported) => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn handle_cpu_command(action: &CpuSubcommand) -> Result<(), Box<dyn std::error::Error>> {
+    use simonlib::cpu_profiles::{AppliedProfile, CpuProfilesConfig, CpuTuner, ProfileSwitcher};
+
+    let load = |path: &Option<PathBuf>| match path {
+        Some(p) => CpuProfilesConfig::load_from(p),
+        None => CpuProfilesConfig::load(),
+    };
+    let state_path = AppliedProfile::default_path()?;
+
+    match action {
+        CpuSubcommand::Profiles { config } => {
+            let config = load(config)?;
+            let active = AppliedProfile::load_from(&state_path)?.map(|a| a.profile);
+            for profile in &config.profiles {
+                let marker = if active.as_deref() == Some(profile.name.as_str()) {
+                    "*".green().bold()
+                } else {
+                    " ".normal()
+                };
+                println!("{} {}", marker, profile.name.white().bold());
+                if let Some(ref desc) = profile.description {
+                    println!("    {}", desc.dimmed());
+                }
+            }
+            if !config.rules.is_empty() {
+                println!("\n{}", "Rules (first match wins):".cyan().bold());
+                for rule in &config.rules {
+                    let when: Vec<String> = rule
+                        .when
+                        .iter()
+                        .map(|c| serde_json::to_string(c).unwrap_or_default())
+                        .collect();
+                    println!("  {} <- {}", rule.profile, when.join(" && "));
+                }
+            }
+            if let Some(ref default) = config.default_profile {
+                println!("  {} <- (default)", default);
+            }
+        }
+        CpuSubcommand::Apply {
+            name,
+            config,
+            dry_run,
+        } => {
+            let config = load(config)?;
+            let profile = config
+                .profile(name)
+                .ok_or_else(|| format!("Unknown profile '{}'", name))?;
+            let tuner = CpuTuner::new();
+
+            if *dry_run {
+                println!("{} profile '{}':", "Planned".cyan().bold(), name);
+                let changes = tuner.plan(profile)?;
+                if changes.is_empty() {
+                    println!("  {} no changes needed", "•".dimmed());
+                }
+                for change in changes {
+                    println!("  {} {}", "•".cyan(), change);
+                }
+                return Ok(());
+            }
+
+            // Return to the original state first so the saved journal always points at it
+            if let Some(previous) = AppliedProfile::load_from(&state_path)? {
+                tuner.revert(&previous)?;
+                std::fs::remove_file(&state_path)?;
+            }
+            let applied = tuner.apply(profile)?;
+            applied.save_to(&state_path)?;
+
+            println!("{} profile '{}':", "Applied".cyan().bold(), name);
+            for change in &applied.changes {
+                println!("  {} {}", "✓".green(), change);
+            }
+        }
+        CpuSubcommand::Revert => match AppliedProfile::load_from(&state_path)? {
+            Some(applied) => {
+                CpuTuner::new().revert(&applied)?;
+                std::fs::remove_file(&state_path)?;
+                for change in applied.changes.iter().rev() {
+                    println!("  {} {}: {}", "↺".cyan(), change.setting, change.previous);
+                }
+            }
+            None => println!("No applied CPU profile to revert"),
+        },
+        CpuSubcommand::Auto { config } => {
+            let config = load(config)?;
+            let interval = Duration::from_millis(config.interval_ms.max(100));
+            let mut switcher = ProfileSwitcher::new(config)?;
+
+            let running = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+            let r = running.clone();
+            ctrlc::set_handler(move || {
+                r.store(false, std::sync::atomic::Ordering::SeqCst);
+            })
+            .map_err(|e| format!("Failed to set Ctrl-C handler: {}", e))?;
+
+            println!("{}", "Switching CPU profiles by rule, press Ctrl+C to restore...".yellow());
+            while running.load(std::sync::atomic::Ordering::SeqCst) {
+                match switcher.tick() {
+                    Ok(Some(name)) => println!("  {} {}", "→".green(), name),
+                    Ok(None) => {}
+                    Err(e) => eprintln!("  {} {}", "✗".red(), e),
+                }
+                let deadline = std::time::Instant::now() + interval;
+                while running.load(std::sync::atomic::Ordering::SeqCst)
+                    && std::time::Instant::now() < deadline
+                {
+                    std::thread::sleep(Duration::from_millis(200));
+                }
+            }
+            switcher.restore()?;
+            println!("  {} original CPU settings restored", "↺".cyan());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn handle_net_command(action: &NetSubcommand) -> Result<(), Box<dyn std::error::Error>> {
+    use simonlib::iperf3::{
+        parse_bitrate, run_client_with, IperfConfig, IperfProtocol, IperfServer,
+    };
+
+    match action {
+        NetSubcommand::Bench {
+            host,
+            server,
+            port,
+            udp,
+            reverse,
+            parallel,
+            time,
+            bitrate,
+            interval,
+            length,
+            one_off,
+            format,
+        } => {
+            let json = format == "json";
+            let interval = Duration::from_secs_f64(interval.max(0.1));
+            let print_interval = |i: &simonlib::iperf3::IperfInterval| {
+                if !json {
+                    print_iperf_interval(i);
+                }
+            };
+
+            if *server {
+                let server = IperfServer::bind(("::", *port))
+                    .or_else(|_| IperfServer::bind(("0.0.0.0", *port)))?
+                    .with_interval(interval);
+                if !json {
+                    println!(
+                        "{} listening on port {}",
+                        "iperf3 server".cyan().bold(),
+                        port
+                    );
+                }
+                loop {
+                    match server.accept_test(print_interval) {
+                        Ok(result) if json => {
+                            println!("{}", serde_json::to_string_pretty(&result)?)
+                        }
+                        Ok(result) => print_iperf_result(&result),
+                        Err(e) if *one_off => return Err(e.into()),
+                        Err(e) => eprintln!("  {} {}", "✗".red(), e),
+                    }
+                    if *one_off {
+                        break;
+                    }
+                }
+                return Ok(());
+            }
+
+            let host = host.as_deref().unwrap_or_default();
+            let mut config = IperfConfig::default()
+                .with_protocol(if *udp {
+                    IperfProtocol::Udp
+                } else {
+                    IperfProtocol::Tcp
+                })
+                .with_duration(Duration::from_secs((*time).max(1)))
+                .with_parallel(*parallel)
+                .with_reverse(*reverse)
+                .with_interval(interval);
+            if let Some(bitrate) = bitrate {
+                config = config.with_bitrate(parse_bitrate(bitrate)?);
+            }
+            if let Some(length) = length {
+                config = config.with_length(*length);
+            }
+
+            if !json {
+                println!(
+                    "{} {}:{} ({}, {} stream{}, {} s{})",
+                    "═══ iperf3 ═══".cyan().bold(),
+                    host,
+                    port,
+                    if *udp { "UDP" } else { "TCP" },
+                    config.parallel,
+                    if config.parallel == 1 { "" } else { "s" },
+                    time,
+                    if *reverse { ", reverse" } else { "" }
+                );
+            }
+            let result = run_client_with(host, *port, &config, print_interval)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                print_iperf_result(&result);
+            }
+        }
+        NetSubcommand::Nic {
+            interface,
+            stats,
+            watch,
+            format,
+        } => {
+            use simonlib::ethtool::NicMonitor;
+
+            let interfaces = match interface {
+                Some(name) => vec![name.clone()],
+                None => {
+                    let mut names: Vec<String> = std::fs::read_dir("/sys/class/net")?
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .filter(|name| name != "lo")
+                        .collect();
+                    names.sort();
+                    names
+                }
+            };
+
+            let mut monitor = NicMonitor::new();
+            let mut reports = Vec::new();
+            for name in &interfaces {
+                match monitor.check(name) {
+                    Ok(report) => reports.push(report),
+                    Err(e) if interface.is_some() => return Err(e.into()),
+                    Err(e) => eprintln!("  {} {}: {}", "✗".red(), name, e),
+                }
+            }
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&reports)?);
+            } else {
+                for report in &reports {
+                    print_nic_report(report, *stats);
+                }
+            }
+
+            if let Some(secs) = watch {
+                println!(
+                    "{}",
+                    format!(
+                        "Watching error counters every {}s, press Ctrl+C to stop...",
+                        secs
+                    )
+                    .yellow()
+                );
+                loop {
+                    std::thread::sleep(Duration::from_secs((*secs).max(1)));
+                    for report in &reports {
+                        let name = &report.details.name;
+                        match monitor.check(name) {
+                            Ok(now) => {
+                                for issue in now.issues.iter().filter(|i| {
+                                    matches!(i, simonlib::ethtool::NicIssue::CounterGrowing { .. })
+                                }) {
+                                    println!(
+                                        "  {} {} {}: {}",
+                                        chrono::Local::now().format("%H:%M:%S"),
+                                        "⚠".yellow(),
+                                        name,
+                                        issue
+                                    );
+                                }
+                            }
+                            Err(e) => eprintln!("  {} {}: {}", "✗".red(), name, e),
+                        }
+                    }
+                }
+            }
+        }
+        NetSubcommand::Wifi {
+            interface,
+            watch,
+            format,
+        } => {
+            use simonlib::wireless::{wireless_interfaces, WirelessConfig, WirelessMonitor};
+
+            let interfaces = match interface {
+                Some(name) => vec![name.clone()],
+                None => wireless_interfaces(),
+            };
+            if interfaces.is_empty() {
+                println!("No wireless interfaces found");
+                return Ok(());
+            }
+
+            let mut monitor = WirelessMonitor::new(WirelessConfig::default())?;
+            let mut links = Vec::new();
+            for name in &interfaces {
+                match monitor.check(name) {
+                    Ok(report) => links.push(report.link),
+                    Err(e) if interface.is_some() => return Err(e.into()),
+                    Err(e) => eprintln!("  {} {}: {}", "✗".red(), name, e),
+                }
+            }
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&links)?);
+            } else {
+                for link in &links {
+                    print_wireless_link(link);
+                }
+            }
+
+            if let Some(secs) = watch {
+                println!(
+                    "{}",
+                    format!("Sampling every {}s, press Ctrl+C to stop...", secs).yellow()
+                );
+                loop {
+                    std::thread::sleep(Duration::from_secs((*secs).max(1)));
+                    for name in links.iter().map(|l| &l.interface) {
+                        let now = chrono::Local::now().format("%H:%M:%S");
+                        let report = match monitor.check(name) {
+                            Ok(report) => report,
+                            Err(e) => {
+                                eprintln!("  {} {}: {}", "✗".red(), name, e);
+                                continue;
+                            }
+                        };
+                        if let Some(ref roam) = report.roam {
+                            println!("  {} {} {}", now, "↔".yellow().bold(), roam);
+                        }
+                        let link = &report.link;
+                        let Some(ref station) = link.station else {
+                            println!("  {} {}: {}", now, name, "not associated".dimmed());
+                            continue;
+                        };
+                        let rate = |r: &Option<simonlib::wireless::Bitrate>| {
+                            r.as_ref()
+                                .map(|r| format!("{:.0}", r.mbps))
+                                .unwrap_or_else(|| "-".into())
+                        };
+                        let signal = link
+                            .signal_dbm()
+                            .map(|s| format!("{} dBm", s))
+                            .unwrap_or_else(|| "-".into());
+                        let retries = link
+                            .retry_rate_pct
+                            .map(|r| format!("{:.1}%", r))
+                            .unwrap_or_else(|| "-".into());
+                        let lost = link.beacon_loss_delta.unwrap_or(0);
+                        let lost = if lost > 0 {
+                            format!("beacon loss {}", lost).red().to_string()
+                        } else {
+                            String::new()
+                        };
+                        println!(
+                            "  {} {}: {:>7}  tx {:>6} rx {:>6} Mb/s  retries {:>6}  failed {} {}",
+                            now,
+                            name,
+                            signal,
+                            rate(&station.tx_bitrate),
+                            rate(&station.rx_bitrate),
+                            retries,
+                            link.tx_failed_delta.unwrap_or(0),
+                            lost
+                        );
+                    }
+                }
+            }
+        }
+        NetSubcommand::Rdma {
+            device,
+            watch,
+            format,
+        } => {
+            use simonlib::error::SimonError;
+            use simonlib::rdma::{RdmaConfig, RdmaMonitor};
+
+            let config = RdmaConfig {
+                devices: device.iter().cloned().collect(),
+                ..Default::default()
+            };
+            let mut monitor = match RdmaMonitor::new(config) {
+                Ok(monitor) => monitor,
+                // No /sys/class/infiniband: the RDMA core is not loaded
+                Err(SimonError::FeatureNotAvailable(_)) => {
+                    println!("No RDMA devices found");
+                    return Ok(());
+                }
+                Err(e) => return Err(e.into()),
+            };
+            monitor.sample();
+            std::thread::sleep(Duration::from_secs(1));
+            let snapshot = monitor.sample();
+            if snapshot.devices.is_empty() {
+                match device {
+                    Some(name) => println!("RDMA device {} not found", name),
+                    None => println!("No RDMA devices found"),
+                }
+                return Ok(());
+            }
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&snapshot.devices)?);
+            } else {
+                for dev in &snapshot.devices {
+                    print_rdma_device(dev);
+                }
+            }
+
+            if let Some(secs) = watch {
+                println!(
+                    "{}",
+                    format!("Sampling every {}s, press Ctrl+C to stop...", secs).yellow()
+                );
+                loop {
+                    std::thread::sleep(Duration::from_secs((*secs).max(1)));
+                    let snapshot = monitor.sample();
+                    let now = chrono::Local::now().format("%H:%M:%S");
+                    for alert in &snapshot.alerts {
+                        println!("  {} {} {}", now, "⚠".red().bold(), alert.message.red());
+                    }
+                    for dev in &snapshot.devices {
+                        for port in &dev.ports {
+                            let Some(ref rates) = port.rates else {
+                                continue;
+                            };
+                            let util = rates
+                                .utilization_pct
+                                .map(|u| format!("{:.0}%", u))
+                                .unwrap_or_else(|| "-".into());
+                            let errors = if rates.errors_per_sec > 0.0 {
+                                format!("errors {:.1}/s", rates.errors_per_sec)
+                                    .red()
+                                    .to_string()
+                            } else {
+                                String::new()
+                            };
+                            println!(
+                                "  {} {}/{}: {:<6} tx {:>16} rx {:>16} ({:>4})  ECN {:.0}/s CNP {:.0}/s {}",
+                                now,
+                                dev.name,
+                                port.port,
+                                port.state.to_string(),
+                                format_iperf_rate(rates.tx_bytes_per_sec * 8.0),
+                                format_iperf_rate(rates.rx_bytes_per_sec * 8.0),
+                                util,
+                                rates.ecn_marked_per_sec,
+                                rates.cnp_handled_per_sec,
+                                errors
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn print_nic_report(report: &simonlib::ethtool::NicReport, all_stats: bool) {
+    use simonlib::ethtool::{format_speed, Duplex, LinkSettings, QueueDirection};
+
+    let d = &report.details;
+    let mut header = format!("═══ {} ═══", d.name).cyan().bold().to_string();
+    if let Some(ref drv) = d.driver {
+        header.push_str(&format!(" {} {}", drv.driver, drv.version));
+        if !drv.firmware_version.is_empty() {
+            header.push_str(&format!(", firmware {}", drv.firmware_version));
+        }
+        if !drv.bus_info.is_empty() {
+            header.push_str(&format!(", {}", drv.bus_info));
+        }
+    }
+    println!("{}", header);
+
+    if let Some(ref link) = d.link {
+        let carrier = match d.link_detected {
+            Some(false) => "no carrier".red().to_string(),
+            _ => match link.speed_mbps {
+                Some(speed) => format_speed(speed).green().bold().to_string(),
+                None => "unknown speed".dimmed().to_string(),
+            },
+        };
+        let duplex = match link.duplex {
+            Duplex::Full => "full duplex",
+            Duplex::Half => "half duplex",
+            Duplex::Unknown => "duplex unknown",
+        };
+        println!(
+            "  Link:     {}, {}, autoneg {}, {}",
+            carrier,
+            duplex,
+            if link.autoneg { "on" } else { "off" },
+            link.port
+        );
+        let modes = |label: &str, modes: &[String]| {
+            if let Some(max) = LinkSettings::max_speed(modes) {
+                println!(
+                    "  {:<9} up to {} ({} modes)",
+                    label,
+                    format_speed(max),
+                    modes.iter().filter(|m| m.contains("base")).count()
+                );
+            }
+        };
+        modes("Supports:", &link.supported);
+        modes("Advert.:", &link.advertising);
+        modes("Partner:", &link.partner_advertising);
+    }
+    if let Some(ref fec) = d.fec {
+        println!(
+            "  FEC:      active {}, configured {}",
+            fec.active.join(","),
+            fec.configured.join(",")
+        );
+    }
+    if let Some(pause) = d.pause {
+        println!(
+            "  Pause:    rx {}, tx {}, autoneg {}",
+            if pause.rx { "on" } else { "off" },
+            if pause.tx { "on" } else { "off" },
+            if pause.autoneg { "on" } else { "off" }
+        );
+    }
+    if let Some(rings) = d.rings {
+        println!(
+            "  Rings:    rx {}/{}, tx {}/{}",
+            rings.rx, rings.rx_max, rings.tx, rings.tx_max
+        );
+    }
+    if let Some(ch) = d.channels {
+        println!(
+            "  Channels: combined {}/{}, rx {}/{}, tx {}/{}",
+            ch.combined, ch.max_combined, ch.rx, ch.max_rx, ch.tx, ch.max_tx
+        );
+    }
+    if !d.features.is_empty() {
+        println!(
+            "  Offloads: {} active of {}",
+            d.features.iter().filter(|f| f.active).count(),
+            d.features.len()
+        );
+    }
+
+    let c = &d.counters;
+    let counter = |label: &str, value: Option<u64>| match value {
+        Some(0) => Some(format!("{} 0", label)),
+        Some(v) => Some(format!("{} {}", label, v.to_string().yellow())),
+        None => None,
+    };
+    let counters: Vec<String> = [
+        counter("rx_missed", c.rx_missed),
+        counter("crc", c.rx_crc_errors),
+        counter("length", c.rx_length_errors),
+        counter("fifo", c.rx_fifo_errors),
+        counter("carrier", c.tx_carrier_errors),
+        counter("pause rx", c.rx_pause),
+        counter("pause tx", c.tx_pause),
+        counter("link flaps", c.carrier_changes),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !counters.is_empty() {
+        println!("  Counters: {}", counters.join(", "));
+    }
+
+    if all_stats {
+        for feature in d.features.iter().filter(|f| f.active) {
+            let fixed = if feature.fixed { " [fixed]" } else { "" };
+            println!("    {}{}", feature.name, fixed.dimmed());
+        }
+        for (name, value) in &d.stats {
+            println!("    {:<40} {}", name, value);
+        }
+        for queue in &d.queues {
+            let counters: Vec<String> = queue
+                .counters
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect();
+            let direction = match queue.direction {
+                QueueDirection::Rx => "rx",
+                QueueDirection::Tx => "tx",
+            };
+            println!(
+                "    {}{:<3} {}",
+                direction,
+                queue.queue,
+                counters.join(" ").dimmed()
+            );
+        }
+    }
+
+    for issue in &report.issues {
+        println!("  {} {}", "⚠".yellow().bold(), issue.to_string().yellow());
+    }
+    println!();
+}
+
+#[cfg(feature = "cli")]
+fn print_wireless_link(link: &simonlib::wireless::WirelessLink) {
+    let mut header = format!("═══ {} ═══", link.interface)
+        .cyan()
+        .bold()
+        .to_string();
+    if let Some(ref mode) = link.mode {
+        header.push_str(&format!(" {}", mode));
+    }
+    if let Some(ref mac) = link.mac_address {
+        header.push_str(&format!(", {}", mac));
+    }
+    println!("{}", header);
+
+    let Some(ref station) = link.station else {
+        println!("  {}", "Not associated".dimmed());
+        println!();
+        return;
+    };
+    println!(
+        "  SSID:      {} ({})",
+        link.ssid.as_deref().unwrap_or("<hidden>").bold(),
+        link.bssid.as_deref().unwrap_or("?")
+    );
+
+    let mut channel = Vec::new();
+    if let Some(ch) = link.channel {
+        channel.push(ch.to_string());
+    }
+    if let Some(band) = link.band {
+        channel.push(band.to_string());
+    }
+    if let Some(width) = link.channel_width_mhz {
+        channel.push(format!("{} MHz wide", width));
+    }
+    if let Some(freq) = link.frequency_mhz {
+        channel.push(format!("{} MHz", freq));
+    }
+    if let Some(power) = link.tx_power_dbm {
+        channel.push(format!("tx power {:.0} dBm", power));
+    }
+    println!("  Channel:   {}", channel.join(", "));
+
+    let mut signal = match (link.signal_dbm(), link.quality()) {
+        (Some(dbm), Some(quality)) => {
+            let text = format!("{} dBm ({})", dbm, quality);
+            match quality {
+                simonlib::wireless::SignalQuality::Excellent
+                | simonlib::wireless::SignalQuality::Good => text.green().to_string(),
+                simonlib::wireless::SignalQuality::Fair => text.yellow().to_string(),
+                simonlib::wireless::SignalQuality::Poor => text.red().to_string(),
+            }
+        }
+        _ => "unknown".dimmed().to_string(),
+    };
+    if let Some(noise) = link.noise_dbm {
+        signal.push_str(&format!(", noise {} dBm", noise));
+    }
+    if let Some(snr) = link.snr_db() {
+        signal.push_str(&format!(", SNR {} dB", snr));
+    }
+    if let Some(busy) = link.channel_busy_pct {
+        signal.push_str(&format!(", channel busy {:.0}%", busy));
+    }
+    println!("  Signal:    {}", signal);
+
+    if let Some(ref rate) = station.tx_bitrate {
+        println!("  TX rate:   {}", rate);
+    }
+    if let Some(ref rate) = station.rx_bitrate {
+        println!("  RX rate:   {}", rate);
+    }
+    println!(
+        "  Traffic:   rx {} ({} packets), tx {} ({} packets)",
+        format_iperf_bytes(station.rx_bytes),
+        station.rx_packets,
+        format_iperf_bytes(station.tx_bytes),
+        station.tx_packets
+    );
+
+    let mut errors = Vec::new();
+    if let Some(retries) = station.tx_retries {
+        let share = if station.tx_packets > 0 {
+            format!(
+                " ({:.1} per 100 packets)",
+                retries as f64 / station.tx_packets as f64 * 100.0
+            )
+        } else {
+            String::new()
+        };
+        errors.push(format!("retries {}{}", retries, share));
+    }
+    if let Some(failed) = station.tx_failed {
+        errors.push(format!("failed {}", failed));
+    }
+    if let Some(lost) = station.beacon_loss {
+        errors.push(format!("beacon loss {}", lost));
+    }
+    if let Some(dropped) = station.rx_drop_misc {
+        errors.push(format!("rx dropped {}", dropped));
+    }
+    if !errors.is_empty() {
+        println!("  Errors:    {}", errors.join(", "));
+    }
+
+    let mut session = Vec::new();
+    if let Some(secs) = station.connected_secs {
+        session.push(format!("connected {}", format_duration(secs as u64)));
+    }
+    if let Some(kbps) = station.expected_throughput_kbps {
+        session.push(format!(
+            "expected throughput {:.0} Mb/s",
+            kbps as f64 / 1000.0
+        ));
+    }
+    if !session.is_empty() {
+        println!("  Session:   {}", session.join(", "));
+    }
+    println!();
+}
+
+#[cfg(feature = "cli")]
+fn print_rdma_device(dev: &simonlib::rdma::RdmaDevice) {
+    use simonlib::rdma::{PhysState, PortState};
+
+    let mut header = format!("═══ {} ═══", dev.name).cyan().bold().to_string();
+    if let Some(ref hca) = dev.hca_type {
+        header.push_str(&format!(" {}", hca));
+    }
+    if let Some(ref fw) = dev.fw_ver {
+        header.push_str(&format!(", firmware {}", fw));
+    }
+    println!("{}", header);
+
+    if let Some(ref pci) = dev.pci {
+        let mut line = format!(
+            "{} Gen{} x{}",
+            pci.bdf,
+            pci.current_link_speed.gen_number(),
+            pci.current_link_width
+        );
+        if pci.is_downgraded() {
+            let speed = pci.max_link_speed.unwrap_or(pci.current_link_speed);
+            line = format!(
+                "{} (capable of Gen{} x{})",
+                line.yellow(),
+                speed.gen_number(),
+                pci.max_link_width.max(pci.current_link_width)
+            );
+        }
+        println!("  PCIe:      {}", line);
+    }
+    if let Some(node) = dev.numa_node {
+        // Collapse the CPU list back into ranges
+        let mut ranges: Vec<(u32, u32)> = Vec::new();
+        for &cpu in &dev.local_cpus {
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == cpu => *end = cpu,
+                _ => ranges.push((cpu, cpu)),
+            }
+        }
+        let cpus = ranges
+            .iter()
+            .map(|(a, b)| {
+                if a == b {
+                    a.to_string()
+                } else {
+                    format!("{}-{}", a, b)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        println!("  NUMA:      node {}, CPUs {}", node, cpus);
+    }
+
+    for port in &dev.ports {
+        let state = format!("{} / {}", port.state, port.phys_state);
+        let state = match (port.state, port.phys_state) {
+            (PortState::Active, PhysState::LinkUp) => state.green().to_string(),
+            (PortState::Down, _) | (_, PhysState::Disabled) => state.red().to_string(),
+            _ => state.yellow().to_string(),
+        };
+        let rate = port
+            .rate
+            .as_ref()
+            .map(|r| r.to_string())
+            .unwrap_or_else(|| "rate unknown".into());
+        println!(
+            "  Port {}:    {}, {}, {}",
+            port.port, state, rate, port.link_layer
+        );
+
+        let mut addressing = Vec::new();
+        if let Some(lid) = port.lid {
+            addressing.push(format!("LID 0x{:x}", lid));
+        }
+        if let Some(sm) = port.sm_lid {
+            addressing.push(format!("SM LID 0x{:x}", sm));
+        }
+        if let Some(ref gid) = port.gid {
+            addressing.push(format!("GID {}", gid));
+        }
+        if let Some(ref netdev) = port.netdev {
+            addressing.push(format!("netdev {}", netdev));
+        }
+        if !addressing.is_empty() {
+            println!("    {}", addressing.join(", "));
+        }
+
+        if let Some(ref rates) = port.rates {
+            let mut traffic = format!(
+                "tx {}, rx {}",
+                format_iperf_rate(rates.tx_bytes_per_sec * 8.0),
+                format_iperf_rate(rates.rx_bytes_per_sec * 8.0)
+            );
+            if let Some(util) = rates.utilization_pct {
+                traffic.push_str(&format!(" ({:.0}% of link)", util));
+            }
+            println!("    Traffic:    {}", traffic);
+            let congestion = [
+                ("xmit wait", rates.xmit_wait_per_sec),
+                ("ECN marked", rates.ecn_marked_per_sec),
+                ("CNP sent", rates.cnp_sent_per_sec),
+                ("CNP handled", rates.cnp_handled_per_sec),
+                ("out of buffer", rates.out_of_buffer_per_sec),
+            ]
+            .iter()
+            .filter(|(_, v)| *v > 0.0)
+            .map(|(name, v)| format!("{} {:.0}/s", name, v))
+            .collect::<Vec<_>>();
+            if !congestion.is_empty() {
+                println!("    Congestion: {}", congestion.join(", ").yellow());
+            }
+        }
+        if let (Some(tx), Some(rx)) = (port.tx_bytes(), port.rx_bytes()) {
+            println!(
+                "    Totals:     tx {}, rx {}",
+                format_iperf_bytes(tx),
+                format_iperf_bytes(rx)
+            );
+        }
+
+        let errors = simonlib::rdma::ERROR_COUNTERS
+            .iter()
+            .filter_map(|c| port.counters.get(*c).filter(|v| **v > 0).map(|v| (c, v)))
+            .map(|(c, v)| format!("{} {}", c, v))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            println!("    {} {}", "Errors:".red().bold(), errors.join(", ").red());
+        }
+    }
+    println!();
+}
+#[cfg(feature = "cli")]
+fn format_iperf_bytes(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1024.0 * 1024.0 * 1024.0 {
+        format!("{:.2} GBytes", bytes / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes >= 1024.0 * 1024.0 {
+        format!("{:.2} MBytes", bytes / (1024.0 * 1024.0))
+    } else {
+        format!("{:.2} KBytes", bytes / 1024.0)
+    }
+}
+
+#[cfg(feature = "cli")]
+fn format_iperf_rate(bits_per_second: f64) -> String {
+    if bits_per_second >= 1e9 {
+        format!("{:.2} Gbits/sec", bits_per_second / 1e9)
+    } else if bits_per_second >= 1e6 {
+        format!("{:.2} Mbits/sec", bits_per_second / 1e6)
+    } else {
+        format!("{:.2} Kbits/sec", bits_per_second / 1e3)
+    }
+}
+
+#[cfg(feature = "cli")]
+fn print_iperf_interval(i: &simonlib::iperf3::IperfInterval) {
+    let mut line = format!(
+        "  {:>6.2}-{:<6.2} sec  {:>13}  {:>15}",
+        i.start_secs,
+        i.end_secs,
+        format_iperf_bytes(i.bytes),
+        format_iperf_rate(i.bits_per_second)
+    );
+    match (i.lost_packets, i.packets, i.jitter_ms) {
+        (Some(lost), Some(packets), Some(jitter)) => {
+            line.push_str(&format!("  {:.3} ms  {}/{}", jitter, lost, packets))
+        }
+        (None, Some(packets), _) => line.push_str(&format!("  {} datagrams", packets)),
+        _ => {}
+    }
+    println!("{}", line);
+}
+
+#[cfg(feature = "cli")]
+fn print_iperf_result(result: &simonlib::iperf3::IperfResult) {
+    use simonlib::iperf3::IperfSummary;
+
+    let line = |label: &str, s: &IperfSummary| {
+        let mut text = format!(
+            "  {:>6.2} sec  {:>13}  {:>15}  {:<8}",
+            s.seconds,
+            format_iperf_bytes(s.bytes),
+            format_iperf_rate(s.bits_per_second).white().bold(),
+            label
+        );
+        if let Some(retransmits) = s.retransmits {
+            text.push_str(&format!("  retr {}", retransmits));
+        }
+        if let (Some(lost), Some(packets), Some(percent)) =
+            (s.lost_packets, s.packets, s.lost_percent)
+        {
+            let loss = format!("{}/{} ({:.2}%)", lost, packets, percent);
+            let loss = if percent >= 1.0 {
+                loss.red()
+            } else {
+                loss.green()
+            };
+            text.push_str(&format!("  {:.3} ms  {}", s.jitter_ms.unwrap_or(0.0), loss));
+        } else if let Some(packets) = s.packets {
+            text.push_str(&format!("  {} datagrams", packets));
+        }
+        text
+    };
+
+    println!(
+        "{} {} ({}{})",
+        "─── Result ───".cyan(),
+        result.peer,
+        if result.local_sender {
+            "sent"
+        } else {
+            "received"
+        },
+        if result.reverse { ", reverse" } else { "" }
+    );
+    if result.streams.len() > 1 {
+        for stream in &result.streams {
+            println!("  [{:>3}]{}", stream.id, line("sender", &stream.sent));
+            println!("  [{:>3}]{}", stream.id, line("receiver", &stream.received));
+        }
+    }
+    println!("  [SUM]{}", line("sender", &result.sent));
+    println!("  [SUM]{}", line("receiver", &result.received));
+    match result.remote_cpu_percent {
+        Some(remote) => println!(
+            "  CPU: {:.1}% local, {:.1}% remote",
+            result.local_cpu_percent, remote
+        ),
+        None => println!("  CPU: {:.1}% local", result.local_cpu_percent),
+    }
+}
+
 /// Handle privacy subcommands for managing data collection consent
 #[cfg(feature = "cli")]
 fn handle_privacy_command(action: &PrivacySubcommand) -> Result<(), Box<dyn std::error::Error>> {
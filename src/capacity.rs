@@ -0,0 +1,858 @@
+//! Filesystem capacity forecasting and inode exhaustion alerts
+//!
+//! Samples used/available bytes and inodes per mount, fits the trend with the
+//! same least-squares regression [`crate::predictive`] uses, and projects when
+//! each filesystem runs out of space or inodes:
+//!
+//! - **Time to full**: available bytes ÷ fitted fill rate over `fit_window_hours`,
+//!   with a ~95% interval from the slope's standard error and R² as confidence
+//! - **Recent rate**: a second fit over `recent_window_minutes` catches bursts
+//!   (checkpoint writers, log storms) that a day-long trend averages away; the
+//!   earlier of the two projections drives alerts
+//! - **Fill-rate change**: recent rate ≥ `fill_rate_change_factor` × long-term rate
+//! - **Inodes**: the same projection on free inodes, plus a usage threshold
+//!
+//! Alerts are [`MaintenanceAlert`]s graded by [`CapacityHorizons`]. History can
+//! be persisted to and preloaded from the [`crate::tsdb`] database so forecasts
+//! survive restarts.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use simonlib::capacity::{CapacityConfig, CapacityForecaster};
+//!
+//! let mut forecaster = CapacityForecaster::new(CapacityConfig::default());
+//! for _ in 0..30 {
+//!     forecaster.sample();
+//!     std::thread::sleep(std::time::Duration::from_secs(60));
+//! }
+//! for f in forecaster.forecasts() {
+//!     if let Some(hours) = f.hours_to_full() {
+//!         println!("{} full in {:.1} h (R² {:.2})", f.mount_point, hours, f.bytes.confidence);
+//!     }
+//! }
+//! ```
+
+use crate::disk::FilesystemInfo;
+use crate::error::{Result, SimonError};
+use crate::observability::{event_types, EventCategory, EventManager, SystemEvent};
+use crate::predictive::{IssueType, LinearFit, MaintenanceAlert, Urgency};
+use crate::tsdb::{FilesystemSnapshot, FilesystemUsage, TimeSeriesDb};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const MS_PER_HOUR: f64 = 3_600_000.0;
+
+/// Projected time-to-exhaustion at which each urgency is raised
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapacityHorizons {
+    pub critical_hours: f64,
+    pub high_hours: f64,
+    pub medium_hours: f64,
+}
+
+impl Default for CapacityHorizons {
+    fn default() -> Self {
+        Self {
+            critical_hours: 6.0,
+            high_hours: 24.0,
+            medium_hours: 168.0,
+        }
+    }
+}
+
+impl CapacityHorizons {
+    fn urgency(&self, hours: f64) -> Option<Urgency> {
+        if hours <= self.critical_hours {
+            Some(Urgency::Critical)
+        } else if hours <= self.high_hours {
+            Some(Urgency::High)
+        } else if hours <= self.medium_hours {
+            Some(Urgency::Medium)
+        } else {
+            None
+        }
+    }
+}
+
+/// Forecasting configuration (`[capacity]` in the daemon config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapacityConfig {
+    pub enabled: bool,
+    /// Sampling interval in seconds
+    pub interval_secs: u64,
+    /// Span of history used for the long-term trend
+    pub fit_window_hours: f64,
+    /// Span used for the recent (burst) rate
+    pub recent_window_minutes: f64,
+    /// Samples required before forecasting a mount
+    pub min_samples: usize,
+    pub horizons: CapacityHorizons,
+    /// Inode usage that raises an alert regardless of trend
+    pub inode_warning_percent: f64,
+    /// Recent fill rate this many times the long-term rate is a rate change
+    pub fill_rate_change_factor: f64,
+    /// Fill rates below this are ignored for rate-change detection (bytes/hour)
+    pub min_fill_rate_bytes_per_hour: f64,
+    /// Filesystem types never forecast
+    pub exclude_fs_types: Vec<String>,
+    /// Persist samples here and preload history on start
+    pub tsdb_path: Option<PathBuf>,
+    /// Database size limit in bytes (0 = default)
+    pub tsdb_max_bytes: u64,
+    /// Minimum minutes between repeated events for the same mount and issue,
+    /// unless urgency rises
+    pub alert_cooldown_minutes: u64,
+}
+
+impl Default for CapacityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 60,
+            fit_window_hours: 24.0,
+            recent_window_minutes: 30.0,
+            min_samples: 10,
+            horizons: CapacityHorizons::default(),
+            inode_warning_percent: 90.0,
+            fill_rate_change_factor: 3.0,
+            min_fill_rate_bytes_per_hour: 1024.0 * 1024.0 * 1024.0,
+            exclude_fs_types: [
+                "tmpfs",
+                "devtmpfs",
+                "overlay",
+                "squashfs",
+                "proc",
+                "sysfs",
+                "cgroup2",
+                "devpts",
+                "autofs",
+                "nsfs",
+                "fuse.snapfuse",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            tsdb_path: None,
+            tsdb_max_bytes: 0,
+            alert_cooldown_minutes: 60,
+        }
+    }
+}
+
+/// Projection for one resource (bytes or inodes)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Projection {
+    /// Long-term consumption rate per hour (negative when freeing)
+    pub rate_per_hour: Option<f64>,
+    /// Consumption rate over the recent window
+    pub recent_rate_per_hour: Option<f64>,
+    /// Hours until exhaustion at the long-term rate
+    pub hours_to_full: Option<f64>,
+    /// ~95% interval on `hours_to_full` (upper bound None = may never fill)
+    pub hours_to_full_low: Option<f64>,
+    pub hours_to_full_high: Option<f64>,
+    /// Hours until exhaustion at the recent rate
+    pub recent_hours_to_full: Option<f64>,
+    /// R² of the long-term fit
+    pub confidence: f64,
+}
+
+impl Projection {
+    /// Earliest projected exhaustion across both rates
+    pub fn earliest_hours(&self) -> Option<f64> {
+        match (self.hours_to_full, self.recent_hours_to_full) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Capacity forecast for one mount
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapacityForecast {
+    pub mount_point: String,
+    pub fs_type: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    /// Percent of usable space consumed (used ÷ (used + available))
+    pub usage_percent: f64,
+    pub bytes: Projection,
+    /// Percent of inodes used (None without a fixed inode table, e.g. btrfs)
+    pub inode_usage_percent: Option<f64>,
+    pub inodes: Option<Projection>,
+    /// Samples in the fit window
+    pub samples: usize,
+    /// Hours of history in the fit window
+    pub history_hours: f64,
+}
+
+impl CapacityForecast {
+    /// Earliest projected exhaustion of space or inodes
+    pub fn hours_to_full(&self) -> Option<f64> {
+        let inodes = self.inodes.as_ref().and_then(|p| p.earliest_hours());
+        match (self.bytes.earliest_hours(), inodes) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+impl From<&FilesystemInfo> for FilesystemUsage {
+    fn from(fs: &FilesystemInfo) -> Self {
+        Self {
+            mount_point: fs.mount_point.to_string_lossy().into_owned(),
+            fs_type: fs.fs_type.clone(),
+            total_bytes: fs.total_size,
+            used_bytes: fs.used_size,
+            available_bytes: fs.available_size,
+            total_inodes: fs.total_inodes.unwrap_or(0),
+            used_inodes: fs.used_inodes.unwrap_or(0),
+        }
+    }
+}
+
+/// Current usage of every mounted filesystem (bind mounts reported once)
+pub fn current_filesystems() -> Vec<FilesystemUsage> {
+    #[cfg(target_os = "linux")]
+    {
+        let mounts = std::fs::read_to_string("/proc/mounts").unwrap_or_default();
+        let mut seen = std::collections::HashSet::new();
+        let mut out = Vec::new();
+        for line in mounts.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 3 || !seen.insert(parts[0]) {
+                continue;
+            }
+            // /proc/mounts escapes spaces and tabs as octal
+            let mount_point = parts[1].replace("\\040", " ").replace("\\011", "\t");
+            let Ok(stat) = nix::sys::statvfs::statvfs(mount_point.as_str()) else {
+                continue;
+            };
+            let bsize = stat.fragment_size();
+            let total_bytes = stat.blocks() * bsize;
+            out.push(FilesystemUsage {
+                mount_point,
+                fs_type: parts[2].to_string(),
+                total_bytes,
+                used_bytes: total_bytes.saturating_sub(stat.blocks_free() * bsize),
+                available_bytes: stat.blocks_available() * bsize,
+                total_inodes: stat.files(),
+                used_inodes: stat.files().saturating_sub(stat.files_free()),
+            });
+        }
+        out
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        crate::disk::enumerate_disks()
+            .map(|disks| {
+                disks
+                    .iter()
+                    .filter_map(|d| d.filesystem_info().ok())
+                    .flatten()
+                    .map(|fs| FilesystemUsage::from(&fs))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    timestamp_ms: u64,
+    used_bytes: f64,
+    available_bytes: f64,
+    used_inodes: f64,
+    free_inodes: f64,
+}
+
+struct MountHistory {
+    fs_type: String,
+    total_bytes: u64,
+    total_inodes: u64,
+    points: VecDeque<Point>,
+}
+
+/// In-memory per-mount history and trend fitting
+pub struct CapacityForecaster {
+    config: CapacityConfig,
+    mounts: HashMap<String, MountHistory>,
+}
+
+impl CapacityForecaster {
+    pub fn new(config: CapacityConfig) -> Self {
+        Self {
+            config,
+            mounts: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &CapacityConfig {
+        &self.config
+    }
+
+    /// Sample all mounted filesystems now
+    pub fn sample(&mut self) -> FilesystemSnapshot {
+        let snapshot = FilesystemSnapshot {
+            timestamp: TimeSeriesDb::now_millis(),
+            filesystems: current_filesystems(),
+        };
+        self.record(&snapshot);
+        snapshot
+    }
+
+    /// Add a snapshot (live or replayed from the TSDB)
+    pub fn record(&mut self, snapshot: &FilesystemSnapshot) {
+        for fs in &snapshot.filesystems {
+            if fs.total_bytes == 0 || self.config.exclude_fs_types.contains(&fs.fs_type) {
+                continue;
+            }
+            let history = self
+                .mounts
+                .entry(fs.mount_point.clone())
+                .or_insert_with(|| MountHistory {
+                    fs_type: fs.fs_type.clone(),
+                    total_bytes: fs.total_bytes,
+                    total_inodes: fs.total_inodes,
+                    points: VecDeque::new(),
+                });
+            // A resized or replaced filesystem invalidates the trend
+            if history.total_bytes != fs.total_bytes || history.fs_type != fs.fs_type {
+                history.points.clear();
+                history.fs_type = fs.fs_type.clone();
+                history.total_bytes = fs.total_bytes;
+            }
+            history.total_inodes = fs.total_inodes;
+            if history
+                .points
+                .back()
+                .is_some_and(|p| p.timestamp_ms >= snapshot.timestamp)
+            {
+                continue;
+            }
+            history.points.push_back(Point {
+                timestamp_ms: snapshot.timestamp,
+                used_bytes: fs.used_bytes as f64,
+                available_bytes: fs.available_bytes as f64,
+                used_inodes: fs.used_inodes as f64,
+                free_inodes: fs.total_inodes.saturating_sub(fs.used_inodes) as f64,
+            });
+            let horizon = (self.config.fit_window_hours * MS_PER_HOUR) as u64;
+            while history
+                .points
+                .front()
+                .is_some_and(|p| snapshot.timestamp - p.timestamp_ms > horizon)
+            {
+                history.points.pop_front();
+            }
+        }
+    }
+
+    /// Replay history from a TSDB file written by [`spawn`] or `simon record`
+    pub fn preload(&mut self, db: &mut TimeSeriesDb) -> Result<usize> {
+        let since = TimeSeriesDb::now_millis()
+            .saturating_sub((self.config.fit_window_hours * MS_PER_HOUR) as u64);
+        let snapshots = db.query_filesystem_range(since, u64::MAX)?;
+        for snapshot in &snapshots {
+            self.record(snapshot);
+        }
+        Ok(snapshots.len())
+    }
+
+    /// Forecasts for every mount with at least `min_samples` samples, soonest
+    /// exhaustion first
+    pub fn forecasts(&self) -> Vec<CapacityForecast> {
+        let mut out: Vec<CapacityForecast> = self
+            .mounts
+            .keys()
+            .filter_map(|m| self.forecast(m))
+            .collect();
+        out.sort_by(|a, b| {
+            let key = |f: &CapacityForecast| f.hours_to_full().unwrap_or(f64::INFINITY);
+            key(a).total_cmp(&key(b))
+        });
+        out
+    }
+
+    pub fn forecast(&self, mount_point: &str) -> Option<CapacityForecast> {
+        let history = self.mounts.get(mount_point)?;
+        let points: Vec<Point> = history.points.iter().copied().collect();
+        if points.len() < self.config.min_samples.max(3) {
+            return None;
+        }
+        let last = *points.last()?;
+        let recent_ms = (self.config.recent_window_minutes * 60_000.0) as u64;
+        let recent: Vec<Point> = points
+            .iter()
+            .copied()
+            .filter(|p| last.timestamp_ms - p.timestamp_ms <= recent_ms)
+            .collect();
+
+        let usable = last.used_bytes + last.available_bytes;
+        let bytes = project(&points, &recent, last.available_bytes, |p| p.used_bytes);
+        let (inode_usage_percent, inodes) = if history.total_inodes > 0 {
+            let pct = last.used_inodes / history.total_inodes as f64 * 100.0;
+            let proj = project(&points, &recent, last.free_inodes, |p| p.used_inodes);
+            (Some(pct), Some(proj))
+        } else {
+            (None, None)
+        };
+
+        Some(CapacityForecast {
+            mount_point: mount_point.to_string(),
+            fs_type: history.fs_type.clone(),
+            total_bytes: history.total_bytes,
+            available_bytes: last.available_bytes as u64,
+            usage_percent: if usable > 0.0 {
+                last.used_bytes / usable * 100.0
+            } else {
+                0.0
+            },
+            bytes,
+            inode_usage_percent,
+            inodes,
+            samples: points.len(),
+            history_hours: (last.timestamp_ms - points[0].timestamp_ms) as f64 / MS_PER_HOUR,
+        })
+    }
+
+    /// Maintenance alerts for projected exhaustion within the horizons,
+    /// inode usage over the threshold, and sudden fill-rate increases
+    pub fn alerts(&self, forecasts: &[CapacityForecast]) -> Vec<MaintenanceAlert> {
+        let mut alerts = Vec::new();
+        for f in forecasts {
+            if let Some(alert) = self.exhaustion_alert(f, &f.bytes, IssueType::CapacityExhaustion) {
+                alerts.push(alert);
+            }
+            if let Some(ref inodes) = f.inodes {
+                let pct = f.inode_usage_percent.unwrap_or(0.0);
+                match self.exhaustion_alert(f, inodes, IssueType::InodeExhaustion) {
+                    Some(alert) => alerts.push(alert),
+                    None if pct >= self.config.inode_warning_percent => {
+                        alerts.push(MaintenanceAlert {
+                            component: f.mount_point.clone(),
+                            issue_type: IssueType::InodeExhaustion,
+                            urgency: Urgency::High,
+                            message: format!(
+                                "{} has used {:.1}% of its inodes",
+                                f.mount_point, pct
+                            ),
+                            eta_hours: None,
+                            degradation_rate: inodes.rate_per_hour,
+                            current_value: pct,
+                            threshold: self.config.inode_warning_percent,
+                            action:
+                                "Remove small files or recreate the filesystem with more inodes"
+                                    .into(),
+                            confidence: 1.0,
+                        })
+                    }
+                    None => {}
+                }
+            }
+            if let Some(alert) = self.rate_change_alert(f) {
+                alerts.push(alert);
+            }
+        }
+        alerts.sort_by_key(|a| std::cmp::Reverse(a.urgency));
+        alerts
+    }
+
+    fn exhaustion_alert(
+        &self,
+        f: &CapacityForecast,
+        p: &Projection,
+        issue_type: IssueType,
+    ) -> Option<MaintenanceAlert> {
+        let hours = p.earliest_hours()?;
+        let urgency = self.config.horizons.urgency(hours)?;
+        let (what, rate_unit, action) = match issue_type {
+            IssueType::InodeExhaustion => (
+                "inodes",
+                "inodes/h".to_string(),
+                "Remove small files or recreate the filesystem with more inodes",
+            ),
+            _ => (
+                "space",
+                "GiB/h".to_string(),
+                "Free space, prune checkpoints/logs or grow the filesystem",
+            ),
+        };
+        let scale = if issue_type == IssueType::InodeExhaustion {
+            1.0
+        } else {
+            1024.0 * 1024.0 * 1024.0
+        };
+        let recent_is_sooner = match (p.recent_hours_to_full, p.hours_to_full) {
+            (Some(r), Some(l)) => r < l,
+            (Some(_), None) => true,
+            _ => false,
+        };
+        let rate = if recent_is_sooner {
+            p.recent_rate_per_hour
+        } else {
+            p.rate_per_hour
+        };
+        let interval = match (p.hours_to_full_low, p.hours_to_full_high) {
+            (Some(lo), Some(hi)) if !recent_is_sooner => format!(" ({:.1}–{:.1} h)", lo, hi),
+            (Some(lo), None) if !recent_is_sooner => format!(" (≥{:.1} h)", lo),
+            _ => String::new(),
+        };
+        Some(MaintenanceAlert {
+            component: f.mount_point.clone(),
+            issue_type,
+            urgency,
+            message: format!(
+                "{} projected to run out of {} in {:.1} h{} at {:.2} {}{}",
+                f.mount_point,
+                what,
+                hours,
+                interval,
+                rate.unwrap_or(0.0) / scale,
+                rate_unit,
+                if recent_is_sooner {
+                    " (recent rate)"
+                } else {
+                    ""
+                }
+            ),
+            eta_hours: Some(hours),
+            degradation_rate: rate,
+            current_value: match issue_type {
+                IssueType::InodeExhaustion => f.inode_usage_percent.unwrap_or(0.0),
+                _ => f.usage_percent,
+            },
+            threshold: 100.0,
+            action: action.into(),
+            // The recent window is short, so its projection is less certain
+            confidence: if recent_is_sooner {
+                p.confidence.min(0.5)
+            } else {
+                p.confidence
+            },
+        })
+    }
+
+    fn rate_change_alert(&self, f: &CapacityForecast) -> Option<MaintenanceAlert> {
+        let recent = f.bytes.recent_rate_per_hour?;
+        let long = f.bytes.rate_per_hour.unwrap_or(0.0).max(0.0);
+        let floor = self.config.min_fill_rate_bytes_per_hour;
+        if recent < floor || recent < self.config.fill_rate_change_factor * long.max(floor) {
+            return None;
+        }
+        let gib = 1024.0 * 1024.0 * 1024.0;
+        let urgency = match f.bytes.recent_hours_to_full {
+            Some(h) if h <= self.config.horizons.high_hours => Urgency::High,
+            _ => Urgency::Medium,
+        };
+        Some(MaintenanceAlert {
+            component: f.mount_point.clone(),
+            issue_type: IssueType::FillRateChange,
+            urgency,
+            message: format!(
+                "{} filling at {:.2} GiB/h over the last {:.0} min, {:.1}x its {:.0} h trend",
+                f.mount_point,
+                recent / gib,
+                self.config.recent_window_minutes,
+                recent / long.max(floor),
+                f.history_hours
+            ),
+            eta_hours: f.bytes.recent_hours_to_full,
+            degradation_rate: Some(recent),
+            current_value: f.usage_percent,
+            threshold: 100.0,
+            action: "Check what started writing (checkpoints, logs, core dumps)".into(),
+            confidence: f.bytes.confidence.min(0.5),
+        })
+    }
+}
+
+/// Fit consumption over time and project exhaustion of `remaining`
+fn project(
+    points: &[Point],
+    recent: &[Point],
+    remaining: f64,
+    consumed: impl Fn(&Point) -> f64,
+) -> Projection {
+    let t0 = points[0].timestamp_ms;
+    let xy = |ps: &[Point]| -> Vec<(f64, f64)> {
+        ps.iter()
+            .map(|p| ((p.timestamp_ms - t0) as f64 / MS_PER_HOUR, consumed(p)))
+            .collect()
+    };
+    let eta = |rate: f64| (rate > 0.0).then(|| (remaining / rate).max(0.0));
+
+    let mut projection = Projection::default();
+    if let Some(fit) = LinearFit::fit(&xy(points)) {
+        let (lo, hi) = fit.slope_interval();
+        projection.rate_per_hour = Some(fit.slope);
+        projection.hours_to_full = eta(fit.slope);
+        // Faster consumption gives the earlier bound
+        projection.hours_to_full_low = eta(hi);
+        projection.hours_to_full_high = projection.hours_to_full.and(eta(lo));
+        projection.confidence = fit.r_squared.clamp(0.0, 1.0);
+    }
+    if let Some(fit) = LinearFit::fit(&xy(recent)) {
+        projection.recent_rate_per_hour = Some(fit.slope);
+        projection.recent_hours_to_full = eta(fit.slope);
+    }
+    projection
+}
+
+/// Event for a capacity alert (critical urgency → critical severity)
+pub fn alert_event(alert: &MaintenanceAlert) -> SystemEvent {
+    let event_type = match alert.issue_type {
+        IssueType::CapacityExhaustion | IssueType::InodeExhaustion => {
+            event_types::disk::CAPACITY_FORECAST
+        }
+        IssueType::FillRateChange => event_types::disk::FILL_RATE_CHANGE,
+        _ => event_types::disk::LOW_SPACE,
+    };
+    let source = format!("mount:{}", alert.component);
+    let event = match alert.urgency {
+        Urgency::Critical => {
+            SystemEvent::critical(EventCategory::Disk, event_type, &alert.message, &source)
+        }
+        _ => SystemEvent::warning(EventCategory::Disk, event_type, &alert.message, &source),
+    };
+    event
+        .with_metadata("issue", alert.issue_type.to_string())
+        .with_metadata("urgency", alert.urgency.to_string())
+        .with_metadata("eta_hours", alert.eta_hours)
+        .with_metadata("confidence", alert.confidence)
+}
+
+/// Latest forecasts and alerts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CapacitySnapshot {
+    /// Unix time of the sample (milliseconds)
+    pub timestamp: u64,
+    pub forecasts: Vec<CapacityForecast>,
+    pub alerts: Vec<MaintenanceAlert>,
+}
+
+/// Most recent forecast, `None` until the first pass completes
+pub type SharedCapacitySnapshot = Arc<RwLock<Option<CapacitySnapshot>>>;
+
+/// Sample filesystems on a background thread, persisting to the TSDB if
+/// configured and emitting alerts to `events` (repeated at most once per
+/// cooldown unless urgency rises)
+pub fn spawn(config: CapacityConfig, events: Arc<EventManager>) -> Result<CapacityHandle> {
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+    let cooldown = Duration::from_secs(config.alert_cooldown_minutes * 60);
+    let mut db = match config.tsdb_path {
+        Some(ref path) => Some(TimeSeriesDb::new(path, config.tsdb_max_bytes)?),
+        None => None,
+    };
+    let mut forecaster = CapacityForecaster::new(config);
+    if let Some(ref mut db) = db {
+        let loaded = forecaster.preload(db)?;
+        log::info!(
+            "Capacity forecaster preloaded {} filesystem samples",
+            loaded
+        );
+    }
+
+    let snapshot: SharedCapacitySnapshot = Arc::new(RwLock::new(None));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let shared = Arc::clone(&snapshot);
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-capacity".into())
+        .spawn(move || {
+            let mut last_event: HashMap<(String, IssueType), (Urgency, Instant)> = HashMap::new();
+            while !flag.load(Ordering::SeqCst) {
+                let sample = forecaster.sample();
+                if let Some(ref mut db) = db {
+                    if let Err(e) = db.record_filesystems(&sample) {
+                        log::warn!("Failed to record filesystem usage: {}", e);
+                    }
+                }
+                let forecasts = forecaster.forecasts();
+                let alerts = forecaster.alerts(&forecasts);
+                for alert in &alerts {
+                    let key = (alert.component.clone(), alert.issue_type);
+                    let due = match last_event.get(&key) {
+                        Some((urgency, at)) => alert.urgency > *urgency || at.elapsed() >= cooldown,
+                        None => true,
+                    };
+                    if due {
+                        events.emit(alert_event(alert));
+                        last_event.insert(key, (alert.urgency, Instant::now()));
+                    }
+                }
+                if let Ok(mut slot) = shared.write() {
+                    *slot = Some(CapacitySnapshot {
+                        timestamp: sample.timestamp,
+                        forecasts,
+                        alerts,
+                    });
+                }
+
+                let deadline = Instant::now() + interval;
+                while !flag.load(Ordering::SeqCst) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(200));
+                }
+            }
+        })
+        .map_err(|e| {
+            SimonError::Other(format!("Failed to spawn capacity forecaster thread: {}", e))
+        })?;
+
+    Ok(CapacityHandle {
+        snapshot,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+/// Handle to a running capacity forecaster thread
+pub struct CapacityHandle {
+    snapshot: SharedCapacitySnapshot,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CapacityHandle {
+    /// Shared snapshot slot for readers
+    pub fn snapshot(&self) -> SharedCapacitySnapshot {
+        Arc::clone(&self.snapshot)
+    }
+
+    /// Stop forecasting and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for CapacityHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn snapshot(minute: u64, used_gib: f64, used_inodes: u64) -> FilesystemSnapshot {
+        let total = 1000 * GIB;
+        let used = (used_gib * GIB as f64) as u64;
+        FilesystemSnapshot {
+            timestamp: minute * 60_000,
+            filesystems: vec![
+                FilesystemUsage {
+                    mount_point: "/ckpt".into(),
+                    fs_type: "xfs".into(),
+                    total_bytes: total,
+                    used_bytes: used,
+                    available_bytes: total - used,
+                    total_inodes: 1_000_000,
+                    used_inodes,
+                },
+                FilesystemUsage {
+                    mount_point: "/run".into(),
+                    fs_type: "tmpfs".into(),
+                    total_bytes: GIB,
+                    used_bytes: 0,
+                    available_bytes: GIB,
+                    total_inodes: 0,
+                    used_inodes: 0,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_steady_fill_projection() {
+        let mut f = CapacityForecaster::new(CapacityConfig::default());
+        // 900 GiB used, filling 10 GiB/h with ±0.5 GiB jitter: 10 h to full
+        for i in 0..=24u64 {
+            let jitter = if i % 2 == 0 { 0.5 } else { -0.5 };
+            f.record(&snapshot(
+                i * 5,
+                880.0 + i as f64 * 10.0 / 12.0 + jitter,
+                1000,
+            ));
+        }
+        assert!(f.forecast("/run").is_none());
+        let fc = f.forecast("/ckpt").unwrap();
+        let hours = fc.bytes.hours_to_full.unwrap();
+        assert!((hours - 10.0).abs() < 0.5, "{}", hours);
+        let (lo, hi) = (
+            fc.bytes.hours_to_full_low.unwrap(),
+            fc.bytes.hours_to_full_high.unwrap(),
+        );
+        assert!(lo < hours && hours < hi);
+        assert!(fc.bytes.confidence > 0.9);
+        assert_eq!(fc.inodes.as_ref().unwrap().hours_to_full, None);
+
+        let alerts = f.alerts(&[fc]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, IssueType::CapacityExhaustion);
+        assert_eq!(alerts[0].urgency, Urgency::High);
+        let event = alert_event(&alerts[0]);
+        assert_eq!(event.event_type, event_types::disk::CAPACITY_FORECAST);
+    }
+
+    #[test]
+    fn test_overnight_burst_and_inodes() {
+        let mut f = CapacityForecaster::new(CapacityConfig::default());
+        // Flat at 500 GiB for 4 h, then a checkpoint writer adds 2 GiB/min
+        for m in (0..240u64).step_by(10) {
+            f.record(&snapshot(m, 500.0, 10_000 + m));
+        }
+        for m in (240..=270u64).step_by(2) {
+            f.record(&snapshot(
+                m,
+                500.0 + (m - 240) as f64 * 2.0,
+                950_000 + (m - 240) * 1000,
+            ));
+        }
+        let fc = f.forecast("/ckpt").unwrap();
+        let recent = fc.bytes.recent_hours_to_full.unwrap();
+        // 440 GiB free at 120 GiB/h
+        assert!((recent - 440.0 / 120.0).abs() < 0.2, "{}", recent);
+        assert!(fc.bytes.earliest_hours().unwrap() <= recent);
+
+        let alerts = f.alerts(&[fc]);
+        let issues: Vec<(IssueType, Urgency)> =
+            alerts.iter().map(|a| (a.issue_type, a.urgency)).collect();
+        assert!(
+            issues.contains(&(IssueType::CapacityExhaustion, Urgency::Critical)),
+            "{:?}",
+            issues
+        );
+        assert!(issues.contains(&(IssueType::FillRateChange, Urgency::High)));
+        // 980k of 1M inodes used, consuming 60k/h: ~20 min left
+        assert!(issues.contains(&(IssueType::InodeExhaustion, Urgency::Critical)));
+        assert_eq!(alerts[0].urgency, Urgency::Critical);
+    }
+
+    #[test]
+    fn test_resize_resets_history() {
+        let mut f = CapacityForecaster::new(CapacityConfig::default());
+        for m in 0..12 {
+            f.record(&snapshot(m, 100.0 + m as f64, 0));
+        }
+        assert!(f.forecast("/ckpt").is_some());
+        let mut grown = snapshot(12, 112.0, 0);
+        grown.filesystems[0].total_bytes *= 2;
+        f.record(&grown);
+        assert!(f.forecast("/ckpt").is_none());
+    }
+}
@@ -4,6 +4,11 @@
 //! including TCP and UDP sockets with their states, local/remote addresses, and
 //! owning process information.
 //!
+//! On Linux the tables come from netlink `sock_diag` (the `ss` interface),
+//! which filters by TCP state in the kernel and reports per-connection
+//! `tcp_info` (RTT, congestion window, retransmits, pacing and delivery
+//! rates). `/proc/net/{tcp,udp}[6]` is parsed when netlink is unavailable.
+//!
 //! # Examples
 //!
 //! ```no_run
@@ -24,7 +29,24 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! Retransmits and RTT of established connections:
+//!
+//! ```no_run
+//! use simon::connections::{ConnectionMonitor, ConnectionState};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let monitor = ConnectionMonitor::new()?;
+//! for conn in monitor.tcp_connections_in_states(&[ConnectionState::Established])? {
+//!     if let Some(info) = conn.tcp_info {
+//!         println!("{} rtt={}us retrans={}", conn.local_address, info.rtt_us, info.total_retrans);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
 
+pub use crate::sock_diag::TcpInfo;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
@@ -52,6 +74,9 @@ pub struct ConnectionInfo {
     pub pid: Option<u32>,
     /// Owning process name (if available)
     pub process_name: Option<String>,
+    /// Kernel TCP internals; Linux netlink backend only
+    #[serde(default)]
+    pub tcp_info: Option<TcpInfo>,
 }
 
 /// Network protocol
@@ -95,6 +120,52 @@ pub enum ConnectionState {
     Stateless,
 }
 
+impl ConnectionState {
+    /// Map a kernel TCP state number (`/proc/net/tcp` `st` column, sock_diag)
+    pub fn from_tcp_state(state: u32) -> Self {
+        match state {
+            0x01 => ConnectionState::Established,
+            0x02 => ConnectionState::SynSent,
+            0x03 => ConnectionState::SynReceived,
+            0x04 => ConnectionState::FinWait1,
+            0x05 => ConnectionState::FinWait2,
+            0x06 => ConnectionState::TimeWait,
+            0x07 => ConnectionState::Closed,
+            0x08 => ConnectionState::CloseWait,
+            0x09 => ConnectionState::LastAck,
+            0x0A => ConnectionState::Listen,
+            0x0B => ConnectionState::Closing,
+            _ => ConnectionState::Unknown,
+        }
+    }
+
+    /// Kernel TCP state number, the inverse of [`Self::from_tcp_state`]
+    pub fn tcp_state(self) -> Option<u32> {
+        match self {
+            ConnectionState::Established => Some(0x01),
+            ConnectionState::SynSent => Some(0x02),
+            ConnectionState::SynReceived => Some(0x03),
+            ConnectionState::FinWait1 => Some(0x04),
+            ConnectionState::FinWait2 => Some(0x05),
+            ConnectionState::TimeWait => Some(0x06),
+            ConnectionState::Closed => Some(0x07),
+            ConnectionState::CloseWait => Some(0x08),
+            ConnectionState::LastAck => Some(0x09),
+            ConnectionState::Listen => Some(0x0A),
+            ConnectionState::Closing => Some(0x0B),
+            _ => None,
+        }
+    }
+
+    /// Kernel state bitmask (bit N = state N) selecting `states`
+    pub fn tcp_state_mask(states: &[ConnectionState]) -> u32 {
+        states
+            .iter()
+            .filter_map(|s| s.tcp_state())
+            .fold(0, |mask, n| mask | (1 << n))
+    }
+}
+
 impl fmt::Display for ConnectionState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -116,11 +187,25 @@ impl fmt::Display for ConnectionState {
     }
 }
 
+/// Source of socket tables on Linux (ignored elsewhere)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ConnectionBackend {
+    /// Netlink sock_diag, falling back to `/proc/net` when it is unavailable
+    #[default]
+    Auto,
+    /// Netlink sock_diag only
+    Netlink,
+    /// `/proc/net/{tcp,udp}[6]` text tables only (no `tcp_info`)
+    Proc,
+}
+
 /// Connection monitor for network sockets
 pub struct ConnectionMonitor {
     /// Cache of process names by PID
     #[allow(dead_code)]
     process_cache: std::collections::HashMap<u32, String>,
+    #[allow(dead_code)]
+    backend: ConnectionBackend,
 }
 
 impl ConnectionMonitor {
@@ -128,9 +213,16 @@ impl ConnectionMonitor {
     pub fn new() -> Result<Self, Error> {
         Ok(Self {
             process_cache: std::collections::HashMap::new(),
+            backend: ConnectionBackend::default(),
         })
     }
 
+    /// Select where socket tables are read from on Linux
+    pub fn with_backend(mut self, backend: ConnectionBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
     /// Get all TCP (IPv4) connections
     pub fn tcp_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
         #[cfg(target_os = "windows")]
@@ -211,22 +303,53 @@ impl ConnectionMonitor {
         Ok(all)
     }
 
+    /// Get TCP connections (IPv4 + IPv6) in any of `states`
+    ///
+    /// With the Linux netlink backend the kernel applies the filter, so only
+    /// matching sockets are dumped and resolved to processes.
+    pub fn tcp_connections_in_states(
+        &self,
+        states: &[ConnectionState],
+    ) -> Result<Vec<ConnectionInfo>, Error> {
+        #[cfg(target_os = "linux")]
+        {
+            let mask = ConnectionState::tcp_state_mask(states);
+            let mut all = self.linux_table(Protocol::Tcp, mask)?;
+            if let Ok(tcp6) = self.linux_table(Protocol::Tcp6, mask) {
+                all.extend(tcp6);
+            }
+            Ok(all)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let mut all = Vec::new();
+            if let Ok(tcp) = self.tcp_connections() {
+                all.extend(tcp);
+            }
+            if let Ok(tcp6) = self.tcp6_connections() {
+                all.extend(tcp6);
+            }
+            all.retain(|c| states.contains(&c.state));
+            Ok(all)
+        }
+    }
+
     /// Get only established TCP connections
     pub fn established_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        let all = self.all_connections()?;
-        Ok(all
-            .into_iter()
-            .filter(|c| c.state == ConnectionState::Established)
-            .collect())
+        self.tcp_connections_in_states(&[ConnectionState::Established])
     }
 
     /// Get only listening sockets
     pub fn listening_sockets(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        let all = self.all_connections()?;
-        Ok(all
-            .into_iter()
-            .filter(|c| c.state == ConnectionState::Listen || c.state == ConnectionState::Stateless)
-            .collect())
+        let mut all = self.tcp_connections_in_states(&[ConnectionState::Listen])?;
+        if let Ok(udp) = self.udp_endpoints() {
+            all.extend(udp);
+        }
+        if let Ok(udp6) = self.udp6_endpoints() {
+            all.extend(udp6);
+        }
+        Ok(all)
     }
 }
 
@@ -316,6 +439,7 @@ impl ConnectionMonitor {
                 state,
                 pid: Some(pid),
                 process_name: self.get_process_name(pid),
+                tcp_info: None,
             });
         }
 
@@ -404,6 +528,7 @@ impl ConnectionMonitor {
                 state,
                 pid: Some(pid),
                 process_name: self.get_process_name(pid),
+                tcp_info: None,
             });
         }
 
@@ -477,6 +602,7 @@ impl ConnectionMonitor {
                 state: ConnectionState::Stateless,
                 pid: Some(pid),
                 process_name: self.get_process_name(pid),
+                tcp_info: None,
             });
         }
 
@@ -550,6 +676,7 @@ impl ConnectionMonitor {
                 state: ConnectionState::Stateless,
                 pid: Some(pid),
                 process_name: self.get_process_name(pid),
+                tcp_info: None,
             });
         }
 
@@ -602,19 +729,78 @@ impl ConnectionMonitor {
 #[cfg(target_os = "linux")]
 impl ConnectionMonitor {
     fn linux_tcp_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        self.parse_proc_net("/proc/net/tcp", Protocol::Tcp)
+        self.linux_table(Protocol::Tcp, crate::sock_diag::ALL_STATES)
     }
 
     fn linux_tcp6_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        self.parse_proc_net("/proc/net/tcp6", Protocol::Tcp6)
+        self.linux_table(Protocol::Tcp6, crate::sock_diag::ALL_STATES)
     }
 
     fn linux_udp_endpoints(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        self.parse_proc_net("/proc/net/udp", Protocol::Udp)
+        self.linux_table(Protocol::Udp, crate::sock_diag::ALL_STATES)
     }
 
     fn linux_udp6_endpoints(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        self.parse_proc_net("/proc/net/udp6", Protocol::Udp6)
+        self.linux_table(Protocol::Udp6, crate::sock_diag::ALL_STATES)
+    }
+
+    /// One protocol's sockets in the kernel states selected by `states`,
+    /// from netlink or `/proc/net` according to the backend
+    fn linux_table(&self, protocol: Protocol, states: u32) -> Result<Vec<ConnectionInfo>, Error> {
+        if self.backend != ConnectionBackend::Proc {
+            match self.netlink_connections(protocol, states) {
+                Ok(connections) => return Ok(connections),
+                Err(e) if self.backend == ConnectionBackend::Netlink => return Err(e),
+                Err(_) => {}
+            }
+        }
+
+        let path = match protocol {
+            Protocol::Tcp => "/proc/net/tcp",
+            Protocol::Tcp6 => "/proc/net/tcp6",
+            Protocol::Udp => "/proc/net/udp",
+            Protocol::Udp6 => "/proc/net/udp6",
+        };
+        let mut connections = self.parse_proc_net(path, protocol)?;
+        if states != crate::sock_diag::ALL_STATES {
+            connections.retain(|c| c.state.tcp_state().is_some_and(|n| states & (1 << n) != 0));
+        }
+        Ok(connections)
+    }
+
+    fn netlink_connections(
+        &self,
+        protocol: Protocol,
+        states: u32,
+    ) -> Result<Vec<ConnectionInfo>, Error> {
+        use crate::sock_diag::{self, Transport};
+
+        let ipv6 = matches!(protocol, Protocol::Tcp6 | Protocol::Udp6);
+        let transport = if matches!(protocol, Protocol::Tcp | Protocol::Tcp6) {
+            Transport::Tcp
+        } else {
+            Transport::Udp
+        };
+        let sockets = sock_diag::dump(ipv6, transport, states, true)
+            .map_err(|e| Error::SystemError(format!("sock_diag dump failed: {}", e)))?;
+
+        // One pass over /proc/*/fd instead of one per socket
+        let owners = crate::net_accounting::socket_owners(std::path::Path::new("/proc"));
+        let mut names: std::collections::HashMap<u32, Option<String>> =
+            std::collections::HashMap::new();
+        Ok(sockets
+            .iter()
+            .map(|socket| {
+                let pid = owners.get(&socket.inode).copied();
+                let process_name = pid.and_then(|p| {
+                    names
+                        .entry(p)
+                        .or_insert_with(|| self.get_process_name_linux(p))
+                        .clone()
+                });
+                connection_from_socket(protocol, socket, pid, process_name)
+            })
+            .collect())
     }
 
     fn parse_proc_net(&self, path: &str, protocol: Protocol) -> Result<Vec<ConnectionInfo>, Error> {
@@ -688,6 +874,7 @@ impl ConnectionMonitor {
             state,
             pid,
             process_name,
+            tcp_info: None,
         })
     }
 
@@ -719,20 +906,7 @@ impl ConnectionMonitor {
     }
 
     fn linux_tcp_state(&self, state: u32) -> ConnectionState {
-        match state {
-            0x01 => ConnectionState::Established,
-            0x02 => ConnectionState::SynSent,
-            0x03 => ConnectionState::SynReceived,
-            0x04 => ConnectionState::FinWait1,
-            0x05 => ConnectionState::FinWait2,
-            0x06 => ConnectionState::TimeWait,
-            0x07 => ConnectionState::Closed,
-            0x08 => ConnectionState::CloseWait,
-            0x09 => ConnectionState::LastAck,
-            0x0A => ConnectionState::Listen,
-            0x0B => ConnectionState::Closing,
-            _ => ConnectionState::Unknown,
-        }
+        ConnectionState::from_tcp_state(state)
     }
 
     fn find_pid_by_inode(&self, inode: &str) -> Option<u32> {
@@ -777,6 +951,38 @@ impl ConnectionMonitor {
     }
 }
 
+/// Convert a sock_diag socket to the portable connection record
+#[cfg(target_os = "linux")]
+fn connection_from_socket(
+    protocol: Protocol,
+    socket: &crate::sock_diag::InetSocket,
+    pid: Option<u32>,
+    process_name: Option<String>,
+) -> ConnectionInfo {
+    let state = if matches!(protocol, Protocol::Tcp | Protocol::Tcp6) {
+        ConnectionState::from_tcp_state(socket.state as u32)
+    } else {
+        ConnectionState::Stateless
+    };
+    let remote =
+        (state != ConnectionState::Listen && socket.remote.port() != 0).then_some(socket.remote);
+
+    ConnectionInfo {
+        protocol,
+        // Same unbracketed form as the /proc parser
+        local_address: format!("{}:{}", socket.local.ip(), socket.local.port()),
+        local_ip: socket.local.ip(),
+        local_port: socket.local.port(),
+        remote_address: remote.map(|r| format!("{}:{}", r.ip(), r.port())),
+        remote_ip: remote.map(|r| r.ip()),
+        remote_port: remote.map(|r| r.port()),
+        state,
+        pid,
+        process_name,
+        tcp_info: socket.tcp_info,
+    }
+}
+
 // macOS implementation using netstat
 #[cfg(target_os = "macos")]
 impl ConnectionMonitor {
@@ -869,6 +1075,7 @@ impl ConnectionMonitor {
                 state,
                 pid: None, // netstat -an doesn't show PIDs; would need lsof -i
                 process_name: None,
+                tcp_info: None,
             });
         }
 
@@ -1029,6 +1236,7 @@ mod tests {
             state: ConnectionState::Established,
             pid: Some(1234),
             process_name: Some("curl".into()),
+            tcp_info: None,
         };
         let json = serde_json::to_string(&info).unwrap();
         let parsed: ConnectionInfo = serde_json::from_str(&json).unwrap();
@@ -1043,4 +1251,100 @@ mod tests {
         let monitor = ConnectionMonitor::new();
         assert!(monitor.is_ok());
     }
+
+    #[test]
+    fn test_tcp_state_mask() {
+        for n in 1..=11 {
+            assert_eq!(ConnectionState::from_tcp_state(n).tcp_state(), Some(n));
+        }
+        assert_eq!(ConnectionState::Stateless.tcp_state(), None);
+        let mask = ConnectionState::tcp_state_mask(&[
+            ConnectionState::Established,
+            ConnectionState::Listen,
+            ConnectionState::Stateless,
+        ]);
+        assert_eq!(mask, (1 << 1) | (1 << 10));
+    }
+
+    #[test]
+    fn test_connection_info_without_tcp_info_deserializes() {
+        let json = r#"{"protocol":"Udp","local_address":"0.0.0.0:53","local_ip":"0.0.0.0",
+            "local_port":53,"remote_address":null,"remote_ip":null,"remote_port":null,
+            "state":"Stateless","pid":null,"process_name":null}"#;
+        let parsed: ConnectionInfo = serde_json::from_str(json).unwrap();
+        assert!(parsed.tcp_info.is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_connection_from_socket() {
+        use crate::sock_diag::{InetSocket, Transport};
+
+        let socket = InetSocket {
+            transport: Transport::Tcp,
+            local: "10.0.0.1:40000".parse().unwrap(),
+            remote: "10.0.0.2:5201".parse().unwrap(),
+            state: 1,
+            uid: 0,
+            inode: 42,
+            cookie: 7,
+            rx_queue: 0,
+            tx_queue: 0,
+            tcp_info: Some(TcpInfo {
+                rtt_us: 180,
+                total_retrans: 12,
+                ..Default::default()
+            }),
+        };
+        let conn = connection_from_socket(Protocol::Tcp, &socket, Some(99), Some("nccl".into()));
+        assert_eq!(conn.state, ConnectionState::Established);
+        assert_eq!(conn.local_address, "10.0.0.1:40000");
+        assert_eq!(conn.remote_address.as_deref(), Some("10.0.0.2:5201"));
+        assert_eq!(conn.tcp_info.unwrap().total_retrans, 12);
+
+        let listener = InetSocket {
+            state: 10,
+            remote: "0.0.0.0:0".parse().unwrap(),
+            tcp_info: None,
+            ..socket
+        };
+        let conn = connection_from_socket(Protocol::Tcp, &listener, None, None);
+        assert_eq!(conn.state, ConnectionState::Listen);
+        assert!(conn.remote_address.is_none() && conn.remote_port.is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_netlink_state_filter_matches_proc() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let _client = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let _server = listener.accept().unwrap();
+
+        let netlink = ConnectionMonitor::new()
+            .unwrap()
+            .with_backend(ConnectionBackend::Netlink);
+        let Ok(established) = netlink.established_connections() else {
+            return; // sock_diag unavailable in this environment
+        };
+        let ours: Vec<_> = established
+            .iter()
+            .filter(|c| c.local_port == port || c.remote_port == Some(port))
+            .collect();
+        assert_eq!(ours.len(), 2);
+        assert!(ours.iter().all(|c| c.state == ConnectionState::Established));
+        assert!(ours.iter().all(|c| c.tcp_info.is_some()));
+        assert!(ours.iter().all(|c| c.pid == Some(std::process::id())));
+
+        let proc_fs = ConnectionMonitor::new()
+            .unwrap()
+            .with_backend(ConnectionBackend::Proc);
+        let listening = proc_fs
+            .tcp_connections_in_states(&[ConnectionState::Listen])
+            .unwrap();
+        assert!(listening
+            .iter()
+            .any(|c| c.local_port == port && c.tcp_info.is_none()));
+        assert!(listening.iter().all(|c| c.state == ConnectionState::Listen));
+    }
 }
@@ -0,0 +1,1373 @@
+//! Named CPU tuning profiles with atomic apply/revert and automatic switching
+//!
+//! A [`TuningProfile`] bundles the knobs that are otherwise set one by one through
+//! [`crate::cpufreq::CpuFreqMonitor`]: scaling governor, energy performance
+//! preference, turbo, frequency limits, C-state disables and the platform power
+//! profile. [`CpuTuner::apply`] records the previous value of every file it
+//! touches and rolls back on the first failed write, so a profile is either fully
+//! applied or not at all. The returned [`AppliedProfile`] is the journal used by
+//! [`CpuTuner::revert`] and can be persisted so a later process can undo it.
+//!
+//! [`ProfileSwitcher`] evaluates [`SwitchRule`]s (AC vs battery, GPU busy, time of
+//! day, process running) and moves between profiles, always reverting to the
+//! original state before applying the next one.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::cpu_profiles::{CpuTuner, TuningProfile};
+//!
+//! let profile = TuningProfile::new("benchmark")
+//!     .governor("performance")
+//!     .turbo(true)
+//!     .disable_idle_state("C6");
+//!
+//! let tuner = CpuTuner::new();
+//! let applied = tuner.apply(&profile).unwrap();
+//! for change in &applied.changes {
+//!     println!("{}", change);
+//! }
+//! // ... run the benchmark ...
+//! tuner.revert(&applied).unwrap();
+//! ```
+//!
+//! # Configuration
+//!
+//! ```toml
+//! default_profile = "balanced"
+//!
+//! [[profiles]]
+//! name = "training"
+//! governor = "performance"
+//! energy_preference = "performance"
+//! turbo = true
+//! disable_idle_states = ["C6", "C10"]
+//!
+//! [[profiles]]
+//! name = "idle-night"
+//! governor = "powersave"
+//! energy_preference = "power"
+//! max_freq_mhz = 2000
+//! power_profile = "power-saver"
+//!
+//! [[rules]]
+//! profile = "training"
+//! when = [{ type = "gpu_busy", min_utilization = 60.0 }]
+//!
+//! [[rules]]
+//! profile = "idle-night"
+//! when = [{ type = "time_of_day", start = "23:00", end = "07:00" }, { type = "on_battery" }]
+//! ```
+
+use crate::cpufreq::{EnergyPreference, Governor};
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// A named bundle of CPU power/performance settings
+///
+/// Every field is optional; unset fields are left untouched when the profile is applied.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TuningProfile {
+    /// Profile name (e.g. "training", "idle-night")
+    pub name: String,
+    /// Free-form description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Scaling governor (e.g. "performance", "schedutil")
+    #[serde(default)]
+    pub governor: Option<String>,
+    /// Energy performance preference (e.g. "balance_power")
+    #[serde(default)]
+    pub energy_preference: Option<String>,
+    /// Enable or disable turbo/boost
+    #[serde(default)]
+    pub turbo: Option<bool>,
+    /// Minimum scaling frequency (MHz)
+    #[serde(default)]
+    pub min_freq_mhz: Option<u32>,
+    /// Maximum scaling frequency (MHz)
+    #[serde(default)]
+    pub max_freq_mhz: Option<u32>,
+    /// C-states to disable, by name ("C6") or index ("state3")
+    #[serde(default)]
+    pub disable_idle_states: Vec<String>,
+    /// Platform power profile ("performance", "balanced", "power-saver")
+    #[serde(default)]
+    pub power_profile: Option<String>,
+}
+
+impl TuningProfile {
+    /// Create an empty profile
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the scaling governor
+    pub fn governor(mut self, governor: impl Into<String>) -> Self {
+        self.governor = Some(governor.into());
+        self
+    }
+
+    /// Set the energy performance preference
+    pub fn energy_preference(mut self, pref: EnergyPreference) -> Self {
+        self.energy_preference = Some(pref.to_string());
+        self
+    }
+
+    /// Enable or disable turbo
+    pub fn turbo(mut self, enabled: bool) -> Self {
+        self.turbo = Some(enabled);
+        self
+    }
+
+    /// Set scaling frequency limits in MHz
+    pub fn freq_range_mhz(mut self, min: Option<u32>, max: Option<u32>) -> Self {
+        self.min_freq_mhz = min;
+        self.max_freq_mhz = max;
+        self
+    }
+
+    /// Disable a C-state by name or `stateN` index
+    pub fn disable_idle_state(mut self, state: impl Into<String>) -> Self {
+        self.disable_idle_states.push(state.into());
+        self
+    }
+
+    /// Set the platform power profile
+    pub fn power_profile(mut self, profile: impl Into<String>) -> Self {
+        self.power_profile = Some(profile.into());
+        self
+    }
+
+    /// Check values that can be validated without touching the system
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(SimonError::Configuration(
+                "Tuning profile name must not be empty".into(),
+            ));
+        }
+        if let Some(ref epp) = self.energy_preference {
+            epp.parse::<EnergyPreference>()?;
+        }
+        if let (Some(min), Some(max)) = (self.min_freq_mhz, self.max_freq_mhz) {
+            if min > max {
+                return Err(SimonError::InvalidValue(format!(
+                    "Profile '{}': min_freq_mhz {} is above max_freq_mhz {}",
+                    self.name, min, max
+                )));
+            }
+        }
+        if let Some(ref p) = self.power_profile {
+            if power_profile_aliases(p).is_empty() {
+                return Err(SimonError::InvalidValue(format!(
+                    "Profile '{}': unknown power profile '{}'",
+                    self.name, p
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the profile changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.governor.is_none()
+            && self.energy_preference.is_none()
+            && self.turbo.is_none()
+            && self.min_freq_mhz.is_none()
+            && self.max_freq_mhz.is_none()
+            && self.disable_idle_states.is_empty()
+            && self.power_profile.is_none()
+    }
+}
+
+/// Names accepted for a power profile, in order of preference
+///
+/// power-profiles-daemon says "power-saver" where ACPI `platform_profile` says "low-power".
+fn power_profile_aliases(name: &str) -> Vec<&'static str> {
+    match name.to_lowercase().replace('_', "-").as_str() {
+        "performance" => vec!["performance"],
+        "balanced" | "balance" => vec!["balanced"],
+        "balanced-performance" => vec!["balanced-performance", "performance"],
+        "power-saver" | "powersave" | "low-power" => vec!["low-power", "power-saver", "quiet"],
+        "quiet" | "cool" => vec!["quiet", "cool", "low-power"],
+        _ => Vec::new(),
+    }
+}
+
+/// What a single tuning change writes to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TuningTarget {
+    /// A sysfs attribute
+    Sysfs { path: PathBuf },
+    /// power-profiles-daemon via `powerprofilesctl`
+    PowerProfilesDaemon,
+}
+
+/// One recorded change: what was written and what it replaced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TuningChange {
+    /// Human-readable setting name (e.g. "cpu3 governor")
+    pub setting: String,
+    /// Where the value is written
+    pub target: TuningTarget,
+    /// Value before the change
+    pub previous: String,
+    /// Value written
+    pub value: String,
+}
+
+impl std::fmt::Display for TuningChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.setting, self.previous, self.value)
+    }
+}
+
+/// Journal of an applied profile, used to restore the previous state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedProfile {
+    /// Name of the applied profile
+    pub profile: String,
+    /// Unix timestamp of application
+    pub applied_at: u64,
+    /// Changes in the order they were made
+    pub changes: Vec<TuningChange>,
+}
+
+impl AppliedProfile {
+    /// Default journal location (`~/.config/simon/cpu_profile_state.toml`)
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::config::Config::default_path()?.join("cpu_profile_state.toml"))
+    }
+
+    /// Save the journal so a later process can revert it
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| SimonError::Configuration(format!("Failed to serialize state: {}", e)))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Load a saved journal, if one exists
+    pub fn load_from(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| SimonError::Configuration(format!("Invalid profile state: {}", e)))
+    }
+}
+
+/// Applies and reverts [`TuningProfile`]s through sysfs
+pub struct CpuTuner {
+    sysfs_root: PathBuf,
+    use_power_profiles_daemon: bool,
+}
+
+impl Default for CpuTuner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpuTuner {
+    /// Tuner for the live system (`/sys`)
+    pub fn new() -> Self {
+        Self {
+            sysfs_root: PathBuf::from("/sys"),
+            use_power_profiles_daemon: true,
+        }
+    }
+
+    /// Tuner rooted at an alternate sysfs tree (power-profiles-daemon is not used)
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            sysfs_root: root.into(),
+            use_power_profiles_daemon: false,
+        }
+    }
+
+    fn cpu_dir(&self) -> PathBuf {
+        self.sysfs_root.join("devices/system/cpu")
+    }
+
+    /// Online CPU directories, sorted by CPU id
+    fn online_cpus(&self) -> Vec<(u32, PathBuf)> {
+        let mut cpus: Vec<(u32, PathBuf)> = fs::read_dir(self.cpu_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let id = name.strip_prefix("cpu")?.parse::<u32>().ok()?;
+                Some((id, e.path()))
+            })
+            .filter(|(_, path)| {
+                // cpu0 often has no `online` file and cannot be offlined
+                read_trimmed(&path.join("online")).map_or(true, |v| v == "1")
+            })
+            .collect();
+        cpus.sort_by_key(|(id, _)| *id);
+        cpus
+    }
+
+    /// Compute the changes needed to apply a profile without writing anything
+    ///
+    /// Settings already at the requested value are omitted. Fails if the profile asks
+    /// for something the hardware does not offer (unknown governor, missing turbo
+    /// control, unknown C-state, unsupported power profile).
+    pub fn plan(&self, profile: &TuningProfile) -> Result<Vec<TuningChange>> {
+        profile.validate()?;
+
+        let cpus = self.online_cpus();
+        if cpus.is_empty() && profile_touches_cpus(profile) {
+            return Err(SimonError::UnsupportedPlatform(
+                "No cpufreq-capable CPUs found".into(),
+            ));
+        }
+
+        let mut changes = Vec::new();
+        let mut push = |setting: String, path: PathBuf, value: String| -> Result<()> {
+            let previous = read_trimmed(&path).ok_or_else(|| {
+                SimonError::FeatureNotAvailable(format!("{} is not available", setting))
+            })?;
+            if previous != value {
+                changes.push(TuningChange {
+                    setting,
+                    target: TuningTarget::Sysfs { path },
+                    previous,
+                    value,
+                });
+            }
+            Ok(())
+        };
+
+        // Turbo first: on intel_pstate it changes the frequency range the limits are checked against
+        if let Some(enabled) = profile.turbo {
+            let intel = self.cpu_dir().join("intel_pstate/no_turbo");
+            let generic = self.cpu_dir().join("cpufreq/boost");
+            if intel.exists() {
+                push(
+                    "turbo".into(),
+                    intel,
+                    if enabled { "0" } else { "1" }.into(),
+                )?;
+            } else if generic.exists() {
+                push(
+                    "turbo".into(),
+                    generic,
+                    if enabled { "1" } else { "0" }.into(),
+                )?;
+            } else {
+                return Err(SimonError::FeatureNotAvailable(
+                    "No turbo control interface found".into(),
+                ));
+            }
+        }
+
+        for (id, dir) in &cpus {
+            let cpufreq = dir.join("cpufreq");
+
+            if let Some(ref gov) = profile.governor {
+                let gov = gov.parse::<Governor>()?.to_string();
+                let available = read_trimmed(&cpufreq.join("scaling_available_governors"));
+                if let Some(avail) = available {
+                    if !avail.split_whitespace().any(|g| g == gov) {
+                        return Err(SimonError::InvalidValue(format!(
+                            "cpu{}: governor '{}' not available ({})",
+                            id, gov, avail
+                        )));
+                    }
+                }
+                push(
+                    format!("cpu{} governor", id),
+                    cpufreq.join("scaling_governor"),
+                    gov,
+                )?;
+            }
+
+            if let Some(ref epp) = profile.energy_preference {
+                let epp = epp.parse::<EnergyPreference>()?.to_string();
+                push(
+                    format!("cpu{} energy_preference", id),
+                    cpufreq.join("energy_performance_preference"),
+                    epp,
+                )?;
+            }
+
+            // The kernel rejects min > max, so order the writes so the pair stays valid
+            let min_khz = profile.min_freq_mhz.map(|m| m as u64 * 1000);
+            let max_khz = profile.max_freq_mhz.map(|m| m as u64 * 1000);
+            let cur_max =
+                read_trimmed(&cpufreq.join("scaling_max_freq")).and_then(|v| v.parse::<u64>().ok());
+            let raise_max_first = matches!((min_khz, cur_max), (Some(min), Some(max)) if min > max);
+            let mut freq_writes = Vec::new();
+            if let Some(min) = min_khz {
+                freq_writes.push(("min_freq", "scaling_min_freq", min));
+            }
+            if let Some(max) = max_khz {
+                freq_writes.push(("max_freq", "scaling_max_freq", max));
+            }
+            if raise_max_first {
+                freq_writes.reverse();
+            }
+            for (label, file, khz) in freq_writes {
+                let hw_min = read_trimmed(&cpufreq.join("cpuinfo_min_freq"))
+                    .and_then(|v| v.parse::<u64>().ok());
+                let hw_max = read_trimmed(&cpufreq.join("cpuinfo_max_freq"))
+                    .and_then(|v| v.parse::<u64>().ok());
+                if hw_min.is_some_and(|lo| khz < lo) || hw_max.is_some_and(|hi| khz > hi) {
+                    return Err(SimonError::InvalidValue(format!(
+                        "cpu{}: {} {} MHz outside hardware range {}-{} MHz",
+                        id,
+                        label,
+                        khz / 1000,
+                        hw_min.unwrap_or(0) / 1000,
+                        hw_max.unwrap_or(0) / 1000
+                    )));
+                }
+                push(
+                    format!("cpu{} {}", id, label),
+                    cpufreq.join(file),
+                    khz.to_string(),
+                )?;
+            }
+
+            for wanted in &profile.disable_idle_states {
+                let state = find_idle_state(&dir.join("cpuidle"), wanted).ok_or_else(|| {
+                    SimonError::FeatureNotAvailable(format!(
+                        "cpu{}: no idle state '{}'",
+                        id, wanted
+                    ))
+                })?;
+                push(
+                    format!("cpu{} {} disable", id, wanted),
+                    state.join("disable"),
+                    "1".into(),
+                )?;
+            }
+        }
+
+        if let Some(ref wanted) = profile.power_profile {
+            changes.extend(self.plan_power_profile(wanted)?);
+        }
+
+        Ok(changes)
+    }
+
+    fn plan_power_profile(&self, wanted: &str) -> Result<Option<TuningChange>> {
+        let aliases = power_profile_aliases(wanted);
+        let acpi = self.sysfs_root.join("firmware/acpi");
+        let path = acpi.join("platform_profile");
+
+        if let Some(previous) = read_trimmed(&path) {
+            let choices = read_trimmed(&acpi.join("platform_profile_choices")).unwrap_or_default();
+            let value = aliases
+                .iter()
+                .find(|a| choices.split_whitespace().any(|c| c == **a))
+                .ok_or_else(|| {
+                    SimonError::FeatureNotAvailable(format!(
+                        "Power profile '{}' not offered by platform ({})",
+                        wanted, choices
+                    ))
+                })?
+                .to_string();
+            return Ok((previous != value).then(|| TuningChange {
+                setting: "power_profile".into(),
+                target: TuningTarget::Sysfs { path },
+                previous,
+                value,
+            }));
+        }
+
+        if self.use_power_profiles_daemon {
+            if let Some(previous) = powerprofilesctl(&["get"]) {
+                let value = match aliases[0] {
+                    "low-power" | "quiet" => "power-saver",
+                    "balanced-performance" => "performance",
+                    other => other,
+                }
+                .to_string();
+                return Ok((previous != value).then(|| TuningChange {
+                    setting: "power_profile".into(),
+                    target: TuningTarget::PowerProfilesDaemon,
+                    previous,
+                    value,
+                }));
+            }
+        }
+
+        Err(SimonError::FeatureNotAvailable(
+            "No platform_profile or power-profiles-daemon found".into(),
+        ))
+    }
+
+    /// Apply a profile atomically
+    ///
+    /// If any write fails, the writes already made are undone in reverse order and
+    /// the error is returned.
+    pub fn apply(&self, profile: &TuningProfile) -> Result<AppliedProfile> {
+        let changes = self.plan(profile)?;
+        self.apply_changes(&profile.name, changes)
+    }
+
+    fn apply_changes(&self, name: &str, changes: Vec<TuningChange>) -> Result<AppliedProfile> {
+        for (i, change) in changes.iter().enumerate() {
+            if let Err(e) = write_target(&change.target, &change.value) {
+                for done in changes[..i].iter().rev() {
+                    if let Err(re) = write_target(&done.target, &done.previous) {
+                        log::warn!("Rollback of '{}' failed: {}", done.setting, re);
+                    }
+                }
+                return Err(SimonError::System(format!(
+                    "Applying profile '{}' failed at {} (rolled back): {}",
+                    name, change.setting, e
+                )));
+            }
+        }
+
+        Ok(AppliedProfile {
+            profile: name.to_string(),
+            applied_at: unix_now(),
+            changes,
+        })
+    }
+
+    /// Restore the state recorded in `applied`
+    ///
+    /// Every change is attempted even if an earlier one fails; the first error is returned.
+    pub fn revert(&self, applied: &AppliedProfile) -> Result<()> {
+        let mut first_error = None;
+        for change in applied.changes.iter().rev() {
+            if let Err(e) = write_target(&change.target, &change.previous) {
+                log::warn!("Failed to restore '{}': {}", change.setting, e);
+                first_error.get_or_insert(e);
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+fn profile_touches_cpus(profile: &TuningProfile) -> bool {
+    profile.governor.is_some()
+        || profile.energy_preference.is_some()
+        || profile.min_freq_mhz.is_some()
+        || profile.max_freq_mhz.is_some()
+        || !profile.disable_idle_states.is_empty()
+}
+
+fn find_idle_state(cpuidle: &Path, wanted: &str) -> Option<PathBuf> {
+    let direct = cpuidle.join(wanted);
+    if wanted.starts_with("state") && direct.join("disable").exists() {
+        return Some(direct);
+    }
+    (0..32)
+        .map(|i| cpuidle.join(format!("state{}", i)))
+        .take_while(|p| p.exists())
+        .find(|p| read_trimmed(&p.join("name")).is_some_and(|n| n.eq_ignore_ascii_case(wanted)))
+}
+
+fn write_target(target: &TuningTarget, value: &str) -> Result<()> {
+    match target {
+        TuningTarget::Sysfs { path } => fs::write(path, value).map_err(|e| {
+            SimonError::System(format!(
+                "Failed to write {} (need root?): {}",
+                path.display(),
+                e
+            ))
+        }),
+        TuningTarget::PowerProfilesDaemon => powerprofilesctl(&["set", value])
+            .map(|_| ())
+            .ok_or_else(|| SimonError::CommandFailed("powerprofilesctl set failed".into())),
+    }
+}
+
+fn powerprofilesctl(args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("powerprofilesctl")
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A condition evaluated by [`ProfileSwitcher`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Condition {
+    /// Running from mains power
+    OnAc,
+    /// Running from battery
+    OnBattery,
+    /// A GPU (or the given one) is at or above a utilization threshold
+    GpuBusy {
+        #[serde(default = "default_gpu_busy_threshold")]
+        min_utilization: f32,
+        #[serde(default)]
+        gpu: Option<usize>,
+    },
+    /// Local time within `start`..`end` ("HH:MM"); wraps past midnight when end < start
+    TimeOfDay { start: String, end: String },
+    /// A process with the given name (comm or executable basename) is running
+    ProcessRunning { name: String },
+}
+
+fn default_gpu_busy_threshold() -> f32 {
+    50.0
+}
+
+impl Condition {
+    /// Evaluate against a snapshot of the system
+    pub fn matches(&self, ctx: &SwitchContext) -> bool {
+        match self {
+            Condition::OnAc => ctx.on_ac == Some(true),
+            Condition::OnBattery => ctx.on_ac == Some(false),
+            Condition::GpuBusy {
+                min_utilization,
+                gpu,
+            } => match gpu {
+                Some(i) => ctx
+                    .gpu_utilization
+                    .get(*i)
+                    .is_some_and(|u| *u >= *min_utilization),
+                None => ctx.gpu_utilization.iter().any(|u| *u >= *min_utilization),
+            },
+            Condition::TimeOfDay { start, end } => match (parse_hhmm(start), parse_hhmm(end)) {
+                (Some(s), Some(e)) => in_window(ctx.minute_of_day, s, e),
+                _ => false,
+            },
+            Condition::ProcessRunning { name } => ctx.processes.contains(name),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Condition::TimeOfDay { start, end } = self {
+            for t in [start, end] {
+                if parse_hhmm(t).is_none() {
+                    return Err(SimonError::Configuration(format!(
+                        "Invalid time '{}' (expected HH:MM)",
+                        t
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_hhmm(s: &str) -> Option<u32> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+fn in_window(now: u32, start: u32, end: u32) -> bool {
+    match start.cmp(&end) {
+        std::cmp::Ordering::Less => now >= start && now < end,
+        std::cmp::Ordering::Greater => now >= start || now < end,
+        std::cmp::Ordering::Equal => true,
+    }
+}
+
+/// Switch to `profile` when all conditions in `when` hold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwitchRule {
+    /// Profile to activate
+    pub profile: String,
+    /// Conditions that must all hold (empty always matches)
+    #[serde(default)]
+    pub when: Vec<Condition>,
+}
+
+/// Snapshot of the conditions rules are evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct SwitchContext {
+    /// Mains power state, if known
+    pub on_ac: Option<bool>,
+    /// Per-GPU utilization (0-100)
+    pub gpu_utilization: Vec<f32>,
+    /// Local minutes since midnight
+    pub minute_of_day: u32,
+    /// Names of running processes
+    pub processes: HashSet<String>,
+}
+
+impl SwitchContext {
+    /// Current local minutes since midnight
+    pub fn local_minute_of_day() -> u32 {
+        use chrono::Timelike;
+        let now = chrono::Local::now();
+        now.hour() * 60 + now.minute()
+    }
+}
+
+/// Running process names from `/proc` (comm and executable basename)
+pub fn running_process_names() -> HashSet<String> {
+    let mut names = HashSet::new();
+    for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+        let path = entry.path();
+        if !entry
+            .file_name()
+            .to_string_lossy()
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+        {
+            continue;
+        }
+        if let Some(comm) = read_trimmed(&path.join("comm")) {
+            names.insert(comm);
+        }
+        if let Ok(cmdline) = fs::read(path.join("cmdline")) {
+            if let Some(arg0) = cmdline.split(|b| *b == 0).next() {
+                let arg0 = String::from_utf8_lossy(arg0);
+                if let Some(base) = Path::new(arg0.as_ref()).file_name() {
+                    names.insert(base.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Profiles, switching rules and loop settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuProfilesConfig {
+    /// Run automatic switching (daemon)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Rule evaluation interval
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    /// Minimum time a profile stays active before switching again
+    #[serde(default = "default_min_dwell_secs")]
+    pub min_dwell_secs: u64,
+    /// Profile used when no rule matches (none: restore the original state)
+    #[serde(default)]
+    pub default_profile: Option<String>,
+    /// Named profiles
+    #[serde(default)]
+    pub profiles: Vec<TuningProfile>,
+    /// Rules in priority order; the first matching rule wins
+    #[serde(default)]
+    pub rules: Vec<SwitchRule>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_interval_ms() -> u64 {
+    5000
+}
+
+fn default_min_dwell_secs() -> u64 {
+    30
+}
+
+impl Default for CpuProfilesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: default_interval_ms(),
+            min_dwell_secs: default_min_dwell_secs(),
+            default_profile: None,
+            profiles: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl CpuProfilesConfig {
+    /// Default profile file (`~/.config/simon/cpu_profiles.toml`)
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::config::Config::default_path()?.join("cpu_profiles.toml"))
+    }
+
+    /// Load from the default path
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    /// Load from a TOML file
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            SimonError::Configuration(format!("Cannot read {}: {}", path.display(), e))
+        })?;
+        Self::from_toml(&content)
+    }
+
+    /// Parse and validate from a TOML string
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)
+            .map_err(|e| SimonError::Configuration(format!("TOML parse error: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check profile values and that every referenced profile exists
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for profile in &self.profiles {
+            profile.validate()?;
+            if !names.insert(profile.name.as_str()) {
+                return Err(SimonError::Configuration(format!(
+                    "Duplicate profile '{}'",
+                    profile.name
+                )));
+            }
+        }
+        let referenced = self
+            .rules
+            .iter()
+            .map(|r| r.profile.as_str())
+            .chain(self.default_profile.as_deref());
+        for name in referenced {
+            if !names.contains(name) {
+                return Err(SimonError::Configuration(format!(
+                    "Unknown profile '{}'",
+                    name
+                )));
+            }
+        }
+        for rule in &self.rules {
+            for cond in &rule.when {
+                cond.validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Look up a profile by name
+    pub fn profile(&self, name: &str) -> Option<&TuningProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Profile selected for the given context
+    pub fn select(&self, ctx: &SwitchContext) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|r| r.when.iter().all(|c| c.matches(ctx)))
+            .map(|r| r.profile.as_str())
+            .or(self.default_profile.as_deref())
+    }
+
+    fn needs_gpu(&self) -> bool {
+        self.rules
+            .iter()
+            .flat_map(|r| &r.when)
+            .any(|c| matches!(c, Condition::GpuBusy { .. }))
+    }
+
+    fn needs_processes(&self) -> bool {
+        self.rules
+            .iter()
+            .flat_map(|r| &r.when)
+            .any(|c| matches!(c, Condition::ProcessRunning { .. }))
+    }
+}
+
+/// Evaluates switching rules and keeps the matching profile applied
+///
+/// Only one profile is applied at a time: the active one is reverted before the
+/// next is applied, so reverting always returns to the state before the switcher
+/// started. Dropping the switcher restores that state.
+pub struct ProfileSwitcher {
+    config: CpuProfilesConfig,
+    tuner: CpuTuner,
+    active: Option<AppliedProfile>,
+    last_switch: Option<Instant>,
+    /// GPU devices, enumerated on first use
+    gpus: Option<Vec<Box<dyn crate::gpu::Device>>>,
+}
+
+impl ProfileSwitcher {
+    /// Create a switcher for the live system
+    pub fn new(config: CpuProfilesConfig) -> Result<Self> {
+        Self::with_tuner(config, CpuTuner::new())
+    }
+
+    /// Create a switcher with a custom tuner (e.g. an alternate sysfs root)
+    pub fn with_tuner(config: CpuProfilesConfig, tuner: CpuTuner) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            tuner,
+            active: None,
+            last_switch: None,
+            gpus: None,
+        })
+    }
+
+    /// Name of the currently applied profile
+    pub fn active_profile(&self) -> Option<&str> {
+        self.active.as_ref().map(|a| a.profile.as_str())
+    }
+
+    /// Collect the live context, querying GPUs and processes only if a rule needs them
+    pub fn gather_context(&mut self) -> SwitchContext {
+        let gpu_utilization = if self.config.needs_gpu() {
+            self.gpus
+                .get_or_insert_with(crate::gpu::control::enumerate_devices)
+                .iter()
+                .map(|g| g.utilization().map(|u| u.gpu).unwrap_or(0.0))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let processes = if self.config.needs_processes() {
+            running_process_names()
+        } else {
+            HashSet::new()
+        };
+        let monitor = crate::power_supply::PowerSupplyMonitor::new().ok();
+        SwitchContext {
+            on_ac: monitor
+                .filter(|m| !m.supplies().is_empty())
+                .map(|m| m.on_ac_power()),
+            gpu_utilization,
+            minute_of_day: SwitchContext::local_minute_of_day(),
+            processes,
+        }
+    }
+
+    /// Evaluate rules against `ctx` and switch profiles if needed
+    ///
+    /// Returns the name of the newly applied profile when a switch happened.
+    pub fn evaluate(&mut self, ctx: &SwitchContext, now: Instant) -> Result<Option<String>> {
+        let wanted = self.config.select(ctx).map(str::to_string);
+        if wanted.as_deref() == self.active_profile() {
+            return Ok(None);
+        }
+        let dwell = Duration::from_secs(self.config.min_dwell_secs);
+        if self.active.is_some()
+            && self
+                .last_switch
+                .is_some_and(|t| now.saturating_duration_since(t) < dwell)
+        {
+            return Ok(None);
+        }
+
+        self.restore()?;
+        self.last_switch = Some(now);
+        let Some(name) = wanted else {
+            return Ok(None);
+        };
+        let profile = self
+            .config
+            .profile(&name)
+            .ok_or_else(|| SimonError::Configuration(format!("Unknown profile '{}'", name)))?;
+        self.active = Some(self.tuner.apply(profile)?);
+        Ok(Some(name))
+    }
+
+    /// Gather the live context and evaluate once
+    pub fn tick(&mut self) -> Result<Option<String>> {
+        let ctx = self.gather_context();
+        self.evaluate(&ctx, Instant::now())
+    }
+
+    /// Revert the active profile, returning to the original state
+    pub fn restore(&mut self) -> Result<()> {
+        if let Some(applied) = self.active.take() {
+            self.tuner.revert(&applied)?;
+        }
+        Ok(())
+    }
+
+    /// Run until `stop` is set, then restore the original state
+    pub fn run(mut self, stop: Arc<AtomicBool>) {
+        let interval = Duration::from_millis(self.config.interval_ms.max(100));
+        while !stop.load(Ordering::SeqCst) {
+            match self.tick() {
+                Ok(Some(name)) => log::info!("CPU profile switched to '{}'", name),
+                Ok(None) => {}
+                Err(e) => log::warn!("CPU profile switch failed: {}", e),
+            }
+            std::thread::sleep(interval);
+        }
+        if let Err(e) = self.restore() {
+            log::warn!("Failed to restore CPU settings: {}", e);
+        }
+    }
+
+    /// Run the switcher on a background thread
+    ///
+    /// If the thread cannot be started the switcher is dropped, which restores
+    /// the original CPU settings.
+    pub fn spawn(self) -> Result<ProfileSwitcherHandle> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let thread = std::thread::Builder::new()
+            .name("simon-cpu-profiles".into())
+            .spawn(move || self.run(flag))
+            .map_err(|e| {
+                SimonError::Other(format!(
+                    "Failed to spawn CPU profile switcher thread: {}",
+                    e
+                ))
+            })?;
+        Ok(ProfileSwitcherHandle {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for ProfileSwitcher {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+/// Handle to a running profile switcher thread
+///
+/// Dropping the handle stops the switcher and restores the original CPU settings.
+pub struct ProfileSwitcherHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ProfileSwitcherHandle {
+    /// Stop the switcher and wait for settings to be restored
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    /// Check if the switcher thread is still running
+    pub fn is_running(&self) -> bool {
+        self.thread.as_ref().is_some_and(|t| !t.is_finished())
+    }
+}
+
+impl Drop for ProfileSwitcherHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    /// cpufreq, cpuidle, intel_pstate and ACPI platform profile files for `cpus` CPUs
+    fn fake_sysfs(tag: &str, cpus: u32) -> FakeRoot {
+        let sys = FakeRoot::new(&format!("cpu-profiles-{}", tag));
+        for id in 0..cpus {
+            let freq = format!("devices/system/cpu/cpu{}/cpufreq", id);
+            for (file, value) in [
+                ("scaling_governor", "schedutil"),
+                (
+                    "scaling_available_governors",
+                    "performance powersave schedutil",
+                ),
+                ("energy_performance_preference", "balance_performance"),
+                ("scaling_min_freq", "800000"),
+                ("scaling_max_freq", "3000000"),
+                ("cpuinfo_min_freq", "400000"),
+                ("cpuinfo_max_freq", "5000000"),
+            ] {
+                sys.write(&format!("{}/{}", freq, file), value);
+            }
+            for (i, name) in ["POLL", "C1", "C6"].iter().enumerate() {
+                let state = format!("devices/system/cpu/cpu{}/cpuidle/state{}", id, i);
+                sys.write(&format!("{}/name", state), name);
+                sys.write(&format!("{}/disable", state), "0");
+            }
+        }
+        sys.write("devices/system/cpu/intel_pstate/no_turbo", "0");
+        sys.write("firmware/acpi/platform_profile", "balanced");
+        sys.write(
+            "firmware/acpi/platform_profile_choices",
+            "low-power balanced performance",
+        );
+        sys
+    }
+
+    fn night_profile() -> TuningProfile {
+        TuningProfile::new("idle-night")
+            .governor("powersave")
+            .energy_preference(EnergyPreference::Power)
+            .turbo(false)
+            .freq_range_mhz(None, Some(2000))
+            .disable_idle_state("C6")
+            .power_profile("power-saver")
+    }
+
+    #[test]
+    fn test_apply_and_revert_restores_previous_state() {
+        let sys = fake_sysfs("revert", 2);
+        let tuner = CpuTuner::with_root(sys.path());
+
+        let applied = tuner.apply(&night_profile()).unwrap();
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu1/cpufreq/scaling_governor"),
+            "powersave"
+        );
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu0/cpufreq/energy_performance_preference"),
+            "power"
+        );
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu0/cpufreq/scaling_max_freq"),
+            "2000000"
+        );
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu1/cpuidle/state2/disable"),
+            "1"
+        );
+        assert_eq!(sys.read("devices/system/cpu/intel_pstate/no_turbo"), "1");
+        assert_eq!(sys.read("firmware/acpi/platform_profile"), "low-power");
+
+        tuner.revert(&applied).unwrap();
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu1/cpufreq/scaling_governor"),
+            "schedutil"
+        );
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu0/cpufreq/scaling_max_freq"),
+            "3000000"
+        );
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu1/cpuidle/state2/disable"),
+            "0"
+        );
+        assert_eq!(sys.read("devices/system/cpu/intel_pstate/no_turbo"), "0");
+        assert_eq!(sys.read("firmware/acpi/platform_profile"), "balanced");
+    }
+
+    #[test]
+    fn test_apply_rolls_back_on_failure() {
+        let sys = fake_sysfs("rollback", 2);
+        let tuner = CpuTuner::with_root(sys.path());
+
+        let mut changes = tuner.plan(&night_profile()).unwrap();
+        changes.push(TuningChange {
+            setting: "broken".into(),
+            target: TuningTarget::Sysfs {
+                path: sys.join("missing/dir/attr"),
+            },
+            previous: "0".into(),
+            value: "1".into(),
+        });
+        assert!(tuner.apply_changes("idle-night", changes).is_err());
+
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            "schedutil"
+        );
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu1/cpuidle/state2/disable"),
+            "0"
+        );
+        assert_eq!(sys.read("devices/system/cpu/intel_pstate/no_turbo"), "0");
+        assert_eq!(sys.read("firmware/acpi/platform_profile"), "balanced");
+
+        // A missing attribute fails planning, before anything is written
+        fs::remove_file(sys.join("devices/system/cpu/cpu1/cpufreq/energy_performance_preference"))
+            .unwrap();
+        assert!(tuner.apply(&night_profile()).is_err());
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            "schedutil"
+        );
+    }
+
+    #[test]
+    fn test_plan_validates_against_hardware() {
+        let sys = fake_sysfs("plan", 1);
+        let tuner = CpuTuner::with_root(sys.path());
+
+        assert!(tuner
+            .plan(&TuningProfile::new("x").governor("ondemand"))
+            .is_err());
+        assert!(tuner
+            .plan(&TuningProfile::new("x").disable_idle_state("C10"))
+            .is_err());
+        assert!(tuner
+            .plan(&TuningProfile::new("x").freq_range_mhz(None, Some(6000)))
+            .is_err());
+
+        // Already-set values produce no changes
+        let noop = TuningProfile::new("noop").governor("schedutil").turbo(true);
+        assert!(tuner.plan(&noop).unwrap().is_empty());
+
+        // Raising min above the current max writes max first
+        let high = TuningProfile::new("high").freq_range_mhz(Some(3500), Some(4500));
+        let changes = tuner.plan(&high).unwrap();
+        assert_eq!(changes[0].setting, "cpu0 max_freq");
+        assert_eq!(changes[1].setting, "cpu0 min_freq");
+    }
+
+    #[test]
+    fn test_conditions_and_rule_selection() {
+        let config = CpuProfilesConfig::from_toml(
+            r#"
+default_profile = "balanced"
+
+[[profiles]]
+name = "balanced"
+governor = "schedutil"
+
+[[profiles]]
+name = "training"
+governor = "performance"
+
+[[profiles]]
+name = "idle-night"
+governor = "powersave"
+
+[[rules]]
+profile = "training"
+when = [{ type = "gpu_busy", min_utilization = 60.0 }, { type = "on_ac" }]
+
+[[rules]]
+profile = "training"
+when = [{ type = "process_running", name = "torchrun" }]
+
+[[rules]]
+profile = "idle-night"
+when = [{ type = "time_of_day", start = "23:00", end = "07:00" }]
+"#,
+        )
+        .unwrap();
+
+        let mut ctx = SwitchContext {
+            on_ac: Some(true),
+            gpu_utilization: vec![10.0, 80.0],
+            minute_of_day: 12 * 60,
+            processes: HashSet::new(),
+        };
+        assert_eq!(config.select(&ctx), Some("training"));
+
+        ctx.on_ac = Some(false);
+        assert_eq!(config.select(&ctx), Some("balanced"));
+
+        ctx.processes.insert("torchrun".into());
+        assert_eq!(config.select(&ctx), Some("training"));
+
+        ctx.processes.clear();
+        ctx.minute_of_day = 2 * 60;
+        assert_eq!(config.select(&ctx), Some("idle-night"));
+        ctx.minute_of_day = 7 * 60;
+        assert_eq!(config.select(&ctx), Some("balanced"));
+
+        assert!(CpuProfilesConfig::from_toml(
+            "[[rules]]\nprofile = \"missing\"\nwhen = [{ type = \"on_ac\" }]\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_switcher_reverts_between_profiles() {
+        let sys = fake_sysfs("switcher", 1);
+        let config = CpuProfilesConfig {
+            min_dwell_secs: 10,
+            profiles: vec![
+                TuningProfile::new("battery").governor("powersave"),
+                TuningProfile::new("ac").turbo(false),
+            ],
+            rules: vec![
+                SwitchRule {
+                    profile: "battery".into(),
+                    when: vec![Condition::OnBattery],
+                },
+                SwitchRule {
+                    profile: "ac".into(),
+                    when: vec![Condition::OnAc],
+                },
+            ],
+            ..Default::default()
+        };
+        let mut switcher =
+            ProfileSwitcher::with_tuner(config, CpuTuner::with_root(sys.path())).unwrap();
+        let t0 = Instant::now();
+        let mut ctx = SwitchContext {
+            on_ac: Some(false),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            switcher.evaluate(&ctx, t0).unwrap().as_deref(),
+            Some("battery")
+        );
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            "powersave"
+        );
+
+        // Within the dwell time nothing changes
+        ctx.on_ac = Some(true);
+        assert_eq!(
+            switcher
+                .evaluate(&ctx, t0 + Duration::from_secs(5))
+                .unwrap(),
+            None
+        );
+        assert_eq!(switcher.active_profile(), Some("battery"));
+
+        // After it, the battery profile is reverted before the AC profile is applied
+        let t1 = t0 + Duration::from_secs(11);
+        assert_eq!(switcher.evaluate(&ctx, t1).unwrap().as_deref(), Some("ac"));
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            "schedutil"
+        );
+        assert_eq!(sys.read("devices/system/cpu/intel_pstate/no_turbo"), "1");
+
+        drop(switcher);
+        assert_eq!(sys.read("devices/system/cpu/intel_pstate/no_turbo"), "0");
+    }
+
+    #[test]
+    fn test_applied_profile_roundtrip() {
+        let sys = fake_sysfs("journal", 1);
+        let tuner = CpuTuner::with_root(sys.path());
+        let applied = tuner.apply(&night_profile()).unwrap();
+
+        let path = sys.join("state.toml");
+        applied.save_to(&path).unwrap();
+        let loaded = AppliedProfile::load_from(&path).unwrap().unwrap();
+        assert_eq!(loaded, applied);
+
+        tuner.revert(&loaded).unwrap();
+        assert_eq!(
+            sys.read("devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            "schedutil"
+        );
+    }
+}
@@ -0,0 +1,905 @@
+//! Unified CPU topology: package → die → L3 domain (CCX) → core type → core → SMT threads
+//!
+//! `cpu_cache`, `numa` and the hybrid-core heuristics in `silicon` each describe one
+//! slice of the CPU. This module joins them into a single tree so per-core
+//! utilization, frequency and temperature can be shown grouped the way the
+//! scheduler sees the hardware: a saturated CCD next to an idle one, P-cores
+//! versus E-cores, or SMT siblings competing for the same core.
+//!
+//! # Platform Support
+//!
+//! - **Linux**: `/sys/devices/system/cpu/cpu*/topology` and `cache/`,
+//!   `/sys/devices/system/node`, hybrid core types from the `cpu_core`/`cpu_atom`
+//!   PMUs (Intel) or `cpu_capacity` (ARM big.LITTLE), temperatures from the
+//!   `coretemp`/`k10temp` hwmon drivers.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::cpu_topology::{CpuTopology, TopologySampler};
+//!
+//! let mut sampler = TopologySampler::new().unwrap();
+//! std::thread::sleep(std::time::Duration::from_secs(1));
+//! let metrics = sampler.sample();
+//!
+//! for load in sampler.topology().domain_loads(&metrics) {
+//!     println!("{}: {:.0}% avg, {:.0}% max", load.label, load.utilization, load.max_utilization);
+//! }
+//! ```
+
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Core microarchitecture class on hybrid CPUs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CoreType {
+    /// Performance cores (Intel P-core, ARM big)
+    Performance,
+    /// Efficiency cores (Intel E-core, ARM LITTLE)
+    Efficiency,
+    /// Non-hybrid CPU
+    Standard,
+}
+
+impl std::fmt::Display for CoreType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Performance => write!(f, "P-core"),
+            Self::Efficiency => write!(f, "E-core"),
+            Self::Standard => write!(f, "Core"),
+        }
+    }
+}
+
+/// One logical CPU (hardware thread) and where it sits in the topology
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogicalCpu {
+    /// Logical CPU number (as in `/proc/stat` and `taskset`)
+    pub cpu: u32,
+    /// Physical package (socket)
+    pub package: u32,
+    /// Die within the package
+    pub die: u32,
+    /// Core ID within the package
+    pub core_id: u32,
+    /// Cluster ID (cores sharing an L2 on Intel E-core modules and ARM)
+    pub cluster_id: Option<u32>,
+    /// Index into [`CpuTopology::l2_domains`]
+    pub l2_domain: Option<usize>,
+    /// Index into [`CpuTopology::l3_domains`]
+    pub l3_domain: Option<usize>,
+    /// NUMA node
+    pub numa_node: Option<u32>,
+    pub core_type: CoreType,
+    /// Logical CPUs on the same physical core, including this one
+    pub smt_siblings: Vec<u32>,
+    /// Maximum frequency in MHz
+    pub max_freq_mhz: Option<u32>,
+}
+
+/// A cache shared by a set of logical CPUs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheDomain {
+    /// Cache level (2 or 3)
+    pub level: u8,
+    /// Cache ID as reported by the kernel (0 if not reported)
+    pub id: u32,
+    pub size_kb: u64,
+    /// Logical CPUs sharing the cache
+    pub cpus: Vec<u32>,
+}
+
+/// A physical core with its SMT threads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhysicalCore {
+    pub core_id: u32,
+    pub core_type: CoreType,
+    pub numa_node: Option<u32>,
+    /// Index into [`CpuTopology::l2_domains`]
+    pub l2_domain: Option<usize>,
+    /// Logical CPUs (SMT threads), ascending
+    pub threads: Vec<u32>,
+}
+
+/// Cores of one type within an L3 domain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreTypeGroup {
+    pub core_type: CoreType,
+    pub cores: Vec<PhysicalCore>,
+}
+
+/// Cores sharing one L3 (a CCX on AMD, the ring on Intel)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct L3Group {
+    /// Index into [`CpuTopology::l3_domains`] (None if no L3 was reported)
+    pub l3_domain: Option<usize>,
+    pub groups: Vec<CoreTypeGroup>,
+}
+
+/// One die (or CCD where the kernel exposes it as a die)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DieNode {
+    pub id: u32,
+    pub l3_groups: Vec<L3Group>,
+}
+
+/// One physical package (socket)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageNode {
+    pub id: u32,
+    /// NUMA nodes with CPUs in this package
+    pub numa_nodes: Vec<u32>,
+    pub dies: Vec<DieNode>,
+}
+
+/// Live per-CPU measurements
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreMetrics {
+    pub cpu: u32,
+    /// Busy percentage since the previous sample (0-100)
+    pub utilization: f32,
+    pub frequency_mhz: Option<u32>,
+    /// Core (or CCD) temperature in Celsius
+    pub temperature: Option<f32>,
+}
+
+/// Aggregate load of one L3 domain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainLoad {
+    pub package: u32,
+    pub die: u32,
+    pub l3_domain: Option<usize>,
+    /// Display label (e.g. "pkg0/die0/L3#1")
+    pub label: String,
+    /// Logical CPUs in the domain
+    pub cpus: usize,
+    /// Mean utilization of the domain's CPUs
+    pub utilization: f32,
+    /// Busiest CPU in the domain
+    pub max_utilization: f32,
+    pub frequency_mhz: Option<u32>,
+    /// Hottest reading in the domain
+    pub temperature: Option<f32>,
+}
+
+/// CPU topology of the whole system
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuTopology {
+    /// Online logical CPUs, ascending
+    pub cpus: Vec<LogicalCpu>,
+    pub l2_domains: Vec<CacheDomain>,
+    pub l3_domains: Vec<CacheDomain>,
+    /// Whether more than one core type is present
+    pub hybrid: bool,
+}
+
+impl CpuTopology {
+    /// Detect the topology of the running system
+    pub fn detect() -> Result<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            Self::from_sysfs("/")
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(SimonError::UnsupportedPlatform(
+                "CPU topology detection is only implemented on Linux".into(),
+            ))
+        }
+    }
+
+    /// Read the topology from a sysfs tree rooted at `root`
+    pub fn from_sysfs(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let cpu_dir = root.join("sys/devices/system/cpu");
+        let entries = std::fs::read_dir(&cpu_dir)?;
+
+        let mut cpu_ids: Vec<u32> = entries
+            .flatten()
+            .filter_map(|e| {
+                e.file_name()
+                    .to_str()?
+                    .strip_prefix("cpu")?
+                    .parse::<u32>()
+                    .ok()
+            })
+            // Offline CPUs have no topology directory
+            .filter(|n| cpu_dir.join(format!("cpu{}/topology/core_id", n)).exists())
+            .collect();
+        cpu_ids.sort_unstable();
+        if cpu_ids.is_empty() {
+            return Err(SimonError::FeatureNotAvailable(
+                "no CPU topology in sysfs".into(),
+            ));
+        }
+
+        let numa = read_numa_nodes(root);
+        let core_types = read_core_types(root, &cpu_ids);
+
+        let mut l2_domains: Vec<CacheDomain> = Vec::new();
+        let mut l3_domains: Vec<CacheDomain> = Vec::new();
+        let mut cpus = Vec::with_capacity(cpu_ids.len());
+
+        for &cpu in &cpu_ids {
+            let base = cpu_dir.join(format!("cpu{}", cpu));
+            let topo = base.join("topology");
+            let read = |name: &str| read_u32(&topo.join(name));
+
+            let mut l2_domain = None;
+            let mut l3_domain = None;
+            for cache in read_caches(&base) {
+                let domains = match cache.level {
+                    2 => &mut l2_domains,
+                    3 => &mut l3_domains,
+                    _ => continue,
+                };
+                let idx = match domains.iter().position(|d| d.cpus == cache.cpus) {
+                    Some(i) => i,
+                    None => {
+                        domains.push(cache);
+                        domains.len() - 1
+                    }
+                };
+                if domains[idx].level == 2 {
+                    l2_domain = Some(idx);
+                } else {
+                    l3_domain = Some(idx);
+                }
+            }
+
+            let smt_siblings = std::fs::read_to_string(topo.join("thread_siblings_list"))
+                .map(|s| parse_cpu_list(&s))
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| vec![cpu]);
+
+            cpus.push(LogicalCpu {
+                cpu,
+                package: read("physical_package_id").unwrap_or(0),
+                die: read("die_id").unwrap_or(0),
+                core_id: read("core_id").unwrap_or(cpu),
+                cluster_id: read("cluster_id"),
+                l2_domain,
+                l3_domain,
+                numa_node: numa.get(&cpu).copied(),
+                core_type: core_types.get(&cpu).copied().unwrap_or(CoreType::Standard),
+                smt_siblings,
+                max_freq_mhz: read_u32(&base.join("cpufreq/cpuinfo_max_freq")).map(|k| k / 1000),
+            });
+        }
+
+        let hybrid = cpus.iter().any(|c| c.core_type == CoreType::Efficiency)
+            && cpus.iter().any(|c| c.core_type == CoreType::Performance);
+
+        Ok(Self {
+            cpus,
+            l2_domains,
+            l3_domains,
+            hybrid,
+        })
+    }
+
+    /// Look up a logical CPU
+    pub fn cpu(&self, cpu: u32) -> Option<&LogicalCpu> {
+        self.cpus.iter().find(|c| c.cpu == cpu)
+    }
+
+    /// Number of physical cores
+    pub fn physical_cores(&self) -> usize {
+        self.cpus
+            .iter()
+            .filter(|c| c.smt_siblings.first() == Some(&c.cpu))
+            .count()
+    }
+
+    /// Number of packages
+    pub fn package_count(&self) -> usize {
+        let mut ids: Vec<u32> = self.cpus.iter().map(|c| c.package).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.len()
+    }
+
+    /// The topology as a tree, ordered by package, die, L3 domain, core type
+    /// (P-cores first) and core
+    pub fn tree(&self) -> Vec<PackageNode> {
+        type CoreKey = (u32, u32, Option<usize>, CoreType, u32);
+        let mut cores: BTreeMap<CoreKey, PhysicalCore> = BTreeMap::new();
+        let mut numa: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+
+        for c in &self.cpus {
+            let key = (c.package, c.die, c.l3_domain, c.core_type, c.core_id);
+            let core = cores.entry(key).or_insert_with(|| PhysicalCore {
+                core_id: c.core_id,
+                core_type: c.core_type,
+                numa_node: c.numa_node,
+                l2_domain: c.l2_domain,
+                threads: Vec::new(),
+            });
+            core.threads.push(c.cpu);
+            if let Some(node) = c.numa_node {
+                let nodes = numa.entry(c.package).or_default();
+                if !nodes.contains(&node) {
+                    nodes.push(node);
+                }
+            }
+        }
+
+        let mut packages: Vec<PackageNode> = Vec::new();
+        for ((package, die, l3, core_type, _), core) in cores {
+            if packages.last().map(|p| p.id) != Some(package) {
+                let mut numa_nodes = numa.get(&package).cloned().unwrap_or_default();
+                numa_nodes.sort_unstable();
+                packages.push(PackageNode {
+                    id: package,
+                    numa_nodes,
+                    dies: Vec::new(),
+                });
+            }
+            let pkg = packages.last_mut().expect("package pushed above");
+            if pkg.dies.last().map(|d| d.id) != Some(die) {
+                pkg.dies.push(DieNode {
+                    id: die,
+                    l3_groups: Vec::new(),
+                });
+            }
+            let die_node = pkg.dies.last_mut().expect("die pushed above");
+            if die_node.l3_groups.last().map(|g| g.l3_domain) != Some(l3) {
+                die_node.l3_groups.push(L3Group {
+                    l3_domain: l3,
+                    groups: Vec::new(),
+                });
+            }
+            let l3_group = die_node
+                .l3_groups
+                .last_mut()
+                .expect("L3 group pushed above");
+            if l3_group.groups.last().map(|g| g.core_type) != Some(core_type) {
+                l3_group.groups.push(CoreTypeGroup {
+                    core_type,
+                    cores: Vec::new(),
+                });
+            }
+            l3_group
+                .groups
+                .last_mut()
+                .expect("core type group pushed above")
+                .cores
+                .push(core);
+        }
+        packages
+    }
+
+    /// Label for an L3 domain, e.g. "L3#1 32 MiB"
+    pub fn l3_label(&self, domain: Option<usize>) -> String {
+        match domain.and_then(|i| self.l3_domains.get(i)) {
+            Some(d) if d.size_kb >= 1024 => format!("L3#{} {} MiB", d.id, d.size_kb / 1024),
+            Some(d) => format!("L3#{} {} KiB", d.id, d.size_kb),
+            None => "no L3".into(),
+        }
+    }
+
+    /// Aggregate per-CPU metrics by L3 domain, busiest first
+    pub fn domain_loads(&self, metrics: &[CoreMetrics]) -> Vec<DomainLoad> {
+        let by_cpu: HashMap<u32, &CoreMetrics> = metrics.iter().map(|m| (m.cpu, m)).collect();
+        let mut groups: BTreeMap<(u32, u32, Option<usize>), Vec<&CoreMetrics>> = BTreeMap::new();
+        for c in &self.cpus {
+            if let Some(m) = by_cpu.get(&c.cpu) {
+                groups
+                    .entry((c.package, c.die, c.l3_domain))
+                    .or_default()
+                    .push(m);
+            }
+        }
+
+        let mut loads: Vec<DomainLoad> = groups
+            .into_iter()
+            .map(|((package, die, l3_domain), ms)| {
+                let n = ms.len() as f32;
+                let freqs: Vec<u32> = ms.iter().filter_map(|m| m.frequency_mhz).collect();
+                DomainLoad {
+                    package,
+                    die,
+                    l3_domain,
+                    label: format!("pkg{}/die{}/{}", package, die, self.l3_label(l3_domain)),
+                    cpus: ms.len(),
+                    utilization: ms.iter().map(|m| m.utilization).sum::<f32>() / n,
+                    max_utilization: ms.iter().map(|m| m.utilization).fold(0.0, f32::max),
+                    frequency_mhz: (!freqs.is_empty())
+                        .then(|| freqs.iter().sum::<u32>() / freqs.len() as u32),
+                    temperature: ms.iter().filter_map(|m| m.temperature).reduce(f32::max),
+                }
+            })
+            .collect();
+        loads.sort_by(|a, b| b.utilization.total_cmp(&a.utilization));
+        loads
+    }
+}
+
+/// Samples per-CPU utilization, frequency and temperature for a [`CpuTopology`]
+pub struct TopologySampler {
+    topology: CpuTopology,
+    root: PathBuf,
+    /// Previous (busy, total) jiffies per CPU
+    prev: HashMap<u32, (u64, u64)>,
+}
+
+impl TopologySampler {
+    /// Detect the topology and take a baseline sample
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_topology(CpuTopology::detect()?, "/"))
+    }
+
+    /// Sample an existing topology with `/proc` and `/sys` under `root`
+    pub fn with_topology(topology: CpuTopology, root: impl AsRef<Path>) -> Self {
+        let mut sampler = Self {
+            topology,
+            root: root.as_ref().to_path_buf(),
+            prev: HashMap::new(),
+        };
+        sampler.sample();
+        sampler
+    }
+
+    pub fn topology(&self) -> &CpuTopology {
+        &self.topology
+    }
+
+    /// Measure every CPU; utilization covers the time since the previous call
+    pub fn sample(&mut self) -> Vec<CoreMetrics> {
+        let times = std::fs::read_to_string(self.root.join("proc/stat"))
+            .map(|s| parse_proc_stat(&s))
+            .unwrap_or_default();
+        let temps = read_temperatures(&self.root, &self.topology);
+        let cpu_dir = self.root.join("sys/devices/system/cpu");
+
+        self.topology
+            .cpus
+            .iter()
+            .map(|c| {
+                let utilization = match (times.get(&c.cpu), self.prev.get(&c.cpu)) {
+                    (Some(&(busy, total)), Some(&(pb, pt))) if total > pt => {
+                        busy.saturating_sub(pb) as f32 / (total - pt) as f32 * 100.0
+                    }
+                    _ => 0.0,
+                };
+                if let Some(&t) = times.get(&c.cpu) {
+                    self.prev.insert(c.cpu, t);
+                }
+                CoreMetrics {
+                    cpu: c.cpu,
+                    utilization: utilization.clamp(0.0, 100.0),
+                    frequency_mhz: read_u32(
+                        &cpu_dir.join(format!("cpu{}/cpufreq/scaling_cur_freq", c.cpu)),
+                    )
+                    .map(|k| k / 1000),
+                    temperature: temps.get(&c.cpu).copied(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn read_u32(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Parse a kernel CPU list such as "0-3,8,10-11"
+fn parse_cpu_list(s: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in s.trim().split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        if let Some((start, end)) = part.split_once('-') {
+            if let (Ok(s), Ok(e)) = (start.parse::<u32>(), end.parse::<u32>()) {
+                cpus.extend(s..=e);
+            }
+        } else if let Ok(n) = part.parse::<u32>() {
+            cpus.push(n);
+        }
+    }
+    cpus
+}
+
+/// Parse a cache size such as "32K" or "16M" into KiB
+fn parse_cache_size(s: &str) -> u64 {
+    let s = s.trim();
+    let (num, mult) = match s.chars().last() {
+        Some('K') => (&s[..s.len() - 1], 1),
+        Some('M') => (&s[..s.len() - 1], 1024),
+        Some('G') => (&s[..s.len() - 1], 1024 * 1024),
+        _ => (s, 1),
+    };
+    num.parse::<u64>().unwrap_or(0) * mult
+}
+
+/// Unified/data caches of a CPU at levels 2 and 3
+fn read_caches(cpu_base: &Path) -> Vec<CacheDomain> {
+    let Ok(entries) = std::fs::read_dir(cpu_base.join("cache")) else {
+        return Vec::new();
+    };
+    let mut caches = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(level) = read_u32(&path.join("level")) else {
+            continue;
+        };
+        let cache_type = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        if !(2..=3).contains(&level) || cache_type.trim() == "Instruction" {
+            continue;
+        }
+        let cpus = std::fs::read_to_string(path.join("shared_cpu_list"))
+            .map(|s| parse_cpu_list(&s))
+            .unwrap_or_default();
+        caches.push(CacheDomain {
+            level: level as u8,
+            id: read_u32(&path.join("id")).unwrap_or(0),
+            size_kb: std::fs::read_to_string(path.join("size"))
+                .map(|s| parse_cache_size(&s))
+                .unwrap_or(0),
+            cpus,
+        });
+    }
+    caches
+}
+
+/// CPU → NUMA node from `/sys/devices/system/node/node*/cpulist`
+fn read_numa_nodes(root: &Path) -> HashMap<u32, u32> {
+    let mut map = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(root.join("sys/devices/system/node")) else {
+        return map;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(node) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix("node"))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if let Ok(list) = std::fs::read_to_string(entry.path().join("cpulist")) {
+            for cpu in parse_cpu_list(&list) {
+                map.insert(cpu, node);
+            }
+        }
+    }
+    map
+}
+
+/// Core types from the Intel hybrid PMUs, falling back to ARM `cpu_capacity`
+fn read_core_types(root: &Path, cpus: &[u32]) -> HashMap<u32, CoreType> {
+    let mut types = HashMap::new();
+    let devices = root.join("sys/devices");
+    let p_cores = std::fs::read_to_string(devices.join("cpu_core/cpus"));
+    let e_cores = std::fs::read_to_string(devices.join("cpu_atom/cpus"));
+    if let (Ok(p), Ok(e)) = (p_cores, e_cores) {
+        for cpu in parse_cpu_list(&p) {
+            types.insert(cpu, CoreType::Performance);
+        }
+        for cpu in parse_cpu_list(&e) {
+            types.insert(cpu, CoreType::Efficiency);
+        }
+        return types;
+    }
+
+    let capacities: HashMap<u32, u32> = cpus
+        .iter()
+        .filter_map(|&cpu| {
+            let path = devices.join(format!("system/cpu/cpu{}/cpu_capacity", cpu));
+            read_u32(&path).map(|c| (cpu, c))
+        })
+        .collect();
+    let max = capacities.values().copied().max();
+    let min = capacities.values().copied().min();
+    if let (Some(max), Some(min)) = (max, min) {
+        if max != min {
+            for (cpu, cap) in capacities {
+                let t = if cap == max {
+                    CoreType::Performance
+                } else {
+                    CoreType::Efficiency
+                };
+                types.insert(cpu, t);
+            }
+        }
+    }
+    types
+}
+
+/// Per-CPU (busy, total) jiffies from `/proc/stat`
+fn parse_proc_stat(text: &str) -> HashMap<u32, (u64, u64)> {
+    let mut map = HashMap::new();
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(cpu) = fields
+            .next()
+            .and_then(|f| f.strip_prefix("cpu"))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let values: Vec<u64> = fields.filter_map(|v| v.parse().ok()).collect();
+        if values.len() < 4 {
+            continue;
+        }
+        // user nice system idle iowait irq softirq steal (guest is already in user)
+        let total: u64 = values.iter().take(8).sum();
+        let idle = values[3] + values.get(4).copied().unwrap_or(0);
+        map.insert(cpu, (total - idle, total));
+    }
+    map
+}
+
+/// Per-CPU temperatures from hwmon
+///
+/// `coretemp` reports one "Core N" sensor per physical core. `k10temp` reports one
+/// "TccdN" per CCD, which is mapped onto L3 domains in order when the counts match
+/// and otherwise falls back to the package temperature (`Tctl`/`Tdie`).
+fn read_temperatures(root: &Path, topology: &CpuTopology) -> HashMap<u32, f32> {
+    let mut temps = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(root.join("sys/class/hwmon")) else {
+        return temps;
+    };
+    let mut hwmons: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    hwmons.sort();
+
+    let mut coretemp_package = 0u32;
+    let mut k10temp_package = 0u32;
+    for path in hwmons {
+        let name = std::fs::read_to_string(path.join("name")).unwrap_or_default();
+        let mut labeled: Vec<(String, f32)> = Vec::new();
+        for i in 1..=64 {
+            let Ok(label) = std::fs::read_to_string(path.join(format!("temp{}_label", i))) else {
+                continue;
+            };
+            if let Some(milli) = read_u32(&path.join(format!("temp{}_input", i))) {
+                labeled.push((label.trim().to_string(), milli as f32 / 1000.0));
+            }
+        }
+
+        match name.trim() {
+            "coretemp" => {
+                let package = labeled
+                    .iter()
+                    .find_map(|(l, _)| l.strip_prefix("Package id ")?.parse().ok())
+                    .unwrap_or(coretemp_package);
+                coretemp_package += 1;
+                for (label, t) in &labeled {
+                    let Some(core_id) = label
+                        .strip_prefix("Core ")
+                        .and_then(|n| n.parse::<u32>().ok())
+                    else {
+                        continue;
+                    };
+                    for c in topology
+                        .cpus
+                        .iter()
+                        .filter(|c| c.package == package && c.core_id == core_id)
+                    {
+                        temps.insert(c.cpu, *t);
+                    }
+                }
+            }
+            "k10temp" => {
+                let package = k10temp_package;
+                k10temp_package += 1;
+                let ccds: Vec<f32> = labeled
+                    .iter()
+                    .filter(|(l, _)| l.starts_with("Tccd"))
+                    .map(|(_, t)| *t)
+                    .collect();
+                let package_temp = labeled
+                    .iter()
+                    .find(|(l, _)| l == "Tdie" || l == "Tctl")
+                    .map(|(_, t)| *t);
+
+                let mut l3s: Vec<Option<usize>> = topology
+                    .cpus
+                    .iter()
+                    .filter(|c| c.package == package)
+                    .map(|c| c.l3_domain)
+                    .collect();
+                l3s.sort_unstable();
+                l3s.dedup();
+
+                for c in topology.cpus.iter().filter(|c| c.package == package) {
+                    let ccd_temp = (ccds.len() == l3s.len())
+                        .then(|| l3s.iter().position(|d| *d == c.l3_domain))
+                        .flatten()
+                        .and_then(|i| ccds.get(i).copied());
+                    if let Some(t) = ccd_temp.or(package_temp) {
+                        temps.insert(c.cpu, t);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    temps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    fn add_cpu(
+        fs: &FakeRoot,
+        cpu: u32,
+        package: u32,
+        core: u32,
+        siblings: &str,
+        l2: &str,
+        l3: &str,
+    ) {
+        let base = format!("sys/devices/system/cpu/cpu{}", cpu);
+        fs.write(
+            &format!("{}/topology/physical_package_id", base),
+            &package.to_string(),
+        );
+        fs.write(&format!("{}/topology/die_id", base), "0");
+        fs.write(&format!("{}/topology/core_id", base), &core.to_string());
+        fs.write(&format!("{}/topology/thread_siblings_list", base), siblings);
+        for (idx, level, ty, shared, size) in [
+            (0, 1, "Data", siblings, "48K"),
+            (1, 1, "Instruction", siblings, "32K"),
+            (2, 2, "Unified", l2, "1024K"),
+            (3, 3, "Unified", l3, "32768K"),
+        ] {
+            let cache = format!("{}/cache/index{}", base, idx);
+            fs.write(&format!("{}/level", cache), &level.to_string());
+            fs.write(&format!("{}/type", cache), ty);
+            fs.write(&format!("{}/shared_cpu_list", cache), shared);
+            fs.write(&format!("{}/size", cache), size);
+        }
+    }
+
+    /// One package, two CCXs of two SMT cores each:
+    /// CCX0 = cores 0,1 (cpus 0,4 and 1,5), CCX1 = cores 2,3 (cpus 2,6 and 3,7)
+    fn two_ccx() -> FakeRoot {
+        let fs = FakeRoot::new("cpu-topology-ccx");
+        for cpu in 0..8u32 {
+            let core = cpu % 4;
+            let siblings = format!("{},{}", core, core + 4);
+            let l3 = if core < 2 { "0-1,4-5" } else { "2-3,6-7" };
+            add_cpu(&fs, cpu, 0, core, &siblings, &siblings, l3);
+        }
+        fs.write("sys/devices/system/node/node0/cpulist", "0-7\n");
+        fs
+    }
+
+    #[test]
+    fn test_parse_helpers() {
+        assert_eq!(parse_cpu_list("0-2,8,10-11\n"), vec![0, 1, 2, 8, 10, 11]);
+        assert!(parse_cpu_list("").is_empty());
+        assert_eq!(parse_cache_size("32K"), 32);
+        assert_eq!(parse_cache_size("16M"), 16 * 1024);
+
+        let stat =
+            "cpu  10 0 10 80 0 0 0 0 0 0\ncpu0 5 0 5 40 0 0 0 0 0 0\ncpu1 1 1 1 7 0 0 0 0 0 0\n";
+        let times = parse_proc_stat(stat);
+        assert_eq!(times.len(), 2);
+        assert_eq!(times[&0], (10, 50));
+        assert_eq!(times[&1], (3, 10));
+    }
+
+    #[test]
+    fn test_ccx_tree_and_domain_loads() {
+        let fs = two_ccx();
+        let topo = CpuTopology::from_sysfs(fs.path()).unwrap();
+
+        assert_eq!(topo.cpus.len(), 8);
+        assert_eq!(topo.physical_cores(), 4);
+        assert_eq!(topo.package_count(), 1);
+        assert_eq!(topo.l3_domains.len(), 2);
+        assert_eq!(topo.l2_domains.len(), 4);
+        assert!(!topo.hybrid);
+        assert_eq!(topo.cpu(6).unwrap().smt_siblings, vec![2, 6]);
+        assert_eq!(topo.cpu(6).unwrap().numa_node, Some(0));
+
+        let tree = topo.tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].numa_nodes, vec![0]);
+        let l3s = &tree[0].dies[0].l3_groups;
+        assert_eq!(l3s.len(), 2);
+        let cores = &l3s[1].groups[0].cores;
+        assert_eq!(cores.len(), 2);
+        assert_eq!(cores[0].threads, vec![2, 6]);
+        assert_eq!(topo.l3_label(l3s[0].l3_domain), "L3#0 32 MiB");
+
+        // CCX1 saturated, CCX0 idle
+        let metrics: Vec<CoreMetrics> = (0..8u32)
+            .map(|cpu| CoreMetrics {
+                cpu,
+                utilization: if cpu % 4 >= 2 { 100.0 } else { 0.0 },
+                frequency_mhz: Some(3000),
+                temperature: None,
+            })
+            .collect();
+        let loads = topo.domain_loads(&metrics);
+        assert_eq!(loads.len(), 2);
+        assert_eq!(loads[0].l3_domain, l3s[1].l3_domain);
+        assert_eq!(loads[0].utilization, 100.0);
+        assert_eq!(loads[1].utilization, 0.0);
+        assert_eq!(loads[0].cpus, 4);
+        assert_eq!(loads[0].frequency_mhz, Some(3000));
+    }
+
+    #[test]
+    fn test_intel_hybrid_core_types() {
+        let fs = FakeRoot::new("cpu-topology-hybrid");
+        // Two SMT P-cores (cpus 0-3), one E-core module of four (cpus 4-7)
+        add_cpu(&fs, 0, 0, 0, "0-1", "0-1", "0-7");
+        add_cpu(&fs, 1, 0, 0, "0-1", "0-1", "0-7");
+        add_cpu(&fs, 2, 0, 4, "2-3", "2-3", "0-7");
+        add_cpu(&fs, 3, 0, 4, "2-3", "2-3", "0-7");
+        for cpu in 4..8 {
+            add_cpu(&fs, cpu, 0, 8 + cpu, &cpu.to_string(), "4-7", "0-7");
+        }
+        fs.write("sys/devices/cpu_core/cpus", "0-3\n");
+        fs.write("sys/devices/cpu_atom/cpus", "4-7\n");
+
+        let topo = CpuTopology::from_sysfs(fs.path()).unwrap();
+        assert!(topo.hybrid);
+        assert_eq!(topo.physical_cores(), 6);
+
+        let groups = &topo.tree()[0].dies[0].l3_groups[0].groups;
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].core_type, CoreType::Performance);
+        assert_eq!(groups[0].cores.len(), 2);
+        assert_eq!(groups[1].core_type, CoreType::Efficiency);
+        assert_eq!(groups[1].cores.len(), 4);
+        // The E-core module shares one L2
+        assert!(groups[1]
+            .cores
+            .iter()
+            .all(|c| c.l2_domain == groups[1].cores[0].l2_domain));
+    }
+
+    #[test]
+    fn test_sampler_utilization_and_temperatures() {
+        let fs = two_ccx();
+        fs.write(
+            "proc/stat",
+            &(0..8)
+                .map(|c| format!("cpu{} 100 0 0 100 0 0 0 0 0 0\n", c))
+                .collect::<String>(),
+        );
+        fs.write("sys/class/hwmon/hwmon0/name", "k10temp\n");
+        fs.write("sys/class/hwmon/hwmon0/temp1_label", "Tctl\n");
+        fs.write("sys/class/hwmon/hwmon0/temp1_input", "60000\n");
+        fs.write("sys/class/hwmon/hwmon0/temp3_label", "Tccd1\n");
+        fs.write("sys/class/hwmon/hwmon0/temp3_input", "55000\n");
+        fs.write("sys/class/hwmon/hwmon0/temp4_label", "Tccd2\n");
+        fs.write("sys/class/hwmon/hwmon0/temp4_input", "75000\n");
+        fs.write(
+            "sys/devices/system/cpu/cpu0/cpufreq/scaling_cur_freq",
+            "4200000\n",
+        );
+
+        let topo = CpuTopology::from_sysfs(fs.path()).unwrap();
+        let mut sampler = TopologySampler::with_topology(topo, fs.path());
+
+        // cpu0 fully busy, cpu1 idle, the rest half busy
+        let mut stat = String::new();
+        for c in 0..8 {
+            let (busy, idle) = match c {
+                0 => (200, 100),
+                1 => (100, 200),
+                _ => (150, 150),
+            };
+            stat.push_str(&format!("cpu{} {} 0 0 {} 0 0 0 0 0 0\n", c, busy, idle));
+        }
+        fs.write("proc/stat", &stat);
+
+        let metrics = sampler.sample();
+        assert_eq!(metrics[0].utilization, 100.0);
+        assert_eq!(metrics[1].utilization, 0.0);
+        assert_eq!(metrics[2].utilization, 50.0);
+        assert_eq!(metrics[0].frequency_mhz, Some(4200));
+        assert_eq!(metrics[0].temperature, Some(55.0));
+        assert_eq!(metrics[2].temperature, Some(75.0));
+    }
+}
@@ -6,6 +6,19 @@
 //! Runs simon as a background service with HTTP API, Prometheus metrics,
 //! and optional fleet push reporting.
 
+use crate::capacity::{CapacityConfig, CapacityHandle};
+use crate::cpu_profiles::{CpuProfilesConfig, ProfileSwitcher, ProfileSwitcherHandle};
+use crate::energy_accounting::{EnergyAccountingConfig, EnergyAccountingHandle};
+use crate::fan_controller::{FanController, FanControllerConfig, FanControllerHandle};
+use crate::io_scheduler::latency::{IoLatencyConfig, IoLatencyHandle};
+use crate::oom::{OomConfig, OomHandle};
+use crate::otlp::{OtlpConfig, OtlpHandle};
+use crate::perf::{PerfConfig, PerfHandle};
+use crate::prometheus::{CollectorRegistry, CollectorsConfig};
+use crate::push::{PushConfig, PushHandle};
+use crate::psi::{PsiConfig, PsiHandle};
+use crate::rdma::{RdmaConfig, RdmaHandle};
+use crate::wireless::{WirelessConfig, WirelessHandle};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -17,6 +30,30 @@ pub enum DaemonError {
     Io(#[from] std::io::Error),
     #[error("Already running (PID file exists): {0}")]
     AlreadyRunning(String),
+    #[error("Fan controller error: {0}")]
+    FanControl(String),
+    #[error("CPU profile error: {0}")]
+    CpuProfiles(String),
+    #[error("Energy accounting error: {0}")]
+    Energy(String),
+    #[error("Perf counter error: {0}")]
+    Perf(String),
+    #[error("PSI error: {0}")]
+    Psi(String),
+    #[error("OOM monitor error: {0}")]
+    Oom(String),
+    #[error("I/O latency monitor error: {0}")]
+    IoLatency(String),
+    #[error("Capacity forecaster error: {0}")]
+    Capacity(String),
+    #[error("Wireless monitor error: {0}")]
+    Wireless(String),
+    #[error("RDMA monitor error: {0}")]
+    Rdma(String),
+    #[error("OTLP exporter error: {0}")]
+    Otlp(String),
+    #[error("Push exporter error: {0}")]
+    Push(String),
 }
 
 /// Log level
@@ -54,6 +91,32 @@ pub struct DaemonConfig {
     pub enable_prometheus: bool,
     pub enable_rest_api: bool,
     pub fleet: Option<FleetPushConfig>,
+    #[serde(default)]
+    pub fan_control: Option<FanControllerConfig>,
+    #[serde(default)]
+    pub cpu_profiles: Option<CpuProfilesConfig>,
+    #[serde(default)]
+    pub energy: Option<EnergyAccountingConfig>,
+    #[serde(default)]
+    pub perf: Option<PerfConfig>,
+    #[serde(default)]
+    pub psi: Option<PsiConfig>,
+    #[serde(default)]
+    pub oom: Option<OomConfig>,
+    #[serde(default)]
+    pub io_latency: Option<IoLatencyConfig>,
+    #[serde(default)]
+    pub capacity: Option<CapacityConfig>,
+    #[serde(default)]
+    pub wireless: Option<WirelessConfig>,
+    #[serde(default)]
+    pub rdma: Option<RdmaConfig>,
+    #[serde(default)]
+    pub collectors: Option<CollectorsConfig>,
+    #[serde(default)]
+    pub otlp: Option<OtlpConfig>,
+    #[serde(default)]
+    pub push: Option<PushConfig>,
 }
 
 impl Default for DaemonConfig {
@@ -67,6 +130,19 @@ impl Default for DaemonConfig {
             enable_prometheus: true,
             enable_rest_api: true,
             fleet: None,
+            fan_control: None,
+            cpu_profiles: None,
+            energy: None,
+            perf: None,
+            psi: None,
+            oom: None,
+            io_latency: None,
+            capacity: None,
+            wireless: None,
+            rdma: None,
+            collectors: None,
+            otlp: None,
+            push: None,
         }
     }
 }
@@ -106,6 +182,194 @@ enable_rest_api = true
 # environment = "production"
 # datacenter = "us-east-1"
 # rack = "rack-42"
+
+# Optional: Closed-loop fan control (returns fans to their original mode on exit)
+# [fan_control]
+# enabled = true
+# interval_ms = 1000
+# [[fan_control.fans]]
+# name = "cpu"
+# chip = "nct6798"
+# pwm = 2
+# combine = "max"
+# curve = "quiet"
+# min_percent = 20.0
+# [[fan_control.fans.sources]]
+# type = "hwmon"
+# chip = "k10temp"
+# sensor = "Tctl"
+# [[fan_control.fans.sources]]
+# type = "gpu"
+# index = 0
+
+# Optional: CPU tuning profiles with automatic switching (first matching rule wins)
+# [cpu_profiles]
+# enabled = true
+# min_dwell_secs = 30
+# default_profile = "balanced"
+# [[cpu_profiles.profiles]]
+# name = "balanced"
+# governor = "schedutil"
+# energy_preference = "balance_performance"
+# [[cpu_profiles.profiles]]
+# name = "training"
+# governor = "performance"
+# turbo = true
+# disable_idle_states = ["C6"]
+# [[cpu_profiles.rules]]
+# profile = "training"
+# when = [{ type = "gpu_busy", min_utilization = 60.0 }, { type = "on_ac" }]
+
+# Optional: Per-process/cgroup energy accounting (served at /api/v1/energy and /metrics)
+# [energy]
+# enabled = true
+# interval_ms = 2000
+# idle_cpu_watts = 15.0
+# [energy.tariff]
+# price_per_kwh = 0.15
+# currency = "USD"
+# co2_grams_per_kwh = 400.0
+
+# Optional: Hardware performance counters (served at /api/v1/perf and /metrics)
+# Per-CPU counters need kernel.perf_event_paranoid <= 0 or CAP_PERFMON
+# [perf]
+# enabled = true
+# interval_ms = 1000
+# events = ["cycles", "instructions", "cache_references", "llc_misses", "branch_instructions", "branch_misses", "context_switches"]
+# per_cpu = true
+# memory_bandwidth = true
+
+# Optional: Pressure stall monitoring (served at /api/v1/pressure and /metrics)
+# Fired triggers are published as events at /api/v1/events
+# [psi]
+# enabled = true
+# interval_ms = 2000
+# cgroups = true
+# cgroup_depth = 3
+# [[psi.triggers]]
+# resource = "memory"
+# kind = "some"
+# threshold_ms = 200
+# window_ms = 2000
+# [[psi.triggers]]
+# resource = "io"
+# kind = "full"
+# threshold_ms = 400
+# window_ms = 2000
+# cgroup = "/system.slice/postgresql.service"
+
+# Optional: OOM kill tracking (served at /api/v1/oom)
+# Kills and cgroup memory limit hits are published as events at /api/v1/events
+# Victim names come from /dev/kmsg, which needs CAP_SYSLOG if dmesg_restrict=1
+# [oom]
+# enabled = true
+# interval_ms = 1000
+# kernel_log = true
+# cgroups = true
+# cgroup_depth = 4
+# limit_events = true
+# limit_cooldown_secs = 60
+
+# Optional: Per-device I/O latency and queue analysis (served at /api/v1/io-latency and /metrics)
+# Saturation warnings are published as events at /api/v1/events
+# [io_latency]
+# enabled = true
+# interval_ms = 2000
+# window = 150
+# virtual_devices = true
+# cgroups = true
+# cgroup_depth = 3
+# event_cooldown_secs = 60
+# [io_latency.thresholds]
+# utilization_pct = 90.0
+# queue_size = 32.0
+# hdd_await_ms = 50.0
+# ssd_await_ms = 10.0
+# spike_factor = 5.0
+# spike_min_ms = 2.0
+
+# Optional: Filesystem capacity and inode exhaustion forecasting (served at /api/v1/capacity and /metrics)
+# Projected exhaustion and fill-rate jumps are published as events at /api/v1/events
+# [capacity]
+# enabled = true
+# interval_secs = 60
+# fit_window_hours = 24.0
+# recent_window_minutes = 30.0
+# min_samples = 10
+# inode_warning_percent = 90.0
+# fill_rate_change_factor = 3.0
+# min_fill_rate_bytes_per_hour = 1073741824.0
+# alert_cooldown_minutes = 60
+# tsdb_path = "/var/lib/simon/capacity.tsdb"
+# [capacity.horizons]
+# critical_hours = 6.0
+# high_hours = 24.0
+# medium_hours = 168.0
+
+# Optional: WiFi link quality via nl80211 (SSID/BSSID, signal, noise, bitrates, retries)
+# Roams, associations and disassociations are published as events at /api/v1/events
+# [wireless]
+# enabled = true
+# interval_ms = 2000
+# interfaces = ["wlan0"]
+# history = 32
+
+# Optional: InfiniBand/RoCE port monitoring (served at /api/v1/rdma and /metrics)
+# Ports leaving ACTIVE, link flaps and error counter increments are published as events
+# [rdma]
+# enabled = true
+# interval_ms = 5000
+# devices = ["mlx5_0", "mlx5_1"]
+# error_threshold = 1
+# event_cooldown_secs = 300
+
+# Optional: hardware collectors run on each /metrics scrape, node_exporter style
+# Defaults: cpu, memory, gpu, disk, network, rapl, edac, thermal_zone, fan,
+# power_supply, pcie, numa, io_scheduler. Opt-in: smart, cgroup, interrupts, ipmi
+# [collectors]
+# enable = ["smart", "ipmi"]
+# disable = ["gpu"]
+# timeout_ms = 5000
+# [collectors.timeouts]
+# smart = 15000
+
+# Optional: push metrics and events to an OpenTelemetry collector (runs alongside /metrics)
+# protocol is "http/protobuf" (default port 4318) or "grpc" (4317); https:// endpoints use TLS
+# [otlp]
+# enabled = true
+# endpoint = "http://otel-collector:4318"
+# protocol = "http/protobuf"
+# compression = "gzip"
+# interval_ms = 10000
+# timeout_ms = 10000
+# max_batch_size = 2000
+# max_retries = 5
+# service_name = "simon"
+# [otlp.headers]
+# authorization = "Bearer <token>"
+# [otlp.resource_attributes]
+# "deployment.environment" = "production"
+
+# Optional: push the /metrics collector data for hosts that cannot be scraped (e.g. behind NAT)
+# Batches are kept in a per-sink WAL until accepted; nothing is sent while SIMON_OFFLINE is set
+# [push]
+# enabled = true
+# interval_ms = 15000
+# wal_dir = "/var/lib/simon/push-wal"
+# wal_max_bytes = 67108864
+# [push.labels]
+# job = "simon"
+# site = "field-7"
+# [push.remote_write]
+# url = "http://prometheus:9090/api/v1/write"
+# [push.remote_write.headers]
+# "X-Scope-OrgID" = "edge"
+# [push.influxdb]
+# url = "http://influxdb:8086/api/v2/write?org=simon&bucket=edge"  # or "udp://telegraf:8089"
+# token = "<token>"
+# [push.statsd]
+# address = "127.0.0.1:8125"
+# dogstatsd = true
 "#.into()
     }
 }
@@ -164,6 +428,22 @@ impl MonitoringDaemon {
         self.config.enable_prometheus
     }
 
+    /// Build the registry of collectors run on each Prometheus scrape
+    ///
+    /// Fails if `[collectors]` names a collector that does not exist.
+    pub fn collector_registry(&self) -> Result<CollectorRegistry, DaemonError> {
+        let config = self.config.collectors.clone().unwrap_or_default();
+        let registry = CollectorRegistry::with_defaults(config);
+        let unknown = registry.unknown_names();
+        if !unknown.is_empty() {
+            return Err(DaemonError::Config(format!(
+                "Unknown collectors: {}",
+                unknown.join(", ")
+            )));
+        }
+        Ok(registry)
+    }
+
     /// Check if REST API is enabled
     pub fn rest_api_enabled(&self) -> bool {
         self.config.enable_rest_api
@@ -173,6 +453,253 @@ impl MonitoringDaemon {
     pub fn fleet_push_enabled(&self) -> bool {
         self.config.fleet.as_ref().map(|f| f.enabled).unwrap_or(false)
     }
+
+    /// Check if the fan controller is enabled
+    pub fn fan_control_enabled(&self) -> bool {
+        self.config.fan_control.as_ref().map(|f| f.enabled).unwrap_or(false)
+    }
+
+    /// Start the fan controller if enabled
+    ///
+    /// The controller runs on its own thread; dropping the returned handle stops it
+    /// and returns all fans to their original control mode.
+    pub fn start_fan_controller(&self) -> Result<Option<FanControllerHandle>, DaemonError> {
+        match &self.config.fan_control {
+            Some(config) if config.enabled => {
+                let controller = FanController::new(config.clone())
+                    .map_err(|e| DaemonError::FanControl(e.to_string()))?;
+                controller
+                    .spawn()
+                    .map(Some)
+                    .map_err(|e| DaemonError::FanControl(e.to_string()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if automatic CPU profile switching is enabled
+    pub fn cpu_profiles_enabled(&self) -> bool {
+        self.config.cpu_profiles.as_ref().map(|c| c.enabled).unwrap_or(false)
+    }
+
+    /// Start the CPU profile switcher if enabled
+    ///
+    /// Dropping the returned handle stops switching and restores the CPU settings
+    /// that were in place before the daemon started.
+    pub fn start_cpu_profile_switcher(&self) -> Result<Option<ProfileSwitcherHandle>, DaemonError> {
+        match &self.config.cpu_profiles {
+            Some(config) if config.enabled => {
+                let switcher = ProfileSwitcher::new(config.clone())
+                    .map_err(|e| DaemonError::CpuProfiles(e.to_string()))?;
+                switcher
+                    .spawn()
+                    .map(Some)
+                    .map_err(|e| DaemonError::CpuProfiles(e.to_string()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if energy accounting is enabled
+    pub fn energy_accounting_enabled(&self) -> bool {
+        self.config.energy.as_ref().map(|e| e.enabled).unwrap_or(false)
+    }
+
+    /// Start energy accounting if enabled
+    ///
+    /// Pass [`EnergyAccountingHandle::accountant`] to
+    /// [`crate::http_server::HttpServer::with_energy_accountant`] to serve the results.
+    pub fn start_energy_accounting(&self) -> Result<Option<EnergyAccountingHandle>, DaemonError> {
+        match &self.config.energy {
+            Some(config) if config.enabled => crate::energy_accounting::spawn(config.clone())
+                .map(Some)
+                .map_err(|e| DaemonError::Energy(e.to_string())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if perf counter sampling is enabled
+    pub fn perf_enabled(&self) -> bool {
+        self.config.perf.as_ref().map(|p| p.enabled).unwrap_or(false)
+    }
+
+    /// Start perf counter sampling if enabled
+    ///
+    /// Fails when per-CPU counters are not permitted. Pass
+    /// [`PerfHandle::snapshot`] to [`crate::http_server::HttpServer::with_perf_snapshot`]
+    /// to serve the results.
+    pub fn start_perf_sampler(&self) -> Result<Option<PerfHandle>, DaemonError> {
+        match &self.config.perf {
+            Some(config) if config.enabled => crate::perf::spawn(config.clone())
+                .map(Some)
+                .map_err(|e| DaemonError::Perf(e.to_string())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if pressure stall monitoring is enabled
+    pub fn psi_enabled(&self) -> bool {
+        self.config.psi.as_ref().map(|p| p.enabled).unwrap_or(false)
+    }
+
+    /// Start PSI sampling and triggers if enabled, publishing fired triggers to
+    /// `events` (see [`crate::http_server::HttpServer::event_manager`])
+    pub fn start_psi_monitor(
+        &self,
+        events: std::sync::Arc<crate::observability::EventManager>,
+    ) -> Result<Option<PsiHandle>, DaemonError> {
+        match &self.config.psi {
+            Some(config) if config.enabled => crate::psi::spawn(config.clone(), events)
+                .map(Some)
+                .map_err(|e| DaemonError::Psi(e.to_string())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if OOM kill tracking is enabled
+    pub fn oom_enabled(&self) -> bool {
+        self.config.oom.as_ref().map(|o| o.enabled).unwrap_or(false)
+    }
+
+    /// Start OOM kill tracking if enabled, publishing kills and limit events to
+    /// `events` (see [`crate::http_server::HttpServer::event_manager`])
+    pub fn start_oom_monitor(
+        &self,
+        events: std::sync::Arc<crate::observability::EventManager>,
+    ) -> Result<Option<OomHandle>, DaemonError> {
+        match &self.config.oom {
+            Some(config) if config.enabled => crate::oom::spawn(config.clone(), events)
+                .map(Some)
+                .map_err(|e| DaemonError::Oom(e.to_string())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if I/O latency analysis is enabled
+    pub fn io_latency_enabled(&self) -> bool {
+        self.config.io_latency.as_ref().map(|l| l.enabled).unwrap_or(false)
+    }
+
+    /// Start per-device latency sampling if enabled, publishing saturation
+    /// warnings to `events` (see [`crate::http_server::HttpServer::event_manager`])
+    pub fn start_io_latency_monitor(
+        &self,
+        events: std::sync::Arc<crate::observability::EventManager>,
+    ) -> Result<Option<IoLatencyHandle>, DaemonError> {
+        match &self.config.io_latency {
+            Some(config) if config.enabled => {
+                crate::io_scheduler::latency::spawn(config.clone(), events)
+                    .map(Some)
+                    .map_err(|e| DaemonError::IoLatency(e.to_string()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if capacity forecasting is enabled
+    pub fn capacity_enabled(&self) -> bool {
+        self.config.capacity.as_ref().map(|c| c.enabled).unwrap_or(false)
+    }
+
+    /// Start filesystem capacity forecasting if enabled, publishing exhaustion
+    /// and fill-rate alerts to `events`
+    pub fn start_capacity_monitor(
+        &self,
+        events: std::sync::Arc<crate::observability::EventManager>,
+    ) -> Result<Option<CapacityHandle>, DaemonError> {
+        match &self.config.capacity {
+            Some(config) if config.enabled => crate::capacity::spawn(config.clone(), events)
+                .map(Some)
+                .map_err(|e| DaemonError::Capacity(e.to_string())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if wireless link monitoring is enabled
+    pub fn wireless_enabled(&self) -> bool {
+        self.config.wireless.as_ref().map(|w| w.enabled).unwrap_or(false)
+    }
+
+    /// Start WiFi link sampling if enabled, publishing roams to `events`
+    ///
+    /// Fails where nl80211 is unavailable (no cfg80211, not Linux).
+    pub fn start_wireless_monitor(
+        &self,
+        events: std::sync::Arc<crate::observability::EventManager>,
+    ) -> Result<Option<WirelessHandle>, DaemonError> {
+        match &self.config.wireless {
+            Some(config) if config.enabled => crate::wireless::spawn(config.clone(), events)
+                .map(Some)
+                .map_err(|e| DaemonError::Wireless(e.to_string())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if RDMA port monitoring is enabled
+    pub fn rdma_enabled(&self) -> bool {
+        self.config.rdma.as_ref().map(|r| r.enabled).unwrap_or(false)
+    }
+
+    /// Start RDMA port sampling if enabled, publishing port-down, link-flap and
+    /// error-counter alerts to `events`
+    pub fn start_rdma_monitor(
+        &self,
+        events: std::sync::Arc<crate::observability::EventManager>,
+    ) -> Result<Option<RdmaHandle>, DaemonError> {
+        match &self.config.rdma {
+            Some(config) if config.enabled => crate::rdma::spawn(config.clone(), events)
+                .map(Some)
+                .map_err(|e| DaemonError::Rdma(e.to_string())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if OTLP export is enabled
+    pub fn otlp_enabled(&self) -> bool {
+        self.config.otlp.as_ref().map(|o| o.enabled).unwrap_or(false)
+    }
+
+    /// Start pushing `metrics` and `events` to the configured OpenTelemetry
+    /// collector if enabled
+    ///
+    /// Fails if the endpoint is not a valid `http(s)://` URL, if the configured
+    /// protocol's transport was not compiled in (`remote-backends` for
+    /// `http/protobuf`, `otlp-grpc` for `grpc`), or if the client cannot be built.
+    pub fn start_otlp_exporter(
+        &self,
+        metrics: std::sync::Arc<crate::observability::MetricCollector>,
+        events: std::sync::Arc<crate::observability::EventManager>,
+    ) -> Result<Option<OtlpHandle>, DaemonError> {
+        match &self.config.otlp {
+            Some(config) if config.enabled => crate::otlp::spawn(config.clone(), metrics, events)
+                .map(Some)
+                .map_err(|e| DaemonError::Otlp(e.to_string())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check if push exporting is enabled
+    pub fn push_enabled(&self) -> bool {
+        self.config.push.as_ref().map(|p| p.enabled).unwrap_or(false)
+    }
+
+    /// Start pushing `registry` scrapes to the configured remote_write,
+    /// InfluxDB and StatsD sinks if enabled
+    ///
+    /// Pass the same registry given to
+    /// [`crate::http_server::HttpServer::with_collector_registry`]. Fails if no
+    /// sink is configured, a URL is invalid or the WAL directory is not writable.
+    pub fn start_push_exporter(
+        &self,
+        registry: std::sync::Arc<CollectorRegistry>,
+    ) -> Result<Option<PushHandle>, DaemonError> {
+        match &self.config.push {
+            Some(config) if config.enabled => crate::push::spawn(config.clone(), registry)
+                .map(Some)
+                .map_err(|e| DaemonError::Push(e.to_string())),
+            _ => Ok(None),
+        }
+    }
 }
 
 impl Drop for MonitoringDaemon {
@@ -0,0 +1,918 @@
+//! Per-process and per-cgroup energy accounting
+//!
+//! Attributes measured energy to the workloads that consumed it:
+//!
+//! - **CPU**: RAPL package (and optionally DRAM) energy is split between processes by
+//!   their share of CPU time over each sample interval.
+//! - **GPU**: each GPU's energy (power x time) is split between the processes running
+//!   on it by their GPU utilization, or evenly when the driver reports no per-process
+//!   utilization.
+//!
+//! Joules accumulate per process, cgroup, container and systemd unit, so totals for a
+//! job survive after its processes exit. [`EnergyTariff`] converts joules to kWh, cost
+//! and CO2.
+//!
+//! [`EnergyAccountant`] is the pure accumulator and can be fed [`EnergySample`]s from
+//! any source; [`EnergyCollector`] builds samples from RAPL, the GPU layer and `/proc`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::energy_accounting::{EnergyAccountant, EnergyAccountingConfig, EnergyCollector};
+//!
+//! let config = EnergyAccountingConfig::default();
+//! let mut collector = EnergyCollector::new(&config);
+//! let mut accountant = EnergyAccountant::new(config);
+//!
+//! for _ in 0..10 {
+//!     std::thread::sleep(std::time::Duration::from_secs(2));
+//!     accountant.record(&collector.sample());
+//! }
+//!
+//! for unit in accountant.report().units.iter().take(5) {
+//!     println!("{}: {:.4} kWh, {:.1} g CO2", unit.name, unit.kwh, unit.co2_grams);
+//! }
+//! ```
+
+use crate::error::{Result, SimonError};
+use crate::gpu::Device;
+use crate::process_monitor::{ProcessMonitor, ProcessMonitorInfo};
+use crate::rapl::{PowerDomain, RaplMonitor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const JOULES_PER_KWH: f64 = 3_600_000.0;
+
+/// Electricity price and carbon intensity used to convert energy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnergyTariff {
+    /// Price per kWh
+    pub price_per_kwh: f64,
+    /// Currency code for display (e.g. "USD", "EUR")
+    pub currency: String,
+    /// Grid carbon intensity in grams CO2-equivalent per kWh
+    pub co2_grams_per_kwh: f64,
+}
+
+impl Default for EnergyTariff {
+    fn default() -> Self {
+        Self {
+            price_per_kwh: 0.15,
+            currency: "USD".into(),
+            co2_grams_per_kwh: 400.0,
+        }
+    }
+}
+
+impl EnergyTariff {
+    /// Cost of the given energy
+    pub fn cost(&self, joules: f64) -> f64 {
+        joules / JOULES_PER_KWH * self.price_per_kwh
+    }
+
+    /// Emissions of the given energy in grams CO2e
+    pub fn co2_grams(&self, joules: f64) -> f64 {
+        joules / JOULES_PER_KWH * self.co2_grams_per_kwh
+    }
+}
+
+/// Energy accounting settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyAccountingConfig {
+    /// Run accounting in the daemon
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Sampling interval
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    /// Conversion to cost and CO2
+    #[serde(default)]
+    pub tariff: EnergyTariff,
+    /// Include RAPL DRAM energy in CPU energy
+    #[serde(default = "default_true")]
+    pub include_dram: bool,
+    /// Static CPU power kept out of attribution and reported as unattributed
+    #[serde(default)]
+    pub idle_cpu_watts: Option<f64>,
+    /// How long exited processes stay in the report
+    #[serde(default = "default_retain_exited_secs")]
+    pub retain_exited_secs: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_interval_ms() -> u64 {
+    2000
+}
+
+fn default_retain_exited_secs() -> u64 {
+    86400
+}
+
+impl Default for EnergyAccountingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: default_interval_ms(),
+            tariff: EnergyTariff::default(),
+            include_dram: true,
+            idle_cpu_watts: None,
+            retain_exited_secs: default_retain_exited_secs(),
+        }
+    }
+}
+
+/// Accumulated CPU and GPU energy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnergyTotals {
+    /// CPU (package/DRAM) energy in joules
+    pub cpu_joules: f64,
+    /// GPU energy in joules
+    pub gpu_joules: f64,
+}
+
+impl EnergyTotals {
+    /// Total energy in joules
+    pub fn total_joules(&self) -> f64 {
+        self.cpu_joules + self.gpu_joules
+    }
+
+    /// Total energy in kWh
+    pub fn kwh(&self) -> f64 {
+        self.total_joules() / JOULES_PER_KWH
+    }
+
+    fn add(&mut self, other: EnergyTotals) {
+        self.cpu_joules += other.cpu_joules;
+        self.gpu_joules += other.gpu_joules;
+    }
+}
+
+/// Process state at one sample
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessSample {
+    /// Process ID
+    pub pid: u32,
+    /// Start time, used to tell reused PIDs apart
+    pub start_time: Option<u64>,
+    /// Process name
+    pub name: String,
+    /// Cumulative CPU time in microseconds
+    pub cpu_time_us: u64,
+    /// Cgroup path
+    pub cgroup: Option<String>,
+    /// Container (`runtime:id`)
+    pub container: Option<String>,
+    /// systemd unit (service or scope)
+    pub unit: Option<String>,
+    /// GPU index -> utilization (0-100) of this process
+    pub gpu_usage: HashMap<usize, f32>,
+}
+
+impl ProcessSample {
+    /// Build from process monitor data (cgroup fields are left empty)
+    ///
+    /// The monitor reports one utilization per process, which is applied to every GPU
+    /// the process uses.
+    pub fn from_process_info(info: &ProcessMonitorInfo) -> Self {
+        let usage = info.gpu_usage_percent.unwrap_or(0.0);
+        Self {
+            pid: info.pid,
+            start_time: info.start_time,
+            name: info.name.clone(),
+            cpu_time_us: info.cpu_time_us,
+            gpu_usage: info.gpu_indices.iter().map(|&i| (i, usage)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn key(&self) -> ProcessKey {
+        (self.pid, self.start_time)
+    }
+}
+
+type ProcessKey = (u32, Option<u64>);
+
+/// Measurements for one interval
+#[derive(Debug, Clone, Default)]
+pub struct EnergySample {
+    /// Interval length in seconds
+    pub elapsed_secs: f64,
+    /// CPU energy over the interval (None if RAPL is unavailable)
+    pub cpu_joules: Option<f64>,
+    /// GPU index -> energy over the interval
+    pub gpu_joules: HashMap<usize, f64>,
+    /// Processes alive at the end of the interval
+    pub processes: Vec<ProcessSample>,
+}
+
+/// Energy accumulated by one process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessEnergy {
+    /// Process ID
+    pub pid: u32,
+    /// Start time, used to tell reused PIDs apart
+    pub start_time: Option<u64>,
+    /// Process name
+    pub name: String,
+    /// Cgroup path
+    pub cgroup: Option<String>,
+    /// Container (`runtime:id`)
+    pub container: Option<String>,
+    /// systemd unit (service or scope)
+    pub unit: Option<String>,
+    /// CPU and GPU energy attributed so far, in joules
+    pub energy: EnergyTotals,
+    /// Unix time of the last sample the process appeared in
+    pub last_seen: u64,
+}
+
+/// Energy and its cost for one consumer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyUsage {
+    /// Consumer name (process name, container, unit or cgroup path)
+    pub name: String,
+    /// CPU (package/DRAM) energy in joules
+    pub cpu_joules: f64,
+    /// GPU energy in joules
+    pub gpu_joules: f64,
+    /// CPU plus GPU energy in joules
+    pub total_joules: f64,
+    /// Total energy in kWh
+    pub kwh: f64,
+    /// Cost of the energy, in the tariff currency
+    pub cost: f64,
+    /// Emissions in grams CO2-equivalent at the tariff carbon intensity
+    pub co2_grams: f64,
+}
+
+impl EnergyUsage {
+    fn new(name: impl Into<String>, totals: EnergyTotals, tariff: &EnergyTariff) -> Self {
+        let total = totals.total_joules();
+        Self {
+            name: name.into(),
+            cpu_joules: totals.cpu_joules,
+            gpu_joules: totals.gpu_joules,
+            total_joules: total,
+            kwh: totals.kwh(),
+            cost: tariff.cost(total),
+            co2_grams: tariff.co2_grams(total),
+        }
+    }
+}
+
+/// Per-process entry in an [`EnergyReport`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessEnergyUsage {
+    /// Process ID
+    pub pid: u32,
+    /// Container (`runtime:id`)
+    pub container: Option<String>,
+    /// systemd unit (service or scope)
+    pub unit: Option<String>,
+    /// Cgroup path
+    pub cgroup: Option<String>,
+    /// Whether the process was alive at the last sample
+    pub running: bool,
+    #[serde(flatten)]
+    pub usage: EnergyUsage,
+}
+
+/// Snapshot of accumulated energy, sorted by total energy (highest first)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyReport {
+    /// Unix time accounting started
+    pub since: u64,
+    /// Accounted time in seconds
+    pub duration_secs: f64,
+    /// Currency of `cost` fields
+    pub currency: String,
+    /// Whether CPU energy comes from RAPL
+    pub cpu_measured: bool,
+    /// All measured energy
+    pub node: EnergyUsage,
+    /// Energy not attributed to any process (idle, kernel, no GPU users)
+    pub unattributed: EnergyUsage,
+    /// Per-process energy, including recently exited processes
+    pub processes: Vec<ProcessEnergyUsage>,
+    /// Energy summed per container
+    pub containers: Vec<EnergyUsage>,
+    /// Energy summed per systemd unit
+    pub units: Vec<EnergyUsage>,
+    /// Energy summed per cgroup
+    pub cgroups: Vec<EnergyUsage>,
+}
+
+/// Accumulates attributed energy from [`EnergySample`]s
+pub struct EnergyAccountant {
+    config: EnergyAccountingConfig,
+    since: u64,
+    duration_secs: f64,
+    cpu_measured: bool,
+    cpu_times: HashMap<ProcessKey, u64>,
+    processes: HashMap<ProcessKey, ProcessEnergy>,
+    cgroups: HashMap<String, EnergyTotals>,
+    containers: HashMap<String, EnergyTotals>,
+    units: HashMap<String, EnergyTotals>,
+    node: EnergyTotals,
+    unattributed: EnergyTotals,
+}
+
+impl EnergyAccountant {
+    /// Create an empty accountant
+    pub fn new(config: EnergyAccountingConfig) -> Self {
+        Self {
+            config,
+            since: unix_now(),
+            duration_secs: 0.0,
+            cpu_measured: false,
+            cpu_times: HashMap::new(),
+            processes: HashMap::new(),
+            cgroups: HashMap::new(),
+            containers: HashMap::new(),
+            units: HashMap::new(),
+            node: EnergyTotals::default(),
+            unattributed: EnergyTotals::default(),
+        }
+    }
+
+    /// Accounting configuration
+    pub fn config(&self) -> &EnergyAccountingConfig {
+        &self.config
+    }
+
+    /// Attribute one interval of energy
+    ///
+    /// CPU time deltas need a previous sighting, so a process's first sample only
+    /// establishes its baseline.
+    pub fn record(&mut self, sample: &EnergySample) {
+        self.record_at(sample, unix_now());
+    }
+
+    fn record_at(&mut self, sample: &EnergySample, now: u64) {
+        let dt = sample.elapsed_secs.max(0.0);
+        self.duration_secs += dt;
+
+        let mut shares: HashMap<ProcessKey, EnergyTotals> = HashMap::new();
+
+        // CPU: split by CPU time share
+        let mut new_times = HashMap::with_capacity(sample.processes.len());
+        let mut deltas = Vec::with_capacity(sample.processes.len());
+        for p in &sample.processes {
+            let key = p.key();
+            if let Some(&prev) = self.cpu_times.get(&key) {
+                deltas.push((key, p.cpu_time_us.saturating_sub(prev)));
+            }
+            new_times.insert(key, p.cpu_time_us);
+        }
+        self.cpu_times = new_times;
+
+        if let Some(cpu_joules) = sample.cpu_joules {
+            self.cpu_measured = true;
+            self.node.cpu_joules += cpu_joules;
+            let idle = self
+                .config
+                .idle_cpu_watts
+                .map(|w| (w * dt).min(cpu_joules))
+                .unwrap_or(0.0);
+            let attributable = cpu_joules - idle;
+            let busy_us: u64 = deltas.iter().map(|(_, d)| d).sum();
+            if busy_us > 0 {
+                for (key, delta) in &deltas {
+                    shares.entry(*key).or_default().cpu_joules +=
+                        attributable * *delta as f64 / busy_us as f64;
+                }
+                self.unattributed.cpu_joules += idle;
+            } else {
+                self.unattributed.cpu_joules += cpu_joules;
+            }
+        }
+
+        // GPU: split by per-process utilization, evenly if none is reported
+        for (&gpu, &joules) in &sample.gpu_joules {
+            self.node.gpu_joules += joules;
+            let users: Vec<(ProcessKey, f32)> = sample
+                .processes
+                .iter()
+                .filter_map(|p| p.gpu_usage.get(&gpu).map(|u| (p.key(), u.max(0.0))))
+                .collect();
+            let weight: f32 = users.iter().map(|(_, u)| u).sum();
+            if users.is_empty() {
+                self.unattributed.gpu_joules += joules;
+            } else if weight > 0.0 {
+                for (key, u) in &users {
+                    shares.entry(*key).or_default().gpu_joules += joules * (*u / weight) as f64;
+                }
+            } else {
+                let each = joules / users.len() as f64;
+                for (key, _) in &users {
+                    shares.entry(*key).or_default().gpu_joules += each;
+                }
+            }
+        }
+
+        for p in &sample.processes {
+            let key = p.key();
+            let share = shares.get(&key).copied().unwrap_or_default();
+            let entry = self.processes.entry(key).or_insert_with(|| ProcessEnergy {
+                pid: p.pid,
+                start_time: p.start_time,
+                name: p.name.clone(),
+                cgroup: None,
+                container: None,
+                unit: None,
+                energy: EnergyTotals::default(),
+                last_seen: now,
+            });
+            entry.last_seen = now;
+            entry.cgroup = p.cgroup.clone().or(entry.cgroup.take());
+            entry.container = p.container.clone().or(entry.container.take());
+            entry.unit = p.unit.clone().or(entry.unit.take());
+            entry.energy.add(share);
+
+            for (map, group) in [
+                (&mut self.cgroups, &p.cgroup),
+                (&mut self.containers, &p.container),
+                (&mut self.units, &p.unit),
+            ] {
+                if let Some(name) = group {
+                    map.entry(name.clone()).or_default().add(share);
+                }
+            }
+        }
+
+        let retain = self.config.retain_exited_secs;
+        self.processes
+            .retain(|_, p| now.saturating_sub(p.last_seen) <= retain);
+    }
+
+    /// Energy accumulated by a running or recently exited process
+    pub fn process(&self, pid: u32) -> Option<&ProcessEnergy> {
+        self.processes
+            .values()
+            .filter(|p| p.pid == pid)
+            .max_by_key(|p| p.last_seen)
+    }
+
+    /// Energy accumulated by a container
+    pub fn container(&self, name: &str) -> Option<EnergyTotals> {
+        self.containers.get(name).copied()
+    }
+
+    /// Energy accumulated by a systemd unit
+    pub fn unit(&self, name: &str) -> Option<EnergyTotals> {
+        self.units.get(name).copied()
+    }
+
+    /// All measured energy
+    pub fn node_totals(&self) -> EnergyTotals {
+        self.node
+    }
+
+    /// Build a report with cost and CO2 conversions
+    pub fn report(&self) -> EnergyReport {
+        let tariff = &self.config.tariff;
+
+        let mut processes: Vec<ProcessEnergyUsage> = self
+            .processes
+            .values()
+            .map(|p| ProcessEnergyUsage {
+                pid: p.pid,
+                container: p.container.clone(),
+                unit: p.unit.clone(),
+                cgroup: p.cgroup.clone(),
+                running: self.cpu_times.contains_key(&(p.pid, p.start_time)),
+                usage: EnergyUsage::new(&p.name, p.energy, tariff),
+            })
+            .collect();
+        processes.sort_by(|a, b| b.usage.total_joules.total_cmp(&a.usage.total_joules));
+
+        let groups = |map: &HashMap<String, EnergyTotals>| {
+            let mut v: Vec<EnergyUsage> = map
+                .iter()
+                .map(|(name, totals)| EnergyUsage::new(name, *totals, tariff))
+                .collect();
+            v.sort_by(|a, b| b.total_joules.total_cmp(&a.total_joules));
+            v
+        };
+
+        EnergyReport {
+            since: self.since,
+            duration_secs: self.duration_secs,
+            currency: tariff.currency.clone(),
+            cpu_measured: self.cpu_measured,
+            node: EnergyUsage::new("node", self.node, tariff),
+            unattributed: EnergyUsage::new("unattributed", self.unattributed, tariff),
+            processes,
+            containers: groups(&self.containers),
+            units: groups(&self.units),
+            cgroups: groups(&self.cgroups),
+        }
+    }
+}
+
+/// systemd unit owning a cgroup path (innermost `.service` or `.scope`)
+pub fn systemd_unit(cgroup_path: &str) -> Option<String> {
+    cgroup_path
+        .rsplit('/')
+        .find(|c| c.ends_with(".service") || c.ends_with(".scope"))
+        .map(str::to_string)
+}
+
+/// Cgroup path, container and systemd unit of a process
+type CgroupAttribution = (Option<String>, Option<String>, Option<String>);
+
+/// Builds [`EnergySample`]s from RAPL, GPU power and `/proc`
+pub struct EnergyCollector {
+    rapl: Option<RaplMonitor>,
+    include_dram: bool,
+    last_sample: Instant,
+    /// Last GPU power reading (watts) per index, for trapezoidal integration
+    gpu_watts: HashMap<usize, f64>,
+    /// Cgroup attribution per process, resolved once
+    cgroups: HashMap<ProcessKey, CgroupAttribution>,
+    process_monitor: Option<ProcessMonitor>,
+    /// GPU devices, enumerated on first use
+    gpus: Option<Vec<Box<dyn Device>>>,
+}
+
+impl EnergyCollector {
+    /// Create a collector; the first sample covers the time since creation
+    pub fn new(config: &EnergyAccountingConfig) -> Self {
+        Self {
+            rapl: RaplMonitor::new().ok().filter(|r| !r.readings().is_empty()),
+            include_dram: config.include_dram,
+            last_sample: Instant::now(),
+            gpu_watts: HashMap::new(),
+            cgroups: HashMap::new(),
+            process_monitor: None,
+            gpus: None,
+        }
+    }
+
+    /// Whether CPU energy is measured (RAPL available)
+    pub fn has_cpu_energy(&self) -> bool {
+        self.rapl.is_some()
+    }
+
+    /// Sample everything, enumerating processes and GPUs itself
+    pub fn sample(&mut self) -> EnergySample {
+        if self.process_monitor.is_none() {
+            self.process_monitor = ProcessMonitor::new()
+                .or_else(|_| ProcessMonitor::without_gpu())
+                .ok();
+        }
+        let processes = self
+            .process_monitor
+            .as_mut()
+            .and_then(|m| m.processes().ok())
+            .unwrap_or_default();
+        let gpus = self
+            .gpus
+            .get_or_insert_with(crate::gpu::control::enumerate_devices);
+        let gpu_watts: Vec<Option<f64>> = gpus
+            .iter()
+            .map(|g| g.power().ok().map(|p| p.current as f64))
+            .collect();
+        self.sample_from(&processes, &gpu_watts)
+    }
+
+    /// Build a sample from process data and GPU power readings already collected
+    /// elsewhere (e.g. by the TUI)
+    pub fn sample_from(
+        &mut self,
+        processes: &[ProcessMonitorInfo],
+        gpu_watts: &[Option<f64>],
+    ) -> EnergySample {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_sample).as_secs_f64();
+        self.last_sample = now;
+
+        let cpu_joules = self.read_cpu_joules();
+
+        let mut gpu_joules = HashMap::new();
+        for (i, watts) in gpu_watts.iter().enumerate() {
+            if let Some(w) = watts {
+                let prev = self.gpu_watts.insert(i, *w).unwrap_or(*w);
+                gpu_joules.insert(i, (prev + w) / 2.0 * elapsed);
+            }
+        }
+
+        let mut seen = HashMap::with_capacity(processes.len());
+        let samples = processes
+            .iter()
+            .map(|info| {
+                let mut p = ProcessSample::from_process_info(info);
+                let attribution = self
+                    .cgroups
+                    .remove(&p.key())
+                    .unwrap_or_else(|| resolve_cgroup(p.pid));
+                (p.cgroup, p.container, p.unit) = attribution.clone();
+                seen.insert(p.key(), attribution);
+                p
+            })
+            .collect();
+        self.cgroups = seen;
+
+        EnergySample {
+            elapsed_secs: elapsed,
+            cpu_joules,
+            gpu_joules,
+            processes: samples,
+        }
+    }
+
+    fn read_cpu_joules(&mut self) -> Option<f64> {
+        let rapl = self.rapl.as_mut()?;
+        rapl.refresh().ok()?;
+        let snapshot = rapl.snapshot()?;
+        let include_dram = self.include_dram;
+        let joules = rapl
+            .readings()
+            .iter()
+            .filter(|r| {
+                r.domain == PowerDomain::Package || (include_dram && r.domain == PowerDomain::Dram)
+            })
+            .filter_map(|r| {
+                snapshot
+                    .energy_delta_uj
+                    .get(&format!("socket{}:{}", r.socket, r.name))
+            })
+            .map(|uj| *uj as f64 / 1_000_000.0)
+            .sum();
+        Some(joules)
+    }
+}
+
+fn resolve_cgroup(pid: u32) -> CgroupAttribution {
+    match crate::process_tree::ProcessTree::cgroup_for_pid(pid) {
+        Some(info) => {
+            let container = info
+                .container_name
+                .clone()
+                .or(info.container_id.clone())
+                .map(|id| match &info.runtime {
+                    Some(rt) => format!("{}:{}", rt.to_string().to_lowercase(), id),
+                    None => id,
+                });
+            let unit = systemd_unit(&info.path);
+            (Some(info.path), container, unit)
+        }
+        None => (None, None, None),
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Accountant shared between the sampling thread and readers (REST, Prometheus)
+pub type SharedEnergyAccountant = Arc<RwLock<EnergyAccountant>>;
+
+/// Start sampling on a background thread
+pub fn spawn(config: EnergyAccountingConfig) -> Result<EnergyAccountingHandle> {
+    let interval = Duration::from_millis(config.interval_ms.max(100));
+    let mut collector = EnergyCollector::new(&config);
+    let accountant: SharedEnergyAccountant = Arc::new(RwLock::new(EnergyAccountant::new(config)));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let shared = Arc::clone(&accountant);
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-energy".into())
+        .spawn(move || {
+            while !flag.load(Ordering::SeqCst) {
+                std::thread::sleep(interval);
+                let sample = collector.sample();
+                if let Ok(mut acct) = shared.write() {
+                    acct.record(&sample);
+                }
+            }
+        })
+        .map_err(|e| {
+            SimonError::Other(format!("Failed to spawn energy accounting thread: {}", e))
+        })?;
+
+    Ok(EnergyAccountingHandle {
+        accountant,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+/// Handle to a running accounting thread
+///
+/// Dropping the handle stops sampling; the accountant stays readable through any
+/// clones of [`EnergyAccountingHandle::accountant`].
+pub struct EnergyAccountingHandle {
+    accountant: SharedEnergyAccountant,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EnergyAccountingHandle {
+    /// Shared accountant for readers
+    pub fn accountant(&self) -> SharedEnergyAccountant {
+        Arc::clone(&self.accountant)
+    }
+
+    /// Current report
+    pub fn report(&self) -> Option<EnergyReport> {
+        self.accountant.read().ok().map(|a| a.report())
+    }
+
+    /// Stop sampling and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for EnergyAccountingHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proc(pid: u32, cpu_time_us: u64, unit: Option<&str>) -> ProcessSample {
+        ProcessSample {
+            pid,
+            start_time: Some(100),
+            name: format!("p{}", pid),
+            cpu_time_us,
+            unit: unit.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cpu_energy_split_by_cpu_time() {
+        let mut acct = EnergyAccountant::new(EnergyAccountingConfig::default());
+        acct.record_at(
+            &EnergySample {
+                elapsed_secs: 1.0,
+                cpu_joules: Some(50.0),
+                processes: vec![proc(1, 0, Some("a.service")), proc(2, 0, None)],
+                ..Default::default()
+            },
+            10,
+        );
+        // First sighting only sets the baseline
+        assert_eq!(acct.unattributed.cpu_joules, 50.0);
+
+        acct.record_at(
+            &EnergySample {
+                elapsed_secs: 1.0,
+                cpu_joules: Some(100.0),
+                processes: vec![proc(1, 750_000, Some("a.service")), proc(2, 250_000, None)],
+                ..Default::default()
+            },
+            11,
+        );
+        assert!((acct.process(1).unwrap().energy.cpu_joules - 75.0).abs() < 1e-9);
+        assert!((acct.process(2).unwrap().energy.cpu_joules - 25.0).abs() < 1e-9);
+        assert!((acct.unit("a.service").unwrap().cpu_joules - 75.0).abs() < 1e-9);
+        assert_eq!(acct.node_totals().cpu_joules, 150.0);
+    }
+
+    #[test]
+    fn test_idle_power_is_not_attributed() {
+        let config = EnergyAccountingConfig {
+            idle_cpu_watts: Some(20.0),
+            ..Default::default()
+        };
+        let mut acct = EnergyAccountant::new(config);
+        acct.record_at(
+            &EnergySample {
+                elapsed_secs: 1.0,
+                cpu_joules: Some(0.0),
+                processes: vec![proc(1, 0, None)],
+                ..Default::default()
+            },
+            10,
+        );
+        acct.record_at(
+            &EnergySample {
+                elapsed_secs: 2.0,
+                cpu_joules: Some(100.0),
+                processes: vec![proc(1, 500_000, None)],
+                ..Default::default()
+            },
+            12,
+        );
+        assert!((acct.process(1).unwrap().energy.cpu_joules - 60.0).abs() < 1e-9);
+        assert!((acct.unattributed.cpu_joules - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gpu_energy_split_by_utilization() {
+        let mut acct = EnergyAccountant::new(EnergyAccountingConfig::default());
+        let mut a = proc(1, 0, None);
+        a.container = Some("docker:abc".into());
+        a.gpu_usage.insert(0, 60.0);
+        let mut b = proc(2, 0, None);
+        b.gpu_usage.insert(0, 20.0);
+        let mut c = proc(3, 0, None);
+        c.gpu_usage.insert(1, 0.0);
+
+        acct.record_at(
+            &EnergySample {
+                elapsed_secs: 1.0,
+                cpu_joules: None,
+                gpu_joules: HashMap::from([(0, 300.0), (1, 100.0), (2, 50.0)]),
+                processes: vec![a, b, c],
+            },
+            10,
+        );
+        assert!((acct.process(1).unwrap().energy.gpu_joules - 225.0).abs() < 1e-9);
+        assert!((acct.process(2).unwrap().energy.gpu_joules - 75.0).abs() < 1e-9);
+        // No utilization reported: split evenly among users
+        assert!((acct.process(3).unwrap().energy.gpu_joules - 100.0).abs() < 1e-9);
+        // GPU without users
+        assert!((acct.unattributed.gpu_joules - 50.0).abs() < 1e-9);
+        assert!((acct.container("docker:abc").unwrap().gpu_joules - 225.0).abs() < 1e-9);
+
+        let report = acct.report();
+        assert!(!report.cpu_measured);
+        assert_eq!(report.processes[0].pid, 1);
+        assert_eq!(report.containers[0].name, "docker:abc");
+    }
+
+    #[test]
+    fn test_pid_reuse_and_retention() {
+        let config = EnergyAccountingConfig {
+            retain_exited_secs: 60,
+            ..Default::default()
+        };
+        let mut acct = EnergyAccountant::new(config);
+        let sample = |start: u64, t: u64| EnergySample {
+            elapsed_secs: 1.0,
+            cpu_joules: Some(10.0),
+            processes: vec![ProcessSample {
+                pid: 7,
+                start_time: Some(start),
+                cpu_time_us: t,
+                unit: Some("job.scope".into()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        acct.record_at(&sample(1, 0), 0);
+        acct.record_at(&sample(1, 1000), 1);
+        // Same PID, new process: baseline only
+        acct.record_at(&sample(2, 5_000_000), 2);
+        assert_eq!(acct.processes.len(), 2);
+        assert!((acct.unit("job.scope").unwrap().cpu_joules - 10.0).abs() < 1e-9);
+
+        acct.record_at(&EnergySample::default(), 100);
+        assert!(acct.processes.is_empty());
+        // Group totals outlive the processes
+        assert!(acct.unit("job.scope").is_some());
+    }
+
+    #[test]
+    fn test_tariff_conversion() {
+        let tariff = EnergyTariff {
+            price_per_kwh: 0.30,
+            currency: "EUR".into(),
+            co2_grams_per_kwh: 250.0,
+        };
+        let totals = EnergyTotals {
+            cpu_joules: 3_600_000.0,
+            gpu_joules: 3_600_000.0,
+        };
+        let usage = EnergyUsage::new("job", totals, &tariff);
+        assert!((usage.kwh - 2.0).abs() < 1e-12);
+        assert!((usage.cost - 0.60).abs() < 1e-12);
+        assert!((usage.co2_grams - 500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_systemd_unit_from_cgroup() {
+        assert_eq!(
+            systemd_unit("/system.slice/nginx.service").as_deref(),
+            Some("nginx.service")
+        );
+        assert_eq!(
+            systemd_unit("/user.slice/user-1000.slice/user@1000.service/app.slice/run-r1.scope")
+                .as_deref(),
+            Some("run-r1.scope")
+        );
+        assert_eq!(systemd_unit("/kubepods/besteffort/pod1/abc"), None);
+    }
+}
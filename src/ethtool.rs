@@ -0,0 +1,1308 @@
+//! NIC deep statistics via the `SIOCETHTOOL` ioctl
+//!
+//! [`crate::network_monitor`] reports the generic counters every interface
+//! has. This module reads what only the driver knows, the same data
+//! `ethtool -i/-S/-g/-l/-k/-a/--show-fec` prints: driver and firmware
+//! versions, link modes (own, advertised and link partner), autonegotiation,
+//! duplex and FEC, ring and channel sizes, offload features, and the vendor
+//! statistics including per-queue counters.
+//!
+//! [`NicDetails::issues`] flags links that negotiated below what both ends
+//! support (a 100G port linking at 25G), half duplex, and error counters that
+//! grew between two snapshots; [`NicMonitor`] keeps the previous snapshot per
+//! interface for that.
+//!
+//! Request layouts (`<linux/ethtool.h>`) are declared here and parsed from
+//! byte buffers so they can be tested on any platform; only
+//! [`nic_details`] talks to the kernel. None of the queries need privileges.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::ethtool::NicMonitor;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = NicMonitor::new();
+//! let report = monitor.check("eth0")?;
+//! if let Some(link) = &report.details.link {
+//!     println!("eth0: {:?} Mb/s, autoneg {}", link.speed_mbps, link.autoneg);
+//! }
+//! for issue in &report.issues {
+//!     println!("[!] {}", issue);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+const ETHTOOL_GDRVINFO: u32 = 0x03;
+const ETHTOOL_GLINK: u32 = 0x0a;
+const ETHTOOL_GRINGPARAM: u32 = 0x10;
+const ETHTOOL_GPAUSEPARAM: u32 = 0x12;
+const ETHTOOL_GSTRINGS: u32 = 0x1b;
+const ETHTOOL_GSTATS: u32 = 0x1d;
+const ETHTOOL_GSSET_INFO: u32 = 0x37;
+const ETHTOOL_GFEATURES: u32 = 0x3a;
+const ETHTOOL_GCHANNELS: u32 = 0x3c;
+const ETHTOOL_GLINKSETTINGS: u32 = 0x4c;
+const ETHTOOL_GFECPARAM: u32 = 0x50;
+
+const ETH_SS_STATS: u32 = 1;
+const ETH_SS_FEATURES: u32 = 4;
+const ETH_GSTRING_LEN: usize = 32;
+
+/// `struct ethtool_drvinfo`
+const DRVINFO_LEN: usize = 196;
+/// `struct ethtool_link_settings` without the link mode masks
+const LINK_SETTINGS_LEN: usize = 48;
+/// `struct ethtool_ringparam` and `struct ethtool_channels`
+const RING_LEN: usize = 36;
+/// `struct ethtool_pauseparam` and `struct ethtool_fecparam`
+const PAUSE_LEN: usize = 16;
+
+const SPEED_UNKNOWN: u32 = u32::MAX;
+
+/// `enum ethtool_link_mode_bit_indices`
+const LINK_MODES: &[&str] = &[
+    "10baseT/Half",
+    "10baseT/Full",
+    "100baseT/Half",
+    "100baseT/Full",
+    "1000baseT/Half",
+    "1000baseT/Full",
+    "Autoneg",
+    "TP",
+    "AUI",
+    "MII",
+    "FIBRE",
+    "BNC",
+    "10000baseT/Full",
+    "Pause",
+    "Asym_Pause",
+    "2500baseX/Full",
+    "Backplane",
+    "1000baseKX/Full",
+    "10000baseKX4/Full",
+    "10000baseKR/Full",
+    "10000baseR_FEC",
+    "20000baseMLD2/Full",
+    "20000baseKR2/Full",
+    "40000baseKR4/Full",
+    "40000baseCR4/Full",
+    "40000baseSR4/Full",
+    "40000baseLR4/Full",
+    "56000baseKR4/Full",
+    "56000baseCR4/Full",
+    "56000baseSR4/Full",
+    "56000baseLR4/Full",
+    "25000baseCR/Full",
+    "25000baseKR/Full",
+    "25000baseSR/Full",
+    "50000baseCR2/Full",
+    "50000baseKR2/Full",
+    "100000baseKR4/Full",
+    "100000baseSR4/Full",
+    "100000baseCR4/Full",
+    "100000baseLR4_ER4/Full",
+    "50000baseSR2/Full",
+    "1000baseX/Full",
+    "10000baseCR/Full",
+    "10000baseSR/Full",
+    "10000baseLR/Full",
+    "10000baseLRM/Full",
+    "10000baseER/Full",
+    "2500baseT/Full",
+    "5000baseT/Full",
+    "FEC_NONE",
+    "FEC_RS",
+    "FEC_BASER",
+    "50000baseKR/Full",
+    "50000baseSR/Full",
+    "50000baseCR/Full",
+    "50000baseLR_ER_FR/Full",
+    "50000baseDR/Full",
+    "100000baseKR2/Full",
+    "100000baseSR2/Full",
+    "100000baseCR2/Full",
+    "100000baseLR2_ER2_FR2/Full",
+    "100000baseDR2/Full",
+    "200000baseKR4/Full",
+    "200000baseSR4/Full",
+    "200000baseLR4_ER4_FR4/Full",
+    "200000baseDR4/Full",
+    "200000baseCR4/Full",
+    "100baseT1/Full",
+    "1000baseT1/Full",
+    "400000baseKR8/Full",
+    "400000baseSR8/Full",
+    "400000baseLR8_ER8_FR8/Full",
+    "400000baseDR8/Full",
+    "400000baseCR8/Full",
+    "FEC_LLRS",
+    "100000baseKR/Full",
+    "100000baseSR/Full",
+    "100000baseLR_ER_FR/Full",
+    "100000baseCR/Full",
+    "100000baseDR/Full",
+    "200000baseKR2/Full",
+    "200000baseSR2/Full",
+    "200000baseLR2_ER2_FR2/Full",
+    "200000baseDR2/Full",
+    "200000baseCR2/Full",
+    "400000baseKR4/Full",
+    "400000baseSR4/Full",
+    "400000baseLR4_ER4_FR4/Full",
+    "400000baseDR4/Full",
+    "400000baseCR4/Full",
+    "100baseFX/Half",
+    "100baseFX/Full",
+    "10baseT1L/Full",
+    "800000baseCR8/Full",
+    "800000baseKR8/Full",
+    "800000baseDR8/Full",
+    "800000baseDR8_2/Full",
+    "800000baseSR8/Full",
+    "800000baseVR8/Full",
+    "10baseT1S/Full",
+    "10baseT1S/Half",
+    "10baseT1S_P2MP/Half",
+];
+
+/// `ETHTOOL_FEC_*_BIT`
+const FEC_MODES: &[&str] = &["none", "auto", "off", "rs", "baser", "llrs"];
+
+/// Driver and firmware identification (`ethtool -i`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriverInfo {
+    /// Kernel driver name
+    pub driver: String,
+    /// Driver version
+    pub version: String,
+    /// NIC firmware version
+    pub firmware_version: String,
+    /// Bus address (e.g. PCI `0000:3b:00.0`)
+    pub bus_info: String,
+    /// Expansion ROM version
+    pub expansion_rom_version: String,
+    /// Number of vendor statistics
+    pub n_stats: u32,
+}
+
+/// Link duplex
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Duplex {
+    Half,
+    Full,
+    Unknown,
+}
+
+/// Link negotiation state (`ethtool <iface>`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkSettings {
+    /// Current speed (None when the link is down or unknown)
+    pub speed_mbps: Option<u32>,
+    /// Current duplex
+    pub duplex: Duplex,
+    /// Connector type ("Twisted Pair", "FIBRE", "Direct Attach Copper", …)
+    pub port: String,
+    /// Autonegotiation enabled
+    pub autoneg: bool,
+    /// Link modes the NIC supports
+    pub supported: Vec<String>,
+    /// Link modes the NIC advertises
+    pub advertising: Vec<String>,
+    /// Link modes the link partner advertises (empty if not reported)
+    pub partner_advertising: Vec<String>,
+}
+
+impl LinkSettings {
+    /// Fastest speed among link modes
+    pub fn max_speed(modes: &[String]) -> Option<u32> {
+        modes.iter().filter_map(|m| mode_speed(m)).max()
+    }
+
+    /// Fastest speed both ends advertise, when the partner reports its modes
+    pub fn best_common_speed(&self) -> Option<u32> {
+        self.advertising
+            .iter()
+            .filter(|m| self.partner_advertising.contains(m))
+            .filter_map(|m| mode_speed(m))
+            .max()
+    }
+}
+
+/// Speed in Mb/s encoded in a link mode name such as `25000baseCR/Full`
+fn mode_speed(mode: &str) -> Option<u32> {
+    mode.split_once("base")
+        .and_then(|(speed, _)| speed.parse().ok())
+}
+
+/// Pause frame (flow control) settings (`ethtool -a`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PauseParams {
+    pub autoneg: bool,
+    pub rx: bool,
+    pub tx: bool,
+}
+
+/// Forward error correction (`ethtool --show-fec`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FecParams {
+    /// Configured FEC modes
+    pub configured: Vec<String>,
+    /// FEC mode in use on the link
+    pub active: Vec<String>,
+}
+
+/// Ring buffer sizes (`ethtool -g`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RingParams {
+    pub rx_max: u32,
+    pub rx_mini_max: u32,
+    pub rx_jumbo_max: u32,
+    pub tx_max: u32,
+    pub rx: u32,
+    pub rx_mini: u32,
+    pub rx_jumbo: u32,
+    pub tx: u32,
+}
+
+/// Queue/channel counts (`ethtool -l`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelParams {
+    pub max_rx: u32,
+    pub max_tx: u32,
+    pub max_other: u32,
+    pub max_combined: u32,
+    pub rx: u32,
+    pub tx: u32,
+    pub other: u32,
+    pub combined: u32,
+}
+
+/// One offload feature flag (`ethtool -k`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffloadFeature {
+    /// Kernel feature name (e.g. `tx-tcp-segmentation`)
+    pub name: String,
+    /// Currently enabled
+    pub active: bool,
+    /// Requested by the user/driver
+    pub requested: bool,
+    /// Cannot be changed on this device
+    pub fixed: bool,
+}
+
+/// Traffic direction of a queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueDirection {
+    Rx,
+    Tx,
+}
+
+/// Vendor statistics of one hardware queue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueStats {
+    pub direction: QueueDirection,
+    pub queue: u32,
+    /// Counter name without the queue prefix (e.g. `packets`)
+    pub counters: BTreeMap<String, u64>,
+}
+
+/// Health counters normalized across drivers, from the standard interface
+/// statistics and the vendor names that carry them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NicCounters {
+    /// Packets dropped because the NIC ran out of receive buffers
+    pub rx_missed: Option<u64>,
+    /// Frames received with a bad CRC/FCS
+    pub rx_crc_errors: Option<u64>,
+    pub rx_length_errors: Option<u64>,
+    pub rx_frame_errors: Option<u64>,
+    pub rx_fifo_errors: Option<u64>,
+    pub rx_over_errors: Option<u64>,
+    pub tx_carrier_errors: Option<u64>,
+    pub tx_fifo_errors: Option<u64>,
+    /// Pause frames received
+    pub rx_pause: Option<u64>,
+    /// Pause frames sent
+    pub tx_pause: Option<u64>,
+    /// Link up/down transitions
+    pub carrier_changes: Option<u64>,
+}
+
+impl NicCounters {
+    /// Error counters by name, for growth checks
+    fn errors(&self) -> [(&'static str, Option<u64>); 9] {
+        [
+            ("rx_missed", self.rx_missed),
+            ("rx_crc_errors", self.rx_crc_errors),
+            ("rx_length_errors", self.rx_length_errors),
+            ("rx_frame_errors", self.rx_frame_errors),
+            ("rx_fifo_errors", self.rx_fifo_errors),
+            ("rx_over_errors", self.rx_over_errors),
+            ("tx_carrier_errors", self.tx_carrier_errors),
+            ("tx_fifo_errors", self.tx_fifo_errors),
+            ("carrier_changes", self.carrier_changes),
+        ]
+    }
+
+    /// Fill pause and error counters from vendor statistics; names are
+    /// matched with any `port.` prefix removed (Intel i40e/ice)
+    fn merge_vendor(&mut self, stats: &BTreeMap<String, u64>) {
+        let lookup = |names: &[&str]| {
+            stats
+                .iter()
+                .filter(|(name, _)| names.contains(&name.strip_prefix("port.").unwrap_or(name)))
+                .map(|(_, value)| *value)
+                .max()
+        };
+        let merge = |field: &mut Option<u64>, names: &[&str]| {
+            if let Some(vendor) = lookup(names) {
+                *field = Some(field.map_or(vendor, |v| v.max(vendor)));
+            }
+        };
+        merge(
+            &mut self.rx_missed,
+            &[
+                "rx_missed_errors",
+                "rx_missed",
+                "rx_out_of_buffer",
+                "rx_no_buffer_count",
+            ],
+        );
+        merge(
+            &mut self.rx_crc_errors,
+            &[
+                "rx_crc_errors",
+                "rx_crc_errors_phy",
+                "rx_fcs_errors",
+                "mac_rx_fcs_err_pkt_num",
+            ],
+        );
+        merge(
+            &mut self.rx_pause,
+            &[
+                "rx_pause",
+                "rx_pause_ctrl_phy",
+                "rx_pause_frames",
+                "link_xoff_rx",
+                "rx_flow_control_xoff",
+                "mac_rx_mac_pause_num",
+            ],
+        );
+        merge(
+            &mut self.tx_pause,
+            &[
+                "tx_pause",
+                "tx_pause_ctrl_phy",
+                "tx_pause_frames",
+                "link_xoff_tx",
+                "tx_flow_control_xoff",
+                "mac_tx_mac_pause_num",
+            ],
+        );
+    }
+}
+
+/// Everything the driver reports about one interface
+///
+/// Sections the driver does not implement are None or empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NicDetails {
+    /// Interface name
+    pub name: String,
+    pub driver: Option<DriverInfo>,
+    /// Carrier detected
+    pub link_detected: Option<bool>,
+    pub link: Option<LinkSettings>,
+    pub pause: Option<PauseParams>,
+    pub fec: Option<FecParams>,
+    pub rings: Option<RingParams>,
+    pub channels: Option<ChannelParams>,
+    /// Offload features (`ethtool -k`)
+    pub features: Vec<OffloadFeature>,
+    /// Vendor statistics (`ethtool -S`), excluding per-queue counters
+    pub stats: BTreeMap<String, u64>,
+    /// Per-queue vendor statistics
+    pub queues: Vec<QueueStats>,
+    /// Normalized health counters
+    pub counters: NicCounters,
+}
+
+/// Something about a NIC that deserves attention
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NicIssue {
+    /// Linked slower than the fastest mode both ends advertise
+    SpeedMismatch { speed_mbps: u32, common_mbps: u32 },
+    /// Linked slower than this NIC advertises; the partner did not report
+    /// its modes, so the partner, cable or module is the likely limit
+    BelowAdvertised {
+        speed_mbps: u32,
+        advertised_mbps: u32,
+    },
+    /// This NIC does not advertise its fastest supported mode
+    NotAdvertisingMax {
+        advertised_mbps: u32,
+        supported_mbps: u32,
+    },
+    /// Negotiated half duplex
+    HalfDuplex,
+    /// An error counter grew since the previous snapshot
+    CounterGrowing { counter: String, delta: u64 },
+}
+
+impl fmt::Display for NicIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SpeedMismatch {
+                speed_mbps,
+                common_mbps,
+            } => write!(
+                f,
+                "link at {} but both ends advertise {}",
+                format_speed(*speed_mbps),
+                format_speed(*common_mbps)
+            ),
+            Self::BelowAdvertised {
+                speed_mbps,
+                advertised_mbps,
+            } => write!(
+                f,
+                "link at {} but this NIC advertises {} (check partner, cable or module)",
+                format_speed(*speed_mbps),
+                format_speed(*advertised_mbps)
+            ),
+            Self::NotAdvertisingMax {
+                advertised_mbps,
+                supported_mbps,
+            } => write!(
+                f,
+                "advertising up to {} but the NIC supports {}",
+                format_speed(*advertised_mbps),
+                format_speed(*supported_mbps)
+            ),
+            Self::HalfDuplex => write!(f, "negotiated half duplex"),
+            Self::CounterGrowing { counter, delta } => {
+                write!(f, "{} grew by {}", counter, delta)
+            }
+        }
+    }
+}
+
+/// Format Mb/s as ethtool-style `25Gb/s` or `100Mb/s`
+pub fn format_speed(mbps: u32) -> String {
+    if mbps >= 1000 && mbps % 1000 == 0 {
+        format!("{}Gb/s", mbps / 1000)
+    } else if mbps >= 1000 {
+        format!("{:.1}Gb/s", f64::from(mbps) / 1000.0)
+    } else {
+        format!("{}Mb/s", mbps)
+    }
+}
+
+impl NicDetails {
+    /// Link problems in this snapshot, plus error counters that grew since
+    /// `previous`
+    pub fn issues(&self, previous: Option<&NicDetails>) -> Vec<NicIssue> {
+        let mut issues = Vec::new();
+        if let Some(link) = &self.link {
+            issues.extend(link_issues(link, self.link_detected != Some(false)));
+        }
+        if let Some(previous) = previous {
+            let mut grown = BTreeMap::new();
+            let pairs = self
+                .counters
+                .errors()
+                .into_iter()
+                .zip(previous.counters.errors());
+            for ((name, now), (_, before)) in pairs {
+                if let (Some(now), Some(before)) = (now, before) {
+                    if now > before {
+                        grown.insert(name.to_string(), now - before);
+                    }
+                }
+            }
+            for (name, now) in &self.stats {
+                if !is_error_counter(name) {
+                    continue;
+                }
+                if let Some(before) = previous.stats.get(name) {
+                    if *now > *before {
+                        grown.entry(name.clone()).or_insert(now - before);
+                    }
+                }
+            }
+            issues.extend(
+                grown
+                    .into_iter()
+                    .map(|(counter, delta)| NicIssue::CounterGrowing { counter, delta }),
+            );
+        }
+        issues
+    }
+}
+
+fn link_issues(link: &LinkSettings, carrier: bool) -> Vec<NicIssue> {
+    let mut issues = Vec::new();
+    let advertised = LinkSettings::max_speed(&link.advertising);
+    let supported = LinkSettings::max_speed(&link.supported);
+    if let (true, Some(advertised), Some(supported)) = (link.autoneg, advertised, supported) {
+        if advertised < supported {
+            issues.push(NicIssue::NotAdvertisingMax {
+                advertised_mbps: advertised,
+                supported_mbps: supported,
+            });
+        }
+    }
+
+    let speed = match link.speed_mbps {
+        Some(speed) if carrier => speed,
+        _ => return issues,
+    };
+    if let Some(common) = link.best_common_speed() {
+        if speed < common {
+            issues.push(NicIssue::SpeedMismatch {
+                speed_mbps: speed,
+                common_mbps: common,
+            });
+        }
+    } else if let (true, Some(advertised)) = (link.autoneg, advertised) {
+        if speed < advertised {
+            issues.push(NicIssue::BelowAdvertised {
+                speed_mbps: speed,
+                advertised_mbps: advertised,
+            });
+        }
+    }
+    if link.duplex == Duplex::Half {
+        issues.push(NicIssue::HalfDuplex);
+    }
+    issues
+}
+
+/// Vendor counters whose growth signals trouble; XDP drops are policy, not
+/// errors
+fn is_error_counter(name: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "err",
+        "crc",
+        "fcs",
+        "missed",
+        "discard",
+        "drop",
+        "timeout",
+        "fifo",
+        "overrun",
+        "no_buf",
+        "out_of_buffer",
+        "symbol",
+    ];
+    let name = name.to_ascii_lowercase();
+    !name.contains("xdp") && MARKERS.iter().any(|m| name.contains(m))
+}
+
+/// Report for one interface from [`NicMonitor::check`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NicReport {
+    pub details: NicDetails,
+    pub issues: Vec<NicIssue>,
+}
+
+/// Keeps the previous snapshot of each interface to spot growing error
+/// counters
+#[derive(Debug, Default)]
+pub struct NicMonitor {
+    previous: HashMap<String, NicDetails>,
+}
+
+impl NicMonitor {
+    /// Create a monitor with no history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read `name` and compare with the previous check of the same interface
+    pub fn check(&mut self, name: &str) -> Result<NicReport> {
+        let details = nic_details(name)?;
+        let issues = details.issues(self.previous.get(name));
+        self.previous.insert(name.to_string(), details.clone());
+        Ok(NicReport { details, issues })
+    }
+}
+
+fn u32_at(buf: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
+
+fn c_string(buf: &[u8]) -> String {
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..end]).trim().to_string()
+}
+
+fn request(cmd: u32, len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    buf[..4].copy_from_slice(&cmd.to_ne_bytes());
+    buf
+}
+
+fn parse_drvinfo(buf: &[u8]) -> DriverInfo {
+    DriverInfo {
+        driver: c_string(&buf[4..36]),
+        version: c_string(&buf[36..68]),
+        firmware_version: c_string(&buf[68..100]),
+        bus_info: c_string(&buf[100..132]),
+        expansion_rom_version: c_string(&buf[132..164]),
+        n_stats: u32_at(buf, 180),
+    }
+}
+
+fn mode_names(words: &[u8]) -> Vec<String> {
+    let mut modes = Vec::new();
+    for (word_index, word) in words.chunks_exact(4).enumerate() {
+        let word = u32::from_ne_bytes([word[0], word[1], word[2], word[3]]);
+        for bit in 0..32 {
+            if word & (1 << bit) != 0 {
+                let index = word_index * 32 + bit;
+                modes.push(
+                    LINK_MODES
+                        .get(index)
+                        .map_or_else(|| format!("mode{}", index), |m| m.to_string()),
+                );
+            }
+        }
+    }
+    modes
+}
+
+fn port_name(port: u8) -> &'static str {
+    match port {
+        0x00 => "Twisted Pair",
+        0x01 => "AUI",
+        0x02 => "BNC",
+        0x03 => "MII",
+        0x04 => "FIBRE",
+        0x05 => "Direct Attach Copper",
+        0xef => "None",
+        _ => "Other",
+    }
+}
+
+/// Parse `struct ethtool_link_settings` followed by `nwords` words each of
+/// supported, advertising and link partner modes
+fn parse_link_settings(buf: &[u8], nwords: usize) -> LinkSettings {
+    let speed = u32_at(buf, 4);
+    let masks = &buf[LINK_SETTINGS_LEN..];
+    let mask = |i: usize| &masks[i * nwords * 4..(i + 1) * nwords * 4];
+    LinkSettings {
+        speed_mbps: (speed != SPEED_UNKNOWN && speed != 0).then_some(speed),
+        duplex: match buf[8] {
+            0 => Duplex::Half,
+            1 => Duplex::Full,
+            _ => Duplex::Unknown,
+        },
+        port: port_name(buf[9]).to_string(),
+        autoneg: buf[11] != 0,
+        supported: mode_names(mask(0)),
+        advertising: mode_names(mask(1)),
+        partner_advertising: mode_names(mask(2)),
+    }
+}
+
+fn fec_names(bits: u32) -> Vec<String> {
+    FEC_MODES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| bits & (1 << bit) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+fn parse_strings(buf: &[u8], count: usize) -> Vec<String> {
+    buf.chunks_exact(ETH_GSTRING_LEN)
+        .take(count)
+        .map(c_string)
+        .collect()
+}
+
+/// Split `ethtool -S` output into per-queue and device-wide counters
+fn split_stats(names: &[String], values: &[u64]) -> (BTreeMap<String, u64>, Vec<QueueStats>) {
+    let mut stats = BTreeMap::new();
+    let mut queues: BTreeMap<(QueueDirection, u32), BTreeMap<String, u64>> = BTreeMap::new();
+    for (name, value) in names.iter().zip(values) {
+        match parse_queue_stat(name) {
+            Some((direction, queue, counter)) => {
+                queues
+                    .entry((direction, queue))
+                    .or_default()
+                    .insert(counter, *value);
+            }
+            None => {
+                stats.insert(name.clone(), *value);
+            }
+        }
+    }
+    let queues = queues
+        .into_iter()
+        .map(|((direction, queue), counters)| QueueStats {
+            direction,
+            queue,
+            counters,
+        })
+        .collect();
+    (stats, queues)
+}
+
+/// Recognize the common per-queue naming schemes: `rx_queue_0_packets`
+/// (virtio, ixgbe), `rx-0.packets` (i40e, ice), `rx0_packets` (mlx5) and
+/// `queue_0_rx_cnt` (ena)
+fn parse_queue_stat(name: &str) -> Option<(QueueDirection, u32, String)> {
+    fn direction(prefix: &str) -> Option<QueueDirection> {
+        match prefix {
+            "rx" => Some(QueueDirection::Rx),
+            "tx" => Some(QueueDirection::Tx),
+            _ => None,
+        }
+    }
+    fn number(s: &str) -> Option<(u32, &str)> {
+        let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+        let queue = s[..digits].parse().ok()?;
+        Some((queue, &s[digits..]))
+    }
+
+    if let Some(rest) = name.strip_prefix("queue_") {
+        let (queue, rest) = number(rest)?;
+        let (dir, counter) = rest.strip_prefix('_')?.split_once('_')?;
+        return Some((direction(dir)?, queue, counter.to_string()));
+    }
+    let dir = direction(name.get(..2)?)?;
+    let rest = &name[2..];
+    if let Some(rest) = rest.strip_prefix("_queue_") {
+        let (queue, counter) = number(rest)?;
+        return Some((dir, queue, counter.strip_prefix('_')?.to_string()));
+    }
+    if let Some(rest) = rest.strip_prefix('-') {
+        let (queue, counter) = number(rest)?;
+        return Some((dir, queue, counter.strip_prefix('.')?.to_string()));
+    }
+    let (queue, counter) = number(rest)?;
+    Some((dir, queue, counter.strip_prefix('_')?.to_string()))
+}
+
+fn parse_features(buf: &[u8], names: &[String]) -> Vec<OffloadFeature> {
+    let blocks = u32_at(buf, 4) as usize;
+    let word = |block: usize, field: usize| u32_at(buf, 8 + block * 16 + field * 4);
+    names
+        .iter()
+        .enumerate()
+        .filter(|(i, name)| i / 32 < blocks && !name.is_empty())
+        .map(|(i, name)| {
+            let (block, bit) = (i / 32, 1u32 << (i % 32));
+            OffloadFeature {
+                name: name.clone(),
+                fixed: word(block, 0) & bit == 0 || word(block, 3) & bit != 0,
+                requested: word(block, 1) & bit != 0,
+                active: word(block, 2) & bit != 0,
+            }
+        })
+        .collect()
+}
+
+/// Read everything the driver of `name` reports
+///
+/// Fails only if the interface does not exist; sections the driver does
+/// not implement are left empty.
+#[cfg(target_os = "linux")]
+pub fn nic_details(name: &str) -> Result<NicDetails> {
+    let socket = sys::EthtoolSocket::open(name)?;
+    let not_found = |e: std::io::Error| match e.raw_os_error() {
+        Some(libc::ENODEV) => {
+            SimonError::DeviceNotFound(format!("Network interface '{}' not found", name))
+        }
+        _ => e.into(),
+    };
+
+    let driver = socket
+        .optional(request(ETHTOOL_GDRVINFO, DRVINFO_LEN))
+        .map_err(not_found)?
+        .map(|buf| parse_drvinfo(&buf));
+    let link_detected = socket
+        .optional(request(ETHTOOL_GLINK, 8))?
+        .map(|buf| u32_at(&buf, 4) != 0);
+    let link = link_settings(&socket)?;
+    let pause = socket
+        .optional(request(ETHTOOL_GPAUSEPARAM, PAUSE_LEN))?
+        .map(|buf| PauseParams {
+            autoneg: u32_at(&buf, 4) != 0,
+            rx: u32_at(&buf, 8) != 0,
+            tx: u32_at(&buf, 12) != 0,
+        });
+    let fec = socket
+        .optional(request(ETHTOOL_GFECPARAM, PAUSE_LEN))?
+        .map(|buf| FecParams {
+            configured: fec_names(u32_at(&buf, 8)),
+            active: fec_names(u32_at(&buf, 4)),
+        });
+    let rings = socket
+        .optional(request(ETHTOOL_GRINGPARAM, RING_LEN))?
+        .map(|buf| RingParams {
+            rx_max: u32_at(&buf, 4),
+            rx_mini_max: u32_at(&buf, 8),
+            rx_jumbo_max: u32_at(&buf, 12),
+            tx_max: u32_at(&buf, 16),
+            rx: u32_at(&buf, 20),
+            rx_mini: u32_at(&buf, 24),
+            rx_jumbo: u32_at(&buf, 28),
+            tx: u32_at(&buf, 32),
+        });
+    let channels = socket
+        .optional(request(ETHTOOL_GCHANNELS, RING_LEN))?
+        .map(|buf| ChannelParams {
+            max_rx: u32_at(&buf, 4),
+            max_tx: u32_at(&buf, 8),
+            max_other: u32_at(&buf, 12),
+            max_combined: u32_at(&buf, 16),
+            rx: u32_at(&buf, 20),
+            tx: u32_at(&buf, 24),
+            other: u32_at(&buf, 28),
+            combined: u32_at(&buf, 32),
+        });
+    let features = features(&socket)?;
+
+    let n_stats = driver.as_ref().map_or(0, |d| d.n_stats as usize);
+    let (stats, queues) = match vendor_stats(&socket, n_stats)? {
+        Some((names, values)) => split_stats(&names, &values),
+        None => Default::default(),
+    };
+    let mut counters = sys::standard_counters(name);
+    counters.merge_vendor(&stats);
+
+    Ok(NicDetails {
+        name: name.to_string(),
+        driver,
+        link_detected,
+        link,
+        pause,
+        fec,
+        rings,
+        channels,
+        features,
+        stats,
+        queues,
+        counters,
+    })
+}
+
+/// Read everything the driver of `name` reports
+#[cfg(not(target_os = "linux"))]
+pub fn nic_details(_name: &str) -> Result<NicDetails> {
+    Err(SimonError::UnsupportedPlatform(
+        "ethtool statistics are only available on Linux".to_string(),
+    ))
+}
+
+/// `ETHTOOL_GLINKSETTINGS` is a two-step handshake: the first call answers
+/// with the negated number of mask words the kernel wants
+#[cfg(target_os = "linux")]
+fn link_settings(socket: &sys::EthtoolSocket) -> Result<Option<LinkSettings>> {
+    let probe = match socket.optional(request(ETHTOOL_GLINKSETTINGS, LINK_SETTINGS_LEN))? {
+        Some(probe) => probe,
+        None => return Ok(None),
+    };
+    let nwords = (probe[15] as i8).unsigned_abs() as usize;
+    if nwords == 0 {
+        return Ok(None);
+    }
+    let mut buf = request(ETHTOOL_GLINKSETTINGS, LINK_SETTINGS_LEN + 3 * nwords * 4);
+    buf[15] = nwords as u8;
+    Ok(socket
+        .optional(buf)?
+        .map(|buf| parse_link_settings(&buf, nwords)))
+}
+
+/// Number of strings in a string set, via `ETHTOOL_GSSET_INFO`
+#[cfg(target_os = "linux")]
+fn string_set_len(socket: &sys::EthtoolSocket, set: u32) -> Result<usize> {
+    let mut buf = request(ETHTOOL_GSSET_INFO, 20);
+    buf[8..16].copy_from_slice(&(1u64 << set).to_ne_bytes());
+    Ok(match socket.optional(buf)? {
+        Some(buf) if u64::from_ne_bytes(buf[8..16].try_into().unwrap_or_default()) != 0 => {
+            u32_at(&buf, 16) as usize
+        }
+        _ => 0,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn strings(socket: &sys::EthtoolSocket, set: u32, count: usize) -> Result<Option<Vec<String>>> {
+    let mut buf = request(ETHTOOL_GSTRINGS, 12 + count * ETH_GSTRING_LEN);
+    buf[4..8].copy_from_slice(&set.to_ne_bytes());
+    buf[8..12].copy_from_slice(&(count as u32).to_ne_bytes());
+    Ok(socket.optional(buf)?.map(|buf| {
+        let count = count.min(u32_at(&buf, 8) as usize);
+        parse_strings(&buf[12..], count)
+    }))
+}
+
+#[cfg(target_os = "linux")]
+fn features(socket: &sys::EthtoolSocket) -> Result<Vec<OffloadFeature>> {
+    let count = string_set_len(socket, ETH_SS_FEATURES)?;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let names = match strings(socket, ETH_SS_FEATURES, count)? {
+        Some(names) => names,
+        None => return Ok(Vec::new()),
+    };
+    let blocks = (count + 31) / 32;
+    let mut buf = request(ETHTOOL_GFEATURES, 8 + blocks * 16);
+    buf[4..8].copy_from_slice(&(blocks as u32).to_ne_bytes());
+    Ok(socket
+        .optional(buf)?
+        .map(|buf| parse_features(&buf, &names))
+        .unwrap_or_default())
+}
+
+#[cfg(target_os = "linux")]
+fn vendor_stats(
+    socket: &sys::EthtoolSocket,
+    count: usize,
+) -> Result<Option<(Vec<String>, Vec<u64>)>> {
+    if count == 0 {
+        return Ok(None);
+    }
+    let names = match strings(socket, ETH_SS_STATS, count)? {
+        Some(names) => names,
+        None => return Ok(None),
+    };
+    let mut buf = request(ETHTOOL_GSTATS, 8 + count * 8);
+    buf[4..8].copy_from_slice(&(count as u32).to_ne_bytes());
+    Ok(socket.optional(buf)?.map(|buf| {
+        let values = buf[8..]
+            .chunks_exact(8)
+            .take(count.min(u32_at(&buf, 4) as usize))
+            .map(|v| u64::from_ne_bytes(v.try_into().unwrap_or_default()))
+            .collect();
+        (names, values)
+    }))
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use super::NicCounters;
+    use std::io;
+    use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+
+    /// `struct ifreq` with the `ifr_data` member of the union
+    #[repr(C)]
+    struct IfReq {
+        name: [u8; libc::IFNAMSIZ],
+        data: *mut libc::c_void,
+        _pad: [u8; 16],
+    }
+
+    /// Datagram socket used only as an ioctl handle for one interface
+    pub struct EthtoolSocket {
+        fd: OwnedFd,
+        name: [u8; libc::IFNAMSIZ],
+    }
+
+    impl EthtoolSocket {
+        pub fn open(interface: &str) -> io::Result<Self> {
+            if interface.is_empty() || interface.len() >= libc::IFNAMSIZ {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid interface name '{}'", interface),
+                ));
+            }
+            let mut name = [0u8; libc::IFNAMSIZ];
+            name[..interface.len()].copy_from_slice(interface.as_bytes());
+
+            // SAFETY: plain socket(2) call; the returned descriptor is owned below.
+            let fd =
+                unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: fd is a freshly created, valid descriptor not owned elsewhere.
+            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+            Ok(Self { fd, name })
+        }
+
+        /// Run one `SIOCETHTOOL` request in place
+        pub fn call(&self, buf: &mut [u8]) -> io::Result<()> {
+            let mut req = IfReq {
+                name: self.name,
+                data: buf.as_mut_ptr().cast(),
+                _pad: [0; 16],
+            };
+            // SAFETY: req points at a buffer sized for the command in its
+            // first word, which the kernel reads and writes within bounds.
+            let rc = unsafe { libc::ioctl(self.fd.as_raw_fd(), libc::SIOCETHTOOL as _, &mut req) };
+            if rc < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        /// Like [`call`](Self::call), but None when the driver does not
+        /// implement the command
+        pub fn optional(&self, mut buf: Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+            match self.call(&mut buf) {
+                Ok(()) => Ok(Some(buf)),
+                Err(e)
+                    if matches!(
+                        e.raw_os_error(),
+                        Some(libc::EOPNOTSUPP) | Some(libc::EINVAL) | Some(libc::EPERM)
+                    ) =>
+                {
+                    Ok(None)
+                }
+                Err(e) => Err(e),
+            }
+        }
+    }
+
+    /// Standard counters from `/sys/class/net/<iface>/statistics`
+    pub fn standard_counters(interface: &str) -> NicCounters {
+        let read = |file: &str| {
+            std::fs::read_to_string(format!("/sys/class/net/{}/{}", interface, file))
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+        };
+        NicCounters {
+            rx_missed: read("statistics/rx_missed_errors"),
+            rx_crc_errors: read("statistics/rx_crc_errors"),
+            rx_length_errors: read("statistics/rx_length_errors"),
+            rx_frame_errors: read("statistics/rx_frame_errors"),
+            rx_fifo_errors: read("statistics/rx_fifo_errors"),
+            rx_over_errors: read("statistics/rx_over_errors"),
+            tx_carrier_errors: read("statistics/tx_carrier_errors"),
+            tx_fifo_errors: read("statistics/tx_fifo_errors"),
+            rx_pause: None,
+            tx_pause: None,
+            carrier_changes: read("carrier_changes"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modes(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn link(speed: u32, advertising: &[&str], partner: &[&str]) -> LinkSettings {
+        LinkSettings {
+            speed_mbps: Some(speed),
+            duplex: Duplex::Full,
+            port: "FIBRE".into(),
+            autoneg: true,
+            supported: modes(advertising),
+            advertising: modes(advertising),
+            partner_advertising: modes(partner),
+        }
+    }
+
+    #[test]
+    fn test_parse_link_settings() {
+        let nwords = 3;
+        let mut buf = request(ETHTOOL_GLINKSETTINGS, LINK_SETTINGS_LEN + 3 * nwords * 4);
+        buf[4..8].copy_from_slice(&25_000u32.to_ne_bytes());
+        buf[8] = 1; // full duplex
+        buf[9] = 0x05; // direct attach
+        buf[11] = 1; // autoneg
+        let set = |buf: &mut Vec<u8>, mask: usize, bit: usize| {
+            let at = LINK_SETTINGS_LEN + mask * nwords * 4 + bit / 32 * 4;
+            let word = u32_at(buf, at) | 1 << (bit % 32);
+            buf[at..at + 4].copy_from_slice(&word.to_ne_bytes());
+        };
+        for bit in [31, 38, 6] {
+            set(&mut buf, 0, bit); // supported: 25G CR, 100G CR4, Autoneg
+            set(&mut buf, 1, bit);
+        }
+        set(&mut buf, 2, 31); // partner: 25G CR only
+
+        let link = parse_link_settings(&buf, nwords);
+        assert_eq!(link.speed_mbps, Some(25_000));
+        assert_eq!(
+            (link.duplex, link.port.as_str()),
+            (Duplex::Full, "Direct Attach Copper")
+        );
+        assert_eq!(
+            link.supported,
+            modes(&["Autoneg", "25000baseCR/Full", "100000baseCR4/Full"])
+        );
+        assert_eq!(LinkSettings::max_speed(&link.advertising), Some(100_000));
+        assert_eq!(link.best_common_speed(), Some(25_000));
+        // The partner only offers 25G, so 25G is the right outcome
+        assert!(link_issues(&link, true).is_empty());
+    }
+
+    #[test]
+    fn test_link_issues() {
+        // Both ends advertise 100G yet the link came up at 25G
+        let both = ["25000baseCR/Full", "100000baseCR4/Full"];
+        assert_eq!(
+            link_issues(&link(25_000, &both, &both), true),
+            vec![NicIssue::SpeedMismatch {
+                speed_mbps: 25_000,
+                common_mbps: 100_000
+            }]
+        );
+        // Partner silent: compare with our own advertisement
+        assert_eq!(
+            link_issues(&link(25_000, &both, &[]), true),
+            vec![NicIssue::BelowAdvertised {
+                speed_mbps: 25_000,
+                advertised_mbps: 100_000
+            }]
+        );
+        // No carrier, nothing to compare
+        assert!(link_issues(&link(25_000, &both, &[]), false).is_empty());
+
+        let mut restricted = link(25_000, &["25000baseCR/Full"], &[]);
+        restricted.supported = modes(&both);
+        restricted.duplex = Duplex::Half;
+        assert_eq!(
+            link_issues(&restricted, true),
+            vec![
+                NicIssue::NotAdvertisingMax {
+                    advertised_mbps: 25_000,
+                    supported_mbps: 100_000
+                },
+                NicIssue::HalfDuplex
+            ]
+        );
+        assert_eq!(format_speed(25_000), "25Gb/s");
+        assert_eq!(format_speed(2_500), "2.5Gb/s");
+    }
+
+    #[test]
+    fn test_queue_stats() {
+        let cases = [
+            (
+                "rx_queue_3_packets",
+                Some((QueueDirection::Rx, 3, "packets")),
+            ),
+            ("tx-12.bytes", Some((QueueDirection::Tx, 12, "bytes"))),
+            (
+                "rx0_cache_full",
+                Some((QueueDirection::Rx, 0, "cache_full")),
+            ),
+            ("queue_1_tx_cnt", Some((QueueDirection::Tx, 1, "cnt"))),
+            ("rx_crc_errors", None),
+            ("tx_pause", None),
+            ("rx_packets", None),
+        ];
+        for (name, expected) in cases {
+            let parsed = parse_queue_stat(name);
+            let expected = expected.map(|(d, q, c)| (d, q, c.to_string()));
+            assert_eq!(parsed, expected, "{}", name);
+        }
+
+        let names = modes(&["rx_queue_0_packets", "rx_queue_1_packets", "rx_crc_errors"]);
+        let (stats, queues) = split_stats(&names, &[10, 20, 3]);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(queues.len(), 2);
+        assert_eq!(queues[1].counters["packets"], 20);
+    }
+
+    #[test]
+    fn test_counters_and_growth() {
+        let stats: BTreeMap<String, u64> = [
+            ("port.rx_crc_errors", 7),
+            ("rx_pause_ctrl_phy", 100),
+            ("rx_queue_0_xdp_drops", 5),
+            ("rx_discards_phy", 2),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+        let mut counters = NicCounters {
+            rx_crc_errors: Some(0),
+            rx_missed: Some(1),
+            ..Default::default()
+        };
+        counters.merge_vendor(&stats);
+        assert_eq!(counters.rx_crc_errors, Some(7));
+        assert_eq!(counters.rx_pause, Some(100));
+        assert_eq!(counters.tx_pause, None);
+
+        let before = NicDetails {
+            name: "eth0".into(),
+            driver: None,
+            link_detected: Some(true),
+            link: None,
+            pause: None,
+            fec: None,
+            rings: None,
+            channels: None,
+            features: Vec::new(),
+            stats,
+            queues: Vec::new(),
+            counters,
+        };
+        let mut after = before.clone();
+        after.counters.rx_crc_errors = Some(9);
+        after.counters.rx_pause = Some(150);
+        after.stats.insert("rx_discards_phy".into(), 6);
+        after.stats.insert("rx_queue_0_xdp_drops".into(), 50);
+
+        assert!(before.issues(None).is_empty());
+        assert_eq!(
+            after.issues(Some(&before)),
+            vec![
+                NicIssue::CounterGrowing {
+                    counter: "rx_crc_errors".into(),
+                    delta: 2
+                },
+                NicIssue::CounterGrowing {
+                    counter: "rx_discards_phy".into(),
+                    delta: 4
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_drvinfo_and_features() {
+        let mut buf = request(ETHTOOL_GDRVINFO, DRVINFO_LEN);
+        buf[4..9].copy_from_slice(b"mlx5_");
+        buf[68..74].copy_from_slice(b"22.36.");
+        buf[100..112].copy_from_slice(b"0000:3b:00.0");
+        buf[180..184].copy_from_slice(&42u32.to_ne_bytes());
+        let info = parse_drvinfo(&buf);
+        assert_eq!(info.driver, "mlx5_");
+        assert_eq!(info.bus_info, "0000:3b:00.0");
+        assert_eq!(info.n_stats, 42);
+
+        let names = modes(&["tx-scatter-gather", "rx-checksum", "rx-gro-hw"]);
+        let mut buf = request(ETHTOOL_GFEATURES, 8 + 16);
+        buf[4..8].copy_from_slice(&1u32.to_ne_bytes());
+        buf[8..12].copy_from_slice(&0b011u32.to_ne_bytes()); // available
+        buf[12..16].copy_from_slice(&0b001u32.to_ne_bytes()); // requested
+        buf[16..20].copy_from_slice(&0b011u32.to_ne_bytes()); // active
+        let features = parse_features(&buf, &names);
+        assert_eq!(features.len(), 3);
+        assert!(features[0].active && features[0].requested && !features[0].fixed);
+        assert!(features[1].active && !features[1].requested);
+        assert!(!features[2].active && features[2].fixed);
+
+        assert_eq!(fec_names(0b1000), vec!["rs".to_string()]);
+    }
+}
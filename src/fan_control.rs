@@ -5,6 +5,7 @@
 //! - RPM (Revolutions Per Minute) monitoring
 //! - Fan profiles (quiet, cool, performance, manual)
 //! - Thermal zone integration
+//! - Fan calibration (PWM→RPM response, start/stop duty, RPM-based curves)
 //! - Multi-platform support (Linux hwmon, Windows WMI)
 //!
 //! # Example
@@ -370,6 +371,330 @@ impl FanCurve {
     }
 }
 
+/// Fan curve point with an RPM target instead of a duty cycle
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RpmCurvePoint {
+    /// Temperature threshold (°C)
+    pub temp_celsius: f32,
+    /// Target fan speed (RPM)
+    pub rpm: u32,
+}
+
+impl FanCurve {
+    /// Build a duty-cycle curve from RPM targets using a fan's calibration profile
+    ///
+    /// Targets above the fan's calibrated maximum map to 100%, and targets below
+    /// its stop threshold map to 0%.
+    pub fn from_rpm_targets(
+        name: impl Into<String>,
+        points: &[RpmCurvePoint],
+        profile: &FanCalibrationProfile,
+        hysteresis: f32,
+    ) -> Self {
+        let mut points: Vec<FanCurvePoint> = points
+            .iter()
+            .map(|p| FanCurvePoint {
+                temp_celsius: p.temp_celsius,
+                speed_percent: profile.duty_for_rpm(p.rpm),
+            })
+            .collect();
+        points.sort_by(|a, b| a.temp_celsius.total_cmp(&b.temp_celsius));
+        Self {
+            name: name.into(),
+            points,
+            hysteresis,
+        }
+    }
+}
+
+/// Measured settled RPM at a given duty cycle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationPoint {
+    /// Duty cycle (%)
+    pub duty_percent: f32,
+    /// Settled speed (RPM)
+    pub rpm: u32,
+}
+
+/// Learned PWM→RPM response of a single fan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanCalibrationProfile {
+    /// Fan identifier (e.g. `nct6798/pwm2`)
+    pub fan: String,
+    /// Response curve, ascending by duty
+    pub points: Vec<CalibrationPoint>,
+    /// Lowest duty that starts the fan from standstill (%)
+    pub start_duty_percent: f32,
+    /// Lowest duty at which a spinning fan keeps running (%)
+    pub stop_duty_percent: f32,
+    /// Speed at 100% duty (RPM)
+    pub max_rpm: u32,
+    /// Calibration time (Unix seconds)
+    pub calibrated_at: u64,
+}
+
+impl FanCalibrationProfile {
+    /// Expected settled RPM at a duty cycle, interpolated from the response curve
+    pub fn rpm_at(&self, duty_percent: f32) -> Option<f32> {
+        if duty_percent < self.stop_duty_percent {
+            return Some(0.0);
+        }
+        let running: Vec<&CalibrationPoint> = self.points.iter().filter(|p| p.rpm > 0).collect();
+        let first = running.first()?;
+        let last = running.last()?;
+        if duty_percent <= first.duty_percent {
+            return Some(first.rpm as f32);
+        }
+        if duty_percent >= last.duty_percent {
+            return Some(last.rpm as f32);
+        }
+        running.windows(2).find_map(|w| {
+            let (a, b) = (w[0], w[1]);
+            (duty_percent >= a.duty_percent && duty_percent <= b.duty_percent).then(|| {
+                let t = (duty_percent - a.duty_percent) / (b.duty_percent - a.duty_percent);
+                a.rpm as f32 + t * (b.rpm as f32 - a.rpm as f32)
+            })
+        })
+    }
+
+    /// Lowest duty cycle expected to reach a target RPM
+    ///
+    /// Returns 0% for a zero target and 100% for targets beyond the fan's maximum.
+    pub fn duty_for_rpm(&self, rpm: u32) -> f32 {
+        if rpm == 0 {
+            return 0.0;
+        }
+        if rpm >= self.max_rpm {
+            return 100.0;
+        }
+        let running: Vec<&CalibrationPoint> = self.points.iter().filter(|p| p.rpm > 0).collect();
+        match running.first() {
+            Some(first) if rpm <= first.rpm => {
+                return first.duty_percent.max(self.stop_duty_percent);
+            }
+            None => return 100.0,
+            _ => {}
+        }
+        running
+            .windows(2)
+            .find_map(|w| {
+                let (a, b) = (w[0], w[1]);
+                (rpm >= a.rpm && rpm <= b.rpm && b.rpm > a.rpm).then(|| {
+                    let t = (rpm - a.rpm) as f32 / (b.rpm - a.rpm) as f32;
+                    a.duty_percent + t * (b.duty_percent - a.duty_percent)
+                })
+            })
+            .unwrap_or(100.0)
+    }
+
+    /// Measured speed as a percentage of the calibrated baseline at the same duty
+    ///
+    /// A healthy fan stays near 100%; worn bearings show up as a sustained drop.
+    /// Returns `None` when the duty is below the stop threshold or the baseline is unknown.
+    pub fn rpm_ratio_percent(&self, duty_percent: f32, rpm: u32) -> Option<f32> {
+        let expected = self.rpm_at(duty_percent)?;
+        if expected <= 0.0 {
+            return None;
+        }
+        Some(rpm as f32 / expected * 100.0)
+    }
+}
+
+/// Persistent collection of fan calibration profiles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FanCalibrationStore {
+    /// Profiles keyed by fan identifier
+    #[serde(default)]
+    pub profiles: HashMap<String, FanCalibrationProfile>,
+}
+
+impl FanCalibrationStore {
+    /// Default store location (`~/.config/simon/fan_profiles.toml`)
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::config::Config::default_path()?.join("fan_profiles.toml"))
+    }
+
+    /// Load from the default path (empty if missing)
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    /// Load from a specific path (empty if missing)
+    pub fn load_from(path: &std::path::Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        toml::from_str(&contents)
+            .map_err(|e| SimonError::Parse(format!("Failed to parse fan profiles: {}", e)))
+    }
+
+    /// Save to the default path
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::default_path()?)
+    }
+
+    /// Save to a specific path
+    pub fn save_to(&self, path: &std::path::Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| SimonError::Other(format!("Failed to serialize fan profiles: {}", e)))?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Get a fan's profile
+    pub fn get(&self, fan: &str) -> Option<&FanCalibrationProfile> {
+        self.profiles.get(fan)
+    }
+
+    /// Insert or replace a fan's profile
+    pub fn insert(&mut self, profile: FanCalibrationProfile) {
+        self.profiles.insert(profile.fan.clone(), profile);
+    }
+}
+
+/// A fan whose duty can be set and whose speed can be measured
+pub trait FanActuator {
+    /// Set the duty cycle (%)
+    fn set_duty_percent(&mut self, percent: f32) -> Result<()>;
+    /// Read the current speed (RPM)
+    fn rpm(&self) -> Option<u32>;
+}
+
+/// Calibration step and timing settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationSettings {
+    /// Duty step for the response curve (%)
+    pub step_percent: f32,
+    /// Finer step used to search for the start threshold (%)
+    pub start_step_percent: f32,
+    /// Wait after each duty change before sampling
+    pub settle: Duration,
+    /// RPM samples averaged per step
+    pub samples: u32,
+    /// Interval between samples
+    pub sample_interval: Duration,
+}
+
+impl Default for CalibrationSettings {
+    fn default() -> Self {
+        Self {
+            step_percent: 10.0,
+            start_step_percent: 2.0,
+            settle: Duration::from_secs(4),
+            samples: 3,
+            sample_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Step a fan through its duty range and learn its response
+///
+/// The fan is first run at 100% to find its maximum speed, then stepped down to
+/// 0% to record the response curve and stop threshold, then stepped up from
+/// standstill to find the start threshold. The fan is left at 100% duty; the
+/// caller is responsible for restoring its control mode. `sleep` is called for
+/// every settle/sample wait so the routine can be driven without real delays.
+pub fn calibrate_fan(
+    fan: &str,
+    actuator: &mut dyn FanActuator,
+    settings: &CalibrationSettings,
+    mut sleep: impl FnMut(Duration),
+) -> Result<FanCalibrationProfile> {
+    if settings.step_percent <= 0.0 || settings.start_step_percent <= 0.0 {
+        return Err(SimonError::InvalidValue(
+            "Calibration steps must be positive".to_string(),
+        ));
+    }
+
+    let mut measure = |actuator: &mut dyn FanActuator, duty: f32| -> Result<u32> {
+        actuator.set_duty_percent(duty)?;
+        sleep(settings.settle);
+        let mut total = 0u64;
+        let mut count = 0u64;
+        for i in 0..settings.samples.max(1) {
+            if i > 0 {
+                sleep(settings.sample_interval);
+            }
+            if let Some(rpm) = actuator.rpm() {
+                total += rpm as u64;
+                count += 1;
+            }
+        }
+        if count == 0 {
+            return Err(SimonError::FeatureNotAvailable(format!(
+                "Fan '{}' has no tachometer reading",
+                fan
+            )));
+        }
+        Ok((total / count) as u32)
+    };
+
+    let max_rpm = measure(actuator, 100.0)?;
+    if max_rpm == 0 {
+        let _ = actuator.set_duty_percent(100.0);
+        return Err(SimonError::HardwareError(format!(
+            "Fan '{}' reports 0 RPM at full duty (disconnected or stalled)",
+            fan
+        )));
+    }
+
+    // Step down: response curve and stop threshold
+    let mut points = vec![CalibrationPoint {
+        duty_percent: 100.0,
+        rpm: max_rpm,
+    }];
+    let mut stop_duty = 0.0;
+    let mut duty = 100.0 - settings.step_percent;
+    while duty >= 0.0 {
+        let rpm = measure(actuator, duty)?;
+        points.push(CalibrationPoint {
+            duty_percent: duty,
+            rpm,
+        });
+        if rpm == 0 {
+            break;
+        }
+        stop_duty = duty;
+        duty -= settings.step_percent;
+    }
+
+    // Step up from standstill: start threshold
+    let mut start_duty = 100.0;
+    if points.last().is_some_and(|p| p.rpm == 0) {
+        measure(actuator, 0.0)?;
+        let mut duty = 0.0;
+        while duty <= 100.0 {
+            if measure(actuator, duty)? > 0 {
+                start_duty = duty;
+                break;
+            }
+            duty += settings.start_step_percent;
+        }
+    } else {
+        // Fan never stopped, so it also starts at any duty
+        start_duty = 0.0;
+    }
+
+    let _ = actuator.set_duty_percent(100.0);
+
+    points.sort_by(|a, b| a.duty_percent.total_cmp(&b.duty_percent));
+    Ok(FanCalibrationProfile {
+        fan: fan.to_string(),
+        points,
+        start_duty_percent: start_duty,
+        stop_duty_percent: stop_duty.min(start_duty),
+        max_rpm,
+        calibrated_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    })
+}
+
 /// Thermal zone information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThermalZone {
@@ -1352,4 +1677,126 @@ mod tests {
         };
         assert_eq!(curve.calculate_speed(50.0), 100.0);
     }
+
+    // === Calibration tests ===
+
+    /// Simulated fan: stops below 20% duty, needs 30% to start, 300 + 15 RPM per %
+    struct SimulatedFan {
+        duty: f32,
+        running: bool,
+    }
+
+    impl FanActuator for SimulatedFan {
+        fn set_duty_percent(&mut self, percent: f32) -> Result<()> {
+            self.duty = percent;
+            if self.running && percent < 20.0 {
+                self.running = false;
+            } else if !self.running && percent >= 30.0 {
+                self.running = true;
+            }
+            Ok(())
+        }
+
+        fn rpm(&self) -> Option<u32> {
+            Some(if self.running {
+                300 + (self.duty * 15.0) as u32
+            } else {
+                0
+            })
+        }
+    }
+
+    fn simulated_profile() -> FanCalibrationProfile {
+        let mut fan = SimulatedFan {
+            duty: 0.0,
+            running: false,
+        };
+        calibrate_fan(
+            "sim/pwm1",
+            &mut fan,
+            &CalibrationSettings::default(),
+            |_| {},
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_calibration_learns_thresholds() {
+        let profile = simulated_profile();
+        assert_eq!(profile.fan, "sim/pwm1");
+        assert_eq!(profile.max_rpm, 1800);
+        assert_eq!(profile.stop_duty_percent, 20.0);
+        assert_eq!(profile.start_duty_percent, 30.0);
+        assert_eq!(profile.points.first().unwrap().rpm, 0);
+        assert_eq!(profile.points.last().unwrap().duty_percent, 100.0);
+    }
+
+    #[test]
+    fn test_calibration_rpm_mapping() {
+        let profile = simulated_profile();
+        assert_eq!(profile.rpm_at(50.0), Some(1050.0));
+        assert_eq!(profile.rpm_at(10.0), Some(0.0));
+        assert!((profile.duty_for_rpm(1050) - 50.0).abs() < 0.01);
+        assert_eq!(profile.duty_for_rpm(5000), 100.0);
+        assert_eq!(profile.duty_for_rpm(0), 0.0);
+        // Below the slowest running speed, the stop threshold is the floor
+        assert_eq!(profile.duty_for_rpm(100), 20.0);
+    }
+
+    #[test]
+    fn test_calibration_no_tachometer() {
+        struct NoTach;
+        impl FanActuator for NoTach {
+            fn set_duty_percent(&mut self, _percent: f32) -> Result<()> {
+                Ok(())
+            }
+            fn rpm(&self) -> Option<u32> {
+                None
+            }
+        }
+        let result = calibrate_fan("x", &mut NoTach, &CalibrationSettings::default(), |_| {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_rpm_ratio_detects_wear() {
+        let profile = simulated_profile();
+        let healthy = profile.rpm_ratio_percent(50.0, 1050).unwrap();
+        let worn = profile.rpm_ratio_percent(50.0, 840).unwrap();
+        assert!((healthy - 100.0).abs() < 0.01);
+        assert!((worn - 80.0).abs() < 0.01);
+        assert_eq!(profile.rpm_ratio_percent(5.0, 0), None);
+    }
+
+    #[test]
+    fn test_fan_curve_from_rpm_targets() {
+        let profile = simulated_profile();
+        let curve = FanCurve::from_rpm_targets(
+            "rpm",
+            &[
+                RpmCurvePoint {
+                    temp_celsius: 70.0,
+                    rpm: 1800,
+                },
+                RpmCurvePoint {
+                    temp_celsius: 40.0,
+                    rpm: 750,
+                },
+            ],
+            &profile,
+            3.0,
+        );
+        assert_eq!(curve.points[0].temp_celsius, 40.0);
+        assert!((curve.points[0].speed_percent - 30.0).abs() < 0.01);
+        assert_eq!(curve.points[1].speed_percent, 100.0);
+    }
+
+    #[test]
+    fn test_calibration_store_roundtrip() {
+        let mut store = FanCalibrationStore::default();
+        store.insert(simulated_profile());
+        let text = toml::to_string_pretty(&store).unwrap();
+        let parsed: FanCalibrationStore = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.get("sim/pwm1"), store.get("sim/pwm1"));
+    }
 }
@@ -0,0 +1,1333 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+// Copyright (c) 2026 nervosys
+
+//! Closed-loop fan curve controller
+//!
+//! Continuously drives PWM fans from one or more temperature sources, in the
+//! spirit of `fancontrol` / CoolerControl. Each fan binding combines its
+//! sources (hwmon sensors, thermal zones, GPU temperatures) with max or
+//! weighted average, maps the result through a [`FanCurve`], and then applies:
+//!
+//! - hysteresis (speed only drops after the temperature falls by the curve's
+//!   hysteresis since the last increase)
+//! - ramp-rate limits (separate up/down rates in %/s)
+//! - minimum duty and spin-up kick for stopped fans
+//! - stall detection via [`FanInfo::is_potentially_stalled`], which forces
+//!   emergency full speed (as does losing every temperature source)
+//!
+//! On drop, every fan is returned to the `pwmN_enable` mode (and duty) it had
+//! before the controller took it over.
+//!
+//! # Configuration
+//!
+//! ```toml
+//! enabled = true
+//! interval_ms = 1000
+//!
+//! [[fans]]
+//! name = "cpu"
+//! chip = "nct6798"
+//! pwm = 2
+//! combine = "max"
+//! curve = "quiet"
+//! min_percent = 20.0
+//!
+//! [[fans.sources]]
+//! type = "hwmon"
+//! chip = "k10temp"
+//! sensor = "Tctl"
+//!
+//! [[fans.sources]]
+//! type = "gpu"
+//! index = 0
+//! weight = 0.5
+//! ```
+
+use crate::error::{Result, SimonError};
+use crate::fan_control::{
+    calibrate_fan, CalibrationSettings, FanActuator, FanCalibrationProfile, FanCalibrationStore,
+    FanCurve, FanCurvePoint, FanInfo, RpmCurvePoint,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// A temperature input for a fan binding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TempSource {
+    /// hwmon temperature sensor, by chip name and `tempN` or `tempN_label` text
+    Hwmon {
+        /// Chip name (contents of the hwmon `name` file)
+        chip: String,
+        /// Sensor: `temp1` style id or label (e.g. `Tctl`, `Package id 0`)
+        sensor: String,
+    },
+    /// Thermal zone, by directory name (`thermal_zone0`) or zone type (`x86_pkg_temp`)
+    ThermalZone {
+        /// Zone name or type
+        zone: String,
+    },
+    /// GPU temperature (hottest of junction/edge/hotspot)
+    Gpu {
+        /// GPU index as listed by `simon gpu status`
+        index: usize,
+    },
+    /// Raw file containing millidegrees Celsius
+    File {
+        /// File path
+        path: PathBuf,
+    },
+}
+
+impl std::fmt::Display for TempSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TempSource::Hwmon { chip, sensor } => write!(f, "{}/{}", chip, sensor),
+            TempSource::ThermalZone { zone } => write!(f, "thermal:{}", zone),
+            TempSource::Gpu { index } => write!(f, "gpu{}", index),
+            TempSource::File { path } => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A weighted temperature input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorInput {
+    /// Temperature source
+    #[serde(flatten)]
+    pub source: TempSource,
+    /// Weight for [`Combine::WeightedAverage`]
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+/// How multiple temperature sources are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Combine {
+    /// Hottest source wins
+    #[default]
+    Max,
+    /// Weighted average of available sources
+    WeightedAverage,
+}
+
+impl Combine {
+    /// Combine `(temperature, weight)` readings; `None` if there are none
+    pub fn apply(&self, readings: &[(f32, f32)]) -> Option<f32> {
+        if readings.is_empty() {
+            return None;
+        }
+        match self {
+            Combine::Max => readings.iter().map(|(t, _)| *t).reduce(f32::max),
+            Combine::WeightedAverage => {
+                let total_weight: f32 = readings.iter().map(|(_, w)| w.max(0.0)).sum();
+                if total_weight <= 0.0 {
+                    return None;
+                }
+                let sum: f32 = readings.iter().map(|(t, w)| t * w.max(0.0)).sum();
+                Some(sum / total_weight)
+            }
+        }
+    }
+}
+
+/// Binding of one PWM fan to its temperature sources and curve
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanBinding {
+    /// Display name
+    pub name: String,
+    /// hwmon chip name owning the PWM output
+    pub chip: String,
+    /// PWM channel number (`pwmN`)
+    pub pwm: u32,
+    /// Temperature sources
+    pub sources: Vec<SensorInput>,
+    /// Source combination
+    #[serde(default)]
+    pub combine: Combine,
+    /// Preset curve: quiet, performance or silent (ignored if `points` is set)
+    #[serde(default)]
+    pub curve: Option<String>,
+    /// Custom curve points
+    #[serde(default)]
+    pub points: Vec<FanCurvePoint>,
+    /// Curve points with RPM targets (requires a calibration profile for this fan)
+    #[serde(default)]
+    pub rpm_points: Vec<RpmCurvePoint>,
+    /// Hysteresis override (°C)
+    #[serde(default)]
+    pub hysteresis: Option<f32>,
+    /// Minimum duty while running (%)
+    #[serde(default)]
+    pub min_percent: f32,
+    /// Duty used to kick a stopped fan (%)
+    #[serde(default = "default_spinup_percent")]
+    pub spinup_percent: f32,
+    /// How long the spin-up kick is held (ms)
+    #[serde(default = "default_spinup_ms")]
+    pub spinup_ms: u64,
+    /// Maximum increase rate (%/s)
+    #[serde(default = "default_ramp_up")]
+    pub ramp_up_per_sec: f32,
+    /// Maximum decrease rate (%/s)
+    #[serde(default = "default_ramp_down")]
+    pub ramp_down_per_sec: f32,
+    /// Time a fan may look stalled before emergency full speed (ms)
+    #[serde(default = "default_stall_timeout_ms")]
+    pub stall_timeout_ms: u64,
+}
+
+fn default_spinup_percent() -> f32 {
+    50.0
+}
+
+fn default_spinup_ms() -> u64 {
+    2000
+}
+
+fn default_ramp_up() -> f32 {
+    30.0
+}
+
+fn default_ramp_down() -> f32 {
+    10.0
+}
+
+fn default_stall_timeout_ms() -> u64 {
+    5000
+}
+
+impl FanBinding {
+    /// Key of this fan in the [`FanCalibrationStore`] (`chip/pwmN`)
+    pub fn calibration_key(&self) -> String {
+        format!("{}/pwm{}", self.chip, self.pwm)
+    }
+
+    /// Resolve the fan curve for this binding
+    pub fn fan_curve(&self) -> Result<FanCurve> {
+        self.fan_curve_with(None)
+    }
+
+    /// Resolve the fan curve, converting RPM targets with the fan's calibration profile
+    pub fn fan_curve_with(&self, profile: Option<&FanCalibrationProfile>) -> Result<FanCurve> {
+        let mut curve = if !self.rpm_points.is_empty() {
+            let profile = profile.ok_or_else(|| {
+                SimonError::Configuration(format!(
+                    "Fan '{}' uses RPM targets but {} is not calibrated",
+                    self.name,
+                    self.calibration_key()
+                ))
+            })?;
+            FanCurve::from_rpm_targets(self.name.clone(), &self.rpm_points, profile, 3.0)
+        } else if !self.points.is_empty() {
+            let mut points = self.points.clone();
+            points.sort_by(|a, b| a.temp_celsius.total_cmp(&b.temp_celsius));
+            FanCurve {
+                name: self.name.clone(),
+                points,
+                hysteresis: 3.0,
+            }
+        } else {
+            match self
+                .curve
+                .as_deref()
+                .unwrap_or("quiet")
+                .to_lowercase()
+                .as_str()
+            {
+                "quiet" => FanCurve::quiet(),
+                "performance" => FanCurve::performance(),
+                "silent" => FanCurve::silent(),
+                other => {
+                    return Err(SimonError::Configuration(format!(
+                        "Fan '{}': unknown curve '{}'",
+                        self.name, other
+                    )))
+                }
+            }
+        };
+        if let Some(h) = self.hysteresis {
+            curve.hysteresis = h;
+        }
+        Ok(curve)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.sources.is_empty() {
+            return Err(SimonError::Configuration(format!(
+                "Fan '{}' has no temperature sources",
+                self.name
+            )));
+        }
+        for (field, value) in [
+            ("min_percent", self.min_percent),
+            ("spinup_percent", self.spinup_percent),
+        ] {
+            if !(0.0..=100.0).contains(&value) {
+                return Err(SimonError::Configuration(format!(
+                    "Fan '{}': {} must be 0-100, got {}",
+                    self.name, field, value
+                )));
+            }
+        }
+        if self.ramp_up_per_sec <= 0.0 || self.ramp_down_per_sec <= 0.0 {
+            return Err(SimonError::Configuration(format!(
+                "Fan '{}': ramp rates must be positive",
+                self.name
+            )));
+        }
+        // RPM curves are resolved once the calibration store is loaded
+        if self.rpm_points.is_empty() {
+            self.fan_curve()?;
+        }
+        Ok(())
+    }
+}
+
+/// Fan controller configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanControllerConfig {
+    /// Run the controller
+    #[serde(default)]
+    pub enabled: bool,
+    /// Control loop interval (ms)
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    /// hwmon class directory
+    #[serde(default = "default_hwmon_root")]
+    pub hwmon_root: PathBuf,
+    /// thermal class directory
+    #[serde(default = "default_thermal_root")]
+    pub thermal_root: PathBuf,
+    /// Fan calibration profiles (default: `~/.config/simon/fan_profiles.toml`)
+    #[serde(default)]
+    pub calibration_file: Option<PathBuf>,
+    /// Controlled fans
+    #[serde(default)]
+    pub fans: Vec<FanBinding>,
+}
+
+fn default_interval_ms() -> u64 {
+    1000
+}
+
+fn default_hwmon_root() -> PathBuf {
+    PathBuf::from("/sys/class/hwmon")
+}
+
+fn default_thermal_root() -> PathBuf {
+    PathBuf::from("/sys/class/thermal")
+}
+
+impl Default for FanControllerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: default_interval_ms(),
+            hwmon_root: default_hwmon_root(),
+            thermal_root: default_thermal_root(),
+            calibration_file: None,
+            fans: Vec::new(),
+        }
+    }
+}
+
+impl FanControllerConfig {
+    /// Parse from TOML string
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)
+            .map_err(|e| SimonError::Configuration(format!("TOML parse error: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Validate all fan bindings
+    pub fn validate(&self) -> Result<()> {
+        if self.interval_ms == 0 {
+            return Err(SimonError::Configuration(
+                "interval_ms must be positive".to_string(),
+            ));
+        }
+        for fan in &self.fans {
+            fan.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Source of temperature readings for the controller
+pub trait TemperatureProvider: Send {
+    /// Read a source in °C, `None` if unavailable
+    fn read(&mut self, source: &TempSource) -> Option<f32>;
+}
+
+/// Reads temperatures from sysfs (hwmon, thermal zones) and the GPU layer
+pub struct SysfsTemperatureProvider {
+    hwmon_root: PathBuf,
+    thermal_root: PathBuf,
+    /// Resolved `*_input` / `temp` file per source
+    resolved: HashMap<String, PathBuf>,
+    /// GPU devices, enumerated on first use
+    gpus: Option<Vec<Box<dyn crate::gpu::Device>>>,
+}
+
+impl SysfsTemperatureProvider {
+    /// Create a provider rooted at the given class directories
+    pub fn new(hwmon_root: impl Into<PathBuf>, thermal_root: impl Into<PathBuf>) -> Self {
+        Self {
+            hwmon_root: hwmon_root.into(),
+            thermal_root: thermal_root.into(),
+            resolved: HashMap::new(),
+            gpus: None,
+        }
+    }
+
+    fn resolve(&self, source: &TempSource) -> Option<PathBuf> {
+        match source {
+            TempSource::Hwmon { chip, sensor } => {
+                let dir = find_hwmon_chip(&self.hwmon_root, chip)?;
+                let by_id = dir.join(format!("{}_input", sensor));
+                if sensor.starts_with("temp") && by_id.exists() {
+                    return Some(by_id);
+                }
+                (1..=32).find_map(|i| {
+                    let label = fs::read_to_string(dir.join(format!("temp{}_label", i))).ok()?;
+                    (label.trim() == sensor).then(|| dir.join(format!("temp{}_input", i)))
+                })
+            }
+            TempSource::ThermalZone { zone } => {
+                let direct = self.thermal_root.join(zone).join("temp");
+                if direct.exists() {
+                    return Some(direct);
+                }
+                fs::read_dir(&self.thermal_root)
+                    .ok()?
+                    .flatten()
+                    .map(|e| e.path())
+                    .find(|p| {
+                        fs::read_to_string(p.join("type"))
+                            .map(|t| t.trim() == zone)
+                            .unwrap_or(false)
+                    })
+                    .map(|p| p.join("temp"))
+            }
+            TempSource::File { path } => Some(path.clone()),
+            TempSource::Gpu { .. } => None,
+        }
+    }
+
+    fn read_gpu(&mut self, index: usize) -> Option<f32> {
+        let gpus = self
+            .gpus
+            .get_or_insert_with(crate::gpu::control::enumerate_devices);
+        let temp = gpus.get(index)?.temperature().ok()?;
+        [temp.junction, temp.edge, temp.hotspot]
+            .into_iter()
+            .flatten()
+            .reduce(f32::max)
+    }
+}
+
+impl TemperatureProvider for SysfsTemperatureProvider {
+    fn read(&mut self, source: &TempSource) -> Option<f32> {
+        if let TempSource::Gpu { index } = source {
+            return self.read_gpu(*index);
+        }
+
+        let key = source.to_string();
+        let path = match self.resolved.get(&key) {
+            Some(p) => p.clone(),
+            None => {
+                let p = self.resolve(source)?;
+                self.resolved.insert(key.clone(), p.clone());
+                p
+            }
+        };
+
+        match read_millidegrees(&path) {
+            Some(t) => Some(t),
+            None => {
+                // hwmon devices can be renumbered (e.g. driver reload); re-resolve next time
+                self.resolved.remove(&key);
+                None
+            }
+        }
+    }
+}
+
+fn read_millidegrees(path: &Path) -> Option<f32> {
+    fs::read_to_string(path)
+        .ok()?
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .map(|t| t as f32 / 1000.0)
+}
+
+fn find_hwmon_chip(root: &Path, chip: &str) -> Option<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(root)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs.into_iter().find(|p| {
+        fs::read_to_string(p.join("name"))
+            .map(|n| n.trim() == chip)
+            .unwrap_or(false)
+    })
+}
+
+/// Control loop state of a fan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FanLoopState {
+    /// Following the curve
+    Normal,
+    /// Kicking a stopped fan
+    SpinUp,
+    /// Fan appears stalled; running at full speed
+    Stalled,
+    /// No temperature source readable; running at full speed
+    SensorFailure,
+}
+
+impl std::fmt::Display for FanLoopState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FanLoopState::Normal => write!(f, "normal"),
+            FanLoopState::SpinUp => write!(f, "spin-up"),
+            FanLoopState::Stalled => write!(f, "stalled"),
+            FanLoopState::SensorFailure => write!(f, "sensor failure"),
+        }
+    }
+}
+
+/// Per-fan control law: curve, hysteresis, ramp limits, spin-up and stall handling
+///
+/// Time is supplied by the caller as the elapsed `dt` per update, so the loop
+/// is deterministic and independent of the wall clock.
+#[derive(Debug, Clone)]
+pub struct FanLoop {
+    name: String,
+    curve: FanCurve,
+    min_percent: f32,
+    spinup_percent: f32,
+    spinup: Duration,
+    ramp_up_per_sec: f32,
+    ramp_down_per_sec: f32,
+    stall_timeout: Duration,
+    /// Curve output after hysteresis
+    target: Option<f32>,
+    /// Temperature at which `target` was last raised
+    anchor_temp: f32,
+    /// Duty currently written to the fan
+    output: f32,
+    spinup_left: Duration,
+    stalled_for: Duration,
+    state: FanLoopState,
+}
+
+impl FanLoop {
+    /// Create a control loop for a binding
+    pub fn new(binding: &FanBinding) -> Result<Self> {
+        Self::with_profile(binding, None)
+    }
+
+    /// Create a control loop using the fan's calibration profile
+    ///
+    /// The profile resolves RPM targets and raises the minimum and spin-up duty
+    /// to the fan's measured stop and start thresholds.
+    pub fn with_profile(
+        binding: &FanBinding,
+        profile: Option<&FanCalibrationProfile>,
+    ) -> Result<Self> {
+        let (stop_duty, start_duty) = profile
+            .map(|p| (p.stop_duty_percent, p.start_duty_percent))
+            .unwrap_or((0.0, 0.0));
+        Ok(Self {
+            name: binding.name.clone(),
+            curve: binding.fan_curve_with(profile)?,
+            min_percent: binding.min_percent.max(stop_duty),
+            spinup_percent: binding.spinup_percent.max(start_duty),
+            spinup: Duration::from_millis(binding.spinup_ms),
+            ramp_up_per_sec: binding.ramp_up_per_sec,
+            ramp_down_per_sec: binding.ramp_down_per_sec,
+            stall_timeout: Duration::from_millis(binding.stall_timeout_ms),
+            target: None,
+            anchor_temp: f32::MIN,
+            output: 0.0,
+            spinup_left: Duration::ZERO,
+            stalled_for: Duration::ZERO,
+            state: FanLoopState::Normal,
+        })
+    }
+
+    /// Seed the loop with the duty the fan is currently running at
+    pub fn with_initial_output(mut self, percent: f32) -> Self {
+        self.output = percent.clamp(0.0, 100.0);
+        self
+    }
+
+    /// Current loop state
+    pub fn state(&self) -> FanLoopState {
+        self.state
+    }
+
+    /// Duty currently commanded (%)
+    pub fn output(&self) -> f32 {
+        self.output
+    }
+
+    /// Curve target after hysteresis (%)
+    pub fn target(&self) -> Option<f32> {
+        self.target
+    }
+
+    /// Advance the loop by `dt` and return the duty to write (%)
+    pub fn update(&mut self, temp: Option<f32>, rpm: Option<u32>, dt: Duration) -> f32 {
+        let Some(temp) = temp else {
+            if self.state != FanLoopState::SensorFailure {
+                log::warn!(
+                    "Fan '{}': no temperature readings, forcing full speed",
+                    self.name
+                );
+            }
+            self.state = FanLoopState::SensorFailure;
+            self.output = 100.0;
+            return self.output;
+        };
+
+        // Stall detection uses the duty we have been commanding and the measured RPM
+        let mut info = FanInfo::new(self.name.clone());
+        info.speed_percent = self.output;
+        info.rpm = rpm;
+        if self.spinup_left.is_zero() && info.is_potentially_stalled() {
+            self.stalled_for += dt;
+        } else {
+            self.stalled_for = Duration::ZERO;
+        }
+        if self.stalled_for >= self.stall_timeout {
+            if self.state != FanLoopState::Stalled {
+                log::warn!(
+                    "Fan '{}' appears stalled (0 RPM at {:.0}%), forcing full speed",
+                    self.name,
+                    self.output
+                );
+            }
+            self.state = FanLoopState::Stalled;
+            self.output = 100.0;
+            return self.output;
+        }
+        if self.state == FanLoopState::Stalled && rpm == Some(0) {
+            self.output = 100.0;
+            return self.output;
+        }
+
+        // Hysteresis: rise immediately, fall only once the temperature drops enough
+        let wanted = self.curve.calculate_speed(temp);
+        let target = match self.target {
+            Some(current)
+                if wanted < current && temp > self.anchor_temp - self.curve.hysteresis =>
+            {
+                current
+            }
+            _ => {
+                if self.target.map_or(true, |current| wanted > current) {
+                    self.anchor_temp = temp;
+                } else {
+                    self.anchor_temp = self.anchor_temp.min(temp);
+                }
+                wanted
+            }
+        };
+        self.target = Some(target);
+
+        let desired = if target > 0.0 {
+            target.max(self.min_percent)
+        } else {
+            0.0
+        };
+
+        // Spin-up kick when starting a fan that was commanded off
+        if desired > 0.0
+            && self.output <= 0.0
+            && self.spinup_left.is_zero()
+            && !self.spinup.is_zero()
+        {
+            self.spinup_left = self.spinup;
+        }
+        if !self.spinup_left.is_zero() {
+            self.spinup_left = self.spinup_left.saturating_sub(dt);
+            if desired > 0.0 {
+                self.state = FanLoopState::SpinUp;
+                self.output = desired.max(self.spinup_percent);
+                return self.output;
+            }
+            self.spinup_left = Duration::ZERO;
+        }
+
+        // Ramp-rate limit toward the desired duty
+        let secs = dt.as_secs_f32();
+        let delta = desired - self.output;
+        let step = if delta > 0.0 {
+            delta.min(self.ramp_up_per_sec * secs)
+        } else {
+            delta.max(-self.ramp_down_per_sec * secs)
+        };
+        self.state = FanLoopState::Normal;
+        self.output = (self.output + step).clamp(0.0, 100.0);
+        self.output
+    }
+}
+
+/// A hwmon PWM output taken over by the controller
+///
+/// The original mode is restored on drop.
+#[derive(Debug)]
+pub struct PwmChannel {
+    dir: PathBuf,
+    index: u32,
+    original_enable: Option<u8>,
+    original_pwm: Option<u8>,
+    controlled: bool,
+}
+
+impl PwmChannel {
+    /// Locate `pwmN` on the named hwmon chip
+    pub fn open(hwmon_root: &Path, chip: &str, index: u32) -> Result<Self> {
+        let dir = find_hwmon_chip(hwmon_root, chip)
+            .ok_or_else(|| SimonError::DeviceNotFound(format!("hwmon chip '{}'", chip)))?;
+        if !dir.join(format!("pwm{}", index)).exists() {
+            return Err(SimonError::DeviceNotFound(format!("{}/pwm{}", chip, index)));
+        }
+        Ok(Self {
+            dir,
+            index,
+            original_enable: None,
+            original_pwm: None,
+            controlled: false,
+        })
+    }
+
+    fn file(&self, suffix: &str) -> PathBuf {
+        self.dir.join(format!("pwm{}{}", self.index, suffix))
+    }
+
+    fn read_u8(path: &Path) -> Option<u8> {
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    fn write(path: &Path, value: u8) -> Result<()> {
+        fs::write(path, value.to_string()).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::PermissionDenied {
+                SimonError::PermissionDenied(format!("{} (need root?)", path.display()))
+            } else {
+                SimonError::Io(e)
+            }
+        })
+    }
+
+    /// Current duty (%)
+    pub fn duty_percent(&self) -> Option<f32> {
+        Self::read_u8(&self.file("")).map(|p| p as f32 / 255.0 * 100.0)
+    }
+
+    /// Measured fan speed
+    pub fn rpm(&self) -> Option<u32> {
+        fs::read_to_string(self.dir.join(format!("fan{}_input", self.index)))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// Save the current mode and switch to manual PWM control
+    pub fn take_control(&mut self) -> Result<()> {
+        if self.controlled {
+            return Ok(());
+        }
+        self.original_enable = Self::read_u8(&self.file("_enable"));
+        self.original_pwm = Self::read_u8(&self.file(""));
+        if self.original_enable.is_some() {
+            Self::write(&self.file("_enable"), 1)?;
+        }
+        self.controlled = true;
+        Ok(())
+    }
+
+    /// Write a duty cycle (%)
+    pub fn set_percent(&self, percent: f32) -> Result<()> {
+        let pwm = (percent.clamp(0.0, 100.0) / 100.0 * 255.0).round() as u8;
+        Self::write(&self.file(""), pwm)
+    }
+
+    /// Return the channel to the mode it had before [`take_control`](Self::take_control)
+    ///
+    /// Falls back to automatic mode (`pwmN_enable = 2`) if the original mode
+    /// was manual, so the fan is never left pinned at the last duty.
+    pub fn restore(&mut self) -> Result<()> {
+        if !self.controlled {
+            return Ok(());
+        }
+        self.controlled = false;
+        if let Some(pwm) = self.original_pwm {
+            let _ = Self::write(&self.file(""), pwm);
+        }
+        match self.original_enable {
+            Some(1) | None if self.file("_enable").exists() => {
+                Self::write(&self.file("_enable"), 2)
+            }
+            Some(mode) => Self::write(&self.file("_enable"), mode),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for PwmChannel {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+impl FanActuator for PwmChannel {
+    fn set_duty_percent(&mut self, percent: f32) -> Result<()> {
+        self.set_percent(percent)
+    }
+
+    fn rpm(&self) -> Option<u32> {
+        PwmChannel::rpm(self)
+    }
+}
+
+/// Calibrate a hwmon PWM fan and return its profile
+///
+/// Takes manual control of `chip/pwmN` for the duration of the run (roughly
+/// `settle` per step) and restores its original mode afterwards, even on failure.
+pub fn calibrate(
+    hwmon_root: &Path,
+    chip: &str,
+    pwm: u32,
+    settings: &CalibrationSettings,
+) -> Result<FanCalibrationProfile> {
+    let mut channel = PwmChannel::open(hwmon_root, chip, pwm)?;
+    channel.take_control()?;
+    let result = calibrate_fan(
+        &format!("{}/pwm{}", chip, pwm),
+        &mut channel,
+        settings,
+        std::thread::sleep,
+    );
+    channel.restore()?;
+    result
+}
+
+/// Status of one controlled fan after a control step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanStatus {
+    /// Binding name
+    pub name: String,
+    /// Combined input temperature (°C)
+    pub temp_celsius: Option<f32>,
+    /// Curve target after hysteresis (%)
+    pub target_percent: Option<f32>,
+    /// Duty written (%)
+    pub output_percent: f32,
+    /// Measured RPM
+    pub rpm: Option<u32>,
+    /// Loop state
+    pub state: FanLoopState,
+}
+
+struct ControlledFan {
+    binding: FanBinding,
+    channel: PwmChannel,
+    control: FanLoop,
+}
+
+/// Closed-loop fan controller service
+pub struct FanController {
+    config: FanControllerConfig,
+    fans: Vec<ControlledFan>,
+    provider: Box<dyn TemperatureProvider>,
+}
+
+impl FanController {
+    /// Create a controller reading temperatures from sysfs and the GPU layer
+    pub fn new(config: FanControllerConfig) -> Result<Self> {
+        let provider =
+            SysfsTemperatureProvider::new(config.hwmon_root.clone(), config.thermal_root.clone());
+        Self::with_provider(config, Box::new(provider))
+    }
+
+    /// Create a controller with a custom temperature provider
+    ///
+    /// Takes manual control of every configured PWM output. If any fan cannot
+    /// be taken over, fans already taken are restored and the error is returned.
+    pub fn with_provider(
+        config: FanControllerConfig,
+        provider: Box<dyn TemperatureProvider>,
+    ) -> Result<Self> {
+        config.validate()?;
+
+        let profiles = if config.fans.iter().any(|f| !f.rpm_points.is_empty()) {
+            match &config.calibration_file {
+                Some(path) => FanCalibrationStore::load_from(path)?,
+                None => FanCalibrationStore::load()?,
+            }
+        } else {
+            FanCalibrationStore::default()
+        };
+
+        let mut fans: Vec<ControlledFan> = Vec::new();
+        for binding in &config.fans {
+            let result = PwmChannel::open(&config.hwmon_root, &binding.chip, binding.pwm).and_then(
+                |mut channel| {
+                    let profile = profiles.get(&binding.calibration_key());
+                    let control = FanLoop::with_profile(binding, profile)?
+                        .with_initial_output(channel.duty_percent().unwrap_or(0.0));
+                    channel.take_control()?;
+                    Ok(ControlledFan {
+                        binding: binding.clone(),
+                        channel,
+                        control,
+                    })
+                },
+            );
+            match result {
+                Ok(fan) => fans.push(fan),
+                Err(e) => {
+                    for fan in &mut fans {
+                        let _ = fan.channel.restore();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(Self {
+            config,
+            fans,
+            provider,
+        })
+    }
+
+    /// Control loop interval
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.config.interval_ms)
+    }
+
+    /// Run one control step for every fan
+    pub fn tick(&mut self, dt: Duration) -> Vec<FanStatus> {
+        let mut statuses = Vec::with_capacity(self.fans.len());
+        for fan in &mut self.fans {
+            let readings: Vec<(f32, f32)> = fan
+                .binding
+                .sources
+                .iter()
+                .filter_map(|input| self.provider.read(&input.source).map(|t| (t, input.weight)))
+                .collect();
+            let temp = fan.binding.combine.apply(&readings);
+            let rpm = fan.channel.rpm();
+            let output = fan.control.update(temp, rpm, dt);
+
+            if let Err(e) = fan.channel.set_percent(output) {
+                log::warn!("Fan '{}': failed to write PWM: {}", fan.binding.name, e);
+            }
+
+            statuses.push(FanStatus {
+                name: fan.binding.name.clone(),
+                temp_celsius: temp,
+                target_percent: fan.control.target(),
+                output_percent: output,
+                rpm,
+                state: fan.control.state(),
+            });
+        }
+        statuses
+    }
+
+    /// Return every fan to its original control mode
+    pub fn restore(&mut self) -> Result<()> {
+        let mut first_error = None;
+        for fan in &mut self.fans {
+            if let Err(e) = fan.channel.restore() {
+                log::warn!("Fan '{}': failed to restore mode: {}", fan.binding.name, e);
+                first_error.get_or_insert(e);
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Run the control loop until `stop` is set, then restore all fans
+    pub fn run(mut self, stop: Arc<AtomicBool>) {
+        let interval = self.interval();
+        while !stop.load(Ordering::SeqCst) {
+            self.tick(interval);
+            std::thread::sleep(interval);
+        }
+        let _ = self.restore();
+    }
+
+    /// Run the control loop on a background thread
+    ///
+    /// If the thread cannot be started the controller is dropped, which hands
+    /// the fans back to their original control mode.
+    pub fn spawn(self) -> Result<FanControllerHandle> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let thread = std::thread::Builder::new()
+            .name("simon-fan-controller".into())
+            .spawn(move || self.run(flag))
+            .map_err(|e| {
+                SimonError::Other(format!("Failed to spawn fan controller thread: {}", e))
+            })?;
+        Ok(FanControllerHandle {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for FanController {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+/// Handle to a running fan controller thread
+///
+/// Dropping the handle stops the controller and restores all fans.
+pub struct FanControllerHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FanControllerHandle {
+    /// Stop the controller and wait for fans to be restored
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    /// Check if the controller thread is still running
+    pub fn is_running(&self) -> bool {
+        self.thread.as_ref().is_some_and(|t| !t.is_finished())
+    }
+}
+
+impl Drop for FanControllerHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    /// hwmon and thermal trees with one PWM fan, a CPU sensor and an ACPI zone
+    fn fake_sysfs(tag: &str) -> FakeRoot {
+        let fake = FakeRoot::new(&format!("fan-controller-{}", tag));
+        for (rel, value) in [
+            ("hwmon/hwmon0/name", "fakechip\n"),
+            ("hwmon/hwmon0/pwm1", "128\n"),
+            ("hwmon/hwmon0/pwm1_enable", "2\n"),
+            ("hwmon/hwmon0/fan1_input", "1200\n"),
+            ("hwmon/hwmon1/name", "k10temp\n"),
+            ("hwmon/hwmon1/temp1_label", "Tctl\n"),
+            ("hwmon/hwmon1/temp1_input", "40000\n"),
+            ("thermal/thermal_zone0/type", "acpitz\n"),
+            ("thermal/thermal_zone0/temp", "35000\n"),
+        ] {
+            fake.write(rel, value);
+        }
+        fake
+    }
+
+    fn controller_config(fake: &FakeRoot, binding: FanBinding) -> FanControllerConfig {
+        FanControllerConfig {
+            enabled: true,
+            interval_ms: 1000,
+            hwmon_root: fake.join("hwmon"),
+            thermal_root: fake.join("thermal"),
+            calibration_file: Some(fake.join("fan_profiles.toml")),
+            fans: vec![binding],
+        }
+    }
+
+    fn binding(sources: Vec<TempSource>) -> FanBinding {
+        FanBinding {
+            name: "cpu".into(),
+            chip: "fakechip".into(),
+            pwm: 1,
+            sources: sources
+                .into_iter()
+                .map(|source| SensorInput {
+                    source,
+                    weight: 1.0,
+                })
+                .collect(),
+            combine: Combine::Max,
+            curve: None,
+            points: vec![
+                FanCurvePoint {
+                    temp_celsius: 40.0,
+                    speed_percent: 20.0,
+                },
+                FanCurvePoint {
+                    temp_celsius: 80.0,
+                    speed_percent: 100.0,
+                },
+            ],
+            rpm_points: Vec::new(),
+            hysteresis: Some(5.0),
+            min_percent: 0.0,
+            spinup_percent: 50.0,
+            spinup_ms: 0,
+            ramp_up_per_sec: 1000.0,
+            ramp_down_per_sec: 1000.0,
+            stall_timeout_ms: 3000,
+        }
+    }
+
+    #[test]
+    fn test_combine() {
+        let readings = [(40.0, 1.0), (60.0, 3.0)];
+        assert_eq!(Combine::Max.apply(&readings), Some(60.0));
+        assert_eq!(Combine::WeightedAverage.apply(&readings), Some(55.0));
+        assert_eq!(Combine::Max.apply(&[]), None);
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let mut fan = FanLoop::new(&binding(vec![])).unwrap();
+        let dt = Duration::from_secs(1);
+        assert_eq!(fan.update(Some(60.0), Some(1000), dt), 60.0);
+        // Within hysteresis band: hold speed
+        assert_eq!(fan.update(Some(57.0), Some(1000), dt), 60.0);
+        // Dropped by more than hysteresis: follow the curve down
+        assert_eq!(fan.update(Some(54.0), Some(1000), dt), 48.0);
+        // Rising again is immediate
+        assert_eq!(fan.update(Some(70.0), Some(1000), dt), 80.0);
+    }
+
+    #[test]
+    fn test_ramp_rate_limit() {
+        let mut b = binding(vec![]);
+        b.ramp_up_per_sec = 10.0;
+        b.ramp_down_per_sec = 5.0;
+        let mut fan = FanLoop::new(&b).unwrap().with_initial_output(30.0);
+        let dt = Duration::from_millis(500);
+        assert_eq!(fan.update(Some(80.0), Some(1000), dt), 35.0);
+        assert_eq!(fan.update(Some(80.0), Some(1000), dt), 40.0);
+        let mut fan = FanLoop::new(&b).unwrap().with_initial_output(90.0);
+        assert_eq!(fan.update(Some(40.0), Some(1000), dt), 87.5);
+    }
+
+    #[test]
+    fn test_spinup_and_min_percent() {
+        let mut b = binding(vec![]);
+        b.min_percent = 25.0;
+        b.spinup_ms = 2000;
+        let mut fan = FanLoop::new(&b).unwrap();
+        let dt = Duration::from_secs(1);
+        // Stopped fan gets kicked to spin-up duty
+        assert_eq!(fan.update(Some(40.0), Some(0), dt), 50.0);
+        assert_eq!(fan.state(), FanLoopState::SpinUp);
+        assert_eq!(fan.update(Some(40.0), Some(800), dt), 50.0);
+        // Then settles at the minimum duty
+        assert_eq!(fan.update(Some(40.0), Some(600), dt), 25.0);
+        assert_eq!(fan.state(), FanLoopState::Normal);
+    }
+
+    #[test]
+    fn test_stall_forces_full_speed() {
+        let mut fan = FanLoop::new(&binding(vec![])).unwrap();
+        let dt = Duration::from_secs(1);
+        assert_eq!(fan.update(Some(60.0), Some(1000), dt), 60.0);
+        fan.update(Some(60.0), Some(0), dt);
+        fan.update(Some(60.0), Some(0), dt);
+        assert_eq!(fan.update(Some(60.0), Some(0), dt), 100.0);
+        assert_eq!(fan.state(), FanLoopState::Stalled);
+        // Recovers once the fan reports RPM again
+        fan.update(Some(60.0), Some(900), dt);
+        assert_eq!(fan.state(), FanLoopState::Normal);
+    }
+
+    #[test]
+    fn test_sensor_failure_forces_full_speed() {
+        let mut fan = FanLoop::new(&binding(vec![])).unwrap();
+        assert_eq!(fan.update(None, Some(1000), Duration::from_secs(1)), 100.0);
+        assert_eq!(fan.state(), FanLoopState::SensorFailure);
+    }
+
+    #[test]
+    fn test_config_from_toml() {
+        let config = FanControllerConfig::from_toml(
+            r#"
+enabled = true
+interval_ms = 500
+
+[[fans]]
+name = "cpu"
+chip = "nct6798"
+pwm = 2
+combine = "weighted_average"
+curve = "performance"
+
+[[fans.sources]]
+type = "hwmon"
+chip = "k10temp"
+sensor = "Tctl"
+
+[[fans.sources]]
+type = "gpu"
+index = 0
+weight = 0.5
+"#,
+        )
+        .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.fans[0].combine, Combine::WeightedAverage);
+        assert_eq!(config.fans[0].sources.len(), 2);
+        assert_eq!(config.fans[0].sources[1].weight, 0.5);
+        assert_eq!(config.fans[0].fan_curve().unwrap().name, "Performance");
+
+        let bad = "[[fans]]\nname = \"x\"\nchip = \"c\"\npwm = 1\nsources = []\n";
+        assert!(FanControllerConfig::from_toml(bad).is_err());
+    }
+
+    #[test]
+    fn test_sysfs_provider_resolves_sources() {
+        let fake = fake_sysfs("provider");
+        let mut provider = SysfsTemperatureProvider::new(fake.join("hwmon"), fake.join("thermal"));
+        let tctl = TempSource::Hwmon {
+            chip: "k10temp".into(),
+            sensor: "Tctl".into(),
+        };
+        let temp1 = TempSource::Hwmon {
+            chip: "k10temp".into(),
+            sensor: "temp1".into(),
+        };
+        let zone = TempSource::ThermalZone {
+            zone: "acpitz".into(),
+        };
+        assert_eq!(provider.read(&tctl), Some(40.0));
+        assert_eq!(provider.read(&temp1), Some(40.0));
+        assert_eq!(provider.read(&zone), Some(35.0));
+        assert_eq!(
+            provider.read(&TempSource::ThermalZone {
+                zone: "missing".into()
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_controller_drives_and_restores_fake_hwmon() {
+        let fake = fake_sysfs("controller");
+        let config = controller_config(
+            &fake,
+            binding(vec![TempSource::Hwmon {
+                chip: "k10temp".into(),
+                sensor: "Tctl".into(),
+            }]),
+        );
+        let provider = SysfsTemperatureProvider::new(fake.join("hwmon"), fake.join("thermal"));
+
+        let mut controller = FanController::with_provider(config, Box::new(provider)).unwrap();
+        assert_eq!(fake.read("hwmon/hwmon0/pwm1_enable"), "1");
+
+        fake.write("hwmon/hwmon1/temp1_input", "60000");
+        let status = controller.tick(Duration::from_secs(1));
+        assert_eq!(status[0].temp_celsius, Some(60.0));
+        assert_eq!(status[0].output_percent, 60.0);
+        assert_eq!(fake.read("hwmon/hwmon0/pwm1"), "153");
+
+        // Stalled fan goes to emergency full speed
+        fake.write("hwmon/hwmon0/fan1_input", "0");
+        for _ in 0..3 {
+            controller.tick(Duration::from_secs(1));
+        }
+        assert_eq!(fake.read("hwmon/hwmon0/pwm1"), "255");
+
+        drop(controller);
+        assert_eq!(fake.read("hwmon/hwmon0/pwm1_enable"), "2");
+        assert_eq!(fake.read("hwmon/hwmon0/pwm1"), "128");
+    }
+
+    #[test]
+    fn test_controller_rpm_targets_use_calibration() {
+        use crate::fan_control::CalibrationPoint;
+
+        let fake = fake_sysfs("rpm-targets");
+        let mut b = binding(vec![TempSource::Hwmon {
+            chip: "k10temp".into(),
+            sensor: "temp1".into(),
+        }]);
+        b.points.clear();
+        b.rpm_points = vec![
+            RpmCurvePoint {
+                temp_celsius: 40.0,
+                rpm: 750,
+            },
+            RpmCurvePoint {
+                temp_celsius: 80.0,
+                rpm: 1800,
+            },
+        ];
+        let config = controller_config(&fake, b.clone());
+
+        // Without a profile the RPM curve cannot be resolved
+        assert!(FanController::new(config.clone()).is_err());
+
+        let mut store = FanCalibrationStore::default();
+        store.insert(FanCalibrationProfile {
+            fan: b.calibration_key(),
+            points: (2..=10)
+                .map(|i| CalibrationPoint {
+                    duty_percent: i as f32 * 10.0,
+                    rpm: 300 + i * 150,
+                })
+                .collect(),
+            start_duty_percent: 30.0,
+            stop_duty_percent: 20.0,
+            max_rpm: 1800,
+            calibrated_at: 0,
+        });
+        store.save_to(&fake.join("fan_profiles.toml")).unwrap();
+
+        let mut controller = FanController::new(config).unwrap();
+        fake.write("hwmon/hwmon1/temp1_input", "60000");
+        let status = controller.tick(Duration::from_secs(1));
+        assert!((status[0].output_percent - 65.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_controller_missing_chip() {
+        let fake = fake_sysfs("missing");
+        let mut b = binding(vec![TempSource::ThermalZone {
+            zone: "thermal_zone0".into(),
+        }]);
+        b.chip = "nonexistent".into();
+        assert!(FanController::new(controller_config(&fake, b)).is_err());
+    }
+}
@@ -4,8 +4,8 @@
 //! This module provides AMD GPU monitoring through sysfs on Linux.
 
 use super::traits::{
-    Clocks, Device, Error, FanSpeed, GpuProcess, Memory, PciInfo, Power, Temperature,
-    TemperatureThresholds, Utilization, Vendor,
+    ClockConstraints, ClockRange, Clocks, Device, Error, FanSpeed, GpuProcess, Memory, PciInfo,
+    Power, Temperature, TemperatureThresholds, Utilization, Vendor,
 };
 use std::fs;
 use std::path::PathBuf;
@@ -98,6 +98,25 @@ impl AmdGpu {
         }
     }
 
+    /// Parse every DPM level frequency (MHz) listed in a pp_dpm file
+    fn read_clock_levels(&self, file: &str) -> Vec<u32> {
+        let Some(content) = self.read_sysfs_string(file) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .filter_map(|line| {
+                let freq = line.split(':').nth(1)?;
+                freq.replace("Mhz", "")
+                    .replace("MHz", "")
+                    .replace('*', "")
+                    .trim()
+                    .parse::<u32>()
+                    .ok()
+            })
+            .collect()
+    }
+
     fn read_current_clock(&self, file: &str) -> Option<u32> {
         // Parse pp_dpm files which have format like:
         // 0: 500Mhz
@@ -437,6 +456,84 @@ impl Device for AmdGpu {
 
         Ok(())
     }
+
+    fn clock_constraints(&self) -> Result<ClockConstraints, Error> {
+        let range = |levels: Vec<u32>| match (levels.iter().min(), levels.iter().max()) {
+            (Some(&min), Some(&max)) => Some(ClockRange::new(min, max)),
+            _ => None,
+        };
+
+        Ok(ClockConstraints {
+            graphics: range(self.read_clock_levels("pp_dpm_sclk")),
+            memory: range(self.read_clock_levels("pp_dpm_mclk")),
+        })
+    }
+
+    fn lock_memory_clocks(&mut self, min_mhz: u32, max_mhz: u32) -> Result<(), Error> {
+        let od_path = self.device_path.join("pp_od_clk_voltage");
+        if !od_path.exists() {
+            return Err(Error::NotSupported);
+        }
+
+        fs::write(
+            self.device_path.join("power_dpm_force_performance_level"),
+            "manual",
+        )
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::PermissionDenied {
+                Error::PermissionDenied("Setting memory clocks requires root".to_string())
+            } else {
+                Error::ControlFailed(format!("Failed to set performance level: {}", e))
+            }
+        })?;
+
+        // Format: "m 0 <min_mhz>" and "m 1 <max_mhz>" then "c" to commit
+        fs::write(&od_path, format!("m 0 {}", min_mhz))
+            .map_err(|e| Error::ControlFailed(format!("Failed to set min memory clock: {}", e)))?;
+        fs::write(&od_path, format!("m 1 {}", max_mhz))
+            .map_err(|e| Error::ControlFailed(format!("Failed to set max memory clock: {}", e)))?;
+        fs::write(&od_path, "c")
+            .map_err(|e| Error::ControlFailed(format!("Failed to commit clocks: {}", e)))
+    }
+
+    fn reset_memory_clocks(&mut self) -> Result<(), Error> {
+        // pp_od_clk_voltage resets graphics and memory together
+        self.reset_gpu_clocks()
+    }
+
+    fn set_fan_speed(&mut self, percent: u32) -> Result<(), Error> {
+        let hwmon = self.hwmon_dir.as_ref().ok_or(Error::NotSupported)?;
+        if percent > 100 {
+            return Err(Error::InvalidArgument(format!(
+                "Fan speed {}% outside range [0%, 100%]",
+                percent
+            )));
+        }
+
+        // pwm1_enable: 1 = manual, 2 = automatic
+        fs::write(hwmon.join("pwm1_enable"), "1").map_err(|e| {
+            if e.kind() == std::io::ErrorKind::PermissionDenied {
+                Error::PermissionDenied("Setting fan speed requires root".to_string())
+            } else {
+                Error::ControlFailed(format!("Failed to enable manual fan control: {}", e))
+            }
+        })?;
+
+        let pwm = percent * 255 / 100;
+        fs::write(hwmon.join("pwm1"), pwm.to_string())
+            .map_err(|e| Error::ControlFailed(format!("Failed to set fan speed: {}", e)))
+    }
+
+    fn reset_fan_control(&mut self) -> Result<(), Error> {
+        let hwmon = self.hwmon_dir.as_ref().ok_or(Error::NotSupported)?;
+        fs::write(hwmon.join("pwm1_enable"), "2").map_err(|e| {
+            if e.kind() == std::io::ErrorKind::PermissionDenied {
+                Error::PermissionDenied("Resetting fan control requires root".to_string())
+            } else {
+                Error::ControlFailed(format!("Failed to restore automatic fan control: {}", e))
+            }
+        })
+    }
 }
 
 pub fn enumerate() -> Result<Vec<Box<dyn Device>>, Error> {
@@ -0,0 +1,1004 @@
+// SPDX-License-Identifier: AGPL-3.0-or-later
+//! Unified GPU control API
+//!
+//! [`GpuControl`] wraps a [`Device`] and applies a [`GpuControlRequest`] (power limit,
+//! clock locks, fan override, persistence and compute mode) as a validated set of
+//! [`ControlAction`]s. Every request is checked against the device's reported
+//! constraints before anything is written, can be run as a dry-run, and records the
+//! inverse of each applied action so the device can be returned to its previous state
+//! explicitly with [`GpuControl::revert`] or automatically when the controller is dropped.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::gpu::control::{self, GpuControl, GpuControlRequest};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let device = control::enumerate_devices().into_iter().next().ok_or("no GPU")?;
+//! let mut ctl = GpuControl::new(device).revert_on_drop(true);
+//!
+//! let request = GpuControlRequest::new().power_limit(250.0).lock_gpu_clocks(1200, 1800);
+//! let report = ctl.apply(&request)?;
+//! for action in &report.actions {
+//!     println!("{}", action);
+//! }
+//! // Settings are reverted when `ctl` goes out of scope
+//! # Ok(())
+//! # }
+//! ```
+
+use super::traits::{ClockConstraints, ClockRange, ComputeMode, Device, Error, FanSpeed, Vendor};
+use crate::observability::permissions::{ApiKey, Capability, Permission, PermissionChecker, Scope};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Requested changes to a GPU's control settings
+///
+/// Unset fields are left untouched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpuControlRequest {
+    /// Power limit in Watts
+    pub power_limit_watts: Option<f32>,
+    /// Graphics clock lock range
+    pub gpu_clocks: Option<ClockRange>,
+    /// Memory clock lock range
+    pub memory_clocks: Option<ClockRange>,
+    /// Fan speed override (0-100%)
+    pub fan_speed_percent: Option<u32>,
+    /// Persistence mode (NVIDIA)
+    pub persistence_mode: Option<bool>,
+    /// Compute mode (NVIDIA)
+    pub compute_mode: Option<ComputeMode>,
+    /// Release any graphics clock lock
+    #[serde(default)]
+    pub reset_gpu_clocks: bool,
+    /// Release any memory clock lock
+    #[serde(default)]
+    pub reset_memory_clocks: bool,
+    /// Return the fan to automatic control
+    #[serde(default)]
+    pub reset_fan: bool,
+}
+
+impl GpuControlRequest {
+    /// Create an empty request
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the power limit (Watts)
+    pub fn power_limit(mut self, watts: f32) -> Self {
+        self.power_limit_watts = Some(watts);
+        self
+    }
+
+    /// Lock graphics clocks to a range (MHz)
+    pub fn lock_gpu_clocks(mut self, min_mhz: u32, max_mhz: u32) -> Self {
+        self.gpu_clocks = Some(ClockRange::new(min_mhz, max_mhz));
+        self
+    }
+
+    /// Lock memory clocks to a range (MHz)
+    pub fn lock_memory_clocks(mut self, min_mhz: u32, max_mhz: u32) -> Self {
+        self.memory_clocks = Some(ClockRange::new(min_mhz, max_mhz));
+        self
+    }
+
+    /// Override fan speed (percent)
+    pub fn fan_speed(mut self, percent: u32) -> Self {
+        self.fan_speed_percent = Some(percent);
+        self
+    }
+
+    /// Enable or disable persistence mode
+    pub fn persistence_mode(mut self, enabled: bool) -> Self {
+        self.persistence_mode = Some(enabled);
+        self
+    }
+
+    /// Set compute mode
+    pub fn compute_mode(mut self, mode: ComputeMode) -> Self {
+        self.compute_mode = Some(mode);
+        self
+    }
+
+    /// Release graphics clock lock
+    pub fn reset_gpu_clocks(mut self) -> Self {
+        self.reset_gpu_clocks = true;
+        self
+    }
+
+    /// Release memory clock lock
+    pub fn reset_memory_clocks(mut self) -> Self {
+        self.reset_memory_clocks = true;
+        self
+    }
+
+    /// Return fan to automatic control
+    pub fn reset_fan(mut self) -> Self {
+        self.reset_fan = true;
+        self
+    }
+
+    /// Check if the request changes nothing
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Capabilities an API key must hold (with write scope) to submit this request
+    pub fn required_capabilities(&self) -> Vec<Capability> {
+        let mut caps = Vec::new();
+        if self.power_limit_watts.is_some()
+            || self.gpu_clocks.is_some()
+            || self.memory_clocks.is_some()
+            || self.persistence_mode.is_some()
+            || self.compute_mode.is_some()
+            || self.reset_gpu_clocks
+            || self.reset_memory_clocks
+        {
+            caps.push(Capability::GpuControl);
+        }
+        if self.fan_speed_percent.is_some() || self.reset_fan {
+            caps.push(Capability::FanControl);
+        }
+        caps
+    }
+}
+
+/// A single control operation against a device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ControlAction {
+    /// Change the power limit
+    SetPowerLimit {
+        /// Previous limit in Watts (if known)
+        from: Option<f32>,
+        /// New limit in Watts
+        to: f32,
+    },
+    /// Lock graphics clocks
+    LockGpuClocks {
+        /// Locked range
+        range: ClockRange,
+    },
+    /// Release graphics clock lock
+    ResetGpuClocks,
+    /// Lock memory clocks
+    LockMemoryClocks {
+        /// Locked range
+        range: ClockRange,
+    },
+    /// Release memory clock lock
+    ResetMemoryClocks,
+    /// Override fan speed
+    SetFanSpeed {
+        /// Fan speed in percent
+        percent: u32,
+    },
+    /// Return fan to automatic control
+    ResetFanControl,
+    /// Change persistence mode
+    SetPersistenceMode {
+        /// Previous mode (if known)
+        from: Option<bool>,
+        /// New mode
+        to: bool,
+    },
+    /// Change compute mode
+    SetComputeMode {
+        /// Previous mode (if known)
+        from: Option<ComputeMode>,
+        /// New mode
+        to: ComputeMode,
+    },
+}
+
+impl ControlAction {
+    /// Execute this action against a device
+    pub fn execute(&self, device: &mut dyn Device) -> Result<(), Error> {
+        match self {
+            ControlAction::SetPowerLimit { to, .. } => device.set_power_limit(*to),
+            ControlAction::LockGpuClocks { range } => {
+                device.lock_gpu_clocks(range.min_mhz, range.max_mhz)
+            }
+            ControlAction::ResetGpuClocks => device.reset_gpu_clocks(),
+            ControlAction::LockMemoryClocks { range } => {
+                device.lock_memory_clocks(range.min_mhz, range.max_mhz)
+            }
+            ControlAction::ResetMemoryClocks => device.reset_memory_clocks(),
+            ControlAction::SetFanSpeed { percent } => device.set_fan_speed(*percent),
+            ControlAction::ResetFanControl => device.reset_fan_control(),
+            ControlAction::SetPersistenceMode { to, .. } => device.set_persistence_mode(*to),
+            ControlAction::SetComputeMode { to, .. } => device.set_compute_mode(*to),
+        }
+    }
+
+    /// The action that undoes this one, if the previous state is known
+    ///
+    /// Clock locks and fan overrides revert to driver defaults; releasing a lock has no
+    /// inverse because the previous lock range cannot be queried.
+    pub fn inverse(&self) -> Option<ControlAction> {
+        match self {
+            ControlAction::SetPowerLimit { from, to } => {
+                from.map(|prev| ControlAction::SetPowerLimit {
+                    from: Some(*to),
+                    to: prev,
+                })
+            }
+            ControlAction::LockGpuClocks { .. } => Some(ControlAction::ResetGpuClocks),
+            ControlAction::LockMemoryClocks { .. } => Some(ControlAction::ResetMemoryClocks),
+            ControlAction::SetFanSpeed { .. } => Some(ControlAction::ResetFanControl),
+            ControlAction::SetPersistenceMode { from, to } => {
+                from.map(|prev| ControlAction::SetPersistenceMode {
+                    from: Some(*to),
+                    to: prev,
+                })
+            }
+            ControlAction::SetComputeMode { from, to } => {
+                from.map(|prev| ControlAction::SetComputeMode {
+                    from: Some(*to),
+                    to: prev,
+                })
+            }
+            ControlAction::ResetGpuClocks
+            | ControlAction::ResetMemoryClocks
+            | ControlAction::ResetFanControl => None,
+        }
+    }
+}
+
+impl fmt::Display for ControlAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlAction::SetPowerLimit {
+                from: Some(from),
+                to,
+            } => {
+                write!(f, "power limit {:.1}W -> {:.1}W", from, to)
+            }
+            ControlAction::SetPowerLimit { from: None, to } => {
+                write!(f, "power limit -> {:.1}W", to)
+            }
+            ControlAction::LockGpuClocks { range } => write!(f, "lock graphics clocks {}", range),
+            ControlAction::ResetGpuClocks => write!(f, "reset graphics clocks"),
+            ControlAction::LockMemoryClocks { range } => {
+                write!(f, "lock memory clocks {}", range)
+            }
+            ControlAction::ResetMemoryClocks => write!(f, "reset memory clocks"),
+            ControlAction::SetFanSpeed { percent } => write!(f, "fan speed -> {}%", percent),
+            ControlAction::ResetFanControl => write!(f, "fan control -> automatic"),
+            ControlAction::SetPersistenceMode { from, to } => match from {
+                Some(from) => write!(f, "persistence mode {} -> {}", from, to),
+                None => write!(f, "persistence mode -> {}", to),
+            },
+            ControlAction::SetComputeMode { from, to } => match from {
+                Some(from) => write!(f, "compute mode {} -> {}", from, to),
+                None => write!(f, "compute mode -> {}", to),
+            },
+        }
+    }
+}
+
+/// Snapshot of a device's controllable settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ControlState {
+    /// Current power limit in Watts
+    pub power_limit_watts: Option<f32>,
+    /// Default power limit in Watts
+    pub default_power_limit_watts: Option<f32>,
+    /// Minimum allowed power limit in Watts
+    pub min_power_limit_watts: Option<f32>,
+    /// Maximum allowed power limit in Watts
+    pub max_power_limit_watts: Option<f32>,
+    /// Current graphics clock in MHz
+    pub graphics_clock_mhz: Option<u32>,
+    /// Current memory clock in MHz
+    pub memory_clock_mhz: Option<u32>,
+    /// Supported clock ranges
+    pub clock_constraints: ClockConstraints,
+    /// Current fan speed
+    pub fan_speed: Option<FanSpeed>,
+    /// Persistence mode (NVIDIA)
+    pub persistence_mode: Option<bool>,
+    /// Compute mode (NVIDIA)
+    pub compute_mode: Option<ComputeMode>,
+}
+
+impl ControlState {
+    /// Read the current control state from a device
+    ///
+    /// Individual queries that fail are recorded as `None`.
+    pub fn capture(device: &dyn Device) -> Self {
+        let power = device.power().ok();
+        let clocks = device.clocks().ok();
+        // Backends report 0 for limits they cannot read
+        let nonzero = |v: f32| if v > 0.0 { Some(v) } else { None };
+
+        Self {
+            power_limit_watts: power.as_ref().and_then(|p| nonzero(p.limit)),
+            default_power_limit_watts: power.as_ref().and_then(|p| nonzero(p.default_limit)),
+            min_power_limit_watts: power.as_ref().and_then(|p| nonzero(p.min_limit)),
+            max_power_limit_watts: power.as_ref().and_then(|p| nonzero(p.max_limit)),
+            graphics_clock_mhz: clocks.as_ref().map(|c| c.graphics).filter(|&c| c > 0),
+            memory_clock_mhz: clocks.as_ref().map(|c| c.memory).filter(|&c| c > 0),
+            clock_constraints: device.clock_constraints().unwrap_or_default(),
+            fan_speed: device.fan_speed().ok().flatten(),
+            persistence_mode: device.persistence_mode().ok().flatten(),
+            compute_mode: device.compute_mode().ok().flatten(),
+        }
+    }
+}
+
+/// Result of applying (or dry-running) a control request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlReport {
+    /// Device index
+    pub gpu_index: u32,
+    /// Device vendor
+    pub vendor: Vendor,
+    /// Whether this was a dry-run (nothing written)
+    pub dry_run: bool,
+    /// Actions planned or applied, in order
+    pub actions: Vec<ControlAction>,
+    /// State before the request
+    pub before: ControlState,
+    /// State after the request (None for dry-runs)
+    pub after: Option<ControlState>,
+}
+
+/// Validate a request against a device's current state and constraints
+///
+/// Returns the ordered list of actions that would be executed.
+pub fn plan(
+    request: &GpuControlRequest,
+    state: &ControlState,
+) -> Result<Vec<ControlAction>, Error> {
+    let mut actions = Vec::new();
+
+    if let Some(watts) = request.power_limit_watts {
+        if !watts.is_finite() || watts <= 0.0 {
+            return Err(Error::InvalidArgument(format!(
+                "Power limit must be positive, got {}W",
+                watts
+            )));
+        }
+        if let (Some(min), Some(max)) = (state.min_power_limit_watts, state.max_power_limit_watts) {
+            if watts < min || watts > max {
+                return Err(Error::InvalidArgument(format!(
+                    "Power limit {:.1}W outside range [{:.1}W, {:.1}W]",
+                    watts, min, max
+                )));
+            }
+        }
+        actions.push(ControlAction::SetPowerLimit {
+            from: state.power_limit_watts,
+            to: watts,
+        });
+    }
+
+    if request.gpu_clocks.is_some() && request.reset_gpu_clocks {
+        return Err(Error::InvalidArgument(
+            "Cannot lock and reset graphics clocks in the same request".to_string(),
+        ));
+    }
+    if let Some(range) = request.gpu_clocks {
+        validate_clock_range("Graphics", &range, state.clock_constraints.graphics)?;
+        actions.push(ControlAction::LockGpuClocks { range });
+    }
+    if request.reset_gpu_clocks {
+        actions.push(ControlAction::ResetGpuClocks);
+    }
+
+    if request.memory_clocks.is_some() && request.reset_memory_clocks {
+        return Err(Error::InvalidArgument(
+            "Cannot lock and reset memory clocks in the same request".to_string(),
+        ));
+    }
+    if let Some(range) = request.memory_clocks {
+        validate_clock_range("Memory", &range, state.clock_constraints.memory)?;
+        actions.push(ControlAction::LockMemoryClocks { range });
+    }
+    if request.reset_memory_clocks {
+        actions.push(ControlAction::ResetMemoryClocks);
+    }
+
+    if request.fan_speed_percent.is_some() && request.reset_fan {
+        return Err(Error::InvalidArgument(
+            "Cannot override and reset fan control in the same request".to_string(),
+        ));
+    }
+    if let Some(percent) = request.fan_speed_percent {
+        if percent > 100 {
+            return Err(Error::InvalidArgument(format!(
+                "Fan speed {}% outside range [0%, 100%]",
+                percent
+            )));
+        }
+        actions.push(ControlAction::SetFanSpeed { percent });
+    }
+    if request.reset_fan {
+        actions.push(ControlAction::ResetFanControl);
+    }
+
+    if let Some(enabled) = request.persistence_mode {
+        if state.persistence_mode != Some(enabled) {
+            actions.push(ControlAction::SetPersistenceMode {
+                from: state.persistence_mode,
+                to: enabled,
+            });
+        }
+    }
+
+    if let Some(mode) = request.compute_mode {
+        if state.compute_mode != Some(mode) {
+            actions.push(ControlAction::SetComputeMode {
+                from: state.compute_mode,
+                to: mode,
+            });
+        }
+    }
+
+    Ok(actions)
+}
+
+fn validate_clock_range(
+    domain: &str,
+    range: &ClockRange,
+    supported: Option<ClockRange>,
+) -> Result<(), Error> {
+    if range.min_mhz > range.max_mhz {
+        return Err(Error::InvalidArgument(format!(
+            "{} clock minimum {} MHz exceeds maximum {} MHz",
+            domain, range.min_mhz, range.max_mhz
+        )));
+    }
+    if let Some(supported) = supported {
+        if !supported.contains(range) {
+            return Err(Error::InvalidArgument(format!(
+                "{} clocks {} outside supported range {}",
+                domain, range, supported
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Check that an API key may submit a control request
+///
+/// Each capability returned by [`GpuControlRequest::required_capabilities`] must be
+/// granted with write scope.
+pub fn authorize(
+    checker: &mut PermissionChecker,
+    api_key: &str,
+    request: &GpuControlRequest,
+) -> Result<(), Error> {
+    for capability in request.required_capabilities() {
+        checker
+            .check(api_key, capability, &Scope::Write)
+            .map_err(|e| Error::PermissionDenied(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// API key under which [`local_operator`] grants control to the command line user
+pub const LOCAL_OPERATOR_KEY: &str = "local";
+
+/// Permission checker for requests made by the local user
+///
+/// Root is granted GPU and fan control with write scope, other users only read
+/// access, so their requests are refused before any device is touched. Platforms
+/// without Unix credentials grant control and leave enforcement to the driver.
+pub fn local_operator() -> PermissionChecker {
+    #[cfg(unix)]
+    // SAFETY: geteuid has no preconditions
+    let privileged = unsafe { libc::geteuid() } == 0;
+    #[cfg(not(unix))]
+    let privileged = true;
+
+    let key = if privileged {
+        ApiKey::new(
+            "local operator",
+            LOCAL_OPERATOR_KEY,
+            vec![
+                Permission::full(Capability::GpuControl),
+                Permission::full(Capability::FanControl),
+            ],
+        )
+    } else {
+        ApiKey::read_only("local user", LOCAL_OPERATOR_KEY)
+    };
+    PermissionChecker::new(vec![key])
+}
+
+/// Validated, revertible controller for a single GPU
+pub struct GpuControl {
+    device: Box<dyn Device>,
+    dry_run: bool,
+    revert_on_drop: bool,
+    /// Checker and API key every applied request is authorized against
+    permissions: Option<(Arc<RwLock<PermissionChecker>>, String)>,
+    /// Inverse actions for everything applied so far, in application order
+    journal: Vec<ControlAction>,
+}
+
+impl GpuControl {
+    /// Create a controller for a device
+    pub fn new(device: Box<dyn Device>) -> Self {
+        Self {
+            device,
+            dry_run: false,
+            revert_on_drop: false,
+            permissions: None,
+            journal: Vec::new(),
+        }
+    }
+
+    /// Authorize every applied request for `api_key` with [`authorize`]
+    ///
+    /// Without this the caller is trusted. Dry runs write nothing and are not checked.
+    pub fn with_permissions(
+        mut self,
+        checker: Arc<RwLock<PermissionChecker>>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        self.permissions = Some((checker, api_key.into()));
+        self
+    }
+
+    /// Only validate and report actions, never write to the device
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Revert all applied changes when the controller is dropped
+    pub fn revert_on_drop(mut self, enabled: bool) -> Self {
+        self.revert_on_drop = enabled;
+        self
+    }
+
+    /// The controlled device
+    pub fn device(&self) -> &dyn Device {
+        self.device.as_ref()
+    }
+
+    /// Current control state of the device
+    pub fn state(&self) -> ControlState {
+        ControlState::capture(self.device.as_ref())
+    }
+
+    /// Validate a request without applying it
+    pub fn plan(&self, request: &GpuControlRequest) -> Result<Vec<ControlAction>, Error> {
+        plan(request, &self.state())
+    }
+
+    /// Validate and apply a request
+    ///
+    /// If any action fails, the actions already applied by this call are rolled back
+    /// before the error is returned.
+    pub fn apply(&mut self, request: &GpuControlRequest) -> Result<ControlReport, Error> {
+        if let (false, Some((checker, api_key))) = (self.dry_run, &self.permissions) {
+            let mut checker = checker
+                .write()
+                .map_err(|_| Error::PermissionDenied("permission checker poisoned".into()))?;
+            authorize(&mut checker, api_key, request)?;
+        }
+
+        let before = self.state();
+        let actions = plan(request, &before)?;
+
+        if self.dry_run {
+            return Ok(self.report(actions, before, None));
+        }
+
+        let mut inverses = Vec::new();
+        for action in &actions {
+            if let Err(e) = action.execute(self.device.as_mut()) {
+                for inverse in inverses.iter().rev() {
+                    let _ = ControlAction::execute(inverse, self.device.as_mut());
+                }
+                return Err(e);
+            }
+            if let Some(inverse) = action.inverse() {
+                inverses.push(inverse);
+            }
+        }
+        self.journal.extend(inverses);
+
+        let after = self.state();
+        Ok(self.report(actions, before, Some(after)))
+    }
+
+    /// Check if there are applied changes that can be reverted
+    pub fn has_pending_revert(&self) -> bool {
+        !self.journal.is_empty()
+    }
+
+    /// Undo every change applied through this controller, newest first
+    ///
+    /// All inverse actions are attempted; the first error (if any) is returned.
+    pub fn revert(&mut self) -> Result<Vec<ControlAction>, Error> {
+        let mut reverted = Vec::new();
+        let mut first_error = None;
+
+        while let Some(action) = self.journal.pop() {
+            match action.execute(self.device.as_mut()) {
+                Ok(()) => reverted.push(action),
+                Err(e) => {
+                    if first_error.is_none() {
+                        first_error = Some(e);
+                    }
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(reverted),
+        }
+    }
+
+    fn report(
+        &self,
+        actions: Vec<ControlAction>,
+        before: ControlState,
+        after: Option<ControlState>,
+    ) -> ControlReport {
+        ControlReport {
+            gpu_index: self.device.index(),
+            vendor: self.device.vendor(),
+            dry_run: self.dry_run,
+            actions,
+            before,
+            after,
+        }
+    }
+}
+
+impl Drop for GpuControl {
+    fn drop(&mut self) {
+        if self.revert_on_drop && self.has_pending_revert() {
+            if let Err(e) = self.revert() {
+                log::warn!(
+                    "Failed to revert GPU {} settings: {}",
+                    self.device.index(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Enumerate all GPUs that support the [`Device`] control interface
+///
+/// Devices are returned in a stable order (NVIDIA, AMD, Intel); the position in the
+/// returned list is the index accepted by `simon gpu set --gpu`.
+pub fn enumerate_devices() -> Vec<Box<dyn Device>> {
+    #[allow(unused_mut)]
+    let mut devices: Vec<Box<dyn Device>> = Vec::new();
+
+    #[cfg(feature = "nvidia")]
+    if let Ok(gpus) = super::nvidia_new::enumerate() {
+        devices.extend(gpus.into_iter().map(|g| Box::new(g) as Box<dyn Device>));
+    }
+
+    #[cfg(feature = "amd")]
+    if let Ok(gpus) = super::amd_rocm::enumerate() {
+        devices.extend(gpus);
+    }
+
+    #[cfg(feature = "intel")]
+    if let Ok(gpus) = super::intel_levelzero::enumerate() {
+        devices.extend(gpus);
+    }
+
+    devices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::traits::{
+        Clocks, GpuProcess, Memory, PciInfo, Power, Temperature, Utilization,
+    };
+    use std::sync::Mutex;
+
+    /// Settings written to the mock device
+    #[derive(Debug, Default)]
+    struct MockSettings {
+        power_limit: f32,
+        gpu_lock: Option<(u32, u32)>,
+        fan_override: Option<u32>,
+        persistence: bool,
+        fail_fan: bool,
+    }
+
+    struct MockDevice {
+        settings: Arc<Mutex<MockSettings>>,
+    }
+
+    impl MockDevice {
+        fn new() -> (Self, Arc<Mutex<MockSettings>>) {
+            let settings = Arc::new(Mutex::new(MockSettings {
+                power_limit: 300.0,
+                ..Default::default()
+            }));
+            (
+                Self {
+                    settings: Arc::clone(&settings),
+                },
+                settings,
+            )
+        }
+    }
+
+    impl Device for MockDevice {
+        fn vendor(&self) -> Vendor {
+            Vendor::Nvidia
+        }
+        fn index(&self) -> u32 {
+            0
+        }
+        fn name(&self) -> Result<String, Error> {
+            Ok("Mock GPU".to_string())
+        }
+        fn uuid(&self) -> Result<String, Error> {
+            Err(Error::NotSupported)
+        }
+        fn pci_info(&self) -> Result<PciInfo, Error> {
+            Err(Error::NotSupported)
+        }
+        fn driver_version(&self) -> Result<String, Error> {
+            Err(Error::NotSupported)
+        }
+        fn temperature(&self) -> Result<Temperature, Error> {
+            Err(Error::NotSupported)
+        }
+        fn power(&self) -> Result<Power, Error> {
+            let limit = self.settings.lock().unwrap().power_limit;
+            Ok(Power {
+                current: 100.0,
+                average: None,
+                limit,
+                default_limit: 300.0,
+                min_limit: 100.0,
+                max_limit: 350.0,
+                enforced_limit: limit,
+            })
+        }
+        fn clocks(&self) -> Result<Clocks, Error> {
+            Ok(Clocks {
+                graphics: 1500,
+                memory: 9000,
+                sm: None,
+                video: None,
+            })
+        }
+        fn utilization(&self) -> Result<Utilization, Error> {
+            Err(Error::NotSupported)
+        }
+        fn memory(&self) -> Result<Memory, Error> {
+            Err(Error::NotSupported)
+        }
+        fn fan_speed(&self) -> Result<Option<FanSpeed>, Error> {
+            Ok(self
+                .settings
+                .lock()
+                .unwrap()
+                .fan_override
+                .map(FanSpeed::Percent))
+        }
+        fn performance_state(&self) -> Result<Option<String>, Error> {
+            Ok(None)
+        }
+        fn processes(&self) -> Result<Vec<Box<dyn GpuProcess>>, Error> {
+            Ok(Vec::new())
+        }
+        fn persistence_mode(&self) -> Result<Option<bool>, Error> {
+            Ok(Some(self.settings.lock().unwrap().persistence))
+        }
+        fn set_power_limit(&mut self, watts: f32) -> Result<(), Error> {
+            self.settings.lock().unwrap().power_limit = watts;
+            Ok(())
+        }
+        fn lock_gpu_clocks(&mut self, min_mhz: u32, max_mhz: u32) -> Result<(), Error> {
+            self.settings.lock().unwrap().gpu_lock = Some((min_mhz, max_mhz));
+            Ok(())
+        }
+        fn reset_gpu_clocks(&mut self) -> Result<(), Error> {
+            self.settings.lock().unwrap().gpu_lock = None;
+            Ok(())
+        }
+        fn set_persistence_mode(&mut self, enabled: bool) -> Result<(), Error> {
+            self.settings.lock().unwrap().persistence = enabled;
+            Ok(())
+        }
+        fn clock_constraints(&self) -> Result<ClockConstraints, Error> {
+            Ok(ClockConstraints {
+                graphics: Some(ClockRange::new(300, 2100)),
+                memory: None,
+            })
+        }
+        fn set_fan_speed(&mut self, percent: u32) -> Result<(), Error> {
+            let mut settings = self.settings.lock().unwrap();
+            if settings.fail_fan {
+                return Err(Error::ControlFailed("fan stuck".to_string()));
+            }
+            settings.fan_override = Some(percent);
+            Ok(())
+        }
+        fn reset_fan_control(&mut self) -> Result<(), Error> {
+            self.settings.lock().unwrap().fan_override = None;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_plan_rejects_power_limit_out_of_range() {
+        let (device, _) = MockDevice::new();
+        let ctl = GpuControl::new(Box::new(device));
+        let err = ctl.plan(&GpuControlRequest::new().power_limit(400.0));
+        assert!(matches!(err, Err(Error::InvalidArgument(_))));
+        assert!(ctl
+            .plan(&GpuControlRequest::new().power_limit(250.0))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_plan_rejects_unsupported_clock_range() {
+        let (device, _) = MockDevice::new();
+        let ctl = GpuControl::new(Box::new(device));
+        assert!(ctl
+            .plan(&GpuControlRequest::new().lock_gpu_clocks(1200, 2500))
+            .is_err());
+        assert!(ctl
+            .plan(&GpuControlRequest::new().lock_gpu_clocks(1800, 1200))
+            .is_err());
+        assert!(ctl
+            .plan(
+                &GpuControlRequest::new()
+                    .lock_gpu_clocks(1200, 1800)
+                    .reset_gpu_clocks()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_plan_skips_unchanged_persistence_mode() {
+        let (device, _) = MockDevice::new();
+        let ctl = GpuControl::new(Box::new(device));
+        let actions = ctl
+            .plan(&GpuControlRequest::new().persistence_mode(false))
+            .unwrap();
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn test_dry_run_does_not_write() {
+        let (device, settings) = MockDevice::new();
+        let mut ctl = GpuControl::new(Box::new(device)).dry_run(true);
+        let report = ctl
+            .apply(&GpuControlRequest::new().power_limit(250.0).fan_speed(80))
+            .unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.actions.len(), 2);
+        assert!(report.after.is_none());
+        assert_eq!(settings.lock().unwrap().power_limit, 300.0);
+        assert!(!ctl.has_pending_revert());
+    }
+
+    #[test]
+    fn test_apply_and_revert() {
+        let (device, settings) = MockDevice::new();
+        let mut ctl = GpuControl::new(Box::new(device));
+        let report = ctl
+            .apply(
+                &GpuControlRequest::new()
+                    .power_limit(250.0)
+                    .lock_gpu_clocks(1200, 1800)
+                    .persistence_mode(true),
+            )
+            .unwrap();
+
+        assert_eq!(report.before.power_limit_watts, Some(300.0));
+        assert_eq!(report.after.unwrap().power_limit_watts, Some(250.0));
+        assert_eq!(settings.lock().unwrap().gpu_lock, Some((1200, 1800)));
+
+        let reverted = ctl.revert().unwrap();
+        assert_eq!(reverted.len(), 3);
+        let settings = settings.lock().unwrap();
+        assert_eq!(settings.power_limit, 300.0);
+        assert_eq!(settings.gpu_lock, None);
+        assert!(!settings.persistence);
+    }
+
+    #[test]
+    fn test_failed_action_rolls_back_batch() {
+        let (device, settings) = MockDevice::new();
+        settings.lock().unwrap().fail_fan = true;
+        let mut ctl = GpuControl::new(Box::new(device));
+
+        let result = ctl.apply(&GpuControlRequest::new().power_limit(200.0).fan_speed(90));
+        assert!(matches!(result, Err(Error::ControlFailed(_))));
+        assert_eq!(settings.lock().unwrap().power_limit, 300.0);
+        assert!(!ctl.has_pending_revert());
+    }
+
+    #[test]
+    fn test_revert_on_drop() {
+        let (device, settings) = MockDevice::new();
+        {
+            let mut ctl = GpuControl::new(Box::new(device)).revert_on_drop(true);
+            ctl.apply(&GpuControlRequest::new().fan_speed(100)).unwrap();
+            assert_eq!(settings.lock().unwrap().fan_override, Some(100));
+        }
+        assert_eq!(settings.lock().unwrap().fan_override, None);
+    }
+
+    #[test]
+    fn test_required_capabilities() {
+        assert!(GpuControlRequest::new().required_capabilities().is_empty());
+        assert_eq!(
+            GpuControlRequest::new()
+                .power_limit(200.0)
+                .required_capabilities(),
+            vec![Capability::GpuControl]
+        );
+        assert_eq!(
+            GpuControlRequest::new()
+                .lock_gpu_clocks(1000, 1500)
+                .fan_speed(50)
+                .required_capabilities(),
+            vec![Capability::GpuControl, Capability::FanControl]
+        );
+    }
+
+    #[test]
+    fn test_authorize_requires_write_scope() {
+        let mut checker = PermissionChecker::new(vec![
+            ApiKey::read_only("reader", "sk-read"),
+            ApiKey::new(
+                "tuner",
+                "sk-tune",
+                vec![Permission::parse("gpu_control:write").unwrap()],
+            ),
+        ]);
+        let request = GpuControlRequest::new().power_limit(200.0);
+
+        assert!(authorize(&mut checker, "sk-read", &request).is_err());
+        assert!(authorize(&mut checker, "sk-tune", &request).is_ok());
+        assert!(authorize(&mut checker, "sk-tune", &request.clone().fan_speed(50)).is_err());
+    }
+
+    #[test]
+    fn test_unauthorized_apply_is_refused() {
+        let checker = Arc::new(RwLock::new(PermissionChecker::new(vec![
+            ApiKey::read_only("reader", "sk-read"),
+            ApiKey::admin("admin", "sk-admin"),
+        ])));
+        let request = GpuControlRequest::new().power_limit(250.0).fan_speed(80);
+
+        let (device, settings) = MockDevice::new();
+        let mut ctl =
+            GpuControl::new(Box::new(device)).with_permissions(Arc::clone(&checker), "sk-read");
+        let result = ctl.apply(&request);
+        assert!(matches!(result, Err(Error::PermissionDenied(_))));
+        assert_eq!(settings.lock().unwrap().power_limit, 300.0);
+        assert_eq!(settings.lock().unwrap().fan_override, None);
+        assert!(!ctl.has_pending_revert());
+
+        // Dry runs write nothing and need no grant
+        let (device, _) = MockDevice::new();
+        let mut ctl = GpuControl::new(Box::new(device))
+            .dry_run(true)
+            .with_permissions(Arc::clone(&checker), "sk-read");
+        assert!(ctl.apply(&request).is_ok());
+
+        let (device, settings) = MockDevice::new();
+        let mut ctl = GpuControl::new(Box::new(device)).with_permissions(checker, "sk-admin");
+        ctl.apply(&request).unwrap();
+        assert_eq!(settings.lock().unwrap().power_limit, 250.0);
+    }
+}
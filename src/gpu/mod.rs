@@ -108,11 +108,14 @@ pub mod traits;
 
 // Re-export key types from traits (with GpuProcess renamed to avoid conflict with legacy)
 pub use traits::{
-    Clocks, ComputeMode, Device, EccErrors, Error as GpuError, FanSpeed,
-    GpuProcess as GpuProcessTrait, LinkState, Memory, MigMode, NvLinkStatus, PciInfo, Power,
-    ProcessType, Temperature, TemperatureStatus, TemperatureThresholds, Utilization, Vendor,
+    ClockConstraints, ClockRange, Clocks, ComputeMode, Device, EccErrors, Error as GpuError,
+    FanSpeed, GpuProcess as GpuProcessTrait, LinkState, Memory, MigMode, NvLinkStatus, PciInfo,
+    Power, ProcessType, Temperature, TemperatureStatus, TemperatureThresholds, Utilization, Vendor,
 };
 
+// Validated, revertible control (power limits, clock locks, fan override)
+pub mod control;
+
 // New vendor implementations
 #[cfg(feature = "nvidia")]
 pub mod nvidia_new;
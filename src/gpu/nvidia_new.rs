@@ -315,8 +315,7 @@ impl Device for NvidiaGpu {
     }
 
     fn persistence_mode(&self) -> Result<Option<bool>, Error> {
-        // persistence_mode API not available in nvml-wrapper 0.10
-        Ok(None)
+        Ok(self.device.is_in_persistent_mode().ok())
     }
 
     // === Control Functions ===
@@ -325,24 +324,30 @@ impl Device for NvidiaGpu {
         let milliwatts = (watts * 1000.0) as u32;
         self.device
             .set_power_management_limit(milliwatts)
-            .map_err(|e| Error::ControlFailed(format!("Failed to set power limit: {}", e)))
+            .map_err(|e| control_error("set power limit", e))
     }
 
     fn lock_gpu_clocks(&mut self, min_mhz: u32, max_mhz: u32) -> Result<(), Error> {
+        use nvml_wrapper::enums::device::GpuLockedClocksSetting;
+
         self.device
-            .set_applications_clocks(max_mhz, min_mhz) // memory, graphics
-            .map_err(|e| Error::ControlFailed(format!("Failed to lock clocks: {}", e)))
+            .set_gpu_locked_clocks(GpuLockedClocksSetting::Numeric {
+                min_clock_mhz: min_mhz,
+                max_clock_mhz: max_mhz,
+            })
+            .map_err(|e| control_error("lock clocks", e))
     }
 
     fn reset_gpu_clocks(&mut self) -> Result<(), Error> {
         self.device
-            .reset_applications_clocks()
-            .map_err(|e| Error::ControlFailed(format!("Failed to reset clocks: {}", e)))
+            .reset_gpu_locked_clocks()
+            .map_err(|e| control_error("reset clocks", e))
     }
 
-    fn set_persistence_mode(&mut self, _enabled: bool) -> Result<(), Error> {
-        // set_persistence_mode API not available in nvml-wrapper 0.10
-        Err(Error::NotSupported)
+    fn set_persistence_mode(&mut self, enabled: bool) -> Result<(), Error> {
+        self.device
+            .set_persistent(enabled)
+            .map_err(|e| control_error("set persistence mode", e))
     }
 
     fn set_compute_mode(&mut self, mode: ComputeMode) -> Result<(), Error> {
@@ -357,7 +362,58 @@ impl Device for NvidiaGpu {
 
         self.device
             .set_compute_mode(nvml_mode)
-            .map_err(|e| Error::ControlFailed(format!("Failed to set compute mode: {}", e)))
+            .map_err(|e| control_error("set compute mode", e))
+    }
+
+    fn clock_constraints(&self) -> Result<ClockConstraints, Error> {
+        let memory_clocks = self.device.supported_memory_clocks().unwrap_or_default();
+        let memory = match (memory_clocks.iter().min(), memory_clocks.iter().max()) {
+            (Some(&min), Some(&max)) => Some(ClockRange::new(min, max)),
+            _ => None,
+        };
+
+        // Graphics clocks are reported per memory clock; take the widest span
+        let graphics_clocks: Vec<u32> = memory_clocks
+            .iter()
+            .filter_map(|&mem| self.device.supported_graphics_clocks(mem).ok())
+            .flatten()
+            .collect();
+        let graphics = match (graphics_clocks.iter().min(), graphics_clocks.iter().max()) {
+            (Some(&min), Some(&max)) => Some(ClockRange::new(min, max)),
+            _ => self
+                .device
+                .max_clock_info(Clock::Graphics)
+                .ok()
+                .map(|max| ClockRange::new(0, max)),
+        };
+
+        Ok(ClockConstraints { graphics, memory })
+    }
+
+    fn lock_memory_clocks(&mut self, min_mhz: u32, max_mhz: u32) -> Result<(), Error> {
+        self.device
+            .set_mem_locked_clocks(min_mhz, max_mhz)
+            .map_err(|e| control_error("lock memory clocks", e))
+    }
+
+    fn reset_memory_clocks(&mut self) -> Result<(), Error> {
+        self.device
+            .reset_mem_locked_clocks()
+            .map_err(|e| control_error("reset memory clocks", e))
+    }
+}
+
+/// Map an NVML control failure to a GPU error, preserving permission failures
+#[cfg(feature = "nvidia")]
+fn control_error(action: &str, e: nvml_wrapper::error::NvmlError) -> Error {
+    use nvml_wrapper::error::NvmlError;
+
+    match e {
+        NvmlError::NoPermission => {
+            Error::PermissionDenied(format!("Cannot {} (requires root/admin)", action))
+        }
+        NvmlError::NotSupported => Error::NotSupported,
+        other => Error::ControlFailed(format!("Failed to {}: {}", action, other)),
     }
 }
 
@@ -130,6 +130,31 @@ pub trait Device: Send + Sync {
     fn set_compute_mode(&mut self, _mode: ComputeMode) -> Result<(), Error> {
         Err(Error::NotSupported)
     }
+
+    /// Get the supported graphics and memory clock ranges (MHz)
+    fn clock_constraints(&self) -> Result<ClockConstraints, Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Lock memory clocks to specified frequency range (MHz)
+    fn lock_memory_clocks(&mut self, _min_mhz: u32, _max_mhz: u32) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Reset memory clocks to default
+    fn reset_memory_clocks(&mut self) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Override fan speed (percentage of maximum)
+    fn set_fan_speed(&mut self, _percent: u32) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Return fan control to the driver/firmware automatic mode
+    fn reset_fan_control(&mut self) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
 }
 
 /// GPU Process trait - information about a process using GPU
@@ -365,6 +390,66 @@ pub struct Clocks {
     pub video: Option<u32>,
 }
 
+/// A frequency range in MHz
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockRange {
+    /// Lower bound in MHz
+    pub min_mhz: u32,
+    /// Upper bound in MHz
+    pub max_mhz: u32,
+}
+
+impl ClockRange {
+    /// Create a new clock range
+    pub fn new(min_mhz: u32, max_mhz: u32) -> Self {
+        Self { min_mhz, max_mhz }
+    }
+
+    /// Check whether `other` lies entirely within this range
+    pub fn contains(&self, other: &ClockRange) -> bool {
+        other.min_mhz >= self.min_mhz && other.max_mhz <= self.max_mhz
+    }
+}
+
+impl fmt::Display for ClockRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} MHz", self.min_mhz, self.max_mhz)
+    }
+}
+
+impl std::str::FromStr for ClockRange {
+    type Err = String;
+
+    /// Parse "MIN-MAX" or "MIN,MAX" (MHz), or a single value to pin both bounds
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |v: &str| {
+            v.trim()
+                .trim_end_matches("MHz")
+                .trim_end_matches("mhz")
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid clock value: {}", v))
+        };
+
+        match s.split_once(['-', ',']) {
+            Some((min, max)) => Ok(Self::new(parse(min)?, parse(max)?)),
+            None => {
+                let value = parse(s)?;
+                Ok(Self::new(value, value))
+            }
+        }
+    }
+}
+
+/// Supported clock ranges reported by the device
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClockConstraints {
+    /// Supported graphics clock range
+    pub graphics: Option<ClockRange>,
+    /// Supported memory clock range
+    pub memory: Option<ClockRange>,
+}
+
 /// Utilization percentages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Utilization {
@@ -441,6 +526,31 @@ pub enum ComputeMode {
     ExclusiveProcess,
 }
 
+impl fmt::Display for ComputeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeMode::Default => write!(f, "default"),
+            ComputeMode::ExclusiveThread => write!(f, "exclusive_thread"),
+            ComputeMode::Prohibited => write!(f, "prohibited"),
+            ComputeMode::ExclusiveProcess => write!(f, "exclusive_process"),
+        }
+    }
+}
+
+impl std::str::FromStr for ComputeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "default" | "shared" => Ok(ComputeMode::Default),
+            "exclusive_thread" => Ok(ComputeMode::ExclusiveThread),
+            "prohibited" => Ok(ComputeMode::Prohibited),
+            "exclusive_process" | "exclusive" => Ok(ComputeMode::ExclusiveProcess),
+            _ => Err(format!("Unknown compute mode: {}", s)),
+        }
+    }
+}
+
 /// NVLink interconnect status (NVIDIA).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NvLinkStatus {
@@ -801,6 +911,37 @@ mod tests {
         assert_eq!(Vendor::Apple.to_string(), "Apple");
     }
 
+    // === ClockRange tests ===
+
+    #[test]
+    fn test_clock_range_parse() {
+        assert_eq!("1200-1800".parse::<ClockRange>(), Ok(ClockRange::new(1200, 1800)));
+        assert_eq!("1200,1800".parse::<ClockRange>(), Ok(ClockRange::new(1200, 1800)));
+        assert_eq!("1500MHz".parse::<ClockRange>(), Ok(ClockRange::new(1500, 1500)));
+        assert!("fast".parse::<ClockRange>().is_err());
+    }
+
+    #[test]
+    fn test_clock_range_contains() {
+        let supported = ClockRange::new(300, 2100);
+        assert!(supported.contains(&ClockRange::new(1200, 1800)));
+        assert!(!supported.contains(&ClockRange::new(200, 1800)));
+        assert!(!supported.contains(&ClockRange::new(1200, 2500)));
+    }
+
+    #[test]
+    fn test_compute_mode_roundtrip() {
+        for mode in [
+            ComputeMode::Default,
+            ComputeMode::ExclusiveThread,
+            ComputeMode::Prohibited,
+            ComputeMode::ExclusiveProcess,
+        ] {
+            assert_eq!(mode.to_string().parse::<ComputeMode>(), Ok(mode));
+        }
+        assert_eq!("exclusive-process".parse::<ComputeMode>(), Ok(ComputeMode::ExclusiveProcess));
+    }
+
     // === TemperatureStatus tests ===
 
     #[test]
@@ -15,6 +15,7 @@ use crate::ai_api::AiDataApi;
 use crate::connections::{ConnectionInfo, ConnectionMonitor, ConnectionState, Protocol};
 use crate::core::cpu::CpuStats;
 use crate::core::memory::MemoryStats;
+use crate::cpu_topology::{CoreMetrics, TopologySampler};
 #[cfg(target_os = "windows")]
 use crate::platform::windows as platform_impl;
 use crate::disk::{self, DiskDevice};
@@ -101,6 +102,9 @@ pub struct SiliconMonitorApp {
     // Historical data for graphs
     cpu_history: VecDeque<f32>,
     per_core_history: Vec<VecDeque<f32>>,
+    // CPU topology (package/die/L3/core type/SMT) with live per-CPU metrics
+    cpu_topology: Option<TopologySampler>,
+    core_metrics: Vec<CoreMetrics>,
     memory_history: VecDeque<f32>,
     gpu_history: Vec<VecDeque<f32>>,
     gpu_memory_history: Vec<VecDeque<f32>>,
@@ -485,6 +489,8 @@ impl SiliconMonitorApp {
             per_core_history: (0..cpu_core_count)
                 .map(|_| VecDeque::with_capacity(HISTORY_SIZE))
                 .collect(),
+            cpu_topology: TopologySampler::new().ok(),
+            core_metrics: Vec::new(),
             memory_history: VecDeque::with_capacity(HISTORY_SIZE),
             gpu_history: (0..gpu_count)
                 .map(|_| VecDeque::with_capacity(HISTORY_SIZE))
@@ -681,6 +687,10 @@ impl SiliconMonitorApp {
             }
 
             self.cpu_stats = Some(stats);
+
+            if let Some(ref mut sampler) = self.cpu_topology {
+                self.core_metrics = sampler.sample();
+            }
         }
 
         // Update Memory
@@ -1889,6 +1899,8 @@ impl SiliconMonitorApp {
                     });
                 }
 
+                self.draw_cpu_topology(ui);
+
                 // CPU Info
                 ui.add_space(16.0);
                 ui.add(SectionHeader::new("CPU Information").icon("ℹ️"));
@@ -1938,6 +1950,110 @@ impl SiliconMonitorApp {
         });
     }
 
+    /// Per-core bars grouped by package, die, L3 domain and core type
+    fn draw_cpu_topology(&self, ui: &mut egui::Ui) {
+        let Some(ref sampler) = self.cpu_topology else {
+            return;
+        };
+        if self.core_metrics.is_empty() {
+            return;
+        }
+        let topo = sampler.topology();
+        let metrics: HashMap<u32, &CoreMetrics> =
+            self.core_metrics.iter().map(|m| (m.cpu, m)).collect();
+        let loads = topo.domain_loads(&self.core_metrics);
+
+        ui.add_space(16.0);
+        ui.add(SectionHeader::new("CPU Topology").icon("🧩"));
+
+        for pkg in topo.tree() {
+            let numa = if pkg.numa_nodes.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    " · NUMA {}",
+                    pkg.numa_nodes
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )
+            };
+            ui.label(
+                RichText::new(format!("Package {}{}", pkg.id, numa))
+                    .color(CyberColors::CYAN)
+                    .strong(),
+            );
+            for die in &pkg.dies {
+                for l3 in &die.l3_groups {
+                    let load = loads.iter().find(|l| {
+                        l.package == pkg.id && l.die == die.id && l.l3_domain == l3.l3_domain
+                    });
+                    let mut title = format!("Die {} · {}", die.id, topo.l3_label(l3.l3_domain));
+                    if let Some(load) = load {
+                        title.push_str(&format!(
+                            " · avg {:.0}% · max {:.0}%",
+                            load.utilization, load.max_utilization
+                        ));
+                        if let Some(mhz) = load.frequency_mhz {
+                            title.push_str(&format!(" · {} MHz", mhz));
+                        }
+                        if let Some(t) = load.temperature {
+                            title.push_str(&format!(" · {:.0}°C", t));
+                        }
+                    }
+                    let color = load
+                        .map(|l| threshold_color(l.utilization))
+                        .unwrap_or(CyberColors::TEXT_SECONDARY);
+
+                    egui::CollapsingHeader::new(RichText::new(title).color(color))
+                        .id_salt(("cpu_topology", pkg.id, die.id, l3.l3_domain))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for group in &l3.groups {
+                                for core in &group.cores {
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            RichText::new(format!(
+                                                "{} {:>3}",
+                                                group.core_type, core.core_id
+                                            ))
+                                            .color(CyberColors::TEXT_SECONDARY)
+                                            .monospace(),
+                                        );
+                                        for &cpu in &core.threads {
+                                            let usage = metrics
+                                                .get(&cpu)
+                                                .map(|m| m.utilization)
+                                                .unwrap_or(0.0);
+                                            ui.add_sized(
+                                                [120.0, 16.0],
+                                                CyberProgressBar::new(usage / 100.0)
+                                                    .with_threshold_color()
+                                                    .label(format!("cpu{}", cpu)),
+                                            );
+                                        }
+                                        if let Some(m) =
+                                            core.threads.first().and_then(|c| metrics.get(c))
+                                        {
+                                            let mut extra = String::new();
+                                            if let Some(mhz) = m.frequency_mhz {
+                                                extra.push_str(&format!("{} MHz", mhz));
+                                            }
+                                            if let Some(t) = m.temperature {
+                                                extra.push_str(&format!("  {:.0}°C", t));
+                                            }
+                                            ui.label(RichText::new(extra).color(CyberColors::CYAN));
+                                        }
+                                    });
+                                }
+                            }
+                        });
+                }
+            }
+        }
+    }
+
     fn draw_accelerators_tab(&mut self, ui: &mut egui::Ui) {
         if self.gpu_static_info.is_empty() {
             ui.vertical_centered(|ui| {
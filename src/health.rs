@@ -135,6 +135,34 @@ pub struct HealthThresholds {
     pub swap_warning: f32,
     /// Swap usage critical threshold (%)
     pub swap_critical: f32,
+    /// CPU "some" pressure warning threshold (% stalled, avg10)
+    #[serde(default = "default_pressure_warning")]
+    pub pressure_warning: f32,
+    /// CPU "some" pressure critical threshold (% stalled, avg10)
+    #[serde(default = "default_pressure_critical")]
+    pub pressure_critical: f32,
+    /// Memory/IO/IRQ "full" pressure warning threshold (% stalled, avg10)
+    #[serde(default = "default_full_pressure_warning")]
+    pub full_pressure_warning: f32,
+    /// Memory/IO/IRQ "full" pressure critical threshold (% stalled, avg10)
+    #[serde(default = "default_full_pressure_critical")]
+    pub full_pressure_critical: f32,
+}
+
+fn default_pressure_warning() -> f32 {
+    25.0
+}
+
+fn default_pressure_critical() -> f32 {
+    60.0
+}
+
+fn default_full_pressure_warning() -> f32 {
+    5.0
+}
+
+fn default_full_pressure_critical() -> f32 {
+    20.0
 }
 
 impl Default for HealthThresholds {
@@ -152,6 +180,10 @@ impl Default for HealthThresholds {
             disk_critical: 95.0,
             swap_warning: 50.0,
             swap_critical: 80.0,
+            pressure_warning: default_pressure_warning(),
+            pressure_critical: default_pressure_critical(),
+            full_pressure_warning: default_full_pressure_warning(),
+            full_pressure_critical: default_full_pressure_critical(),
         }
     }
 }
@@ -457,6 +489,12 @@ impl SystemHealth {
             }
         }
 
+        // Pressure stall checks (Linux PSI): time lost waiting on a resource is a
+        // better saturation signal than utilization
+        for pressure in crate::psi::read_system(std::path::Path::new("/")).resources {
+            checks.push(pressure_check(&pressure, thresholds));
+        }
+
         // Calculate overall health
         let healthy_count = checks
             .iter()
@@ -561,6 +599,65 @@ pub fn has_critical_issues() -> bool {
         .unwrap_or(false)
 }
 
+/// Health check for one PSI resource: CPU is judged on "some" stall, memory,
+/// IO and IRQ on "full" stall (no task making progress)
+fn pressure_check(
+    pressure: &crate::psi::ResourcePressure,
+    thresholds: &HealthThresholds,
+) -> HealthCheck {
+    let (kind, line) = pressure.headline();
+    let (warning, critical) = match kind {
+        crate::psi::StallKind::Some => (thresholds.pressure_warning, thresholds.pressure_critical),
+        crate::psi::StallKind::Full => (
+            thresholds.full_pressure_warning,
+            thresholds.full_pressure_critical,
+        ),
+    };
+    let stall = line.avg10;
+    let resource = pressure.resource;
+    let (status, message) = if stall >= critical as f64 {
+        (
+            HealthStatus::Critical,
+            format!(
+                "{} {} pressure critical: {:.1}% stalled",
+                resource, kind, stall
+            ),
+        )
+    } else if stall >= warning as f64 {
+        (
+            HealthStatus::Warning,
+            format!(
+                "{} {} pressure elevated: {:.1}% stalled",
+                resource, kind, stall
+            ),
+        )
+    } else if stall >= warning as f64 / 2.0 {
+        (
+            HealthStatus::Good,
+            format!(
+                "{} {} pressure moderate: {:.1}% stalled",
+                resource, kind, stall
+            ),
+        )
+    } else {
+        (
+            HealthStatus::Healthy,
+            format!("{} {} pressure low: {:.1}% stalled", resource, kind, stall),
+        )
+    };
+    let category = match resource {
+        crate::psi::PsiResource::Cpu | crate::psi::PsiResource::Irq => "CPU",
+        crate::psi::PsiResource::Memory => "Memory",
+        crate::psi::PsiResource::Io => "Storage",
+    };
+    HealthCheck::new(
+        &format!("{} Pressure", resource.name().to_uppercase()),
+        category,
+    )
+    .with_status(status, &message)
+    .with_value(stall, Some(warning as f64))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -578,5 +675,33 @@ mod tests {
         let thresholds = HealthThresholds::default();
         assert!(thresholds.cpu_warning < thresholds.cpu_critical);
         assert!(thresholds.memory_warning < thresholds.memory_critical);
+        assert!(thresholds.pressure_warning < thresholds.pressure_critical);
+        assert!(thresholds.full_pressure_warning < thresholds.full_pressure_critical);
+    }
+
+    #[test]
+    fn test_pressure_check() {
+        use crate::psi::{PsiResource, ResourcePressure};
+        let thresholds = HealthThresholds::default();
+        let memory = ResourcePressure::parse(
+            PsiResource::Memory,
+            "some avg10=40.00 avg60=0 avg300=0 total=0\nfull avg10=25.00 avg60=0 avg300=0 total=0\n",
+        )
+        .unwrap();
+        let check = pressure_check(&memory, &thresholds);
+        assert_eq!(check.status, HealthStatus::Critical);
+        assert_eq!(check.category, "Memory");
+        assert_eq!(check.value, Some(25.0));
+
+        // CPU is judged on "some": 30% is above the 25% warning
+        let cpu = ResourcePressure::parse(
+            PsiResource::Cpu,
+            "some avg10=30.00 avg60=0 avg300=0 total=0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            pressure_check(&cpu, &thresholds).status,
+            HealthStatus::Warning
+        );
     }
 }
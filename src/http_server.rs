@@ -122,6 +122,89 @@ impl HttpServer {
         })
     }
 
+    /// Serve energy accounting at `/api/v1/energy` and in the Prometheus output
+    pub fn with_energy_accountant(
+        self,
+        accountant: crate::energy_accounting::SharedEnergyAccountant,
+    ) -> Self {
+        if let Ok(mut api) = self.handler.api.write() {
+            api.set_energy_accountant(accountant);
+        }
+        self
+    }
+
+    /// Serve perf counters at `/api/v1/perf` and in the Prometheus output
+    pub fn with_perf_snapshot(self, snapshot: crate::perf::SharedPerfSnapshot) -> Self {
+        if let Ok(mut api) = self.handler.api.write() {
+            api.set_perf_snapshot(snapshot);
+        }
+        self
+    }
+
+    /// Serve pressure stall information at `/api/v1/pressure` and in the Prometheus output
+    pub fn with_psi_snapshot(self, snapshot: crate::psi::SharedPsiSnapshot) -> Self {
+        if let Ok(mut api) = self.handler.api.write() {
+            api.set_psi_snapshot(snapshot);
+        }
+        self
+    }
+
+    /// Serve recent OOM kills at `/api/v1/oom`
+    pub fn with_oom_snapshot(self, snapshot: crate::oom::SharedOomSnapshot) -> Self {
+        if let Ok(mut api) = self.handler.api.write() {
+            api.set_oom_snapshot(snapshot);
+        }
+        self
+    }
+
+    /// Serve per-device I/O latency at `/api/v1/io-latency` and in the Prometheus output
+    pub fn with_io_latency_snapshot(
+        self,
+        snapshot: crate::io_scheduler::latency::SharedIoLatencySnapshot,
+    ) -> Self {
+        if let Ok(mut api) = self.handler.api.write() {
+            api.set_io_latency_snapshot(snapshot);
+        }
+        self
+    }
+
+    /// Serve filesystem capacity forecasts at `/api/v1/capacity` and in the Prometheus output
+    pub fn with_capacity_snapshot(self, snapshot: crate::capacity::SharedCapacitySnapshot) -> Self {
+        if let Ok(mut api) = self.handler.api.write() {
+            api.set_capacity_snapshot(snapshot);
+        }
+        self
+    }
+
+    /// Serve RDMA port status at `/api/v1/rdma` and in the Prometheus output
+    pub fn with_rdma_snapshot(self, snapshot: crate::rdma::SharedRdmaSnapshot) -> Self {
+        if let Ok(mut api) = self.handler.api.write() {
+            api.set_rdma_snapshot(snapshot);
+        }
+        self
+    }
+
+    /// Run the registry's enabled collectors on every Prometheus scrape
+    pub fn with_collector_registry(
+        self,
+        registry: Arc<crate::prometheus::CollectorRegistry>,
+    ) -> Self {
+        if let Ok(mut api) = self.handler.api.write() {
+            api.set_collector_registry(registry);
+        }
+        self
+    }
+
+    /// Event manager behind `/api/v1/events`, for background monitors to publish to
+    pub fn event_manager(&self) -> Arc<crate::observability::EventManager> {
+        Arc::clone(&self.handler.event_manager)
+    }
+
+    /// Time series recorded by the metric collection loop, for push exporters
+    pub fn metric_collector(&self) -> Arc<MetricCollector> {
+        Arc::clone(&self.metric_collector)
+    }
+
     /// Run the HTTP server (blocks until shutdown)
     #[cfg(feature = "cli")]
     pub async fn run(&self) -> crate::Result<()> {
@@ -0,0 +1,879 @@
+//! Per-device I/O latency and queue analysis (`iostat -x` semantics)
+//!
+//! [`IoStats`] holds cumulative counters; this module samples `/proc/diskstats`
+//! and turns the deltas into per-interval await, service time, average queue
+//! size and utilization, exactly as `iostat -x` does:
+//!
+//! - `r_await`/`w_await`: Δ read/write ticks ÷ Δ completed I/Os (ms, includes queueing)
+//! - `aqu-sz`: Δ weighted I/O ticks ÷ interval
+//! - `%util`: Δ I/O ticks ÷ interval
+//! - `svctm`: Δ I/O ticks ÷ Δ completed I/Os (deprecated in iostat, kept for comparison)
+//!
+//! The kernel keeps no per-I/O latency histogram outside BPF, so p50/p99 are
+//! estimated from a rolling window of per-interval awaits weighted by I/O
+//! count. That smooths out sub-interval outliers (the true per-I/O p99 is
+//! higher) but reliably shows the multi-second latency spikes that stall
+//! readers while throughput looks normal.
+//!
+//! cgroup v2 `io.stat` adds per-cgroup, per-device I/O rates and, for groups
+//! with an `io.latency` target, blk-iolatency's measured `avg_lat`.
+//!
+//! ## Platform Support
+//!
+//! - **Linux**: `/proc/diskstats`, `/sys/block/*/queue`, cgroup v2 `io.stat`
+//! - **Other**: unsupported
+
+use super::IoStats;
+use crate::error::{Result, SimonError};
+use crate::observability::{event_types, EventCategory, EventManager, SystemEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Parse `/proc/diskstats` into `(major:minor, name, counters)`
+pub fn parse_diskstats(text: &str) -> Vec<(String, String, IoStats)> {
+    text.lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 14 {
+                return None;
+            }
+            let n = |i: usize| f.get(i).and_then(|v| v.parse().ok()).unwrap_or(0);
+            Some((
+                format!("{}:{}", f[0], f[1]),
+                f[2].to_string(),
+                IoStats {
+                    reads_completed: n(3),
+                    reads_merged: n(4),
+                    sectors_read: n(5),
+                    read_time_ms: n(6),
+                    writes_completed: n(7),
+                    writes_merged: n(8),
+                    sectors_written: n(9),
+                    write_time_ms: n(10),
+                    in_flight: n(11),
+                    io_time_ms: n(12),
+                    weighted_io_time_ms: n(13),
+                    discards_completed: n(14),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Extended statistics for one device over one interval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceLatency {
+    pub device: String,
+    pub rotational: bool,
+    /// Request queue size (`queue/nr_requests`)
+    pub nr_requests: Option<u32>,
+    pub reads_per_sec: f64,
+    pub writes_per_sec: f64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    /// Merged requests per second (`rrqm/s`, `wrqm/s`)
+    pub read_merges_per_sec: f64,
+    pub write_merges_per_sec: f64,
+    /// Average read latency including queueing (`r_await`), None without reads
+    pub read_await_ms: Option<f64>,
+    pub write_await_ms: Option<f64>,
+    /// Average over reads and writes (`await`)
+    pub await_ms: Option<f64>,
+    /// Device busy time per I/O (`svctm`)
+    pub service_time_ms: Option<f64>,
+    /// Average requests in flight or queued (`aqu-sz`)
+    pub avg_queue_size: f64,
+    /// Percent of the interval with I/O in flight (`%util`)
+    pub utilization: f64,
+    /// Average request size in KiB (`rareq-sz`, `wareq-sz`)
+    pub avg_read_size_kb: Option<f64>,
+    pub avg_write_size_kb: Option<f64>,
+    /// Requests in flight at the end of the interval
+    pub in_flight: u64,
+    /// Estimated median await over the window (I/O-weighted interval awaits)
+    pub p50_ms: Option<f64>,
+    /// Estimated 99th percentile await over the window
+    pub p99_ms: Option<f64>,
+}
+
+impl DeviceLatency {
+    /// Derive interval statistics from two counter samples `secs` apart
+    ///
+    /// Returns None if the counters went backwards (device re-created).
+    pub fn from_delta(device: &str, prev: &IoStats, now: &IoStats, secs: f64) -> Option<Self> {
+        if secs <= 0.0 {
+            return None;
+        }
+        let d = |a: u64, b: u64| a.checked_sub(b);
+        let reads = d(now.reads_completed, prev.reads_completed)? as f64;
+        let writes = d(now.writes_completed, prev.writes_completed)? as f64;
+        let read_ticks = d(now.read_time_ms, prev.read_time_ms)? as f64;
+        let write_ticks = d(now.write_time_ms, prev.write_time_ms)? as f64;
+        let io_ticks = d(now.io_time_ms, prev.io_time_ms)? as f64;
+        let weighted = d(now.weighted_io_time_ms, prev.weighted_io_time_ms)? as f64;
+        let read_bytes = d(now.sectors_read, prev.sectors_read)? as f64 * 512.0;
+        let write_bytes = d(now.sectors_written, prev.sectors_written)? as f64 * 512.0;
+        let read_merges = d(now.reads_merged, prev.reads_merged)? as f64;
+        let write_merges = d(now.writes_merged, prev.writes_merged)? as f64;
+        let ms = secs * 1000.0;
+        let ratio = |num: f64, den: f64| (den > 0.0).then(|| num / den);
+
+        Some(Self {
+            device: device.to_string(),
+            rotational: false,
+            nr_requests: None,
+            reads_per_sec: reads / secs,
+            writes_per_sec: writes / secs,
+            read_bytes_per_sec: read_bytes / secs,
+            write_bytes_per_sec: write_bytes / secs,
+            read_merges_per_sec: read_merges / secs,
+            write_merges_per_sec: write_merges / secs,
+            read_await_ms: ratio(read_ticks, reads),
+            write_await_ms: ratio(write_ticks, writes),
+            await_ms: ratio(read_ticks + write_ticks, reads + writes),
+            service_time_ms: ratio(io_ticks, reads + writes),
+            avg_queue_size: weighted / ms,
+            utilization: (io_ticks / ms * 100.0).min(100.0),
+            avg_read_size_kb: ratio(read_bytes / 1024.0, reads),
+            avg_write_size_kb: ratio(write_bytes / 1024.0, writes),
+            in_flight: now.in_flight,
+            p50_ms: None,
+            p99_ms: None,
+        })
+    }
+
+    /// Completed I/Os in the interval
+    pub fn iops(&self) -> f64 {
+        self.reads_per_sec + self.writes_per_sec
+    }
+}
+
+/// I/O-weighted percentile of `(await_ms, ios)` samples
+pub fn weighted_percentile(samples: &[(f64, u64)], q: f64) -> Option<f64> {
+    let total: u64 = samples.iter().map(|(_, n)| n).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let target = (q.clamp(0.0, 1.0) * total as f64).ceil().max(1.0) as u64;
+    let mut seen = 0;
+    for (value, n) in sorted {
+        seen += n;
+        if seen >= target {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Why a device is flagged as saturated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaturationKind {
+    /// Rotational device busy most of the interval
+    Utilization,
+    /// Average queue size at or above the threshold
+    QueueBacklog,
+    /// Await above the absolute threshold for the device class
+    HighLatency,
+    /// Await far above the device's recent median
+    LatencySpike,
+}
+
+impl std::fmt::Display for SaturationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utilization => write!(f, "utilization"),
+            Self::QueueBacklog => write!(f, "queue_backlog"),
+            Self::HighLatency => write!(f, "high_latency"),
+            Self::LatencySpike => write!(f, "latency_spike"),
+        }
+    }
+}
+
+/// A saturation condition found in the latest interval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaturationWarning {
+    pub device: String,
+    pub kind: SaturationKind,
+    pub message: String,
+}
+
+impl SaturationWarning {
+    pub fn event(&self, latency: Option<&DeviceLatency>) -> SystemEvent {
+        let event_type = match self.kind {
+            SaturationKind::HighLatency | SaturationKind::LatencySpike => {
+                event_types::disk::HIGH_LATENCY
+            }
+            SaturationKind::Utilization | SaturationKind::QueueBacklog => {
+                event_types::disk::HIGH_IO
+            }
+        };
+        let mut event = SystemEvent::warning(
+            EventCategory::Disk,
+            event_type,
+            &self.message,
+            &format!("disk:{}", self.device),
+        )
+        .with_metadata("kind", self.kind.to_string());
+        if let Some(l) = latency {
+            event = event
+                .with_metadata("await_ms", l.await_ms)
+                .with_metadata("p99_ms", l.p99_ms)
+                .with_metadata("avg_queue_size", l.avg_queue_size)
+                .with_metadata("utilization", l.utilization);
+        }
+        event
+    }
+}
+
+/// Saturation warning thresholds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaturationThresholds {
+    /// `%util` on rotational devices (SSDs serve requests in parallel, so
+    /// 100% util there only means "never idle")
+    pub utilization_pct: f64,
+    /// Average queue size
+    pub queue_size: f64,
+    /// Await for rotational devices (ms)
+    pub hdd_await_ms: f64,
+    /// Await for SSD/NVMe (ms)
+    pub ssd_await_ms: f64,
+    /// Await this many times the window median counts as a spike
+    pub spike_factor: f64,
+    /// Spikes below this await (ms) are ignored
+    pub spike_min_ms: f64,
+}
+
+impl Default for SaturationThresholds {
+    fn default() -> Self {
+        Self {
+            utilization_pct: 90.0,
+            queue_size: 32.0,
+            hdd_await_ms: 50.0,
+            ssd_await_ms: 10.0,
+            spike_factor: 5.0,
+            spike_min_ms: 2.0,
+        }
+    }
+}
+
+/// Per-cgroup, per-device I/O from cgroup v2 `io.stat`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CgroupIoLatency {
+    pub cgroup: String,
+    pub device: String,
+    pub reads_per_sec: f64,
+    pub writes_per_sec: f64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    /// blk-iolatency measured average latency (µs), present with an `io.latency` target
+    pub avg_lat_us: Option<u64>,
+    /// blk-iolatency allowed queue depth (None = unthrottled)
+    pub depth: Option<u64>,
+    /// blk-iolatency sampling window (ms)
+    pub window_ms: Option<u64>,
+}
+
+/// One line of `io.stat`: device and its `key=value` fields
+pub fn parse_io_stat(text: &str) -> Vec<(String, HashMap<String, String>)> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let dev = parts.next()?.to_string();
+            let fields = parts
+                .filter_map(|kv| kv.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            Some((dev, fields))
+        })
+        .collect()
+}
+
+/// cgroup v2 groups with an `io.stat`, up to `max_depth` levels deep
+fn list_io_cgroups(root: &Path, max_depth: usize) -> Vec<String> {
+    fn walk(dir: &Path, rel: &str, depth: usize, max_depth: usize, out: &mut Vec<String>) {
+        if depth > max_depth {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                continue;
+            }
+            let path = entry.path();
+            let rel_path = format!("{}/{}", rel, entry.file_name().to_string_lossy());
+            if path.join("io.stat").exists() {
+                out.push(rel_path.clone());
+            }
+            walk(&path, &rel_path, depth + 1, max_depth, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(&root.join("sys/fs/cgroup"), "", 1, max_depth, &mut out);
+    out.sort();
+    out
+}
+
+/// Latency, queue and saturation state for all devices
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IoLatencySnapshot {
+    /// Unix time of the sample
+    pub timestamp: u64,
+    pub interval_secs: f64,
+    pub devices: Vec<DeviceLatency>,
+    pub cgroups: Vec<CgroupIoLatency>,
+    pub warnings: Vec<SaturationWarning>,
+}
+
+impl IoLatencySnapshot {
+    pub fn device(&self, name: &str) -> Option<&DeviceLatency> {
+        self.devices.iter().find(|d| d.device == name)
+    }
+
+    /// Devices ordered by worst current await
+    pub fn slowest(&self) -> Vec<&DeviceLatency> {
+        let mut devices: Vec<&DeviceLatency> = self.devices.iter().collect();
+        devices.sort_by(|a, b| {
+            b.await_ms
+                .unwrap_or(0.0)
+                .total_cmp(&a.await_ms.unwrap_or(0.0))
+        });
+        devices
+    }
+}
+
+/// Monitor configuration (`[io_latency]` in the daemon config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IoLatencyConfig {
+    pub enabled: bool,
+    /// Sampling interval in milliseconds
+    pub interval_ms: u64,
+    /// Intervals kept for the p50/p99 estimates
+    pub window: usize,
+    /// Include device-mapper and md devices (what filesystems see)
+    pub virtual_devices: bool,
+    /// Read cgroup v2 `io.stat`
+    pub cgroups: bool,
+    /// How many levels below the cgroup root to scan
+    pub cgroup_depth: usize,
+    pub thresholds: SaturationThresholds,
+    /// Minimum seconds between events for the same device and condition
+    pub event_cooldown_secs: u64,
+}
+
+impl Default for IoLatencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 2000,
+            window: 150,
+            virtual_devices: true,
+            cgroups: true,
+            cgroup_depth: 3,
+            thresholds: SaturationThresholds::default(),
+            event_cooldown_secs: 60,
+        }
+    }
+}
+
+/// Samples `/proc/diskstats` and cgroup `io.stat`, keeping the per-device
+/// history used for percentile estimates and spike detection
+pub struct IoLatencyMonitor {
+    root: PathBuf,
+    config: IoLatencyConfig,
+    last: HashMap<String, IoStats>,
+    last_cgroup: HashMap<(String, String), [u64; 4]>,
+    last_at: Option<Instant>,
+    history: HashMap<String, VecDeque<(f64, u64)>>,
+}
+
+impl IoLatencyMonitor {
+    /// Monitor the live system
+    pub fn new(config: IoLatencyConfig) -> Result<Self> {
+        Self::with_root("/", config)
+    }
+
+    /// Monitor a filesystem tree rooted at `root` (for tests)
+    pub fn with_root(root: impl Into<PathBuf>, config: IoLatencyConfig) -> Result<Self> {
+        let root = root.into();
+        if !root.join("proc/diskstats").exists() {
+            return Err(SimonError::UnsupportedPlatform(
+                "I/O latency analysis requires Linux /proc/diskstats".into(),
+            ));
+        }
+        Ok(Self {
+            root,
+            config,
+            last: HashMap::new(),
+            last_cgroup: HashMap::new(),
+            last_at: None,
+            history: HashMap::new(),
+        })
+    }
+
+    /// Sample all devices, returning interval statistics against the
+    /// previous call (empty device list on the first call)
+    pub fn sample(&mut self) -> IoLatencySnapshot {
+        let now = Instant::now();
+        let elapsed = self.last_at.map(|t| now.duration_since(t).as_secs_f64());
+        self.last_at = Some(now);
+        self.sample_with_elapsed(elapsed)
+    }
+
+    fn sample_with_elapsed(&mut self, elapsed: Option<f64>) -> IoLatencySnapshot {
+        let text = std::fs::read_to_string(self.root.join("proc/diskstats")).unwrap_or_default();
+        let stats = parse_diskstats(&text);
+        let names: HashMap<String, String> = stats
+            .iter()
+            .map(|(dev, name, _)| (dev.clone(), name.clone()))
+            .collect();
+
+        let mut devices = Vec::new();
+        let mut warnings = Vec::new();
+        let mut current = HashMap::new();
+        for (_, name, now) in stats {
+            if !self.tracked(&name) {
+                continue;
+            }
+            if let (Some(prev), Some(secs)) = (self.last.get(&name), elapsed) {
+                if let Some(mut latency) = DeviceLatency::from_delta(&name, prev, &now, secs) {
+                    let queue = self.root.join("sys/block").join(&name).join("queue");
+                    latency.rotational =
+                        read_trimmed(&queue.join("rotational")).as_deref() == Some("1");
+                    latency.nr_requests =
+                        read_trimmed(&queue.join("nr_requests")).and_then(|v| v.parse().ok());
+                    warnings.extend(self.record(&mut latency, secs));
+                    devices.push(latency);
+                }
+            }
+            current.insert(name, now);
+        }
+        self.last = current;
+        self.history.retain(|name, _| self.last.contains_key(name));
+
+        let cgroups = if self.config.cgroups {
+            self.sample_cgroups(&names, elapsed)
+        } else {
+            Vec::new()
+        };
+
+        IoLatencySnapshot {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            interval_secs: elapsed.unwrap_or(0.0),
+            devices,
+            cgroups,
+            warnings,
+        }
+    }
+
+    /// Whole devices only: partitions are double counted by their disk
+    fn tracked(&self, name: &str) -> bool {
+        if name.starts_with("loop") || name.starts_with("ram") || name.starts_with("zram") {
+            return false;
+        }
+        if !self.config.virtual_devices && (name.starts_with("dm-") || name.starts_with("md")) {
+            return false;
+        }
+        self.root.join("sys/block").join(name).exists()
+    }
+
+    /// Update percentile history and check thresholds for one interval
+    fn record(&mut self, latency: &mut DeviceLatency, secs: f64) -> Vec<SaturationWarning> {
+        let t = &self.config.thresholds;
+        let history = self.history.entry(latency.device.clone()).or_default();
+        let samples: Vec<(f64, u64)> = history.iter().copied().collect();
+        let median_before = (samples.len() >= 10)
+            .then(|| weighted_percentile(&samples, 0.5))
+            .flatten();
+
+        let ios = (latency.iops() * secs).round() as u64;
+        if let Some(await_ms) = latency.await_ms.filter(|_| ios > 0) {
+            history.push_back((await_ms, ios));
+            while history.len() > self.config.window.max(1) {
+                history.pop_front();
+            }
+        }
+        let samples: Vec<(f64, u64)> = history.iter().copied().collect();
+        latency.p50_ms = weighted_percentile(&samples, 0.5);
+        latency.p99_ms = weighted_percentile(&samples, 0.99);
+
+        let mut warnings = Vec::new();
+        let mut warn = |kind, message: String| {
+            warnings.push(SaturationWarning {
+                device: latency.device.clone(),
+                kind,
+                message,
+            })
+        };
+        if latency.rotational && latency.utilization >= t.utilization_pct {
+            warn(
+                SaturationKind::Utilization,
+                format!("{} is {:.0}% busy", latency.device, latency.utilization),
+            );
+        }
+        if latency.avg_queue_size >= t.queue_size {
+            warn(
+                SaturationKind::QueueBacklog,
+                format!(
+                    "{} has {:.1} requests queued on average",
+                    latency.device, latency.avg_queue_size
+                ),
+            );
+        }
+        if let Some(await_ms) = latency.await_ms {
+            let limit = if latency.rotational {
+                t.hdd_await_ms
+            } else {
+                t.ssd_await_ms
+            };
+            if await_ms >= limit {
+                warn(
+                    SaturationKind::HighLatency,
+                    format!(
+                        "{} await {:.1} ms (threshold {:.0} ms)",
+                        latency.device, await_ms, limit
+                    ),
+                );
+            } else if let Some(median) = median_before.filter(|m| *m > 0.0) {
+                if await_ms >= t.spike_min_ms && await_ms >= median * t.spike_factor {
+                    warn(
+                        SaturationKind::LatencySpike,
+                        format!(
+                            "{} await {:.1} ms is {:.0}x its recent median ({:.2} ms)",
+                            latency.device,
+                            await_ms,
+                            await_ms / median,
+                            median
+                        ),
+                    );
+                }
+            }
+        }
+        warnings
+    }
+
+    fn sample_cgroups(
+        &mut self,
+        names: &HashMap<String, String>,
+        elapsed: Option<f64>,
+    ) -> Vec<CgroupIoLatency> {
+        let mut out = Vec::new();
+        let mut current = HashMap::new();
+        for cgroup in list_io_cgroups(&self.root, self.config.cgroup_depth) {
+            let path = self
+                .root
+                .join("sys/fs/cgroup")
+                .join(cgroup.trim_start_matches('/'));
+            let Ok(text) = std::fs::read_to_string(path.join("io.stat")) else {
+                continue;
+            };
+            for (dev, fields) in parse_io_stat(&text) {
+                let Some(device) = names.get(&dev) else {
+                    continue;
+                };
+                let get = |k: &str| fields.get(k).and_then(|v| v.parse::<u64>().ok());
+                let counters = [
+                    get("rios").unwrap_or(0),
+                    get("wios").unwrap_or(0),
+                    get("rbytes").unwrap_or(0),
+                    get("wbytes").unwrap_or(0),
+                ];
+                let key = (cgroup.clone(), device.clone());
+                let rates = match (self.last_cgroup.get(&key), elapsed.filter(|s| *s > 0.0)) {
+                    (Some(prev), Some(secs)) => {
+                        let mut r = [0.0; 4];
+                        for i in 0..4 {
+                            r[i] = counters[i].saturating_sub(prev[i]) as f64 / secs;
+                        }
+                        Some(r)
+                    }
+                    _ => None,
+                };
+                current.insert(key, counters);
+                let avg_lat_us = get("avg_lat");
+                let Some(r) = rates else { continue };
+                if avg_lat_us.is_none() && r.iter().all(|v| *v == 0.0) {
+                    continue;
+                }
+                out.push(CgroupIoLatency {
+                    cgroup: cgroup.clone(),
+                    device: device.clone(),
+                    reads_per_sec: r[0],
+                    writes_per_sec: r[1],
+                    read_bytes_per_sec: r[2],
+                    write_bytes_per_sec: r[3],
+                    avg_lat_us,
+                    depth: get("depth"),
+                    window_ms: get("win"),
+                });
+            }
+        }
+        self.last_cgroup = current;
+        out
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// Per-device and per-cgroup latency from the last interval
+pub type SharedIoLatencySnapshot = Arc<RwLock<Option<IoLatencySnapshot>>>;
+
+/// Start sampling on a background thread, emitting saturation warnings to
+/// `events` at most once per cooldown for each device and condition
+pub fn spawn(config: IoLatencyConfig, events: Arc<EventManager>) -> Result<IoLatencyHandle> {
+    let interval = Duration::from_millis(config.interval_ms.max(500));
+    let cooldown = Duration::from_secs(config.event_cooldown_secs);
+    let mut monitor = IoLatencyMonitor::new(config)?;
+    monitor.sample();
+    let snapshot: SharedIoLatencySnapshot = Arc::new(RwLock::new(None));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let shared = Arc::clone(&snapshot);
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-io-latency".into())
+        .spawn(move || {
+            let mut last_event: HashMap<(String, SaturationKind), Instant> = HashMap::new();
+            while !flag.load(Ordering::SeqCst) {
+                let deadline = Instant::now() + interval;
+                while !flag.load(Ordering::SeqCst) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                if flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let sample = monitor.sample();
+                for warning in &sample.warnings {
+                    let key = (warning.device.clone(), warning.kind);
+                    if last_event.get(&key).is_some_and(|t| t.elapsed() < cooldown) {
+                        continue;
+                    }
+                    events.emit(warning.event(sample.device(&warning.device)));
+                    last_event.insert(key, Instant::now());
+                }
+                if let Ok(mut slot) = shared.write() {
+                    *slot = Some(sample);
+                }
+            }
+        })
+        .map_err(|e| {
+            SimonError::Other(format!("Failed to spawn I/O latency monitor thread: {}", e))
+        })?;
+
+    Ok(IoLatencyHandle {
+        snapshot,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+/// Handle to a running I/O latency monitor thread
+pub struct IoLatencyHandle {
+    snapshot: SharedIoLatencySnapshot,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl IoLatencyHandle {
+    /// Shared snapshot slot for readers
+    pub fn snapshot(&self) -> SharedIoLatencySnapshot {
+        Arc::clone(&self.snapshot)
+    }
+
+    /// Stop monitoring and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for IoLatencyHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    /// `/proc/diskstats` for nvme0n1 (+ a partition) and sda
+    fn diskstats(root: &FakeRoot, nvme: [u64; 4], sda: [u64; 4]) {
+        // reads, read ticks, io ticks, weighted ticks; writes fixed at 0
+        let line = |maj: u32, min: u32, name: &str, c: [u64; 4]| {
+            format!(
+                "{:>4} {:>7} {} {} 0 {} {} 0 0 0 0 0 {} {} 0 0 0 0\n",
+                maj,
+                min,
+                name,
+                c[0],
+                c[0] * 8,
+                c[1],
+                c[2],
+                c[3]
+            )
+        };
+        let text = line(259, 0, "nvme0n1", nvme)
+            + &line(259, 1, "nvme0n1p1", nvme)
+            + &line(8, 0, "sda", sda)
+            + &line(7, 0, "loop0", [0; 4]);
+        root.write("proc/diskstats", &text);
+    }
+
+    #[test]
+    fn test_iostat_semantics() {
+        let prev = IoStats {
+            reads_completed: 1000,
+            reads_merged: 10,
+            sectors_read: 80_000,
+            read_time_ms: 500,
+            writes_completed: 2000,
+            writes_merged: 0,
+            sectors_written: 160_000,
+            write_time_ms: 800,
+            in_flight: 0,
+            io_time_ms: 1000,
+            weighted_io_time_ms: 1300,
+            discards_completed: 0,
+        };
+        let now = IoStats {
+            reads_completed: 1200,
+            reads_merged: 30,
+            sectors_read: 80_000 + 200 * 8,
+            read_time_ms: 500 + 400,
+            writes_completed: 2100,
+            sectors_written: 160_000 + 100 * 256,
+            write_time_ms: 800 + 1100,
+            in_flight: 3,
+            io_time_ms: 1000 + 1500,
+            weighted_io_time_ms: 1300 + 8000,
+            ..prev.clone()
+        };
+        let l = DeviceLatency::from_delta("sda", &prev, &now, 2.0).unwrap();
+        assert_eq!(l.reads_per_sec, 100.0);
+        assert_eq!(l.read_merges_per_sec, 10.0);
+        assert_eq!(l.read_await_ms, Some(2.0));
+        assert_eq!(l.write_await_ms, Some(11.0));
+        assert_eq!(l.await_ms, Some(5.0));
+        assert_eq!(l.service_time_ms, Some(5.0));
+        assert_eq!(l.avg_queue_size, 4.0);
+        assert_eq!(l.utilization, 75.0);
+        assert_eq!(l.avg_read_size_kb, Some(4.0));
+        assert_eq!(l.avg_write_size_kb, Some(128.0));
+
+        // Counter reset
+        assert!(DeviceLatency::from_delta("sda", &now, &prev, 2.0).is_none());
+
+        let samples = [(1.0, 90), (2.0, 9), (50.0, 1)];
+        assert_eq!(weighted_percentile(&samples, 0.5), Some(1.0));
+        assert_eq!(weighted_percentile(&samples, 0.99), Some(2.0));
+        assert_eq!(weighted_percentile(&samples, 1.0), Some(50.0));
+        assert_eq!(weighted_percentile(&[], 0.5), None);
+    }
+
+    #[test]
+    fn test_monitor_spikes_and_saturation() {
+        let root = FakeRoot::new("io-latency-monitor");
+        root.write("sys/block/nvme0n1/queue/rotational", "0\n");
+        root.write("sys/block/nvme0n1/queue/nr_requests", "1023\n");
+        root.write("sys/block/sda/queue/rotational", "1\n");
+        diskstats(&root, [0; 4], [0; 4]);
+        let mut monitor =
+            IoLatencyMonitor::with_root(root.path(), IoLatencyConfig::default()).unwrap();
+        assert!(monitor.sample_with_elapsed(None).devices.is_empty());
+
+        // 10 quiet intervals: nvme 1000 reads at 0.2 ms, sda idle
+        let mut nvme = [0u64; 4];
+        for _ in 0..10 {
+            nvme = [nvme[0] + 1000, nvme[1] + 200, nvme[2] + 150, nvme[3] + 200];
+            diskstats(&root, nvme, [0; 4]);
+            let s = monitor.sample_with_elapsed(Some(1.0));
+            assert!(s.warnings.is_empty(), "{:?}", s.warnings);
+        }
+        let s = monitor.sample_with_elapsed(Some(1.0));
+        let d = s.device("nvme0n1").unwrap();
+        assert_eq!(d.nr_requests, Some(1023));
+        assert!(s.device("nvme0n1p1").is_none() && s.device("loop0").is_none());
+
+        // Spike: 200 reads at 5 ms on the NVMe (~2% of window I/Os); sda saturated
+        nvme = [nvme[0] + 200, nvme[1] + 1000, nvme[2] + 800, nvme[3] + 1000];
+        diskstats(&root, nvme, [300, 30_000, 980, 40_000]);
+        let s = monitor.sample_with_elapsed(Some(1.0));
+        let kinds: Vec<(&str, SaturationKind)> = s
+            .warnings
+            .iter()
+            .map(|w| (w.device.as_str(), w.kind))
+            .collect();
+        assert!(
+            kinds.contains(&("nvme0n1", SaturationKind::LatencySpike)),
+            "{:?}",
+            kinds
+        );
+        assert!(kinds.contains(&("sda", SaturationKind::Utilization)));
+        assert!(kinds.contains(&("sda", SaturationKind::QueueBacklog)));
+        assert!(kinds.contains(&("sda", SaturationKind::HighLatency)));
+
+        let d = s.device("nvme0n1").unwrap();
+        assert_eq!(d.p50_ms, Some(0.2));
+        assert_eq!(d.p99_ms, Some(5.0));
+        assert_eq!(s.slowest()[0].device, "sda");
+
+        let event = s.warnings[0].event(s.device(&s.warnings[0].device));
+        assert_eq!(event.event_type, event_types::disk::HIGH_LATENCY);
+    }
+
+    #[test]
+    fn test_cgroup_io_stat() {
+        let root = FakeRoot::new("io-latency-cgroup");
+        root.write("sys/block/nvme0n1/queue/rotational", "0\n");
+        diskstats(&root, [0; 4], [0; 4]);
+        let stat = |rios: u64| {
+            format!(
+                "259:0 rbytes={} wbytes=0 rios={} wios=0 dbytes=0 dios=0 depth=max avg_lat=850 win=100\n\
+                 8:0 rbytes=0 wbytes=0 rios=0 wios=0 dbytes=0 dios=0\n",
+                rios * 4096,
+                rios
+            )
+        };
+        root.write("sys/fs/cgroup/train.slice/io.stat", &stat(0));
+        let mut monitor =
+            IoLatencyMonitor::with_root(root.path(), IoLatencyConfig::default()).unwrap();
+        assert!(monitor.sample_with_elapsed(None).cgroups.is_empty());
+
+        root.write("sys/fs/cgroup/train.slice/io.stat", &stat(500));
+        let s = monitor.sample_with_elapsed(Some(2.0));
+        assert_eq!(s.cgroups.len(), 1, "{:?}", s.cgroups);
+        let cg = &s.cgroups[0];
+        assert_eq!(
+            (cg.cgroup.as_str(), cg.device.as_str()),
+            ("/train.slice", "nvme0n1")
+        );
+        assert_eq!(cg.reads_per_sec, 250.0);
+        assert_eq!(cg.read_bytes_per_sec, 250.0 * 4096.0);
+        assert_eq!(
+            (cg.avg_lat_us, cg.depth, cg.window_ms),
+            (Some(850), None, Some(100))
+        );
+    }
+}
@@ -10,6 +10,8 @@
 //! - **Windows**: I/O priority via performance counters
 //! - **macOS**: IOKit disk stats
 
+pub mod latency;
+
 use serde::{Deserialize, Serialize};
 use crate::error::SimonError;
 
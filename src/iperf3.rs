@@ -0,0 +1,1735 @@
+//! iperf3-compatible bandwidth testing
+//!
+//! Implements both ends of the iperf3 control protocol, so a simon client
+//! can test against a stock `iperf3 -s` and a simon server can serve a stock
+//! `iperf3 -c`. [`crate::bandwidth::bandwidth_test`] only streams into a raw
+//! TCP sink; this module negotiates the test with the peer and exchanges
+//! results, so both ends report the sender and receiver side.
+//!
+//! A test runs over one TCP control connection:
+//!
+//! 1. The client sends a 37-byte cookie identifying the test.
+//! 2. The server asks for parameters (`PARAM_EXCHANGE`) and the client
+//!    answers with a length-prefixed JSON object (protocol, duration,
+//!    parallel streams, reverse, block size, bitrate).
+//! 3. On `CREATE_STREAMS` the client opens the data streams to the same
+//!    port: TCP streams send the cookie, UDP streams send a 4-byte hello
+//!    that the server answers.
+//! 4. `TEST_START`/`TEST_RUNNING` start the transfer. The client ends it
+//!    with `TEST_END` once the duration has elapsed.
+//! 5. Both sides swap per-stream results as JSON (`EXCHANGE_RESULTS`), the
+//!    server sends `DISPLAY_RESULTS` and the client closes with `IPERF_DONE`.
+//!
+//! UDP datagrams carry a send timestamp and sequence number, from which the
+//! receiver derives loss, reordering and RFC 1889 jitter. On Linux the TCP
+//! sender reports retransmissions from `TCP_INFO`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simonlib::iperf3::{run_client, IperfConfig, IperfProtocol};
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = IperfConfig::default()
+//!     .with_protocol(IperfProtocol::Udp)
+//!     .with_duration(Duration::from_secs(5))
+//!     .with_bitrate(100_000_000);
+//!
+//! let result = run_client("iperf.example.com", 5201, &config)?;
+//! println!(
+//!     "{:.1} Mbit/s, {:.2}% lost",
+//!     result.received.bits_per_second / 1e6,
+//!     result.received.lost_percent.unwrap_or(0.0)
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ```no_run
+//! use simonlib::iperf3::IperfServer;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let server = IperfServer::bind("0.0.0.0:5201")?;
+//! loop {
+//!     let result = server.accept_test(|_| {})?;
+//!     println!("{}: {:.1} Mbit/s", result.peer, result.received.bits_per_second / 1e6);
+//! }
+//! # }
+//! ```
+
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub use crate::bandwidth::DEFAULT_PORT;
+
+/// Default TCP read/write size (iperf3's `DEFAULT_TCP_BLKSIZE`)
+pub const DEFAULT_TCP_LENGTH: usize = 128 * 1024;
+
+/// Default UDP datagram payload (iperf3's `DEFAULT_UDP_BLKSIZE`)
+pub const DEFAULT_UDP_LENGTH: usize = 1460;
+
+/// Default UDP target bitrate per stream in bits/s
+pub const DEFAULT_UDP_BITRATE: u64 = 1024 * 1024;
+
+/// Cookie length including the trailing NUL
+const COOKIE_SIZE: usize = 37;
+const COOKIE_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+// Control connection states, sent as a single signed byte
+const TEST_START: i8 = 1;
+const TEST_RUNNING: i8 = 2;
+const TEST_END: i8 = 4;
+const PARAM_EXCHANGE: i8 = 9;
+const CREATE_STREAMS: i8 = 10;
+const SERVER_TERMINATE: i8 = 11;
+const CLIENT_TERMINATE: i8 = 12;
+const EXCHANGE_RESULTS: i8 = 13;
+const DISPLAY_RESULTS: i8 = 14;
+const IPERF_START: i8 = 15;
+const IPERF_DONE: i8 = 16;
+const ACCESS_DENIED: i8 = -1;
+const SERVER_ERROR: i8 = -2;
+
+/// UDP stream hello and the server's answer, in host byte order as iperf3
+/// writes them
+const UDP_CONNECT_MSG: u32 = 0x3637_3839;
+const UDP_CONNECT_REPLY: u32 = 0x3938_3736;
+const LEGACY_UDP_CONNECT_REPLY: u32 = 987_654_321;
+
+/// UDP header: seconds, microseconds, then a 32- or 64-bit sequence number
+const UDP_HEADER_LEN: usize = 16;
+const MAX_UDP_LENGTH: usize = 65_507;
+const MAX_TCP_LENGTH: usize = 16 * 1024 * 1024;
+const MAX_JSON_LEN: usize = 1 << 20;
+
+/// How often blocked workers re-check the stop flag
+const POLL: Duration = Duration::from_millis(100);
+/// How long the server waits past the agreed duration for `TEST_END`
+const END_GRACE: Duration = Duration::from_secs(30);
+
+/// Transport used for the data streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IperfProtocol {
+    Tcp,
+    Udp,
+}
+
+/// Which end of the control connection this side was
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IperfRole {
+    Client,
+    Server,
+}
+
+/// Client test configuration
+#[derive(Debug, Clone)]
+pub struct IperfConfig {
+    /// Data stream transport
+    pub protocol: IperfProtocol,
+    /// Test duration
+    pub duration: Duration,
+    /// Number of parallel data streams
+    pub parallel: u32,
+    /// Server sends and client receives
+    pub reverse: bool,
+    /// Read/write size per call (None for the protocol default)
+    pub length: Option<usize>,
+    /// Target bitrate per stream in bits/s; None for the protocol default
+    /// (1 Mbit/s for UDP, unlimited for TCP), 0 for unlimited
+    pub bitrate: Option<u64>,
+    /// Interval report period
+    pub interval: Duration,
+    /// Control connection timeout
+    pub connect_timeout: Duration,
+}
+
+impl Default for IperfConfig {
+    fn default() -> Self {
+        Self {
+            protocol: IperfProtocol::Tcp,
+            duration: Duration::from_secs(crate::bandwidth::DEFAULT_DURATION_SECS),
+            parallel: 1,
+            reverse: false,
+            length: None,
+            bitrate: None,
+            interval: Duration::from_secs(1),
+            connect_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl IperfConfig {
+    /// Set data stream transport
+    pub fn with_protocol(mut self, protocol: IperfProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Set test duration
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Set number of parallel streams
+    pub fn with_parallel(mut self, streams: u32) -> Self {
+        self.parallel = streams.clamp(1, 128);
+        self
+    }
+
+    /// Set reverse mode (server sends)
+    pub fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Set read/write size
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Set target bitrate per stream in bits/s (0 for unlimited)
+    pub fn with_bitrate(mut self, bits_per_second: u64) -> Self {
+        self.bitrate = Some(bits_per_second);
+        self
+    }
+
+    /// Set interval report period
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set connection timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    fn settings(&self) -> Result<Settings> {
+        let length = self.length.unwrap_or(match self.protocol {
+            IperfProtocol::Tcp => DEFAULT_TCP_LENGTH,
+            IperfProtocol::Udp => DEFAULT_UDP_LENGTH,
+        });
+        let bitrate = self.bitrate.unwrap_or(match self.protocol {
+            IperfProtocol::Tcp => 0,
+            IperfProtocol::Udp => DEFAULT_UDP_BITRATE,
+        });
+        let settings = Settings {
+            protocol: self.protocol,
+            duration: self.duration,
+            parallel: self.parallel.clamp(1, 128),
+            reverse: self.reverse,
+            length,
+            bitrate,
+            counters_64bit: false,
+            interval: self.interval,
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+/// One side of a test (or one stream), as iperf3 reports `sum_sent` and
+/// `sum_received`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IperfSummary {
+    /// Bytes sent or received
+    pub bytes: u64,
+    /// Measurement period
+    pub seconds: f64,
+    /// Average throughput
+    pub bits_per_second: f64,
+    /// TCP retransmissions (sender only, when the OS reports them)
+    pub retransmits: Option<u64>,
+    /// UDP datagrams sent, or the highest sequence number received
+    pub packets: Option<u64>,
+    /// UDP datagrams lost (receiver only)
+    pub lost_packets: Option<u64>,
+    /// UDP loss percentage (receiver only)
+    pub lost_percent: Option<f64>,
+    /// UDP datagrams received out of order (receiver only, local side)
+    pub out_of_order: Option<u64>,
+    /// UDP jitter in milliseconds (receiver only)
+    pub jitter_ms: Option<f64>,
+}
+
+impl IperfSummary {
+    fn new(bytes: u64, seconds: f64) -> Self {
+        Self {
+            bytes,
+            seconds,
+            bits_per_second: if seconds > 0.0 {
+                bytes as f64 * 8.0 / seconds
+            } else {
+                0.0
+            },
+            ..Default::default()
+        }
+    }
+
+    fn with_loss(mut self, lost: u64, packets: u64) -> Self {
+        self.lost_packets = Some(lost);
+        self.packets = Some(packets);
+        self.lost_percent = Some(if packets > 0 {
+            lost as f64 * 100.0 / packets as f64
+        } else {
+            0.0
+        });
+        self
+    }
+
+    /// Sum streams: counters add up, jitter is averaged
+    fn sum(parts: &[&IperfSummary], seconds: f64) -> Self {
+        let mut total = Self::new(parts.iter().map(|p| p.bytes).sum(), seconds);
+        let add = |f: fn(&IperfSummary) -> Option<u64>| {
+            parts
+                .iter()
+                .filter_map(|p| f(p))
+                .fold(None, |acc: Option<u64>, v| Some(acc.unwrap_or(0) + v))
+        };
+        total.retransmits = add(|p| p.retransmits);
+        total.out_of_order = add(|p| p.out_of_order);
+        if let (Some(lost), Some(packets)) = (add(|p| p.lost_packets), add(|p| p.packets)) {
+            total = total.with_loss(lost, packets);
+        } else {
+            total.packets = add(|p| p.packets);
+        }
+        let jitters: Vec<f64> = parts.iter().filter_map(|p| p.jitter_ms).collect();
+        if !jitters.is_empty() {
+            total.jitter_ms = Some(jitters.iter().sum::<f64>() / jitters.len() as f64);
+        }
+        total
+    }
+}
+
+/// Final result of one stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IperfStreamResult {
+    /// iperf3 stream id (1, 3, 4, … for historical reasons)
+    pub id: u32,
+    /// Sender side
+    pub sent: IperfSummary,
+    /// Receiver side
+    pub received: IperfSummary,
+}
+
+/// Throughput over one reporting interval, measured locally across all
+/// streams
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IperfInterval {
+    /// Interval start, seconds since the transfer began
+    pub start_secs: f64,
+    /// Interval end
+    pub end_secs: f64,
+    /// Bytes moved by this side during the interval
+    pub bytes: u64,
+    /// Throughput during the interval
+    pub bits_per_second: f64,
+    /// UDP datagrams sent or expected during the interval
+    pub packets: Option<u64>,
+    /// UDP datagrams lost during the interval (receiving side)
+    pub lost_packets: Option<u64>,
+    /// UDP jitter at the end of the interval (receiving side)
+    pub jitter_ms: Option<f64>,
+}
+
+/// Complete test result from one side's point of view
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IperfResult {
+    /// Whether this side ran the client or the server
+    pub role: IperfRole,
+    /// Remote address of the control connection
+    pub peer: String,
+    /// Server port
+    pub port: u16,
+    /// Data stream transport
+    pub protocol: IperfProtocol,
+    /// Whether the server sent and the client received
+    pub reverse: bool,
+    /// Number of data streams
+    pub parallel: u32,
+    /// Read/write size
+    pub length: usize,
+    /// Measured transfer time in seconds
+    pub duration_secs: f64,
+    /// Whether this side sent the data
+    pub local_sender: bool,
+    /// Interval reports measured on this side
+    pub intervals: Vec<IperfInterval>,
+    /// Per-stream results combining both sides
+    pub streams: Vec<IperfStreamResult>,
+    /// Totals reported by the sender
+    pub sent: IperfSummary,
+    /// Totals reported by the receiver
+    pub received: IperfSummary,
+    /// This process's CPU utilization during the test, in percent
+    pub local_cpu_percent: f64,
+    /// The peer's reported CPU utilization, in percent
+    pub remote_cpu_percent: Option<f64>,
+}
+
+/// Parse an iperf3-style bitrate such as `100M`, `1.5G` or `800k` into
+/// bits/s (decimal multipliers, as iperf3 uses for rates)
+pub fn parse_bitrate(text: &str) -> Result<u64> {
+    let text = text.trim();
+    let (number, multiplier) = match text.chars().last() {
+        Some('k' | 'K') => (&text[..text.len() - 1], 1e3),
+        Some('m' | 'M') => (&text[..text.len() - 1], 1e6),
+        Some('g' | 'G') => (&text[..text.len() - 1], 1e9),
+        Some('t' | 'T') => (&text[..text.len() - 1], 1e12),
+        _ => (text, 1.0),
+    };
+    match number.parse::<f64>() {
+        Ok(value) if value >= 0.0 && value.is_finite() => Ok((value * multiplier) as u64),
+        _ => Err(SimonError::InvalidArgument(format!(
+            "Invalid bitrate '{}'",
+            text
+        ))),
+    }
+}
+
+/// Run an iperf3 client test against `host:port`
+pub fn run_client(host: &str, port: u16, config: &IperfConfig) -> Result<IperfResult> {
+    run_client_with(host, port, config, |_| {})
+}
+
+/// Run an iperf3 client test, calling `on_interval` as each interval report
+/// completes
+pub fn run_client_with<F: FnMut(&IperfInterval)>(
+    host: &str,
+    port: u16,
+    config: &IperfConfig,
+    mut on_interval: F,
+) -> Result<IperfResult> {
+    let settings = config.settings()?;
+    let target = format!("{}:{}", host, port);
+    let addr = target
+        .to_socket_addrs()
+        .map_err(|e| SimonError::Network(format!("Failed to resolve {}: {}", target, e)))?
+        .next()
+        .ok_or_else(|| SimonError::Network(format!("No addresses found for {}", target)))?;
+
+    let mut control = TcpStream::connect_timeout(&addr, config.connect_timeout)
+        .map_err(|e| SimonError::Network(format!("Failed to connect to {}: {}", addr, e)))?;
+    control.set_nodelay(true)?;
+    let cookie = make_cookie();
+    control.write_all(&cookie)?;
+
+    let state_timeout = config.connect_timeout.max(END_GRACE);
+    let mut streams = Vec::new();
+    let mut run = None;
+    let mut outcome = None;
+    loop {
+        let state = match wait_state(&mut control, state_timeout)? {
+            Some(state) => state,
+            None => {
+                return Err(SimonError::Network(
+                    "iperf3 server stopped responding".into(),
+                ))
+            }
+        };
+        match state {
+            PARAM_EXCHANGE => write_json(&mut control, &settings.to_params())?,
+            CREATE_STREAMS => streams = connect_streams(addr, &cookie, &settings)?,
+            TEST_START | IPERF_START => {}
+            TEST_RUNNING => {
+                let streams = std::mem::take(&mut streams);
+                run = Some(run_transfer(
+                    &mut control,
+                    IperfRole::Client,
+                    &settings,
+                    streams,
+                    &mut on_interval,
+                )?);
+            }
+            EXCHANGE_RESULTS => {
+                let run = run
+                    .as_ref()
+                    .ok_or_else(|| SimonError::Network("results requested before test".into()))?;
+                write_json(&mut control, &run.results_json())?;
+                outcome = Some(read_json(&mut control)?);
+            }
+            DISPLAY_RESULTS => {
+                write_state(&mut control, IPERF_DONE)?;
+                break;
+            }
+            other => return Err(state_error(&mut control, other)),
+        }
+    }
+
+    let (run, peer) = run
+        .zip(outcome)
+        .ok_or_else(|| SimonError::Network("iperf3 server ended the test early".into()))?;
+    run.into_result(IperfRole::Client, addr, port, &settings, &peer)
+}
+
+/// iperf3-compatible server on one TCP (and, for UDP tests, UDP) port
+///
+/// Tests are served one at a time. A connection that arrives while the data
+/// streams are being set up and does not carry the test's cookie is refused
+/// with `ACCESS_DENIED`, as iperf3 does.
+pub struct IperfServer {
+    listener: TcpListener,
+    interval: Duration,
+}
+
+impl IperfServer {
+    /// Listen on `addr` (usually port [`DEFAULT_PORT`])
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| SimonError::Network(format!("Failed to listen: {}", e)))?;
+        Ok(Self {
+            listener,
+            interval: Duration::from_secs(1),
+        })
+    }
+
+    /// Set interval report period
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Wait for a client and run one test
+    pub fn accept_test<F: FnMut(&IperfInterval)>(&self, mut on_interval: F) -> Result<IperfResult> {
+        self.listener.set_nonblocking(false)?;
+        let (mut control, peer) = self.listener.accept()?;
+        control.set_nonblocking(false)?;
+        control.set_nodelay(true)?;
+        control.set_read_timeout(Some(END_GRACE))?;
+
+        let mut cookie = [0u8; COOKIE_SIZE];
+        control.read_exact(&mut cookie)?;
+
+        write_state(&mut control, PARAM_EXCHANGE)?;
+        let params = read_json(&mut control)?;
+        let settings = match Settings::from_params(&params, self.interval) {
+            Ok(settings) => settings,
+            Err(e) => {
+                let _ = send_server_error(&mut control);
+                return Err(e);
+            }
+        };
+
+        let udp = match settings.protocol {
+            IperfProtocol::Udp => Some(Arc::new(UdpSocket::bind(self.listener.local_addr()?)?)),
+            IperfProtocol::Tcp => None,
+        };
+        write_state(&mut control, CREATE_STREAMS)?;
+        let streams = match udp {
+            Some(socket) => accept_udp_streams(socket, settings.parallel),
+            None => self.accept_tcp_streams(&cookie, settings.parallel),
+        };
+        let streams = match streams {
+            Ok(streams) => streams,
+            Err(e) => {
+                let _ = send_server_error(&mut control);
+                return Err(e);
+            }
+        };
+
+        write_state(&mut control, TEST_START)?;
+        write_state(&mut control, TEST_RUNNING)?;
+        let run = run_transfer(
+            &mut control,
+            IperfRole::Server,
+            &settings,
+            streams,
+            &mut on_interval,
+        )?;
+
+        write_state(&mut control, EXCHANGE_RESULTS)?;
+        let client_results = read_json(&mut control)?;
+        write_json(&mut control, &run.results_json())?;
+        write_state(&mut control, DISPLAY_RESULTS)?;
+        // The client answers IPERF_DONE and closes; either is fine
+        let _ = wait_state(&mut control, Duration::from_secs(5));
+
+        let port = self.listener.local_addr()?.port();
+        run.into_result(IperfRole::Server, peer, port, &settings, &client_results)
+    }
+
+    fn accept_tcp_streams(&self, cookie: &[u8], count: u32) -> Result<Vec<DataStream>> {
+        let deadline = Instant::now() + END_GRACE;
+        let mut streams = Vec::new();
+        self.listener.set_nonblocking(true)?;
+        while streams.len() < count as usize {
+            let mut stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        break;
+                    }
+                    thread::sleep(Duration::from_millis(5));
+                    continue;
+                }
+                Err(e) => {
+                    self.listener.set_nonblocking(false)?;
+                    return Err(e.into());
+                }
+            };
+            stream.set_nonblocking(false)?;
+            stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+            let mut theirs = [0u8; COOKIE_SIZE];
+            if stream.read_exact(&mut theirs).is_ok() && theirs[..] == *cookie {
+                streams.push(DataStream::Tcp(stream));
+            } else {
+                // Another client's control connection while we are busy
+                let _ = stream.write_all(&[ACCESS_DENIED as u8]);
+            }
+        }
+        self.listener.set_nonblocking(false)?;
+        if streams.len() < count as usize {
+            return Err(SimonError::Network(format!(
+                "Only {} of {} data streams connected",
+                streams.len(),
+                count
+            )));
+        }
+        Ok(streams)
+    }
+}
+
+/// Parameters both sides agree on during `PARAM_EXCHANGE`
+#[derive(Debug, Clone)]
+struct Settings {
+    protocol: IperfProtocol,
+    duration: Duration,
+    parallel: u32,
+    reverse: bool,
+    length: usize,
+    bitrate: u64,
+    counters_64bit: bool,
+    interval: Duration,
+}
+
+impl Settings {
+    /// iperf3 only checks whether the `tcp`/`udp`/`reverse` keys exist, so
+    /// false flags are left out rather than sent as `false`
+    fn to_params(&self) -> Value {
+        let mut params = json!({
+            "omit": 0,
+            "time": self.duration.as_secs().max(1),
+            "num": 0,
+            "blockcount": 0,
+            "parallel": self.parallel,
+            "len": self.length,
+            "bandwidth": self.bitrate,
+            "pacing_timer": 1000,
+            "client_version": concat!("simon ", env!("CARGO_PKG_VERSION")),
+        });
+        let protocol = match self.protocol {
+            IperfProtocol::Tcp => "tcp",
+            IperfProtocol::Udp => "udp",
+        };
+        params[protocol] = json!(true);
+        if self.reverse {
+            params["reverse"] = json!(true);
+        }
+        params
+    }
+
+    fn from_params(params: &Value, interval: Duration) -> Result<Self> {
+        let num = |key: &str| params.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+        let protocol = if params.get("udp").is_some() {
+            IperfProtocol::Udp
+        } else if params.get("tcp").is_some() {
+            IperfProtocol::Tcp
+        } else {
+            return Err(SimonError::NotImplemented(
+                "iperf3 client requested an unsupported protocol".into(),
+            ));
+        };
+        if params.get("bidirectional").is_some() {
+            return Err(SimonError::NotImplemented(
+                "iperf3 bidirectional mode is not supported".into(),
+            ));
+        }
+        if num("num") > 0.0 || num("blockcount") > 0.0 {
+            return Err(SimonError::NotImplemented(
+                "iperf3 byte- and block-count limited tests are not supported".into(),
+            ));
+        }
+        let time = num("time");
+        let length = num("len") as usize;
+        let settings = Self {
+            protocol,
+            duration: Duration::from_secs_f64(if time > 0.0 { time } else { 10.0 }),
+            parallel: (num("parallel") as u32).max(1),
+            reverse: params.get("reverse").is_some(),
+            length: if length > 0 {
+                length
+            } else if protocol == IperfProtocol::Udp {
+                DEFAULT_UDP_LENGTH
+            } else {
+                DEFAULT_TCP_LENGTH
+            },
+            bitrate: num("bandwidth") as u64,
+            counters_64bit: num("udp_counters_64bit") > 0.0,
+            interval,
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let (min, max) = match self.protocol {
+            IperfProtocol::Tcp => (1, MAX_TCP_LENGTH),
+            IperfProtocol::Udp => (UDP_HEADER_LEN, MAX_UDP_LENGTH),
+        };
+        if !(min..=max).contains(&self.length) {
+            return Err(SimonError::InvalidArgument(format!(
+                "Block length {} outside {}..={}",
+                self.length, min, max
+            )));
+        }
+        if self.parallel > 128 {
+            return Err(SimonError::InvalidArgument(format!(
+                "Too many parallel streams: {}",
+                self.parallel
+            )));
+        }
+        Ok(())
+    }
+
+    fn local_sender(&self, role: IperfRole) -> bool {
+        (role == IperfRole::Client) != self.reverse
+    }
+}
+
+/// Stream ids as iperf3 assigns them: 1, then 3, 4, 5, …
+fn stream_id(index: usize) -> u32 {
+    if index == 0 {
+        1
+    } else {
+        index as u32 + 2
+    }
+}
+
+fn make_cookie() -> [u8; COOKIE_SIZE] {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let state = RandomState::new();
+    let mut cookie = [0u8; COOKIE_SIZE];
+    for (i, c) in cookie[..COOKIE_SIZE - 1].iter_mut().enumerate() {
+        let mut hasher = state.build_hasher();
+        hasher.write_u128(seed);
+        hasher.write_usize(i);
+        *c = COOKIE_CHARS[(hasher.finish() % COOKIE_CHARS.len() as u64) as usize];
+    }
+    cookie
+}
+
+fn write_state(control: &mut TcpStream, state: i8) -> io::Result<()> {
+    control.write_all(&[state as u8])
+}
+
+/// Read one state byte, or None if nothing arrives within `timeout`
+fn wait_state(control: &mut TcpStream, timeout: Duration) -> io::Result<Option<i8>> {
+    control.set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+    let mut byte = [0u8; 1];
+    match control.read(&mut byte) {
+        Ok(0) => Err(ErrorKind::UnexpectedEof.into()),
+        Ok(_) => Ok(Some(byte[0] as i8)),
+        Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// JSON messages are prefixed with their length as a big-endian u32
+fn write_json(control: &mut TcpStream, value: &Value) -> Result<()> {
+    let text = serde_json::to_vec(value)?;
+    let mut message = (text.len() as u32).to_be_bytes().to_vec();
+    message.extend_from_slice(&text);
+    control.write_all(&message)?;
+    Ok(())
+}
+
+fn read_json(control: &mut TcpStream) -> Result<Value> {
+    control.set_read_timeout(Some(END_GRACE))?;
+    let mut len = [0u8; 4];
+    control.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_JSON_LEN {
+        return Err(SimonError::Parse(format!(
+            "iperf3 JSON message too large: {} bytes",
+            len
+        )));
+    }
+    let mut text = vec![0u8; len];
+    control.read_exact(&mut text)?;
+    Ok(serde_json::from_slice(&text)?)
+}
+
+/// `SERVER_ERROR` is followed by iperf3's error code and errno
+fn send_server_error(control: &mut TcpStream) -> io::Result<()> {
+    let mut message = vec![SERVER_ERROR as u8];
+    message.extend_from_slice(&1i32.to_be_bytes());
+    message.extend_from_slice(&0i32.to_be_bytes());
+    control.write_all(&message)
+}
+
+fn state_error(control: &mut TcpStream, state: i8) -> SimonError {
+    match state {
+        ACCESS_DENIED => SimonError::Network("iperf3 server is busy running a test".into()),
+        SERVER_ERROR => {
+            let mut codes = [0u8; 8];
+            match control.read_exact(&mut codes) {
+                Ok(()) => SimonError::Network(format!(
+                    "iperf3 server error {} (errno {})",
+                    i32::from_be_bytes([codes[0], codes[1], codes[2], codes[3]]),
+                    i32::from_be_bytes([codes[4], codes[5], codes[6], codes[7]])
+                )),
+                Err(_) => SimonError::Network("iperf3 server error".into()),
+            }
+        }
+        SERVER_TERMINATE => SimonError::Network("iperf3 server terminated the test".into()),
+        CLIENT_TERMINATE => SimonError::Network("iperf3 client terminated the test".into()),
+        IPERF_DONE => SimonError::Network("iperf3 peer closed the test early".into()),
+        other => SimonError::Parse(format!("Unexpected iperf3 control state {}", other)),
+    }
+}
+
+/// One data stream's socket
+enum DataStream {
+    Tcp(TcpStream),
+    /// UDP streams are connected on the client; the server shares one
+    /// socket and addresses each client stream explicitly
+    Udp {
+        socket: Arc<UdpSocket>,
+        peer: Option<SocketAddr>,
+    },
+}
+
+fn connect_streams(
+    addr: SocketAddr,
+    cookie: &[u8],
+    settings: &Settings,
+) -> Result<Vec<DataStream>> {
+    (0..settings.parallel)
+        .map(|_| match settings.protocol {
+            IperfProtocol::Tcp => {
+                let mut stream = TcpStream::connect_timeout(&addr, END_GRACE)?;
+                stream.write_all(cookie)?;
+                Ok(DataStream::Tcp(stream))
+            }
+            IperfProtocol::Udp => {
+                let local: SocketAddr = if addr.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    ([0u16; 8], 0).into()
+                };
+                let socket = UdpSocket::bind(local)?;
+                socket.connect(addr)?;
+                socket.set_read_timeout(Some(Duration::from_secs(1)))?;
+                udp_handshake(&socket)?;
+                Ok(DataStream::Udp {
+                    socket: Arc::new(socket),
+                    peer: None,
+                })
+            }
+        })
+        .collect()
+}
+
+fn udp_handshake(socket: &UdpSocket) -> Result<()> {
+    let mut reply = [0u8; 4];
+    for _ in 0..5 {
+        socket.send(&UDP_CONNECT_MSG.to_ne_bytes())?;
+        match socket.recv(&mut reply) {
+            Ok(4) => {
+                let value = u32::from_ne_bytes(reply);
+                if value == UDP_CONNECT_REPLY || value == LEGACY_UDP_CONNECT_REPLY {
+                    return Ok(());
+                }
+                return Err(SimonError::Parse(format!(
+                    "Unexpected iperf3 UDP connect reply {:#x}",
+                    value
+                )));
+            }
+            Ok(_) => continue,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(SimonError::Network(
+        "iperf3 server did not answer the UDP stream handshake".into(),
+    ))
+}
+
+fn accept_udp_streams(socket: Arc<UdpSocket>, count: u32) -> Result<Vec<DataStream>> {
+    let deadline = Instant::now() + END_GRACE;
+    socket.set_read_timeout(Some(POLL))?;
+    let mut peers: Vec<SocketAddr> = Vec::new();
+    let mut buf = [0u8; 64];
+    while peers.len() < count as usize && Instant::now() < deadline {
+        let peer = match socket.recv_from(&mut buf) {
+            Ok((_, peer)) => peer,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        // Retransmitted hellos get another reply but no second stream
+        if !peers.contains(&peer) {
+            peers.push(peer);
+        }
+        socket.send_to(&UDP_CONNECT_REPLY.to_ne_bytes(), peer)?;
+    }
+    if peers.len() < count as usize {
+        return Err(SimonError::Network(format!(
+            "Only {} of {} UDP streams connected",
+            peers.len(),
+            count
+        )));
+    }
+    Ok(peers
+        .into_iter()
+        .map(|peer| DataStream::Udp {
+            socket: socket.clone(),
+            peer: Some(peer),
+        })
+        .collect())
+}
+
+/// Loss, reordering and jitter bookkeeping for one UDP stream
+#[derive(Debug, Clone, Copy, Default)]
+struct UdpStats {
+    /// Datagrams sent, or the highest sequence number received
+    packets: u64,
+    lost: u64,
+    out_of_order: u64,
+    /// Smoothed jitter in seconds
+    jitter: f64,
+    prev_transit: Option<f64>,
+}
+
+impl UdpStats {
+    /// Account one received datagram the way iperf3 does: a gap counts as
+    /// loss, a late arrival cancels one loss, and jitter follows RFC 1889
+    fn on_datagram(&mut self, datagram: &[u8], counters_64bit: bool, arrival: f64) {
+        if datagram.len() < if counters_64bit { 16 } else { 12 } {
+            return;
+        }
+        let be32 = |at: usize| {
+            u32::from_be_bytes([
+                datagram[at],
+                datagram[at + 1],
+                datagram[at + 2],
+                datagram[at + 3],
+            ])
+        };
+        let sent = f64::from(be32(0)) + f64::from(be32(4)) / 1e6;
+        let seq = if counters_64bit {
+            (u64::from(be32(8)) << 32) | u64::from(be32(12))
+        } else {
+            u64::from(be32(8))
+        };
+
+        if seq > self.packets {
+            self.lost += seq - self.packets - 1;
+            self.packets = seq;
+        } else {
+            self.out_of_order += 1;
+            self.lost = self.lost.saturating_sub(1);
+        }
+
+        let transit = arrival - sent;
+        if let Some(prev) = self.prev_transit {
+            self.jitter += ((transit - prev).abs() - self.jitter) / 16.0;
+        }
+        self.prev_transit = Some(transit);
+    }
+}
+
+#[derive(Default)]
+struct StreamCounters {
+    bytes: AtomicU64,
+    udp: Mutex<UdpStats>,
+}
+
+impl StreamCounters {
+    fn udp(&self) -> UdpStats {
+        *self.udp.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Keeps a sender at or below the target bitrate (0 for unlimited)
+struct Pacer {
+    start: Instant,
+    bitrate: u64,
+    sent_bits: f64,
+}
+
+impl Pacer {
+    fn new(bitrate: u64) -> Self {
+        Self {
+            start: Instant::now(),
+            bitrate,
+            sent_bits: 0.0,
+        }
+    }
+
+    /// How long to wait before the next write may go out
+    fn delay(&self) -> Option<Duration> {
+        if self.bitrate == 0 {
+            return None;
+        }
+        let ahead = self.sent_bits / self.bitrate as f64 - self.start.elapsed().as_secs_f64();
+        (ahead > 0.0).then(|| Duration::from_secs_f64(ahead).min(POLL))
+    }
+
+    fn sent(&mut self, bytes: usize) {
+        self.sent_bits += bytes as f64 * 8.0;
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+    )
+}
+
+/// Total TCP retransmissions on a sending socket
+#[cfg(target_os = "linux")]
+fn tcp_retransmits(stream: &TcpStream) -> Option<u64> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut buf = [0u8; 256];
+    let mut len = buf.len() as libc::socklen_t;
+    // SAFETY: buf is valid for len bytes and the kernel writes at most len
+    let rc = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::IPPROTO_TCP,
+            libc::TCP_INFO,
+            buf.as_mut_ptr().cast(),
+            &mut len,
+        )
+    };
+    if rc != 0 {
+        return None;
+    }
+    crate::sock_diag::TcpInfo::parse(&buf[..len as usize]).map(|info| u64::from(info.total_retrans))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn tcp_retransmits(_stream: &TcpStream) -> Option<u64> {
+    None
+}
+
+fn tcp_send(
+    mut stream: TcpStream,
+    length: usize,
+    bitrate: u64,
+    counters: &StreamCounters,
+    stop: &AtomicBool,
+) -> Option<u64> {
+    let buf = vec![0u8; length];
+    let mut pacer = Pacer::new(bitrate);
+    let _ = stream.set_write_timeout(Some(POLL));
+    while !stop.load(Ordering::Relaxed) {
+        if let Some(delay) = pacer.delay() {
+            thread::sleep(delay);
+            continue;
+        }
+        match stream.write(&buf) {
+            Ok(n) => {
+                counters.bytes.fetch_add(n as u64, Ordering::Relaxed);
+                pacer.sent(n);
+            }
+            Err(e) if is_timeout(&e) => {}
+            Err(_) => break,
+        }
+    }
+    tcp_retransmits(&stream)
+}
+
+fn tcp_receive(mut stream: TcpStream, length: usize, counters: &StreamCounters, stop: &AtomicBool) {
+    let mut buf = vec![0u8; length];
+    let _ = stream.set_read_timeout(Some(POLL));
+    while !stop.load(Ordering::Relaxed) {
+        match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                counters.bytes.fetch_add(n as u64, Ordering::Relaxed);
+            }
+            Err(e) if is_timeout(&e) => {}
+            Err(_) => break,
+        }
+    }
+}
+
+fn udp_send(
+    socket: &UdpSocket,
+    peer: Option<SocketAddr>,
+    settings: &Settings,
+    counters: &StreamCounters,
+    stop: &AtomicBool,
+) {
+    let mut buf = vec![0u8; settings.length];
+    let mut pacer = Pacer::new(settings.bitrate);
+    let mut seq = 0u64;
+    let _ = socket.set_write_timeout(Some(POLL));
+    while !stop.load(Ordering::Relaxed) {
+        if let Some(delay) = pacer.delay() {
+            thread::sleep(delay);
+            continue;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        seq += 1;
+        buf[0..4].copy_from_slice(&(now.as_secs() as u32).to_be_bytes());
+        buf[4..8].copy_from_slice(&now.subsec_micros().to_be_bytes());
+        if settings.counters_64bit {
+            buf[8..16].copy_from_slice(&seq.to_be_bytes());
+        } else {
+            buf[8..12].copy_from_slice(&(seq as u32).to_be_bytes());
+        }
+        let sent = match peer {
+            Some(peer) => socket.send_to(&buf, peer),
+            None => socket.send(&buf),
+        };
+        match sent {
+            Ok(n) => {
+                counters.bytes.fetch_add(n as u64, Ordering::Relaxed);
+                counters
+                    .udp
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .packets = seq;
+                pacer.sent(n);
+            }
+            // ENOBUFS and friends: the datagram never left, so reuse its number
+            Err(_) => seq -= 1,
+        }
+    }
+}
+
+/// Receive on one UDP socket. A connected client socket feeds a single
+/// stream; the server socket routes datagrams by source address.
+fn udp_receive(
+    socket: &UdpSocket,
+    routes: &[(Option<SocketAddr>, Arc<StreamCounters>)],
+    settings: &Settings,
+    stop: &AtomicBool,
+) {
+    let mut buf = vec![0u8; settings.length.max(UDP_HEADER_LEN)];
+    let _ = socket.set_read_timeout(Some(POLL));
+    while !stop.load(Ordering::Relaxed) {
+        let (n, from) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e) if is_timeout(&e) => continue,
+            Err(_) => break,
+        };
+        let arrival = unix_now();
+        let route = if routes.len() == 1 && routes[0].0.is_none() {
+            routes.first()
+        } else {
+            routes.iter().find(|(peer, _)| *peer == Some(from))
+        };
+        if let Some((_, counters)) = route {
+            counters.bytes.fetch_add(n as u64, Ordering::Relaxed);
+            counters
+                .udp
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .on_datagram(&buf[..n], settings.counters_64bit, arrival);
+        }
+    }
+}
+
+/// Start one worker per stream (one per socket for UDP receivers); each
+/// returns the stream's TCP retransmissions where known
+fn spawn_workers(
+    streams: Vec<DataStream>,
+    settings: &Settings,
+    sender: bool,
+    counters: &[Arc<StreamCounters>],
+    stop: &Arc<AtomicBool>,
+) -> Vec<(Option<usize>, JoinHandle<Option<u64>>)> {
+    let mut workers = Vec::new();
+    let mut udp_routes: Vec<(Option<SocketAddr>, Arc<StreamCounters>)> = Vec::new();
+    let mut shared_socket = None;
+
+    for (index, stream) in streams.into_iter().enumerate() {
+        let counters = counters[index].clone();
+        let stop = stop.clone();
+        let settings = settings.clone();
+        match stream {
+            DataStream::Tcp(stream) => {
+                let handle = thread::spawn(move || {
+                    if sender {
+                        tcp_send(stream, settings.length, settings.bitrate, &counters, &stop)
+                    } else {
+                        tcp_receive(stream, settings.length, &counters, &stop);
+                        None
+                    }
+                });
+                workers.push((Some(index), handle));
+            }
+            DataStream::Udp { socket, peer } if sender => {
+                let handle = thread::spawn(move || {
+                    udp_send(&socket, peer, &settings, &counters, &stop);
+                    None
+                });
+                workers.push((Some(index), handle));
+            }
+            DataStream::Udp {
+                socket,
+                peer: Some(peer),
+            } => {
+                shared_socket = Some(socket);
+                udp_routes.push((Some(peer), counters));
+            }
+            DataStream::Udp { socket, peer: None } => {
+                let handle = thread::spawn(move || {
+                    udp_receive(&socket, &[(None, counters)], &settings, &stop);
+                    None
+                });
+                workers.push((Some(index), handle));
+            }
+        }
+    }
+
+    if let Some(socket) = shared_socket {
+        let stop = stop.clone();
+        let settings = settings.clone();
+        let handle = thread::spawn(move || {
+            udp_receive(&socket, &udp_routes, &settings, &stop);
+            None
+        });
+        workers.push((None, handle));
+    }
+    workers
+}
+
+/// Turns counter snapshots into interval reports
+struct IntervalSampler {
+    start: Instant,
+    last_at: f64,
+    last: Vec<(u64, UdpStats)>,
+    udp: bool,
+    sender: bool,
+    intervals: Vec<IperfInterval>,
+}
+
+impl IntervalSampler {
+    fn new(streams: usize, settings: &Settings, sender: bool) -> Self {
+        Self {
+            start: Instant::now(),
+            last_at: 0.0,
+            last: vec![(0, UdpStats::default()); streams],
+            udp: settings.protocol == IperfProtocol::Udp,
+            sender,
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Close the interval ending `now` seconds into the transfer
+    fn sample(&mut self, counters: &[Arc<StreamCounters>], now: f64) -> Option<&IperfInterval> {
+        let span = now - self.last_at;
+        if span < 0.001 {
+            return None;
+        }
+        let (mut bytes, mut packets, mut lost, mut jitter) = (0, 0, 0, 0.0);
+        for (counter, last) in counters.iter().zip(self.last.iter_mut()) {
+            let total = counter.bytes.load(Ordering::Relaxed);
+            let udp = counter.udp();
+            bytes += total - last.0;
+            packets += udp.packets.saturating_sub(last.1.packets);
+            lost += udp.lost.saturating_sub(last.1.lost);
+            jitter += udp.jitter;
+            *last = (total, udp);
+        }
+        let receiving_udp = self.udp && !self.sender;
+        self.intervals.push(IperfInterval {
+            start_secs: self.last_at,
+            end_secs: now,
+            bytes,
+            bits_per_second: bytes as f64 * 8.0 / span,
+            packets: self.udp.then_some(packets),
+            lost_packets: receiving_udp.then_some(lost),
+            jitter_ms: receiving_udp.then(|| jitter / counters.len().max(1) as f64 * 1000.0),
+        });
+        self.last_at = now;
+        self.intervals.last()
+    }
+}
+
+/// Per-stream totals from one side
+#[derive(Debug, Clone, Default)]
+struct SideStream {
+    id: u32,
+    bytes: u64,
+    retransmits: Option<u64>,
+    udp: UdpStats,
+    seconds: Option<f64>,
+}
+
+/// Everything measured locally during the transfer
+struct TransferRun {
+    sender: bool,
+    udp: bool,
+    duration_secs: f64,
+    streams: Vec<SideStream>,
+    intervals: Vec<IperfInterval>,
+    cpu: CpuUsage,
+}
+
+/// Move data until the test ends: the client ends it after the configured
+/// duration by sending `TEST_END`, the server when `TEST_END` arrives
+fn run_transfer<F: FnMut(&IperfInterval)>(
+    control: &mut TcpStream,
+    role: IperfRole,
+    settings: &Settings,
+    streams: Vec<DataStream>,
+    on_interval: &mut F,
+) -> Result<TransferRun> {
+    let sender = settings.local_sender(role);
+    let count = streams.len();
+    let counters: Vec<Arc<StreamCounters>> = (0..count).map(|_| Arc::default()).collect();
+    let stop = Arc::new(AtomicBool::new(false));
+    let cpu = CpuClock::start();
+    let mut sampler = IntervalSampler::new(count, settings, sender);
+    let workers = spawn_workers(streams, settings, sender, &counters, &stop);
+
+    let interval = settings.interval.max(Duration::from_millis(10));
+    let end = match role {
+        IperfRole::Client => sampler.start + settings.duration,
+        IperfRole::Server => sampler.start + settings.duration + END_GRACE,
+    };
+    let mut next_sample = sampler.start + interval;
+    let outcome = loop {
+        let now = Instant::now();
+        if now >= end {
+            break match role {
+                IperfRole::Client => Ok(()),
+                IperfRole::Server => Err(SimonError::Network(
+                    "iperf3 client never ended the test".into(),
+                )),
+            };
+        }
+        if now >= next_sample {
+            let elapsed = sampler.start.elapsed().as_secs_f64();
+            if let Some(report) = sampler.sample(&counters, elapsed) {
+                on_interval(report);
+            }
+            next_sample += interval;
+            continue;
+        }
+        match wait_state(control, next_sample.min(end) - now) {
+            Ok(None) => {}
+            Ok(Some(TEST_END)) if role == IperfRole::Server => break Ok(()),
+            Ok(Some(state)) => break Err(state_error(control, state)),
+            Err(e) => break Err(e.into()),
+        }
+    };
+
+    stop.store(true, Ordering::Relaxed);
+    let duration_secs = sampler.start.elapsed().as_secs_f64();
+    if role == IperfRole::Client && outcome.is_ok() {
+        write_state(control, TEST_END)?;
+    }
+    let mut retransmits = vec![None; count];
+    for (index, worker) in workers {
+        let value = worker.join().unwrap_or(None);
+        if let Some(index) = index {
+            retransmits[index] = value;
+        }
+    }
+    outcome?;
+
+    // Data still in flight when the workers stopped belongs to the last
+    // interval, not to a later one
+    if let Some(report) = sampler.sample(&counters, duration_secs) {
+        on_interval(report);
+    }
+    let streams = counters
+        .iter()
+        .zip(retransmits)
+        .enumerate()
+        .map(|(index, (counter, retransmits))| SideStream {
+            id: stream_id(index),
+            bytes: counter.bytes.load(Ordering::Relaxed),
+            retransmits,
+            udp: counter.udp(),
+            seconds: Some(duration_secs),
+        })
+        .collect();
+
+    Ok(TransferRun {
+        sender,
+        udp: settings.protocol == IperfProtocol::Udp,
+        duration_secs,
+        streams,
+        intervals: sampler.intervals,
+        cpu: cpu.usage(),
+    })
+}
+
+impl TransferRun {
+    fn results_json(&self) -> Value {
+        let has_retransmits = self.sender && self.streams.iter().all(|s| s.retransmits.is_some());
+        let streams: Vec<Value> = self
+            .streams
+            .iter()
+            .map(|s| {
+                json!({
+                    "id": s.id,
+                    "bytes": s.bytes,
+                    "retransmits": match s.retransmits {
+                        Some(r) if has_retransmits => r as i64,
+                        _ => -1,
+                    },
+                    "jitter": s.udp.jitter,
+                    "errors": s.udp.lost,
+                    "packets": s.udp.packets,
+                    "start_time": 0.0,
+                    "end_time": self.duration_secs,
+                })
+            })
+            .collect();
+        json!({
+            "cpu_util_total": self.cpu.total,
+            "cpu_util_user": self.cpu.user,
+            "cpu_util_system": self.cpu.system,
+            "sender_has_retransmits": i32::from(has_retransmits),
+            "streams": streams,
+        })
+    }
+
+    fn into_result(
+        self,
+        role: IperfRole,
+        peer: SocketAddr,
+        port: u16,
+        settings: &Settings,
+        peer_results: &Value,
+    ) -> Result<IperfResult> {
+        let remote = parse_results(peer_results)?;
+        let summarize = |side: &SideStream, sender: bool| {
+            let seconds = side.seconds.unwrap_or(self.duration_secs);
+            let mut summary = IperfSummary::new(side.bytes, seconds);
+            if sender {
+                summary.retransmits = side.retransmits;
+                if self.udp {
+                    summary.packets = Some(side.udp.packets);
+                }
+            } else if self.udp {
+                summary = summary.with_loss(side.udp.lost, side.udp.packets);
+                summary.jitter_ms = Some(side.udp.jitter * 1000.0);
+            }
+            summary
+        };
+
+        let streams: Vec<IperfStreamResult> = self
+            .streams
+            .iter()
+            .map(|local| {
+                let fallback = SideStream {
+                    id: local.id,
+                    ..Default::default()
+                };
+                let other = remote
+                    .iter()
+                    .find(|r| r.id == local.id)
+                    .unwrap_or(&fallback);
+                let mut local_summary = summarize(local, self.sender);
+                // Reordering is only tracked locally, not exchanged
+                if self.udp && !self.sender {
+                    local_summary.out_of_order = Some(local.udp.out_of_order);
+                }
+                let other_summary = summarize(other, !self.sender);
+                let (sent, received) = if self.sender {
+                    (local_summary, other_summary)
+                } else {
+                    (other_summary, local_summary)
+                };
+                IperfStreamResult {
+                    id: local.id,
+                    sent,
+                    received,
+                }
+            })
+            .collect();
+
+        let sent: Vec<&IperfSummary> = streams.iter().map(|s| &s.sent).collect();
+        let received: Vec<&IperfSummary> = streams.iter().map(|s| &s.received).collect();
+        let period = |parts: &[&IperfSummary]| {
+            parts
+                .iter()
+                .map(|p| p.seconds)
+                .fold(0.0, f64::max)
+                .max(f64::EPSILON)
+        };
+
+        Ok(IperfResult {
+            role,
+            peer: peer.ip().to_string(),
+            port,
+            protocol: settings.protocol,
+            reverse: settings.reverse,
+            parallel: settings.parallel,
+            length: settings.length,
+            duration_secs: self.duration_secs,
+            local_sender: self.sender,
+            sent: IperfSummary::sum(&sent, period(&sent)),
+            received: IperfSummary::sum(&received, period(&received)),
+            streams,
+            intervals: self.intervals,
+            local_cpu_percent: self.cpu.total,
+            remote_cpu_percent: peer_results.get("cpu_util_total").and_then(Value::as_f64),
+        })
+    }
+}
+
+/// Parse the peer's `EXCHANGE_RESULTS` message
+fn parse_results(results: &Value) -> Result<Vec<SideStream>> {
+    let streams = results
+        .get("streams")
+        .and_then(Value::as_array)
+        .ok_or_else(|| SimonError::Parse("iperf3 results without streams".into()))?;
+    let has_retransmits = results
+        .get("sender_has_retransmits")
+        .and_then(Value::as_i64)
+        .unwrap_or(0)
+        > 0;
+    streams
+        .iter()
+        .map(|stream| {
+            let num = |key: &str| stream.get(key).and_then(Value::as_f64);
+            let id = num("id")
+                .ok_or_else(|| SimonError::Parse("iperf3 stream result without id".into()))?;
+            let retransmits = num("retransmits").filter(|r| has_retransmits && *r >= 0.0);
+            let seconds = match (num("start_time"), num("end_time")) {
+                (Some(start), Some(end)) if end > start => Some(end - start),
+                _ => None,
+            };
+            Ok(SideStream {
+                id: id as u32,
+                bytes: num("bytes").unwrap_or(0.0) as u64,
+                retransmits: retransmits.map(|r| r as u64),
+                udp: UdpStats {
+                    packets: num("packets").unwrap_or(0.0) as u64,
+                    lost: num("errors").unwrap_or(0.0).max(0.0) as u64,
+                    jitter: num("jitter").unwrap_or(0.0),
+                    ..Default::default()
+                },
+                seconds,
+            })
+        })
+        .collect()
+}
+
+/// Process CPU utilization in percent of one core, as iperf3 reports it
+#[derive(Debug, Clone, Copy, Default)]
+struct CpuUsage {
+    total: f64,
+    user: f64,
+    system: f64,
+}
+
+struct CpuClock {
+    wall: Instant,
+    times: Option<(f64, f64)>,
+}
+
+impl CpuClock {
+    fn start() -> Self {
+        Self {
+            wall: Instant::now(),
+            times: process_cpu_times(),
+        }
+    }
+
+    fn usage(&self) -> CpuUsage {
+        let wall = self.wall.elapsed().as_secs_f64();
+        match (self.times, process_cpu_times()) {
+            (Some((user0, system0)), Some((user1, system1))) if wall > 0.0 => {
+                let user = (user1 - user0) / wall * 100.0;
+                let system = (system1 - system0) / wall * 100.0;
+                CpuUsage {
+                    total: user + system,
+                    user,
+                    system,
+                }
+            }
+            _ => CpuUsage::default(),
+        }
+    }
+}
+
+/// User and system CPU seconds consumed by this process
+#[cfg(unix)]
+fn process_cpu_times() -> Option<(f64, f64)> {
+    // SAFETY: getrusage only writes into the zeroed struct we pass
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return None;
+    }
+    let secs = |tv: libc::timeval| tv.tv_sec as f64 + tv.tv_usec as f64 / 1e6;
+    Some((secs(usage.ru_utime), secs(usage.ru_stime)))
+}
+
+#[cfg(not(unix))]
+fn process_cpu_times() -> Option<(f64, f64)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loopback(config: IperfConfig) -> (IperfResult, IperfResult) {
+        let server = IperfServer::bind("127.0.0.1:0").unwrap();
+        let port = server.local_addr().unwrap().port();
+        let handle = thread::spawn(move || server.accept_test(|_| {}).unwrap());
+        let client = run_client("127.0.0.1", port, &config).unwrap();
+        (client, handle.join().unwrap())
+    }
+
+    #[test]
+    fn test_tcp_parallel() {
+        let config = IperfConfig::default()
+            .with_duration(Duration::from_secs(1))
+            .with_interval(Duration::from_millis(250))
+            .with_parallel(3);
+        let (client, server) = loopback(config);
+
+        assert!(client.local_sender && !server.local_sender);
+        let ids: Vec<u32> = client.streams.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+        assert!(client.sent.bytes > 0);
+        // Both sides agree on what was sent and received
+        assert_eq!(client.sent.bytes, server.sent.bytes);
+        assert_eq!(client.received.bytes, server.received.bytes);
+        assert!(client.received.bytes <= client.sent.bytes);
+        assert!(client.intervals.len() >= 3);
+        assert!(client.received.packets.is_none());
+    }
+
+    #[test]
+    fn test_tcp_reverse() {
+        let config = IperfConfig::default()
+            .with_duration(Duration::from_secs(1))
+            .with_reverse(true);
+        let (client, server) = loopback(config);
+
+        assert!(!client.local_sender && server.local_sender);
+        assert!(client.received.bytes > 0);
+        assert_eq!(client.received.bytes, server.received.bytes);
+        #[cfg(target_os = "linux")]
+        assert!(client.sent.retransmits.is_some());
+    }
+
+    #[test]
+    fn test_udp_loss_and_jitter() {
+        let config = IperfConfig::default()
+            .with_protocol(IperfProtocol::Udp)
+            .with_duration(Duration::from_secs(1))
+            .with_bitrate(8_000_000)
+            .with_parallel(2);
+        let (client, server) = loopback(config);
+
+        let sent = client.sent.packets.unwrap();
+        let received = server.received.packets.unwrap();
+        // ~8 Mbit/s of 1460-byte datagrams for a second, over two streams
+        assert!(sent > 500, "sent {}", sent);
+        assert!(received <= sent);
+        assert_eq!(client.received.lost_packets, server.received.lost_packets);
+        assert!(server.received.jitter_ms.is_some());
+        assert!(server.intervals.iter().all(|i| i.lost_packets.is_some()));
+    }
+
+    #[test]
+    fn test_udp_reverse() {
+        let config = IperfConfig::default()
+            .with_protocol(IperfProtocol::Udp)
+            .with_duration(Duration::from_secs(1))
+            .with_reverse(true);
+        let (client, _) = loopback(config);
+
+        assert!(client.received.packets.unwrap() > 0);
+        assert!(client.received.out_of_order.is_some());
+        assert!(client.sent.packets.unwrap() >= client.received.packets.unwrap());
+    }
+
+    #[test]
+    fn test_udp_stats() {
+        let datagram = |seq: u32, sent: f64| {
+            let mut d = vec![0u8; 32];
+            d[0..4].copy_from_slice(&(sent as u32).to_be_bytes());
+            d[4..8].copy_from_slice(&((sent.fract() * 1e6) as u32).to_be_bytes());
+            d[8..12].copy_from_slice(&seq.to_be_bytes());
+            d
+        };
+        let mut stats = UdpStats::default();
+        stats.on_datagram(&datagram(1, 100.0), false, 100.010);
+        stats.on_datagram(&datagram(2, 100.1), false, 100.110);
+        // 3 and 4 missing, then 3 arrives late
+        stats.on_datagram(&datagram(5, 100.4), false, 100.426);
+        stats.on_datagram(&datagram(3, 100.2), false, 100.5);
+        assert_eq!((stats.packets, stats.lost, stats.out_of_order), (5, 1, 1));
+        assert!(stats.jitter > 0.0);
+    }
+
+    #[test]
+    fn test_parse_bitrate() {
+        assert_eq!(parse_bitrate("100M").unwrap(), 100_000_000);
+        assert_eq!(parse_bitrate("1.5g").unwrap(), 1_500_000_000);
+        assert_eq!(parse_bitrate("800").unwrap(), 800);
+        assert!(parse_bitrate("fast").is_err());
+    }
+
+    #[test]
+    fn test_params_round_trip() {
+        let config = IperfConfig::default()
+            .with_protocol(IperfProtocol::Udp)
+            .with_reverse(true)
+            .with_parallel(4)
+            .with_bitrate(0);
+        let params = config.settings().unwrap().to_params();
+        assert_eq!(params["udp"], json!(true));
+        assert!(params.get("tcp").is_none());
+        assert_eq!(params["len"], json!(DEFAULT_UDP_LENGTH));
+
+        let parsed = Settings::from_params(&params, Duration::from_secs(1)).unwrap();
+        assert_eq!(parsed.protocol, IperfProtocol::Udp);
+        assert!(parsed.reverse);
+        assert_eq!((parsed.parallel, parsed.bitrate), (4, 0));
+
+        // Sent by a stock client with -n
+        let bytes = json!({"tcp": true, "num": 1048576, "parallel": 1});
+        assert!(Settings::from_params(&bytes, Duration::from_secs(1)).is_err());
+    }
+}
@@ -161,30 +161,41 @@ pub mod config; // Configuration management with TOML persistence
 pub mod connections; // Network connection monitoring (netstat-like)
 pub mod consent; // User consent management for ethical data collection
 pub mod core;
+pub mod cpu_profiles; // Named CPU tuning profiles with atomic apply/revert and auto-switching rules
 pub mod cpufreq; // CPU frequency scaling and governor control
 pub mod disk; // Disk/storage monitoring
 pub mod display; // Display/monitor information
+pub mod energy_accounting; // Per-process/cgroup energy attribution from RAPL and GPU power (kWh, cost, CO2)
 pub mod error;
+pub mod ethtool; // NIC driver/firmware info, link modes, FEC, rings, offloads and vendor stats via SIOCETHTOOL
 pub mod fan_control; // Advanced fan monitoring and control
+pub mod fan_controller; // Closed-loop fan curve controller (hysteresis, ramp limits, stall detection)
 pub mod gpu; // GPU abstraction layer
 pub mod health; // System health scoring and alerts
 pub mod hwmon; // Hardware monitoring (temperatures, voltages, fans) - native implementation
+pub mod iperf3; // iperf3-compatible bandwidth test client and server (TCP/UDP, reverse, parallel)
 pub mod memory_management; // Memory and swap management (jetson_stats style)
 pub mod motherboard; // Motherboard sensors, BIOS, system information
+pub mod net_accounting; // Per-process/cgroup/container TCP bandwidth attribution (nethogs-style)
 pub mod network_monitor; // Network interface monitoring
 pub mod network_tools; // Network diagnostic tools (ping, traceroute, port scan) - nmap/netcat style
+pub mod node_power; // Whole-node power model (measured + estimated components, PSU losses, residual)
 pub mod observability; // Full system observability API with MCP-like permissions for external AI access
+pub mod packet_capture; // Native AF_PACKET capture with BPF filters, protocol decoding, pcapng I/O and offline analysis
 pub mod platform;
 pub mod power_supply; // Battery and power supply monitoring
 pub mod process_monitor; // Unified process monitoring with GPU attribution
 pub mod process_tree; // Process tree visualization with container/cgroup awareness
+pub mod rdma; // InfiniBand/RoCE port state, link rate, IB and vendor counters with PCIe/NUMA correlation
 pub mod sandbox; // Sandbox and VM detection for ethical data collection
 pub mod services; // System service monitoring and control
 pub mod silicon; // New: Unified silicon monitoring (CPU, NPU, I/O, network)
+pub mod sock_diag; // Netlink sock_diag (INET_DIAG) socket dumps with tcp_info
 pub mod stats;
 pub mod system_stats; // System-wide stats (load avg, vmstat, uptime) - Linux/BSD style
 pub mod tsdb; // Time-series database for recording metrics
 pub mod usb; // USB device enumeration
+pub mod wireless; // WiFi link quality via nl80211 (SSID/BSSID, channel, signal/noise, MCS/NSS, retries, roaming)
 
 // Additional hardware monitors
 pub mod camera; // Camera and webcam device monitoring
@@ -201,17 +212,20 @@ pub mod printer; // Printer and print queue monitoring
 pub mod sensors; // Environmental sensors (accelerometer, gyroscope, light)
 pub mod smart; // S.M.A.R.T. disk health monitoring with AI inference
 pub mod storage_controller; // Storage controllers, RAID arrays, NVMe
+pub mod storage_topology; // Block stack graph (dm/LVM/LUKS/md/btrfs/ZFS) from mounts down to disks
 pub mod tpm; // Trusted Platform Module monitoring
 
 // Deep hardware topology, security, and resource monitors
 pub mod cgroup_monitor; // Linux cgroup v1/v2 resource monitoring for containers
 pub mod cpu_microarch; // CPU microarchitecture detection and ISA extensions
+pub mod cpu_topology; // Unified package/die/CCX/core-type/SMT topology with grouped per-core metrics
 pub mod crypto_accel; // Cryptographic hardware acceleration (AES-NI, SHA, RNG)
 pub mod drm_monitor; // DRM/KMS subsystem monitoring (connectors, CRTCs, planes)
 pub mod interconnect; // CPU interconnect topology (QPI/UPI, Infinity Fabric, Apple UMA)
 pub mod interrupt_map; // IRQ topology, per-CPU counts, MSI/MSI-X, affinity
 pub mod memory_bandwidth; // Memory bandwidth monitoring and estimation
 pub mod memory_topology; // Physical DIMM enumeration, speed, timing, ECC
+pub mod perf; // Hardware performance counters (IPC, cache/branch miss rates, uncore memory bandwidth)
 pub mod rapl; // Intel RAPL / AMD power capping energy monitoring
 pub mod security_mitigations; // CPU vulnerability mitigations, kernel security, posture scoring
 
@@ -222,19 +236,24 @@ pub mod gpu_topology; // Multi-GPU topology, NVLink/xGMI/PCIe P2P, NUMA affinity
 pub mod io_scheduler; // Block I/O scheduler monitoring (mq-deadline, BFQ, kyber)
 pub mod iommu; // IOMMU detection (VT-d, AMD-Vi, SMMU), group enumeration
 pub mod kernel_params; // Kernel sysctl parameter monitoring and security scoring
+pub mod oom; // OOM kills (kernel log, memory.events, vmstat) with container attribution
+pub mod psi; // Pressure stall information (system + cgroup), kernel triggers, stall events
 pub mod scheduler; // Linux process scheduler (CFS/EEVDF, PSI, schedstat)
 pub mod thermal_zone; // Thermal zone, trip point, and cooling device monitoring
 pub mod voltage_regulator; // Voltage regulator monitoring (SoC/CPU/GPU rails)
 pub mod watchdog; // Hardware/software watchdog timer monitoring
 
 // Datacenter, virtualization, and fleet monitoring
+pub mod capacity; // Filesystem capacity and inode exhaustion forecasting
 pub mod daemon; // Monitoring daemon for headless/remote operation
 pub mod datacenter; // Datacenter chassis, IPMI, rack topology
 pub mod fleet; // Fleet-level multi-host monitoring and aggregation
 pub mod http_server; // HTTP server for REST API and Prometheus
+pub mod otlp; // OpenTelemetry OTLP metrics and logs export (HTTP/protobuf, gRPC)
 pub mod pcie; // PCIe device monitoring
 pub mod predictive; // Predictive maintenance and failure analysis
 pub mod prometheus; // Prometheus metrics exporter
+pub mod push; // Push exporters (Prometheus remote_write, InfluxDB, StatsD) with an on-disk WAL
 pub mod virtualization; // VM/container detection and resource monitoring
 pub mod wsl; // WSL2 detection and monitoring
 
@@ -246,6 +265,9 @@ pub mod utils;
 // Unified backend for CLI, TUI, and GUI
 pub mod backend;
 
+#[cfg(test)]
+pub(crate) mod test_util; // Shared test fixtures (fake /sys and /proc trees)
+
 #[cfg(feature = "cli")]
 pub mod tui; // Terminal UI
 
@@ -298,7 +320,9 @@ pub use process_monitor::{
 pub use network_monitor::{NetworkInterfaceInfo, NetworkMonitor};
 
 // Re-export connection monitor (netstat-like)
-pub use connections::{ConnectionInfo, ConnectionMonitor, ConnectionState, Protocol};
+pub use connections::{
+    ConnectionBackend, ConnectionInfo, ConnectionMonitor, ConnectionState, Protocol,
+};
 
 // Re-export AI workload monitoring
 pub use ai_workload::{
@@ -485,8 +509,8 @@ pub use backend::{
 
 // Re-export time-series database for metrics recording
 pub use tsdb::{
-    format_size, parse_size, DatabaseStats, MetricSample, MetricsRecorder, ProcessSnapshot,
-    SystemSnapshot, TimeSeriesDb,
+    format_size, parse_size, DatabaseStats, FilesystemSnapshot, FilesystemUsage, MetricSample,
+    MetricsRecorder, ProcessSnapshot, SystemSnapshot, TimeSeriesDb,
 };
 
 // Re-export datacenter monitoring
@@ -533,38 +533,31 @@ impl MemoryBandwidthMonitor {
 
     #[cfg(target_os = "linux")]
     fn read_imc_counters(estimate: &BandwidthEstimate) -> Option<BandwidthMeasurement> {
-        // Check for uncore IMC perf events
-        let imc_path = std::path::Path::new("/sys/devices");
-        if !imc_path.exists() {
-            return None;
-        }
-
-        // Look for uncore_imc_* directories
-        let entries = std::fs::read_dir(imc_path).ok()?;
-        let has_imc = entries
-            .filter_map(|e| e.ok())
-            .any(|e| {
-                e.file_name()
-                    .to_string_lossy()
-                    .starts_with("uncore_imc")
-            });
-
-        if has_imc {
-            // IMC counters found but would need perf_event_open() to read
-            // Return a stub indicating capability exists
-            Some(BandwidthMeasurement {
-                read_gbs: 0.0,
-                write_gbs: 0.0,
-                total_gbs: 0.0,
-                source: "uncore_imc (counters detected, perf_event needed)".into(),
-                duration_us: 0,
-                utilization_pct: 0.0,
-            })
+        // Sample uncore IMC/DF counters over a short window; needs
+        // perf_event_paranoid <= 0 or CAP_PERFMON
+        let mut counters = crate::perf::MemoryBandwidthCounters::open().ok()?;
+        let window = std::time::Duration::from_millis(100);
+        let start = std::time::Instant::now();
+        std::thread::sleep(window);
+        let elapsed = start.elapsed();
+        let bw = counters.read(elapsed.as_secs_f64());
+
+        let read_gbs = bw.read_bytes_per_sec / 1e9;
+        let write_gbs = bw.write_bytes_per_sec / 1e9;
+        let total_gbs = read_gbs + write_gbs;
+        let utilization_pct = if estimate.peak_bandwidth_gbs > 0.0 {
+            (total_gbs / estimate.peak_bandwidth_gbs * 100.0).min(100.0)
         } else {
-            // No hardware counters, just use estimate
-            let _ = estimate;
-            None
-        }
+            0.0
+        };
+        Some(BandwidthMeasurement {
+            read_gbs,
+            write_gbs,
+            total_gbs,
+            source: format!("perf uncore ({})", counters.pmus().join(", ")),
+            duration_us: elapsed.as_micros() as u64,
+            utilization_pct,
+        })
     }
 }
 
@@ -302,6 +302,12 @@ impl NetworkMonitor {
         (0.0, 0.0)
     }
 
+    /// Driver-level details for an interface: link modes, FEC, rings,
+    /// offloads and vendor statistics (see [`crate::ethtool`])
+    pub fn nic_details(&self, name: &str) -> Result<crate::ethtool::NicDetails> {
+        crate::ethtool::nic_details(name)
+    }
+
     /// Get interface count
     pub fn interface_count(&mut self) -> Result<usize> {
         Ok(self.interfaces()?.len())
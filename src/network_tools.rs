@@ -5,7 +5,7 @@
 //! - **Traceroute** - Path tracing with hop-by-hop latency
 //! - **Port Scanning** - TCP/UDP port discovery (nmap-style)
 //! - **Service Detection** - Banner grabbing and version detection
-//! - **Packet Capture** - Network traffic capture (tcpdump-style)
+//! - **Packet Capture** - Network traffic capture (tcpdump-style, native AF_PACKET on Linux)
 //! - **Bandwidth Testing** - Network throughput measurement (iperf-style)
 //!
 //! # Examples
@@ -1396,6 +1396,11 @@ pub struct CaptureConfig {
     pub include_data: bool,
     /// Capture filter expression (BPF syntax)
     pub custom_filter: Option<String>,
+    /// Bytes kept per packet
+    pub snaplen: u32,
+    /// Also write captured frames to this pcapng file (native Linux capture
+    /// only; external tools are not used when set)
+    pub pcapng_path: Option<std::path::PathBuf>,
 }
 
 impl Default for CaptureConfig {
@@ -1409,6 +1414,8 @@ impl Default for CaptureConfig {
             timeout_secs: 30,
             include_data: false,
             custom_filter: None,
+            snaplen: 262_144,
+            pcapng_path: None,
         }
     }
 }
@@ -1436,6 +1443,9 @@ pub struct CaptureResult {
     pub top_sources: Vec<(String, u32)>,
     /// Top destinations (by packet count)
     pub top_destinations: Vec<(String, u32)>,
+    /// Packets the kernel dropped (native capture only)
+    #[serde(default)]
+    pub dropped_packets: u64,
 }
 
 /// List available network interfaces for capture
@@ -1559,10 +1569,12 @@ fn build_capture_filter(config: &CaptureConfig) -> String {
     }
 }
 
-/// Capture packets using system tcpdump/tshark
+/// Capture packets, natively on Linux (see [`crate::packet_capture`]) or
+/// with system tcpdump/tshark
 ///
-/// **Note**: This function requires elevated privileges (administrator/root)
-/// and tcpdump/Wireshark to be installed.
+/// **Note**: This function requires elevated privileges (administrator/root,
+/// or `CAP_NET_RAW` on Linux). Outside Linux tcpdump/Wireshark must be
+/// installed.
 ///
 /// # Example
 /// ```no_run
@@ -1629,7 +1641,27 @@ fn capture_packets_unix(
     filter: &str,
     start: Instant,
 ) -> Result<CaptureResult> {
-    // Try tcpdump first
+    // In-process AF_PACKET capture first
+    #[cfg(target_os = "linux")]
+    let native_error = match crate::packet_capture::capture(config, filter) {
+        Ok(native) => {
+            let mut result = finalize_capture_result(native.packets, start, filter)?;
+            result.interface = native.interface;
+            result.dropped_packets = native.stats.dropped;
+            return Ok(result);
+        }
+        // The external tools cannot write pcapng; don't silently skip it
+        Err(e) if config.pcapng_path.is_some() => return Err(e),
+        Err(e) => e,
+    };
+    #[cfg(not(target_os = "linux"))]
+    if config.pcapng_path.is_some() {
+        return Err(SimonError::UnsupportedPlatform(
+            "pcapng output requires native capture (Linux)".to_string(),
+        ));
+    }
+
+    // Then tcpdump
     let tcpdump_result = capture_with_tcpdump(config, filter);
     if tcpdump_result.is_ok() {
         return finalize_capture_result(tcpdump_result?, start, filter);
@@ -1641,9 +1673,19 @@ fn capture_packets_unix(
         return finalize_capture_result(tshark_result?, start, filter);
     }
 
-    Err(SimonError::Other(
-        "No packet capture tool available. Install tcpdump or Wireshark.".to_string(),
-    ))
+    // Native capture failing (usually for lack of CAP_NET_RAW) is the more
+    // useful error
+    #[cfg(target_os = "linux")]
+    {
+        Err(native_error)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(SimonError::Other(
+            "No packet capture tool available. Install tcpdump or Wireshark.".to_string(),
+        ))
+    }
 }
 
 /// Capture using tshark (cross-platform)
@@ -1993,6 +2035,7 @@ fn finalize_capture_result(
         protocol_stats,
         top_sources,
         top_destinations,
+        dropped_packets: 0,
     })
 }
 
@@ -2044,7 +2087,8 @@ pub fn is_capture_available() -> bool {
 
     #[cfg(not(target_os = "windows"))]
     {
-        Command::new("tcpdump").arg("--version").output().is_ok()
+        crate::packet_capture::is_available()
+            || Command::new("tcpdump").arg("--version").output().is_ok()
             || Command::new("tshark").arg("--version").output().is_ok()
     }
 }
@@ -0,0 +1,969 @@
+//! Whole-node power model
+//!
+//! Combines every power source the crate knows about into a single "node watts"
+//! figure with a per-component breakdown:
+//!
+//! - **Measured**: RAPL package and DRAM domains, GPU board power, and PSU/BMC wall
+//!   power (IPMI DCMI, PMBus `pin`/`pout` hwmon sensors, or battery discharge rate).
+//! - **Estimated**: components without a power sensor (DIMMs, disks, NICs, fans and
+//!   the baseboard) from per-device [`PowerCoefficients`], scaled by utilization
+//!   where it is observable (disk busy time, fan RPM).
+//!
+//! The DC component sum is reconciled against wall power when available: the PSU
+//! efficiency (measured from `pin`/`pout`, or taken from the configured curve) gives
+//! the conversion loss, and whatever DC power is left over is reported as an
+//! unexplained residual. Without a wall reading, node power is the DC sum grossed up
+//! by the PSU efficiency.
+//!
+//! [`NodePowerModel`] is pure and works on [`NodePowerInputs`];
+//! [`NodePowerCollector`] gathers those inputs from the running system.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::node_power::{NodePowerCollector, PowerCoefficients};
+//!
+//! let mut collector = NodePowerCollector::new(PowerCoefficients::default());
+//! std::thread::sleep(std::time::Duration::from_secs(1));
+//! let report = collector.sample();
+//!
+//! println!("Node: {:.1} W", report.node_watts);
+//! for c in &report.components {
+//!     println!("  {:<24} {:>7.1} W ({})", c.name, c.watts, c.provenance);
+//! }
+//! if let Some(residual) = report.residual_watts {
+//!     println!("Unexplained: {:.1} W", residual);
+//! }
+//! ```
+
+use crate::disk::DiskType;
+use crate::gpu::Device;
+use crate::rapl::{PowerDomain, RaplMonitor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Kind of component contributing to node power
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    Cpu,
+    Dram,
+    Gpu,
+    Storage,
+    Network,
+    Fan,
+    Baseboard,
+}
+
+impl std::fmt::Display for ComponentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Cpu => "cpu",
+            Self::Dram => "dram",
+            Self::Gpu => "gpu",
+            Self::Storage => "storage",
+            Self::Network => "network",
+            Self::Fan => "fan",
+            Self::Baseboard => "baseboard",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Whether a component's power was read from a sensor or estimated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Provenance {
+    Measured,
+    Estimated,
+}
+
+impl std::fmt::Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Measured => write!(f, "measured"),
+            Self::Estimated => write!(f, "estimated"),
+        }
+    }
+}
+
+/// Power drawn by one component (DC side of the PSU)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentPower {
+    /// Component name (e.g. "socket0 package", "nvme0n1", "GPU 0")
+    pub name: String,
+    pub kind: ComponentKind,
+    pub watts: f64,
+    pub provenance: Provenance,
+}
+
+impl ComponentPower {
+    /// A sensor reading
+    pub fn measured(name: impl Into<String>, kind: ComponentKind, watts: f64) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            watts,
+            provenance: Provenance::Measured,
+        }
+    }
+
+    /// A coefficient-based estimate
+    pub fn estimated(name: impl Into<String>, kind: ComponentKind, watts: f64) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            watts,
+            provenance: Provenance::Estimated,
+        }
+    }
+}
+
+/// Where a wall (AC input) power reading came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WallSource {
+    /// BMC via `ipmitool dcmi power reading`
+    Ipmi,
+    /// PSU telemetry exposed through hwmon (PMBus `pin`)
+    PsuSensor,
+    /// Battery discharge rate (no PSU in the path)
+    Battery,
+}
+
+/// Total power drawn by the node at its input
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WallPower {
+    pub watts: f64,
+    pub source: WallSource,
+    /// PSU output power, when the PSU reports it (gives a measured efficiency)
+    pub psu_output_watts: Option<f64>,
+}
+
+/// Per-device coefficients for components without a power sensor
+///
+/// Defaults are typical datasheet figures for commodity server and desktop parts;
+/// override them for a specific fleet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerCoefficients {
+    /// Watts per populated DIMM (used only when RAPL has no DRAM domain)
+    pub dimm_watts: f64,
+    /// NVMe SSD power at idle and fully busy
+    pub nvme_idle_watts: f64,
+    pub nvme_active_watts: f64,
+    /// SATA/SAS SSD power at idle and fully busy
+    pub ssd_idle_watts: f64,
+    pub ssd_active_watts: f64,
+    /// Spinning disk power at idle and fully busy
+    pub hdd_idle_watts: f64,
+    pub hdd_active_watts: f64,
+    /// Watts per NIC port by link speed
+    pub nic_1g_watts: f64,
+    pub nic_10g_watts: f64,
+    pub nic_25g_watts: f64,
+    pub nic_100g_watts: f64,
+    /// Watts per fan at its maximum speed (scaled by the cube of the speed ratio)
+    pub fan_max_watts: f64,
+    /// Chipset, VRM, BMC and other baseboard logic
+    pub baseboard_watts: f64,
+    /// PSU rated output; enables the load-dependent efficiency curve
+    pub psu_rated_watts: Option<f64>,
+    /// PSU efficiency as (load fraction, efficiency) points, ascending by load
+    pub psu_efficiency_curve: Vec<(f64, f64)>,
+    /// PSU efficiency when the rated output is unknown
+    pub psu_efficiency: f64,
+}
+
+impl Default for PowerCoefficients {
+    fn default() -> Self {
+        Self {
+            dimm_watts: 3.0,
+            nvme_idle_watts: 2.5,
+            nvme_active_watts: 8.0,
+            ssd_idle_watts: 0.5,
+            ssd_active_watts: 3.0,
+            hdd_idle_watts: 5.0,
+            hdd_active_watts: 8.0,
+            nic_1g_watts: 1.0,
+            nic_10g_watts: 5.0,
+            nic_25g_watts: 8.0,
+            nic_100g_watts: 15.0,
+            fan_max_watts: 3.0,
+            baseboard_watts: 15.0,
+            psu_rated_watts: None,
+            // 80 PLUS Gold (115V internal)
+            psu_efficiency_curve: vec![(0.1, 0.82), (0.2, 0.87), (0.5, 0.90), (1.0, 0.87)],
+            psu_efficiency: 0.90,
+        }
+    }
+}
+
+impl PowerCoefficients {
+    /// PSU efficiency at a DC output load
+    pub fn psu_efficiency_at(&self, dc_watts: f64) -> f64 {
+        let (Some(rated), Some(first), Some(last)) = (
+            self.psu_rated_watts.filter(|r| *r > 0.0),
+            self.psu_efficiency_curve.first(),
+            self.psu_efficiency_curve.last(),
+        ) else {
+            return self.psu_efficiency;
+        };
+        let load = dc_watts / rated;
+        if load <= first.0 {
+            return first.1;
+        }
+        if load >= last.0 {
+            return last.1;
+        }
+        for w in self.psu_efficiency_curve.windows(2) {
+            let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+            if load <= x1 {
+                return y0 + (y1 - y0) * (load - x0) / (x1 - x0);
+            }
+        }
+        last.1
+    }
+
+    /// Disk power at a busy fraction (0.0-1.0)
+    pub fn disk_watts(&self, disk_type: DiskType, busy: f64) -> Option<f64> {
+        let (idle, active) = match disk_type {
+            DiskType::NvmeSsd => (self.nvme_idle_watts, self.nvme_active_watts),
+            DiskType::SataSsd => (self.ssd_idle_watts, self.ssd_active_watts),
+            DiskType::SataHdd | DiskType::Scsi => (self.hdd_idle_watts, self.hdd_active_watts),
+            // Bus-powered or not local hardware
+            DiskType::Usb | DiskType::Virtual | DiskType::Unknown => return None,
+        };
+        Some(idle + (active - idle) * busy.clamp(0.0, 1.0))
+    }
+
+    /// NIC port power at a link speed
+    pub fn nic_watts(&self, speed_mbps: u32) -> f64 {
+        match speed_mbps {
+            0..=1_000 => self.nic_1g_watts,
+            1_001..=10_000 => self.nic_10g_watts,
+            10_001..=25_000 => self.nic_25g_watts,
+            _ => self.nic_100g_watts,
+        }
+    }
+
+    /// Fan power at a fraction of its maximum speed (fan affinity law: P ~ n^3)
+    pub fn fan_watts(&self, speed_fraction: f64) -> f64 {
+        self.fan_max_watts * speed_fraction.clamp(0.0, 1.0).powi(3)
+    }
+}
+
+/// A disk and how busy it was over the sample interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskLoad {
+    pub name: String,
+    pub disk_type: DiskType,
+    /// Fraction of the interval with I/O in flight (0.0-1.0)
+    pub busy: f64,
+}
+
+/// A fan and its current speed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanLoad {
+    pub name: String,
+    /// Fraction of maximum speed (0.0-1.0)
+    pub speed_fraction: f64,
+}
+
+/// A physical NIC port with link up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NicLink {
+    pub name: String,
+    pub speed_mbps: u32,
+}
+
+/// Everything the model needs for one sample
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodePowerInputs {
+    /// Components with a power sensor (RAPL, GPU)
+    pub measured: Vec<ComponentPower>,
+    /// Populated DIMMs
+    pub dimms: usize,
+    pub disks: Vec<DiskLoad>,
+    pub nics: Vec<NicLink>,
+    pub fans: Vec<FanLoad>,
+    pub wall: Option<WallPower>,
+}
+
+/// Node power with its breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodePowerReport {
+    /// Unix time of the sample
+    pub timestamp: u64,
+    /// Node power at the wall (measured if available, otherwise modeled)
+    pub node_watts: f64,
+    /// Per-component DC power, highest first
+    pub components: Vec<ComponentPower>,
+    /// Sum of measured components
+    pub measured_watts: f64,
+    /// Sum of estimated components
+    pub estimated_watts: f64,
+    /// Sum of all components
+    pub dc_watts: f64,
+    /// PSU efficiency used for reconciliation
+    pub psu_efficiency: f64,
+    /// Whether the efficiency came from PSU input/output telemetry
+    pub psu_efficiency_measured: bool,
+    /// Power lost in AC-DC conversion
+    pub psu_loss_watts: f64,
+    /// Wall reading, if any
+    pub wall: Option<WallPower>,
+    /// DC power available from the wall reading not accounted for by components
+    /// (negative when the model overestimates); None without a wall reading
+    pub residual_watts: Option<f64>,
+}
+
+impl NodePowerReport {
+    /// Total DC power of one component kind
+    pub fn watts_by_kind(&self, kind: ComponentKind) -> f64 {
+        self.components
+            .iter()
+            .filter(|c| c.kind == kind)
+            .fold(0.0, |total, c| total + c.watts)
+    }
+
+    /// Fraction of the DC sum backed by sensors (0.0-1.0)
+    pub fn measured_fraction(&self) -> f64 {
+        if self.dc_watts > 0.0 {
+            self.measured_watts / self.dc_watts
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Combines measured and estimated components and reconciles them with wall power
+#[derive(Debug, Clone, Default)]
+pub struct NodePowerModel {
+    coefficients: PowerCoefficients,
+}
+
+impl NodePowerModel {
+    pub fn new(coefficients: PowerCoefficients) -> Self {
+        Self { coefficients }
+    }
+
+    pub fn coefficients(&self) -> &PowerCoefficients {
+        &self.coefficients
+    }
+
+    /// Build a report from one set of inputs
+    pub fn compute(&self, inputs: &NodePowerInputs) -> NodePowerReport {
+        let c = &self.coefficients;
+        let mut components = inputs.measured.clone();
+
+        // RAPL DRAM already covers the DIMMs
+        let dram_measured = inputs
+            .measured
+            .iter()
+            .any(|m| m.kind == ComponentKind::Dram);
+        if !dram_measured && inputs.dimms > 0 {
+            components.push(ComponentPower::estimated(
+                format!("{} DIMMs", inputs.dimms),
+                ComponentKind::Dram,
+                inputs.dimms as f64 * c.dimm_watts,
+            ));
+        }
+        for disk in &inputs.disks {
+            if let Some(w) = c.disk_watts(disk.disk_type, disk.busy) {
+                components.push(ComponentPower::estimated(
+                    &disk.name,
+                    ComponentKind::Storage,
+                    w,
+                ));
+            }
+        }
+        for nic in &inputs.nics {
+            components.push(ComponentPower::estimated(
+                &nic.name,
+                ComponentKind::Network,
+                c.nic_watts(nic.speed_mbps),
+            ));
+        }
+        for fan in &inputs.fans {
+            components.push(ComponentPower::estimated(
+                &fan.name,
+                ComponentKind::Fan,
+                c.fan_watts(fan.speed_fraction),
+            ));
+        }
+        if c.baseboard_watts > 0.0 {
+            components.push(ComponentPower::estimated(
+                "baseboard",
+                ComponentKind::Baseboard,
+                c.baseboard_watts,
+            ));
+        }
+        components.sort_by(|a, b| b.watts.total_cmp(&a.watts));
+
+        // Folded from +0.0: `sum()` of no components is -0.0
+        let sum = |p: Provenance| -> f64 {
+            components
+                .iter()
+                .filter(|c| c.provenance == p)
+                .fold(0.0, |total, c| total + c.watts)
+        };
+        let measured_watts = sum(Provenance::Measured);
+        let estimated_watts = sum(Provenance::Estimated);
+        let dc_watts = measured_watts + estimated_watts;
+
+        let (node_watts, psu_efficiency, psu_efficiency_measured, residual_watts) =
+            match inputs.wall {
+                Some(wall) if wall.source == WallSource::Battery => {
+                    (wall.watts, 1.0, false, Some(wall.watts - dc_watts))
+                }
+                Some(wall) => {
+                    let measured_eff = wall
+                        .psu_output_watts
+                        .filter(|out| wall.watts > 0.0 && *out > 0.0 && *out <= wall.watts)
+                        .map(|out| out / wall.watts);
+                    let eff = measured_eff.unwrap_or_else(|| c.psu_efficiency_at(dc_watts));
+                    let dc_available = wall.psu_output_watts.unwrap_or(wall.watts * eff);
+                    (
+                        wall.watts,
+                        eff,
+                        measured_eff.is_some(),
+                        Some(dc_available - dc_watts),
+                    )
+                }
+                None => {
+                    let eff = c.psu_efficiency_at(dc_watts);
+                    let node = if eff > 0.0 { dc_watts / eff } else { dc_watts };
+                    (node, eff, false, None)
+                }
+            };
+
+        NodePowerReport {
+            timestamp: chrono::Utc::now().timestamp().max(0) as u64,
+            node_watts,
+            components,
+            measured_watts,
+            estimated_watts,
+            dc_watts,
+            psu_efficiency,
+            psu_efficiency_measured,
+            psu_loss_watts: node_watts * (1.0 - psu_efficiency),
+            wall: inputs.wall,
+            residual_watts,
+        }
+    }
+}
+
+/// Gathers [`NodePowerInputs`] from the running system and evaluates the model
+pub struct NodePowerCollector {
+    model: NodePowerModel,
+    root: PathBuf,
+    rapl: Option<RaplMonitor>,
+    /// GPU devices, enumerated on first use
+    gpus: Option<Vec<Box<dyn Device>>>,
+    /// Populated DIMM count, read once
+    dimms: Option<usize>,
+    /// Previous `io_ticks` (ms) per disk, for busy fractions
+    disk_busy_ms: HashMap<String, u64>,
+    last_sample: Instant,
+    use_ipmi: bool,
+}
+
+impl NodePowerCollector {
+    /// Create a collector; the first sample's RAPL and disk figures cover the time
+    /// since creation
+    pub fn new(coefficients: PowerCoefficients) -> Self {
+        Self::with_root(coefficients, "/")
+    }
+
+    /// Create a collector reading sysfs under `root` (for testing)
+    pub fn with_root(coefficients: PowerCoefficients, root: impl AsRef<Path>) -> Self {
+        Self {
+            model: NodePowerModel::new(coefficients),
+            root: root.as_ref().to_path_buf(),
+            rapl: RaplMonitor::new().ok().filter(|r| !r.readings().is_empty()),
+            gpus: None,
+            dimms: None,
+            disk_busy_ms: HashMap::new(),
+            last_sample: Instant::now(),
+            use_ipmi: true,
+        }
+    }
+
+    /// Enable or disable querying the BMC with `ipmitool` (on by default)
+    pub fn with_ipmi(mut self, enabled: bool) -> Self {
+        self.use_ipmi = enabled;
+        self
+    }
+
+    /// Model used to turn gathered inputs into a report
+    pub fn model(&self) -> &NodePowerModel {
+        &self.model
+    }
+
+    /// Gather inputs and compute a report
+    pub fn sample(&mut self) -> NodePowerReport {
+        let inputs = self.gather();
+        self.model.compute(&inputs)
+    }
+
+    /// Gather inputs without evaluating the model
+    pub fn gather(&mut self) -> NodePowerInputs {
+        let now = Instant::now();
+        let elapsed_ms = now.duration_since(self.last_sample).as_secs_f64() * 1000.0;
+        self.last_sample = now;
+
+        let mut measured = self.rapl_components();
+        let gpus = self
+            .gpus
+            .get_or_insert_with(crate::gpu::control::enumerate_devices);
+        for (i, gpu) in gpus.iter().enumerate() {
+            if let Ok(p) = gpu.power() {
+                if p.current > 0.0 {
+                    let name = gpu.name().unwrap_or_else(|_| format!("GPU {}", i));
+                    measured.push(ComponentPower::measured(
+                        name,
+                        ComponentKind::Gpu,
+                        p.current as f64,
+                    ));
+                }
+            }
+        }
+
+        let dimms = *self.dimms.get_or_insert_with(|| {
+            crate::memory_topology::MemoryTopologyMonitor::new()
+                .map(|m| m.populated_dimms().len())
+                .unwrap_or(0)
+        });
+
+        NodePowerInputs {
+            measured,
+            dimms,
+            disks: self.disk_loads(elapsed_ms),
+            nics: read_nic_links(&self.root),
+            fans: fan_loads(),
+            wall: self.wall_power(),
+        }
+    }
+
+    fn rapl_components(&mut self) -> Vec<ComponentPower> {
+        let Some(ref mut rapl) = self.rapl else {
+            return Vec::new();
+        };
+        if rapl.refresh().is_err() {
+            return Vec::new();
+        }
+        let Some(snapshot) = rapl.snapshot() else {
+            return Vec::new();
+        };
+        rapl.readings()
+            .iter()
+            .filter_map(|r| {
+                let kind = match r.domain {
+                    PowerDomain::Package => ComponentKind::Cpu,
+                    PowerDomain::Dram => ComponentKind::Dram,
+                    // Core/uncore are inside the package; psys overlaps everything
+                    _ => return None,
+                };
+                let watts = snapshot
+                    .domain_watts
+                    .get(&format!("socket{}:{}", r.socket, r.name))?;
+                Some(ComponentPower::measured(
+                    format!("socket{} {}", r.socket, r.name),
+                    kind,
+                    *watts,
+                ))
+            })
+            .collect()
+    }
+
+    fn disk_loads(&mut self, elapsed_ms: f64) -> Vec<DiskLoad> {
+        let Ok(disks) = crate::disk::enumerate_disks() else {
+            return Vec::new();
+        };
+        let io_ticks = read_disk_busy_ms(&self.root);
+        disks
+            .iter()
+            .filter(|d| is_physical_disk(&self.root, d.name()))
+            .map(|d| {
+                let name = d.name().to_string();
+                let busy_ms = io_ticks.get(&name).copied();
+                let busy = match (busy_ms, self.disk_busy_ms.get(&name)) {
+                    (Some(now), Some(prev)) if elapsed_ms > 0.0 => {
+                        now.saturating_sub(*prev) as f64 / elapsed_ms
+                    }
+                    _ => 0.0,
+                };
+                if let Some(ms) = busy_ms {
+                    self.disk_busy_ms.insert(name.clone(), ms);
+                }
+                DiskLoad {
+                    name,
+                    disk_type: d.disk_type(),
+                    busy: busy.clamp(0.0, 1.0),
+                }
+            })
+            .collect()
+    }
+
+    /// Wall power by preference: BMC, PSU telemetry, then battery discharge
+    fn wall_power(&self) -> Option<WallPower> {
+        if self.use_ipmi {
+            if let Ok(r) = crate::datacenter::IpmiController::new().power_reading() {
+                if r.current_watts > 0.0 {
+                    return Some(WallPower {
+                        watts: r.current_watts,
+                        source: WallSource::Ipmi,
+                        psu_output_watts: None,
+                    });
+                }
+            }
+        }
+        if let Some(wall) = read_psu_hwmon(&self.root) {
+            return Some(wall);
+        }
+        let supplies = crate::power_supply::PowerSupplyMonitor::new().ok()?;
+        if supplies.on_battery() {
+            let watts = supplies.primary_battery()?.power_w()? as f64;
+            if watts > 0.0 {
+                return Some(WallPower {
+                    watts,
+                    source: WallSource::Battery,
+                    psu_output_watts: None,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Time each block device spent with I/O in flight (`io_ticks`, ms), from
+/// `/proc/diskstats`
+///
+/// Unlike summed read and write ticks, overlapping requests are counted once, so
+/// the delta over an interval never exceeds its wall time.
+fn read_disk_busy_ms(root: &Path) -> HashMap<String, u64> {
+    let text = std::fs::read_to_string(root.join("proc/diskstats")).unwrap_or_default();
+    crate::io_scheduler::latency::parse_diskstats(&text)
+        .into_iter()
+        .map(|(_, name, stats)| (name, stats.io_time_ms))
+        .collect()
+}
+
+/// Whether a block device is a physical disk drawing power of its own
+///
+/// RAM-backed, loop, device-mapper and virtio disks are skipped, as is anything
+/// without a backing `device` link in `/sys/block`.
+fn is_physical_disk(root: &Path, name: &str) -> bool {
+    const VIRTUAL: [&str; 5] = ["zram", "loop", "ram", "dm-", "vd"];
+    !VIRTUAL.iter().any(|prefix| name.starts_with(prefix))
+        && root.join("sys/block").join(name).join("device").exists()
+}
+
+/// Physical NICs with link up, from `/sys/class/net`
+///
+/// Virtual interfaces (no `device` link) and links reporting no speed are skipped.
+fn read_nic_links(root: &Path) -> Vec<NicLink> {
+    let mut links = Vec::new();
+    let Ok(entries) = std::fs::read_dir(root.join("sys/class/net")) else {
+        return links;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.join("device").exists() {
+            continue;
+        }
+        let operstate = std::fs::read_to_string(path.join("operstate")).unwrap_or_default();
+        if operstate.trim() != "up" {
+            continue;
+        }
+        // Reads "-1" (or fails) when the speed is unknown
+        let speed = std::fs::read_to_string(path.join("speed"))
+            .ok()
+            .and_then(|s| s.trim().parse::<i64>().ok())
+            .filter(|s| *s > 0);
+        if let Some(speed) = speed {
+            links.push(NicLink {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                speed_mbps: speed as u32,
+            });
+        }
+    }
+    links.sort_by(|a, b| a.name.cmp(&b.name));
+    links
+}
+
+/// PSU input (and output) power from PMBus-style hwmon labels (`pin`, `pout*`)
+fn read_psu_hwmon(root: &Path) -> Option<WallPower> {
+    let mut input = 0.0;
+    let mut output = 0.0;
+    let entries = std::fs::read_dir(root.join("sys/class/hwmon")).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        for i in 1..=8 {
+            let Ok(label) = std::fs::read_to_string(path.join(format!("power{}_label", i))) else {
+                continue;
+            };
+            let watts = std::fs::read_to_string(path.join(format!("power{}_input", i)))
+                .ok()
+                .and_then(|s| s.trim().parse::<f64>().ok())
+                .map(|uw| uw / 1_000_000.0);
+            let Some(watts) = watts else { continue };
+            let label = label.trim().to_lowercase();
+            if label.starts_with("pin") {
+                input += watts;
+            } else if label.starts_with("pout") {
+                output += watts;
+            }
+        }
+    }
+    (input > 0.0).then_some(WallPower {
+        watts: input,
+        source: WallSource::PsuSensor,
+        psu_output_watts: (output > 0.0).then_some(output),
+    })
+}
+
+/// Fan speeds as a fraction of maximum, by RPM when the limits are known
+fn fan_loads() -> Vec<FanLoad> {
+    let Ok(monitor) = crate::fan_control::FanMonitor::new() else {
+        return Vec::new();
+    };
+    monitor
+        .fans()
+        .iter()
+        .map(|f| {
+            let speed_fraction = match (f.rpm, f.rpm_max) {
+                (Some(rpm), Some(max)) if max > 0 => rpm as f64 / max as f64,
+                _ => f.speed_percent as f64 / 100.0,
+            };
+            FanLoad {
+                name: f.name.clone(),
+                speed_fraction,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    fn coefficients() -> PowerCoefficients {
+        PowerCoefficients {
+            baseboard_watts: 10.0,
+            psu_efficiency: 0.9,
+            ..Default::default()
+        }
+    }
+
+    fn inputs() -> NodePowerInputs {
+        NodePowerInputs {
+            measured: vec![
+                ComponentPower::measured("socket0 package-0", ComponentKind::Cpu, 100.0),
+                ComponentPower::measured("GPU 0", ComponentKind::Gpu, 200.0),
+            ],
+            dimms: 8,
+            disks: vec![DiskLoad {
+                name: "nvme0n1".into(),
+                disk_type: DiskType::NvmeSsd,
+                busy: 0.5,
+            }],
+            nics: vec![NicLink {
+                name: "eth0".into(),
+                speed_mbps: 25_000,
+            }],
+            fans: vec![FanLoad {
+                name: "fan1".into(),
+                speed_fraction: 0.5,
+            }],
+            wall: None,
+        }
+    }
+
+    #[test]
+    fn test_breakdown_without_wall_power() {
+        let report = NodePowerModel::new(coefficients()).compute(&inputs());
+
+        // 8 DIMMs * 3 + NVMe (2.5 + 5.5 * 0.5) + 25G NIC + fan 3 * 0.125 + baseboard
+        let estimated = 24.0 + 5.25 + 8.0 + 0.375 + 10.0;
+        assert!(approx(report.measured_watts, 300.0));
+        assert!(approx(report.estimated_watts, estimated));
+        assert!(approx(report.dc_watts, 300.0 + estimated));
+        assert!(approx(report.node_watts, report.dc_watts / 0.9));
+        assert!(approx(
+            report.psu_loss_watts,
+            report.node_watts - report.dc_watts
+        ));
+        assert!(report.residual_watts.is_none());
+        assert_eq!(report.components[0].name, "GPU 0");
+        assert!(approx(report.watts_by_kind(ComponentKind::Dram), 24.0));
+    }
+
+    #[test]
+    fn test_rapl_dram_replaces_dimm_estimate() {
+        let mut inputs = inputs();
+        inputs.measured.push(ComponentPower::measured(
+            "socket0 dram",
+            ComponentKind::Dram,
+            12.0,
+        ));
+        let report = NodePowerModel::new(coefficients()).compute(&inputs);
+        let dram: Vec<_> = report
+            .components
+            .iter()
+            .filter(|c| c.kind == ComponentKind::Dram)
+            .collect();
+        assert_eq!(dram.len(), 1);
+        assert_eq!(dram[0].provenance, Provenance::Measured);
+    }
+
+    #[test]
+    fn test_wall_reconciliation_and_residual() {
+        let mut inputs = inputs();
+        let dc = NodePowerModel::new(coefficients())
+            .compute(&inputs)
+            .dc_watts;
+
+        inputs.wall = Some(WallPower {
+            watts: 450.0,
+            source: WallSource::Ipmi,
+            psu_output_watts: None,
+        });
+        let report = NodePowerModel::new(coefficients()).compute(&inputs);
+        assert!(approx(report.node_watts, 450.0));
+        assert!(approx(report.psu_loss_watts, 45.0));
+        assert!(approx(report.residual_watts.unwrap(), 405.0 - dc));
+        assert!(!report.psu_efficiency_measured);
+
+        // PSU telemetry gives the efficiency directly
+        inputs.wall = Some(WallPower {
+            watts: 400.0,
+            source: WallSource::PsuSensor,
+            psu_output_watts: Some(368.0),
+        });
+        let report = NodePowerModel::new(coefficients()).compute(&inputs);
+        assert!(report.psu_efficiency_measured);
+        assert!(approx(report.psu_efficiency, 0.92));
+        assert!(approx(report.psu_loss_watts, 32.0));
+        assert!(approx(report.residual_watts.unwrap(), 368.0 - dc));
+
+        // Battery power is DC already
+        inputs.wall = Some(WallPower {
+            watts: 30.0,
+            source: WallSource::Battery,
+            psu_output_watts: None,
+        });
+        let report = NodePowerModel::new(coefficients()).compute(&inputs);
+        assert!(approx(report.psu_loss_watts, 0.0));
+    }
+
+    #[test]
+    fn test_psu_efficiency_curve() {
+        let c = PowerCoefficients {
+            psu_rated_watts: Some(1000.0),
+            ..Default::default()
+        };
+        assert!(approx(c.psu_efficiency_at(50.0), 0.82));
+        assert!(approx(c.psu_efficiency_at(200.0), 0.87));
+        assert!(approx(c.psu_efficiency_at(350.0), 0.885));
+        assert!(approx(c.psu_efficiency_at(2000.0), 0.87));
+
+        let flat = PowerCoefficients::default();
+        assert!(approx(flat.psu_efficiency_at(350.0), flat.psu_efficiency));
+    }
+
+    #[test]
+    fn test_coefficients() {
+        let c = PowerCoefficients::default();
+        assert!(approx(c.fan_watts(1.0), c.fan_max_watts));
+        assert!(approx(c.fan_watts(0.5), c.fan_max_watts / 8.0));
+        assert!(approx(c.nic_watts(1000), c.nic_1g_watts));
+        assert!(approx(c.nic_watts(10_000), c.nic_10g_watts));
+        assert!(approx(c.nic_watts(100_000), c.nic_100g_watts));
+        assert!(approx(
+            c.disk_watts(DiskType::SataHdd, 2.0).unwrap(),
+            c.hdd_active_watts
+        ));
+        assert!(c.disk_watts(DiskType::Usb, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_sysfs_nics_and_psu() {
+        let fs = FakeRoot::new("node-power-sysfs");
+        fs.write("sys/class/net/eth0/operstate", "up\n");
+        fs.write("sys/class/net/eth0/speed", "10000\n");
+        fs.write("sys/class/net/eth0/device/vendor", "0x8086\n");
+        fs.write("sys/class/net/eth1/operstate", "down\n");
+        fs.write("sys/class/net/eth1/speed", "-1\n");
+        fs.write("sys/class/net/eth1/device/vendor", "0x8086\n");
+        fs.write("sys/class/net/lo/operstate", "unknown\n");
+        fs.write("sys/class/net/docker0/operstate", "up\n");
+        fs.write("sys/class/net/docker0/speed", "10000\n");
+
+        let nics = read_nic_links(fs.path());
+        assert_eq!(nics.len(), 1);
+        assert_eq!(nics[0].name, "eth0");
+        assert_eq!(nics[0].speed_mbps, 10_000);
+
+        assert!(read_psu_hwmon(fs.path()).is_none());
+        fs.write("sys/class/hwmon/hwmon3/name", "pmbus\n");
+        fs.write("sys/class/hwmon/hwmon3/power1_label", "pin\n");
+        fs.write("sys/class/hwmon/hwmon3/power1_input", "250000000\n");
+        fs.write("sys/class/hwmon/hwmon3/power2_label", "pout1\n");
+        fs.write("sys/class/hwmon/hwmon3/power2_input", "230000000\n");
+
+        let wall = read_psu_hwmon(fs.path()).unwrap();
+        assert_eq!(wall.source, WallSource::PsuSensor);
+        assert!(approx(wall.watts, 250.0));
+        assert!(approx(wall.psu_output_watts.unwrap(), 230.0));
+    }
+
+    #[test]
+    fn test_disk_busy_uses_io_ticks() {
+        let fs = FakeRoot::new("node-power-diskstats");
+        assert!(read_disk_busy_ms(fs.path()).is_empty());
+
+        // Read and write ticks sum to 9000 ms, but overlapping I/O kept the
+        // device busy for only 4000 ms
+        fs.write(
+            "proc/diskstats",
+            " 259       0 nvme0n1 100 0 800 5000 100 0 800 4000 0 4000 9000 0 0 0 0\n   \
+             8       0 sda 10 0 80 300 0 0 0 0 0 250 300 0 0 0 0\n",
+        );
+        let busy = read_disk_busy_ms(fs.path());
+        assert_eq!(busy.get("nvme0n1"), Some(&4000));
+        assert_eq!(busy.get("sda"), Some(&250));
+    }
+
+    #[test]
+    fn test_virtual_disks_skipped() {
+        let fs = FakeRoot::new("node-power-block");
+        for dev in ["sda", "nvme0n1", "vda", "loop0"] {
+            fs.write(&format!("sys/block/{}/device/model", dev), "disk\n");
+        }
+        fs.write("sys/block/zram0/size", "0\n");
+        fs.write("sys/block/md0/size", "0\n");
+
+        assert!(is_physical_disk(fs.path(), "sda"));
+        assert!(is_physical_disk(fs.path(), "nvme0n1"));
+        for dev in ["vda", "loop0", "zram0", "md0", "dm-0", "ram0"] {
+            assert!(!is_physical_disk(fs.path(), dev), "{}", dev);
+        }
+    }
+
+    #[test]
+    fn test_no_measured_components_is_positive_zero() {
+        let mut inputs = inputs();
+        inputs.measured.clear();
+        let report = NodePowerModel::new(coefficients()).compute(&inputs);
+        assert!(report.measured_watts == 0.0 && report.measured_watts.is_sign_positive());
+    }
+}
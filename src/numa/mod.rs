@@ -280,7 +280,7 @@ impl NumaMonitor {
         // Distance matrix
         if let Some(first_node) = node_ids.first() {
             let dist_path = node_base.join(format!("node{}/distance", first_node));
-            if let Ok(text) = std::fs::read_to_string(&dist_path) {
+            if std::fs::read_to_string(&dist_path).is_ok() {
                 let size = node_ids.len();
                 let mut distances = Vec::with_capacity(size * size);
 
@@ -300,7 +300,6 @@ impl NumaMonitor {
                     self.distance_matrix = Some(NumaDistanceMatrix { size, distances });
                 }
             }
-            let _ = text;
         }
     }
 
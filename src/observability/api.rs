@@ -138,6 +138,23 @@ pub struct ObservabilityApi {
     /// System context builder (caches static info)
     system_identity: Option<SystemIdentity>,
     hardware_inventory: Option<HardwareContext>,
+    /// Energy accountant fed by a background sampler, if running
+    energy: Option<crate::energy_accounting::SharedEnergyAccountant>,
+    /// Latest perf counter snapshot from a background sampler, if running
+    perf: Option<crate::perf::SharedPerfSnapshot>,
+    /// Latest pressure stall snapshot from a background monitor, if running
+    psi: Option<crate::psi::SharedPsiSnapshot>,
+    /// Latest OOM kill snapshot from a background monitor, if running
+    oom: Option<crate::oom::SharedOomSnapshot>,
+    /// Latest per-device I/O latency snapshot from a background monitor, if running
+    io_latency: Option<crate::io_scheduler::latency::SharedIoLatencySnapshot>,
+    /// Latest filesystem capacity forecast from a background forecaster, if running
+    capacity: Option<crate::capacity::SharedCapacitySnapshot>,
+    /// Latest RDMA port snapshot from a background monitor, if running
+    rdma: Option<crate::rdma::SharedRdmaSnapshot>,
+    /// Collectors run on each Prometheus scrape, including one per attached
+    /// snapshot source
+    collectors: Arc<crate::prometheus::CollectorRegistry>,
 }
 
 impl ObservabilityApi {
@@ -147,6 +164,14 @@ impl ObservabilityApi {
             permission_checker: Arc::new(RwLock::new(PermissionChecker::new(config.keys))),
             system_identity: None,
             hardware_inventory: None,
+            energy: None,
+            perf: None,
+            psi: None,
+            oom: None,
+            io_latency: None,
+            capacity: None,
+            rdma: None,
+            collectors: Arc::new(crate::prometheus::CollectorRegistry::new(Default::default())),
         }
     }
 
@@ -156,6 +181,183 @@ impl ObservabilityApi {
             permission_checker: Arc::new(RwLock::new(checker)),
             system_identity: None,
             hardware_inventory: None,
+            energy: None,
+            perf: None,
+            psi: None,
+            oom: None,
+            io_latency: None,
+            capacity: None,
+            rdma: None,
+            collectors: Arc::new(crate::prometheus::CollectorRegistry::new(Default::default())),
+        }
+    }
+
+    /// Attach an energy accountant to serve per-process/cgroup energy
+    pub fn set_energy_accountant(
+        &mut self,
+        accountant: crate::energy_accounting::SharedEnergyAccountant,
+    ) {
+        self.energy = Some(accountant);
+        self.register_snapshot_collectors();
+    }
+
+    /// Current energy report, if an accountant is attached (no permission check)
+    pub fn energy_report(&self) -> Option<crate::energy_accounting::EnergyReport> {
+        let accountant = self.energy.as_ref()?.read().ok()?;
+        Some(accountant.report())
+    }
+
+    /// Attach a perf sampler's snapshot slot to serve hardware counters
+    pub fn set_perf_snapshot(&mut self, snapshot: crate::perf::SharedPerfSnapshot) {
+        self.perf = Some(snapshot);
+        self.register_snapshot_collectors();
+    }
+
+    /// Latest perf snapshot, if a sampler is attached (no permission check)
+    pub fn perf_snapshot(&self) -> Option<crate::perf::PerfSnapshot> {
+        self.perf.as_ref()?.read().ok()?.clone()
+    }
+
+    /// Attach a PSI monitor's snapshot slot to serve pressure stall information
+    pub fn set_psi_snapshot(&mut self, snapshot: crate::psi::SharedPsiSnapshot) {
+        self.psi = Some(snapshot);
+        self.register_snapshot_collectors();
+    }
+
+    /// Latest PSI snapshot, if a monitor is attached (no permission check)
+    pub fn psi_snapshot(&self) -> Option<crate::psi::PsiSnapshot> {
+        self.psi.as_ref()?.read().ok()?.clone()
+    }
+
+    /// Attach an OOM monitor's snapshot slot to serve recent kills
+    pub fn set_oom_snapshot(&mut self, snapshot: crate::oom::SharedOomSnapshot) {
+        self.oom = Some(snapshot);
+    }
+
+    /// Latest OOM snapshot, if a monitor is attached (no permission check)
+    pub fn oom_snapshot(&self) -> Option<crate::oom::OomSnapshot> {
+        self.oom.as_ref()?.read().ok()?.clone()
+    }
+
+    /// Attach an I/O latency monitor's snapshot slot to serve await/queue statistics
+    pub fn set_io_latency_snapshot(
+        &mut self,
+        snapshot: crate::io_scheduler::latency::SharedIoLatencySnapshot,
+    ) {
+        self.io_latency = Some(snapshot);
+        self.register_snapshot_collectors();
+    }
+
+    /// Latest I/O latency snapshot, if a monitor is attached (no permission check)
+    pub fn io_latency_snapshot(&self) -> Option<crate::io_scheduler::latency::IoLatencySnapshot> {
+        self.io_latency.as_ref()?.read().ok()?.clone()
+    }
+
+    /// Attach a capacity forecaster's snapshot slot to serve time-to-full projections
+    pub fn set_capacity_snapshot(&mut self, snapshot: crate::capacity::SharedCapacitySnapshot) {
+        self.capacity = Some(snapshot);
+        self.register_snapshot_collectors();
+    }
+
+    /// Latest capacity snapshot, if a forecaster is attached (no permission check)
+    pub fn capacity_snapshot(&self) -> Option<crate::capacity::CapacitySnapshot> {
+        self.capacity.as_ref()?.read().ok()?.clone()
+    }
+
+    /// Attach an RDMA monitor's snapshot slot to serve fabric port state and counters
+    pub fn set_rdma_snapshot(&mut self, snapshot: crate::rdma::SharedRdmaSnapshot) {
+        self.rdma = Some(snapshot);
+        self.register_snapshot_collectors();
+    }
+
+    /// Latest RDMA snapshot, if a monitor is attached (no permission check)
+    pub fn rdma_snapshot(&self) -> Option<crate::rdma::RdmaSnapshot> {
+        self.rdma.as_ref()?.read().ok()?.clone()
+    }
+
+    /// Attach a collector registry whose collectors are run on each Prometheus scrape
+    ///
+    /// Collectors for the attached snapshot sources are added to it.
+    pub fn set_collector_registry(
+        &mut self,
+        registry: Arc<crate::prometheus::CollectorRegistry>,
+    ) {
+        self.collectors = registry;
+        self.register_snapshot_collectors();
+    }
+
+    /// Registry run on each Prometheus scrape (no permission check)
+    pub fn collector_registry(&self) -> Arc<crate::prometheus::CollectorRegistry> {
+        Arc::clone(&self.collectors)
+    }
+
+    /// Register a collector for each attached snapshot source, replacing any
+    /// registered earlier, so they share the registry's timeouts and
+    /// scrape meta-metrics
+    fn register_snapshot_collectors(&self) {
+        use crate::prometheus::{FnCollector, PrometheusExporter};
+
+        fn snapshot<T: Send + Sync + 'static>(
+            name: &'static str,
+            slot: Arc<RwLock<Option<T>>>,
+            collect: fn(&mut PrometheusExporter, &T),
+        ) -> Arc<FnCollector> {
+            Arc::new(FnCollector::new(name, move |exporter| {
+                let snapshot = slot.read().map_err(|_| {
+                    crate::error::SimonError::Other(format!("{} snapshot lock poisoned", name))
+                })?;
+                if let Some(snapshot) = snapshot.as_ref() {
+                    collect(exporter, snapshot);
+                }
+                Ok(())
+            }))
+        }
+
+        if let Some(energy) = self.energy.clone() {
+            self.collectors
+                .register(Arc::new(FnCollector::new("energy", move |exporter| {
+                    let accountant = energy.read().map_err(|_| {
+                        crate::error::SimonError::Other("energy accountant lock poisoned".into())
+                    })?;
+                    exporter.collect_energy_metrics(&accountant.report());
+                    Ok(())
+                })));
+        }
+
+        if let Some(slot) = self.perf.clone() {
+            self.collectors.register(snapshot(
+                "perf",
+                slot,
+                PrometheusExporter::collect_perf_metrics,
+            ));
+        }
+        if let Some(slot) = self.psi.clone() {
+            self.collectors.register(snapshot(
+                "psi",
+                slot,
+                PrometheusExporter::collect_psi_metrics,
+            ));
+        }
+        if let Some(slot) = self.io_latency.clone() {
+            self.collectors.register(snapshot(
+                "io_latency",
+                slot,
+                PrometheusExporter::collect_io_latency_metrics,
+            ));
+        }
+        if let Some(slot) = self.capacity.clone() {
+            self.collectors.register(snapshot(
+                "capacity",
+                slot,
+                PrometheusExporter::collect_capacity_metrics,
+            ));
+        }
+        if let Some(slot) = self.rdma.clone() {
+            self.collectors.register(snapshot(
+                "rdma",
+                slot,
+                PrometheusExporter::collect_rdma_metrics,
+            ));
         }
     }
 
@@ -219,7 +421,7 @@ impl ObservabilityApi {
             .check_permission(ctx, Capability::SystemInfo, Scope::Read)
             .is_ok()
         {
-            builder = builder.system(self.collect_system_identity());
+            builder = builder.system(SystemIdentity::detect());
             included.push("system".to_string());
         } else {
             excluded.push("system".to_string());
@@ -425,6 +627,128 @@ impl ObservabilityApi {
         })
     }
 
+    /// Get energy attributed to processes, containers, units and cgroups
+    pub fn get_energy(
+        &self,
+        ctx: &RequestContext,
+    ) -> Result<ApiResponse<crate::energy_accounting::EnergyReport>> {
+        self.check_permission(ctx, Capability::Power, Scope::Read)?;
+        self.check_permission(ctx, Capability::Process, Scope::Read)?;
+
+        let start = Instant::now();
+        let report = self
+            .energy_report()
+            .ok_or_else(|| ObservabilityError::NotAvailable("Energy accounting not running".into()))?;
+
+        Ok(ApiResponse {
+            data: report,
+            meta: self.build_response_meta(ctx, start),
+        })
+    }
+
+    /// Get hardware performance counters (IPC, miss rates, memory bandwidth)
+    pub fn get_perf(&self, ctx: &RequestContext) -> Result<ApiResponse<crate::perf::PerfSnapshot>> {
+        self.check_permission(ctx, Capability::Cpu, Scope::Read)?;
+
+        let start = Instant::now();
+        let snapshot = self
+            .perf_snapshot()
+            .ok_or_else(|| ObservabilityError::NotAvailable("Perf sampler not running".into()))?;
+
+        Ok(ApiResponse {
+            data: snapshot,
+            meta: self.build_response_meta(ctx, start),
+        })
+    }
+
+    /// Get system-wide and per-cgroup pressure stall information
+    pub fn get_pressure(
+        &self,
+        ctx: &RequestContext,
+    ) -> Result<ApiResponse<crate::psi::PsiSnapshot>> {
+        self.check_permission(ctx, Capability::Cpu, Scope::Read)?;
+        self.check_permission(ctx, Capability::Memory, Scope::Read)?;
+
+        let start = Instant::now();
+        let snapshot = self
+            .psi_snapshot()
+            .ok_or_else(|| ObservabilityError::NotAvailable("PSI monitor not running".into()))?;
+
+        Ok(ApiResponse {
+            data: snapshot,
+            meta: self.build_response_meta(ctx, start),
+        })
+    }
+
+    /// Get recent OOM kills, per-cgroup memory limit counters and reclaim activity
+    pub fn get_oom(&self, ctx: &RequestContext) -> Result<ApiResponse<crate::oom::OomSnapshot>> {
+        self.check_permission(ctx, Capability::Memory, Scope::Read)?;
+        self.check_permission(ctx, Capability::Process, Scope::Read)?;
+
+        let start = Instant::now();
+        let snapshot = self
+            .oom_snapshot()
+            .ok_or_else(|| ObservabilityError::NotAvailable("OOM monitor not running".into()))?;
+
+        Ok(ApiResponse {
+            data: snapshot,
+            meta: self.build_response_meta(ctx, start),
+        })
+    }
+
+    /// Get per-device await, queue size, utilization, latency percentiles and
+    /// saturation warnings
+    pub fn get_io_latency(
+        &self,
+        ctx: &RequestContext,
+    ) -> Result<ApiResponse<crate::io_scheduler::latency::IoLatencySnapshot>> {
+        self.check_permission(ctx, Capability::Disk, Scope::Read)?;
+
+        let start = Instant::now();
+        let snapshot = self.io_latency_snapshot().ok_or_else(|| {
+            ObservabilityError::NotAvailable("I/O latency monitor not running".into())
+        })?;
+
+        Ok(ApiResponse {
+            data: snapshot,
+            meta: self.build_response_meta(ctx, start),
+        })
+    }
+
+    /// Get per-mount fill rates, time-to-full projections for space and inodes,
+    /// and capacity alerts
+    pub fn get_capacity(
+        &self,
+        ctx: &RequestContext,
+    ) -> Result<ApiResponse<crate::capacity::CapacitySnapshot>> {
+        self.check_permission(ctx, Capability::Disk, Scope::Read)?;
+
+        let start = Instant::now();
+        let snapshot = self.capacity_snapshot().ok_or_else(|| {
+            ObservabilityError::NotAvailable("Capacity forecaster not running".into())
+        })?;
+
+        Ok(ApiResponse {
+            data: snapshot,
+            meta: self.build_response_meta(ctx, start),
+        })
+    }
+
+    /// Get RDMA port state, link rates, counters and rates with PCIe/NUMA placement
+    pub fn get_rdma(&self, ctx: &RequestContext) -> Result<ApiResponse<crate::rdma::RdmaSnapshot>> {
+        self.check_permission(ctx, Capability::Network, Scope::Read)?;
+
+        let start = Instant::now();
+        let snapshot = self
+            .rdma_snapshot()
+            .ok_or_else(|| ObservabilityError::NotAvailable("RDMA monitor not running".into()))?;
+
+        Ok(ApiResponse {
+            data: snapshot,
+            meta: self.build_response_meta(ctx, start),
+        })
+    }
+
     /// Get power/battery status
     pub fn get_power_status(
         &self,
@@ -493,54 +817,6 @@ impl ObservabilityApi {
     // ========== Data Collection Methods ==========
     // These would integrate with the actual monitoring systems
 
-    fn collect_system_identity(&self) -> SystemIdentity {
-        use crate::motherboard;
-        let sys_info = motherboard::get_system_info().ok();
-        let os_version = sys_info
-            .as_ref()
-            .map(|s| s.os_version.clone())
-            .unwrap_or_default();
-        let kernel_version = sys_info
-            .as_ref()
-            .and_then(|s| s.kernel_version.clone())
-            .unwrap_or_default();
-        let hostname = sys_info
-            .as_ref()
-            .and_then(|s| s.hostname.clone())
-            .or_else(|| std::env::var("COMPUTERNAME").ok())
-            .or_else(|| std::env::var("HOSTNAME").ok())
-            .unwrap_or_else(|| "unknown".into());
-
-        #[cfg(target_os = "windows")]
-        let uptime_seconds = Some(crate::platform::windows::get_system_uptime().as_secs());
-        #[cfg(not(target_os = "windows"))]
-        let uptime_seconds = std::fs::read_to_string("/proc/uptime")
-            .ok()
-            .and_then(|s| {
-                s.split_whitespace()
-                    .next()
-                    .and_then(|v| v.parse::<f64>().ok())
-            })
-            .map(|v| v as u64);
-
-        SystemIdentity {
-            hostname,
-            os_name: sys_info
-                .as_ref()
-                .map(|s| s.os_name.clone())
-                .unwrap_or_else(|| std::env::consts::OS.to_string()),
-            os_version,
-            kernel_version,
-            architecture: sys_info
-                .as_ref()
-                .map(|s| s.architecture.clone())
-                .unwrap_or_else(|| std::env::consts::ARCH.to_string()),
-            machine_id: sys_info.as_ref().and_then(|s| s.uuid.clone()),
-            boot_time: None,
-            uptime_seconds,
-        }
-    }
-
     fn collect_hardware_context(
         &self,
         ctx: &RequestContext,
@@ -955,7 +1231,8 @@ impl ObservabilityApi {
                             || iface.name == "Loopback Pseudo-Interface 1"
                         {
                             "loopback".to_string()
-                        } else if iface.name.starts_with("wl")
+                        } else if crate::wireless::is_wireless(&iface.name)
+                            || iface.name.starts_with("wl")
                             || iface.name.contains("Wi-Fi")
                             || iface.name.contains("Wireless")
                         {
@@ -1427,4 +1704,21 @@ mod tests {
         let checker = api.permission_checker();
         assert!(checker.read().unwrap().rate_limit_status("nonexistent").is_none());
     }
+
+    #[test]
+    fn test_snapshot_sources_join_the_collector_registry() {
+        let mut api = ObservabilityApi::new(ApiConfig::default());
+        api.set_psi_snapshot(Arc::new(RwLock::new(None)));
+        assert_eq!(api.collector_registry().names(), vec!["psi"]);
+
+        // A registry attached later picks up the sources attached before it
+        let shared = Arc::new(crate::prometheus::CollectorRegistry::new(Default::default()));
+        api.set_collector_registry(Arc::clone(&shared));
+        api.set_rdma_snapshot(Arc::new(RwLock::new(None)));
+        assert_eq!(shared.names(), vec!["psi", "rdma"]);
+
+        let output = api.collector_registry().gather("simon");
+        assert!(output.contains("simon_scrape_collector_success{collector=\"psi\"} 1"));
+        assert!(output.contains("simon_scrape_collector_success{collector=\"rdma\"} 1"));
+    }
 }
@@ -102,6 +102,57 @@ pub struct SystemIdentity {
     pub uptime_seconds: Option<u64>,
 }
 
+impl SystemIdentity {
+    /// Identify the running host (hostname, OS, kernel, architecture, uptime)
+    pub fn detect() -> Self {
+        use crate::motherboard;
+        let sys_info = motherboard::get_system_info().ok();
+        let os_version = sys_info
+            .as_ref()
+            .map(|s| s.os_version.clone())
+            .unwrap_or_default();
+        let kernel_version = sys_info
+            .as_ref()
+            .and_then(|s| s.kernel_version.clone())
+            .unwrap_or_default();
+        let hostname = sys_info
+            .as_ref()
+            .and_then(|s| s.hostname.clone())
+            .or_else(|| std::env::var("COMPUTERNAME").ok())
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .unwrap_or_else(|| "unknown".into());
+
+        #[cfg(target_os = "windows")]
+        let uptime_seconds = Some(crate::platform::windows::get_system_uptime().as_secs());
+        #[cfg(not(target_os = "windows"))]
+        let uptime_seconds = std::fs::read_to_string("/proc/uptime")
+            .ok()
+            .and_then(|s| {
+                s.split_whitespace()
+                    .next()
+                    .and_then(|v| v.parse::<f64>().ok())
+            })
+            .map(|v| v as u64);
+
+        Self {
+            hostname,
+            os_name: sys_info
+                .as_ref()
+                .map(|s| s.os_name.clone())
+                .unwrap_or_else(|| std::env::consts::OS.to_string()),
+            os_version,
+            kernel_version,
+            architecture: sys_info
+                .as_ref()
+                .map(|s| s.architecture.clone())
+                .unwrap_or_else(|| std::env::consts::ARCH.to_string()),
+            machine_id: sys_info.as_ref().and_then(|s| s.uuid.clone()),
+            boot_time: None,
+            uptime_seconds,
+        }
+    }
+}
+
 /// Hardware inventory context
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HardwareContext {
@@ -207,6 +207,7 @@ pub mod event_types {
         pub const THROTTLING: &str = "throttling";
         pub const HIGH_TEMPERATURE: &str = "high_temperature";
         pub const FREQUENCY_CHANGE: &str = "frequency_change";
+        pub const PRESSURE_STALL: &str = "pressure_stall";
     }
 
     /// GPU events
@@ -227,6 +228,9 @@ pub mod event_types {
         pub const SWAP_ACTIVE: &str = "swap_active";
         pub const HIGH_SWAP: &str = "high_swap";
         pub const OOM_RISK: &str = "oom_risk";
+        pub const PRESSURE_STALL: &str = "pressure_stall";
+        pub const OOM_KILL: &str = "oom_kill";
+        pub const MEMORY_LIMIT: &str = "memory_limit";
     }
 
     /// Disk events
@@ -235,6 +239,10 @@ pub mod event_types {
         pub const HIGH_IO: &str = "high_io";
         pub const SMART_WARNING: &str = "smart_warning";
         pub const MOUNT_CHANGE: &str = "mount_change";
+        pub const PRESSURE_STALL: &str = "pressure_stall";
+        pub const HIGH_LATENCY: &str = "high_latency";
+        pub const CAPACITY_FORECAST: &str = "capacity_forecast";
+        pub const FILL_RATE_CHANGE: &str = "fill_rate_change";
     }
 
     /// Network events
@@ -244,6 +252,7 @@ pub mod event_types {
         pub const LINK_DOWN: &str = "link_down";
         pub const HIGH_ERRORS: &str = "high_errors";
         pub const HIGH_DROPPED: &str = "high_dropped";
+        pub const WIFI_ROAM: &str = "wifi_roam";
     }
 
     /// Power events
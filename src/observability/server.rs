@@ -95,11 +95,18 @@ pub mod routes {
     /// Hardware inventory
     pub const GPUS: &str = "/gpus";
     pub const CPU: &str = "/cpu";
+    pub const PERF: &str = "/perf";
+    pub const PRESSURE: &str = "/pressure";
+    pub const OOM: &str = "/oom";
+    pub const IO_LATENCY: &str = "/io-latency";
+    pub const CAPACITY: &str = "/capacity";
+    pub const RDMA: &str = "/rdma";
     pub const MEMORY: &str = "/memory";
     pub const DISKS: &str = "/disks";
     pub const NETWORK: &str = "/network";
     pub const MOTHERBOARD: &str = "/motherboard";
     pub const POWER: &str = "/power";
+    pub const ENERGY: &str = "/energy";
     pub const FANS: &str = "/fans";
     pub const TEMPERATURES: &str = "/temperatures";
 
@@ -376,6 +383,153 @@ impl OpenApiSpec {
             },
         );
 
+        // Energy
+        paths.insert(
+            format!("{}{}", routes::API_V1, routes::ENERGY),
+            OpenApiPath {
+                get: Some(OpenApiOperation {
+                    summary: "Get energy accounting".to_string(),
+                    description: "Returns energy, cost and CO2 per process, container, systemd unit and cgroup".to_string(),
+                    operation_id: "getEnergy".to_string(),
+                    tags: vec!["power".to_string()],
+                    security: vec![HashMap::from([("bearerAuth".to_string(), vec![])])],
+                    responses: HashMap::from([
+                        ("200".to_string(), OpenApiResponse { description: "Energy report".to_string() }),
+                        ("404".to_string(), OpenApiResponse { description: "Energy accounting not running".to_string() }),
+                    ]),
+                }),
+                post: None,
+                put: None,
+                delete: None,
+            },
+        );
+
+        // Hardware performance counters
+        paths.insert(
+            format!("{}{}", routes::API_V1, routes::PERF),
+            OpenApiPath {
+                get: Some(OpenApiOperation {
+                    summary: "Get hardware performance counters".to_string(),
+                    description: "Returns IPC, cache/branch miss rates, stalled cycles and memory bandwidth from perf_event counters".to_string(),
+                    operation_id: "getPerf".to_string(),
+                    tags: vec!["hardware".to_string()],
+                    security: vec![HashMap::from([("bearerAuth".to_string(), vec![])])],
+                    responses: HashMap::from([
+                        ("200".to_string(), OpenApiResponse { description: "Perf counter snapshot".to_string() }),
+                        ("404".to_string(), OpenApiResponse { description: "Perf sampler not running".to_string() }),
+                    ]),
+                }),
+                post: None,
+                put: None,
+                delete: None,
+            },
+        );
+
+        // Pressure stall information
+        paths.insert(
+            format!("{}{}", routes::API_V1, routes::PRESSURE),
+            OpenApiPath {
+                get: Some(OpenApiOperation {
+                    summary: "Get pressure stall information".to_string(),
+                    description: "Returns CPU, memory, IO and IRQ PSI (some/full avg10/60/300, interval stall %) system-wide and per cgroup".to_string(),
+                    operation_id: "getPressure".to_string(),
+                    tags: vec!["system".to_string()],
+                    security: vec![HashMap::from([("bearerAuth".to_string(), vec![])])],
+                    responses: HashMap::from([
+                        ("200".to_string(), OpenApiResponse { description: "PSI snapshot".to_string() }),
+                        ("404".to_string(), OpenApiResponse { description: "PSI monitor not running".to_string() }),
+                    ]),
+                }),
+                post: None,
+                put: None,
+                delete: None,
+            },
+        );
+
+        // OOM kills
+        paths.insert(
+            format!("{}{}", routes::API_V1, routes::OOM),
+            OpenApiPath {
+                get: Some(OpenApiOperation {
+                    summary: "Get OOM kills".to_string(),
+                    description: "Returns recent OOM kill victims with container/pod attribution, per-cgroup memory.events counters and reclaim activity".to_string(),
+                    operation_id: "getOom".to_string(),
+                    tags: vec!["system".to_string()],
+                    security: vec![HashMap::from([("bearerAuth".to_string(), vec![])])],
+                    responses: HashMap::from([
+                        ("200".to_string(), OpenApiResponse { description: "OOM snapshot".to_string() }),
+                        ("404".to_string(), OpenApiResponse { description: "OOM monitor not running".to_string() }),
+                    ]),
+                }),
+                post: None,
+                put: None,
+                delete: None,
+            },
+        );
+
+        // I/O latency
+        paths.insert(
+            format!("{}{}", routes::API_V1, routes::IO_LATENCY),
+            OpenApiPath {
+                get: Some(OpenApiOperation {
+                    summary: "Get disk I/O latency".to_string(),
+                    description: "Returns per-device await, service time, queue size and utilization (iostat -x), windowed p50/p99 estimates, cgroup io.stat latency and saturation warnings".to_string(),
+                    operation_id: "getIoLatency".to_string(),
+                    tags: vec!["system".to_string()],
+                    security: vec![HashMap::from([("bearerAuth".to_string(), vec![])])],
+                    responses: HashMap::from([
+                        ("200".to_string(), OpenApiResponse { description: "I/O latency snapshot".to_string() }),
+                        ("404".to_string(), OpenApiResponse { description: "I/O latency monitor not running".to_string() }),
+                    ]),
+                }),
+                post: None,
+                put: None,
+                delete: None,
+            },
+        );
+
+        // Capacity forecasting
+        paths.insert(
+            format!("{}{}", routes::API_V1, routes::CAPACITY),
+            OpenApiPath {
+                get: Some(OpenApiOperation {
+                    summary: "Get filesystem capacity forecast".to_string(),
+                    description: "Returns per-mount fill rates, projected time to full for space and inodes with confidence intervals, and exhaustion/fill-rate alerts".to_string(),
+                    operation_id: "getCapacity".to_string(),
+                    tags: vec!["system".to_string()],
+                    security: vec![HashMap::from([("bearerAuth".to_string(), vec![])])],
+                    responses: HashMap::from([
+                        ("200".to_string(), OpenApiResponse { description: "Capacity snapshot".to_string() }),
+                        ("404".to_string(), OpenApiResponse { description: "Capacity forecaster not running".to_string() }),
+                    ]),
+                }),
+                post: None,
+                put: None,
+                delete: None,
+            },
+        );
+
+        // RDMA fabric
+        paths.insert(
+            format!("{}{}", routes::API_V1, routes::RDMA),
+            OpenApiPath {
+                get: Some(OpenApiOperation {
+                    summary: "Get RDMA port status".to_string(),
+                    description: "Returns InfiniBand/RoCE port state, link rate, LID/GID, IB and vendor counters with per-second rates, PCIe/NUMA placement and port alerts".to_string(),
+                    operation_id: "getRdma".to_string(),
+                    tags: vec!["hardware".to_string()],
+                    security: vec![HashMap::from([("bearerAuth".to_string(), vec![])])],
+                    responses: HashMap::from([
+                        ("200".to_string(), OpenApiResponse { description: "RDMA snapshot".to_string() }),
+                        ("404".to_string(), OpenApiResponse { description: "RDMA monitor not running".to_string() }),
+                    ]),
+                }),
+                post: None,
+                put: None,
+                delete: None,
+            },
+        );
+
         // Add more paths...
 
         let mut security_schemes = HashMap::new();
@@ -504,11 +658,18 @@ impl RequestHandler {
             // Hardware
             ("GET", path) if path == routes::GPUS => self.handle_get_gpus(ctx),
             ("GET", path) if path == routes::CPU => self.handle_get_cpu(ctx),
+            ("GET", path) if path == routes::PERF => self.handle_get_perf(ctx),
+            ("GET", path) if path == routes::PRESSURE => self.handle_get_pressure(ctx),
+            ("GET", path) if path == routes::OOM => self.handle_get_oom(ctx),
+            ("GET", path) if path == routes::IO_LATENCY => self.handle_get_io_latency(ctx),
+            ("GET", path) if path == routes::CAPACITY => self.handle_get_capacity(ctx),
+            ("GET", path) if path == routes::RDMA => self.handle_get_rdma(ctx),
             ("GET", path) if path == routes::MEMORY => self.handle_get_memory(ctx),
             ("GET", path) if path == routes::DISKS => self.handle_get_disks(ctx),
             ("GET", path) if path == routes::NETWORK => self.handle_get_network(ctx),
             ("GET", path) if path == routes::MOTHERBOARD => self.handle_get_motherboard(ctx),
             ("GET", path) if path == routes::POWER => self.handle_get_power(ctx),
+            ("GET", path) if path == routes::ENERGY => self.handle_get_energy(ctx),
             ("GET", path) if path == routes::FANS => self.handle_get_fans(ctx),
             ("GET", path) if path == routes::TEMPERATURES => self.handle_get_temperatures(ctx),
 
@@ -693,8 +854,81 @@ impl RequestHandler {
         }
     }
 
+    fn handle_get_energy(&self, ctx: RequestContext) -> HttpResponse {
+        match self.api.read() {
+            Ok(api) => match api.get_energy(&ctx) {
+                Ok(response) => HttpResponse::ok(&response),
+                Err(e) => self.error_response(e),
+            },
+            Err(_) => HttpResponse::internal_error("Failed to acquire API lock"),
+        }
+    }
+
+    fn handle_get_perf(&self, ctx: RequestContext) -> HttpResponse {
+        match self.api.read() {
+            Ok(api) => match api.get_perf(&ctx) {
+                Ok(response) => HttpResponse::ok(&response),
+                Err(e) => self.error_response(e),
+            },
+            Err(_) => HttpResponse::internal_error("Failed to acquire API lock"),
+        }
+    }
+
+    fn handle_get_pressure(&self, ctx: RequestContext) -> HttpResponse {
+        match self.api.read() {
+            Ok(api) => match api.get_pressure(&ctx) {
+                Ok(response) => HttpResponse::ok(&response),
+                Err(e) => self.error_response(e),
+            },
+            Err(_) => HttpResponse::internal_error("Failed to acquire API lock"),
+        }
+    }
+
+    fn handle_get_oom(&self, ctx: RequestContext) -> HttpResponse {
+        match self.api.read() {
+            Ok(api) => match api.get_oom(&ctx) {
+                Ok(response) => HttpResponse::ok(&response),
+                Err(e) => self.error_response(e),
+            },
+            Err(_) => HttpResponse::internal_error("Failed to acquire API lock"),
+        }
+    }
+
+    fn handle_get_io_latency(&self, ctx: RequestContext) -> HttpResponse {
+        match self.api.read() {
+            Ok(api) => match api.get_io_latency(&ctx) {
+                Ok(response) => HttpResponse::ok(&response),
+                Err(e) => self.error_response(e),
+            },
+            Err(_) => HttpResponse::internal_error("Failed to acquire API lock"),
+        }
+    }
+
+    fn handle_get_capacity(&self, ctx: RequestContext) -> HttpResponse {
+        match self.api.read() {
+            Ok(api) => match api.get_capacity(&ctx) {
+                Ok(response) => HttpResponse::ok(&response),
+                Err(e) => self.error_response(e),
+            },
+            Err(_) => HttpResponse::internal_error("Failed to acquire API lock"),
+        }
+    }
+
+    fn handle_get_rdma(&self, ctx: RequestContext) -> HttpResponse {
+        match self.api.read() {
+            Ok(api) => match api.get_rdma(&ctx) {
+                Ok(response) => HttpResponse::ok(&response),
+                Err(e) => self.error_response(e),
+            },
+            Err(_) => HttpResponse::internal_error("Failed to acquire API lock"),
+        }
+    }
+
     fn handle_prometheus_metrics(&self) -> HttpResponse {
-        let metrics = self.metric_collector.export_prometheus();
+        let mut metrics = self.metric_collector.export_prometheus();
+        if let Some(registry) = self.api.read().ok().map(|api| api.collector_registry()) {
+            metrics.push_str(&registry.gather("simon"));
+        }
         HttpResponse {
             status: 200,
             headers: HashMap::from([
@@ -0,0 +1,903 @@
+//! OpenTelemetry (OTLP) export
+//!
+//! Pushes [`MetricCollector`] time series as OTLP metrics and
+//! [`EventManager`] events as OTLP log records to an OpenTelemetry collector,
+//! over OTLP/HTTP (protobuf) or OTLP/gRPC. Every request carries resource
+//! attributes for the host: [`SystemIdentity`], the cloud provider reported
+//! by the hypervisor, Kubernetes pod details and the container ID.
+//!
+//! Series keys recorded with labels (`name:{k=v,...}`) become data point
+//! attributes; names ending in `_total` are exported as monotonic cumulative
+//! sums and everything else as gauges. Requests are split into batches of at
+//! most `max_batch_size` points or records, gzip-compressed, and retried with
+//! exponential backoff when the collector throttles or is unreachable.
+//!
+//! OTLP/HTTP needs the `remote-backends` feature and OTLP/gRPC the
+//! `otlp-grpc` feature; both accept `https://` endpoints. With `otlp-grpc`,
+//! [`OtlpReceiver`] is a loopback stand-in collector for tests and local
+//! debugging.
+
+pub mod proto;
+#[cfg(feature = "otlp-grpc")]
+pub mod receiver;
+pub(crate) mod transport;
+
+pub use proto::{AnyValue, KeyValue, LogRecord, Metric, MetricData, NumberDataPoint};
+#[cfg(feature = "otlp-grpc")]
+pub use receiver::{OtlpReceiver, ReceivedExport};
+
+use crate::error::{Result, SimonError};
+use crate::observability::{
+    EventFilter, EventManager, EventSeverity, MetricCollector, SubscriptionId, SystemEvent,
+    SystemIdentity,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use transport::{Request, Transport};
+
+/// OTLP transport protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OtlpProtocol {
+    /// Protobuf over HTTP (`POST /v1/metrics`, `/v1/logs`), default port 4318
+    #[default]
+    #[serde(rename = "http/protobuf")]
+    HttpProtobuf,
+    /// gRPC over HTTP/2, default port 4317
+    #[serde(rename = "grpc")]
+    Grpc,
+}
+
+/// Request body compression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OtlpCompression {
+    #[default]
+    Gzip,
+    None,
+}
+
+/// Exporter configuration (`[otlp]` in the daemon config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtlpConfig {
+    /// Start the exporter with the daemon (off unless set in `[otlp]`)
+    pub enabled: bool,
+    /// Collector base URL (`http://` or `https://`); defaults to
+    /// `http://localhost:4318` for HTTP and `http://localhost:4317` for gRPC
+    pub endpoint: Option<String>,
+    pub protocol: OtlpProtocol,
+    pub compression: OtlpCompression,
+    /// Extra request headers (e.g. authentication tokens)
+    pub headers: BTreeMap<String, String>,
+    /// Export interval in milliseconds
+    pub interval_ms: u64,
+    /// Per-request timeout in milliseconds
+    pub timeout_ms: u64,
+    /// Maximum data points or log records per request
+    pub max_batch_size: usize,
+    /// Events held while waiting for export; the oldest are dropped beyond this
+    pub max_queue_size: usize,
+    /// Retries after a retryable failure before a batch is dropped
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// `service.name` resource attribute
+    pub service_name: String,
+    /// Extra resource attributes; these override detected values
+    pub resource_attributes: BTreeMap<String, String>,
+    pub export_metrics: bool,
+    pub export_logs: bool,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            protocol: OtlpProtocol::HttpProtobuf,
+            compression: OtlpCompression::Gzip,
+            headers: BTreeMap::new(),
+            interval_ms: 10_000,
+            timeout_ms: 10_000,
+            max_batch_size: 2000,
+            max_queue_size: 10_000,
+            max_retries: 5,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 30_000,
+            service_name: "simon".into(),
+            resource_attributes: BTreeMap::new(),
+            export_metrics: true,
+            export_logs: true,
+        }
+    }
+}
+
+impl OtlpConfig {
+    /// Configured endpoint, or the protocol's default local collector
+    pub fn endpoint(&self) -> String {
+        match (&self.endpoint, self.protocol) {
+            (Some(endpoint), _) => endpoint.clone(),
+            (None, OtlpProtocol::HttpProtobuf) => "http://localhost:4318".into(),
+            (None, OtlpProtocol::Grpc) => "http://localhost:4317".into(),
+        }
+    }
+}
+
+/// Delivery counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OtlpStats {
+    /// Requests accepted by the collector
+    pub exports_ok: u64,
+    /// Requests dropped after a permanent error or exhausted retries
+    pub exports_failed: u64,
+    pub retries: u64,
+    pub data_points_sent: u64,
+    pub data_points_dropped: u64,
+    pub log_records_sent: u64,
+    /// Records lost to a full queue or a failed export
+    pub log_records_dropped: u64,
+    /// Items the collector reported as rejected in `partial_success`
+    pub rejected: u64,
+    pub last_error: Option<String>,
+    /// Unix time of the last successful export
+    pub last_export: Option<u64>,
+}
+
+/// Resource attributes for this host, following OpenTelemetry semantic conventions
+pub fn resource_attributes(config: &OtlpConfig) -> Vec<KeyValue> {
+    let identity = SystemIdentity::detect();
+    let mut attrs = vec![
+        KeyValue::new("service.name", config.service_name.as_str()),
+        KeyValue::new("service.version", crate::VERSION),
+        KeyValue::new("host.name", identity.hostname.as_str()),
+        KeyValue::new("host.arch", host_arch(&identity.architecture)),
+        KeyValue::new("os.type", os_type()),
+        KeyValue::new(
+            "os.description",
+            format!("{} {}", identity.os_name, identity.os_version).trim(),
+        ),
+    ];
+    if !identity.kernel_version.is_empty() {
+        attrs.push(KeyValue::new(
+            "os.version",
+            identity.kernel_version.as_str(),
+        ));
+    }
+    if let Some(id) = identity.machine_id.as_deref().filter(|id| !id.is_empty()) {
+        attrs.push(KeyValue::new("host.id", id));
+    }
+
+    if let Some(hv) = crate::virtualization::detect::detect_hypervisor() {
+        if let Some(provider) = hv.cloud_provider.as_deref() {
+            attrs.push(KeyValue::new("cloud.provider", cloud_provider(provider)));
+        }
+        if let Some(instance_type) = hv.instance_type {
+            attrs.push(KeyValue::new("host.type", instance_type));
+        }
+    }
+    let pod = crate::virtualization::containers::detect_orchestrator().and_then(|o| o.pod_info);
+    if let Some(pod) = pod {
+        for (key, value) in [
+            ("k8s.pod.name", pod.pod_name),
+            ("k8s.namespace.name", pod.pod_namespace),
+            ("k8s.pod.uid", pod.pod_uid),
+            ("k8s.node.name", pod.node_name),
+        ] {
+            if let Some(value) = value {
+                attrs.push(KeyValue::new(key, value));
+            }
+        }
+    }
+    if let Some(container) = crate::virtualization::containers::detect_container() {
+        if let Some(id) = container.container_id {
+            attrs.push(KeyValue::new("container.id", id));
+        }
+        attrs.push(KeyValue::new(
+            "container.runtime",
+            format!("{:?}", container.engine).to_lowercase(),
+        ));
+    }
+
+    for (key, value) in &config.resource_attributes {
+        attrs.retain(|kv| &kv.key != key);
+        attrs.push(KeyValue::new(key.as_str(), value.as_str()));
+    }
+    attrs
+}
+
+fn host_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" | "i686" => "x86",
+        "powerpc64" => "ppc64",
+        other => other,
+    }
+}
+
+fn os_type() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "darwin",
+        other => other,
+    }
+}
+
+/// Map the hypervisor module's provider names onto `cloud.provider` values
+fn cloud_provider(name: &str) -> String {
+    match name {
+        "AWS" => "aws".into(),
+        "Google Cloud" => "gcp".into(),
+        "Azure" => "azure".into(),
+        other => other.to_lowercase().replace(' ', "_"),
+    }
+}
+
+/// Split a [`MetricCollector`] key (`name` or `name:{k=v,...}`) into name and attributes
+fn parse_series_key(key: &str) -> (&str, Vec<KeyValue>) {
+    let Some((name, labels)) = key.split_once(":{") else {
+        return (key, Vec::new());
+    };
+    let labels = labels.strip_suffix('}').unwrap_or(labels);
+    let attributes = labels
+        .split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| KeyValue::new(k, v))
+        .collect();
+    (name, attributes)
+}
+
+/// UCUM unit implied by a Prometheus-style name suffix
+fn infer_unit(name: &str) -> &'static str {
+    let base = name.strip_suffix("_total").unwrap_or(name);
+    [
+        ("_percent", "%"),
+        ("_bytes", "By"),
+        ("_celsius", "Cel"),
+        ("_watts", "W"),
+        ("_joules", "J"),
+        ("_seconds", "s"),
+        ("_mhz", "MHz"),
+        ("_hz", "Hz"),
+        ("_volts", "V"),
+    ]
+    .iter()
+    .find(|(suffix, _)| base.ends_with(suffix))
+    .map_or("", |(_, unit)| unit)
+}
+
+fn now_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// Convert a [`SystemEvent`] into an OTLP log record
+pub fn event_to_log_record(event: &SystemEvent) -> LogRecord {
+    let (severity_number, severity_text) = match event.severity {
+        EventSeverity::Info => (proto::severity::INFO, "INFO"),
+        EventSeverity::Warning => (proto::severity::WARN, "WARN"),
+        EventSeverity::Error => (proto::severity::ERROR, "ERROR"),
+        EventSeverity::Critical => (proto::severity::FATAL, "FATAL"),
+    };
+    let mut attributes = vec![
+        KeyValue::new("event.id", event.id.as_str()),
+        KeyValue::new("event.category", event.category.to_string()),
+        KeyValue::new("event.type", event.event_type.as_str()),
+        KeyValue::new("event.source", event.source.as_str()),
+    ];
+    let mut metadata: Vec<_> = event.metadata.iter().collect();
+    metadata.sort_by(|a, b| a.0.cmp(b.0));
+    for (key, value) in metadata {
+        attributes.push(KeyValue::new(
+            format!("event.metadata.{}", key),
+            json_value(value),
+        ));
+    }
+    if let Some(value) = &event.previous_value {
+        attributes.push(KeyValue::new("event.previous_value", json_value(value)));
+    }
+    if let Some(value) = &event.current_value {
+        attributes.push(KeyValue::new("event.current_value", json_value(value)));
+    }
+    LogRecord {
+        time_unix_nano: event.timestamp.saturating_mul(1_000_000_000),
+        observed_time_unix_nano: now_nanos(),
+        severity_number,
+        severity_text: severity_text.into(),
+        body: AnyValue::from(event.message.as_str()),
+        attributes,
+    }
+}
+
+fn json_value(value: &serde_json::Value) -> AnyValue {
+    match value {
+        serde_json::Value::String(s) => AnyValue::String(s.clone()),
+        serde_json::Value::Bool(b) => AnyValue::Bool(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => AnyValue::Int(i),
+            None => AnyValue::Double(n.as_f64().unwrap_or(0.0)),
+        },
+        other => AnyValue::String(other.to_string()),
+    }
+}
+
+/// Split metrics into requests of at most `max` data points each
+fn batch_metrics(metrics: Vec<Metric>, max: usize) -> Vec<Vec<Metric>> {
+    let max = max.max(1);
+    let mut batches = Vec::new();
+    let mut current: Vec<Metric> = Vec::new();
+    let mut size = 0;
+    for metric in metrics {
+        let (points, monotonic) = match metric.data {
+            MetricData::Gauge(points) => (points, None),
+            MetricData::Sum { points, monotonic } => (points, Some(monotonic)),
+        };
+        let mut points = points.into_iter().peekable();
+        while points.peek().is_some() {
+            if size == max {
+                batches.push(std::mem::take(&mut current));
+                size = 0;
+            }
+            let chunk: Vec<_> = points.by_ref().take(max - size).collect();
+            size += chunk.len();
+            current.push(Metric {
+                name: metric.name.clone(),
+                description: metric.description.clone(),
+                unit: metric.unit.clone(),
+                data: match monotonic {
+                    None => MetricData::Gauge(chunk),
+                    Some(monotonic) => MetricData::Sum {
+                        points: chunk,
+                        monotonic,
+                    },
+                },
+            });
+        }
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+/// Events captured from the [`EventManager`] subscription, awaiting export
+#[derive(Default)]
+struct EventQueue {
+    events: VecDeque<SystemEvent>,
+    dropped: u64,
+}
+
+/// Reads the metric collector and event queue and ships them to a collector
+pub struct OtlpExporter {
+    config: OtlpConfig,
+    transport: Transport,
+    resource: Vec<KeyValue>,
+    metrics: Arc<MetricCollector>,
+    events: Arc<EventManager>,
+    subscription: Option<SubscriptionId>,
+    queue: Arc<Mutex<EventQueue>>,
+    /// Last exported timestamp (unix seconds) per series key
+    cursor: HashMap<String, u64>,
+    /// Start of the cumulative window for sums
+    start_time_unix_nano: u64,
+    stats: Arc<Mutex<OtlpStats>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl OtlpExporter {
+    /// Validate the endpoint, detect resource attributes and subscribe to `events`
+    pub fn new(
+        config: OtlpConfig,
+        metrics: Arc<MetricCollector>,
+        events: Arc<EventManager>,
+    ) -> Result<Self> {
+        let transport = Transport::new(&config)?;
+        let resource = resource_attributes(&config);
+        let queue = Arc::new(Mutex::new(EventQueue::default()));
+        let subscription = if config.export_logs {
+            let sink = Arc::clone(&queue);
+            let limit = config.max_queue_size.max(1);
+            Some(events.subscribe(
+                EventFilter::new(),
+                Box::new(move |event| {
+                    if let Ok(mut queue) = sink.lock() {
+                        if queue.events.len() >= limit {
+                            queue.events.pop_front();
+                            queue.dropped += 1;
+                        }
+                        queue.events.push_back(event.clone());
+                    }
+                }),
+            ))
+        } else {
+            None
+        };
+        Ok(Self {
+            config,
+            transport,
+            resource,
+            metrics,
+            events,
+            subscription,
+            queue,
+            cursor: HashMap::new(),
+            start_time_unix_nano: now_nanos(),
+            stats: Arc::new(Mutex::new(OtlpStats::default())),
+            stop: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Resource attributes sent with every request
+    pub fn resource(&self) -> &[KeyValue] {
+        &self.resource
+    }
+
+    /// Delivery counters so far
+    pub fn stats(&self) -> OtlpStats {
+        self.stats.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Export every pending data point and event now
+    ///
+    /// Returns the last delivery error if any batch was dropped.
+    pub fn flush(&mut self) -> Result<()> {
+        self.export(u64::MAX)
+    }
+
+    /// Export data points recorded up to and including `until` (unix seconds)
+    /// and all queued events
+    fn export(&mut self, until: u64) -> Result<()> {
+        let mut error = None;
+        if self.config.export_metrics {
+            let metrics = self.collect_metrics(until);
+            for batch in batch_metrics(metrics, self.config.max_batch_size) {
+                let points: usize = batch.iter().map(|m| m.data.points().len()).sum();
+                let request = Request::Metrics(proto::metrics_request(&self.resource, &batch));
+                let delivered = self.send(&request);
+                self.update_stats(|s| match &delivered {
+                    Ok(()) => s.data_points_sent += points as u64,
+                    Err(_) => s.data_points_dropped += points as u64,
+                });
+                error = delivered.err().or(error);
+            }
+        }
+        if self.config.export_logs {
+            let (events, dropped) = match self.queue.lock() {
+                Ok(mut queue) => (
+                    queue.events.drain(..).collect::<Vec<_>>(),
+                    std::mem::take(&mut queue.dropped),
+                ),
+                Err(_) => (Vec::new(), 0),
+            };
+            self.update_stats(|s| s.log_records_dropped += dropped);
+            let records: Vec<LogRecord> = events.iter().map(event_to_log_record).collect();
+            for batch in records.chunks(self.config.max_batch_size.max(1)) {
+                let request = Request::Logs(proto::logs_request(&self.resource, batch));
+                let delivered = self.send(&request);
+                self.update_stats(|s| match &delivered {
+                    Ok(()) => s.log_records_sent += batch.len() as u64,
+                    Err(_) => s.log_records_dropped += batch.len() as u64,
+                });
+                error = delivered.err().or(error);
+            }
+        }
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// New data points since the previous export, grouped into metrics by name
+    fn collect_metrics(&mut self, until: u64) -> Vec<Metric> {
+        let mut keys = self.metrics.list_metrics();
+        keys.sort();
+        let mut metrics: BTreeMap<String, Metric> = BTreeMap::new();
+        for key in keys {
+            let from = self.cursor.get(&key).map_or(0, |t| t + 1);
+            if from > until {
+                continue;
+            }
+            let points = self.metrics.get_time_series_range(&key, from, until);
+            let Some(last) = points.last() else {
+                continue;
+            };
+            self.cursor.insert(key.clone(), last.timestamp);
+
+            let (name, attributes) = parse_series_key(&key);
+            let is_sum = name.ends_with("_total");
+            let metric = metrics.entry(name.to_string()).or_insert_with(|| Metric {
+                name: name.to_string(),
+                description: String::new(),
+                unit: infer_unit(name).to_string(),
+                data: if is_sum {
+                    MetricData::Sum {
+                        points: Vec::new(),
+                        monotonic: true,
+                    }
+                } else {
+                    MetricData::Gauge(Vec::new())
+                },
+            });
+            let target = match &mut metric.data {
+                MetricData::Gauge(points) | MetricData::Sum { points, .. } => points,
+            };
+            target.extend(points.iter().map(|p| NumberDataPoint {
+                attributes: attributes.clone(),
+                start_time_unix_nano: if is_sum { self.start_time_unix_nano } else { 0 },
+                time_unix_nano: p.timestamp.saturating_mul(1_000_000_000),
+                value: p.value,
+            }));
+        }
+        metrics.into_values().collect()
+    }
+
+    /// Send one request, retrying transient failures with exponential backoff
+    fn send(&self, request: &Request) -> Result<()> {
+        let mut backoff = Duration::from_millis(self.config.initial_backoff_ms);
+        let max_backoff = Duration::from_millis(self.config.max_backoff_ms.max(1));
+        let mut attempt = 0;
+        loop {
+            let error = match self.transport.send(request) {
+                Ok(partial) => {
+                    self.update_stats(|s| {
+                        s.exports_ok += 1;
+                        s.last_export = Some(now_nanos() / 1_000_000_000);
+                        if let Some(partial) = partial {
+                            s.rejected += partial.rejected.max(0) as u64;
+                            s.last_error = Some(partial.error_message);
+                        }
+                    });
+                    return Ok(());
+                }
+                Err(e) => e,
+            };
+            self.update_stats(|s| s.last_error = Some(error.message.clone()));
+            // Once stopping, make a single attempt rather than holding up shutdown
+            if !error.retryable
+                || attempt >= self.config.max_retries
+                || self.stop.load(Ordering::SeqCst)
+            {
+                self.update_stats(|s| s.exports_failed += 1);
+                return Err(SimonError::Network(format!(
+                    "OTLP export failed: {}",
+                    error.message
+                )));
+            }
+            attempt += 1;
+            self.update_stats(|s| s.retries += 1);
+
+            // Up to 20% jitter so a fleet does not retry in lockstep
+            let jitter = backoff.mul_f64((now_nanos() % 1000) as f64 / 5000.0);
+            let delay = error
+                .retry_after
+                .unwrap_or(backoff + jitter)
+                .min(max_backoff);
+            let deadline = Instant::now() + delay;
+            while Instant::now() < deadline && !self.stop.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(20).min(delay));
+            }
+            backoff = (backoff * 2).min(max_backoff);
+        }
+    }
+
+    fn update_stats(&self, f: impl FnOnce(&mut OtlpStats)) {
+        if let Ok(mut stats) = self.stats.lock() {
+            f(&mut stats);
+        }
+    }
+}
+
+impl Drop for OtlpExporter {
+    fn drop(&mut self) {
+        if let Some(id) = self.subscription.take() {
+            self.events.unsubscribe(id);
+        }
+    }
+}
+
+/// Start exporting on a background thread every `interval_ms`, with a final
+/// flush when the handle is stopped
+pub fn spawn(
+    config: OtlpConfig,
+    metrics: Arc<MetricCollector>,
+    events: Arc<EventManager>,
+) -> Result<OtlpHandle> {
+    let interval = Duration::from_millis(config.interval_ms.max(100));
+    let mut exporter = OtlpExporter::new(config, metrics, events)?;
+    let stats = Arc::clone(&exporter.stats);
+    let stop = Arc::clone(&exporter.stop);
+
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-otlp".into())
+        .spawn(move || {
+            while !flag.load(Ordering::SeqCst) {
+                let deadline = Instant::now() + interval;
+                while !flag.load(Ordering::SeqCst) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                if flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                // Only whole seconds, so points recorded later this second are not skipped
+                let until = (now_nanos() / 1_000_000_000).saturating_sub(1);
+                let _ = exporter.export(until);
+            }
+            let _ = exporter.flush();
+        })
+        .map_err(|e| SimonError::Other(format!("Failed to spawn OTLP exporter thread: {}", e)))?;
+
+    Ok(OtlpHandle {
+        stats,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+/// Handle to a running OTLP exporter thread
+pub struct OtlpHandle {
+    stats: Arc<Mutex<OtlpStats>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OtlpHandle {
+    /// Delivery counters so far
+    pub fn stats(&self) -> OtlpStats {
+        self.stats.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Flush pending data, stop exporting and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for OtlpHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::EventCategory;
+
+    #[cfg(all(feature = "remote-backends", feature = "otlp-grpc"))]
+    fn exporter_for(
+        receiver: &OtlpReceiver,
+        protocol: OtlpProtocol,
+    ) -> (OtlpExporter, Arc<MetricCollector>, Arc<EventManager>) {
+        let config = OtlpConfig {
+            endpoint: Some(receiver.endpoint()),
+            protocol,
+            initial_backoff_ms: 10,
+            max_backoff_ms: 50,
+            max_batch_size: 3,
+            resource_attributes: [("deployment.environment".to_string(), "test".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let metrics = Arc::new(MetricCollector::new());
+        let events = Arc::new(EventManager::new(100));
+        let exporter =
+            OtlpExporter::new(config, Arc::clone(&metrics), Arc::clone(&events)).unwrap();
+        (exporter, metrics, events)
+    }
+
+    #[test]
+    fn test_series_key_and_units() {
+        let (name, attrs) = parse_series_key("network_rx_bytes_total:{interface=eth0,host=a}");
+        assert_eq!(name, "network_rx_bytes_total");
+        assert_eq!(
+            attrs,
+            vec![
+                KeyValue::new("interface", "eth0"),
+                KeyValue::new("host", "a")
+            ]
+        );
+        assert_eq!(parse_series_key("cpu_usage_percent").0, "cpu_usage_percent");
+        assert_eq!(infer_unit("network_rx_bytes_total"), "By");
+        assert_eq!(infer_unit("gpu_0_temperature_celsius"), "Cel");
+        assert_eq!(infer_unit("load_average"), "");
+    }
+
+    #[test]
+    fn test_batching_splits_points() {
+        let point = |v| NumberDataPoint {
+            attributes: Vec::new(),
+            start_time_unix_nano: 0,
+            time_unix_nano: 1,
+            value: v,
+        };
+        let metrics = vec![
+            Metric {
+                name: "a".into(),
+                description: String::new(),
+                unit: String::new(),
+                data: MetricData::Gauge(vec![point(1.0), point(2.0)]),
+            },
+            Metric {
+                name: "b_total".into(),
+                description: String::new(),
+                unit: String::new(),
+                data: MetricData::Sum {
+                    points: vec![point(3.0), point(4.0), point(5.0)],
+                    monotonic: true,
+                },
+            },
+        ];
+        let batches = batch_metrics(metrics, 2);
+        let sizes: Vec<Vec<usize>> = batches
+            .iter()
+            .map(|b| b.iter().map(|m| m.data.points().len()).collect())
+            .collect();
+        assert_eq!(sizes, vec![vec![2], vec![2], vec![1]]);
+        assert!(matches!(
+            batches[2][0].data,
+            MetricData::Sum {
+                monotonic: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_event_to_log_record() {
+        let event = SystemEvent::warning(EventCategory::Gpu, "high_temp", "GPU 0 at 91C", "gpu:0")
+            .with_metadata("threshold", 85)
+            .with_change(serde_json::json!(80.5), serde_json::json!(91.0));
+        let record = event_to_log_record(&event);
+        assert_eq!(record.severity_number, proto::severity::WARN);
+        assert_eq!(record.body, AnyValue::from("GPU 0 at 91C"));
+        assert_eq!(record.time_unix_nano, event.timestamp * 1_000_000_000);
+        assert!(record
+            .attributes
+            .contains(&KeyValue::new("event.category", "gpu")));
+        assert!(record
+            .attributes
+            .contains(&KeyValue::new("event.metadata.threshold", 85i64)));
+        assert!(record
+            .attributes
+            .contains(&KeyValue::new("event.current_value", 91.0)));
+    }
+
+    #[test]
+    #[cfg(all(feature = "remote-backends", feature = "otlp-grpc"))]
+    fn test_export_to_receiver_over_http_and_grpc() {
+        for protocol in [OtlpProtocol::HttpProtobuf, OtlpProtocol::Grpc] {
+            let receiver = OtlpReceiver::start().unwrap();
+            let (mut exporter, metrics, events) = exporter_for(&receiver, protocol);
+            metrics.record("cpu_usage_percent", 12.5);
+            metrics.record_with_labels("network_rx_bytes_total", 1e6, &[("interface", "eth0")]);
+            metrics.record_with_labels("network_rx_bytes_total", 2e6, &[("interface", "eth1")]);
+            metrics.record("memory_used_bytes", 4096.0);
+            events.emit(SystemEvent::critical(
+                EventCategory::Memory,
+                "oom",
+                "OOM kill",
+                "kernel",
+            ));
+
+            exporter.flush().unwrap();
+            let exports = receiver.exports();
+            // 4 points in batches of 3, plus one logs request
+            assert_eq!(exports.len(), 3, "{:?}", protocol);
+            assert!(exports
+                .iter()
+                .all(|e| e.protocol == protocol && e.compressed));
+            let resource = &exports[0].resource;
+            assert!(resource.contains(&KeyValue::new("service.name", "simon")));
+            assert!(resource.contains(&KeyValue::new("deployment.environment", "test")));
+            assert!(resource.iter().any(|kv| kv.key == "host.name"));
+
+            let received = receiver.metrics();
+            let rx: Vec<_> = received
+                .iter()
+                .filter(|m| m.name == "network_rx_bytes_total")
+                .flat_map(|m| m.data.points().to_vec())
+                .collect();
+            assert_eq!(rx.len(), 2);
+            assert!(received
+                .iter()
+                .filter(|m| m.name == "network_rx_bytes_total")
+                .all(|m| m.unit == "By"
+                    && matches!(
+                        m.data,
+                        MetricData::Sum {
+                            monotonic: true,
+                            ..
+                        }
+                    )));
+            let cpu = received
+                .iter()
+                .find(|m| m.name == "cpu_usage_percent")
+                .unwrap();
+            assert_eq!(cpu.unit, "%");
+            assert_eq!(
+                cpu.data,
+                MetricData::Gauge(vec![NumberDataPoint {
+                    attributes: Vec::new(),
+                    start_time_unix_nano: 0,
+                    time_unix_nano: cpu.data.points()[0].time_unix_nano,
+                    value: 12.5,
+                }])
+            );
+
+            let logs = receiver.logs();
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].severity_number, proto::severity::FATAL);
+
+            // Nothing new: no further requests
+            exporter.flush().unwrap();
+            assert_eq!(receiver.exports().len(), 3);
+            let stats = exporter.stats();
+            assert_eq!((stats.data_points_sent, stats.log_records_sent), (4, 1));
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "remote-backends", feature = "otlp-grpc"))]
+    fn test_retry_then_give_up() {
+        for protocol in [OtlpProtocol::HttpProtobuf, OtlpProtocol::Grpc] {
+            let receiver = OtlpReceiver::start().unwrap();
+            let (mut exporter, metrics, _events) = exporter_for(&receiver, protocol);
+            receiver.fail_next(2);
+            metrics.record("cpu_usage_percent", 50.0);
+            exporter.flush().unwrap();
+            assert_eq!(receiver.metrics().len(), 1);
+            assert_eq!(exporter.stats().retries, 2);
+
+            receiver.fail_next(10);
+            metrics.record("memory_used_bytes", 1.0);
+            metrics.record("memory_used_bytes", 2.0);
+            assert!(exporter.flush().is_err());
+            let stats = exporter.stats();
+            assert_eq!(stats.exports_failed, 1);
+            assert_eq!(stats.data_points_dropped, 2);
+            assert!(stats.last_error.is_some());
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "remote-backends", feature = "otlp-grpc"))]
+    fn test_spawn_flushes_on_stop() {
+        let receiver = OtlpReceiver::start().unwrap();
+        let metrics = Arc::new(MetricCollector::new());
+        let events = Arc::new(EventManager::new(100));
+        let config = OtlpConfig {
+            endpoint: Some(receiver.endpoint()),
+            protocol: OtlpProtocol::Grpc,
+            interval_ms: 60_000,
+            ..Default::default()
+        };
+        let mut handle = spawn(config, Arc::clone(&metrics), Arc::clone(&events)).unwrap();
+        metrics.record("fan_speed_rpm", 1200.0);
+        events.emit(SystemEvent::info(
+            EventCategory::Fan,
+            "started",
+            "Fan spun up",
+            "fan:0",
+        ));
+        handle.stop();
+        assert_eq!(receiver.metrics().len(), 1);
+        assert_eq!(receiver.logs().len(), 1);
+        assert_eq!(handle.stats().exports_ok, 2);
+
+        let unsupported = OtlpConfig {
+            endpoint: Some("ftp://collector:4318".into()),
+            ..Default::default()
+        };
+        assert!(spawn(unsupported, metrics, events).is_err());
+    }
+}
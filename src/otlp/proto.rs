@@ -0,0 +1,697 @@
+//! OTLP protobuf messages
+//!
+//! Simon's view of the OTLP data model, plus [`prost`] definitions for the
+//! subset of `opentelemetry.proto.collector.{metrics,logs}.v1` it produces:
+//! gauges, cumulative sums and log records with scalar attributes. Field
+//! numbers follow opentelemetry-proto v1.x.
+
+use crate::error::{Result, SimonError};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+/// Instrumentation scope name reported with every batch
+pub const SCOPE_NAME: &str = "simon";
+
+/// `SeverityNumber` values used for log records
+pub mod severity {
+    pub const INFO: i32 = 9;
+    pub const WARN: i32 = 13;
+    pub const ERROR: i32 = 17;
+    pub const FATAL: i32 = 21;
+}
+
+/// Attribute value (the scalar subset of OTLP `AnyValue`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnyValue {
+    String(String),
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+}
+
+impl std::fmt::Display for AnyValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::String(s) => write!(f, "{}", s),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Int(i) => write!(f, "{}", i),
+            Self::Double(d) => write!(f, "{}", d),
+        }
+    }
+}
+
+impl From<&str> for AnyValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for AnyValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<bool> for AnyValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for AnyValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for AnyValue {
+    fn from(value: f64) -> Self {
+        Self::Double(value)
+    }
+}
+
+/// Attribute key/value pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: AnyValue,
+}
+
+impl KeyValue {
+    pub fn new(key: impl Into<String>, value: impl Into<AnyValue>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// A single numeric sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumberDataPoint {
+    pub attributes: Vec<KeyValue>,
+    /// Start of the cumulative window (sums only; 0 for gauges)
+    pub start_time_unix_nano: u64,
+    pub time_unix_nano: u64,
+    pub value: f64,
+}
+
+/// Metric payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MetricData {
+    Gauge(Vec<NumberDataPoint>),
+    /// Cumulative sum
+    Sum {
+        points: Vec<NumberDataPoint>,
+        monotonic: bool,
+    },
+}
+
+impl MetricData {
+    /// Data points regardless of kind
+    pub fn points(&self) -> &[NumberDataPoint] {
+        match self {
+            Self::Gauge(points) | Self::Sum { points, .. } => points,
+        }
+    }
+}
+
+/// An OTLP metric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metric {
+    pub name: String,
+    pub description: String,
+    /// UCUM unit (e.g. `%`, `By`, `Cel`)
+    pub unit: String,
+    pub data: MetricData,
+}
+
+/// An OTLP log record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub time_unix_nano: u64,
+    pub observed_time_unix_nano: u64,
+    /// See [`severity`]
+    pub severity_number: i32,
+    pub severity_text: String,
+    pub body: AnyValue,
+    pub attributes: Vec<KeyValue>,
+}
+
+/// `partial_success` from an export response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialSuccess {
+    /// Rejected data points or log records
+    pub rejected: i64,
+    pub error_message: String,
+}
+
+/// `prost` messages mirroring opentelemetry-proto v1.x
+///
+/// Fields simon neither sends nor reads (exemplars, flags, trace context,
+/// histograms) are left out; prost skips them when decoding.
+pub(crate) mod wire {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExportMetricsServiceRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub resource_metrics: Vec<ResourceMetrics>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExportLogsServiceRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub resource_logs: Vec<ResourceLogs>,
+    }
+
+    /// `ExportMetricsServiceResponse` and `ExportLogsServiceResponse`, which
+    /// share a wire layout
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExportResponse {
+        #[prost(message, optional, tag = "1")]
+        pub partial_success: Option<ExportPartialSuccess>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ExportPartialSuccess {
+        /// `rejected_data_points` or `rejected_log_records`
+        #[prost(int64, tag = "1")]
+        pub rejected: i64,
+        #[prost(string, tag = "2")]
+        pub error_message: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ResourceMetrics {
+        #[prost(message, optional, tag = "1")]
+        pub resource: Option<Resource>,
+        #[prost(message, repeated, tag = "2")]
+        pub scope_metrics: Vec<ScopeMetrics>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ScopeMetrics {
+        #[prost(message, optional, tag = "1")]
+        pub scope: Option<InstrumentationScope>,
+        #[prost(message, repeated, tag = "2")]
+        pub metrics: Vec<Metric>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Metric {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub description: String,
+        #[prost(string, tag = "3")]
+        pub unit: String,
+        #[prost(oneof = "metric::Data", tags = "5, 7")]
+        pub data: Option<metric::Data>,
+    }
+
+    pub mod metric {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Data {
+            #[prost(message, tag = "5")]
+            Gauge(super::Gauge),
+            #[prost(message, tag = "7")]
+            Sum(super::Sum),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Gauge {
+        #[prost(message, repeated, tag = "1")]
+        pub data_points: Vec<NumberDataPoint>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sum {
+        #[prost(message, repeated, tag = "1")]
+        pub data_points: Vec<NumberDataPoint>,
+        #[prost(int32, tag = "2")]
+        pub aggregation_temporality: i32,
+        #[prost(bool, tag = "3")]
+        pub is_monotonic: bool,
+    }
+
+    /// `AGGREGATION_TEMPORALITY_CUMULATIVE`
+    pub const CUMULATIVE: i32 = 2;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct NumberDataPoint {
+        #[prost(message, repeated, tag = "7")]
+        pub attributes: Vec<KeyValue>,
+        #[prost(fixed64, tag = "2")]
+        pub start_time_unix_nano: u64,
+        #[prost(fixed64, tag = "3")]
+        pub time_unix_nano: u64,
+        #[prost(oneof = "number_data_point::Value", tags = "4, 6")]
+        pub value: Option<number_data_point::Value>,
+    }
+
+    pub mod number_data_point {
+        #[derive(Clone, Copy, PartialEq, prost::Oneof)]
+        pub enum Value {
+            #[prost(double, tag = "4")]
+            AsDouble(f64),
+            #[prost(sfixed64, tag = "6")]
+            AsInt(i64),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ResourceLogs {
+        #[prost(message, optional, tag = "1")]
+        pub resource: Option<Resource>,
+        #[prost(message, repeated, tag = "2")]
+        pub scope_logs: Vec<ScopeLogs>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ScopeLogs {
+        #[prost(message, optional, tag = "1")]
+        pub scope: Option<InstrumentationScope>,
+        #[prost(message, repeated, tag = "2")]
+        pub log_records: Vec<LogRecord>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct LogRecord {
+        #[prost(fixed64, tag = "1")]
+        pub time_unix_nano: u64,
+        #[prost(fixed64, tag = "11")]
+        pub observed_time_unix_nano: u64,
+        #[prost(int32, tag = "2")]
+        pub severity_number: i32,
+        #[prost(string, tag = "3")]
+        pub severity_text: String,
+        #[prost(message, optional, tag = "5")]
+        pub body: Option<AnyValue>,
+        #[prost(message, repeated, tag = "6")]
+        pub attributes: Vec<KeyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Resource {
+        #[prost(message, repeated, tag = "1")]
+        pub attributes: Vec<KeyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct InstrumentationScope {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub version: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct KeyValue {
+        #[prost(string, tag = "1")]
+        pub key: String,
+        #[prost(message, optional, tag = "2")]
+        pub value: Option<AnyValue>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct AnyValue {
+        #[prost(oneof = "any_value::Value", tags = "1, 2, 3, 4")]
+        pub value: Option<any_value::Value>,
+    }
+
+    pub mod any_value {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Value {
+            #[prost(string, tag = "1")]
+            String(String),
+            #[prost(bool, tag = "2")]
+            Bool(bool),
+            #[prost(int64, tag = "3")]
+            Int(i64),
+            #[prost(double, tag = "4")]
+            Double(f64),
+        }
+    }
+}
+
+/// Build an `ExportMetricsServiceRequest`
+pub(crate) fn metrics_request(
+    resource: &[KeyValue],
+    metrics: &[Metric],
+) -> wire::ExportMetricsServiceRequest {
+    wire::ExportMetricsServiceRequest {
+        resource_metrics: vec![wire::ResourceMetrics {
+            resource: Some(to_resource(resource)),
+            scope_metrics: vec![wire::ScopeMetrics {
+                scope: Some(scope()),
+                metrics: metrics.iter().map(to_metric).collect(),
+            }],
+        }],
+    }
+}
+
+/// Build an `ExportLogsServiceRequest`
+pub(crate) fn logs_request(
+    resource: &[KeyValue],
+    logs: &[LogRecord],
+) -> wire::ExportLogsServiceRequest {
+    wire::ExportLogsServiceRequest {
+        resource_logs: vec![wire::ResourceLogs {
+            resource: Some(to_resource(resource)),
+            scope_logs: vec![wire::ScopeLogs {
+                scope: Some(scope()),
+                log_records: logs.iter().map(to_log).collect(),
+            }],
+        }],
+    }
+}
+
+/// Resource attributes and metrics of an `ExportMetricsServiceRequest`
+pub(crate) fn from_metrics_request(
+    request: wire::ExportMetricsServiceRequest,
+) -> (Vec<KeyValue>, Vec<Metric>) {
+    let mut resource = Vec::new();
+    let mut metrics = Vec::new();
+    for rm in request.resource_metrics {
+        resource.extend(from_resource(rm.resource));
+        for sm in rm.scope_metrics {
+            metrics.extend(sm.metrics.into_iter().map(from_metric));
+        }
+    }
+    (resource, metrics)
+}
+
+/// Resource attributes and log records of an `ExportLogsServiceRequest`
+pub(crate) fn from_logs_request(
+    request: wire::ExportLogsServiceRequest,
+) -> (Vec<KeyValue>, Vec<LogRecord>) {
+    let mut resource = Vec::new();
+    let mut logs = Vec::new();
+    for rl in request.resource_logs {
+        resource.extend(from_resource(rl.resource));
+        for sl in rl.scope_logs {
+            logs.extend(sl.log_records.into_iter().map(from_log));
+        }
+    }
+    (resource, logs)
+}
+
+/// `partial_success` of an export response, if present and non-empty
+pub(crate) fn from_response(response: wire::ExportResponse) -> Option<PartialSuccess> {
+    response
+        .partial_success
+        .filter(|p| p.rejected != 0 || !p.error_message.is_empty())
+        .map(|p| PartialSuccess {
+            rejected: p.rejected,
+            error_message: p.error_message,
+        })
+}
+
+/// Encode an `ExportMetricsServiceRequest`
+pub fn encode_metrics_request(resource: &[KeyValue], metrics: &[Metric]) -> Vec<u8> {
+    metrics_request(resource, metrics).encode_to_vec()
+}
+
+/// Encode an `ExportLogsServiceRequest`
+pub fn encode_logs_request(resource: &[KeyValue], logs: &[LogRecord]) -> Vec<u8> {
+    logs_request(resource, logs).encode_to_vec()
+}
+
+/// Encode an export response carrying `partial_success`
+pub fn encode_partial_success(partial: &PartialSuccess) -> Vec<u8> {
+    wire::ExportResponse {
+        partial_success: Some(wire::ExportPartialSuccess {
+            rejected: partial.rejected,
+            error_message: partial.error_message.clone(),
+        }),
+    }
+    .encode_to_vec()
+}
+
+/// Decode an `ExportMetricsServiceRequest` into resource attributes and metrics
+pub fn decode_metrics_request(buf: &[u8]) -> Result<(Vec<KeyValue>, Vec<Metric>)> {
+    wire::ExportMetricsServiceRequest::decode(buf)
+        .map(from_metrics_request)
+        .map_err(wire_error)
+}
+
+/// Decode an `ExportLogsServiceRequest` into resource attributes and log records
+pub fn decode_logs_request(buf: &[u8]) -> Result<(Vec<KeyValue>, Vec<LogRecord>)> {
+    wire::ExportLogsServiceRequest::decode(buf)
+        .map(from_logs_request)
+        .map_err(wire_error)
+}
+
+/// Decode `partial_success` from an export response, if present and non-empty
+pub fn decode_partial_success(buf: &[u8]) -> Result<Option<PartialSuccess>> {
+    wire::ExportResponse::decode(buf)
+        .map(from_response)
+        .map_err(wire_error)
+}
+
+fn wire_error(e: prost::DecodeError) -> SimonError {
+    SimonError::Parse(format!("OTLP protobuf: {}", e))
+}
+
+fn scope() -> wire::InstrumentationScope {
+    wire::InstrumentationScope {
+        name: SCOPE_NAME.into(),
+        version: crate::VERSION.into(),
+    }
+}
+
+fn to_resource(attributes: &[KeyValue]) -> wire::Resource {
+    wire::Resource {
+        attributes: to_attributes(attributes),
+    }
+}
+
+fn to_attributes(attributes: &[KeyValue]) -> Vec<wire::KeyValue> {
+    attributes
+        .iter()
+        .map(|kv| wire::KeyValue {
+            key: kv.key.clone(),
+            value: Some(to_any_value(&kv.value)),
+        })
+        .collect()
+}
+
+fn to_any_value(value: &AnyValue) -> wire::AnyValue {
+    use wire::any_value::Value;
+    wire::AnyValue {
+        value: Some(match value {
+            AnyValue::String(s) => Value::String(s.clone()),
+            AnyValue::Bool(b) => Value::Bool(*b),
+            AnyValue::Int(i) => Value::Int(*i),
+            AnyValue::Double(d) => Value::Double(*d),
+        }),
+    }
+}
+
+fn to_metric(metric: &Metric) -> wire::Metric {
+    let points = |points: &[NumberDataPoint]| {
+        points
+            .iter()
+            .map(|point| wire::NumberDataPoint {
+                attributes: to_attributes(&point.attributes),
+                start_time_unix_nano: point.start_time_unix_nano,
+                time_unix_nano: point.time_unix_nano,
+                value: Some(wire::number_data_point::Value::AsDouble(point.value)),
+            })
+            .collect()
+    };
+    wire::Metric {
+        name: metric.name.clone(),
+        description: metric.description.clone(),
+        unit: metric.unit.clone(),
+        data: Some(match &metric.data {
+            MetricData::Gauge(p) => wire::metric::Data::Gauge(wire::Gauge {
+                data_points: points(p),
+            }),
+            MetricData::Sum {
+                points: p,
+                monotonic,
+            } => wire::metric::Data::Sum(wire::Sum {
+                data_points: points(p),
+                aggregation_temporality: wire::CUMULATIVE,
+                is_monotonic: *monotonic,
+            }),
+        }),
+    }
+}
+
+fn to_log(log: &LogRecord) -> wire::LogRecord {
+    wire::LogRecord {
+        time_unix_nano: log.time_unix_nano,
+        observed_time_unix_nano: log.observed_time_unix_nano,
+        severity_number: log.severity_number,
+        severity_text: log.severity_text.clone(),
+        body: Some(to_any_value(&log.body)),
+        attributes: to_attributes(&log.attributes),
+    }
+}
+
+fn from_resource(resource: Option<wire::Resource>) -> Vec<KeyValue> {
+    resource
+        .map(|r| from_attributes(r.attributes))
+        .unwrap_or_default()
+}
+
+fn from_attributes(attributes: Vec<wire::KeyValue>) -> Vec<KeyValue> {
+    attributes
+        .into_iter()
+        .map(|kv| KeyValue {
+            key: kv.key,
+            value: from_any_value(kv.value),
+        })
+        .collect()
+}
+
+fn from_any_value(value: Option<wire::AnyValue>) -> AnyValue {
+    use wire::any_value::Value;
+    match value.and_then(|v| v.value) {
+        Some(Value::String(s)) => AnyValue::String(s),
+        Some(Value::Bool(b)) => AnyValue::Bool(b),
+        Some(Value::Int(i)) => AnyValue::Int(i),
+        Some(Value::Double(d)) => AnyValue::Double(d),
+        None => AnyValue::String(String::new()),
+    }
+}
+
+fn from_metric(metric: wire::Metric) -> Metric {
+    let points = |points: Vec<wire::NumberDataPoint>| {
+        points
+            .into_iter()
+            .map(|point| NumberDataPoint {
+                attributes: from_attributes(point.attributes),
+                start_time_unix_nano: point.start_time_unix_nano,
+                time_unix_nano: point.time_unix_nano,
+                value: match point.value {
+                    Some(wire::number_data_point::Value::AsDouble(v)) => v,
+                    Some(wire::number_data_point::Value::AsInt(v)) => v as f64,
+                    None => 0.0,
+                },
+            })
+            .collect()
+    };
+    Metric {
+        name: metric.name,
+        description: metric.description,
+        unit: metric.unit,
+        data: match metric.data {
+            Some(wire::metric::Data::Sum(sum)) => MetricData::Sum {
+                points: points(sum.data_points),
+                monotonic: sum.is_monotonic,
+            },
+            Some(wire::metric::Data::Gauge(gauge)) => MetricData::Gauge(points(gauge.data_points)),
+            None => MetricData::Gauge(Vec::new()),
+        },
+    }
+}
+
+fn from_log(log: wire::LogRecord) -> LogRecord {
+    LogRecord {
+        time_unix_nano: log.time_unix_nano,
+        observed_time_unix_nano: log.observed_time_unix_nano,
+        severity_number: log.severity_number,
+        severity_text: log.severity_text,
+        body: from_any_value(log.body),
+        attributes: from_attributes(log.attributes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(value: f64, attrs: Vec<KeyValue>) -> NumberDataPoint {
+        NumberDataPoint {
+            attributes: attrs,
+            start_time_unix_nano: 0,
+            time_unix_nano: 1_700_000_000_000_000_000,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_key_value_wire_format() {
+        let resource = to_resource(&[KeyValue::new("a", "b")]);
+        // Resource.attributes { key: "a", value { string_value: "b" } }
+        assert_eq!(
+            resource.encode_to_vec(),
+            [0x0a, 0x08, 0x0a, 0x01, b'a', 0x12, 0x03, 0x0a, 0x01, b'b']
+        );
+
+        // oneof members are written even when they hold the default
+        let attribute = to_attributes(&[KeyValue::new("n", 0i64)]);
+        assert_eq!(
+            attribute[0].encode_to_vec(),
+            [0x0a, 0x01, b'n', 0x12, 0x02, 0x18, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_metrics_round_trip() {
+        let resource = vec![
+            KeyValue::new("service.name", "simon"),
+            KeyValue::new("host.cpu.count", 16i64),
+        ];
+        let metrics = vec![
+            Metric {
+                name: "cpu_usage_percent".into(),
+                description: String::new(),
+                unit: "%".into(),
+                data: MetricData::Gauge(vec![point(42.5, vec![]), point(43.0, vec![])]),
+            },
+            Metric {
+                name: "network_rx_bytes_total".into(),
+                description: "Bytes received".into(),
+                unit: "By".into(),
+                data: MetricData::Sum {
+                    points: vec![NumberDataPoint {
+                        start_time_unix_nano: 1,
+                        ..point(1e12, vec![KeyValue::new("interface", "eth0")])
+                    }],
+                    monotonic: true,
+                },
+            },
+        ];
+        let buf = encode_metrics_request(&resource, &metrics);
+        let (decoded_resource, decoded) = decode_metrics_request(&buf).unwrap();
+        assert_eq!(decoded_resource, resource);
+        assert_eq!(decoded, metrics);
+    }
+
+    #[test]
+    fn test_logs_round_trip() {
+        let logs = vec![LogRecord {
+            time_unix_nano: 1_700_000_000_000_000_000,
+            observed_time_unix_nano: 1_700_000_000_500_000_000,
+            severity_number: severity::WARN,
+            severity_text: "warning".into(),
+            body: AnyValue::from("GPU 0 temperature high"),
+            attributes: vec![
+                KeyValue::new("event.category", "gpu"),
+                KeyValue::new("gpu.temperature", 91.5),
+                KeyValue::new("acknowledged", false),
+            ],
+        }];
+        let buf = encode_logs_request(&[KeyValue::new("host.name", "edge-7")], &logs);
+        let (resource, decoded) = decode_logs_request(&buf).unwrap();
+        assert_eq!(resource, vec![KeyValue::new("host.name", "edge-7")]);
+        assert_eq!(decoded, logs);
+    }
+
+    #[test]
+    fn test_partial_success() {
+        assert_eq!(decode_partial_success(&[]).unwrap(), None);
+        let partial = PartialSuccess {
+            rejected: 3,
+            error_message: "bad metric name".into(),
+        };
+        let buf = encode_partial_success(&partial);
+        assert_eq!(decode_partial_success(&buf).unwrap(), Some(partial));
+        assert!(decode_metrics_request(&[0x0a, 0x05, 0x01]).is_err());
+    }
+}
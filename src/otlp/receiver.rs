@@ -0,0 +1,333 @@
+//! Local OTLP receiver
+//!
+//! A small in-process stand-in for an OpenTelemetry collector. It listens on
+//! loopback, accepts both OTLP/HTTP (protobuf) and OTLP/gRPC on the same port,
+//! decodes every export and keeps it for inspection. Failures can be injected
+//! to exercise the exporter's retry path.
+
+use super::proto::{self, wire, KeyValue, LogRecord, Metric};
+use super::OtlpProtocol;
+use crate::error::{Result, SimonError};
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io::Read;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tonic::body::BoxBody;
+use tonic::codec::{CompressionEncoding, ProstCodec};
+use tonic::server::{Grpc, UnaryService};
+
+/// One decoded export request
+#[derive(Debug, Clone)]
+pub struct ReceivedExport {
+    pub protocol: OtlpProtocol,
+    pub path: String,
+    /// Request headers, names lower-cased
+    pub headers: HashMap<String, String>,
+    /// Whether the payload arrived gzip-compressed
+    pub compressed: bool,
+    pub resource: Vec<KeyValue>,
+    pub metrics: Vec<Metric>,
+    pub logs: Vec<LogRecord>,
+}
+
+#[derive(Default)]
+struct State {
+    exports: Vec<ReceivedExport>,
+    /// Requests still to be answered with a retryable failure
+    fail_next: usize,
+}
+
+/// Loopback OTLP receiver for tests and local debugging
+pub struct OtlpReceiver {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OtlpReceiver {
+    /// Listen on an ephemeral loopback port
+    pub fn start() -> Result<Self> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let (shutdown, mut stopped) = oneshot::channel();
+
+        let shared = Arc::clone(&state);
+        let thread = std::thread::Builder::new()
+            .name("simon-otlp-receiver".into())
+            .spawn(move || {
+                runtime.block_on(async move {
+                    let Ok(listener) = tokio::net::TcpListener::from_std(listener) else {
+                        return;
+                    };
+                    loop {
+                        let stream = tokio::select! {
+                            _ = &mut stopped => break,
+                            accepted = listener.accept() => match accepted {
+                                Ok((stream, _)) => stream,
+                                Err(_) => continue,
+                            },
+                        };
+                        let state = Arc::clone(&shared);
+                        let service =
+                            service_fn(move |request| handle(request, Arc::clone(&state)));
+                        // HTTP/1.1 for OTLP/HTTP, h2c (prior knowledge) for gRPC
+                        tokio::spawn(async move {
+                            let _ = auto::Builder::new(TokioExecutor::new())
+                                .serve_connection(TokioIo::new(stream), service)
+                                .await;
+                        });
+                    }
+                });
+            })
+            .map_err(|e| SimonError::Other(format!("Failed to spawn OTLP receiver: {}", e)))?;
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        })
+    }
+
+    /// Endpoint URL to point an exporter at
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answer the next `count` requests with 503 / `UNAVAILABLE`
+    pub fn fail_next(&self, count: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            state.fail_next = count;
+        }
+    }
+
+    /// Exports received so far, in arrival order
+    pub fn exports(&self) -> Vec<ReceivedExport> {
+        self.state
+            .lock()
+            .map(|s| s.exports.clone())
+            .unwrap_or_default()
+    }
+
+    /// All metrics received so far
+    pub fn metrics(&self) -> Vec<Metric> {
+        self.exports().into_iter().flat_map(|e| e.metrics).collect()
+    }
+
+    /// All log records received so far
+    pub fn logs(&self) -> Vec<LogRecord> {
+        self.exports().into_iter().flat_map(|e| e.logs).collect()
+    }
+
+    /// Wait until at least `count` exports have arrived
+    pub fn wait_for_exports(&self, count: usize, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if self.state.lock().map(|s| s.exports.len()).unwrap_or(0) >= count {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    /// Stop listening, drop open connections and wait for the thread to exit
+    pub fn stop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for OtlpReceiver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Adapts a closure returning whether an export was accepted to tonic's
+/// unary service interface
+struct Unary<F>(F);
+
+impl<T, F> UnaryService<T> for Unary<F>
+where
+    F: FnMut(T) -> bool,
+{
+    type Response = wire::ExportResponse;
+    type Future =
+        std::future::Ready<std::result::Result<tonic::Response<Self::Response>, tonic::Status>>;
+
+    fn call(&mut self, request: tonic::Request<T>) -> Self::Future {
+        std::future::ready(if (self.0)(request.into_inner()) {
+            // No partial_success
+            Ok(tonic::Response::new(wire::ExportResponse::default()))
+        } else {
+            Err(tonic::Status::unavailable("injected failure"))
+        })
+    }
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    state: Arc<Mutex<State>>,
+) -> std::result::Result<Response<BoxBody>, Infallible> {
+    let path = request.uri().path().to_string();
+    let headers: HashMap<String, String> = request
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let is_grpc = headers
+        .get("content-type")
+        .is_some_and(|v| v.starts_with("application/grpc"));
+    if !is_grpc {
+        return Ok(handle_http(request, path, headers, &state).await);
+    }
+
+    let compressed = headers.get("grpc-encoding").is_some_and(|v| v == "gzip");
+    let export = ReceivedExport {
+        protocol: OtlpProtocol::Grpc,
+        path: path.clone(),
+        headers,
+        compressed,
+        resource: Vec::new(),
+        metrics: Vec::new(),
+        logs: Vec::new(),
+    };
+    let response = if path.ends_with("MetricsService/Export") {
+        let service = Unary(move |request| {
+            let (resource, metrics) = proto::from_metrics_request(request);
+            record(
+                &state,
+                ReceivedExport {
+                    resource,
+                    metrics,
+                    ..export.clone()
+                },
+            )
+        });
+        Grpc::new(ProstCodec::<wire::ExportResponse, _>::default())
+            .accept_compressed(CompressionEncoding::Gzip)
+            .unary(service, request)
+            .await
+    } else if path.ends_with("LogsService/Export") {
+        let service = Unary(move |request| {
+            let (resource, logs) = proto::from_logs_request(request);
+            record(
+                &state,
+                ReceivedExport {
+                    resource,
+                    logs,
+                    ..export.clone()
+                },
+            )
+        });
+        Grpc::new(ProstCodec::<wire::ExportResponse, _>::default())
+            .accept_compressed(CompressionEncoding::Gzip)
+            .unary(service, request)
+            .await
+    } else {
+        tonic::Status::unimplemented("unknown service").into_http()
+    };
+    Ok(response)
+}
+
+async fn handle_http(
+    request: Request<Incoming>,
+    path: String,
+    headers: HashMap<String, String>,
+    state: &Mutex<State>,
+) -> Response<BoxBody> {
+    let compressed = headers
+        .get("content-encoding")
+        .is_some_and(|v| v.eq_ignore_ascii_case("gzip"));
+    let body = match request.into_body().collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(e) => return http_reply(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    let payload = if compressed {
+        let mut payload = Vec::new();
+        if let Err(e) = flate2::read::GzDecoder::new(body.as_ref()).read_to_end(&mut payload) {
+            return http_reply(StatusCode::BAD_REQUEST, e.to_string());
+        }
+        payload
+    } else {
+        body.to_vec()
+    };
+
+    let mut export = ReceivedExport {
+        protocol: OtlpProtocol::HttpProtobuf,
+        path,
+        headers,
+        compressed,
+        resource: Vec::new(),
+        metrics: Vec::new(),
+        logs: Vec::new(),
+    };
+    let decoded = if export.path.ends_with("/v1/metrics") {
+        proto::decode_metrics_request(&payload).map(|(resource, metrics)| {
+            export.resource = resource;
+            export.metrics = metrics;
+        })
+    } else if export.path.ends_with("/v1/logs") {
+        proto::decode_logs_request(&payload).map(|(resource, logs)| {
+            export.resource = resource;
+            export.logs = logs;
+        })
+    } else {
+        return http_reply(StatusCode::NOT_FOUND, String::new());
+    };
+    match decoded {
+        Ok(()) if record(state, export) => http_reply(StatusCode::OK, String::new()),
+        Ok(()) => {
+            let mut response = http_reply(StatusCode::SERVICE_UNAVAILABLE, String::new());
+            response
+                .headers_mut()
+                .insert("retry-after", hyper::header::HeaderValue::from_static("0"));
+            response
+        }
+        Err(e) => http_reply(StatusCode::BAD_REQUEST, e.to_string()),
+    }
+}
+
+fn http_reply(status: StatusCode, body: String) -> Response<BoxBody> {
+    let mut response = Response::new(tonic::body::boxed(Full::new(Bytes::from(body))));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        "content-type",
+        hyper::header::HeaderValue::from_static("application/x-protobuf"),
+    );
+    response
+}
+
+/// Keep `export` unless a failure is due, returning whether it was accepted
+fn record(state: &Mutex<State>, export: ReceivedExport) -> bool {
+    let Ok(mut state) = state.lock() else {
+        return false;
+    };
+    if state.fail_next > 0 {
+        state.fail_next -= 1;
+        return false;
+    }
+    state.exports.push(export);
+    true
+}
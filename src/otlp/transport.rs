@@ -0,0 +1,432 @@
+//! OTLP/HTTP and OTLP/gRPC delivery
+//!
+//! `http/protobuf` requests go out through a blocking [`reqwest`] client
+//! (`remote-backends` feature) and `grpc` calls through a [`tonic`] channel
+//! driven by a private current-thread runtime (`otlp-grpc` feature). Both
+//! speak TLS to `https://` endpoints. Failures are classified per the OTLP
+//! specification so the exporter knows whether to retry.
+
+use super::proto::{wire, PartialSuccess};
+#[cfg(any(feature = "remote-backends", feature = "otlp-grpc"))]
+use super::OtlpCompression;
+use super::{OtlpConfig, OtlpProtocol};
+use crate::error::{Result, SimonError};
+use std::time::Duration;
+
+/// One export request, kept as a message until the transport encodes it
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    not(any(feature = "remote-backends", feature = "otlp-grpc")),
+    allow(dead_code)
+)]
+pub(crate) enum Request {
+    Metrics(wire::ExportMetricsServiceRequest),
+    Logs(wire::ExportLogsServiceRequest),
+}
+
+impl Request {
+    #[cfg(feature = "remote-backends")]
+    fn http_path(&self) -> &'static str {
+        match self {
+            Self::Metrics(_) => "/v1/metrics",
+            Self::Logs(_) => "/v1/logs",
+        }
+    }
+
+    #[cfg(feature = "otlp-grpc")]
+    fn grpc_path(&self) -> &'static str {
+        match self {
+            Self::Metrics(_) => "/opentelemetry.proto.collector.metrics.v1.MetricsService/Export",
+            Self::Logs(_) => "/opentelemetry.proto.collector.logs.v1.LogsService/Export",
+        }
+    }
+}
+
+/// Why an export did not succeed
+#[derive(Debug)]
+pub(crate) struct ExportError {
+    pub(crate) message: String,
+    /// Transient failure (throttling, unavailable, network) worth retrying
+    pub(crate) retryable: bool,
+    /// Server-requested delay before the next attempt
+    pub(crate) retry_after: Option<Duration>,
+}
+
+impl ExportError {
+    pub(crate) fn retryable(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            retryable: true,
+            retry_after: None,
+        }
+    }
+
+    pub(crate) fn fatal(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            retryable: false,
+            retry_after: None,
+        }
+    }
+}
+
+/// Client for the configured collector
+pub(crate) struct Transport {
+    client: Client,
+}
+
+enum Client {
+    #[cfg(feature = "remote-backends")]
+    Http(HttpClient),
+    #[cfg(feature = "otlp-grpc")]
+    Grpc(GrpcClient),
+}
+
+impl Transport {
+    /// Validate the endpoint and set up the client for its protocol
+    pub(crate) fn new(config: &OtlpConfig) -> Result<Self> {
+        let timeout = Duration::from_millis(config.timeout_ms.max(100));
+        #[cfg(not(any(feature = "remote-backends", feature = "otlp-grpc")))]
+        let _ = timeout;
+        let client = match config.protocol {
+            #[cfg(feature = "remote-backends")]
+            OtlpProtocol::HttpProtobuf => HttpClient::new(config, timeout).map(Client::Http),
+            #[cfg(not(feature = "remote-backends"))]
+            OtlpProtocol::HttpProtobuf => Err(SimonError::FeatureNotAvailable(
+                "OTLP/HTTP export requires 'remote-backends' feature".into(),
+            )),
+            #[cfg(feature = "otlp-grpc")]
+            OtlpProtocol::Grpc => GrpcClient::new(config, timeout).map(Client::Grpc),
+            #[cfg(not(feature = "otlp-grpc"))]
+            OtlpProtocol::Grpc => Err(SimonError::FeatureNotAvailable(
+                "OTLP/gRPC export requires 'otlp-grpc' feature".into(),
+            )),
+        }?;
+        Ok(Self { client })
+    }
+
+    /// Deliver one export request
+    pub(crate) fn send(
+        &self,
+        request: &Request,
+    ) -> std::result::Result<Option<PartialSuccess>, ExportError> {
+        match &self.client {
+            #[cfg(feature = "remote-backends")]
+            Client::Http(client) => client.send(request),
+            #[cfg(feature = "otlp-grpc")]
+            Client::Grpc(client) => client.send(request),
+            #[cfg(not(any(feature = "remote-backends", feature = "otlp-grpc")))]
+            _ => {
+                let _ = request;
+                Err(ExportError::fatal(
+                    "OTLP export requires 'remote-backends' or 'otlp-grpc' feature",
+                ))
+            }
+        }
+    }
+}
+
+/// Split a `http(s)://host[:port][/path]` endpoint into `scheme://host:port`
+/// and path, filling in `default_port` for `http://` endpoints without one
+///
+/// `https://` endpoints keep the scheme's port 443, as hosted collectors
+/// expect.
+fn split_endpoint(url: &str, default_port: u16) -> Result<(String, String)> {
+    let (scheme, rest) = url
+        .split_once("://")
+        .filter(|(scheme, _)| matches!(*scheme, "http" | "https"))
+        .ok_or_else(|| {
+            SimonError::Configuration(format!(
+                "Endpoint must start with http:// or https://: {}",
+                url
+            ))
+        })?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+    if authority.is_empty() {
+        return Err(SimonError::Configuration(format!(
+            "Endpoint has no host: {}",
+            url
+        )));
+    }
+    // Bracketed IPv6 literals carry colons of their own
+    let has_port = authority.rsplit_once(':').is_some_and(|(host, port)| {
+        !port.is_empty() && (!host.contains(':') || host.ends_with(']'))
+    });
+    let origin = if has_port || scheme == "https" {
+        format!("{}://{}", scheme, authority)
+    } else {
+        format!("{}://{}:{}", scheme, authority, default_port)
+    };
+    Ok((origin, path.trim_end_matches('/').to_string()))
+}
+
+/// OTLP/HTTP with protobuf bodies
+#[cfg(feature = "remote-backends")]
+struct HttpClient {
+    /// Endpoint URL without trailing slash; signal paths are appended
+    base_url: String,
+    headers: Vec<(String, String)>,
+    gzip: bool,
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "remote-backends")]
+impl HttpClient {
+    fn new(config: &OtlpConfig, timeout: Duration) -> Result<Self> {
+        let (origin, path) = split_endpoint(&config.endpoint(), 4318)?;
+        let client = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .user_agent(format!("simon/{}", crate::VERSION))
+            .build()
+            .map_err(|e| SimonError::Network(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(Self {
+            base_url: origin + &path,
+            headers: config
+                .headers
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            gzip: config.compression == OtlpCompression::Gzip,
+            client,
+        })
+    }
+
+    fn send(&self, request: &Request) -> std::result::Result<Option<PartialSuccess>, ExportError> {
+        use prost::Message;
+        use std::io::Write;
+
+        let payload = match request {
+            Request::Metrics(m) => m.encode_to_vec(),
+            Request::Logs(l) => l.encode_to_vec(),
+        };
+        let body = if self.gzip {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder
+                .write_all(&payload)
+                .and_then(|_| encoder.finish())
+                .map_err(|e| ExportError::fatal(format!("gzip: {}", e)))?
+        } else {
+            payload
+        };
+
+        let url = format!("{}{}", self.base_url, request.http_path());
+        let mut builder = self
+            .client
+            .post(&url)
+            .header("content-type", "application/x-protobuf")
+            .body(body);
+        if self.gzip {
+            builder = builder.header("content-encoding", "gzip");
+        }
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let response = builder.send().map_err(|e| {
+            let message = format!("POST {}: {}", url, e);
+            if e.is_builder() {
+                ExportError::fatal(message)
+            } else {
+                ExportError::retryable(message)
+            }
+        })?;
+
+        let status = response.status().as_u16();
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+            .map(Duration::from_secs);
+        let body = response
+            .bytes()
+            .map_err(|e| ExportError::retryable(format!("http: {}", e)))?;
+        if (200..300).contains(&status) {
+            // Collectors may answer with an empty or JSON body; only protobuf
+            // carries partial_success
+            return Ok(wire::ExportResponse::decode(body.as_ref())
+                .ok()
+                .and_then(super::proto::from_response));
+        }
+        let detail = String::from_utf8_lossy(&body);
+        let message = format!(
+            "HTTP {} from collector: {}",
+            status,
+            detail.trim().chars().take(200).collect::<String>()
+        );
+        Err(match status {
+            429 | 502 | 503 | 504 => ExportError {
+                message,
+                retryable: true,
+                retry_after,
+            },
+            _ => ExportError::fatal(message),
+        })
+    }
+}
+
+/// OTLP/gRPC unary `Export` calls
+#[cfg(feature = "otlp-grpc")]
+struct GrpcClient {
+    runtime: tokio::runtime::Runtime,
+    channel: tonic::transport::Channel,
+    metadata: tonic::metadata::MetadataMap,
+    gzip: bool,
+}
+
+#[cfg(feature = "otlp-grpc")]
+impl GrpcClient {
+    fn new(config: &OtlpConfig, timeout: Duration) -> Result<Self> {
+        use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+        use tonic::transport::{ClientTlsConfig, Endpoint};
+
+        let (origin, _) = split_endpoint(&config.endpoint(), 4317)?;
+        let invalid = |e: &dyn std::fmt::Display| {
+            SimonError::Configuration(format!("Invalid OTLP endpoint {}: {}", origin, e))
+        };
+        let mut endpoint = Endpoint::from_shared(origin.clone())
+            .map_err(|e| invalid(&e))?
+            .connect_timeout(timeout)
+            .timeout(timeout)
+            .user_agent(format!("simon/{}", crate::VERSION))
+            .map_err(|e| invalid(&e))?;
+        if origin.starts_with("https://") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new().with_native_roots())
+                .map_err(|e| invalid(&e))?;
+        }
+
+        let mut metadata = MetadataMap::new();
+        for (name, value) in &config.headers {
+            let key = MetadataKey::from_bytes(name.to_ascii_lowercase().as_bytes());
+            let value = MetadataValue::try_from(value.as_str());
+            match (key, value) {
+                (Ok(key), Ok(value)) => {
+                    metadata.insert(key, value);
+                }
+                _ => {
+                    return Err(SimonError::Configuration(format!(
+                        "Invalid OTLP header: {}",
+                        name
+                    )))
+                }
+            }
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| SimonError::Other(format!("Failed to start gRPC runtime: {}", e)))?;
+        // Connects on first use and reconnects after failures
+        let channel = {
+            let _guard = runtime.enter();
+            endpoint.connect_lazy()
+        };
+        Ok(Self {
+            runtime,
+            channel,
+            metadata,
+            gzip: config.compression == OtlpCompression::Gzip,
+        })
+    }
+
+    /// `message` with the configured headers as request metadata
+    fn call<T>(&self, message: T) -> tonic::Request<T> {
+        let mut call = tonic::Request::new(message);
+        *call.metadata_mut() = self.metadata.clone();
+        call
+    }
+
+    fn send(&self, request: &Request) -> std::result::Result<Option<PartialSuccess>, ExportError> {
+        use tonic::codec::{CompressionEncoding, ProstCodec};
+        use tonic::codegen::http::uri::PathAndQuery;
+
+        let path = PathAndQuery::from_static(request.grpc_path());
+        let result = self.runtime.block_on(async {
+            let mut grpc = tonic::client::Grpc::new(self.channel.clone())
+                .accept_compressed(CompressionEncoding::Gzip);
+            if self.gzip {
+                grpc = grpc.send_compressed(CompressionEncoding::Gzip);
+            }
+            grpc.ready()
+                .await
+                .map_err(|e| tonic::Status::unavailable(e.to_string()))?;
+            match request {
+                Request::Metrics(m) => {
+                    let codec = ProstCodec::<_, wire::ExportResponse>::default();
+                    grpc.unary(self.call(m.clone()), path, codec).await
+                }
+                Request::Logs(l) => {
+                    let codec = ProstCodec::<_, wire::ExportResponse>::default();
+                    grpc.unary(self.call(l.clone()), path, codec).await
+                }
+            }
+        });
+
+        match result {
+            Ok(response) => Ok(super::proto::from_response(response.into_inner())),
+            Err(status) => {
+                let message = format!("gRPC status {}: {}", status.code() as i32, status.message());
+                // CANCELLED, DEADLINE_EXCEEDED, RESOURCE_EXHAUSTED, ABORTED,
+                // OUT_OF_RANGE, UNAVAILABLE, DATA_LOSS
+                use tonic::Code;
+                Err(match status.code() {
+                    Code::Cancelled
+                    | Code::DeadlineExceeded
+                    | Code::ResourceExhausted
+                    | Code::Aborted
+                    | Code::OutOfRange
+                    | Code::Unavailable
+                    | Code::DataLoss => ExportError::retryable(message),
+                    _ => ExportError::fatal(message),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_parsing() {
+        assert_eq!(
+            split_endpoint("http://collector.local/otel/", 4318).unwrap(),
+            (
+                "http://collector.local:4318".to_string(),
+                "/otel".to_string()
+            )
+        );
+        assert_eq!(
+            split_endpoint("http://[::1]", 4317).unwrap().0,
+            "http://[::1]:4317"
+        );
+        assert_eq!(
+            split_endpoint("http://[::1]:9999", 4317).unwrap().0,
+            "http://[::1]:9999"
+        );
+        assert_eq!(
+            split_endpoint("https://otlp.example.com/v1", 4318).unwrap(),
+            ("https://otlp.example.com".to_string(), "/v1".to_string())
+        );
+        assert!(split_endpoint("ftp://collector.local", 4318).is_err());
+        assert!(split_endpoint("https:///v1", 4318).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "remote-backends", feature = "otlp-grpc"))]
+    fn test_https_endpoints_are_accepted() {
+        let mut config = OtlpConfig {
+            endpoint: Some("https://collector.local:4318".into()),
+            ..Default::default()
+        };
+        assert!(Transport::new(&config).is_ok());
+        config.protocol = OtlpProtocol::Grpc;
+        config.endpoint = Some("https://collector.local:4317".into());
+        assert!(Transport::new(&config).is_ok());
+    }
+}
@@ -0,0 +1,1349 @@
+//! Offline capture analysis
+//!
+//! Turns a pcap/pcapng file ([`analyze_file`]) or the packet list of a
+//! [`CaptureResult`] ([`analyze_capture_result`]) into:
+//! - a flow table keyed by 5-tuple, with per-direction packets and bytes
+//! - top talkers and protocol distribution by bytes
+//! - TCP health: retransmissions, zero-window advertisements and resets
+//! - DNS queries paired with their responses (latency, rcode)
+//!
+//! Files give full fidelity. A `CaptureResult` only carries what the capture
+//! backend printed: native captures and tcpdump include sequence numbers and
+//! windows in `info`, tshark marks retransmissions itself; anything else is
+//! reported in [`CaptureAnalysis::warnings`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::packet_capture::analysis::analyze_file;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let analysis = analyze_file("/tmp/eth0.pcapng".as_ref(), 10)?;
+//! for talker in &analysis.top_talkers {
+//!     println!("{:<40} {:>5.1}%", talker.address, talker.share_percent);
+//! }
+//! println!("{} TCP retransmissions", analysis.tcp.retransmissions);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::decode::{self, tcp_flags, Packet, TransportLayer};
+use super::reader::{CaptureReader, FileFrame};
+use super::LinkType;
+use crate::error::Result;
+use crate::network_tools::{CaptureResult, CapturedPacket};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Shortest flow span that still gets a bit rate
+const MIN_FLOW_RATE_SECS: f64 = 0.001;
+
+/// Traffic between two endpoints over one protocol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowSummary {
+    /// Transport protocol (TCP, UDP, ICMP, ...) or link protocol (ARP)
+    pub protocol: String,
+    /// Endpoint that sent the first packet (the client for TCP handshakes)
+    pub src: String,
+    pub src_port: Option<u16>,
+    pub dst: String,
+    pub dst_port: Option<u16>,
+    /// src -> dst
+    pub packets_out: u64,
+    pub bytes_out: u64,
+    /// dst -> src
+    pub packets_in: u64,
+    pub bytes_in: u64,
+    /// Seconds after the first packet of the capture
+    pub start_secs: f64,
+    pub duration_secs: f64,
+    /// Average over the flow's duration (None for flows shorter than 1 ms)
+    pub bits_per_sec: Option<f64>,
+    pub retransmissions: u64,
+    pub zero_windows: u64,
+    pub resets: u64,
+}
+
+impl FlowSummary {
+    pub fn packets(&self) -> u64 {
+        self.packets_out + self.packets_in
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes_out + self.bytes_in
+    }
+}
+
+/// Per-host traffic totals
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Talker {
+    pub address: String,
+    pub packets_sent: u64,
+    pub bytes_sent: u64,
+    pub packets_received: u64,
+    pub bytes_received: u64,
+    /// Share of all captured bytes this host sent or received
+    pub share_percent: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtocolShare {
+    /// Highest decoded protocol, as in the capture list
+    pub protocol: String,
+    pub packets: u64,
+    pub bytes: u64,
+    pub percent_bytes: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TcpHealth {
+    pub segments: u64,
+    pub retransmissions: u64,
+    /// Retransmitted share of segments, in percent
+    pub retransmission_percent: f64,
+    pub zero_windows: u64,
+    pub resets: u64,
+    /// Connection attempts (SYN without ACK)
+    pub syns: u64,
+}
+
+/// A DNS query and its response, if one was captured
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DnsTransaction {
+    pub id: u16,
+    pub client: String,
+    pub server: String,
+    pub name: Option<String>,
+    /// Query type (A, AAAA, ...)
+    pub qtype: Option<String>,
+    /// None while unanswered
+    pub rcode: Option<u8>,
+    pub answers: u16,
+    pub latency_ms: Option<f64>,
+    /// Queries sent for this transaction; more than one means retries
+    pub attempts: u32,
+}
+
+impl DnsTransaction {
+    pub fn rcode_name(&self) -> &'static str {
+        match self.rcode {
+            None => "unanswered",
+            Some(0) => "NOERROR",
+            Some(1) => "FORMERR",
+            Some(2) => "SERVFAIL",
+            Some(3) => "NXDOMAIN",
+            Some(4) => "NOTIMP",
+            Some(5) => "REFUSED",
+            Some(_) => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DnsSummary {
+    pub queries: u64,
+    pub responses: u64,
+    /// Transactions without a captured response
+    pub unanswered: u64,
+    /// Responses with a non-zero rcode
+    pub errors: u64,
+    pub mean_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<f64>,
+    /// Most queried names with their query counts
+    pub top_names: Vec<(String, u64)>,
+    /// Unanswered and failed transactions first, then the slowest
+    pub transactions: Vec<DnsTransaction>,
+}
+
+/// Result of analysing a capture
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureAnalysis {
+    /// File path or capture interface
+    pub source: String,
+    pub packets: u64,
+    pub bytes: u64,
+    /// From the first to the last packet timestamp, or the capture window for
+    /// live captures
+    pub duration_secs: f64,
+    /// Average over the capture (None without usable timestamps)
+    pub bits_per_sec: Option<f64>,
+    pub protocols: Vec<ProtocolShare>,
+    pub top_talkers: Vec<Talker>,
+    /// Largest flows by bytes
+    pub flows: Vec<FlowSummary>,
+    pub total_flows: usize,
+    pub tcp: TcpHealth,
+    pub dns: DnsSummary,
+    /// Frames that could not be analysed and other caveats
+    pub warnings: Vec<String>,
+}
+
+/// Analyse a pcap or pcapng file, keeping `top_n` entries per table
+pub fn analyze_file(path: &Path, top_n: usize) -> Result<CaptureAnalysis> {
+    let mut reader = CaptureReader::open(path)?;
+    let mut analyzer = CaptureAnalyzer::new(top_n);
+    while let Some(frame) = reader.next_frame()? {
+        analyzer.add_file_frame(&frame);
+    }
+    if reader.truncated() {
+        analyzer
+            .warnings
+            .push("file ends mid-record; the partial record was ignored".into());
+    }
+    let mut analysis = analyzer.finish();
+    analysis.source = path.display().to_string();
+    Ok(analysis)
+}
+
+/// Analyse the packets of a live capture
+pub fn analyze_capture_result(result: &CaptureResult, top_n: usize) -> CaptureAnalysis {
+    let mut analyzer = CaptureAnalyzer::new(top_n);
+    for packet in &result.packets {
+        analyzer.add_captured(packet);
+    }
+    if analyzer.untracked_segments > 0 {
+        analyzer.warnings.push(format!(
+            "{} TCP segments without sequence numbers; retransmissions may be undercounted",
+            analyzer.untracked_segments
+        ));
+    }
+    if result.dropped_packets > 0 {
+        analyzer.warnings.push(format!(
+            "kernel dropped {} packets during capture",
+            result.dropped_packets
+        ));
+    }
+    let mut analysis = analyzer.finish();
+    analysis.source = result.interface.clone();
+    // Rate over the time spent listening, not just between the first and last packet
+    if result.duration_secs > 0.0 {
+        analysis.duration_secs = result.duration_secs;
+        analysis.bits_per_sec = Some(analysis.bytes as f64 * 8.0 / result.duration_secs);
+    }
+    analysis
+}
+
+/// What the analyzer needs from one packet, whatever its source
+#[derive(Debug, Clone, Default)]
+struct Observation {
+    /// Seconds on any fixed epoch
+    timestamp: Option<f64>,
+    /// Protocol for the distribution (DNS, TCP, ...)
+    protocol: String,
+    /// Protocol for the flow key (DNS counts as UDP or TCP)
+    flow_protocol: String,
+    src: String,
+    src_port: Option<u16>,
+    dst: String,
+    dst_port: Option<u16>,
+    length: u64,
+    tcp: Option<Segment>,
+    dns: Option<DnsObservation>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Segment {
+    flags: u8,
+    seq: Option<u32>,
+    len: u32,
+    window: Option<u16>,
+    /// Flagged by the capture tool itself (tshark)
+    retransmission: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct DnsObservation {
+    id: u16,
+    response: bool,
+    rcode: u8,
+    answers: u16,
+    name: Option<String>,
+    qtype: Option<String>,
+}
+
+type Endpoint = (String, Option<u16>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FlowKey {
+    protocol: String,
+    a: Endpoint,
+    b: Endpoint,
+}
+
+#[derive(Debug)]
+struct FlowState {
+    summary: FlowSummary,
+    first: f64,
+    last: f64,
+    /// Highest sequence number sent, per direction (out, in)
+    next_seq: [Option<u32>; 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DnsKey {
+    id: u16,
+    client: Endpoint,
+    server: String,
+}
+
+struct PendingDns {
+    transaction: DnsTransaction,
+    sent_at: Option<f64>,
+}
+
+/// Incremental analyzer; feed packets in capture order, then [`Self::finish`]
+pub struct CaptureAnalyzer {
+    top_n: usize,
+    packets: u64,
+    bytes: u64,
+    first: Option<f64>,
+    last: Option<f64>,
+    protocols: HashMap<String, (u64, u64)>,
+    talkers: HashMap<String, Talker>,
+    flows: HashMap<FlowKey, FlowState>,
+    tcp: TcpHealth,
+    dns: DnsSummary,
+    dns_pending: HashMap<DnsKey, PendingDns>,
+    dns_done: Vec<DnsTransaction>,
+    dns_names: HashMap<String, u64>,
+    skipped_links: HashMap<u16, u64>,
+    untracked_segments: u64,
+    warnings: Vec<String>,
+}
+
+impl CaptureAnalyzer {
+    pub fn new(top_n: usize) -> Self {
+        Self {
+            top_n: top_n.max(1),
+            packets: 0,
+            bytes: 0,
+            first: None,
+            last: None,
+            protocols: HashMap::new(),
+            talkers: HashMap::new(),
+            flows: HashMap::new(),
+            tcp: TcpHealth::default(),
+            dns: DnsSummary::default(),
+            dns_pending: HashMap::new(),
+            dns_done: Vec::new(),
+            dns_names: HashMap::new(),
+            skipped_links: HashMap::new(),
+            untracked_segments: 0,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Add a frame read from a capture file
+    pub fn add_file_frame(&mut self, frame: &FileFrame) {
+        match frame.payload() {
+            Some((link, data)) => self.add_frame(link, frame.timestamp_ns, data, frame.orig_len),
+            None => *self.skipped_links.entry(frame.link_code).or_default() += 1,
+        }
+    }
+
+    /// Add a raw frame captured at `timestamp_ns` (Unix epoch nanoseconds)
+    pub fn add_frame(&mut self, link: LinkType, timestamp_ns: u64, frame: &[u8], orig_len: u32) {
+        let packet = decode::decode(link, frame);
+        let observation = observe_packet(&packet, timestamp_ns, frame.len(), orig_len);
+        self.observe(observation);
+    }
+
+    /// Add an entry of a [`CaptureResult`] packet list
+    pub fn add_captured(&mut self, packet: &CapturedPacket) {
+        let observation = observe_captured(packet);
+        // tcpdump omits seq on pure ACKs, which carry nothing to track
+        let carries_data =
+            |s: &Segment| s.len > 0 || s.flags & (tcp_flags::SYN | tcp_flags::FIN) != 0;
+        if observation
+            .tcp
+            .is_some_and(|s| s.seq.is_none() && !s.retransmission && carries_data(&s))
+        {
+            self.untracked_segments += 1;
+        }
+        self.observe(observation);
+    }
+
+    fn observe(&mut self, obs: Observation) {
+        self.packets += 1;
+        self.bytes += obs.length;
+        if let Some(ts) = obs.timestamp {
+            self.first = Some(self.first.map_or(ts, |f| f.min(ts)));
+            self.last = Some(self.last.map_or(ts, |l| l.max(ts)));
+        }
+        let proto = self.protocols.entry(obs.protocol.clone()).or_default();
+        proto.0 += 1;
+        proto.1 += obs.length;
+
+        if !obs.src.is_empty() {
+            let talker = self.talker(&obs.src);
+            talker.packets_sent += 1;
+            talker.bytes_sent += obs.length;
+        }
+        if !obs.dst.is_empty() {
+            let talker = self.talker(&obs.dst);
+            talker.packets_received += 1;
+            talker.bytes_received += obs.length;
+        }
+
+        self.observe_flow(&obs);
+        if let Some(dns) = &obs.dns {
+            self.observe_dns(&obs, dns);
+        }
+    }
+
+    fn talker(&mut self, address: &str) -> &mut Talker {
+        self.talkers
+            .entry(address.to_string())
+            .or_insert_with(|| Talker {
+                address: address.to_string(),
+                packets_sent: 0,
+                bytes_sent: 0,
+                packets_received: 0,
+                bytes_received: 0,
+                share_percent: 0.0,
+            })
+    }
+
+    fn observe_flow(&mut self, obs: &Observation) {
+        let src: Endpoint = (obs.src.clone(), obs.src_port);
+        let dst: Endpoint = (obs.dst.clone(), obs.dst_port);
+        let key = FlowKey {
+            protocol: obs.flow_protocol.clone(),
+            a: src.clone().min(dst.clone()),
+            b: src.clone().max(dst.clone()),
+        };
+        let ts = obs.timestamp.unwrap_or(0.0);
+        let flow = self.flows.entry(key).or_insert_with(|| {
+            // A SYN-ACK seen first means the handshake started before the
+            // capture: the receiver is the client
+            let reversed = obs.tcp.is_some_and(|s| {
+                s.flags & (tcp_flags::SYN | tcp_flags::ACK) == tcp_flags::SYN | tcp_flags::ACK
+            });
+            let (s, d) = if reversed { (&dst, &src) } else { (&src, &dst) };
+            FlowState {
+                summary: FlowSummary {
+                    protocol: obs.flow_protocol.clone(),
+                    src: s.0.clone(),
+                    src_port: s.1,
+                    dst: d.0.clone(),
+                    dst_port: d.1,
+                    packets_out: 0,
+                    bytes_out: 0,
+                    packets_in: 0,
+                    bytes_in: 0,
+                    start_secs: 0.0,
+                    duration_secs: 0.0,
+                    bits_per_sec: None,
+                    retransmissions: 0,
+                    zero_windows: 0,
+                    resets: 0,
+                },
+                first: ts,
+                last: ts,
+                next_seq: [None; 2],
+            }
+        });
+        flow.first = flow.first.min(ts);
+        flow.last = flow.last.max(ts);
+        let outbound = flow.summary.src == src.0 && flow.summary.src_port == src.1;
+        if outbound {
+            flow.summary.packets_out += 1;
+            flow.summary.bytes_out += obs.length;
+        } else {
+            flow.summary.packets_in += 1;
+            flow.summary.bytes_in += obs.length;
+        }
+
+        let Some(segment) = obs.tcp else {
+            return;
+        };
+        self.tcp.segments += 1;
+        if segment.flags & tcp_flags::RST != 0 {
+            self.tcp.resets += 1;
+            flow.summary.resets += 1;
+            return;
+        }
+        if segment.flags & (tcp_flags::SYN | tcp_flags::ACK) == tcp_flags::SYN {
+            self.tcp.syns += 1;
+        }
+        if segment.window == Some(0) {
+            self.tcp.zero_windows += 1;
+            flow.summary.zero_windows += 1;
+        }
+        let next = &mut flow.next_seq[usize::from(!outbound)];
+        if is_retransmission(segment, next) {
+            self.tcp.retransmissions += 1;
+            flow.summary.retransmissions += 1;
+        }
+    }
+
+    fn observe_dns(&mut self, obs: &Observation, dns: &DnsObservation) {
+        if let Some(name) = &dns.name {
+            if !dns.response {
+                *self.dns_names.entry(name.clone()).or_default() += 1;
+            }
+        }
+        let key = if dns.response {
+            DnsKey {
+                id: dns.id,
+                client: (obs.dst.clone(), obs.dst_port),
+                server: obs.src.clone(),
+            }
+        } else {
+            DnsKey {
+                id: dns.id,
+                client: (obs.src.clone(), obs.src_port),
+                server: obs.dst.clone(),
+            }
+        };
+
+        if !dns.response {
+            self.dns.queries += 1;
+            let pending = self.dns_pending.entry(key).or_insert_with(|| PendingDns {
+                transaction: DnsTransaction {
+                    id: dns.id,
+                    client: obs.src.clone(),
+                    server: obs.dst.clone(),
+                    name: dns.name.clone(),
+                    qtype: dns.qtype.clone(),
+                    rcode: None,
+                    answers: 0,
+                    latency_ms: None,
+                    attempts: 0,
+                },
+                sent_at: obs.timestamp,
+            });
+            pending.transaction.attempts += 1;
+            return;
+        }
+
+        self.dns.responses += 1;
+        if dns.rcode != 0 {
+            self.dns.errors += 1;
+        }
+        // Responses to queries sent before the capture started have no
+        // transaction to complete
+        if let Some(pending) = self.dns_pending.remove(&key) {
+            let mut t = pending.transaction;
+            t.rcode = Some(dns.rcode);
+            t.answers = dns.answers;
+            t.name = t.name.or_else(|| dns.name.clone());
+            t.qtype = t.qtype.or_else(|| dns.qtype.clone());
+            t.latency_ms = pending
+                .sent_at
+                .zip(obs.timestamp)
+                .map(|(sent, received)| ((received - sent) * 1000.0).max(0.0));
+            self.dns_done.push(t);
+        }
+    }
+
+    pub fn finish(mut self) -> CaptureAnalysis {
+        let top_n = self.top_n;
+        let duration = match (self.first, self.last) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0.0,
+        };
+        let percent = |part: u64, total: u64| {
+            if total == 0 {
+                0.0
+            } else {
+                (part as f64 * 100.0 / total as f64).min(100.0)
+            }
+        };
+
+        let mut protocols: Vec<ProtocolShare> = self
+            .protocols
+            .into_iter()
+            .map(|(protocol, (packets, bytes))| ProtocolShare {
+                protocol,
+                packets,
+                bytes,
+                percent_bytes: percent(bytes, self.bytes),
+            })
+            .collect();
+        protocols.sort_by(|a, b| {
+            b.bytes
+                .cmp(&a.bytes)
+                .then_with(|| a.protocol.cmp(&b.protocol))
+        });
+
+        let mut top_talkers: Vec<Talker> = self.talkers.into_values().collect();
+        for t in &mut top_talkers {
+            t.share_percent = percent(t.bytes_sent + t.bytes_received, self.bytes);
+        }
+        top_talkers.sort_by(|a, b| {
+            (b.bytes_sent + b.bytes_received)
+                .cmp(&(a.bytes_sent + a.bytes_received))
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        top_talkers.truncate(top_n);
+
+        let total_flows = self.flows.len();
+        let first = self.first.unwrap_or(0.0);
+        let mut flows: Vec<FlowSummary> = self
+            .flows
+            .into_values()
+            .map(|state| {
+                let mut s = state.summary;
+                s.start_secs = state.first - first;
+                s.duration_secs = state.last - state.first;
+                s.bits_per_sec = (s.duration_secs >= MIN_FLOW_RATE_SECS)
+                    .then(|| (s.bytes_out + s.bytes_in) as f64 * 8.0 / s.duration_secs);
+                s
+            })
+            .collect();
+        flows.sort_by(|a, b| {
+            b.bytes()
+                .cmp(&a.bytes())
+                .then_with(|| a.start_secs.total_cmp(&b.start_secs))
+                .then_with(|| (&a.src, a.src_port).cmp(&(&b.src, b.src_port)))
+        });
+        flows.truncate(top_n);
+
+        self.tcp.retransmission_percent = if self.tcp.segments == 0 {
+            0.0
+        } else {
+            self.tcp.retransmissions as f64 * 100.0 / self.tcp.segments as f64
+        };
+
+        let mut transactions = self.dns_done;
+        transactions.extend(self.dns_pending.into_values().map(|p| p.transaction));
+        self.dns.unanswered = transactions.iter().filter(|t| t.rcode.is_none()).count() as u64;
+        let latencies: Vec<f64> = transactions.iter().filter_map(|t| t.latency_ms).collect();
+        if !latencies.is_empty() {
+            self.dns.mean_latency_ms = Some(latencies.iter().sum::<f64>() / latencies.len() as f64);
+            self.dns.max_latency_ms = latencies.iter().copied().reduce(f64::max);
+        }
+        // Unanswered, then failed, then slowest
+        transactions.sort_by(|a, b| {
+            let rank = |t: &DnsTransaction| match t.rcode {
+                None => 0,
+                Some(0) => 2,
+                Some(_) => 1,
+            };
+            rank(a).cmp(&rank(b)).then_with(|| {
+                b.latency_ms
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.latency_ms.unwrap_or(0.0))
+            })
+        });
+        transactions.truncate(top_n);
+        self.dns.transactions = transactions;
+        let mut names: Vec<(String, u64)> = self.dns_names.into_iter().collect();
+        names.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        names.truncate(top_n);
+        self.dns.top_names = names;
+
+        let mut skipped: Vec<(u16, u64)> = self.skipped_links.into_iter().collect();
+        skipped.sort_unstable();
+        for (code, count) in skipped {
+            self.warnings.push(format!(
+                "{} frames with unsupported link type {} skipped",
+                count, code
+            ));
+        }
+
+        CaptureAnalysis {
+            source: String::new(),
+            packets: self.packets,
+            bytes: self.bytes,
+            duration_secs: duration,
+            bits_per_sec: (duration > 0.0).then(|| self.bytes as f64 * 8.0 / duration),
+            protocols,
+            top_talkers,
+            flows,
+            total_flows,
+            tcp: self.tcp,
+            dns: self.dns,
+            warnings: self.warnings,
+        }
+    }
+}
+
+/// Whether `segment` re-sends data already seen in its direction; advances
+/// `next` (the highest sequence number sent) otherwise
+fn is_retransmission(segment: Segment, next: &mut Option<u32>) -> bool {
+    if segment.retransmission {
+        return true;
+    }
+    let Some(seq) = segment.seq else {
+        return false;
+    };
+    // SYN and FIN occupy one sequence number each
+    let syn_fin = segment.flags & (tcp_flags::SYN | tcp_flags::FIN);
+    let span = segment.len + syn_fin.count_ones();
+    if span == 0 {
+        return false;
+    }
+    let end = seq.wrapping_add(span);
+    let Some(expected) = *next else {
+        *next = Some(end);
+        return false;
+    };
+    if (end.wrapping_sub(expected) as i32) > 0 {
+        *next = Some(end);
+        return false;
+    }
+    // Keep-alive: one byte (or none) just below the next sequence number
+    let keep_alive = syn_fin == 0 && segment.len <= 1 && seq == expected.wrapping_sub(1);
+    !keep_alive
+}
+
+fn observe_packet(
+    packet: &Packet<'_>,
+    timestamp_ns: u64,
+    caplen: usize,
+    orig_len: u32,
+) -> Observation {
+    let protocol = packet.protocol_name();
+    let flow_protocol = match packet.transport {
+        Some(TransportLayer::Tcp { .. }) => "TCP".to_string(),
+        Some(TransportLayer::Udp { .. }) => "UDP".to_string(),
+        _ => protocol.clone(),
+    };
+    let (src_ip, dst_ip) = packet.endpoints();
+    let (src_port, dst_port) = packet.ports();
+    let (src, dst) = match (src_ip, dst_ip, &packet.ethernet) {
+        (Some(s), Some(d), _) => (s.to_string(), d.to_string()),
+        (_, _, Some(eth)) => (decode::format_mac(&eth.src), decode::format_mac(&eth.dst)),
+        _ => Default::default(),
+    };
+    let tcp = match packet.transport {
+        Some(TransportLayer::Tcp {
+            seq, flags, window, ..
+        }) => Some(Segment {
+            flags,
+            seq: Some(seq),
+            // Bytes cut by the snap length were still sent
+            len: (packet.payload.len() + (orig_len as usize).saturating_sub(caplen)) as u32,
+            window: Some(window),
+            retransmission: false,
+        }),
+        _ => None,
+    };
+    let dns = packet.dns.as_ref().map(|d| DnsObservation {
+        id: d.id,
+        response: d.response,
+        rcode: d.rcode,
+        answers: d.answers,
+        name: d.question.as_ref().map(|q| q.name.clone()),
+        qtype: d.question.as_ref().map(|q| decode::dns_type_name(q.qtype)),
+    });
+    Observation {
+        timestamp: Some(timestamp_ns as f64 / 1e9),
+        protocol,
+        flow_protocol,
+        src,
+        src_port,
+        dst,
+        dst_port,
+        length: orig_len as u64,
+        tcp,
+        dns,
+    }
+}
+
+fn observe_captured(packet: &CapturedPacket) -> Observation {
+    // tcpdump prints ports as the last dotted component of the address
+    let (src, src_port) = split_port(&packet.source, packet.src_port);
+    let (dst, dst_port) = split_port(&packet.destination, packet.dst_port);
+    let info = packet.info.as_str();
+    let is_dns_port = |p: Option<u16>| matches!(p, Some(53 | 5353));
+
+    let mut protocol = match packet.protocol.as_str() {
+        // tcpdump only prints the network protocol
+        "IP" | "IP6" => {
+            if packet.tcp_flags.is_some() {
+                "TCP"
+            } else if info.contains("ICMP") {
+                if packet.protocol == "IP6" {
+                    "ICMPv6"
+                } else {
+                    "ICMP"
+                }
+            } else if info.starts_with("UDP") || src_port.is_some() {
+                "UDP"
+            } else {
+                packet.protocol.as_str()
+            }
+        }
+        p => p,
+    }
+    .to_string();
+    let dns = if protocol == "DNS" || is_dns_port(src_port) || is_dns_port(dst_port) {
+        parse_dns_info(info)
+    } else {
+        None
+    };
+    let flow_protocol = match (protocol.as_str(), &packet.tcp_flags) {
+        ("DNS", Some(_)) => "TCP".to_string(),
+        ("DNS", None) => "UDP".to_string(),
+        (p, _) => p.to_string(),
+    };
+    if dns.is_some() {
+        protocol = "DNS".into();
+    }
+    let tcp = packet.tcp_flags.as_deref().map(|flags| Segment {
+        flags: parse_tcp_flags(flags),
+        seq: info_number(info, &["seq ", "Seq="]),
+        len: info_seq_len(info)
+            .or_else(|| info_number(info, &["length "]))
+            .unwrap_or(0),
+        window: info_number(info, &["win ", "Win="]).map(|w| w.min(u16::MAX as u32) as u16),
+        retransmission: info.contains("Retransmission"),
+    });
+
+    Observation {
+        timestamp: parse_timestamp(&packet.timestamp),
+        protocol,
+        flow_protocol,
+        src,
+        src_port,
+        dst,
+        dst_port,
+        length: packet.length as u64,
+        tcp,
+        dns,
+    }
+}
+
+fn split_port(address: &str, port: Option<u16>) -> Endpoint {
+    if port.is_some() || address.parse::<std::net::IpAddr>().is_ok() {
+        return (address.to_string(), port);
+    }
+    match address.rsplit_once('.') {
+        Some((host, p)) if host.parse::<std::net::IpAddr>().is_ok() => match p.parse() {
+            Ok(p) => (host.to_string(), Some(p)),
+            Err(_) => (address.to_string(), None),
+        },
+        _ => (address.to_string(), None),
+    }
+}
+
+/// Seconds from a native/tcpdump `YYYY-MM-DD HH:MM:SS.ffffff` timestamp or a
+/// tshark relative time
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let timestamp = timestamp.trim();
+    if let Ok(secs) = timestamp.parse::<f64>() {
+        return Some(secs);
+    }
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|t| t.and_utc().timestamp_micros() as f64 / 1e6)
+}
+
+/// tcpdump letters (`S.`, `FP.`) or tshark hex (`0x0012`)
+fn parse_tcp_flags(flags: &str) -> u8 {
+    if let Some(hex) = flags.strip_prefix("0x") {
+        return u16::from_str_radix(hex, 16).unwrap_or(0) as u8;
+    }
+    flags.chars().fold(0, |acc, c| {
+        acc | match c {
+            'F' => tcp_flags::FIN,
+            'S' => tcp_flags::SYN,
+            'R' => tcp_flags::RST,
+            'P' => tcp_flags::PSH,
+            '.' => tcp_flags::ACK,
+            'U' => tcp_flags::URG,
+            'E' => tcp_flags::ECE,
+            'W' => tcp_flags::CWR,
+            _ => 0,
+        }
+    })
+}
+
+/// First number after any of `prefixes` in `info`
+fn info_number(info: &str, prefixes: &[&str]) -> Option<u32> {
+    prefixes.iter().find_map(|prefix| {
+        let rest = &info[info.find(prefix)? + prefix.len()..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        rest[..end].parse().ok()
+    })
+}
+
+/// Payload length from tcpdump's `seq A:B` or tshark's `Len=N`
+fn info_seq_len(info: &str) -> Option<u32> {
+    if let Some(len) = info_number(info, &["Len="]) {
+        return Some(len);
+    }
+    let start = info_number(info, &["seq "])?;
+    let rest = &info[info.find("seq ")? + 4..];
+    let end = rest.split([',', ' ']).next()?.split_once(':')?.1;
+    Some(end.parse::<u32>().ok()?.wrapping_sub(start))
+}
+
+/// DNS fields from a tcpdump/native summary (`4660+ A? example.com. (29)`,
+/// `4660 NXDomain 0/1/0 (98)`) or a tshark `Standard query` info column
+fn parse_dns_info(info: &str) -> Option<DnsObservation> {
+    if let Some(rest) = info
+        .find("Standard query")
+        .map(|i| &info[i + "Standard query".len()..])
+    {
+        let (response, rest) = match rest.trim_start().strip_prefix("response") {
+            Some(r) => (true, r),
+            None => (false, rest),
+        };
+        // The rcode text sits between the id and the question
+        let (rcode, rest) = match [
+            ("Format error", 1),
+            ("Server failure", 2),
+            ("No such name", 3),
+            ("Refused", 5),
+        ]
+        .iter()
+        .find(|(text, _)| rest.contains(text))
+        {
+            Some(&(text, code)) => (code, rest.replacen(text, "", 1)),
+            None => (0, rest.to_string()),
+        };
+        let mut tokens = rest.split_whitespace();
+        let id = u16::from_str_radix(tokens.next()?.strip_prefix("0x")?, 16).ok()?;
+        return Some(DnsObservation {
+            id,
+            response,
+            rcode,
+            answers: 0,
+            qtype: tokens.next().map(String::from),
+            name: tokens.next().map(|s| s.trim_end_matches('.').to_string()),
+        });
+    }
+
+    let mut tokens = info.split_whitespace();
+    let first = tokens.next()?;
+    let digits = first
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(first.len());
+    let id = first[..digits].parse().ok()?;
+    let words: Vec<&str> = tokens.collect();
+    let counts = words.iter().find_map(|w| {
+        let mut parts = w.split('/');
+        let answers = parts.next()?.parse::<u16>().ok()?;
+        (parts.count() == 2).then_some(answers)
+    });
+    let question = words.iter().position(|w| w.ends_with('?') && w.len() > 1);
+    if counts.is_none() && question.is_none() {
+        return None;
+    }
+    let rcode = words
+        .iter()
+        .find_map(|w| match *w {
+            "FormErr" => Some(1),
+            "ServFail" => Some(2),
+            "NXDomain" => Some(3),
+            "NotImp" => Some(4),
+            "Refused" => Some(5),
+            _ => None,
+        })
+        .or_else(|| {
+            let i = words.iter().position(|w| *w == "rcode")?;
+            words.get(i + 1)?.parse().ok()
+        })
+        .unwrap_or(0);
+    Some(DnsObservation {
+        id,
+        response: counts.is_some(),
+        rcode,
+        answers: counts.unwrap_or(0),
+        qtype: question.map(|i| words[i].trim_end_matches('?').to_string()),
+        name: question
+            .and_then(|i| words.get(i + 1))
+            .map(|s| s.trim_end_matches('.').to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet_capture::decode::fixtures::tcp_v4_frame;
+    use crate::packet_capture::PcapngWriter;
+    use crate::test_util::FakeRoot;
+
+    /// Ethernet/IPv4/TCP frame with the given addresses, flags and payload
+    fn tcp_frame(
+        src: [u8; 4],
+        dst: [u8; 4],
+        ports: (u16, u16),
+        seq: u32,
+        flags: u8,
+        window: u16,
+        payload: usize,
+    ) -> Vec<u8> {
+        let mut f = tcp_v4_frame()[..54].to_vec();
+        f[16..18].copy_from_slice(&((40 + payload) as u16).to_be_bytes());
+        f[26..30].copy_from_slice(&src);
+        f[30..34].copy_from_slice(&dst);
+        f[34..36].copy_from_slice(&ports.0.to_be_bytes());
+        f[36..38].copy_from_slice(&ports.1.to_be_bytes());
+        f[38..42].copy_from_slice(&seq.to_be_bytes());
+        f[47] = flags;
+        f[48..50].copy_from_slice(&window.to_be_bytes());
+        f.resize(54 + payload, 0x61);
+        f
+    }
+
+    /// Ethernet/IPv4/UDP DNS message
+    fn dns_frame(src: [u8; 4], dst: [u8; 4], ports: (u16, u16), dns: &[u8]) -> Vec<u8> {
+        let mut f = tcp_v4_frame()[..34].to_vec();
+        f[16..18].copy_from_slice(&((28 + dns.len()) as u16).to_be_bytes());
+        f[23] = 17;
+        f[26..30].copy_from_slice(&src);
+        f[30..34].copy_from_slice(&dst);
+        f.extend_from_slice(&ports.0.to_be_bytes());
+        f.extend_from_slice(&ports.1.to_be_bytes());
+        f.extend_from_slice(&((8 + dns.len()) as u16).to_be_bytes());
+        f.extend_from_slice(&[0, 0]);
+        f.extend_from_slice(dns);
+        f
+    }
+
+    fn dns_message(id: u16, flags: u16, answers: u16) -> Vec<u8> {
+        let mut m = id.to_be_bytes().to_vec();
+        m.extend_from_slice(&flags.to_be_bytes());
+        m.extend_from_slice(&[0, 1]);
+        m.extend_from_slice(&answers.to_be_bytes());
+        m.extend_from_slice(&[0, 0, 0, 0]);
+        m.extend_from_slice(b"\x07example\x03com\x00\x00\x01\x00\x01");
+        m
+    }
+
+    const CLIENT: [u8; 4] = [10, 0, 0, 2];
+    const SERVER: [u8; 4] = [10, 0, 0, 1];
+    const RESOLVER: [u8; 4] = [10, 0, 0, 53];
+
+    /// Client downloads from a server with one retransmission, a zero window
+    /// and a reset, and resolves a name twice (one answered, one not)
+    fn session() -> Vec<(u64, Vec<u8>)> {
+        use tcp_flags::*;
+        let ms = |n: u64| 1_700_000_000_000_000_000 + n * 1_000_000;
+        let c2s =
+            |seq, flags, win, len| tcp_frame(CLIENT, SERVER, (40000, 443), seq, flags, win, len);
+        let s2c =
+            |seq, flags, win, len| tcp_frame(SERVER, CLIENT, (443, 40000), seq, flags, win, len);
+        vec![
+            (
+                ms(0),
+                dns_frame(CLIENT, RESOLVER, (5000, 53), &dns_message(7, 0x0100, 0)),
+            ),
+            (
+                ms(12),
+                dns_frame(RESOLVER, CLIENT, (53, 5000), &dns_message(7, 0x8180, 1)),
+            ),
+            (
+                ms(13),
+                dns_frame(CLIENT, RESOLVER, (5001, 53), &dns_message(8, 0x0100, 0)),
+            ),
+            (ms(20), c2s(100, SYN, 64240, 0)),
+            (ms(30), s2c(500, SYN | ACK, 65535, 0)),
+            (ms(31), c2s(101, ACK, 502, 0)),
+            (ms(40), s2c(501, ACK | PSH, 65535, 1000)),
+            (ms(41), s2c(1501, ACK | PSH, 65535, 1000)),
+            (ms(60), s2c(501, ACK | PSH, 65535, 1000)), // retransmission
+            (ms(61), c2s(101, ACK, 0, 0)),              // zero window
+            (ms(70), c2s(101, RST | ACK, 0, 0)),
+        ]
+    }
+
+    fn analyze(frames: &[(u64, Vec<u8>)]) -> CaptureAnalysis {
+        let mut analyzer = CaptureAnalyzer::new(10);
+        for (ts, f) in frames {
+            analyzer.add_frame(LinkType::Ethernet, *ts, f, f.len() as u32);
+        }
+        analyzer.finish()
+    }
+
+    #[test]
+    fn test_flows_talkers_and_protocols() {
+        let a = analyze(&session());
+        assert_eq!(a.packets, 11);
+        assert!((a.duration_secs - 0.070).abs() < 1e-6);
+        assert_eq!(a.total_flows, 3);
+
+        let tcp = &a.flows[0];
+        assert_eq!(
+            (tcp.protocol.as_str(), tcp.src.as_str()),
+            ("TCP", "10.0.0.2")
+        );
+        assert_eq!((tcp.src_port, tcp.dst_port), (Some(40000), Some(443)));
+        assert_eq!((tcp.packets_out, tcp.packets_in), (4, 4));
+        assert_eq!(tcp.bytes_in, 54 * 4 + 3000);
+        assert!((tcp.start_secs - 0.020).abs() < 1e-6);
+        assert!((tcp.duration_secs - 0.050).abs() < 1e-6);
+        assert!(tcp.bits_per_sec.is_some());
+        assert_eq!(
+            (tcp.retransmissions, tcp.zero_windows, tcp.resets),
+            (1, 1, 1)
+        );
+
+        // Too short a span for a meaningful rate
+        let frames = session();
+        let burst = [(0, frames[3].1.clone()), (500_000, frames[4].1.clone())];
+        assert_eq!(analyze(&burst).flows[0].bits_per_sec, None);
+
+        assert_eq!(a.top_talkers[0].address, "10.0.0.2");
+        assert_eq!(a.top_talkers[1].address, "10.0.0.1");
+        assert!(a.top_talkers[1].share_percent > 80.0);
+        assert_eq!(a.protocols[0].protocol, "TCP");
+        assert_eq!(
+            (a.protocols[1].protocol.as_str(), a.protocols[1].packets),
+            ("DNS", 3)
+        );
+    }
+
+    #[test]
+    fn test_tcp_health() {
+        let a = analyze(&session());
+        assert_eq!(a.tcp.segments, 8);
+        assert_eq!(a.tcp.syns, 1);
+        assert_eq!(a.tcp.retransmissions, 1);
+        assert_eq!(a.tcp.zero_windows, 1);
+        assert_eq!(a.tcp.resets, 1);
+        assert!((a.tcp.retransmission_percent - 12.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_retransmission_tracking() {
+        let seg = |seq, len, flags| Segment {
+            flags,
+            seq: Some(seq),
+            len,
+            window: None,
+            retransmission: false,
+        };
+        let mut next = None;
+        assert!(!is_retransmission(seg(u32::MAX - 9, 10, 0), &mut next));
+        // Sequence numbers wrap
+        assert!(!is_retransmission(seg(0, 10, 0), &mut next));
+        assert!(is_retransmission(seg(u32::MAX - 9, 10, 0), &mut next));
+        // Partial overlap that extends the stream is new data
+        assert!(!is_retransmission(seg(5, 10, 0), &mut next));
+        // Keep-alive probe and pure ACKs are not retransmissions
+        assert!(!is_retransmission(seg(14, 1, 0), &mut next));
+        assert!(!is_retransmission(seg(15, 0, 0), &mut next));
+        // A repeated FIN is
+        assert!(!is_retransmission(seg(15, 0, tcp_flags::FIN), &mut next));
+        assert!(is_retransmission(seg(15, 0, tcp_flags::FIN), &mut next));
+    }
+
+    #[test]
+    fn test_dns_pairing() {
+        let a = analyze(&session());
+        assert_eq!(
+            (a.dns.queries, a.dns.responses, a.dns.unanswered),
+            (2, 1, 1)
+        );
+        assert_eq!(a.dns.top_names, [("example.com".to_string(), 2)]);
+        // Unanswered first
+        let [pending, answered] = &a.dns.transactions[..] else {
+            panic!("{:?}", a.dns.transactions);
+        };
+        assert_eq!((pending.id, pending.rcode_name()), (8, "unanswered"));
+        assert_eq!(
+            (answered.id, answered.rcode, answered.answers),
+            (7, Some(0), 1)
+        );
+        assert_eq!(answered.qtype.as_deref(), Some("A"));
+        assert!((answered.latency_ms.unwrap() - 12.0).abs() < 1e-3);
+        assert_eq!(
+            (answered.client.as_str(), answered.server.as_str()),
+            ("10.0.0.2", "10.0.0.53")
+        );
+    }
+
+    #[test]
+    fn test_analyze_file() {
+        let mut w = PcapngWriter::new(Vec::new()).unwrap();
+        w.add_interface("eth0", LinkType::Ethernet, 65535).unwrap();
+        let session = session();
+        for (ts, f) in &session {
+            w.write_packet(0, *ts, f, f.len() as u32).unwrap();
+        }
+        let fs = FakeRoot::new("analysis");
+        let path = fs.join("session.pcapng");
+        std::fs::write(&path, w.into_inner()).unwrap();
+
+        let analysis = analyze_file(&path, 10).unwrap();
+        let expected = CaptureAnalysis {
+            source: path.display().to_string(),
+            ..analyze(&session)
+        };
+        assert_eq!(analysis, expected);
+        assert!(analysis.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_native_capture_result() {
+        // The packet list a native capture of the same session produces
+        let packets: Vec<CapturedPacket> = session()
+            .iter()
+            .enumerate()
+            .map(|(i, (ts, f))| {
+                let mut p = decode::to_captured_packet(
+                    i as u32 + 1,
+                    LinkType::Ethernet,
+                    *ts,
+                    f,
+                    f.len() as u32,
+                    false,
+                );
+                // Fixed zone: to_captured_packet formats local time
+                p.timestamp = format!("2023-11-14 22:13:20.{:06}", (ts % 1_000_000_000) / 1000);
+                p
+            })
+            .collect();
+        let result = CaptureResult {
+            interface: "eth0".into(),
+            filter: String::new(),
+            total_packets: packets.len() as u32,
+            packets,
+            duration_secs: 1.0,
+            packets_per_sec: 11.0,
+            total_bytes: 0,
+            protocol_stats: HashMap::new(),
+            top_sources: Vec::new(),
+            top_destinations: Vec::new(),
+            dropped_packets: 0,
+        };
+        let a = analyze_capture_result(&result, 10);
+        let from_frames = analyze(&session());
+        assert_eq!(a.source, "eth0");
+        assert_eq!(
+            (a.packets, a.bytes),
+            (from_frames.packets, from_frames.bytes)
+        );
+        // Live rates cover the whole capture window
+        assert_eq!(a.duration_secs, 1.0);
+        assert_eq!(a.bits_per_sec, Some(a.bytes as f64 * 8.0));
+        // Timestamps take different float paths; compare the counters
+        let counters = |flows: &[FlowSummary]| {
+            flows
+                .iter()
+                .map(|f| {
+                    (
+                        f.src.clone(),
+                        f.bytes_out,
+                        f.bytes_in,
+                        f.retransmissions,
+                        f.zero_windows,
+                        f.resets,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(counters(&a.flows), counters(&from_frames.flows));
+        assert_eq!(a.tcp, from_frames.tcp);
+        assert_eq!(
+            (a.dns.queries, a.dns.responses, a.dns.unanswered),
+            (2, 1, 1)
+        );
+        assert!(a.warnings.is_empty(), "{:?}", a.warnings);
+    }
+
+    #[test]
+    fn test_tcpdump_and_tshark_lines() {
+        let packet =
+            |protocol: &str, source: &str, destination: &str, info: &str, flags: Option<&str>| {
+                CapturedPacket {
+                    number: 1,
+                    timestamp: "2024-01-14 12:00:00.500000".into(),
+                    source: source.into(),
+                    destination: destination.into(),
+                    protocol: protocol.into(),
+                    length: 100,
+                    info: info.into(),
+                    src_port: None,
+                    dst_port: None,
+                    tcp_flags: flags.map(String::from),
+                    data_preview: None,
+                }
+            };
+
+        let obs = observe_captured(&packet(
+            "IP",
+            "192.168.1.1.443",
+            "192.168.1.2.54321",
+            "Flags [P.], seq 1:1449, ack 1, win 0, length 1448",
+            Some("P."),
+        ));
+        assert_eq!((obs.src.as_str(), obs.src_port), ("192.168.1.1", Some(443)));
+        assert_eq!(
+            (obs.protocol.as_str(), obs.flow_protocol.as_str()),
+            ("TCP", "TCP")
+        );
+        let seg = obs.tcp.unwrap();
+        assert_eq!((seg.seq, seg.len, seg.window), (Some(1), 1448, Some(0)));
+        assert_eq!(seg.flags, tcp_flags::PSH | tcp_flags::ACK);
+        assert!((obs.timestamp.unwrap() - 1_705_233_600.5).abs() < 1e-6);
+
+        let obs = observe_captured(&packet(
+            "IP6",
+            "2001:db8::1.5353",
+            "2001:db8::53.53",
+            "4660+ AAAA? example.com. (29)",
+            None,
+        ));
+        assert_eq!((obs.dst.as_str(), obs.dst_port), ("2001:db8::53", Some(53)));
+        assert_eq!(
+            (obs.protocol.as_str(), obs.flow_protocol.as_str()),
+            ("DNS", "UDP")
+        );
+        let dns = obs.dns.unwrap();
+        assert_eq!(
+            (dns.id, dns.response, dns.qtype.as_deref()),
+            (4660, false, Some("AAAA"))
+        );
+        assert_eq!(dns.name.as_deref(), Some("example.com"));
+
+        let dns = parse_dns_info("4660 NXDomain 0/1/0 (98)").unwrap();
+        assert_eq!((dns.id, dns.response, dns.rcode), (4660, true, 3));
+        let dns = parse_dns_info("4660 1/0/0 A 93.184.216.34 (45)").unwrap();
+        assert_eq!((dns.rcode, dns.answers), (0, 1));
+        assert!(parse_dns_info("Flags [S], seq 1, win 64240, length 0").is_none());
+
+        let mut tshark = packet(
+            "TCP",
+            "10.0.0.1",
+            "10.0.0.2",
+            "[TCP Retransmission] 443 \u{2192} 51234 [PSH, ACK] Seq=1 Ack=1 Win=512 Len=5",
+            Some("0x0018"),
+        );
+        tshark.timestamp = "1.250000".into();
+        let obs = observe_captured(&tshark);
+        let seg = obs.tcp.unwrap();
+        assert!(seg.retransmission);
+        assert_eq!((seg.flags, seg.len, seg.window), (0x18, 5, Some(512)));
+        assert_eq!(obs.timestamp, Some(1.25));
+
+        let dns = parse_dns_info(
+            "Standard query response 0x1a2b No such name A nx.example.com SOA a.iana-servers.net",
+        )
+        .unwrap();
+        assert_eq!((dns.id, dns.response, dns.rcode), (0x1a2b, true, 3));
+        assert_eq!(
+            (dns.qtype.as_deref(), dns.name.as_deref()),
+            (Some("A"), Some("nx.example.com"))
+        );
+        let dns = parse_dns_info("Standard query 0x1a2b AAAA example.com").unwrap();
+        assert_eq!((dns.response, dns.qtype.as_deref()), (false, Some("AAAA")));
+    }
+
+    #[test]
+    fn test_unsupported_link_and_empty_capture() {
+        let mut analyzer = CaptureAnalyzer::new(5);
+        analyzer.add_file_frame(&FileFrame {
+            interface: 0,
+            link_code: 127,
+            timestamp_ns: 0,
+            data: vec![0; 10],
+            orig_len: 10,
+        });
+        let a = analyzer.finish();
+        assert_eq!((a.packets, a.bits_per_sec), (0, None));
+        assert_eq!(
+            a.warnings,
+            ["1 frames with unsupported link type 127 skipped"]
+        );
+    }
+}
@@ -0,0 +1,699 @@
+//! Classic BPF filter compiler and interpreter
+//!
+//! Compiles the tcpdump filter subset used by [`crate::network_tools`] into a
+//! classic BPF program the kernel can run on an `AF_PACKET` socket:
+//!
+//! - protocols: `ip`, `ip6`, `arp`, `tcp`, `udp`, `icmp`, `icmp6`
+//! - `[src|dst] host <addr>` (IPv4/IPv6 literal or resolvable name)
+//! - `[tcp|udp] [src|dst] port <n>`
+//! - `and`/`&&`, `or`/`||`, `not`/`!` and parentheses
+//!
+//! Offsets assume either an Ethernet header or a bare IP packet, depending on
+//! the interface's [`LinkType`]. IPv6 extension headers are not walked, as
+//! with libpcap's simple cases. The same program can be evaluated in user
+//! space with [`BpfProgram::run`], which offline analysis and tests rely on.
+
+use super::LinkType;
+use crate::error::{Result, SimonError};
+use std::net::{IpAddr, ToSocketAddrs};
+
+// Instruction classes, sizes, modes and operations (<linux/bpf_common.h>)
+const BPF_LD: u16 = 0x00;
+const BPF_LDX: u16 = 0x01;
+const BPF_ALU: u16 = 0x04;
+const BPF_JMP: u16 = 0x05;
+const BPF_RET: u16 = 0x06;
+const BPF_W: u16 = 0x00;
+const BPF_H: u16 = 0x08;
+const BPF_B: u16 = 0x10;
+const BPF_ABS: u16 = 0x20;
+const BPF_IND: u16 = 0x40;
+const BPF_MSH: u16 = 0xa0;
+const BPF_K: u16 = 0x00;
+const BPF_AND: u16 = 0x50;
+const BPF_JA: u16 = 0x00;
+const BPF_JEQ: u16 = 0x10;
+const BPF_JSET: u16 = 0x40;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_ARP: u16 = 0x0806;
+const IPPROTO_ICMP: u8 = 1;
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+const IPPROTO_ICMPV6: u8 = 58;
+
+/// One classic BPF instruction (`struct sock_filter`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BpfInsn {
+    pub code: u16,
+    pub jt: u8,
+    pub jf: u8,
+    pub k: u32,
+}
+
+impl BpfInsn {
+    fn new(code: u16, k: u32) -> Self {
+        Self {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+}
+
+/// A compiled classic BPF program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BpfProgram {
+    insns: Vec<BpfInsn>,
+}
+
+impl BpfProgram {
+    /// Compile a filter expression for `link`; an empty expression accepts
+    /// every packet. Accepted packets are truncated to `snaplen` bytes.
+    pub fn compile(expr: &str, link: LinkType, snaplen: u32) -> Result<Self> {
+        let tokens = tokenize(expr);
+        if tokens.is_empty() {
+            return Ok(Self::accept_all(snaplen));
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            link,
+        };
+        let tree = parser.expr()?;
+        if let Some(extra) = parser.tokens.get(parser.pos) {
+            return Err(SimonError::Parse(format!(
+                "unexpected '{}' in filter",
+                extra
+            )));
+        }
+
+        let mut gen = CodeGen::default();
+        let (accept, reject) = (gen.label(), gen.label());
+        gen.node(&tree, accept, reject);
+        gen.place(accept);
+        gen.emit(BpfInsn::new(BPF_RET | BPF_K, snaplen), None, None);
+        gen.place(reject);
+        gen.emit(BpfInsn::new(BPF_RET | BPF_K, 0), None, None);
+        gen.finish().map(|insns| Self { insns })
+    }
+
+    /// Program that accepts every packet, truncated to `snaplen`
+    pub fn accept_all(snaplen: u32) -> Self {
+        Self {
+            insns: vec![BpfInsn::new(BPF_RET | BPF_K, snaplen)],
+        }
+    }
+
+    pub fn instructions(&self) -> &[BpfInsn] {
+        &self.insns
+    }
+
+    /// Run the program on a packet as the kernel would; returns the number
+    /// of bytes to keep (0 = drop)
+    pub fn run(&self, packet: &[u8]) -> u32 {
+        let (mut a, mut x) = (0u32, 0u32);
+        let mut pc = 0;
+        let load = |off: u32, size: usize| -> Option<u32> {
+            let off = off as usize;
+            let bytes = packet.get(off..off.checked_add(size)?)?;
+            Some(bytes.iter().fold(0u32, |v, &b| (v << 8) | b as u32))
+        };
+        while let Some(insn) = self.insns.get(pc) {
+            pc += 1;
+            let size = match insn.code & 0x18 {
+                BPF_H => 2,
+                BPF_B => 1,
+                _ => 4,
+            };
+            match insn.code & 0x07 {
+                BPF_LD => {
+                    let off = match insn.code & 0xe0 {
+                        BPF_IND => x.wrapping_add(insn.k),
+                        _ => insn.k,
+                    };
+                    match load(off, size) {
+                        Some(v) => a = v,
+                        None => return 0,
+                    }
+                }
+                BPF_LDX => match load(insn.k, 1) {
+                    Some(v) => x = (v & 0xf) * 4,
+                    None => return 0,
+                },
+                BPF_ALU => a &= insn.k,
+                BPF_JMP => {
+                    let taken = match insn.code & 0xf0 {
+                        BPF_JA => {
+                            pc += insn.k as usize;
+                            continue;
+                        }
+                        BPF_JEQ => a == insn.k,
+                        BPF_JSET => a & insn.k != 0,
+                        _ => return 0,
+                    };
+                    pc += if taken { insn.jt } else { insn.jf } as usize;
+                }
+                BPF_RET => return insn.k,
+                _ => return 0,
+            }
+        }
+        0
+    }
+
+    /// Whether the program accepts the packet
+    pub fn matches(&self, packet: &[u8]) -> bool {
+        self.run(packet) > 0
+    }
+}
+
+/// Direction qualifier of `host`/`port`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dir {
+    Src,
+    Dst,
+    Any,
+}
+
+/// Filter tree over single-comparison tests
+#[derive(Debug, Clone)]
+enum Node {
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Const(bool),
+    /// Load `size` bytes at `off`, mask, compare with `value`
+    Eq {
+        off: u32,
+        size: u16,
+        mask: Option<u32>,
+        value: u32,
+    },
+    /// IPv4 packet is not a non-first fragment
+    FirstFragment {
+        l3: u32,
+    },
+    /// 16-bit field at `off` past the variable-length IPv4 header
+    Ipv4Payload {
+        l3: u32,
+        off: u32,
+        value: u32,
+    },
+}
+
+impl Node {
+    fn and(a: Node, b: Node) -> Node {
+        Node::And(Box::new(a), Box::new(b))
+    }
+
+    fn or(a: Node, b: Node) -> Node {
+        Node::Or(Box::new(a), Box::new(b))
+    }
+
+    fn any(nodes: Vec<Node>) -> Node {
+        nodes
+            .into_iter()
+            .reduce(Node::or)
+            .unwrap_or(Node::Const(false))
+    }
+
+    fn all(nodes: Vec<Node>) -> Node {
+        nodes
+            .into_iter()
+            .reduce(Node::and)
+            .unwrap_or(Node::Const(true))
+    }
+}
+
+fn tokenize(expr: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut chars = expr.chars().peekable();
+    while let Some(c) = chars.next() {
+        let op = match c {
+            '(' | ')' | '!' => Some(c.to_string()),
+            '&' | '|' if chars.peek() == Some(&c) => {
+                chars.next();
+                Some(format!("{c}{c}"))
+            }
+            _ => None,
+        };
+        if op.is_some() || c.is_whitespace() {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            tokens.extend(op);
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+    link: LinkType,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, alternatives: &[&str]) -> bool {
+        let hit = self.peek().is_some_and(|t| alternatives.contains(&t));
+        if hit {
+            self.pos += 1;
+        }
+        hit
+    }
+
+    fn expr(&mut self) -> Result<Node> {
+        let mut node = self.term()?;
+        while self.eat(&["or", "||"]) {
+            node = Node::or(node, self.term()?);
+        }
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Node> {
+        let mut node = self.factor()?;
+        while self.eat(&["and", "&&"]) {
+            node = Node::and(node, self.factor()?);
+        }
+        Ok(node)
+    }
+
+    fn factor(&mut self) -> Result<Node> {
+        if self.eat(&["not", "!"]) {
+            return Ok(Node::Not(Box::new(self.factor()?)));
+        }
+        if self.eat(&["("]) {
+            let node = self.expr()?;
+            if !self.eat(&[")"]) {
+                return Err(SimonError::Parse("missing ')' in filter".into()));
+            }
+            return Ok(node);
+        }
+        self.primitive()
+    }
+
+    fn primitive(&mut self) -> Result<Node> {
+        let proto = match self.peek() {
+            Some(p @ ("ip" | "ip6" | "arp" | "tcp" | "udp" | "icmp" | "icmp6")) => {
+                let p = p.to_string();
+                self.pos += 1;
+                Some(p)
+            }
+            _ => None,
+        };
+        let dir = if self.eat(&["src"]) {
+            Dir::Src
+        } else if self.eat(&["dst"]) {
+            Dir::Dst
+        } else {
+            Dir::Any
+        };
+
+        let qualified = if self.eat(&["port"]) {
+            let value = self.value("port")?;
+            let port = value
+                .parse::<u16>()
+                .map_err(|_| SimonError::Parse(format!("invalid port '{}'", value)))?;
+            let transports = match proto.as_deref() {
+                None => vec![IPPROTO_TCP, IPPROTO_UDP],
+                Some("tcp") => vec![IPPROTO_TCP],
+                Some("udp") => vec![IPPROTO_UDP],
+                Some(other) => {
+                    return Err(SimonError::Parse(format!(
+                        "'port' cannot qualify '{}'",
+                        other
+                    )))
+                }
+            };
+            return Ok(self.port(dir, port, &transports));
+        } else if self.eat(&["host"]) || (dir != Dir::Any && self.peek().is_some()) {
+            // `src 10.0.0.1` implies host
+            let value = self.value("host")?;
+            Some(self.host(dir, &value)?)
+        } else {
+            None
+        };
+
+        let proto = proto.map(|p| self.protocol(&p));
+        match (proto, qualified) {
+            (Some(p), Some(q)) => Ok(Node::and(p, q)),
+            (Some(p), None) => Ok(p),
+            (None, Some(q)) => Ok(q),
+            (None, None) => Err(match self.peek() {
+                Some(t) => SimonError::Parse(format!("unknown filter primitive '{}'", t)),
+                None => SimonError::Parse("incomplete filter expression".into()),
+            }),
+        }
+    }
+
+    fn value(&mut self, keyword: &str) -> Result<String> {
+        match self.next() {
+            Some(v) if !matches!(v.as_str(), "(" | ")" | "!" | "&&" | "||") => Ok(v),
+            _ => Err(SimonError::Parse(format!("'{}' needs a value", keyword))),
+        }
+    }
+
+    /// Offset of the network header
+    fn l3(&self) -> u32 {
+        match self.link {
+            LinkType::Ethernet => 14,
+            LinkType::Raw => 0,
+        }
+    }
+
+    fn ethertype(&self, ethertype: u16) -> Node {
+        match self.link {
+            LinkType::Ethernet => Node::Eq {
+                off: 12,
+                size: 2,
+                mask: None,
+                value: ethertype as u32,
+            },
+            LinkType::Raw => {
+                let version = match ethertype {
+                    ETHERTYPE_IPV4 => 4,
+                    ETHERTYPE_IPV6 => 6,
+                    _ => return Node::Const(false),
+                };
+                Node::Eq {
+                    off: 0,
+                    size: 1,
+                    mask: Some(0xf0),
+                    value: version << 4,
+                }
+            }
+        }
+    }
+
+    fn byte(&self, off: u32, value: u8) -> Node {
+        Node::Eq {
+            off: self.l3() + off,
+            size: 1,
+            mask: None,
+            value: value as u32,
+        }
+    }
+
+    fn ipv4_proto(&self, proto: u8) -> Node {
+        Node::and(self.ethertype(ETHERTYPE_IPV4), self.byte(9, proto))
+    }
+
+    fn ipv6_next(&self, next: u8) -> Node {
+        Node::and(self.ethertype(ETHERTYPE_IPV6), self.byte(6, next))
+    }
+
+    fn protocol(&self, name: &str) -> Node {
+        match name {
+            "ip" => self.ethertype(ETHERTYPE_IPV4),
+            "ip6" => self.ethertype(ETHERTYPE_IPV6),
+            "arp" => self.ethertype(ETHERTYPE_ARP),
+            "icmp" => self.ipv4_proto(IPPROTO_ICMP),
+            "icmp6" => self.ipv6_next(IPPROTO_ICMPV6),
+            "tcp" => Node::or(self.ipv4_proto(IPPROTO_TCP), self.ipv6_next(IPPROTO_TCP)),
+            _ => Node::or(self.ipv4_proto(IPPROTO_UDP), self.ipv6_next(IPPROTO_UDP)),
+        }
+    }
+
+    fn host(&self, dir: Dir, value: &str) -> Result<Node> {
+        let addrs: Vec<IpAddr> = match value.parse::<IpAddr>() {
+            Ok(ip) => vec![ip],
+            Err(_) => (value, 0)
+                .to_socket_addrs()
+                .map_err(|e| SimonError::Parse(format!("cannot resolve host '{}': {}", value, e)))?
+                .map(|a| a.ip())
+                .collect(),
+        };
+        Ok(Node::any(
+            addrs.iter().map(|ip| self.host_addr(dir, *ip)).collect(),
+        ))
+    }
+
+    fn host_addr(&self, dir: Dir, ip: IpAddr) -> Node {
+        let l3 = self.l3();
+        let (ethertype, words, src, dst) = match ip {
+            IpAddr::V4(v4) => (ETHERTYPE_IPV4, vec![u32::from(v4)], 12, 16),
+            IpAddr::V6(v6) => {
+                let o = v6.octets();
+                let words = o
+                    .chunks(4)
+                    .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+                (ETHERTYPE_IPV6, words, 8, 24)
+            }
+        };
+        let at = |base: u32| {
+            Node::all(
+                words
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &w)| Node::Eq {
+                        off: l3 + base + 4 * i as u32,
+                        size: 4,
+                        mask: None,
+                        value: w,
+                    })
+                    .collect(),
+            )
+        };
+        let addr = match dir {
+            Dir::Src => at(src),
+            Dir::Dst => at(dst),
+            Dir::Any => Node::or(at(src), at(dst)),
+        };
+        Node::and(self.ethertype(ethertype), addr)
+    }
+
+    fn port(&self, dir: Dir, port: u16, transports: &[u8]) -> Node {
+        let l3 = self.l3();
+        let fields: &[u32] = match dir {
+            Dir::Src => &[0],
+            Dir::Dst => &[2],
+            Dir::Any => &[0, 2],
+        };
+        let v4 = Node::all(vec![
+            self.ethertype(ETHERTYPE_IPV4),
+            Node::any(transports.iter().map(|&p| self.byte(9, p)).collect()),
+            Node::FirstFragment { l3 },
+            Node::any(
+                fields
+                    .iter()
+                    .map(|&off| Node::Ipv4Payload {
+                        l3,
+                        off,
+                        value: port as u32,
+                    })
+                    .collect(),
+            ),
+        ]);
+        let v6 = Node::all(vec![
+            self.ethertype(ETHERTYPE_IPV6),
+            Node::any(transports.iter().map(|&p| self.byte(6, p)).collect()),
+            Node::any(
+                fields
+                    .iter()
+                    .map(|&off| Node::Eq {
+                        off: l3 + 40 + off,
+                        size: 2,
+                        mask: None,
+                        value: port as u32,
+                    })
+                    .collect(),
+            ),
+        ]);
+        Node::or(v4, v6)
+    }
+}
+
+/// Emits instructions with symbolic jump targets, resolved in [`Self::finish`]
+#[derive(Default)]
+struct CodeGen {
+    insns: Vec<(BpfInsn, Option<usize>, Option<usize>)>,
+    labels: Vec<Option<usize>>,
+}
+
+impl CodeGen {
+    fn label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = Some(self.insns.len());
+    }
+
+    fn emit(&mut self, insn: BpfInsn, jt: Option<usize>, jf: Option<usize>) {
+        self.insns.push((insn, jt, jf));
+    }
+
+    fn jump(&mut self, code: u16, k: u32, yes: usize, no: usize) {
+        self.emit(BpfInsn::new(BPF_JMP | code | BPF_K, k), Some(yes), Some(no));
+    }
+
+    /// Branch to `yes` when `node` holds, `no` otherwise
+    fn node(&mut self, node: &Node, yes: usize, no: usize) {
+        match node {
+            Node::And(a, b) => {
+                let next = self.label();
+                self.node(a, next, no);
+                self.place(next);
+                self.node(b, yes, no);
+            }
+            Node::Or(a, b) => {
+                let next = self.label();
+                self.node(a, yes, next);
+                self.place(next);
+                self.node(b, yes, no);
+            }
+            Node::Not(a) => self.node(a, no, yes),
+            Node::Const(value) => {
+                let target = if *value { yes } else { no };
+                self.emit(BpfInsn::new(BPF_JMP | BPF_JA, 0), Some(target), None);
+            }
+            Node::Eq {
+                off,
+                size,
+                mask,
+                value,
+            } => {
+                let width = match size {
+                    1 => BPF_B,
+                    2 => BPF_H,
+                    _ => BPF_W,
+                };
+                self.emit(BpfInsn::new(BPF_LD | width | BPF_ABS, *off), None, None);
+                if let Some(mask) = mask {
+                    self.emit(BpfInsn::new(BPF_ALU | BPF_AND | BPF_K, *mask), None, None);
+                }
+                self.jump(BPF_JEQ, *value, yes, no);
+            }
+            Node::FirstFragment { l3 } => {
+                self.emit(BpfInsn::new(BPF_LD | BPF_H | BPF_ABS, l3 + 6), None, None);
+                self.jump(BPF_JSET, 0x1fff, no, yes);
+            }
+            Node::Ipv4Payload { l3, off, value } => {
+                self.emit(BpfInsn::new(BPF_LDX | BPF_B | BPF_MSH, *l3), None, None);
+                self.emit(BpfInsn::new(BPF_LD | BPF_H | BPF_IND, l3 + off), None, None);
+                self.jump(BPF_JEQ, *value, yes, no);
+            }
+        }
+    }
+
+    fn finish(self) -> Result<Vec<BpfInsn>> {
+        let labels = self.labels;
+        let offset = |pc: usize, label: usize| -> usize {
+            labels[label].expect("every label is placed") - (pc + 1)
+        };
+        let mut out = Vec::with_capacity(self.insns.len());
+        for (pc, (mut insn, jt, jf)) in self.insns.into_iter().enumerate() {
+            if insn.code == BPF_JMP | BPF_JA {
+                insn.k = jt.map_or(0, |l| offset(pc, l)) as u32;
+            } else {
+                for (slot, label) in [(&mut insn.jt, jt), (&mut insn.jf, jf)] {
+                    if let Some(label) = label {
+                        *slot = u8::try_from(offset(pc, label)).map_err(|_| {
+                            SimonError::Parse("filter expression is too long".into())
+                        })?;
+                    }
+                }
+            }
+            out.push(insn);
+        }
+        if out.len() > 4096 {
+            return Err(SimonError::Parse("filter expression is too long".into()));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet_capture::decode::fixtures::{tcp_v4_frame, udp_v6_frame};
+
+    fn compile(expr: &str) -> BpfProgram {
+        BpfProgram::compile(expr, LinkType::Ethernet, 65535).unwrap()
+    }
+
+    #[test]
+    fn test_protocol_and_port_filters() {
+        let tcp = tcp_v4_frame();
+        let udp = udp_v6_frame();
+        assert!(compile("").matches(&tcp));
+        assert!(compile("tcp").matches(&tcp) && !compile("tcp").matches(&udp));
+        assert!(compile("udp and ip6").matches(&udp));
+        assert!(compile("tcp port 443").matches(&tcp));
+        assert!(compile("dst port 443").matches(&tcp));
+        assert!(!compile("src port 443").matches(&tcp));
+        assert!(compile("port 53").matches(&udp));
+        assert!(!compile("tcp port 53").matches(&udp));
+        assert!(compile("not arp && (icmp || port 53)").matches(&udp));
+        assert!(!compile("!(tcp or udp)").matches(&tcp));
+        assert_eq!(compile("tcp").run(&tcp), 65535);
+    }
+
+    #[test]
+    fn test_host_filters() {
+        let tcp = tcp_v4_frame();
+        let udp = udp_v6_frame();
+        assert!(compile("host 192.168.1.10").matches(&tcp));
+        assert!(compile("src host 192.168.1.10").matches(&tcp));
+        assert!(!compile("dst 192.168.1.10").matches(&tcp));
+        assert!(compile("host 2001:db8::53 and udp").matches(&udp));
+        assert!(!compile("host 2001:db8::54").matches(&udp));
+        assert!(compile("tcp and host 192.168.1.10 and port 443").matches(&tcp));
+    }
+
+    #[test]
+    fn test_raw_ip_link() {
+        let frame = tcp_v4_frame();
+        let prog = BpfProgram::compile("tcp port 443", LinkType::Raw, 128).unwrap();
+        assert_eq!(prog.run(&frame[14..]), 128);
+        assert!(!BpfProgram::compile("arp", LinkType::Raw, 128)
+            .unwrap()
+            .matches(&frame[14..]));
+    }
+
+    #[test]
+    fn test_fragment_not_matched_by_port() {
+        let mut frame = tcp_v4_frame();
+        frame[14 + 6] = 0x00;
+        frame[14 + 7] = 0xb9; // fragment offset 185
+        assert!(compile("tcp").matches(&frame));
+        assert!(!compile("port 443").matches(&frame));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in ["port", "port http", "icmp port 1", "(tcp", "tcp )", "bogus"] {
+            assert!(
+                BpfProgram::compile(bad, LinkType::Ethernet, 100).is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_truncated_packet_rejected() {
+        let frame = tcp_v4_frame();
+        assert!(!compile("port 443").matches(&frame[..30]));
+    }
+}
@@ -0,0 +1,841 @@
+//! Protocol decoders: Ethernet, ARP, IPv4, IPv6, TCP, UDP, ICMP/ICMPv6, DNS
+//!
+//! Decoding never fails: each layer is filled in as far as the captured
+//! bytes allow, so truncated (snaplen) or malformed frames still yield the
+//! outer headers.
+
+use super::LinkType;
+use crate::network_tools::CapturedPacket;
+use std::fmt::Write as _;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_ARP: u16 = 0x0806;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+
+/// TCP flag bits
+pub mod tcp_flags {
+    pub const FIN: u8 = 0x01;
+    pub const SYN: u8 = 0x02;
+    pub const RST: u8 = 0x04;
+    pub const PSH: u8 = 0x08;
+    pub const ACK: u8 = 0x10;
+    pub const URG: u8 = 0x20;
+    pub const ECE: u8 = 0x40;
+    pub const CWR: u8 = 0x80;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthernetHeader {
+    pub dst: [u8; 6],
+    pub src: [u8; 6],
+    /// EtherType after any 802.1Q tag
+    pub ethertype: u16,
+    pub vlan: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkLayer {
+    Ipv4 {
+        src: Ipv4Addr,
+        dst: Ipv4Addr,
+        ttl: u8,
+        protocol: u8,
+    },
+    Ipv6 {
+        src: Ipv6Addr,
+        dst: Ipv6Addr,
+        hop_limit: u8,
+        /// Upper-layer protocol after extension headers
+        next_header: u8,
+    },
+    Arp {
+        /// 1 = request, 2 = reply
+        operation: u16,
+        sender_mac: [u8; 6],
+        sender_ip: Ipv4Addr,
+        target_ip: Ipv4Addr,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportLayer {
+    Tcp {
+        src_port: u16,
+        dst_port: u16,
+        seq: u32,
+        ack: u32,
+        /// See [`tcp_flags`]
+        flags: u8,
+        window: u16,
+    },
+    Udp {
+        src_port: u16,
+        dst_port: u16,
+    },
+    Icmp {
+        icmp_type: u8,
+        code: u8,
+        /// Echo identifier and sequence number
+        echo: Option<(u16, u16)>,
+    },
+    Icmpv6 {
+        icmp_type: u8,
+        code: u8,
+        echo: Option<(u16, u16)>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsQuestion {
+    /// Dotted name without the trailing root dot
+    pub name: String,
+    pub qtype: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsMessage {
+    pub id: u16,
+    pub response: bool,
+    pub opcode: u8,
+    pub rcode: u8,
+    pub questions: u16,
+    pub answers: u16,
+    pub authority: u16,
+    pub additional: u16,
+    /// First question, if it could be decoded
+    pub question: Option<DnsQuestion>,
+}
+
+/// A decoded frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet<'a> {
+    pub ethernet: Option<EthernetHeader>,
+    pub network: Option<NetworkLayer>,
+    pub transport: Option<TransportLayer>,
+    pub dns: Option<DnsMessage>,
+    /// Transport payload (or the undecoded remainder)
+    pub payload: &'a [u8],
+}
+
+fn be16(buf: &[u8], off: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(buf.get(off..off + 2)?.try_into().ok()?))
+}
+
+fn be32(buf: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(buf.get(off..off + 4)?.try_into().ok()?))
+}
+
+fn mac_at(buf: &[u8], off: usize) -> Option<[u8; 6]> {
+    buf.get(off..off + 6)?.try_into().ok()
+}
+
+/// Format a MAC address as `aa:bb:cc:dd:ee:ff`
+pub fn format_mac(mac: &[u8; 6]) -> String {
+    mac.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Decode a frame captured on a link of type `link`
+pub fn decode(link: LinkType, frame: &[u8]) -> Packet<'_> {
+    let mut packet = Packet {
+        ethernet: None,
+        network: None,
+        transport: None,
+        dns: None,
+        payload: frame,
+    };
+
+    let (ethertype, l3) = match link {
+        LinkType::Ethernet => {
+            let (Some(dst), Some(src), Some(mut ethertype)) =
+                (mac_at(frame, 0), mac_at(frame, 6), be16(frame, 12))
+            else {
+                return packet;
+            };
+            let mut off = 14;
+            let mut vlan = None;
+            if ethertype == ETHERTYPE_VLAN {
+                let (Some(tci), Some(inner)) = (be16(frame, 14), be16(frame, 16)) else {
+                    return packet;
+                };
+                vlan = Some(tci & 0x0fff);
+                ethertype = inner;
+                off = 18;
+            }
+            packet.ethernet = Some(EthernetHeader {
+                dst,
+                src,
+                ethertype,
+                vlan,
+            });
+            (ethertype, &frame[off..])
+        }
+        LinkType::Raw => match frame.first().map(|b| b >> 4) {
+            Some(4) => (ETHERTYPE_IPV4, frame),
+            Some(6) => (ETHERTYPE_IPV6, frame),
+            _ => return packet,
+        },
+    };
+    packet.payload = l3;
+
+    let decoded = match ethertype {
+        ETHERTYPE_IPV4 => decode_ipv4(l3),
+        ETHERTYPE_IPV6 => decode_ipv6(l3),
+        ETHERTYPE_ARP => {
+            packet.network = decode_arp(l3);
+            return packet;
+        }
+        _ => None,
+    };
+    let Some((network, protocol, l4)) = decoded else {
+        return packet;
+    };
+    packet.network = Some(network);
+    // A non-first fragment carries no transport header
+    let Some(l4) = l4 else {
+        return packet;
+    };
+    packet.payload = l4;
+
+    let (transport, payload) = match protocol {
+        6 => {
+            let Some(offset) = l4.get(12).map(|b| ((b >> 4) as usize) * 4) else {
+                return packet;
+            };
+            let (Some(src_port), Some(dst_port), Some(seq), Some(ack), Some(window)) = (
+                be16(l4, 0),
+                be16(l4, 2),
+                be32(l4, 4),
+                be32(l4, 8),
+                be16(l4, 14),
+            ) else {
+                return packet;
+            };
+            let transport = TransportLayer::Tcp {
+                src_port,
+                dst_port,
+                seq,
+                ack,
+                flags: l4[13],
+                window,
+            };
+            (transport, l4.get(offset.max(20)..).unwrap_or(&[]))
+        }
+        17 => {
+            let (Some(src_port), Some(dst_port)) = (be16(l4, 0), be16(l4, 2)) else {
+                return packet;
+            };
+            let transport = TransportLayer::Udp { src_port, dst_port };
+            (transport, l4.get(8..).unwrap_or(&[]))
+        }
+        1 | 58 => {
+            let (Some(&icmp_type), Some(&code)) = (l4.first(), l4.get(1)) else {
+                return packet;
+            };
+            let is_echo = if protocol == 1 {
+                matches!(icmp_type, 0 | 8)
+            } else {
+                matches!(icmp_type, 128 | 129)
+            };
+            let echo = if is_echo {
+                be16(l4, 4).zip(be16(l4, 6))
+            } else {
+                None
+            };
+            let transport = if protocol == 1 {
+                TransportLayer::Icmp {
+                    icmp_type,
+                    code,
+                    echo,
+                }
+            } else {
+                TransportLayer::Icmpv6 {
+                    icmp_type,
+                    code,
+                    echo,
+                }
+            };
+            (transport, l4.get(8..).unwrap_or(&[]))
+        }
+        _ => return packet,
+    };
+    packet.transport = Some(transport);
+    packet.payload = payload;
+
+    packet.dns = match transport {
+        TransportLayer::Udp { src_port, dst_port } if is_dns_port(src_port, dst_port) => {
+            decode_dns(payload)
+        }
+        // DNS over TCP has a two-byte length prefix
+        TransportLayer::Tcp {
+            src_port, dst_port, ..
+        } if is_dns_port(src_port, dst_port) && payload.len() > 2 => decode_dns(&payload[2..]),
+        _ => None,
+    };
+    packet
+}
+
+fn is_dns_port(src: u16, dst: u16) -> bool {
+    [53, 5353].contains(&src) || [53, 5353].contains(&dst)
+}
+
+/// Header, upper-layer protocol and transport bytes (None for a non-first
+/// fragment)
+fn decode_ipv4(buf: &[u8]) -> Option<(NetworkLayer, u8, Option<&[u8]>)> {
+    let header_len = ((*buf.first()? & 0x0f) as usize) * 4;
+    let total_len = be16(buf, 2)? as usize;
+    let fragment_offset = be16(buf, 6)? & 0x1fff;
+    let protocol = *buf.get(9)?;
+    let network = NetworkLayer::Ipv4 {
+        ttl: *buf.get(8)?,
+        protocol,
+        src: Ipv4Addr::from(be32(buf, 12)?),
+        dst: Ipv4Addr::from(be32(buf, 16)?),
+    };
+    // Ethernet pads short frames; trust the IP length when it is sane
+    let end = if total_len >= header_len {
+        total_len.min(buf.len())
+    } else {
+        buf.len()
+    };
+    let l4 = (fragment_offset == 0).then(|| buf.get(header_len.max(20)..end).unwrap_or(&[]));
+    Some((network, protocol, l4))
+}
+
+fn decode_ipv6(buf: &[u8]) -> Option<(NetworkLayer, u8, Option<&[u8]>)> {
+    let payload_len = be16(buf, 4)? as usize;
+    let mut next_header = *buf.get(6)?;
+    let hop_limit = *buf.get(7)?;
+    let src: [u8; 16] = buf.get(8..24)?.try_into().ok()?;
+    let dst: [u8; 16] = buf.get(24..40)?.try_into().ok()?;
+    let end = (40 + payload_len).min(buf.len());
+    let mut off = 40;
+    let mut first_fragment = true;
+    // Walk hop-by-hop, routing, fragment and destination options headers
+    while matches!(next_header, 0 | 43 | 44 | 60) && off + 8 <= end {
+        let len = if next_header == 44 {
+            first_fragment = be16(buf, off + 2)? >> 3 == 0;
+            8
+        } else {
+            (buf[off + 1] as usize + 1) * 8
+        };
+        next_header = buf[off];
+        off += len;
+    }
+    let network = NetworkLayer::Ipv6 {
+        src: Ipv6Addr::from(src),
+        dst: Ipv6Addr::from(dst),
+        hop_limit,
+        next_header,
+    };
+    let l4 = first_fragment.then(|| buf.get(off..end).unwrap_or(&[]));
+    Some((network, next_header, l4))
+}
+
+fn decode_arp(buf: &[u8]) -> Option<NetworkLayer> {
+    // Ethernet/IPv4 ARP only: hardware type 1, protocol 0x0800, sizes 6/4
+    if be16(buf, 0)? != 1 || be16(buf, 2)? != ETHERTYPE_IPV4 || buf.get(4..6)? != [6, 4] {
+        return None;
+    }
+    Some(NetworkLayer::Arp {
+        operation: be16(buf, 6)?,
+        sender_mac: mac_at(buf, 8)?,
+        sender_ip: Ipv4Addr::from(be32(buf, 14)?),
+        target_ip: Ipv4Addr::from(be32(buf, 24)?),
+    })
+}
+
+/// Decode a DNS message header and its first question
+pub fn decode_dns(buf: &[u8]) -> Option<DnsMessage> {
+    let flags = be16(buf, 2)?;
+    let questions = be16(buf, 4)?;
+    let question = if questions > 0 {
+        dns_name(buf, 12).and_then(|(name, end)| {
+            Some(DnsQuestion {
+                name,
+                qtype: be16(buf, end)?,
+            })
+        })
+    } else {
+        None
+    };
+    Some(DnsMessage {
+        id: be16(buf, 0)?,
+        response: flags & 0x8000 != 0,
+        opcode: ((flags >> 11) & 0x0f) as u8,
+        rcode: (flags & 0x0f) as u8,
+        questions,
+        answers: be16(buf, 6)?,
+        authority: be16(buf, 8)?,
+        additional: be16(buf, 10)?,
+        question,
+    })
+}
+
+/// Read a possibly compressed name at `off`; returns it and the offset just
+/// past it in the original position
+fn dns_name(buf: &[u8], mut off: usize) -> Option<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut end = None;
+    // Bound pointer chasing so crafted loops terminate
+    for _ in 0..128 {
+        let len = *buf.get(off)? as usize;
+        match len {
+            0 => {
+                return Some((labels.join("."), end.unwrap_or(off + 1)));
+            }
+            l if l & 0xc0 == 0xc0 => {
+                let target = (be16(buf, off)? & 0x3fff) as usize;
+                end.get_or_insert(off + 2);
+                off = target;
+            }
+            l if l <= 63 => {
+                let label = buf.get(off + 1..off + 1 + l)?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                off += 1 + l;
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Mnemonic for a DNS record type
+pub fn dns_type_name(qtype: u16) -> String {
+    match qtype {
+        1 => "A".into(),
+        2 => "NS".into(),
+        5 => "CNAME".into(),
+        6 => "SOA".into(),
+        12 => "PTR".into(),
+        15 => "MX".into(),
+        16 => "TXT".into(),
+        28 => "AAAA".into(),
+        33 => "SRV".into(),
+        64 => "SVCB".into(),
+        65 => "HTTPS".into(),
+        255 => "ANY".into(),
+        other => format!("TYPE{}", other),
+    }
+}
+
+/// TCP flags in tcpdump's notation (`S.`, `P.`, `F.`, `R`, …)
+pub fn format_tcp_flags(flags: u8) -> String {
+    use tcp_flags::*;
+    let names = [
+        (FIN, 'F'),
+        (SYN, 'S'),
+        (RST, 'R'),
+        (PSH, 'P'),
+        (ACK, '.'),
+        (URG, 'U'),
+        (ECE, 'E'),
+        (CWR, 'W'),
+    ];
+    let s: String = names
+        .iter()
+        .filter(|(bit, _)| flags & bit != 0)
+        .map(|(_, c)| *c)
+        .collect();
+    if s.is_empty() {
+        "none".into()
+    } else {
+        s
+    }
+}
+
+impl Packet<'_> {
+    /// Highest decoded protocol, as shown in the capture list
+    pub fn protocol_name(&self) -> String {
+        if self.dns.is_some() {
+            return "DNS".into();
+        }
+        match (&self.transport, &self.network) {
+            (Some(TransportLayer::Tcp { .. }), _) => "TCP".into(),
+            (Some(TransportLayer::Udp { .. }), _) => "UDP".into(),
+            (Some(TransportLayer::Icmp { .. }), _) => "ICMP".into(),
+            (Some(TransportLayer::Icmpv6 { .. }), _) => "ICMPv6".into(),
+            (None, Some(NetworkLayer::Ipv4 { .. })) => "IPv4".into(),
+            (None, Some(NetworkLayer::Ipv6 { .. })) => "IPv6".into(),
+            (None, Some(NetworkLayer::Arp { .. })) => "ARP".into(),
+            (None, None) => match self.ethernet {
+                Some(eth) if eth.ethertype == ETHERTYPE_ARP => "ARP".into(),
+                Some(eth) => format!("0x{:04x}", eth.ethertype),
+                None => "unknown".into(),
+            },
+        }
+    }
+
+    /// Source and destination addresses (IP when present, else MAC)
+    pub fn endpoints(&self) -> (Option<IpAddr>, Option<IpAddr>) {
+        match self.network {
+            Some(NetworkLayer::Ipv4 { src, dst, .. }) => (Some(src.into()), Some(dst.into())),
+            Some(NetworkLayer::Ipv6 { src, dst, .. }) => (Some(src.into()), Some(dst.into())),
+            Some(NetworkLayer::Arp {
+                sender_ip,
+                target_ip,
+                ..
+            }) => (Some(sender_ip.into()), Some(target_ip.into())),
+            None => (None, None),
+        }
+    }
+
+    /// Transport ports, if TCP or UDP
+    pub fn ports(&self) -> (Option<u16>, Option<u16>) {
+        match self.transport {
+            Some(TransportLayer::Tcp {
+                src_port, dst_port, ..
+            })
+            | Some(TransportLayer::Udp { src_port, dst_port }) => (Some(src_port), Some(dst_port)),
+            _ => (None, None),
+        }
+    }
+
+    /// One-line tcpdump-style description
+    pub fn summary(&self) -> String {
+        let len = self.payload.len();
+        if let Some(dns) = &self.dns {
+            let mut s = format!("{}{}", dns.id, if dns.response { "" } else { "+" });
+            if let Some(q) = &dns.question {
+                let _ = write!(s, " {}? {}.", dns_type_name(q.qtype), q.name);
+            }
+            if dns.response {
+                let _ = write!(s, " {}/{}/{}", dns.answers, dns.authority, dns.additional);
+                if dns.rcode != 0 {
+                    let _ = write!(s, " rcode {}", dns.rcode);
+                }
+            }
+            let _ = write!(s, " ({})", len);
+            return s;
+        }
+        match self.transport {
+            Some(TransportLayer::Tcp {
+                seq,
+                ack,
+                flags,
+                window,
+                ..
+            }) => {
+                let mut s = format!("Flags [{}], seq {}", format_tcp_flags(flags), seq);
+                if len > 0 {
+                    let _ = write!(s, ":{}", seq.wrapping_add(len as u32));
+                }
+                if flags & tcp_flags::ACK != 0 {
+                    let _ = write!(s, ", ack {}", ack);
+                }
+                let _ = write!(s, ", win {}, length {}", window, len);
+                s
+            }
+            Some(TransportLayer::Udp { .. }) => format!("UDP, length {}", len),
+            Some(TransportLayer::Icmp {
+                icmp_type,
+                code,
+                echo,
+            }) => {
+                let kind = match icmp_type {
+                    0 => "echo reply".to_string(),
+                    3 => format!("destination unreachable, code {}", code),
+                    8 => "echo request".to_string(),
+                    11 => "time exceeded".to_string(),
+                    t => format!("type {}, code {}", t, code),
+                };
+                icmp_summary("ICMP", &kind, echo, len)
+            }
+            Some(TransportLayer::Icmpv6 {
+                icmp_type,
+                code,
+                echo,
+            }) => {
+                let kind = match icmp_type {
+                    1 => format!("destination unreachable, code {}", code),
+                    128 => "echo request".to_string(),
+                    129 => "echo reply".to_string(),
+                    133 => "router solicitation".to_string(),
+                    134 => "router advertisement".to_string(),
+                    135 => "neighbor solicitation".to_string(),
+                    136 => "neighbor advertisement".to_string(),
+                    t => format!("type {}, code {}", t, code),
+                };
+                icmp_summary("ICMP6", &kind, echo, len)
+            }
+            None => match self.network {
+                Some(NetworkLayer::Arp {
+                    operation: 1,
+                    sender_ip,
+                    target_ip,
+                    ..
+                }) => format!("Request who-has {} tell {}", target_ip, sender_ip),
+                Some(NetworkLayer::Arp {
+                    operation: 2,
+                    sender_ip,
+                    sender_mac,
+                    ..
+                }) => format!("Reply {} is-at {}", sender_ip, format_mac(&sender_mac)),
+                Some(NetworkLayer::Arp { operation, .. }) => {
+                    format!("ARP, operation {}", operation)
+                }
+                Some(NetworkLayer::Ipv4 { protocol, .. }) => {
+                    format!("ip-proto-{}, length {}", protocol, len)
+                }
+                Some(NetworkLayer::Ipv6 { next_header, .. }) => {
+                    format!("ip-proto-{}, length {}", next_header, len)
+                }
+                None => format!("length {}", len),
+            },
+        }
+    }
+}
+
+fn icmp_summary(name: &str, kind: &str, echo: Option<(u16, u16)>, len: usize) -> String {
+    match echo {
+        Some((id, seq)) => format!("{} {}, id {}, seq {}, length {}", name, kind, id, seq, len),
+        None => format!("{} {}, length {}", name, kind, len),
+    }
+}
+
+/// Decode a captured frame into the capture list entry used by
+/// [`crate::network_tools::capture_packets`]
+pub fn to_captured_packet(
+    number: u32,
+    link: LinkType,
+    timestamp_ns: u64,
+    frame: &[u8],
+    orig_len: u32,
+    include_data: bool,
+) -> CapturedPacket {
+    let packet = decode(link, frame);
+    let (src_ip, dst_ip) = packet.endpoints();
+    let (src_port, dst_port) = packet.ports();
+    let mac = |f: fn(&EthernetHeader) -> [u8; 6]| {
+        packet
+            .ethernet
+            .as_ref()
+            .map(|e| format_mac(&f(e)))
+            .unwrap_or_default()
+    };
+    let timestamp = chrono::DateTime::from_timestamp(
+        (timestamp_ns / 1_000_000_000) as i64,
+        (timestamp_ns % 1_000_000_000) as u32,
+    )
+    .map(|t| {
+        t.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S%.6f")
+            .to_string()
+    })
+    .unwrap_or_default();
+
+    CapturedPacket {
+        number,
+        timestamp,
+        source: src_ip.map_or_else(|| mac(|e| e.src), |ip| ip.to_string()),
+        destination: dst_ip.map_or_else(|| mac(|e| e.dst), |ip| ip.to_string()),
+        protocol: packet.protocol_name(),
+        length: orig_len,
+        info: packet.summary(),
+        src_port,
+        dst_port,
+        tcp_flags: match packet.transport {
+            Some(TransportLayer::Tcp { flags, .. }) => Some(format_tcp_flags(flags)),
+            _ => None,
+        },
+        data_preview: include_data.then(|| {
+            frame
+                .iter()
+                .take(64)
+                .map(|b| format!("{:02x}", b))
+                .collect()
+        }),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    /// Ethernet/IPv4/TCP 192.168.1.10:51234 -> 93.184.216.34:443, PSH|ACK,
+    /// 5 payload bytes, padded to the Ethernet minimum
+    pub(crate) fn tcp_v4_frame() -> Vec<u8> {
+        let mut f = Vec::new();
+        f.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x01]); // dst
+        f.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02]); // src
+        f.extend_from_slice(&[0x08, 0x00]);
+        f.extend_from_slice(&[0x45, 0x00, 0x00, 45, 0x12, 0x34, 0x40, 0x00, 64, 6, 0, 0]);
+        f.extend_from_slice(&[192, 168, 1, 10, 93, 184, 216, 34]);
+        f.extend_from_slice(&51234u16.to_be_bytes());
+        f.extend_from_slice(&443u16.to_be_bytes());
+        f.extend_from_slice(&1000u32.to_be_bytes());
+        f.extend_from_slice(&2000u32.to_be_bytes());
+        f.extend_from_slice(&[0x50, 0x18]);
+        f.extend_from_slice(&502u16.to_be_bytes());
+        f.extend_from_slice(&[0, 0, 0, 0]);
+        f.extend_from_slice(b"hello");
+        f.resize(64, 0); // trailer padding
+        f
+    }
+
+    /// Ethernet/IPv6/UDP 2001:db8::1:40000 -> 2001:db8::53:53 carrying a DNS
+    /// query for example.com/A
+    pub(crate) fn udp_v6_frame() -> Vec<u8> {
+        let mut dns = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in ["example", "com"] {
+            dns.push(label.len() as u8);
+            dns.extend_from_slice(label.as_bytes());
+        }
+        dns.extend_from_slice(&[0, 0, 1, 0, 1]);
+
+        let mut f = Vec::new();
+        f.extend_from_slice(&[0x33, 0x33, 0, 0, 0, 0x53]);
+        f.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02]);
+        f.extend_from_slice(&[0x86, 0xdd]);
+        f.extend_from_slice(&[0x60, 0, 0, 0]);
+        f.extend_from_slice(&((8 + dns.len()) as u16).to_be_bytes());
+        f.extend_from_slice(&[17, 64]);
+        let src: std::net::Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let dst: std::net::Ipv6Addr = "2001:db8::53".parse().unwrap();
+        f.extend_from_slice(&src.octets());
+        f.extend_from_slice(&dst.octets());
+        f.extend_from_slice(&40000u16.to_be_bytes());
+        f.extend_from_slice(&53u16.to_be_bytes());
+        f.extend_from_slice(&((8 + dns.len()) as u16).to_be_bytes());
+        f.extend_from_slice(&[0, 0]);
+        f.extend_from_slice(&dns);
+        f
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::*;
+    use super::*;
+
+    #[test]
+    fn test_decode_tcp_v4() {
+        let frame = tcp_v4_frame();
+        let p = decode(LinkType::Ethernet, &frame);
+        assert_eq!(p.ethernet.unwrap().src, [0x02, 0, 0, 0, 0, 0x02]);
+        assert_eq!(
+            p.network,
+            Some(NetworkLayer::Ipv4 {
+                src: Ipv4Addr::new(192, 168, 1, 10),
+                dst: Ipv4Addr::new(93, 184, 216, 34),
+                ttl: 64,
+                protocol: 6,
+            })
+        );
+        assert_eq!(p.ports(), (Some(51234), Some(443)));
+        // Ethernet padding is not payload
+        assert_eq!(p.payload, b"hello");
+        assert_eq!(
+            p.summary(),
+            "Flags [P.], seq 1000:1005, ack 2000, win 502, length 5"
+        );
+
+        // The same packet without a link header
+        let raw = decode(LinkType::Raw, &frame[14..]);
+        assert!(raw.ethernet.is_none());
+        assert_eq!(raw.transport, p.transport);
+    }
+
+    #[test]
+    fn test_decode_udp_v6_dns() {
+        let frame = udp_v6_frame();
+        let p = decode(LinkType::Ethernet, &frame);
+        assert_eq!(p.protocol_name(), "DNS");
+        let dns = p.dns.as_ref().unwrap();
+        assert!(!dns.response);
+        assert_eq!(
+            dns.question,
+            Some(DnsQuestion {
+                name: "example.com".into(),
+                qtype: 1
+            })
+        );
+        assert_eq!(p.summary(), "4660+ A? example.com. (29)");
+        let (src, dst) = p.endpoints();
+        assert_eq!(src.unwrap().to_string(), "2001:db8::1");
+        assert_eq!(dst.unwrap().to_string(), "2001:db8::53");
+    }
+
+    #[test]
+    fn test_decode_dns_response_with_compression() {
+        // Response: question example.com/AAAA, one answer pointing back at it
+        let mut msg = vec![0xab, 0xcd, 0x81, 0x83, 0, 1, 0, 1, 0, 0, 0, 0];
+        msg.extend_from_slice(b"\x07example\x03com\x00\x00\x1c\x00\x01");
+        msg.extend_from_slice(&[0xc0, 12, 0, 28, 0, 1, 0, 0, 0, 60, 0, 0]);
+        let dns = decode_dns(&msg).unwrap();
+        assert!(dns.response);
+        assert_eq!((dns.rcode, dns.answers), (3, 1));
+        assert_eq!(dns_name(&msg, 29).unwrap().0, "example.com");
+        // Pointer loops terminate
+        assert!(dns_name(&[0xc0, 0x00], 0).is_none());
+    }
+
+    #[test]
+    fn test_decode_icmp_and_arp() {
+        let mut icmp = vec![
+            0x45, 0, 0, 28, 0, 0, 0, 0, 64, 1, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2,
+        ];
+        icmp.extend_from_slice(&[8, 0, 0, 0, 0, 7, 0, 3]);
+        let p = decode(LinkType::Raw, &icmp);
+        assert_eq!(p.protocol_name(), "ICMP");
+        assert_eq!(p.summary(), "ICMP echo request, id 7, seq 3, length 0");
+
+        let mut arp = vec![0xff; 6];
+        arp.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02, 0x08, 0x06]);
+        arp.extend_from_slice(&[0, 1, 0x08, 0x00, 6, 4, 0, 1]);
+        arp.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02, 10, 0, 0, 1]);
+        arp.extend_from_slice(&[0, 0, 0, 0, 0, 0, 10, 0, 0, 2]);
+        let p = decode(LinkType::Ethernet, &arp);
+        assert_eq!(p.protocol_name(), "ARP");
+        assert_eq!(p.summary(), "Request who-has 10.0.0.2 tell 10.0.0.1");
+    }
+
+    #[test]
+    fn test_truncated_frames() {
+        let frame = tcp_v4_frame();
+        for len in [0, 10, 20, 40] {
+            let p = decode(LinkType::Ethernet, &frame[..len]);
+            assert!(p.transport.is_none(), "len {len}");
+        }
+        // Ports survive a short snaplen
+        let p = decode(LinkType::Ethernet, &frame[..50]);
+        assert_eq!(p.ports(), (Some(51234), Some(443)));
+        // Non-first IPv4 fragment: network layer only
+        let mut frag = frame.clone();
+        frag[20] = 0x00;
+        frag[21] = 0x10;
+        let p = decode(LinkType::Ethernet, &frag);
+        assert!(p.network.is_some() && p.transport.is_none());
+    }
+
+    #[test]
+    fn test_tcp_flags_format() {
+        use tcp_flags::*;
+        assert_eq!(format_tcp_flags(SYN), "S");
+        assert_eq!(format_tcp_flags(SYN | ACK), "S.");
+        assert_eq!(format_tcp_flags(FIN | PSH | ACK), "FP.");
+        assert_eq!(format_tcp_flags(0), "none");
+    }
+
+    #[test]
+    fn test_to_captured_packet() {
+        let frame = tcp_v4_frame();
+        let pkt = to_captured_packet(
+            1,
+            LinkType::Ethernet,
+            1_700_000_000_000_000_000,
+            &frame,
+            64,
+            true,
+        );
+        assert_eq!(pkt.source, "192.168.1.10");
+        assert_eq!(pkt.destination, "93.184.216.34");
+        assert_eq!(pkt.protocol, "TCP");
+        assert_eq!(pkt.tcp_flags.as_deref(), Some("P."));
+        assert_eq!(pkt.length, 64);
+        assert!(pkt.timestamp.starts_with("2023-11-1"));
+        assert_eq!(pkt.data_preview.unwrap().len(), 128);
+    }
+}
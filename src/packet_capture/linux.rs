@@ -0,0 +1,332 @@
+//! `AF_PACKET` capture through a `TPACKET_V3` memory-mapped ring
+//!
+//! The kernel fills fixed-size blocks with frames and hands whole blocks to
+//! user space, so one `poll` wakeup delivers many packets without a copy or
+//! syscall per frame. The BPF filter runs in the kernel before frames reach
+//! the ring.
+
+use super::bpf::BpfProgram;
+use super::{CaptureStats, LinkType, RawFrame};
+use crate::error::{Result, SimonError};
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::atomic::{fence, Ordering};
+use std::time::Duration;
+
+const TPACKET_V3: libc::c_int = 2;
+const TP_STATUS_KERNEL: u32 = 0;
+const TP_STATUS_USER: u32 = 1;
+/// `sll_pkttype` of frames sent by this host
+const PACKET_OUTGOING: u8 = 4;
+/// `TPACKET_ALIGN(sizeof(struct tpacket3_hdr))`: `sockaddr_ll` follows
+const TPACKET3_HDRLEN: usize = 48;
+
+const BLOCK_SIZE: u32 = 1 << 20;
+const BLOCK_COUNT: u32 = 16;
+const FRAME_SIZE: u32 = 1 << 11;
+/// Retire a partly filled block after this long so slow traffic still flows
+const BLOCK_TIMEOUT_MS: u32 = 50;
+
+/// `struct tpacket_req3`
+#[repr(C)]
+struct TpacketReq3 {
+    block_size: u32,
+    block_nr: u32,
+    frame_size: u32,
+    frame_nr: u32,
+    retire_blk_tov: u32,
+    sizeof_priv: u32,
+    feature_req_word: u32,
+}
+
+/// `struct tpacket_stats_v3`
+#[repr(C)]
+#[derive(Default)]
+struct TpacketStatsV3 {
+    packets: u32,
+    drops: u32,
+    freeze_q_cnt: u32,
+}
+
+fn os_error(context: &str) -> SimonError {
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::EPERM) | Some(libc::EACCES) => SimonError::PermissionDenied(format!(
+            "{}: {} (needs root or CAP_NET_RAW)",
+            context, err
+        )),
+        _ => SimonError::Io(io::Error::new(err.kind(), format!("{}: {}", context, err))),
+    }
+}
+
+fn setsockopt<T>(fd: libc::c_int, level: libc::c_int, name: libc::c_int, value: &T) -> bool {
+    // SAFETY: value points to a live T of the size passed.
+    unsafe {
+        libc::setsockopt(
+            fd,
+            level,
+            name,
+            value as *const T as *const libc::c_void,
+            std::mem::size_of::<T>() as libc::socklen_t,
+        ) == 0
+    }
+}
+
+/// Link type of an interface from its ARPHRD type in sysfs
+pub fn link_type(interface: &str) -> Result<LinkType> {
+    let path = format!("/sys/class/net/{}/type", interface);
+    let arphrd = std::fs::read_to_string(&path)
+        .map_err(|_| SimonError::DeviceNotFound(format!("network interface '{}'", interface)))?;
+    match arphrd.trim() {
+        // Ethernet, loopback (Ethernet-framed on Linux)
+        "1" | "772" => Ok(LinkType::Ethernet),
+        // Tunnels without a link header: none, raw IP
+        "65534" | "519" => Ok(LinkType::Raw),
+        other => Err(SimonError::FeatureNotAvailable(format!(
+            "capture on '{}': unsupported link type (ARPHRD {})",
+            interface, other
+        ))),
+    }
+}
+
+/// Default capture interface: the first non-loopback interface that is up,
+/// else loopback
+pub fn default_interface() -> String {
+    let mut names: Vec<String> = std::fs::read_dir("/sys/class/net")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+        .into_iter()
+        .find(|name| {
+            name != "lo"
+                && std::fs::read_to_string(format!("/sys/class/net/{}/operstate", name))
+                    .map(|s| s.trim() == "up")
+                    .unwrap_or(false)
+                && link_type(name).is_ok()
+        })
+        .unwrap_or_else(|| "lo".to_string())
+}
+
+/// Memory-mapped receive ring bound to one interface
+pub struct PacketRing {
+    sock: OwnedFd,
+    ring: *mut u8,
+    ring_len: usize,
+    block: usize,
+    loopback: bool,
+    stats: CaptureStats,
+}
+
+// SAFETY: the mapping is owned exclusively by this value and only accessed
+// through &mut self.
+unsafe impl Send for PacketRing {}
+
+impl PacketRing {
+    /// Open a ring on `interface`; only frames accepted by `filter` are
+    /// delivered
+    pub fn open(interface: &str, filter: &BpfProgram) -> Result<Self> {
+        let name = std::ffi::CString::new(interface)
+            .map_err(|_| SimonError::DeviceNotFound(interface.to_string()))?;
+        // SAFETY: name is a valid NUL-terminated string.
+        let ifindex = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if ifindex == 0 {
+            return Err(SimonError::DeviceNotFound(format!(
+                "network interface '{}'",
+                interface
+            )));
+        }
+
+        // Protocol 0: nothing is queued until bind() names ETH_P_ALL, so no
+        // unfiltered frame from another interface slips into the ring
+        // SAFETY: plain socket(2) call; the descriptor is owned below.
+        let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW | libc::SOCK_CLOEXEC, 0) };
+        if fd < 0 {
+            return Err(os_error("AF_PACKET socket"));
+        }
+        // SAFETY: fd is a freshly created descriptor not owned elsewhere.
+        let sock = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        if !setsockopt(fd, libc::SOL_PACKET, libc::PACKET_VERSION, &TPACKET_V3) {
+            return Err(os_error("PACKET_VERSION TPACKET_V3"));
+        }
+        let insns = filter.instructions();
+        let prog = libc::sock_fprog {
+            len: insns.len() as libc::c_ushort,
+            filter: insns.as_ptr() as *mut libc::sock_filter,
+        };
+        if !setsockopt(fd, libc::SOL_SOCKET, libc::SO_ATTACH_FILTER, &prog) {
+            return Err(os_error("SO_ATTACH_FILTER"));
+        }
+        let req = TpacketReq3 {
+            block_size: BLOCK_SIZE,
+            block_nr: BLOCK_COUNT,
+            frame_size: FRAME_SIZE,
+            frame_nr: BLOCK_SIZE / FRAME_SIZE * BLOCK_COUNT,
+            retire_blk_tov: BLOCK_TIMEOUT_MS,
+            sizeof_priv: 0,
+            feature_req_word: 0,
+        };
+        if !setsockopt(fd, libc::SOL_PACKET, libc::PACKET_RX_RING, &req) {
+            return Err(os_error("PACKET_RX_RING"));
+        }
+
+        let ring_len = (BLOCK_SIZE * BLOCK_COUNT) as usize;
+        // SAFETY: maps the ring just configured on fd; checked for failure.
+        let ring = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                ring_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+        if ring == libc::MAP_FAILED {
+            return Err(os_error("mmap packet ring"));
+        }
+        let ring = Self {
+            sock,
+            ring: ring as *mut u8,
+            ring_len,
+            block: 0,
+            // ARPHRD_LOOPBACK
+            loopback: std::fs::read_to_string(format!("/sys/class/net/{}/type", interface))
+                .map(|t| t.trim() == "772")
+                .unwrap_or(false),
+            stats: CaptureStats::default(),
+        };
+
+        // SAFETY: sockaddr_ll is plain data.
+        let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as u16;
+        addr.sll_protocol = (libc::ETH_P_ALL as u16).to_be();
+        addr.sll_ifindex = ifindex as i32;
+        // SAFETY: addr is valid for the length passed.
+        let bound = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if bound != 0 {
+            return Err(os_error("bind AF_PACKET"));
+        }
+        Ok(ring)
+    }
+
+    fn block_ptr(&self, index: usize) -> *mut u8 {
+        // SAFETY: index < BLOCK_COUNT, so the offset stays inside the mapping.
+        unsafe { self.ring.add(index * BLOCK_SIZE as usize) }
+    }
+
+    /// Wait up to `timeout` for the next filled block and pass each frame in
+    /// it to `f`; returns the number of frames delivered
+    pub fn next_block(
+        &mut self,
+        timeout: Duration,
+        mut f: impl FnMut(RawFrame<'_>),
+    ) -> Result<usize> {
+        let block = self.block_ptr(self.block);
+        // SAFETY: block_status is the u32 at offset 8 of tpacket_block_desc;
+        // the kernel updates it concurrently, hence the volatile read.
+        let status = unsafe { std::ptr::read_volatile(block.add(8) as *const u32) };
+        if status & TP_STATUS_USER == 0 {
+            let mut pfd = libc::pollfd {
+                fd: self.sock.as_raw_fd(),
+                events: libc::POLLIN | libc::POLLERR,
+                revents: 0,
+            };
+            let ms = timeout.as_millis().min(i32::MAX as u128) as libc::c_int;
+            // SAFETY: pfd is a valid pollfd array of length 1.
+            let n = unsafe { libc::poll(&mut pfd, 1, ms) };
+            if n < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    return Ok(0);
+                }
+                return Err(SimonError::Io(err));
+            }
+            // SAFETY: as above.
+            let status = unsafe { std::ptr::read_volatile(block.add(8) as *const u32) };
+            if status & TP_STATUS_USER == 0 {
+                return Ok(0);
+            }
+        }
+        fence(Ordering::Acquire);
+
+        // SAFETY: the kernel handed this block to user space (TP_STATUS_USER);
+        // its header and frames stay valid until we return it below.
+        let delivered = unsafe {
+            let num_pkts = (block.add(12) as *const u32).read_unaligned();
+            let mut frame = block.add((block.add(16) as *const u32).read_unaligned() as usize);
+            let mut delivered = 0;
+            for _ in 0..num_pkts {
+                let hdr = |off: usize| (frame.add(off) as *const u32).read_unaligned();
+                let next_offset = hdr(0);
+                let (sec, nsec, snaplen, len) = (hdr(4), hdr(8), hdr(12), hdr(16));
+                let mac = (frame.add(24) as *const u16).read_unaligned() as usize;
+                let pkttype = *frame.add(TPACKET3_HDRLEN + 10);
+                // Loopback frames are seen twice, once outgoing; keep one copy
+                if !(self.loopback && pkttype == PACKET_OUTGOING) {
+                    let data = std::slice::from_raw_parts(frame.add(mac), snaplen as usize);
+                    f(RawFrame {
+                        timestamp_ns: sec as u64 * 1_000_000_000 + nsec as u64,
+                        data,
+                        orig_len: len,
+                    });
+                    delivered += 1;
+                }
+                if next_offset == 0 {
+                    break;
+                }
+                frame = frame.add(next_offset as usize);
+            }
+            delivered
+        };
+
+        fence(Ordering::Release);
+        // SAFETY: returns the block to the kernel; we hold no references into it.
+        unsafe { std::ptr::write_volatile(block.add(8) as *mut u32, TP_STATUS_KERNEL) };
+        self.block = (self.block + 1) % BLOCK_COUNT as usize;
+        self.stats.packets += delivered as u64;
+        Ok(delivered)
+    }
+
+    /// Packets delivered and dropped (ring full) so far
+    pub fn stats(&mut self) -> CaptureStats {
+        let mut raw = TpacketStatsV3::default();
+        let mut len = std::mem::size_of::<TpacketStatsV3>() as libc::socklen_t;
+        // SAFETY: raw is valid for writes of len bytes. The kernel resets its
+        // counters on each read, so they are accumulated here.
+        let ok = unsafe {
+            libc::getsockopt(
+                self.sock.as_raw_fd(),
+                libc::SOL_PACKET,
+                libc::PACKET_STATISTICS,
+                &mut raw as *mut TpacketStatsV3 as *mut libc::c_void,
+                &mut len,
+            ) == 0
+        };
+        if ok {
+            self.stats.dropped += raw.drops as u64;
+        }
+        self.stats
+    }
+}
+
+impl Drop for PacketRing {
+    fn drop(&mut self) {
+        // SAFETY: unmaps the region mapped in open(); no references outlive self.
+        unsafe {
+            libc::munmap(self.ring as *mut libc::c_void, self.ring_len);
+        }
+    }
+}
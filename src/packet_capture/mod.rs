@@ -0,0 +1,278 @@
+//! Native Packet Capture
+//!
+//! In-process capture for [`crate::network_tools::capture_packets`], without
+//! tcpdump or tshark:
+//! - Linux `AF_PACKET` sockets with a `TPACKET_V3` memory-mapped ring
+//! - Kernel-side classic BPF filters compiled from tcpdump-style expressions
+//! - Ethernet/ARP/IPv4/IPv6/TCP/UDP/ICMP/DNS decoding into
+//!   [`CapturedPacket`](crate::network_tools::CapturedPacket)
+//! - pcapng output readable by Wireshark and tcpdump
+//! - Offline analysis of pcap/pcapng files and capture results: flows, top
+//!   talkers, protocol mix, TCP retransmissions and DNS latency
+//!   ([`analysis`])
+//!
+//! Capturing needs root or `CAP_NET_RAW`. The filter compiler, decoders,
+//! file reader/writer and analyzer are platform-independent.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::network_tools::CaptureConfig;
+//! use simonlib::packet_capture;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = CaptureConfig {
+//!     interface: Some("eth0".into()),
+//!     packet_count: 100,
+//!     pcapng_path: Some("/tmp/eth0.pcapng".into()),
+//!     ..Default::default()
+//! };
+//! let capture = packet_capture::capture(&config, "tcp port 443")?;
+//! println!("{} packets, {} dropped", capture.packets.len(), capture.stats.dropped);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod analysis;
+pub mod bpf;
+pub mod decode;
+#[cfg(target_os = "linux")]
+pub mod linux;
+pub mod pcapng;
+pub mod reader;
+
+pub use analysis::{analyze_capture_result, analyze_file, CaptureAnalysis, CaptureAnalyzer};
+pub use bpf::BpfProgram;
+pub use decode::{decode, Packet};
+pub use pcapng::PcapngWriter;
+pub use reader::CaptureReader;
+
+use crate::error::Result;
+use crate::network_tools::{CaptureConfig, CapturedPacket};
+use serde::{Deserialize, Serialize};
+
+/// Link-layer header of captured frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkType {
+    /// Ethernet II (also Linux loopback)
+    Ethernet,
+    /// Bare IPv4/IPv6 packets (tun devices, WireGuard)
+    Raw,
+}
+
+impl LinkType {
+    /// pcap `LINKTYPE_*` code
+    pub fn code(self) -> u16 {
+        match self {
+            LinkType::Ethernet => 1,
+            LinkType::Raw => 101,
+        }
+    }
+
+    pub fn from_code(code: u16) -> Option<Self> {
+        match code {
+            1 => Some(LinkType::Ethernet),
+            101 => Some(LinkType::Raw),
+            _ => None,
+        }
+    }
+}
+
+/// A frame as delivered by the kernel
+#[derive(Debug, Clone, Copy)]
+pub struct RawFrame<'a> {
+    /// Unix epoch nanoseconds
+    pub timestamp_ns: u64,
+    /// Captured bytes (at most the snap length)
+    pub data: &'a [u8],
+    /// Length on the wire
+    pub orig_len: u32,
+}
+
+/// Kernel capture counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureStats {
+    /// Frames delivered to the capture
+    pub packets: u64,
+    /// Frames the kernel dropped because the ring was full
+    pub dropped: u64,
+}
+
+/// Result of a native capture
+#[derive(Debug, Clone)]
+pub struct NativeCapture {
+    pub interface: String,
+    pub link: LinkType,
+    pub packets: Vec<CapturedPacket>,
+    pub stats: CaptureStats,
+}
+
+/// Capture on `config.interface` (default: first interface that is up)
+/// until `config.packet_count` frames (0 = unlimited) or
+/// `config.timeout_secs` elapse, keeping frames that match `filter`
+#[cfg(target_os = "linux")]
+pub fn capture(config: &CaptureConfig, filter: &str) -> Result<NativeCapture> {
+    use std::time::{Duration, Instant};
+
+    let interface = config
+        .interface
+        .clone()
+        .unwrap_or_else(linux::default_interface);
+    let link = linux::link_type(&interface)?;
+    let program = BpfProgram::compile(filter, link, config.snaplen)?;
+    let mut ring = linux::PacketRing::open(&interface, &program)?;
+
+    let mut writer = match &config.pcapng_path {
+        Some(path) => {
+            let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+            let mut writer = PcapngWriter::new(file)?;
+            writer.add_interface(&interface, link, config.snaplen)?;
+            Some(writer)
+        }
+        None => None,
+    };
+
+    let limit = match config.packet_count {
+        0 => usize::MAX,
+        n => n as usize,
+    };
+    let deadline = Instant::now() + Duration::from_secs(config.timeout_secs as u64);
+    let mut packets = Vec::new();
+    let mut write_error = None;
+    while packets.len() < limit {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        ring.next_block(deadline - now, |frame| {
+            if packets.len() >= limit {
+                return;
+            }
+            if let Some(writer) = writer.as_mut() {
+                if let Err(e) =
+                    writer.write_packet(0, frame.timestamp_ns, frame.data, frame.orig_len)
+                {
+                    write_error.get_or_insert(e);
+                }
+            }
+            packets.push(decode::to_captured_packet(
+                packets.len() as u32 + 1,
+                link,
+                frame.timestamp_ns,
+                frame.data,
+                frame.orig_len,
+                config.include_data,
+            ));
+        })?;
+    }
+    if let Some(e) = write_error {
+        return Err(e.into());
+    }
+    if let Some(mut writer) = writer {
+        writer.flush()?;
+    }
+
+    Ok(NativeCapture {
+        interface,
+        link,
+        packets,
+        stats: ring.stats(),
+    })
+}
+
+/// Native capture is only implemented for Linux
+#[cfg(not(target_os = "linux"))]
+pub fn capture(_config: &CaptureConfig, _filter: &str) -> Result<NativeCapture> {
+    Err(crate::error::SimonError::UnsupportedPlatform(
+        "native packet capture requires Linux AF_PACKET".into(),
+    ))
+}
+
+/// Whether this process may open capture sockets
+pub fn is_available() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        // SAFETY: plain socket(2) call; closed immediately.
+        let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW | libc::SOCK_CLOEXEC, 0) };
+        if fd >= 0 {
+            // SAFETY: fd was just opened and is not used afterwards.
+            unsafe { libc::close(fd) };
+        }
+        fd >= 0
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SimonError;
+    use crate::test_util::FakeRoot;
+
+    #[test]
+    fn test_link_type_codes() {
+        for link in [LinkType::Ethernet, LinkType::Raw] {
+            assert_eq!(LinkType::from_code(link.code()), Some(link));
+        }
+        assert_eq!(LinkType::from_code(113), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_loopback_capture() {
+        use std::net::UdpSocket;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let port = receiver.local_addr().unwrap().port();
+        let fs = FakeRoot::new("capture");
+        let pcapng_path = fs.join("lo.pcapng");
+        let config = CaptureConfig {
+            interface: Some("lo".into()),
+            packet_count: 3,
+            timeout_secs: 10,
+            pcapng_path: Some(pcapng_path.clone()),
+            ..Default::default()
+        };
+
+        // Keep sending until the capture returns: the ring may open after
+        // the first datagrams
+        let done = Arc::new(AtomicBool::new(false));
+        let sender = {
+            let done = done.clone();
+            std::thread::spawn(move || {
+                let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+                while !done.load(Ordering::Relaxed) {
+                    let _ = socket.send_to(b"simon-capture", ("127.0.0.1", port));
+                    std::thread::sleep(std::time::Duration::from_millis(20));
+                }
+            })
+        };
+        let result = capture(&config, &format!("udp and dst port {}", port));
+        done.store(true, Ordering::Relaxed);
+        sender.join().unwrap();
+
+        let capture = match result {
+            Ok(c) => c,
+            Err(SimonError::PermissionDenied(_)) => return, // needs CAP_NET_RAW
+            Err(e) => panic!("capture failed: {}", e),
+        };
+        assert_eq!(capture.packets.len(), 3);
+        for (i, pkt) in capture.packets.iter().enumerate() {
+            assert_eq!(pkt.number, i as u32 + 1);
+            assert_eq!(pkt.protocol, "UDP");
+            assert_eq!(pkt.destination, "127.0.0.1");
+            assert_eq!(pkt.dst_port, Some(port));
+            // Ethernet + IPv4 + UDP headers + payload
+            assert_eq!(pkt.length, 14 + 20 + 8 + 13);
+        }
+        let file = std::fs::read(&pcapng_path).unwrap();
+        assert_eq!(&file[..4], &0x0A0D_0D0Au32.to_ne_bytes());
+        assert!(file.windows(13).filter(|w| w == b"simon-capture").count() == 3);
+    }
+}
@@ -0,0 +1,596 @@
+//! pcap and pcapng file reader
+//!
+//! Reads classic pcap (microsecond and nanosecond, either byte order) and
+//! pcapng (any number of sections and interfaces, `if_tsresol` and
+//! `if_tsoffset`, Enhanced and Simple Packet Blocks). Other pcapng blocks are
+//! skipped. A file that ends part-way through a record, as one still being
+//! written does, ends the stream and sets [`CaptureReader::truncated`].
+
+use super::pcapng::{BYTE_ORDER_MAGIC, EPB_TYPE, IDB_TYPE, SHB_TYPE};
+use super::LinkType;
+use crate::error::{Result, SimonError};
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+const PCAP_MAGIC_USEC: u32 = 0xA1B2_C3D4;
+const PCAP_MAGIC_NSEC: u32 = 0xA1B2_3C4D;
+const SPB_TYPE: u32 = 0x0000_0003;
+const IF_TSRESOL: u16 = 9;
+const IF_TSOFFSET: u16 = 14;
+
+/// Largest record or block accepted; anything bigger is a corrupt length
+const MAX_RECORD: usize = 16 << 20;
+
+/// `LINKTYPE_*` codes understood by [`link_payload`]
+const LINKTYPE_NULL: u16 = 0;
+const LINKTYPE_LINUX_SLL: u16 = 113;
+const LINKTYPE_IPV4: u16 = 228;
+const LINKTYPE_IPV6: u16 = 229;
+const LINKTYPE_LINUX_SLL2: u16 = 276;
+
+/// One frame read from a capture file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFrame {
+    /// pcapng interface id (always 0 for pcap)
+    pub interface: u32,
+    /// `LINKTYPE_*` of the interface
+    pub link_code: u16,
+    /// Unix epoch nanoseconds (0 for Simple Packet Blocks)
+    pub timestamp_ns: u64,
+    pub data: Vec<u8>,
+    /// Length on the wire
+    pub orig_len: u32,
+}
+
+impl FileFrame {
+    /// The frame as the decoder expects it, see [`link_payload`]
+    pub fn payload(&self) -> Option<(LinkType, &[u8])> {
+        link_payload(self.link_code, &self.data)
+    }
+}
+
+/// Map a frame of link type `code` onto a link the decoder handles
+///
+/// Besides Ethernet and raw IP this strips Linux cooked (`tcpdump -i any`,
+/// SLL and SLL2) and BSD loopback headers. Returns None for other links and
+/// for non-IP cooked frames.
+pub fn link_payload(code: u16, data: &[u8]) -> Option<(LinkType, &[u8])> {
+    let ip_after = |off: usize, proto: Option<u16>| match proto? {
+        0x0800 | 0x86dd => Some((LinkType::Raw, data.get(off..)?)),
+        _ => None,
+    };
+    let be16 = |off: usize| Some(u16::from_be_bytes(data.get(off..off + 2)?.try_into().ok()?));
+    if let Some(link) = LinkType::from_code(code) {
+        return Some((link, data));
+    }
+    match code {
+        LINKTYPE_IPV4 | LINKTYPE_IPV6 => Some((LinkType::Raw, data)),
+        LINKTYPE_LINUX_SLL => ip_after(16, be16(14)),
+        LINKTYPE_LINUX_SLL2 => ip_after(20, be16(0)),
+        // 4-byte address family in the writer's byte order; the IP version
+        // nibble is enough to tell v4 from v6
+        LINKTYPE_NULL => match data.get(4).map(|b| b >> 4) {
+            Some(4 | 6) => Some((LinkType::Raw, &data[4..])),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Pcap { nanos: bool, link_code: u16 },
+    Pcapng,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    link_code: u16,
+    snaplen: u32,
+    /// Timestamp units per second
+    units_per_sec: u64,
+    offset_secs: i64,
+}
+
+/// Streams frames out of a pcap or pcapng file
+pub struct CaptureReader<R: Read> {
+    input: R,
+    format: Format,
+    big_endian: bool,
+    interfaces: Vec<Interface>,
+    truncated: bool,
+}
+
+impl CaptureReader<BufReader<std::fs::File>> {
+    /// Open a capture file, detecting the format from its magic number
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::new(BufReader::new(std::fs::File::open(path)?))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut input: R) -> Result<Self> {
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+        let mut reader = Self {
+            input,
+            format: Format::Pcapng,
+            big_endian: false,
+            interfaces: Vec::new(),
+            truncated: false,
+        };
+
+        if u32::from_ne_bytes(magic) == SHB_TYPE {
+            // Palindromic block type: the byte-order magic decides endianness
+            reader.read_section_header()?;
+            return Ok(reader);
+        }
+        let (nanos, big_endian) = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+            (PCAP_MAGIC_USEC, _) => (false, false),
+            (PCAP_MAGIC_NSEC, _) => (true, false),
+            (_, PCAP_MAGIC_USEC) => (false, true),
+            (_, PCAP_MAGIC_NSEC) => (true, true),
+            _ => {
+                return Err(SimonError::Parse(
+                    "not a pcap or pcapng file (unknown magic number)".into(),
+                ))
+            }
+        };
+        reader.big_endian = big_endian;
+        let mut header = [0u8; 20];
+        reader.input.read_exact(&mut header)?;
+        reader.format = Format::Pcap {
+            nanos,
+            link_code: reader.u32_at(&header, 16) as u16,
+        };
+        Ok(reader)
+    }
+
+    /// Whether the file ended in the middle of a record
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Read the next frame; None at end of file
+    pub fn next_frame(&mut self) -> Result<Option<FileFrame>> {
+        match self.format {
+            Format::Pcap { nanos, link_code } => self.next_pcap_record(nanos, link_code),
+            Format::Pcapng => self.next_pcapng_packet(),
+        }
+    }
+
+    fn next_pcap_record(&mut self, nanos: bool, link_code: u16) -> Result<Option<FileFrame>> {
+        let mut header = [0u8; 16];
+        if !self.fill(&mut header)? {
+            return Ok(None);
+        }
+        let secs = self.u32_at(&header, 0) as u64;
+        let frac = self.u32_at(&header, 4) as u64;
+        let caplen = self.u32_at(&header, 8) as usize;
+        let orig_len = self.u32_at(&header, 12);
+        if caplen > MAX_RECORD {
+            return Err(SimonError::Parse(format!(
+                "pcap record of {} bytes is corrupt",
+                caplen
+            )));
+        }
+        let mut data = vec![0u8; caplen];
+        if !self.fill(&mut data)? {
+            self.truncated = true;
+            return Ok(None);
+        }
+        Ok(Some(FileFrame {
+            interface: 0,
+            link_code,
+            timestamp_ns: secs * 1_000_000_000 + if nanos { frac } else { frac * 1000 },
+            data,
+            orig_len,
+        }))
+    }
+
+    fn next_pcapng_packet(&mut self) -> Result<Option<FileFrame>> {
+        loop {
+            let mut block_type = [0u8; 4];
+            if !self.fill(&mut block_type)? {
+                return Ok(None);
+            }
+            if u32::from_ne_bytes(block_type) == SHB_TYPE {
+                self.read_section_header()?;
+                continue;
+            }
+            let block_type = self.u32_at(&block_type, 0);
+            let Some(body) = self.read_block_body()? else {
+                return Ok(None);
+            };
+            match block_type {
+                IDB_TYPE => self.add_interface(&body)?,
+                EPB_TYPE => return self.enhanced_packet(&body).map(Some),
+                SPB_TYPE => return self.simple_packet(&body).map(Some),
+                _ => {}
+            }
+        }
+    }
+
+    /// Read a Section Header Block after its type; starts a new interface list
+    fn read_section_header(&mut self) -> Result<()> {
+        let mut head = [0u8; 8];
+        self.input.read_exact(&mut head)?;
+        self.big_endian = match u32::from_le_bytes(head[4..8].try_into().unwrap()) {
+            BYTE_ORDER_MAGIC => false,
+            m if m.swap_bytes() == BYTE_ORDER_MAGIC => true,
+            _ => return Err(SimonError::Parse("pcapng: bad byte-order magic".into())),
+        };
+        let len = self.u32_at(&head, 0) as usize;
+        if !(28..=MAX_RECORD).contains(&len) || len % 4 != 0 {
+            return Err(SimonError::Parse(format!(
+                "pcapng: bad section header length {}",
+                len
+            )));
+        }
+        // Version, section length, options and trailing length
+        let mut rest = vec![0u8; len - 12];
+        self.input.read_exact(&mut rest)?;
+        self.interfaces.clear();
+        Ok(())
+    }
+
+    /// Body of a non-SHB block after its type, without the trailing length
+    fn read_block_body(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut len = [0u8; 4];
+        if !self.fill(&mut len)? {
+            self.truncated = true;
+            return Ok(None);
+        }
+        let len = self.u32_at(&len, 0) as usize;
+        if !(12..=MAX_RECORD).contains(&len) || len % 4 != 0 {
+            return Err(SimonError::Parse(format!(
+                "pcapng: bad block length {}",
+                len
+            )));
+        }
+        let mut body = vec![0u8; len - 8];
+        if !self.fill(&mut body)? {
+            self.truncated = true;
+            return Ok(None);
+        }
+        body.truncate(len - 12);
+        Ok(Some(body))
+    }
+
+    fn add_interface(&mut self, body: &[u8]) -> Result<()> {
+        if body.len() < 8 {
+            return Err(SimonError::Parse("pcapng: short interface block".into()));
+        }
+        let mut iface = Interface {
+            link_code: self.u16_at(body, 0),
+            snaplen: self.u32_at(body, 4),
+            units_per_sec: 1_000_000,
+            offset_secs: 0,
+        };
+        for (code, value) in self.options(&body[8..]) {
+            match (code, value) {
+                (IF_TSRESOL, [res, ..]) => {
+                    let exp = (res & 0x7f) as u32;
+                    iface.units_per_sec = if res & 0x80 == 0 {
+                        10u64.checked_pow(exp)
+                    } else {
+                        2u64.checked_pow(exp)
+                    }
+                    .ok_or_else(|| {
+                        SimonError::Parse(format!("pcapng: unsupported if_tsresol {:#x}", res))
+                    })?;
+                }
+                (IF_TSOFFSET, _) if value.len() >= 8 => {
+                    let bytes = value[..8].try_into().unwrap();
+                    iface.offset_secs = if self.big_endian {
+                        i64::from_be_bytes(bytes)
+                    } else {
+                        i64::from_le_bytes(bytes)
+                    };
+                }
+                _ => {}
+            }
+        }
+        self.interfaces.push(iface);
+        Ok(())
+    }
+
+    fn enhanced_packet(&self, body: &[u8]) -> Result<FileFrame> {
+        if body.len() < 20 {
+            return Err(SimonError::Parse("pcapng: short packet block".into()));
+        }
+        let interface = self.u32_at(body, 0);
+        let iface = self.interface(interface)?;
+        let units = ((self.u32_at(body, 4) as u64) << 32) | self.u32_at(body, 8) as u64;
+        let caplen = (self.u32_at(body, 12) as usize).min(body.len() - 20);
+        let frac = units % iface.units_per_sec;
+        let frac_ns = (frac as u128 * 1_000_000_000 / iface.units_per_sec as u128) as u64;
+        let timestamp_ns = i64::try_from(units / iface.units_per_sec)
+            .ok()
+            .and_then(|secs| secs.checked_add(iface.offset_secs))
+            .and_then(|secs| (secs.max(0) as u64).checked_mul(1_000_000_000))
+            .and_then(|ns| ns.checked_add(frac_ns))
+            .ok_or_else(|| SimonError::Parse("pcapng: packet timestamp out of range".into()))?;
+        Ok(FileFrame {
+            interface,
+            link_code: iface.link_code,
+            timestamp_ns,
+            data: body[20..20 + caplen].to_vec(),
+            orig_len: self.u32_at(body, 16),
+        })
+    }
+
+    fn simple_packet(&self, body: &[u8]) -> Result<FileFrame> {
+        if body.len() < 4 {
+            return Err(SimonError::Parse(
+                "pcapng: short simple packet block".into(),
+            ));
+        }
+        let iface = self.interface(0)?;
+        let orig_len = self.u32_at(body, 0);
+        let mut caplen = (orig_len as usize).min(body.len() - 4);
+        if iface.snaplen != 0 {
+            caplen = caplen.min(iface.snaplen as usize);
+        }
+        Ok(FileFrame {
+            interface: 0,
+            link_code: iface.link_code,
+            timestamp_ns: 0,
+            data: body[4..4 + caplen].to_vec(),
+            orig_len,
+        })
+    }
+
+    fn interface(&self, id: u32) -> Result<Interface> {
+        self.interfaces.get(id as usize).copied().ok_or_else(|| {
+            SimonError::Parse(format!("pcapng: packet for undeclared interface {}", id))
+        })
+    }
+
+    /// `(code, value)` pairs up to `opt_endofopt`
+    fn options<'b>(&self, mut buf: &'b [u8]) -> Vec<(u16, &'b [u8])> {
+        let mut options = Vec::new();
+        while buf.len() >= 4 {
+            let code = self.u16_at(buf, 0);
+            let len = self.u16_at(buf, 2) as usize;
+            if code == 0 || buf.len() < 4 + len {
+                break;
+            }
+            options.push((code, &buf[4..4 + len]));
+            buf = &buf[(4 + len + 3) & !3..];
+        }
+        options
+    }
+
+    /// Fill `buf`; false at a clean end of file or after a partial read
+    fn fill(&mut self, buf: &mut [u8]) -> Result<bool> {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.input.read(&mut buf[read..]) {
+                Ok(0) => {
+                    self.truncated |= read > 0;
+                    return Ok(false);
+                }
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(true)
+    }
+
+    fn u16_at(&self, buf: &[u8], off: usize) -> u16 {
+        let bytes = buf[off..off + 2].try_into().unwrap();
+        if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        }
+    }
+
+    fn u32_at(&self, buf: &[u8], off: usize) -> u32 {
+        let bytes = buf[off..off + 4].try_into().unwrap();
+        if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<FileFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet_capture::decode::fixtures::tcp_v4_frame;
+    use crate::packet_capture::PcapngWriter;
+
+    fn pcap_file(big_endian: bool, magic: u32, records: &[(u32, u32, &[u8])]) -> Vec<u8> {
+        let u16b = |v: u16| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let u32b = |v: u32| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let mut f = Vec::new();
+        f.extend_from_slice(&u32b(magic));
+        f.extend_from_slice(&u16b(2));
+        f.extend_from_slice(&u16b(4));
+        f.extend_from_slice(&[0; 8]); // thiszone, sigfigs
+        f.extend_from_slice(&u32b(65535));
+        f.extend_from_slice(&u32b(1)); // Ethernet
+        for &(secs, frac, data) in records {
+            f.extend_from_slice(&u32b(secs));
+            f.extend_from_slice(&u32b(frac));
+            f.extend_from_slice(&u32b(data.len() as u32));
+            f.extend_from_slice(&u32b(data.len() as u32 + 10));
+            f.extend_from_slice(data);
+        }
+        f
+    }
+
+    #[test]
+    fn test_pcap_byte_orders_and_resolutions() {
+        let frame = tcp_v4_frame();
+        for (big_endian, magic, frac, ns) in [
+            (false, PCAP_MAGIC_USEC, 250_000, 250_000_000),
+            (true, PCAP_MAGIC_USEC, 250_000, 250_000_000),
+            (false, PCAP_MAGIC_NSEC, 7, 7),
+            (true, PCAP_MAGIC_NSEC, 7, 7),
+        ] {
+            let file = pcap_file(big_endian, magic, &[(1_700_000_000, frac, &frame)]);
+            let mut reader = CaptureReader::new(&file[..]).unwrap();
+            let f = reader.next_frame().unwrap().unwrap();
+            assert_eq!(f.timestamp_ns, 1_700_000_000_000_000_000 + ns);
+            assert_eq!((f.link_code, f.orig_len), (1, frame.len() as u32 + 10));
+            assert_eq!(f.data, frame);
+            assert!(reader.next_frame().unwrap().is_none());
+            assert!(!reader.truncated());
+        }
+    }
+
+    #[test]
+    fn test_pcap_truncated_record() {
+        let frame = tcp_v4_frame();
+        let file = pcap_file(false, PCAP_MAGIC_USEC, &[(1, 0, &frame), (2, 0, &frame)]);
+        let mut reader = CaptureReader::new(&file[..file.len() - 5]).unwrap();
+        assert!(reader.next_frame().unwrap().is_some());
+        assert!(reader.next_frame().unwrap().is_none());
+        assert!(reader.truncated());
+    }
+
+    #[test]
+    fn test_pcapng_round_trip() {
+        let mut w = PcapngWriter::new(Vec::new()).unwrap();
+        let eth = w.add_interface("eth0", LinkType::Ethernet, 65535).unwrap();
+        let tun = w.add_interface("tun0", LinkType::Raw, 65535).unwrap();
+        let frame = tcp_v4_frame();
+        w.write_packet(eth, 1_700_000_000_123_456_789, &frame, 1514)
+            .unwrap();
+        w.write_packet(tun, 5, &frame[14..], 50).unwrap();
+        let file = w.into_inner();
+
+        let frames: Vec<FileFrame> = CaptureReader::new(&file[..])
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].timestamp_ns, 1_700_000_000_123_456_789);
+        assert_eq!((frames[0].link_code, frames[0].orig_len), (1, 1514));
+        assert_eq!(frames[0].data, frame);
+        assert_eq!((frames[1].interface, frames[1].link_code), (1, 101));
+        assert_eq!(frames[1].payload(), Some((LinkType::Raw, &frame[14..])));
+    }
+
+    /// Little-endian pcapng block
+    fn block(t: u32, body: &[u8]) -> Vec<u8> {
+        let len = (12 + body.len()) as u32;
+        let mut b = t.to_le_bytes().to_vec();
+        b.extend_from_slice(&len.to_le_bytes());
+        b.extend_from_slice(body);
+        b.extend_from_slice(&len.to_le_bytes());
+        b
+    }
+
+    /// Section header block
+    fn shb() -> Vec<u8> {
+        let mut shb = BYTE_ORDER_MAGIC.to_le_bytes().to_vec();
+        shb.extend_from_slice(&[1, 0, 0, 0]);
+        shb.extend_from_slice(&(-1i64).to_le_bytes());
+        block(SHB_TYPE, &shb)
+    }
+
+    /// Enhanced packet block on interface 0 with a 3-byte packet
+    fn epb(units: u64) -> Vec<u8> {
+        let mut epb = vec![0; 4];
+        epb.extend_from_slice(&((units >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(units as u32).to_le_bytes());
+        epb.extend_from_slice(&3u32.to_le_bytes());
+        epb.extend_from_slice(&3u32.to_le_bytes());
+        epb.extend_from_slice(&[0xaa, 0xbb, 0xcc, 0]);
+        block(EPB_TYPE, &epb)
+    }
+
+    #[test]
+    fn test_pcapng_microsecond_default_and_unknown_blocks() {
+        // Hand-built: SHB, a custom block, IDB without if_tsresol, EPB
+        let mut file = shb();
+        file.extend(block(0x0BAD, &[0; 8]));
+        file.extend(block(IDB_TYPE, &[101, 0, 0, 0, 0, 0, 0, 0]));
+        file.extend(epb(1_500_000));
+
+        let mut reader = CaptureReader::new(&file[..]).unwrap();
+        let f = reader.next_frame().unwrap().unwrap();
+        assert_eq!(f.timestamp_ns, 1_500_000_000);
+        assert_eq!(f.data, [0xaa, 0xbb, 0xcc]);
+        assert!(reader.next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_pcapng_timestamp_out_of_range() {
+        // if_tsresol of 1 s makes the largest count ~1.8e19 s, and an
+        // if_tsoffset of i64::MAX overflows even a small one
+        let mut idb = vec![101, 0, 0, 0, 0, 0, 0, 0];
+        idb.extend_from_slice(&[IF_TSRESOL as u8, 0, 1, 0, 0, 0, 0, 0]);
+        idb.extend_from_slice(&[0; 4]);
+        let mut offset = vec![101, 0, 0, 0, 0, 0, 0, 0];
+        offset.extend_from_slice(&[IF_TSOFFSET as u8, 0, 8, 0]);
+        offset.extend_from_slice(&i64::MAX.to_le_bytes());
+        offset.extend_from_slice(&[0; 4]);
+
+        for (idb, units) in [(idb, u64::MAX), (offset, 1_000_000)] {
+            let mut file = shb();
+            file.extend(block(IDB_TYPE, &idb));
+            file.extend(epb(units));
+            let mut reader = CaptureReader::new(&file[..]).unwrap();
+            assert!(matches!(reader.next_frame(), Err(SimonError::Parse(_))));
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_magic() {
+        assert!(matches!(
+            CaptureReader::new(&b"GIF89a.........................."[..]),
+            Err(SimonError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_link_payload() {
+        let ip = &tcp_v4_frame()[14..];
+        let mut sll = vec![0u8; 14];
+        sll.extend_from_slice(&[0x08, 0x00]);
+        sll.extend_from_slice(ip);
+        assert_eq!(link_payload(113, &sll), Some((LinkType::Raw, ip)));
+
+        let mut sll2 = vec![0x86, 0xdd];
+        sll2.extend_from_slice(&[0; 18]);
+        sll2.extend_from_slice(ip);
+        assert_eq!(link_payload(276, &sll2).unwrap().1, ip);
+
+        let mut null = 2u32.to_le_bytes().to_vec();
+        null.extend_from_slice(ip);
+        assert_eq!(link_payload(0, &null), Some((LinkType::Raw, ip)));
+
+        assert_eq!(link_payload(228, ip), Some((LinkType::Raw, ip)));
+        assert_eq!(link_payload(127, ip), None);
+        sll[14] = 0x08;
+        sll[15] = 0x06; // ARP over cooked capture
+        assert_eq!(link_payload(113, &sll), None);
+    }
+}
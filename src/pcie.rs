@@ -253,6 +253,115 @@ impl PcieDevice {
     pub fn is_gpu(&self) -> bool {
         self.device_class.is_gpu()
     }
+
+    /// Read a device from its sysfs directory (`/sys/bus/pci/devices/<bdf>`, or
+    /// a link to it such as `/sys/class/net/<if>/device`)
+    #[cfg(target_os = "linux")]
+    pub fn from_sysfs(path: &std::path::Path) -> Self {
+        use std::fs;
+
+        let bdf = fs::canonicalize(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        // Read vendor and device IDs
+        let vendor_id = PcieMonitor::read_hex_file(&path.join("vendor")).unwrap_or(0) as u16;
+        let device_id = PcieMonitor::read_hex_file(&path.join("device")).unwrap_or(0) as u16;
+        let class_code = PcieMonitor::read_hex_file(&path.join("class")).unwrap_or(0) as u32;
+
+        let device_class = PcieDeviceClass::from_class_code(class_code);
+
+        // Read link information
+        let current_speed_str =
+            fs::read_to_string(path.join("current_link_speed")).unwrap_or_default();
+        let max_speed_str = fs::read_to_string(path.join("max_link_speed")).unwrap_or_default();
+        let current_width_str =
+            fs::read_to_string(path.join("current_link_width")).unwrap_or_default();
+        let max_width_str = fs::read_to_string(path.join("max_link_width")).unwrap_or_default();
+
+        let current_link_speed = PcieLinkSpeed::from_sysfs(&current_speed_str);
+        let max_link_speed = if max_speed_str.trim().is_empty() {
+            None
+        } else {
+            Some(PcieLinkSpeed::from_sysfs(&max_speed_str))
+        };
+
+        let current_link_width = current_width_str.trim().parse::<u8>().unwrap_or(0);
+        let max_link_width = max_width_str.trim().parse::<u8>().unwrap_or(0);
+
+        // Read driver
+        let driver = fs::read_link(path.join("driver"))
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
+
+        // Read NUMA node
+        let numa_node = fs::read_to_string(path.join("numa_node"))
+            .ok()
+            .and_then(|s| s.trim().parse::<i32>().ok())
+            .unwrap_or(-1);
+
+        // Read IOMMU group
+        let iommu_group = fs::read_link(path.join("iommu_group")).ok().and_then(|p| {
+            p.file_name()
+                .and_then(|n| n.to_string_lossy().parse::<u32>().ok())
+        });
+
+        // Read subsystem
+        let subsys_vendor = PcieMonitor::read_hex_file(&path.join("subsystem_vendor"))
+            .map(|v| format!("{:04x}", v));
+        let subsys_device = PcieMonitor::read_hex_file(&path.join("subsystem_device"))
+            .map(|v| format!("{:04x}", v));
+        let subsystem = match (subsys_vendor, subsys_device) {
+            (Some(v), Some(d)) => Some(format!("{}:{}", v, d)),
+            _ => None,
+        };
+
+        // Power state
+        let power_state = fs::read_to_string(path.join("power_state"))
+            .ok()
+            .map(|s| s.trim().to_string());
+
+        // Vendor name lookup
+        let vendor_name = match vendor_id {
+            0x10DE => "NVIDIA".to_string(),
+            0x1002 => "AMD".to_string(),
+            0x8086 => "Intel".to_string(),
+            0x14E4 => "Broadcom".to_string(),
+            0x1B73 => "Fresco Logic".to_string(),
+            0x1912 => "Renesas".to_string(),
+            0x1B21 => "ASMedia".to_string(),
+            0x1B4B => "Marvell".to_string(),
+            0x15B3 => "Mellanox".to_string(),
+            0x144D => "Samsung".to_string(),
+            0x1C5C | 0x1E0F => "SK Hynix".to_string(),
+            0x1179 | 0xC0A9 => "Toshiba / Kioxia".to_string(),
+            0x126F => "Silicon Motion".to_string(),
+            0x1987 | 0x1E4B => "Phison".to_string(),
+            _ => format!("{:04x}", vendor_id),
+        };
+
+        let name = format!("{} {} [{}]", vendor_name, device_class, bdf);
+
+        PcieDevice {
+            bdf,
+            vendor_id,
+            device_id,
+            device_class,
+            name,
+            vendor_name,
+            numa_node,
+            iommu_group,
+            current_link_speed,
+            max_link_speed,
+            current_link_width,
+            max_link_width,
+            driver,
+            subsystem,
+            power_state,
+        }
+    }
 }
 
 /// PCIe bus monitor
@@ -310,114 +419,14 @@ impl PcieMonitor {
         })?;
 
         for entry in entries.flatten() {
-            let path = entry.path();
-            let bdf = entry.file_name().to_string_lossy().to_string();
-
-            // Read vendor and device IDs
-            let vendor_id = Self::read_hex_file(&path.join("vendor")).unwrap_or(0) as u16;
-            let device_id = Self::read_hex_file(&path.join("device")).unwrap_or(0) as u16;
-            let class_code = Self::read_hex_file(&path.join("class")).unwrap_or(0) as u32;
-
-            let device_class = PcieDeviceClass::from_class_code(class_code);
-
-            // Read link information
-            let current_speed_str =
-                fs::read_to_string(path.join("current_link_speed")).unwrap_or_default();
-            let max_speed_str = fs::read_to_string(path.join("max_link_speed")).unwrap_or_default();
-            let current_width_str =
-                fs::read_to_string(path.join("current_link_width")).unwrap_or_default();
-            let max_width_str = fs::read_to_string(path.join("max_link_width")).unwrap_or_default();
-
-            let current_link_speed = PcieLinkSpeed::from_sysfs(&current_speed_str);
-            let max_link_speed = if max_speed_str.trim().is_empty() {
-                None
-            } else {
-                Some(PcieLinkSpeed::from_sysfs(&max_speed_str))
-            };
-
-            let current_link_width = current_width_str.trim().parse::<u8>().unwrap_or(0);
-            let max_link_width = max_width_str.trim().parse::<u8>().unwrap_or(0);
-
-            // Skip devices with no link info (bridges, etc. with width 0)
-            // but include if class is known
-            if current_link_width == 0 && !device_class.is_gpu() {
-                // Still include bridges and endpoint devices
-                if matches!(device_class, PcieDeviceClass::Bridge) {
-                    // skip pure bridges
-                    continue;
-                }
+            let device = PcieDevice::from_sysfs(&entry.path());
+            // Skip pure bridges (no link of their own)
+            if device.current_link_width == 0
+                && matches!(device.device_class, PcieDeviceClass::Bridge)
+            {
+                continue;
             }
-
-            // Read driver
-            let driver = fs::read_link(path.join("driver"))
-                .ok()
-                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
-
-            // Read NUMA node
-            let numa_node = fs::read_to_string(path.join("numa_node"))
-                .ok()
-                .and_then(|s| s.trim().parse::<i32>().ok())
-                .unwrap_or(-1);
-
-            // Read IOMMU group
-            let iommu_group = fs::read_link(path.join("iommu_group")).ok().and_then(|p| {
-                p.file_name()
-                    .and_then(|n| n.to_string_lossy().parse::<u32>().ok())
-            });
-
-            // Read subsystem
-            let subsys_vendor =
-                Self::read_hex_file(&path.join("subsystem_vendor")).map(|v| format!("{:04x}", v));
-            let subsys_device =
-                Self::read_hex_file(&path.join("subsystem_device")).map(|v| format!("{:04x}", v));
-            let subsystem = match (subsys_vendor, subsys_device) {
-                (Some(v), Some(d)) => Some(format!("{}:{}", v, d)),
-                _ => None,
-            };
-
-            // Power state
-            let power_state = fs::read_to_string(path.join("power_state"))
-                .ok()
-                .map(|s| s.trim().to_string());
-
-            // Vendor name lookup
-            let vendor_name = match vendor_id {
-                0x10DE => "NVIDIA".to_string(),
-                0x1002 => "AMD".to_string(),
-                0x8086 => "Intel".to_string(),
-                0x14E4 => "Broadcom".to_string(),
-                0x1B73 => "Fresco Logic".to_string(),
-                0x1912 => "Renesas".to_string(),
-                0x1B21 => "ASMedia".to_string(),
-                0x1B4B => "Marvell".to_string(),
-                0x15B3 => "Mellanox".to_string(),
-                0x144D => "Samsung".to_string(),
-                0x1C5C | 0x1E0F => "SK Hynix".to_string(),
-                0x1179 | 0xC0A9 => "Toshiba / Kioxia".to_string(),
-                0x126F => "Silicon Motion".to_string(),
-                0x1987 | 0x1E4B => "Phison".to_string(),
-                _ => format!("{:04x}", vendor_id),
-            };
-
-            let name = format!("{} {} [{}]", vendor_name, device_class, bdf);
-
-            devices.push(PcieDevice {
-                bdf,
-                vendor_id,
-                device_id,
-                device_class,
-                name,
-                vendor_name,
-                numa_node,
-                iommu_group,
-                current_link_speed,
-                max_link_speed,
-                current_link_width,
-                max_link_width,
-                driver,
-                subsystem,
-                power_state,
-            });
+            devices.push(device);
         }
 
         // Sort by BDF address
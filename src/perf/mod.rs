@@ -0,0 +1,837 @@
+//! Hardware performance counters via `perf_event_open(2)`
+//!
+//! Counts cycles, instructions, last-level cache misses, branch misses and
+//! stalled cycles per CPU (system-wide) or per process, plus DRAM bandwidth
+//! from uncore memory controller PMUs. Derived metrics (IPC, miss rates, MPKI)
+//! are computed from counter deltas between reads.
+//!
+//! Access is governed by `/proc/sys/kernel/perf_event_paranoid`:
+//!
+//! | level | unprivileged users may open                     |
+//! |-------|-------------------------------------------------|
+//! | -1    | everything                                      |
+//! | 0     | per-CPU and per-process counters                |
+//! | 1     | per-process counters (user + kernel)            |
+//! | 2     | per-process counters (user space only)          |
+//! | >= 3  | nothing (Debian/Android hardening patch)        |
+//!
+//! Root, `CAP_PERFMON` and `CAP_SYS_ADMIN` bypass the limit. Counters that
+//! cannot be opened are reported in [`CounterSet::unavailable`] rather than
+//! failing the whole set, so virtual machines without a PMU still get software
+//! counters.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use simonlib::perf::{CounterSet, PerfCounter, PerfTarget};
+//!
+//! let mut set = CounterSet::open(PerfTarget::Process(0), PerfCounter::HARDWARE)?;
+//! std::thread::sleep(std::time::Duration::from_millis(100));
+//! let values = set.read();
+//! if let Some(ipc) = values.ipc() {
+//!     println!("IPC {:.2}", ipc);
+//! }
+//! # Ok::<(), simonlib::SimonError>(())
+//! ```
+
+#[cfg(target_os = "linux")]
+mod sys;
+pub mod uncore;
+
+pub use uncore::{MemoryBandwidth, MemoryBandwidthCounters};
+
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// A generalized hardware or software perf event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PerfCounter {
+    Cycles,
+    Instructions,
+    CacheReferences,
+    /// `PERF_COUNT_HW_CACHE_MISSES`, which the kernel maps to LLC misses
+    LlcMisses,
+    BranchInstructions,
+    BranchMisses,
+    StalledCyclesFrontend,
+    StalledCyclesBackend,
+    TaskClock,
+    ContextSwitches,
+    PageFaults,
+    CpuMigrations,
+}
+
+impl PerfCounter {
+    /// Hardware PMU events
+    pub const HARDWARE: &'static [PerfCounter] = &[
+        PerfCounter::Cycles,
+        PerfCounter::Instructions,
+        PerfCounter::CacheReferences,
+        PerfCounter::LlcMisses,
+        PerfCounter::BranchInstructions,
+        PerfCounter::BranchMisses,
+        PerfCounter::StalledCyclesFrontend,
+        PerfCounter::StalledCyclesBackend,
+    ];
+
+    /// Kernel software events, available without a PMU (VMs, containers)
+    pub const SOFTWARE: &'static [PerfCounter] = &[
+        PerfCounter::TaskClock,
+        PerfCounter::ContextSwitches,
+        PerfCounter::PageFaults,
+        PerfCounter::CpuMigrations,
+    ];
+
+    /// Metric-friendly name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cycles => "cycles",
+            Self::Instructions => "instructions",
+            Self::CacheReferences => "cache_references",
+            Self::LlcMisses => "llc_misses",
+            Self::BranchInstructions => "branch_instructions",
+            Self::BranchMisses => "branch_misses",
+            Self::StalledCyclesFrontend => "stalled_cycles_frontend",
+            Self::StalledCyclesBackend => "stalled_cycles_backend",
+            Self::TaskClock => "task_clock_ns",
+            Self::ContextSwitches => "context_switches",
+            Self::PageFaults => "page_faults",
+            Self::CpuMigrations => "cpu_migrations",
+        }
+    }
+
+    pub fn is_software(&self) -> bool {
+        Self::SOFTWARE.contains(self)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn attr(&self) -> sys::PerfEventAttr {
+        let (type_, config) = match self {
+            Self::Cycles => (sys::PERF_TYPE_HARDWARE, sys::PERF_COUNT_HW_CPU_CYCLES),
+            Self::Instructions => (sys::PERF_TYPE_HARDWARE, sys::PERF_COUNT_HW_INSTRUCTIONS),
+            Self::CacheReferences => (sys::PERF_TYPE_HARDWARE, sys::PERF_COUNT_HW_CACHE_REFERENCES),
+            Self::LlcMisses => (sys::PERF_TYPE_HARDWARE, sys::PERF_COUNT_HW_CACHE_MISSES),
+            Self::BranchInstructions => (
+                sys::PERF_TYPE_HARDWARE,
+                sys::PERF_COUNT_HW_BRANCH_INSTRUCTIONS,
+            ),
+            Self::BranchMisses => (sys::PERF_TYPE_HARDWARE, sys::PERF_COUNT_HW_BRANCH_MISSES),
+            Self::StalledCyclesFrontend => (
+                sys::PERF_TYPE_HARDWARE,
+                sys::PERF_COUNT_HW_STALLED_CYCLES_FRONTEND,
+            ),
+            Self::StalledCyclesBackend => (
+                sys::PERF_TYPE_HARDWARE,
+                sys::PERF_COUNT_HW_STALLED_CYCLES_BACKEND,
+            ),
+            Self::TaskClock => (sys::PERF_TYPE_SOFTWARE, sys::PERF_COUNT_SW_TASK_CLOCK),
+            Self::ContextSwitches => (sys::PERF_TYPE_SOFTWARE, sys::PERF_COUNT_SW_CONTEXT_SWITCHES),
+            Self::PageFaults => (sys::PERF_TYPE_SOFTWARE, sys::PERF_COUNT_SW_PAGE_FAULTS),
+            Self::CpuMigrations => (sys::PERF_TYPE_SOFTWARE, sys::PERF_COUNT_SW_CPU_MIGRATIONS),
+        };
+        sys::PerfEventAttr::counting(type_, config)
+    }
+}
+
+impl std::fmt::Display for PerfCounter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What a counter set observes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PerfTarget {
+    /// Every task on one CPU
+    Cpu(u32),
+    /// One process and its threads on every CPU; 0 is the calling process
+    Process(u32),
+}
+
+/// Effective perf_event permissions for the current user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PerfAccess {
+    /// Privileged or paranoid -1
+    Full,
+    /// Per-CPU and per-process counters
+    CpuWide,
+    /// Per-process counters including kernel time
+    Process,
+    /// Per-process counters, user space only
+    ProcessUserOnly,
+    /// perf_event_open is disabled for this user
+    Denied,
+}
+
+impl PerfAccess {
+    /// Access level for a paranoid setting
+    pub fn from_paranoid(level: i32, privileged: bool) -> Self {
+        if privileged || level < 0 {
+            Self::Full
+        } else {
+            match level {
+                0 => Self::CpuWide,
+                1 => Self::Process,
+                2 => Self::ProcessUserOnly,
+                _ => Self::Denied,
+            }
+        }
+    }
+
+    /// Detect from `/proc/sys/kernel/perf_event_paranoid` and process credentials
+    pub fn detect() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            // Kernel default when the file is missing is 2
+            let level = paranoid_level(Path::new("/")).unwrap_or(2);
+            Self::from_paranoid(level, is_privileged())
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Self::Denied
+        }
+    }
+
+    pub fn allows_cpu_wide(&self) -> bool {
+        matches!(self, Self::Full | Self::CpuWide)
+    }
+
+    pub fn allows_process(&self) -> bool {
+        !matches!(self, Self::Denied)
+    }
+
+    /// Whether kernel-mode counting must be excluded
+    pub fn user_only(&self) -> bool {
+        matches!(self, Self::ProcessUserOnly)
+    }
+
+    /// Human-readable hint on how to widen access
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Full | Self::CpuWide => None,
+            Self::Process | Self::ProcessUserOnly => {
+                Some("per-CPU counters need kernel.perf_event_paranoid <= 0 or CAP_PERFMON")
+            }
+            Self::Denied => {
+                Some("perf events disabled: lower kernel.perf_event_paranoid or grant CAP_PERFMON")
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for PerfAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Full => "full",
+            Self::CpuWide => "cpu-wide",
+            Self::Process => "process",
+            Self::ProcessUserOnly => "process (user only)",
+            Self::Denied => "denied",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Read `kernel.perf_event_paranoid` under `root`
+pub fn paranoid_level(root: &Path) -> Option<i32> {
+    std::fs::read_to_string(root.join("proc/sys/kernel/perf_event_paranoid"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Root, or `CAP_PERFMON` (38) / `CAP_SYS_ADMIN` (21) in the effective set
+#[cfg(target_os = "linux")]
+fn is_privileged() -> bool {
+    // SAFETY: geteuid has no preconditions
+    if unsafe { libc::geteuid() } == 0 {
+        return true;
+    }
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|l| l.strip_prefix("CapEff:"))
+                .and_then(|hex| u64::from_str_radix(hex.trim(), 16).ok())
+        })
+        .map(|caps| caps & (1 << 38 | 1 << 21) != 0)
+        .unwrap_or(false)
+}
+
+/// Counter delta corrected for multiplexing: `value * enabled / running`
+fn scaled_delta(last: (u64, u64, u64), now: (u64, u64, u64)) -> f64 {
+    let value = now.0.wrapping_sub(last.0) as f64;
+    let enabled = now.1.wrapping_sub(last.1) as f64;
+    let running = now.2.wrapping_sub(last.2) as f64;
+    if running <= 0.0 {
+        0.0
+    } else if enabled > running {
+        value * enabled / running
+    } else {
+        value
+    }
+}
+
+/// Counter deltas over one interval with derived metrics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CounterValues {
+    pub counts: BTreeMap<PerfCounter, f64>,
+}
+
+impl CounterValues {
+    pub fn get(&self, counter: PerfCounter) -> Option<f64> {
+        self.counts.get(&counter).copied()
+    }
+
+    /// Add another set's counts (e.g. to aggregate CPUs)
+    pub fn merge(&mut self, other: &CounterValues) {
+        for (counter, value) in &other.counts {
+            *self.counts.entry(*counter).or_insert(0.0) += value;
+        }
+    }
+
+    fn ratio(&self, num: PerfCounter, den: PerfCounter) -> Option<f64> {
+        let den = self.get(den)?;
+        if den <= 0.0 {
+            return None;
+        }
+        Some(self.get(num)? / den)
+    }
+
+    /// Instructions per cycle
+    pub fn ipc(&self) -> Option<f64> {
+        self.ratio(PerfCounter::Instructions, PerfCounter::Cycles)
+    }
+
+    /// LLC misses per cache reference
+    pub fn llc_miss_rate(&self) -> Option<f64> {
+        self.ratio(PerfCounter::LlcMisses, PerfCounter::CacheReferences)
+    }
+
+    /// LLC misses per thousand instructions
+    pub fn llc_mpki(&self) -> Option<f64> {
+        self.ratio(PerfCounter::LlcMisses, PerfCounter::Instructions)
+            .map(|r| r * 1000.0)
+    }
+
+    /// Branch misses per branch instruction
+    pub fn branch_miss_rate(&self) -> Option<f64> {
+        self.ratio(PerfCounter::BranchMisses, PerfCounter::BranchInstructions)
+    }
+
+    /// Fraction of cycles stalled in the front end (fetch/decode)
+    pub fn frontend_stall_rate(&self) -> Option<f64> {
+        self.ratio(PerfCounter::StalledCyclesFrontend, PerfCounter::Cycles)
+    }
+
+    /// Fraction of cycles stalled in the back end (execution/memory)
+    pub fn backend_stall_rate(&self) -> Option<f64> {
+        self.ratio(PerfCounter::StalledCyclesBackend, PerfCounter::Cycles)
+    }
+}
+
+/// A counter that could not be opened and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnavailableCounter {
+    pub counter: PerfCounter,
+    pub reason: String,
+}
+
+#[cfg(target_os = "linux")]
+struct OpenCounter {
+    counter: PerfCounter,
+    file: std::fs::File,
+    last: (u64, u64, u64),
+}
+
+/// A group of independently opened counters on one target
+pub struct CounterSet {
+    target: PerfTarget,
+    #[cfg(target_os = "linux")]
+    counters: Vec<OpenCounter>,
+    unavailable: Vec<UnavailableCounter>,
+}
+
+impl CounterSet {
+    /// Open `events` on `target` using the detected access level
+    pub fn open(target: PerfTarget, events: &[PerfCounter]) -> Result<Self> {
+        Self::open_with_access(target, events, PerfAccess::detect())
+    }
+
+    /// Open `events` on `target`
+    ///
+    /// Events the PMU lacks or that are not permitted are skipped and listed in
+    /// [`CounterSet::unavailable`]; fails only when nothing could be opened.
+    #[cfg(target_os = "linux")]
+    pub fn open_with_access(
+        target: PerfTarget,
+        events: &[PerfCounter],
+        access: PerfAccess,
+    ) -> Result<Self> {
+        let (pid, cpu, inherit) = match target {
+            PerfTarget::Cpu(cpu) => (-1, cpu as i32, false),
+            PerfTarget::Process(pid) => (pid as i32, -1, true),
+        };
+        let mut counters = Vec::new();
+        let mut unavailable = Vec::new();
+        let mut denied = false;
+        for &counter in events {
+            let mut attr = counter.attr();
+            if inherit {
+                attr = attr.inherit();
+            }
+            if access.user_only() {
+                attr = attr.exclude_kernel();
+            }
+            let mut result = sys::perf_event_open(&attr, pid, cpu);
+            // Retry user-only when kernel counting is what was refused
+            if is_permission_error(&result) && !access.user_only() {
+                result = sys::perf_event_open(&attr.exclude_kernel(), pid, cpu);
+            }
+            match result {
+                Ok(file) => {
+                    let _ = sys::enable(&file);
+                    let last = sys::read_counter(&file).unwrap_or_default();
+                    counters.push(OpenCounter {
+                        counter,
+                        file,
+                        last,
+                    });
+                }
+                Err(e) => {
+                    let reason = match e.raw_os_error() {
+                        Some(libc::EACCES) | Some(libc::EPERM) => {
+                            denied = true;
+                            format!("permission denied (access: {})", access)
+                        }
+                        Some(libc::ENOENT) | Some(libc::EOPNOTSUPP) | Some(libc::EINVAL) => {
+                            "not supported by this PMU".to_string()
+                        }
+                        Some(libc::ESRCH) => {
+                            return Err(SimonError::NotFound(format!("process {}", pid)))
+                        }
+                        _ => e.to_string(),
+                    };
+                    unavailable.push(UnavailableCounter { counter, reason });
+                }
+            }
+        }
+        if counters.is_empty() && !events.is_empty() {
+            let detail = access.hint().unwrap_or("no counters could be opened");
+            return Err(if denied {
+                SimonError::PermissionDenied(detail.to_string())
+            } else {
+                SimonError::FeatureNotAvailable(format!(
+                    "perf counters unavailable on {:?}",
+                    target
+                ))
+            });
+        }
+        Ok(Self {
+            target,
+            counters,
+            unavailable,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open_with_access(
+        _target: PerfTarget,
+        _events: &[PerfCounter],
+        _access: PerfAccess,
+    ) -> Result<Self> {
+        Err(SimonError::UnsupportedPlatform(
+            "perf_event_open requires Linux".into(),
+        ))
+    }
+
+    pub fn target(&self) -> PerfTarget {
+        self.target
+    }
+
+    /// Counters that were requested but could not be opened
+    pub fn unavailable(&self) -> &[UnavailableCounter] {
+        &self.unavailable
+    }
+
+    /// Counters that are live
+    pub fn counters(&self) -> Vec<PerfCounter> {
+        #[cfg(target_os = "linux")]
+        {
+            self.counters.iter().map(|c| c.counter).collect()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Vec::new()
+        }
+    }
+
+    /// Deltas since the previous read (or since opening)
+    pub fn read(&mut self) -> CounterValues {
+        let mut values = CounterValues::default();
+        #[cfg(target_os = "linux")]
+        for counter in &mut self.counters {
+            if let Ok(now) = sys::read_counter(&counter.file) {
+                values
+                    .counts
+                    .insert(counter.counter, scaled_delta(counter.last, now));
+                counter.last = now;
+            }
+        }
+        values
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_permission_error(result: &std::io::Result<std::fs::File>) -> bool {
+    matches!(
+        result.as_ref().map_err(|e| e.raw_os_error()),
+        Err(Some(libc::EACCES)) | Err(Some(libc::EPERM))
+    )
+}
+
+/// Counter deltas for one CPU
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuCounters {
+    pub cpu: u32,
+    pub values: CounterValues,
+}
+
+/// One system-wide sample
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerfSnapshot {
+    /// Unix time of the sample
+    pub timestamp: u64,
+    pub elapsed_secs: f64,
+    pub access: PerfAccess,
+    /// Sum over all CPUs
+    pub total: CounterValues,
+    pub per_cpu: Vec<CpuCounters>,
+    pub memory_bandwidth: Option<MemoryBandwidth>,
+    /// Events or sources that could not be opened, with reasons
+    pub unavailable: Vec<String>,
+}
+
+impl PerfSnapshot {
+    /// Events per second for a counter in `total`
+    pub fn rate(&self, counter: PerfCounter) -> Option<f64> {
+        if self.elapsed_secs <= 0.0 {
+            return None;
+        }
+        self.total.get(counter).map(|v| v / self.elapsed_secs)
+    }
+}
+
+/// Sampler configuration (`[perf]` in the daemon config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerfConfig {
+    pub enabled: bool,
+    /// Sampling interval in milliseconds
+    pub interval_ms: u64,
+    /// Events to count on every CPU
+    pub events: Vec<PerfCounter>,
+    /// Keep per-CPU values in snapshots (totals are always reported)
+    pub per_cpu: bool,
+    /// Read uncore memory controller bandwidth
+    pub memory_bandwidth: bool,
+}
+
+impl Default for PerfConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 1000,
+            // Software events keep the sampler useful on VMs without a PMU
+            events: PerfCounter::HARDWARE
+                .iter()
+                .chain(&[
+                    PerfCounter::ContextSwitches,
+                    PerfCounter::CpuMigrations,
+                    PerfCounter::PageFaults,
+                ])
+                .copied()
+                .collect(),
+            per_cpu: true,
+            memory_bandwidth: true,
+        }
+    }
+}
+
+/// System-wide counters on every online CPU
+pub struct PerfMonitor {
+    config: PerfConfig,
+    access: PerfAccess,
+    cpus: Vec<CounterSet>,
+    memory: Option<MemoryBandwidthCounters>,
+    unavailable: Vec<String>,
+    last: Instant,
+}
+
+impl PerfMonitor {
+    /// Open counters on every online CPU
+    ///
+    /// Fails with `PermissionDenied` when the paranoid level only allows
+    /// per-process counters; use [`CounterSet`] with [`PerfTarget::Process`]
+    /// in that case.
+    pub fn new(config: PerfConfig) -> Result<Self> {
+        let access = PerfAccess::detect();
+        if !access.allows_cpu_wide() {
+            return Err(SimonError::PermissionDenied(
+                access.hint().unwrap_or_default().to_string(),
+            ));
+        }
+        let mut cpus = Vec::new();
+        let mut unavailable = BTreeMap::new();
+        let mut last_err = None;
+        for cpu in online_cpus() {
+            match CounterSet::open_with_access(PerfTarget::Cpu(cpu), &config.events, access) {
+                Ok(set) => {
+                    for u in set.unavailable() {
+                        unavailable.insert(u.counter, u.reason.clone());
+                    }
+                    cpus.push(set);
+                }
+                // Offline or isolated CPUs
+                Err(e) => last_err = Some(e),
+            }
+        }
+        if cpus.is_empty() {
+            return Err(last_err.unwrap_or_else(|| {
+                SimonError::FeatureNotAvailable("no online CPUs found".into())
+            }));
+        }
+        let mut unavailable: Vec<String> = unavailable
+            .into_iter()
+            .map(|(c, reason)| format!("{}: {}", c, reason))
+            .collect();
+
+        let memory = if config.memory_bandwidth {
+            match MemoryBandwidthCounters::open() {
+                Ok(m) => Some(m),
+                Err(e) => {
+                    unavailable.push(format!("memory_bandwidth: {}", e));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            config,
+            access,
+            cpus,
+            memory,
+            unavailable,
+            last: Instant::now(),
+        })
+    }
+
+    pub fn access(&self) -> PerfAccess {
+        self.access
+    }
+
+    /// Counter deltas since the previous sample
+    pub fn sample(&mut self) -> PerfSnapshot {
+        let now = Instant::now();
+        let elapsed_secs = now.duration_since(self.last).as_secs_f64();
+        self.last = now;
+
+        let mut total = CounterValues::default();
+        let mut per_cpu = Vec::new();
+        for set in &mut self.cpus {
+            let values = set.read();
+            total.merge(&values);
+            if self.config.per_cpu {
+                if let PerfTarget::Cpu(cpu) = set.target() {
+                    per_cpu.push(CpuCounters { cpu, values });
+                }
+            }
+        }
+        let memory_bandwidth = self.memory.as_mut().map(|m| m.read(elapsed_secs));
+
+        PerfSnapshot {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            elapsed_secs,
+            access: self.access,
+            total,
+            per_cpu,
+            memory_bandwidth,
+            unavailable: self.unavailable.clone(),
+        }
+    }
+}
+
+fn online_cpus() -> Vec<u32> {
+    let online = std::fs::read_to_string("/sys/devices/system/cpu/online").unwrap_or_default();
+    let mut cpus = Vec::new();
+    for part in online.trim().split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((a, b)) => {
+                if let (Ok(a), Ok(b)) = (a.parse::<u32>(), b.parse::<u32>()) {
+                    cpus.extend(a..=b);
+                }
+            }
+            None => cpus.extend(part.parse::<u32>().ok()),
+        }
+    }
+    if cpus.is_empty() {
+        let n = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        cpus.extend(0..n as u32);
+    }
+    cpus
+}
+
+/// Latest snapshot shared between the sampling thread and readers
+pub type SharedPerfSnapshot = Arc<RwLock<Option<PerfSnapshot>>>;
+
+/// Start sampling on a background thread
+pub fn spawn(config: PerfConfig) -> Result<PerfHandle> {
+    let interval = Duration::from_millis(config.interval_ms.max(100));
+    let mut monitor = PerfMonitor::new(config)?;
+    let snapshot: SharedPerfSnapshot = Arc::new(RwLock::new(None));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let shared = Arc::clone(&snapshot);
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-perf".into())
+        .spawn(move || {
+            while !flag.load(Ordering::SeqCst) {
+                std::thread::sleep(interval);
+                let sample = monitor.sample();
+                if let Ok(mut slot) = shared.write() {
+                    *slot = Some(sample);
+                }
+            }
+        })
+        .map_err(|e| SimonError::Other(format!("Failed to spawn perf sampling thread: {}", e)))?;
+
+    Ok(PerfHandle {
+        snapshot,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+/// Handle to a running perf sampling thread
+pub struct PerfHandle {
+    snapshot: SharedPerfSnapshot,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PerfHandle {
+    /// Shared snapshot slot for readers
+    pub fn snapshot(&self) -> SharedPerfSnapshot {
+        Arc::clone(&self.snapshot)
+    }
+
+    /// Stop sampling and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for PerfHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    #[test]
+    fn test_access_levels() {
+        assert_eq!(PerfAccess::from_paranoid(-1, false), PerfAccess::Full);
+        assert_eq!(PerfAccess::from_paranoid(0, false), PerfAccess::CpuWide);
+        assert_eq!(PerfAccess::from_paranoid(1, false), PerfAccess::Process);
+        assert_eq!(
+            PerfAccess::from_paranoid(2, false),
+            PerfAccess::ProcessUserOnly
+        );
+        assert_eq!(PerfAccess::from_paranoid(4, false), PerfAccess::Denied);
+        assert_eq!(PerfAccess::from_paranoid(4, true), PerfAccess::Full);
+        assert!(PerfAccess::ProcessUserOnly.user_only());
+        assert!(!PerfAccess::Process.allows_cpu_wide());
+        assert!(PerfAccess::Process.allows_process());
+        assert!(!PerfAccess::Denied.allows_process());
+
+        let root = FakeRoot::new("perf-paranoid");
+        root.write("proc/sys/kernel/perf_event_paranoid", "2\n");
+        assert_eq!(paranoid_level(root.path()), Some(2));
+    }
+
+    #[test]
+    fn test_derived_metrics_and_scaling() {
+        // Counter ran half the time it was enabled: scale x2
+        assert_eq!(scaled_delta((100, 0, 0), (600, 1000, 500)), 1000.0);
+        assert_eq!(scaled_delta((0, 0, 0), (500, 1000, 1000)), 500.0);
+        assert_eq!(scaled_delta((0, 0, 0), (500, 1000, 0)), 0.0);
+
+        let mut a = CounterValues::default();
+        a.counts.insert(PerfCounter::Cycles, 1000.0);
+        a.counts.insert(PerfCounter::Instructions, 1500.0);
+        a.counts.insert(PerfCounter::CacheReferences, 100.0);
+        a.counts.insert(PerfCounter::LlcMisses, 10.0);
+        a.counts.insert(PerfCounter::BranchInstructions, 200.0);
+        a.counts.insert(PerfCounter::BranchMisses, 4.0);
+        a.counts.insert(PerfCounter::StalledCyclesBackend, 250.0);
+        let mut b = a.clone();
+        b.counts.insert(PerfCounter::Cycles, 1000.0);
+        b.counts.insert(PerfCounter::Instructions, 500.0);
+        a.merge(&b);
+
+        assert_eq!(a.ipc(), Some(1.0));
+        assert_eq!(a.llc_miss_rate(), Some(0.1));
+        assert_eq!(a.llc_mpki(), Some(10.0));
+        assert_eq!(a.branch_miss_rate(), Some(0.02));
+        assert_eq!(a.backend_stall_rate(), Some(0.25));
+        assert_eq!(a.frontend_stall_rate(), None);
+        assert_eq!(CounterValues::default().ipc(), None);
+    }
+
+    /// Software events on the test process itself; skipped when perf_event_open
+    /// is unavailable (paranoid >= 3, seccomp in containers, non-Linux)
+    #[test]
+    fn test_software_counters_on_self() {
+        let mut set = match CounterSet::open(PerfTarget::Process(0), PerfCounter::SOFTWARE) {
+            Ok(set) => set,
+            Err(e) => {
+                eprintln!("skipping: {}", e);
+                return;
+            }
+        };
+        assert!(set.counters().contains(&PerfCounter::TaskClock));
+        // Burn some CPU so task-clock advances
+        let mut x = 0u64;
+        for i in 0..2_000_000u64 {
+            x = x.wrapping_mul(31).wrapping_add(i);
+        }
+        std::hint::black_box(x);
+        let values = set.read();
+        assert!(values.get(PerfCounter::TaskClock).unwrap_or(0.0) > 0.0);
+    }
+}
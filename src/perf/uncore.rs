@@ -0,0 +1,440 @@
+//! Uncore memory controller PMUs (Intel IMC, AMD DF/UMC) for DRAM bandwidth
+//!
+//! Dynamic PMUs are described in `/sys/bus/event_source/devices/<pmu>/`:
+//! `type` is the `perf_event_attr.type` to use, `cpumask` lists the CPUs the
+//! counters must be opened on (one per socket), `format/*` maps event terms to
+//! config bits and `events/*` holds named events with optional `.scale`/`.unit`.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[cfg(target_os = "linux")]
+use super::sys;
+use crate::error::{Result, SimonError};
+
+/// PMU name prefixes of memory controllers
+const MEMORY_PMU_PREFIXES: &[&str] = &["uncore_imc", "amd_df", "amd_umc"];
+
+/// Event names counting DRAM reads, by PMU generation
+const READ_EVENTS: &[&str] = &["cas_count_read", "data_reads", "data_read"];
+/// Event names counting DRAM writes, by PMU generation
+const WRITE_EVENTS: &[&str] = &["cas_count_write", "data_writes", "data_write"];
+
+/// Traffic direction of an uncore event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Read,
+    Write,
+}
+
+/// A named uncore event resolved to raw config values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UncoreEvent {
+    pub name: String,
+    pub direction: Direction,
+    pub config: u64,
+    pub config1: u64,
+    pub config2: u64,
+    /// Bytes per counted unit
+    pub bytes_per_count: f64,
+}
+
+/// A memory controller PMU and its bandwidth events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UncorePmu {
+    pub name: String,
+    /// `perf_event_attr.type`
+    pub pmu_type: u32,
+    /// CPUs to open counters on
+    pub cpus: Vec<u32>,
+    pub events: Vec<UncoreEvent>,
+}
+
+/// Config word a format term writes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigField {
+    Config,
+    Config1,
+    Config2,
+}
+
+/// A parsed `format/<term>` entry, e.g. "config:0-7,32-35"
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FormatSpec {
+    field: ConfigField,
+    /// Inclusive bit ranges, low bits of the value first
+    ranges: Vec<(u32, u32)>,
+}
+
+impl FormatSpec {
+    fn parse(s: &str) -> Option<Self> {
+        let (field, bits) = s.trim().split_once(':')?;
+        let field = match field {
+            "config" => ConfigField::Config,
+            "config1" => ConfigField::Config1,
+            "config2" => ConfigField::Config2,
+            _ => return None,
+        };
+        let mut ranges = Vec::new();
+        for part in bits.split(',') {
+            let (lo, hi) = match part.split_once('-') {
+                Some((lo, hi)) => (lo.parse().ok()?, hi.parse().ok()?),
+                None => {
+                    let bit = part.parse().ok()?;
+                    (bit, bit)
+                }
+            };
+            if lo > hi || hi > 63 {
+                return None;
+            }
+            ranges.push((lo, hi));
+        }
+        Some(Self { field, ranges })
+    }
+
+    /// Scatter `value` into the spec's bit ranges
+    fn encode(&self, mut value: u64) -> u64 {
+        let mut out = 0u64;
+        for &(lo, hi) in &self.ranges {
+            let width = hi - lo + 1;
+            let mask = if width == 64 {
+                u64::MAX
+            } else {
+                (1u64 << width) - 1
+            };
+            out |= (value & mask) << lo;
+            value = if width == 64 { 0 } else { value >> width };
+        }
+        out
+    }
+}
+
+/// Resolve an event string such as "event=0x04,umask=0x3" into config words
+fn encode_event(
+    event: &str,
+    format: &dyn Fn(&str) -> Option<FormatSpec>,
+) -> Option<(u64, u64, u64)> {
+    let mut config = (0u64, 0u64, 0u64);
+    for term in event.trim().split(',').filter(|t| !t.is_empty()) {
+        let (name, value) = match term.split_once('=') {
+            Some((n, v)) => {
+                let v = v.trim();
+                let value = match v.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+                    None => v.parse().ok()?,
+                };
+                (n.trim(), value)
+            }
+            // Bare flags such as "edge" mean 1
+            None => (term.trim(), 1),
+        };
+        let spec = format(name)?;
+        let bits = spec.encode(value);
+        match spec.field {
+            ConfigField::Config => config.0 |= bits,
+            ConfigField::Config1 => config.1 |= bits,
+            ConfigField::Config2 => config.2 |= bits,
+        }
+    }
+    Some(config)
+}
+
+/// Bytes per count from an event's `.scale` and `.unit`
+fn bytes_per_count(name: &str, scale: Option<f64>, unit: Option<&str>) -> f64 {
+    let unit_bytes = match unit.map(str::trim) {
+        Some("MiB") => 1024.0 * 1024.0,
+        Some("KiB") => 1024.0,
+        Some("MB") => 1e6,
+        Some("B") | Some("bytes") => 1.0,
+        // One CAS command moves one 64-byte cache line
+        _ if name.starts_with("cas_count") => 64.0,
+        _ => 1.0,
+    };
+    scale.unwrap_or(1.0) * unit_bytes
+}
+
+fn parse_cpumask(s: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in s.trim().split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((a, b)) => {
+                if let (Ok(a), Ok(b)) = (a.parse::<u32>(), b.parse::<u32>()) {
+                    cpus.extend(a..=b);
+                }
+            }
+            None => cpus.extend(part.parse::<u32>().ok()),
+        }
+    }
+    cpus
+}
+
+/// Memory controller PMUs with at least one read or write bandwidth event
+pub fn discover_memory_pmus(root: &Path) -> Vec<UncorePmu> {
+    let mut pmus = Vec::new();
+    let Ok(entries) = std::fs::read_dir(root.join("sys/bus/event_source/devices")) else {
+        return pmus;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !MEMORY_PMU_PREFIXES.iter().any(|p| name.starts_with(p)) {
+            continue;
+        }
+        let dir = entry.path();
+        let Some(pmu_type) = std::fs::read_to_string(dir.join("type"))
+            .ok()
+            .and_then(|s| s.trim().parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let cpus = std::fs::read_to_string(dir.join("cpumask"))
+            .map(|s| parse_cpumask(&s))
+            .unwrap_or_else(|_| vec![0]);
+
+        let format = |term: &str| -> Option<FormatSpec> {
+            FormatSpec::parse(&std::fs::read_to_string(dir.join("format").join(term)).ok()?)
+        };
+        let read_opt = |file: String| std::fs::read_to_string(dir.join("events").join(file)).ok();
+
+        let mut events = Vec::new();
+        for (direction, names) in [
+            (Direction::Read, READ_EVENTS),
+            (Direction::Write, WRITE_EVENTS),
+        ] {
+            // First matching name wins; PMUs expose one generation's names
+            for event_name in names.iter() {
+                let Some(spec) = read_opt(event_name.to_string()) else {
+                    continue;
+                };
+                let Some((config, config1, config2)) = encode_event(&spec, &format) else {
+                    continue;
+                };
+                let scale = read_opt(format!("{}.scale", event_name))
+                    .and_then(|s| s.trim().parse::<f64>().ok());
+                let unit = read_opt(format!("{}.unit", event_name));
+                events.push(UncoreEvent {
+                    name: event_name.to_string(),
+                    direction,
+                    config,
+                    config1,
+                    config2,
+                    bytes_per_count: bytes_per_count(event_name, scale, unit.as_deref()),
+                });
+                break;
+            }
+        }
+        if !events.is_empty() {
+            pmus.push(UncorePmu {
+                name,
+                pmu_type,
+                cpus,
+                events,
+            });
+        }
+    }
+    pmus.sort_by(|a, b| a.name.cmp(&b.name));
+    pmus
+}
+
+/// DRAM bandwidth over one sampling interval
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryBandwidth {
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+}
+
+impl MemoryBandwidth {
+    pub fn total_bytes_per_sec(&self) -> f64 {
+        self.read_bytes_per_sec + self.write_bytes_per_sec
+    }
+}
+
+#[cfg(target_os = "linux")]
+struct OpenUncoreCounter {
+    direction: Direction,
+    bytes_per_count: f64,
+    file: std::fs::File,
+    last: (u64, u64, u64),
+}
+
+/// Open read/write counters on every memory controller PMU
+///
+/// Uncore events are system-wide, so opening them needs `perf_event_paranoid`
+/// <= 0 or `CAP_PERFMON`.
+pub struct MemoryBandwidthCounters {
+    #[cfg(target_os = "linux")]
+    counters: Vec<OpenUncoreCounter>,
+    pmus: Vec<String>,
+}
+
+impl MemoryBandwidthCounters {
+    /// Discover and open counters on the live system
+    pub fn open() -> Result<Self> {
+        Self::open_pmus(&discover_memory_pmus(Path::new("/")))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn open_pmus(pmus: &[UncorePmu]) -> Result<Self> {
+        if pmus.is_empty() {
+            return Err(SimonError::FeatureNotAvailable(
+                "no memory controller PMU with bandwidth events".into(),
+            ));
+        }
+        let mut counters = Vec::new();
+        let mut last_err = None;
+        for pmu in pmus {
+            for event in &pmu.events {
+                for &cpu in &pmu.cpus {
+                    let mut attr = sys::PerfEventAttr::counting(pmu.pmu_type, event.config);
+                    attr.config1 = event.config1;
+                    attr.config2 = event.config2;
+                    match sys::perf_event_open(&attr, -1, cpu as i32) {
+                        Ok(file) => {
+                            let _ = sys::enable(&file);
+                            let last = sys::read_counter(&file).unwrap_or_default();
+                            counters.push(OpenUncoreCounter {
+                                direction: event.direction,
+                                bytes_per_count: event.bytes_per_count,
+                                file,
+                                last,
+                            });
+                        }
+                        Err(e) => last_err = Some(e),
+                    }
+                }
+            }
+        }
+        if counters.is_empty() {
+            return Err(match last_err {
+                Some(e) if matches!(e.raw_os_error(), Some(libc::EACCES) | Some(libc::EPERM)) => {
+                    SimonError::PermissionDenied(format!(
+                        "uncore counters need perf_event_paranoid <= 0 or CAP_PERFMON: {}",
+                        e
+                    ))
+                }
+                Some(e) => SimonError::Io(e),
+                None => SimonError::FeatureNotAvailable("no uncore counters opened".into()),
+            });
+        }
+        Ok(Self {
+            counters,
+            pmus: pmus.iter().map(|p| p.name.clone()).collect(),
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open_pmus(_pmus: &[UncorePmu]) -> Result<Self> {
+        Err(SimonError::UnsupportedPlatform(
+            "uncore perf counters require Linux".into(),
+        ))
+    }
+
+    /// PMU names the counters were opened on
+    pub fn pmus(&self) -> &[String] {
+        &self.pmus
+    }
+
+    /// Bandwidth since the previous read
+    #[cfg(target_os = "linux")]
+    pub fn read(&mut self, elapsed_secs: f64) -> MemoryBandwidth {
+        let mut bw = MemoryBandwidth::default();
+        if elapsed_secs <= 0.0 {
+            return bw;
+        }
+        for counter in &mut self.counters {
+            let Ok(now) = sys::read_counter(&counter.file) else {
+                continue;
+            };
+            let count = super::scaled_delta(counter.last, now);
+            counter.last = now;
+            let rate = count * counter.bytes_per_count / elapsed_secs;
+            match counter.direction {
+                Direction::Read => bw.read_bytes_per_sec += rate,
+                Direction::Write => bw.write_bytes_per_sec += rate,
+            }
+        }
+        bw
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn read(&mut self, _elapsed_secs: f64) -> MemoryBandwidth {
+        MemoryBandwidth::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    #[test]
+    fn test_format_encoding() {
+        let spec = FormatSpec::parse("config:0-7\n").unwrap();
+        assert_eq!(spec.encode(0x04), 0x04);
+        let spec = FormatSpec::parse("config:8-15").unwrap();
+        assert_eq!(spec.encode(0x03), 0x0300);
+        // Split field: low 4 bits at 0-3, the rest at 32-35
+        let spec = FormatSpec::parse("config:0-3,32-35").unwrap();
+        assert_eq!(spec.encode(0xab), 0xa_0000_000b);
+        assert!(FormatSpec::parse("config:8-4").is_none());
+        assert!(FormatSpec::parse("bogus:0-7").is_none());
+
+        let format = |term: &str| match term {
+            "event" => FormatSpec::parse("config:0-7"),
+            "umask" => FormatSpec::parse("config:8-15"),
+            "edge" => FormatSpec::parse("config:18"),
+            "thresh" => FormatSpec::parse("config1:0-7"),
+            _ => None,
+        };
+        assert_eq!(
+            encode_event("event=0x04,umask=0x3", &format),
+            Some((0x0304, 0, 0))
+        );
+        assert_eq!(
+            encode_event("event=0x1,edge,thresh=2", &format),
+            Some((0x1 | 1 << 18, 2, 0))
+        );
+        assert_eq!(encode_event("event=0x1,unknown=1", &format), None);
+    }
+
+    #[test]
+    fn test_discover_imc_fixture() {
+        let root = FakeRoot::new("perf-uncore");
+        let imc = "sys/bus/event_source/devices/uncore_imc_0";
+        root.write(&format!("{}/type", imc), "14\n");
+        root.write(&format!("{}/cpumask", imc), "0,28\n");
+        root.write(&format!("{}/format/event", imc), "config:0-7\n");
+        root.write(&format!("{}/format/umask", imc), "config:8-15\n");
+        root.write(
+            &format!("{}/events/cas_count_read", imc),
+            "event=0x04,umask=0x0f\n",
+        );
+        root.write(
+            &format!("{}/events/cas_count_read.scale", imc),
+            "6.103515625e-5\n",
+        );
+        root.write(&format!("{}/events/cas_count_read.unit", imc), "MiB\n");
+        root.write(
+            &format!("{}/events/cas_count_write", imc),
+            "event=0x04,umask=0x30\n",
+        );
+        // Not a memory controller
+        root.write("sys/bus/event_source/devices/cpu/type", "4\n");
+
+        let pmus = discover_memory_pmus(root.path());
+
+        assert_eq!(pmus.len(), 1);
+        let pmu = &pmus[0];
+        assert_eq!(pmu.pmu_type, 14);
+        assert_eq!(pmu.cpus, vec![0, 28]);
+        assert_eq!(pmu.events.len(), 2);
+        let read = &pmu.events[0];
+        assert_eq!(read.direction, Direction::Read);
+        assert_eq!(read.config, 0x0f04);
+        // 6.103515625e-5 MiB = 64 bytes
+        assert!((read.bytes_per_count - 64.0).abs() < 1e-9);
+        let write = &pmu.events[1];
+        assert_eq!(write.direction, Direction::Write);
+        assert_eq!(write.config, 0x3004);
+        assert!((write.bytes_per_count - 64.0).abs() < 1e-9);
+    }
+}
@@ -27,6 +27,7 @@
 //! }
 //! ```
 
+use crate::fan_control::FanCalibrationProfile;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -43,6 +44,9 @@ pub struct PredictionConfig {
     pub fan_min_rpm: f64,
     /// Fan degradation rate threshold (RPM/hour decline)
     pub fan_degradation_rate: f64,
+    /// Calibrated fans: alert when speed falls below this percentage of baseline
+    #[serde(default = "default_fan_wear_threshold")]
+    pub fan_wear_threshold_percent: f64,
     /// Memory error rate threshold (errors/hour)
     pub memory_error_threshold: f64,
     /// GPU clock degradation threshold (MHz decline over window)
@@ -51,6 +55,10 @@ pub struct PredictionConfig {
     pub window_size: usize,
 }
 
+fn default_fan_wear_threshold() -> f64 {
+    85.0
+}
+
 impl Default for PredictionConfig {
     fn default() -> Self {
         Self {
@@ -59,6 +67,7 @@ impl Default for PredictionConfig {
             disk_health_warning: 80.0,
             fan_min_rpm: 500.0,
             fan_degradation_rate: 50.0,
+            fan_wear_threshold_percent: default_fan_wear_threshold(),
             memory_error_threshold: 1.0,
             clock_degradation_mhz: 100.0,
             window_size: 100,
@@ -116,7 +125,7 @@ pub struct MaintenanceAlert {
 }
 
 /// Type of predicted issue
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum IssueType {
     /// Component approaching thermal limits
     ThermalDegradation,
@@ -132,6 +141,12 @@ pub enum IssueType {
     PowerDegradation,
     /// Capacitor aging (increasing voltage ripple)
     CapacitorAging,
+    /// Filesystem projected to run out of space
+    CapacityExhaustion,
+    /// Filesystem projected to run out of inodes
+    InodeExhaustion,
+    /// Filesystem filling much faster than its usual rate
+    FillRateChange,
 }
 
 impl std::fmt::Display for IssueType {
@@ -144,6 +159,9 @@ impl std::fmt::Display for IssueType {
             Self::MemoryFailure => write!(f, "Memory Failure Risk"),
             Self::PowerDegradation => write!(f, "Power Degradation"),
             Self::CapacitorAging => write!(f, "Capacitor Aging"),
+            Self::CapacityExhaustion => write!(f, "Capacity Exhaustion"),
+            Self::InodeExhaustion => write!(f, "Inode Exhaustion"),
+            Self::FillRateChange => write!(f, "Fill Rate Change"),
         }
     }
 }
@@ -184,33 +202,19 @@ impl TimeSeries {
         self.values.iter().sum::<f64>() / self.values.len() as f64
     }
 
+    fn fit(&self) -> Option<LinearFit> {
+        let points: Vec<(f64, f64)> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, &y)| (i as f64, y))
+            .collect();
+        LinearFit::fit(&points)
+    }
+
     /// Simple linear regression: returns (slope, intercept)
     fn linear_regression(&self) -> Option<(f64, f64)> {
-        let n = self.values.len() as f64;
-        if n < 3.0 {
-            return None;
-        }
-
-        let x_mean = (n - 1.0) / 2.0;
-        let y_mean = self.mean();
-
-        let mut ss_xy = 0.0;
-        let mut ss_xx = 0.0;
-
-        for (i, y) in self.values.iter().enumerate() {
-            let x = i as f64;
-            ss_xy += (x - x_mean) * (y - y_mean);
-            ss_xx += (x - x_mean) * (x - x_mean);
-        }
-
-        if ss_xx.abs() < 1e-10 {
-            return None;
-        }
-
-        let slope = ss_xy / ss_xx;
-        let intercept = y_mean - slope * x_mean;
-
-        Some((slope, intercept))
+        self.fit().map(|f| (f.slope, f.intercept))
     }
 
     /// Predict value at future index (from current last position)
@@ -239,25 +243,80 @@ impl TimeSeries {
 
     /// R² coefficient of determination (goodness of fit)
     fn r_squared(&self) -> f64 {
-        if self.values.len() < 3 {
-            return 0.0;
+        self.fit().map(|f| f.r_squared).unwrap_or(0.0)
+    }
+}
+
+/// Ordinary least-squares line through `(x, y)` points
+///
+/// Shared by the sample-indexed trends above and time-based forecasts such as
+/// [`crate::capacity`], where x is hours and the points are irregularly spaced.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LinearFit {
+    pub slope: f64,
+    pub intercept: f64,
+    /// Coefficient of determination (0 when y is constant)
+    pub r_squared: f64,
+    /// Standard error of the slope, for confidence intervals
+    pub slope_std_error: f64,
+    /// Number of points fitted
+    pub n: usize,
+}
+
+impl LinearFit {
+    /// Fit at least 3 points with distinct x values
+    pub fn fit(points: &[(f64, f64)]) -> Option<Self> {
+        let n = points.len() as f64;
+        if n < 3.0 {
+            return None;
         }
-        let (slope, intercept) = match self.linear_regression() {
-            Some(v) => v,
-            None => return 0.0,
-        };
-        let y_mean = self.mean();
+
+        let x_mean = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let y_mean = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+        let mut ss_xy = 0.0;
+        let mut ss_xx = 0.0;
+        for &(x, y) in points {
+            ss_xy += (x - x_mean) * (y - y_mean);
+            ss_xx += (x - x_mean) * (x - x_mean);
+        }
+        if ss_xx.abs() < 1e-10 {
+            return None;
+        }
+
+        let slope = ss_xy / ss_xx;
+        let intercept = y_mean - slope * x_mean;
+
         let mut ss_res = 0.0;
         let mut ss_tot = 0.0;
-        for (i, y) in self.values.iter().enumerate() {
-            let predicted = slope * i as f64 + intercept;
-            ss_res += (y - predicted).powi(2);
+        for &(x, y) in points {
+            ss_res += (y - (slope * x + intercept)).powi(2);
             ss_tot += (y - y_mean).powi(2);
         }
-        if ss_tot.abs() < 1e-10 {
-            return 0.0;
-        }
-        1.0 - (ss_res / ss_tot)
+        let r_squared = if ss_tot.abs() < 1e-10 {
+            0.0
+        } else {
+            1.0 - ss_res / ss_tot
+        };
+
+        Some(Self {
+            slope,
+            intercept,
+            r_squared,
+            slope_std_error: (ss_res / (n - 2.0) / ss_xx).sqrt(),
+            n: points.len(),
+        })
+    }
+
+    /// Fitted y at `x`
+    pub fn at(&self, x: f64) -> f64 {
+        self.slope * x + self.intercept
+    }
+
+    /// Slope bounds at ~95% confidence (±1.96 standard errors)
+    pub fn slope_interval(&self) -> (f64, f64) {
+        let margin = 1.96 * self.slope_std_error;
+        (self.slope - margin, self.slope + margin)
     }
 }
 
@@ -268,6 +327,9 @@ pub struct MaintenanceEngine {
     gpu_clocks: HashMap<usize, TimeSeries>,
     disk_health: HashMap<usize, TimeSeries>,
     fan_rpms: HashMap<usize, TimeSeries>,
+    fan_baselines: HashMap<usize, FanCalibrationProfile>,
+    /// Measured RPM as a percentage of the calibrated baseline
+    fan_baseline_ratios: HashMap<usize, TimeSeries>,
     memory_errors: TimeSeries,
 }
 
@@ -281,6 +343,8 @@ impl MaintenanceEngine {
             gpu_clocks: HashMap::new(),
             disk_health: HashMap::new(),
             fan_rpms: HashMap::new(),
+            fan_baselines: HashMap::new(),
+            fan_baseline_ratios: HashMap::new(),
             memory_errors: TimeSeries::new(ws),
         }
     }
@@ -317,6 +381,29 @@ impl MaintenanceEngine {
             .push(rpm);
     }
 
+    /// Set a fan's calibrated PWM→RPM baseline
+    ///
+    /// Once set, bearing wear for this fan is judged against the baseline using
+    /// [`record_fan_sample`](Self::record_fan_sample) instead of raw RPM trends.
+    pub fn set_fan_baseline(&mut self, fan_idx: usize, profile: FanCalibrationProfile) {
+        self.fan_baselines.insert(fan_idx, profile);
+    }
+
+    /// Record fan RPM together with the duty it was running at
+    pub fn record_fan_sample(&mut self, fan_idx: usize, duty_percent: f32, rpm: u32) {
+        self.record_fan_rpm(fan_idx, rpm as f64);
+        let ratio = self
+            .fan_baselines
+            .get(&fan_idx)
+            .and_then(|p| p.rpm_ratio_percent(duty_percent, rpm));
+        if let Some(ratio) = ratio {
+            self.fan_baseline_ratios
+                .entry(fan_idx)
+                .or_insert_with(|| TimeSeries::new(self.config.window_size))
+                .push(ratio as f64);
+        }
+    }
+
     /// Record memory error count
     pub fn record_memory_errors(&mut self, errors: f64) {
         self.memory_errors.push(errors);
@@ -330,6 +417,7 @@ impl MaintenanceEngine {
         self.predict_gpu_degradation(&mut alerts);
         self.predict_disk_failure(&mut alerts);
         self.predict_fan_failure(&mut alerts);
+        self.predict_fan_wear(&mut alerts);
         self.predict_memory_failure(&mut alerts);
 
         alerts.sort_by(|a, b| b.urgency.cmp(&a.urgency));
@@ -466,6 +554,10 @@ impl MaintenanceEngine {
 
     fn predict_fan_failure(&self, alerts: &mut Vec<MaintenanceAlert>) {
         for (&fan_idx, ts) in &self.fan_rpms {
+            // Calibrated fans are judged against their baseline instead
+            if self.fan_baselines.contains_key(&fan_idx) {
+                continue;
+            }
             if ts.len() < self.config.min_data_points {
                 continue;
             }
@@ -512,6 +604,56 @@ impl MaintenanceEngine {
         }
     }
 
+    fn predict_fan_wear(&self, alerts: &mut Vec<MaintenanceAlert>) {
+        for (&fan_idx, ts) in &self.fan_baseline_ratios {
+            if ts.len() < self.config.min_data_points {
+                continue;
+            }
+            let threshold = self.config.fan_wear_threshold_percent;
+            // Mean over the window smooths out transient ramping
+            let current = ts.mean();
+            let slope = ts.linear_regression().map(|(s, _)| s);
+            let declining = slope.is_some_and(|s| s < 0.0);
+
+            let urgency = if current < threshold {
+                if current < threshold - 15.0 {
+                    Urgency::Critical
+                } else {
+                    Urgency::High
+                }
+            } else if declining && current < 100.0 - (100.0 - threshold) / 2.0 {
+                Urgency::Medium
+            } else {
+                continue;
+            };
+
+            let component = self
+                .fan_baselines
+                .get(&fan_idx)
+                .map(|p| p.fan.clone())
+                .unwrap_or_else(|| format!("Fan {}", fan_idx));
+            alerts.push(MaintenanceAlert {
+                message: format!(
+                    "{} running at {:.0}% of calibrated RPM (alert below {:.0}%)",
+                    component, current, threshold
+                ),
+                component,
+                issue_type: IssueType::FanFailure,
+                urgency,
+                eta_hours: if declining {
+                    ts.steps_until_threshold(threshold)
+                } else {
+                    None
+                },
+                degradation_rate: slope,
+                current_value: current,
+                threshold,
+                action: "Inspect fan bearings, clean dust, plan replacement.".into(),
+                confidence: 0.5 + 0.5 * ts.r_squared().clamp(0.0, 1.0),
+            });
+        }
+    }
+
     fn predict_memory_failure(&self, alerts: &mut Vec<MaintenanceAlert>) {
         if self.memory_errors.len() < self.config.min_data_points {
             return;
@@ -669,6 +811,62 @@ mod tests {
         );
     }
 
+    fn calibrated_profile() -> FanCalibrationProfile {
+        use crate::fan_control::CalibrationPoint;
+        FanCalibrationProfile {
+            fan: "nct6798/pwm2".into(),
+            points: (2..=10)
+                .map(|i| CalibrationPoint {
+                    duty_percent: i as f32 * 10.0,
+                    rpm: i * 200,
+                })
+                .collect(),
+            start_duty_percent: 30.0,
+            stop_duty_percent: 20.0,
+            max_rpm: 2000,
+            calibrated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_fan_wear_uses_calibrated_baseline() {
+        let mut engine = MaintenanceEngine::new(PredictionConfig::default());
+        engine.set_fan_baseline(0, calibrated_profile());
+        // Duty changes cause large RPM swings, but the fan matches its baseline
+        for i in 0..20 {
+            let duty = if i % 2 == 0 { 40.0 } else { 90.0 };
+            engine.record_fan_sample(0, duty, (duty * 20.0) as u32);
+        }
+        assert!(engine.predict().is_empty());
+
+        // Worn fan: 75% of expected RPM at every duty
+        let mut engine = MaintenanceEngine::new(PredictionConfig::default());
+        engine.set_fan_baseline(0, calibrated_profile());
+        for _ in 0..20 {
+            engine.record_fan_sample(0, 50.0, 750);
+        }
+        let alerts = engine.predict();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].issue_type, IssueType::FanFailure);
+        assert_eq!(alerts[0].component, "nct6798/pwm2");
+        assert_eq!(alerts[0].urgency, Urgency::High);
+    }
+
+    #[test]
+    fn test_linear_fit_irregular_spacing() {
+        // y = 100 - 2x sampled at uneven x, with small noise
+        let points = [(0.0, 100.1), (0.5, 98.9), (2.0, 96.0), (2.25, 95.6), (5.0, 89.9)];
+        let fit = LinearFit::fit(&points).unwrap();
+        assert!((fit.slope + 2.0).abs() < 0.05);
+        assert!((fit.at(0.0) - 100.0).abs() < 0.2);
+        assert!(fit.r_squared > 0.99);
+        let (lo, hi) = fit.slope_interval();
+        assert!(lo < fit.slope && fit.slope < hi);
+
+        assert!(LinearFit::fit(&points[..2]).is_none());
+        assert!(LinearFit::fit(&[(1.0, 1.0), (1.0, 2.0), (1.0, 3.0)]).is_none());
+    }
+
     #[test]
     fn test_no_alerts_stable_system() {
         let mut engine = MaintenanceEngine::new(PredictionConfig::default());
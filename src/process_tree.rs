@@ -327,6 +327,17 @@ impl ProcessTree {
 
     #[cfg(target_os = "linux")]
     fn detect_container_runtime(cgroup_path: &str) -> (Option<ContainerRuntime>, Option<String>) {
+        // Kubernetes pods, checked first so the pod wins over the CRI runtime
+        // that runs it. The cgroupfs driver uses /kubepods/<qos>/pod<uid>/<id>;
+        // the systemd driver uses /kubepods.slice/.../<runtime>-<id>.scope with
+        // cri-containerd, crio or docker as the runtime.
+        if cgroup_path.contains("/kubepods") {
+            let id = ["cri-containerd", "crio", "docker"]
+                .iter()
+                .find_map(|prefix| Self::extract_container_id(cgroup_path, prefix));
+            return (Some(ContainerRuntime::Kubernetes), id);
+        }
+
         // Docker: /docker/<container_id> or /system.slice/docker-<id>.scope
         if cgroup_path.contains("/docker/") || cgroup_path.contains("/docker-") {
             let id = Self::extract_container_id(cgroup_path, "docker");
@@ -345,12 +356,6 @@ impl ProcessTree {
             return (Some(ContainerRuntime::Containerd), id);
         }
 
-        // Kubernetes pods: /kubepods/ or /kubepods.slice/
-        if cgroup_path.contains("/kubepods") {
-            let id = Self::extract_container_id(cgroup_path, "kubepods");
-            return (Some(ContainerRuntime::Kubernetes), id);
-        }
-
         // LXC: /lxc/<name>
         if cgroup_path.contains("/lxc/") {
             let name = cgroup_path
@@ -528,6 +533,53 @@ impl ProcessTree {
         self.nodes.get(&pid).and_then(|n| n.cgroup.as_ref())
     }
 
+    /// Read cgroup and container info for a single process without building the tree
+    pub fn cgroup_for_pid(pid: u32) -> Option<CgroupInfo> {
+        #[cfg(target_os = "linux")]
+        {
+            Self::read_cgroup_linux(pid)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = pid;
+            None
+        }
+    }
+
+    /// Detect the container runtime and ID from a cgroup path alone, for
+    /// processes that have already exited (e.g. OOM kill victims)
+    pub fn container_for_cgroup(cgroup_path: &str) -> (Option<ContainerRuntime>, Option<String>) {
+        #[cfg(target_os = "linux")]
+        {
+            Self::detect_container_runtime(cgroup_path)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = cgroup_path;
+            (None, None)
+        }
+    }
+
+    /// Kubernetes pod UID from a kubepods cgroup path
+    ///
+    /// Handles both the cgroupfs driver (`/kubepods/burstable/pod<uid>/...`)
+    /// and the systemd driver (`kubepods-burstable-pod<uid_with_underscores>.slice`).
+    pub fn kubernetes_pod_uid(cgroup_path: &str) -> Option<String> {
+        if !cgroup_path.contains("kubepods") {
+            return None;
+        }
+        cgroup_path.split('/').find_map(|segment| {
+            let segment = segment.strip_suffix(".slice").unwrap_or(segment);
+            let uid = segment
+                .rsplit_once("-pod")
+                .map(|(_, uid)| uid)
+                .or_else(|| segment.strip_prefix("pod"))?
+                .replace('_', "-");
+            let valid = uid.len() == 36 && uid.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+            valid.then_some(uid)
+        })
+    }
+
     /// Get all containerized processes
     pub fn containerized_processes(&self) -> Vec<&ProcessNode> {
         self.nodes.values().filter(|n| n.cgroup.is_some()).collect()
@@ -724,6 +776,32 @@ mod tests {
         assert!(id.is_some());
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_detect_kubernetes_cgroup() {
+        let systemd = "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1b4e28ba_2fa1_11d2_883f_0016d3cca427.slice/cri-containerd-4f2a9c8e1b7d3a5f6e0c9b8a7d6e5f4a3b2c1d0e9f8a7b6c5d4e3f2a1b0c9d8e.scope";
+        let (runtime, id) = ProcessTree::container_for_cgroup(systemd);
+        assert_eq!(runtime, Some(ContainerRuntime::Kubernetes));
+        assert_eq!(id.as_deref(), Some("4f2a9c8e1b7d"));
+        assert_eq!(
+            ProcessTree::kubernetes_pod_uid(systemd).as_deref(),
+            Some("1b4e28ba-2fa1-11d2-883f-0016d3cca427")
+        );
+
+        let cgroupfs = "/kubepods/besteffort/pod1b4e28ba-2fa1-11d2-883f-0016d3cca427/4f2a9c8e1b7d3a5f6e0c9b8a7d6e5f4a3b2c1d0e9f8a7b6c5d4e3f2a1b0c9d8e";
+        let (runtime, id) = ProcessTree::container_for_cgroup(cgroupfs);
+        assert_eq!(runtime, Some(ContainerRuntime::Kubernetes));
+        assert_eq!(id.as_deref(), Some("4f2a9c8e1b7d"));
+        assert_eq!(
+            ProcessTree::kubernetes_pod_uid(cgroupfs).as_deref(),
+            Some("1b4e28ba-2fa1-11d2-883f-0016d3cca427")
+        );
+        assert_eq!(
+            ProcessTree::kubernetes_pod_uid("/system.slice/ssh.service"),
+            None
+        );
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn test_detect_host_cgroup() {
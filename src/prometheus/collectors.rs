@@ -0,0 +1,1445 @@
+//! Pluggable metric collectors
+//!
+//! Every hardware subsystem that has something worth scraping implements
+//! [`Collector`] and is registered with a [`CollectorRegistry`]. The registry
+//! runs each enabled collector on its own thread under a scrape timeout and
+//! reports, per collector, how long it took and whether it succeeded:
+//!
+//! ```text
+//! simon_scrape_collector_duration_seconds{collector="smart"} 0.412
+//! simon_scrape_collector_success{collector="smart"} 1
+//! ```
+//!
+//! A collector that is still running when its timeout expires is reported as
+//! failed and is skipped on later scrapes until the stuck call returns, so a
+//! hung `ipmitool` cannot pile up threads.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simonlib::prometheus::{CollectorRegistry, CollectorsConfig, PrometheusExporter};
+//!
+//! let config = CollectorsConfig {
+//!     enable: vec!["smart".into()],
+//!     disable: vec!["gpu".into()],
+//!     ..Default::default()
+//! };
+//! let registry = CollectorRegistry::with_defaults(config);
+//!
+//! let mut exporter = PrometheusExporter::new("simon");
+//! registry.collect(&mut exporter);
+//! println!("{}", exporter.export());
+//! ```
+
+use super::{MetricFamily, MetricSample, MetricType, PrometheusExporter};
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// A source of metric families
+///
+/// Collectors add families to the exporter they are handed; names should be
+/// built with [`PrometheusExporter::prefixed`] so they share the namespace.
+pub trait Collector: Send + Sync {
+    /// Short, unique name used in configuration and the `collector` label
+    fn name(&self) -> &'static str;
+
+    /// Whether the collector runs when the configuration does not mention it
+    ///
+    /// Collectors that shell out, wake disks or emit high-cardinality series
+    /// should return `false`.
+    fn default_enabled(&self) -> bool {
+        true
+    }
+
+    /// Add this collector's metric families to `exporter`
+    fn collect(&self, exporter: &mut PrometheusExporter) -> Result<()>;
+}
+
+/// Collector selection and timeouts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollectorsConfig {
+    /// Collectors to run in addition to the default set (e.g. `["smart", "ipmi"]`)
+    pub enable: Vec<String>,
+    /// Collectors to skip; takes precedence over `enable`
+    pub disable: Vec<String>,
+    /// Scrape timeout for each collector, in milliseconds
+    pub timeout_ms: u64,
+    /// Per-collector timeout overrides, in milliseconds
+    pub timeouts: BTreeMap<String, u64>,
+}
+
+impl Default for CollectorsConfig {
+    fn default() -> Self {
+        Self {
+            enable: Vec::new(),
+            disable: Vec::new(),
+            timeout_ms: 5000,
+            timeouts: BTreeMap::new(),
+        }
+    }
+}
+
+struct RegisteredCollector {
+    collector: Arc<dyn Collector>,
+    /// Set while a scrape of this collector is in progress (including one
+    /// that has already timed out)
+    running: Arc<AtomicBool>,
+}
+
+/// Result of one collector run, sent back from its worker thread
+struct Outcome {
+    duration: Duration,
+    success: bool,
+    families: Vec<MetricFamily>,
+}
+
+/// Set of collectors with their enable state and timeouts
+///
+/// Collectors can be registered through a shared reference, so sources that
+/// start later (such as background monitors) can join a registry that is
+/// already being scraped.
+pub struct CollectorRegistry {
+    config: CollectorsConfig,
+    collectors: RwLock<Vec<RegisteredCollector>>,
+}
+
+impl CollectorRegistry {
+    /// Create an empty registry
+    pub fn new(config: CollectorsConfig) -> Self {
+        Self {
+            config,
+            collectors: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Create a registry holding every built-in collector
+    pub fn with_defaults(config: CollectorsConfig) -> Self {
+        let registry = Self::new(config);
+        for builtin in BUILTIN_COLLECTORS {
+            registry.register(Arc::new(*builtin));
+        }
+        registry
+    }
+
+    /// Create a registry holding the CPU, memory, GPU, disk and network
+    /// collectors, the cheap readings behind
+    /// [`PrometheusExporter::collect_system_metrics`]
+    pub(crate) fn system() -> Self {
+        let registry = Self::new(CollectorsConfig::default());
+        for builtin in BUILTIN_COLLECTORS
+            .iter()
+            .filter(|b| SYSTEM_COLLECTORS.contains(&b.name))
+        {
+            registry.register(Arc::new(*builtin));
+        }
+        registry
+    }
+
+    /// Add a collector, replacing any registered under the same name
+    pub fn register(&self, collector: Arc<dyn Collector>) {
+        let entry = RegisteredCollector {
+            collector,
+            running: Arc::new(AtomicBool::new(false)),
+        };
+        let name = entry.collector.name();
+        let mut collectors = self.collectors.write().unwrap_or_else(|e| e.into_inner());
+        match collectors.iter_mut().find(|c| c.collector.name() == name) {
+            Some(existing) => *existing = entry,
+            None => collectors.push(entry),
+        }
+    }
+
+    /// Names of all registered collectors, in registration order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.read().iter().map(|c| c.collector.name()).collect()
+    }
+
+    /// Whether the named collector is registered and enabled
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.read()
+            .iter()
+            .find(|c| c.collector.name() == name)
+            .is_some_and(|entry| self.enabled(entry))
+    }
+
+    fn enabled(&self, entry: &RegisteredCollector) -> bool {
+        let name = entry.collector.name();
+        if self.config.disable.iter().any(|n| n == name) {
+            return false;
+        }
+        self.config.enable.iter().any(|n| n == name) || entry.collector.default_enabled()
+    }
+
+    /// Registered collectors; a panic while registering cannot leave the list
+    /// half-updated, so a poisoned lock is still usable
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Vec<RegisteredCollector>> {
+        self.collectors.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Names in `enable`/`disable` that match no registered collector
+    pub fn unknown_names(&self) -> Vec<String> {
+        let names = self.names();
+        let mut unknown: Vec<String> = self
+            .config
+            .enable
+            .iter()
+            .chain(&self.config.disable)
+            .chain(self.config.timeouts.keys())
+            .filter(|n| !names.contains(&n.as_str()))
+            .cloned()
+            .collect();
+        unknown.sort();
+        unknown.dedup();
+        unknown
+    }
+
+    /// Scrape timeout for the named collector
+    pub fn timeout(&self, name: &str) -> Duration {
+        let ms = self
+            .config
+            .timeouts
+            .get(name)
+            .copied()
+            .unwrap_or(self.config.timeout_ms);
+        Duration::from_millis(ms)
+    }
+
+    /// Run every enabled collector and add its families to `exporter`
+    ///
+    /// Collectors run concurrently. Families are added in registration order
+    /// followed by `scrape_collector_duration_seconds` and
+    /// `scrape_collector_success`; a failed, panicked or timed-out collector
+    /// contributes only its meta-metrics.
+    pub fn collect(&self, exporter: &mut PrometheusExporter) {
+        let collectors = self.read();
+        let (tx, rx) = mpsc::channel::<(usize, Outcome)>();
+        let start = Instant::now();
+        let mut outcomes: Vec<Option<Outcome>> = collectors.iter().map(|_| None).collect();
+        let mut pending: Vec<(usize, Instant)> = Vec::new();
+
+        for (index, entry) in collectors.iter().enumerate() {
+            let name = entry.collector.name();
+            if !self.enabled(entry) {
+                continue;
+            }
+            if entry.running.swap(true, Ordering::AcqRel) {
+                log::warn!(
+                    "Collector {} is still running a previous scrape, skipping",
+                    name
+                );
+                outcomes[index] = Some(Outcome::failed(Duration::ZERO));
+                continue;
+            }
+
+            let collector = Arc::clone(&entry.collector);
+            let running = Arc::clone(&entry.running);
+            let prefix = exporter.prefix.clone();
+            let tx = tx.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("simon-collector-{}", name))
+                .spawn(move || {
+                    let started = Instant::now();
+                    let mut scratch = PrometheusExporter::new(&prefix);
+                    let result =
+                        panic::catch_unwind(AssertUnwindSafe(|| collector.collect(&mut scratch)));
+                    running.store(false, Ordering::Release);
+                    let success = match result {
+                        Ok(Ok(())) => true,
+                        Ok(Err(e)) => {
+                            log::debug!("Collector {} failed: {}", collector.name(), e);
+                            false
+                        }
+                        Err(_) => {
+                            log::warn!("Collector {} panicked", collector.name());
+                            false
+                        }
+                    };
+                    let outcome = Outcome {
+                        duration: started.elapsed(),
+                        success,
+                        families: if success {
+                            scratch.families
+                        } else {
+                            Vec::new()
+                        },
+                    };
+                    let _ = tx.send((index, outcome));
+                });
+            match spawned {
+                Ok(_) => pending.push((index, start + self.timeout(name))),
+                Err(e) => {
+                    log::warn!("Failed to spawn collector {}: {}", name, e);
+                    entry.running.store(false, Ordering::Release);
+                    outcomes[index] = Some(Outcome::failed(Duration::ZERO));
+                }
+            }
+        }
+        drop(tx);
+
+        while let Some(deadline) = pending.iter().map(|(_, d)| *d).min() {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok((index, outcome)) => {
+                    if let Some(pos) = pending.iter().position(|(i, _)| *i == index) {
+                        pending.swap_remove(pos);
+                        outcomes[index] = Some(outcome);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let now = Instant::now();
+                    pending.retain(|&(index, deadline)| {
+                        if deadline > now {
+                            return true;
+                        }
+                        let name = collectors[index].collector.name();
+                        log::warn!(
+                            "Collector {} timed out after {:?}",
+                            name,
+                            self.timeout(name)
+                        );
+                        outcomes[index] = Some(Outcome::failed(now - start));
+                        false
+                    });
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        let meta = |name: &str, help: &str| MetricFamily {
+            name: exporter.prefixed(name),
+            help: help.into(),
+            metric_type: MetricType::Gauge,
+            samples: Vec::new(),
+        };
+        let mut duration = meta(
+            "scrape_collector_duration_seconds",
+            "Duration of a collector scrape",
+        );
+        let mut success = meta(
+            "scrape_collector_success",
+            "Whether a collector succeeded within its timeout",
+        );
+        for (entry, outcome) in collectors.iter().zip(outcomes) {
+            let Some(outcome) = outcome else { continue };
+            let labels = labels([("collector", entry.collector.name().to_string())]);
+            duration.add_sample(outcome.duration.as_secs_f64(), labels.clone());
+            success.add_sample(if outcome.success { 1.0 } else { 0.0 }, labels);
+            for family in outcome.families {
+                exporter.add(family);
+            }
+        }
+        if !duration.samples.is_empty() {
+            exporter.add(duration);
+            exporter.add(success);
+        }
+    }
+
+    /// Run every enabled collector and render the families in the text
+    /// exposition format, with names under `prefix`
+    pub fn gather(&self, prefix: &str) -> String {
+        let mut exporter = PrometheusExporter::new(prefix);
+        self.collect(&mut exporter);
+        exporter
+            .families()
+            .iter()
+            .map(MetricFamily::format)
+            .collect()
+    }
+}
+
+impl Outcome {
+    fn failed(duration: Duration) -> Self {
+        Self {
+            duration,
+            success: false,
+            families: Vec::new(),
+        }
+    }
+}
+
+/// A collector backed by a plain function
+#[derive(Clone, Copy)]
+struct BuiltinCollector {
+    name: &'static str,
+    default_enabled: bool,
+    collect: fn(&mut PrometheusExporter) -> Result<()>,
+}
+
+impl Collector for BuiltinCollector {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn default_enabled(&self) -> bool {
+        self.default_enabled
+    }
+
+    fn collect(&self, exporter: &mut PrometheusExporter) -> Result<()> {
+        (self.collect)(exporter)
+    }
+}
+
+type CollectFn = dyn Fn(&mut PrometheusExporter) -> Result<()> + Send + Sync;
+
+/// A collector backed by a closure
+///
+/// Used for sources attached at runtime, such as the snapshot slot of a
+/// background monitor.
+pub struct FnCollector {
+    name: &'static str,
+    collect: Box<CollectFn>,
+}
+
+impl FnCollector {
+    /// Create a collector named `name` that runs `collect` on each scrape
+    pub fn new(
+        name: &'static str,
+        collect: impl Fn(&mut PrometheusExporter) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name,
+            collect: Box::new(collect),
+        }
+    }
+}
+
+impl Collector for FnCollector {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn collect(&self, exporter: &mut PrometheusExporter) -> Result<()> {
+        (self.collect)(exporter)
+    }
+}
+
+/// Built-in collectors in [`CollectorRegistry::system`]
+const SYSTEM_COLLECTORS: &[&str] = &["cpu", "memory", "gpu", "disk", "network"];
+
+/// Built-in collectors; SMART, cgroup, interrupt and IPMI are opt-in because
+/// they wake disks, shell out or produce per-cgroup/per-IRQ series
+const BUILTIN_COLLECTORS: &[BuiltinCollector] = &[
+    BuiltinCollector {
+        name: "cpu",
+        default_enabled: true,
+        collect: collect_cpu,
+    },
+    BuiltinCollector {
+        name: "memory",
+        default_enabled: true,
+        collect: collect_memory,
+    },
+    BuiltinCollector {
+        name: "gpu",
+        default_enabled: true,
+        collect: collect_gpu,
+    },
+    BuiltinCollector {
+        name: "disk",
+        default_enabled: true,
+        collect: collect_disk,
+    },
+    BuiltinCollector {
+        name: "network",
+        default_enabled: true,
+        collect: collect_network,
+    },
+    BuiltinCollector {
+        name: "rapl",
+        default_enabled: true,
+        collect: collect_rapl,
+    },
+    BuiltinCollector {
+        name: "edac",
+        default_enabled: true,
+        collect: collect_edac,
+    },
+    BuiltinCollector {
+        name: "thermal_zone",
+        default_enabled: true,
+        collect: collect_thermal_zone,
+    },
+    BuiltinCollector {
+        name: "fan",
+        default_enabled: true,
+        collect: collect_fan,
+    },
+    BuiltinCollector {
+        name: "power_supply",
+        default_enabled: true,
+        collect: collect_power_supply,
+    },
+    BuiltinCollector {
+        name: "pcie",
+        default_enabled: true,
+        collect: collect_pcie,
+    },
+    BuiltinCollector {
+        name: "numa",
+        default_enabled: true,
+        collect: collect_numa,
+    },
+    BuiltinCollector {
+        name: "io_scheduler",
+        default_enabled: true,
+        collect: collect_io_scheduler,
+    },
+    BuiltinCollector {
+        name: "smart",
+        default_enabled: false,
+        collect: collect_smart,
+    },
+    BuiltinCollector {
+        name: "cgroup",
+        default_enabled: false,
+        collect: collect_cgroup,
+    },
+    BuiltinCollector {
+        name: "interrupts",
+        default_enabled: false,
+        collect: collect_interrupts,
+    },
+    BuiltinCollector {
+        name: "ipmi",
+        default_enabled: false,
+        collect: collect_ipmi,
+    },
+];
+
+fn labels<const N: usize>(pairs: [(&str, String); N]) -> BTreeMap<String, String> {
+    pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+}
+
+fn sample(labels: BTreeMap<String, String>, value: f64) -> MetricSample {
+    MetricSample {
+        suffix: String::new(),
+        labels,
+        value,
+    }
+}
+
+fn collect_cpu(exporter: &mut PrometheusExporter) -> Result<()> {
+    exporter.collect_cpu_metrics();
+    Ok(())
+}
+
+fn collect_memory(exporter: &mut PrometheusExporter) -> Result<()> {
+    exporter.collect_memory_metrics();
+    Ok(())
+}
+
+fn collect_gpu(exporter: &mut PrometheusExporter) -> Result<()> {
+    exporter.collect_gpu_metrics();
+    Ok(())
+}
+
+fn collect_disk(exporter: &mut PrometheusExporter) -> Result<()> {
+    exporter.collect_disk_metrics();
+    Ok(())
+}
+
+fn collect_network(exporter: &mut PrometheusExporter) -> Result<()> {
+    exporter.collect_network_metrics();
+    Ok(())
+}
+
+fn collect_rapl(exporter: &mut PrometheusExporter) -> Result<()> {
+    let monitor = crate::rapl::RaplMonitor::new()?;
+    for reading in monitor.readings() {
+        let l = labels([
+            ("domain", reading.domain.to_string()),
+            ("name", reading.name.clone()),
+            ("socket", reading.socket.to_string()),
+        ]);
+        exporter.add(MetricFamily::counter_with_labels(
+            &exporter.prefixed("rapl_energy_joules_total"),
+            "Cumulative RAPL energy counter (wraps at the max energy range)",
+            reading.energy_uj as f64 / 1e6,
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("rapl_max_energy_range_joules"),
+            "Value at which the RAPL energy counter wraps",
+            reading.max_energy_range_uj as f64 / 1e6,
+            l.clone(),
+        ));
+        if let Some(limit) = reading.power_limit_uw {
+            exporter.add(MetricFamily::gauge_with_labels(
+                &exporter.prefixed("rapl_power_limit_watts"),
+                "Long-term RAPL power limit",
+                limit as f64 / 1e6,
+                l,
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn collect_edac(exporter: &mut PrometheusExporter) -> Result<()> {
+    let monitor = crate::edac::EdacMonitor::new()?;
+    exporter.add(MetricFamily::gauge(
+        &exporter.prefixed("edac_ecc_active"),
+        "Whether any EDAC memory controller is registered",
+        if monitor.overview().ecc_active {
+            1.0
+        } else {
+            0.0
+        },
+    ));
+    for mc in monitor.controllers() {
+        let l = labels([("controller", mc.index.to_string())]);
+        exporter.add(MetricFamily::counter_with_labels(
+            &exporter.prefixed("edac_correctable_errors_total"),
+            "Correctable memory errors reported by the controller",
+            mc.ce_count as f64,
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::counter_with_labels(
+            &exporter.prefixed("edac_uncorrectable_errors_total"),
+            "Uncorrectable memory errors reported by the controller",
+            mc.ue_count as f64,
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::counter_with_labels(
+            &exporter.prefixed("edac_correctable_noinfo_errors_total"),
+            "Correctable errors that could not be attributed to a csrow",
+            mc.ce_noinfo_count as f64,
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::counter_with_labels(
+            &exporter.prefixed("edac_uncorrectable_noinfo_errors_total"),
+            "Uncorrectable errors that could not be attributed to a csrow",
+            mc.ue_noinfo_count as f64,
+            l,
+        ));
+        for row in &mc.csrows {
+            let l = labels([
+                ("controller", mc.index.to_string()),
+                ("csrow", row.index.to_string()),
+                ("label", row.label.clone()),
+            ]);
+            exporter.add(MetricFamily::counter_with_labels(
+                &exporter.prefixed("edac_csrow_correctable_errors_total"),
+                "Correctable memory errors on the chip-select row",
+                row.ce_count as f64,
+                l.clone(),
+            ));
+            exporter.add(MetricFamily::counter_with_labels(
+                &exporter.prefixed("edac_csrow_uncorrectable_errors_total"),
+                "Uncorrectable memory errors on the chip-select row",
+                row.ue_count as f64,
+                l,
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn collect_thermal_zone(exporter: &mut PrometheusExporter) -> Result<()> {
+    let monitor = crate::thermal_zone::ThermalZoneMonitor::new()?;
+    for zone in monitor.zones() {
+        let l = labels([
+            ("zone", zone.name.clone()),
+            ("type", zone.type_string.clone()),
+        ]);
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("thermal_zone_temperature_celsius"),
+            "Thermal zone temperature",
+            zone.temp_c(),
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("thermal_zone_throttling"),
+            "Whether the zone is at or above a passive trip point",
+            if zone.is_throttling() { 1.0 } else { 0.0 },
+            l,
+        ));
+        for trip in &zone.trip_points {
+            exporter.add(MetricFamily::gauge_with_labels(
+                &exporter.prefixed("thermal_zone_trip_point_celsius"),
+                "Thermal zone trip point temperature",
+                trip.temp_mc as f64 / 1000.0,
+                labels([
+                    ("zone", zone.name.clone()),
+                    ("type", zone.type_string.clone()),
+                    ("trip", trip.index.to_string()),
+                    ("trip_type", trip.trip_type.to_string()),
+                ]),
+            ));
+        }
+    }
+    for dev in monitor.cooling_devices() {
+        let l = labels([
+            ("device", dev.name.clone()),
+            ("type", dev.cooling_type.clone()),
+        ]);
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("cooling_device_state"),
+            "Current cooling device state",
+            dev.cur_state as f64,
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("cooling_device_max_state"),
+            "Maximum cooling device state",
+            dev.max_state as f64,
+            l,
+        ));
+    }
+    Ok(())
+}
+
+fn collect_fan(exporter: &mut PrometheusExporter) -> Result<()> {
+    for fan in crate::fan_control::list_fans()? {
+        let l = labels([
+            ("fan", fan.name.clone()),
+            ("type", fan.fan_type.to_string()),
+        ]);
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("fan_speed_percent"),
+            "Fan duty cycle",
+            fan.speed_percent as f64,
+            l.clone(),
+        ));
+        if let Some(rpm) = fan.rpm {
+            exporter.add(MetricFamily::gauge_with_labels(
+                &exporter.prefixed("fan_speed_rpm"),
+                "Measured fan speed",
+                rpm as f64,
+                l.clone(),
+            ));
+        }
+        if let Some(target) = fan.rpm_target {
+            exporter.add(MetricFamily::gauge_with_labels(
+                &exporter.prefixed("fan_target_rpm"),
+                "Requested fan speed",
+                target as f64,
+                l.clone(),
+            ));
+        }
+        if let Some(pwm) = fan.pwm_value {
+            exporter.add(MetricFamily::gauge_with_labels(
+                &exporter.prefixed("fan_pwm"),
+                "Raw PWM value (0-255)",
+                pwm as f64,
+                l,
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn collect_power_supply(exporter: &mut PrometheusExporter) -> Result<()> {
+    let monitor = crate::power_supply::PowerSupplyMonitor::new()?;
+    for supply in monitor.supplies() {
+        let l = labels([("supply", supply.name.clone())]);
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("power_supply_info"),
+            "Power supply type, charging status and health",
+            1.0,
+            labels([
+                ("supply", supply.name.clone()),
+                ("type", supply.supply_type.to_string()),
+                ("status", supply.status.to_string()),
+                ("health", supply.health.to_string()),
+            ]),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("power_supply_online"),
+            "Whether the supply is online",
+            if supply.online { 1.0 } else { 0.0 },
+            l.clone(),
+        ));
+        let optional = [
+            (
+                "power_supply_capacity_percent",
+                "Remaining battery capacity",
+                supply.capacity_percent.map(f32::from),
+            ),
+            (
+                "power_supply_voltage_volts",
+                "Present voltage",
+                supply.voltage_v(),
+            ),
+            (
+                "power_supply_current_amperes",
+                "Present current",
+                supply.current_a(),
+            ),
+            (
+                "power_supply_power_watts",
+                "Present power draw",
+                supply.power_w(),
+            ),
+            (
+                "power_supply_energy_watthours",
+                "Remaining energy",
+                supply.energy_wh(),
+            ),
+            (
+                "power_supply_energy_full_watthours",
+                "Energy when fully charged",
+                supply.energy_full_wh(),
+            ),
+            (
+                "power_supply_wear_percent",
+                "Capacity lost relative to the design capacity",
+                supply.wear_level_percent(),
+            ),
+            (
+                "power_supply_temperature_celsius",
+                "Battery temperature",
+                supply.temperature_celsius(),
+            ),
+        ];
+        for (name, help, value) in optional {
+            if let Some(value) = value {
+                exporter.add(MetricFamily::gauge_with_labels(
+                    &exporter.prefixed(name),
+                    help,
+                    value as f64,
+                    l.clone(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn collect_pcie(exporter: &mut PrometheusExporter) -> Result<()> {
+    for dev in crate::pcie::PcieMonitor::enumerate()? {
+        let l = labels([
+            ("bdf", dev.bdf.clone()),
+            ("class", dev.device_class.to_string()),
+            ("driver", dev.driver.clone().unwrap_or_default()),
+        ]);
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("pcie_link_speed_gts"),
+            "Negotiated PCIe link speed in GT/s",
+            dev.current_link_speed.transfer_rate_gts(),
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("pcie_link_width"),
+            "Negotiated PCIe link width in lanes",
+            dev.current_link_width as f64,
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("pcie_max_link_width"),
+            "Maximum PCIe link width in lanes",
+            dev.max_link_width as f64,
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("pcie_link_downgraded"),
+            "Whether the link trained below its maximum speed or width",
+            if dev.is_downgraded() { 1.0 } else { 0.0 },
+            l,
+        ));
+    }
+    Ok(())
+}
+
+fn collect_numa(exporter: &mut PrometheusExporter) -> Result<()> {
+    let monitor = crate::numa::NumaMonitor::new()?;
+    for node in monitor.nodes() {
+        let l = labels([("node", node.id.to_string())]);
+        let gauges = [
+            ("numa_cpus", "CPUs in the node", node.cpus.len() as f64),
+            (
+                "numa_memory_total_bytes",
+                "Memory attached to the node",
+                node.memory_total_bytes as f64,
+            ),
+            (
+                "numa_memory_free_bytes",
+                "Free memory on the node",
+                node.memory_free_bytes as f64,
+            ),
+            (
+                "numa_memory_used_bytes",
+                "Used memory on the node",
+                node.memory_used_bytes as f64,
+            ),
+            (
+                "numa_hugepages_total",
+                "Huge pages reserved on the node",
+                node.hugepages_total as f64,
+            ),
+            (
+                "numa_hugepages_free",
+                "Free huge pages on the node",
+                node.hugepages_free as f64,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            exporter.add(MetricFamily::gauge_with_labels(
+                &exporter.prefixed(name),
+                help,
+                value,
+                l.clone(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn collect_io_scheduler(exporter: &mut PrometheusExporter) -> Result<()> {
+    const SECTOR_BYTES: f64 = 512.0;
+    let monitor = crate::io_scheduler::IoSchedulerMonitor::new()?;
+    for dev in monitor.devices() {
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("block_device_info"),
+            "Block device scheduler, media and model",
+            1.0,
+            labels([
+                ("device", dev.name.clone()),
+                ("scheduler", dev.scheduler.to_string()),
+                ("rotational", dev.rotational.to_string()),
+                ("model", dev.model.clone()),
+            ]),
+        ));
+        let l = labels([("device", dev.name.clone())]);
+        let s = &dev.stats;
+        let counters = [
+            (
+                "block_reads_completed_total",
+                "Reads completed",
+                s.reads_completed as f64,
+            ),
+            (
+                "block_writes_completed_total",
+                "Writes completed",
+                s.writes_completed as f64,
+            ),
+            (
+                "block_read_bytes_total",
+                "Bytes read",
+                s.sectors_read as f64 * SECTOR_BYTES,
+            ),
+            (
+                "block_written_bytes_total",
+                "Bytes written",
+                s.sectors_written as f64 * SECTOR_BYTES,
+            ),
+            (
+                "block_read_time_seconds_total",
+                "Time spent on reads",
+                s.read_time_ms as f64 / 1000.0,
+            ),
+            (
+                "block_write_time_seconds_total",
+                "Time spent on writes",
+                s.write_time_ms as f64 / 1000.0,
+            ),
+            (
+                "block_io_time_seconds_total",
+                "Time the device had I/O in flight",
+                s.io_time_ms as f64 / 1000.0,
+            ),
+            (
+                "block_io_weighted_time_seconds_total",
+                "In-flight I/O count integrated over time",
+                s.weighted_io_time_ms as f64 / 1000.0,
+            ),
+            (
+                "block_discards_completed_total",
+                "Discards completed",
+                s.discards_completed as f64,
+            ),
+        ];
+        for (name, help, value) in counters {
+            exporter.add(MetricFamily::counter_with_labels(
+                &exporter.prefixed(name),
+                help,
+                value,
+                l.clone(),
+            ));
+        }
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("block_io_in_flight"),
+            "I/O requests currently in flight",
+            s.in_flight as f64,
+            l.clone(),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("block_queue_depth"),
+            "Request queue depth",
+            dev.queue_depth as f64,
+            l,
+        ));
+    }
+    Ok(())
+}
+
+fn collect_smart(exporter: &mut PrometheusExporter) -> Result<()> {
+    let monitor = crate::smart::SmartMonitor::new()?;
+    for disk in monitor.disks() {
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("smart_device_info"),
+            "SMART device model, serial and overall health",
+            1.0,
+            labels([
+                ("device", disk.device.clone()),
+                ("model", disk.model.clone()),
+                ("serial", disk.serial.clone()),
+                ("health", disk.health.to_string()),
+            ]),
+        ));
+        let l = labels([("device", disk.device.clone())]);
+        let gauges = [
+            (
+                "smart_temperature_celsius",
+                "Drive temperature",
+                Some(disk.temperature_celsius as f64),
+            ),
+            (
+                "smart_reallocated_sectors",
+                "Reallocated sector count",
+                Some(disk.reallocated_sectors as f64),
+            ),
+            (
+                "smart_pending_sectors",
+                "Sectors pending reallocation",
+                Some(disk.pending_sectors as f64),
+            ),
+            (
+                "smart_wear_leveling_percent",
+                "Remaining endurance reported by the wear-leveling attribute",
+                disk.wear_leveling_percent.map(f64::from),
+            ),
+            (
+                "smart_nvme_percentage_used",
+                "NVMe endurance used estimate",
+                disk.nvme_percentage_used.map(f64::from),
+            ),
+            (
+                "smart_nvme_available_spare_percent",
+                "NVMe available spare capacity",
+                disk.nvme_available_spare.map(f64::from),
+            ),
+        ];
+        for (name, help, value) in gauges {
+            if let Some(value) = value {
+                exporter.add(MetricFamily::gauge_with_labels(
+                    &exporter.prefixed(name),
+                    help,
+                    value,
+                    l.clone(),
+                ));
+            }
+        }
+        let counters = [
+            (
+                "smart_power_on_hours_total",
+                "Power-on hours",
+                disk.power_on_hours as f64,
+            ),
+            (
+                "smart_power_cycles_total",
+                "Power cycle count",
+                disk.power_cycle_count as f64,
+            ),
+            (
+                "smart_uncorrectable_errors_total",
+                "Uncorrectable errors",
+                disk.uncorrectable_errors as f64,
+            ),
+            (
+                "smart_written_bytes_total",
+                "Bytes written over the drive's lifetime",
+                disk.total_bytes_written as f64,
+            ),
+            (
+                "smart_read_bytes_total",
+                "Bytes read over the drive's lifetime",
+                disk.total_bytes_read as f64,
+            ),
+        ];
+        for (name, help, value) in counters {
+            exporter.add(MetricFamily::counter_with_labels(
+                &exporter.prefixed(name),
+                help,
+                value,
+                l.clone(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn collect_cgroup(exporter: &mut PrometheusExporter) -> Result<()> {
+    let monitor = crate::cgroup_monitor::CgroupMonitor::new()?;
+    for cg in &monitor.overview().cgroups {
+        let l = labels([("cgroup", cg.path.clone())]);
+        let mut counters: Vec<(&str, &str, f64)> = Vec::new();
+        let mut gauges: Vec<(&str, &str, f64)> = Vec::new();
+        if let Some(cpu) = &cg.cpu {
+            counters.push((
+                "cgroup_cpu_usage_seconds_total",
+                "CPU time consumed",
+                cpu.usage_us as f64 / 1e6,
+            ));
+            counters.push((
+                "cgroup_cpu_throttled_periods_total",
+                "Enforcement periods in which the cgroup was throttled",
+                cpu.throttled_periods as f64,
+            ));
+            counters.push((
+                "cgroup_cpu_throttled_seconds_total",
+                "Time the cgroup spent throttled",
+                cpu.throttled_time_us as f64 / 1e6,
+            ));
+            gauges.push((
+                "cgroup_cpu_limit_cpus",
+                "CPU quota expressed in CPUs",
+                cpu.effective_cpus,
+            ));
+        }
+        if let Some(mem) = &cg.memory {
+            gauges.push((
+                "cgroup_memory_usage_bytes",
+                "Memory charged to the cgroup",
+                mem.usage_bytes as f64,
+            ));
+            if mem.limit_bytes != u64::MAX {
+                gauges.push((
+                    "cgroup_memory_limit_bytes",
+                    "Memory limit",
+                    mem.limit_bytes as f64,
+                ));
+            }
+            counters.push((
+                "cgroup_memory_oom_events_total",
+                "OOM kills inside the cgroup",
+                mem.oom_events as f64,
+            ));
+        }
+        if let Some(io) = &cg.io {
+            counters.push((
+                "cgroup_io_read_bytes_total",
+                "Bytes read",
+                io.read_bytes as f64,
+            ));
+            counters.push((
+                "cgroup_io_written_bytes_total",
+                "Bytes written",
+                io.write_bytes as f64,
+            ));
+            counters.push((
+                "cgroup_io_reads_total",
+                "Read operations",
+                io.read_ios as f64,
+            ));
+            counters.push((
+                "cgroup_io_writes_total",
+                "Write operations",
+                io.write_ios as f64,
+            ));
+        }
+        if let Some(pids) = &cg.pids {
+            gauges.push(("cgroup_pids", "Tasks in the cgroup", pids.current as f64));
+            if pids.limit != u64::MAX && pids.limit > 0 {
+                gauges.push(("cgroup_pids_limit", "Task limit", pids.limit as f64));
+            }
+        }
+        for (name, help, value) in counters {
+            exporter.add(MetricFamily::counter_with_labels(
+                &exporter.prefixed(name),
+                help,
+                value,
+                l.clone(),
+            ));
+        }
+        for (name, help, value) in gauges {
+            exporter.add(MetricFamily::gauge_with_labels(
+                &exporter.prefixed(name),
+                help,
+                value,
+                l.clone(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn collect_interrupts(exporter: &mut PrometheusExporter) -> Result<()> {
+    let monitor = crate::interrupt_map::InterruptMapMonitor::new()?;
+    let mut family = MetricFamily {
+        name: exporter.prefixed("interrupts_total"),
+        help: "Interrupts serviced per CPU".into(),
+        metric_type: MetricType::Counter,
+        samples: Vec::new(),
+    };
+    for irq in monitor.interrupts() {
+        for (cpu, count) in irq.per_cpu_counts.iter().enumerate() {
+            family.samples.push(sample(
+                labels([
+                    ("irq", irq.irq.clone()),
+                    ("type", irq.interrupt_type.to_string()),
+                    ("description", irq.description.clone()),
+                    ("cpu", cpu.to_string()),
+                ]),
+                *count as f64,
+            ));
+        }
+    }
+    exporter.add(family);
+    exporter.add(MetricFamily::gauge(
+        &exporter.prefixed("interrupts_imbalance_ratio"),
+        "Busiest to quietest CPU interrupt ratio",
+        monitor.analysis().imbalance_ratio,
+    ));
+    Ok(())
+}
+
+fn collect_ipmi(exporter: &mut PrometheusExporter) -> Result<()> {
+    use crate::datacenter::{IpmiController, IpmiSensorType, SensorStatus};
+
+    let controller = IpmiController::new();
+    let sensors = controller
+        .read_sensors()
+        .map_err(|e| SimonError::CommandFailed(e.to_string()))?;
+    for sensor in sensors {
+        let sensor_type = match sensor.sensor_type {
+            IpmiSensorType::Temperature => "temperature",
+            IpmiSensorType::Voltage => "voltage",
+            IpmiSensorType::Fan => "fan",
+            IpmiSensorType::Power => "power",
+            IpmiSensorType::Current => "current",
+            IpmiSensorType::Other => "other",
+        };
+        let state = match sensor.status {
+            SensorStatus::Ok => 0.0,
+            SensorStatus::Warning => 1.0,
+            SensorStatus::Critical => 2.0,
+            SensorStatus::NonRecoverable => 3.0,
+            SensorStatus::Unknown => -1.0,
+        };
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("ipmi_sensor_value"),
+            "BMC sensor reading in the sensor's unit",
+            sensor.value,
+            labels([
+                ("sensor", sensor.name.clone()),
+                ("type", sensor_type.to_string()),
+                ("unit", sensor.unit.clone()),
+            ]),
+        ));
+        exporter.add(MetricFamily::gauge_with_labels(
+            &exporter.prefixed("ipmi_sensor_state"),
+            "BMC sensor state (0=ok, 1=warning, 2=critical, 3=non-recoverable, -1=unknown)",
+            state,
+            labels([("sensor", sensor.name)]),
+        ));
+    }
+    if let Ok(power) = controller.power_reading() {
+        exporter.add(MetricFamily::gauge(
+            &exporter.prefixed("ipmi_power_watts"),
+            "Chassis power draw reported by the BMC",
+            power.current_watts,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct Mock {
+        name: &'static str,
+        default_enabled: bool,
+        delay: Duration,
+        fail: bool,
+        calls: AtomicUsize,
+    }
+
+    impl Mock {
+        fn new(name: &'static str) -> Self {
+            Self {
+                name,
+                default_enabled: true,
+                delay: Duration::ZERO,
+                fail: false,
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl Collector for Mock {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn default_enabled(&self) -> bool {
+            self.default_enabled
+        }
+
+        fn collect(&self, exporter: &mut PrometheusExporter) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(self.delay);
+            if self.fail {
+                return Err(SimonError::FeatureNotAvailable("mock".into()));
+            }
+            exporter.add(MetricFamily::gauge_with_labels(
+                &exporter.prefixed("mock_value"),
+                "Mock value",
+                1.0,
+                labels([("source", self.name.to_string())]),
+            ));
+            Ok(())
+        }
+    }
+
+    fn scrape(registry: &CollectorRegistry) -> String {
+        let mut exporter = PrometheusExporter::new("test");
+        registry.collect(&mut exporter);
+        exporter.export()
+    }
+
+    #[test]
+    fn test_success_meta_metrics() {
+        let registry = CollectorRegistry::new(CollectorsConfig::default());
+        registry.register(Arc::new(Mock::new("good")));
+        registry.register(Arc::new(Mock {
+            fail: true,
+            ..Mock::new("bad")
+        }));
+
+        let output = scrape(&registry);
+        assert!(output.contains("test_mock_value{source=\"good\"} 1"));
+        assert!(!output.contains("source=\"bad\""));
+        assert!(output.contains("test_scrape_collector_success{collector=\"good\"} 1"));
+        assert!(output.contains("test_scrape_collector_success{collector=\"bad\"} 0"));
+        assert!(output.contains("test_scrape_collector_duration_seconds{collector=\"bad\"}"));
+        // Both collectors share one family header
+        assert_eq!(output.matches("# TYPE test_mock_value gauge").count(), 1);
+    }
+
+    #[test]
+    fn test_enable_disable() {
+        let config = CollectorsConfig {
+            enable: vec!["optin".into(), "missing".into()],
+            disable: vec!["noisy".into()],
+            ..Default::default()
+        };
+        let registry = CollectorRegistry::new(config);
+        registry.register(Arc::new(Mock::new("plain")));
+        registry.register(Arc::new(Mock::new("noisy")));
+        registry.register(Arc::new(Mock {
+            default_enabled: false,
+            ..Mock::new("optin")
+        }));
+        registry.register(Arc::new(Mock {
+            default_enabled: false,
+            ..Mock::new("off")
+        }));
+
+        assert!(registry.is_enabled("plain"));
+        assert!(!registry.is_enabled("noisy"));
+        assert!(registry.is_enabled("optin"));
+        assert!(!registry.is_enabled("off"));
+        assert_eq!(registry.unknown_names(), vec!["missing".to_string()]);
+
+        let output = scrape(&registry);
+        assert!(output.contains("collector=\"optin\""));
+        assert!(!output.contains("collector=\"noisy\""));
+        assert!(!output.contains("collector=\"off\""));
+    }
+
+    #[test]
+    fn test_timeout_and_in_flight_skip() {
+        let config = CollectorsConfig {
+            timeouts: BTreeMap::from([("slow".to_string(), 50)]),
+            ..Default::default()
+        };
+        let slow = Arc::new(Mock {
+            delay: Duration::from_millis(400),
+            ..Mock::new("slow")
+        });
+        let registry = CollectorRegistry::new(config);
+        registry.register(slow.clone());
+        registry.register(Arc::new(Mock::new("fast")));
+        assert_eq!(registry.timeout("slow"), Duration::from_millis(50));
+        assert_eq!(registry.timeout("fast"), Duration::from_millis(5000));
+
+        let started = Instant::now();
+        let output = scrape(&registry);
+        assert!(started.elapsed() < Duration::from_millis(300));
+        assert!(output.contains("test_scrape_collector_success{collector=\"slow\"} 0"));
+        assert!(output.contains("test_scrape_collector_success{collector=\"fast\"} 1"));
+        assert!(!output.contains("source=\"slow\""));
+
+        // The first call is still sleeping, so the next scrape must not start another
+        let output = scrape(&registry);
+        assert!(output.contains("test_scrape_collector_success{collector=\"slow\"} 0"));
+        assert_eq!(slow.calls.load(Ordering::SeqCst), 1);
+
+        std::thread::sleep(Duration::from_millis(500));
+        scrape(&registry);
+        assert_eq!(slow.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_panicking_collector() {
+        struct Panics;
+        impl Collector for Panics {
+            fn name(&self) -> &'static str {
+                "panics"
+            }
+            fn collect(&self, _: &mut PrometheusExporter) -> Result<()> {
+                panic!("collector bug");
+            }
+        }
+
+        let registry = CollectorRegistry::new(CollectorsConfig::default());
+        registry.register(Arc::new(Panics));
+        let output = scrape(&registry);
+        assert!(output.contains("test_scrape_collector_success{collector=\"panics\"} 0"));
+        // The in-flight flag is cleared, so it runs again next time
+        assert!(!registry.read()[0].running.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_builtin_registry() {
+        let registry = CollectorRegistry::with_defaults(CollectorsConfig::default());
+        let names = registry.names();
+        for name in [
+            "cpu",
+            "rapl",
+            "edac",
+            "thermal_zone",
+            "pcie",
+            "numa",
+            "smart",
+            "ipmi",
+        ] {
+            assert!(names.contains(&name), "missing {}", name);
+        }
+        assert!(registry.is_enabled("rapl"));
+        assert!(!registry.is_enabled("smart"));
+        assert!(!registry.is_enabled("ipmi"));
+        assert!(registry.unknown_names().is_empty());
+
+        let config: CollectorsConfig =
+            toml::from_str("enable = [\"smart\"]\n[timeouts]\nsmart = 15000\n").unwrap();
+        let registry = CollectorRegistry::with_defaults(config);
+        assert!(registry.is_enabled("smart"));
+        assert_eq!(registry.timeout("smart"), Duration::from_secs(15));
+        assert_eq!(registry.timeout("cpu"), Duration::from_secs(5));
+
+        assert_eq!(
+            CollectorRegistry::system().names(),
+            ["cpu", "memory", "gpu", "disk", "network"]
+        );
+    }
+}
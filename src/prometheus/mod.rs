@@ -0,0 +1,1557 @@
+//! Prometheus Metrics Exporter
+//!
+//! Exports Silicon Monitor metrics in Prometheus exposition format with proper
+//! `# HELP`, `# TYPE` annotations and label support. Compatible with
+//! Prometheus, Grafana, and other metric collection systems.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::prometheus::{PrometheusExporter, MetricFamily};
+//!
+//! let mut exporter = PrometheusExporter::new("simon");
+//!
+//! // Collect system metrics
+//! exporter.collect_system_metrics();
+//!
+//! // Export in Prometheus text format
+//! let output = exporter.export();
+//! println!("{}", output);
+//! // Output:
+//! // # HELP simon_cpu_usage_percent CPU utilization percentage
+//! // # TYPE simon_cpu_usage_percent gauge
+//! // simon_cpu_usage_percent 42.5
+//! // ...
+//! ```
+//!
+//! Hardware subsystems contribute metrics through the [`Collector`] trait; a
+//! [`CollectorRegistry`] runs the enabled collectors with per-collector
+//! timeouts and reports node_exporter-style scrape meta-metrics.
+
+pub mod collectors;
+
+pub use collectors::{Collector, CollectorRegistry, CollectorsConfig, FnCollector};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Prometheus metric type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetricType {
+    /// A gauge is a metric that represents a single numerical value that can go up and down
+    Gauge,
+    /// A counter is a metric that represents a single monotonically increasing counter
+    Counter,
+    /// A histogram samples observations and counts them in configurable buckets
+    Histogram,
+    /// A summary is similar to a histogram but calculates configurable quantiles
+    Summary,
+    /// Untyped metric
+    Untyped,
+}
+
+impl std::fmt::Display for MetricType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gauge => write!(f, "gauge"),
+            Self::Counter => write!(f, "counter"),
+            Self::Histogram => write!(f, "histogram"),
+            Self::Summary => write!(f, "summary"),
+            Self::Untyped => write!(f, "untyped"),
+        }
+    }
+}
+
+/// A single metric sample with optional labels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSample {
+    /// Metric name suffix (empty for simple metrics)
+    pub suffix: String,
+    /// Label key-value pairs
+    pub labels: BTreeMap<String, String>,
+    /// Metric value
+    pub value: f64,
+}
+
+/// A complete metric family with metadata and samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricFamily {
+    /// Metric name
+    pub name: String,
+    /// Help text
+    pub help: String,
+    /// Metric type
+    pub metric_type: MetricType,
+    /// Samples
+    pub samples: Vec<MetricSample>,
+}
+
+impl MetricFamily {
+    /// Create a new gauge metric
+    pub fn gauge(name: &str, help: &str, value: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: MetricType::Gauge,
+            samples: vec![MetricSample {
+                suffix: String::new(),
+                labels: BTreeMap::new(),
+                value,
+            }],
+        }
+    }
+
+    /// Create a gauge with labels
+    pub fn gauge_with_labels(
+        name: &str,
+        help: &str,
+        value: f64,
+        labels: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: MetricType::Gauge,
+            samples: vec![MetricSample {
+                suffix: String::new(),
+                labels,
+                value,
+            }],
+        }
+    }
+
+    /// Create a counter metric
+    pub fn counter(name: &str, help: &str, value: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: MetricType::Counter,
+            samples: vec![MetricSample {
+                suffix: String::new(),
+                labels: BTreeMap::new(),
+                value,
+            }],
+        }
+    }
+
+    /// Create a counter with labels
+    pub fn counter_with_labels(
+        name: &str,
+        help: &str,
+        value: f64,
+        labels: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric_type: MetricType::Counter,
+            samples: vec![MetricSample {
+                suffix: String::new(),
+                labels,
+                value,
+            }],
+        }
+    }
+
+    /// Add a labeled sample to this family
+    pub fn add_sample(&mut self, value: f64, labels: BTreeMap<String, String>) {
+        self.samples.push(MetricSample {
+            suffix: String::new(),
+            labels,
+            value,
+        });
+    }
+
+    /// Format this metric family in Prometheus exposition format
+    pub fn format(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("# HELP {} {}\n", self.name, self.help));
+        out.push_str(&format!("# TYPE {} {}\n", self.name, self.metric_type));
+
+        for sample in &self.samples {
+            let full_name = if sample.suffix.is_empty() {
+                self.name.clone()
+            } else {
+                format!("{}_{}", self.name, sample.suffix)
+            };
+
+            if sample.labels.is_empty() {
+                out.push_str(&format!("{} {}\n", full_name, format_value(sample.value)));
+            } else {
+                let label_str: Vec<String> = sample
+                    .labels
+                    .iter()
+                    .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+                    .collect();
+                out.push_str(&format!(
+                    "{}{{{}}} {}\n",
+                    full_name,
+                    label_str.join(","),
+                    format_value(sample.value)
+                ));
+            }
+        }
+
+        out
+    }
+}
+
+/// Maximum number of per-process energy series exported
+pub const MAX_ENERGY_PROCESSES: usize = 50;
+
+/// Prometheus metrics exporter for Silicon Monitor
+pub struct PrometheusExporter {
+    /// Namespace prefix for all metrics
+    prefix: String,
+    /// Collected metric families
+    families: Vec<MetricFamily>,
+    /// Registry behind [`Self::collect_system_metrics`], built on first use
+    system: Option<Arc<CollectorRegistry>>,
+}
+
+impl PrometheusExporter {
+    /// Create a new exporter with the given namespace prefix
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: sanitize_metric_name(prefix),
+            families: Vec::new(),
+            system: None,
+        }
+    }
+
+    /// Add a metric family
+    ///
+    /// Samples of a family whose name was already added are merged into the
+    /// existing family so each metric gets a single `# HELP`/`# TYPE` header.
+    pub fn add(&mut self, family: MetricFamily) {
+        match self.families.iter_mut().find(|f| f.name == family.name) {
+            Some(existing) => existing.samples.extend(family.samples),
+            None => self.families.push(family),
+        }
+    }
+
+    /// Collect CPU, memory, GPU, disk and network metrics
+    ///
+    /// Use a [`CollectorRegistry`] directly to add the other built-in
+    /// collectors (RAPL, EDAC, thermal zones, ...) or to choose timeouts.
+    pub fn collect_system_metrics(&mut self) {
+        let registry = Arc::clone(
+            self.system
+                .get_or_insert_with(|| Arc::new(CollectorRegistry::system())),
+        );
+        registry.collect(self);
+    }
+
+    /// Metric name under this exporter's namespace prefix
+    pub fn prefixed(&self, name: &str) -> String {
+        format!("{}_{}", self.prefix, name)
+    }
+
+    fn collect_cpu_metrics(&mut self) {
+        if let Ok(cpu) = crate::CpuStats::new() {
+            self.add(MetricFamily::gauge(
+                &self.prefixed("cpu_usage_percent"),
+                "Total CPU utilization percentage",
+                100.0_f64 - cpu.total.idle as f64,
+            ));
+
+            // Per-core utilization
+            let mut per_core = MetricFamily {
+                name: self.prefixed("cpu_core_usage_percent"),
+                help: "Per-core CPU utilization percentage".into(),
+                metric_type: MetricType::Gauge,
+                samples: Vec::new(),
+            };
+            for core in &cpu.cores {
+                let usage = 100.0 - core.idle.unwrap_or(100.0) as f64;
+                let mut labels = BTreeMap::new();
+                labels.insert("core".into(), core.id.to_string());
+                per_core.samples.push(MetricSample {
+                    suffix: String::new(),
+                    labels,
+                    value: usage,
+                });
+            }
+            if !per_core.samples.is_empty() {
+                self.add(per_core);
+            }
+
+            self.add(MetricFamily::gauge(
+                &self.prefixed("cpu_cores_total"),
+                "Total number of CPU cores",
+                cpu.cores.len() as f64,
+            ));
+        }
+    }
+
+    fn collect_memory_metrics(&mut self) {
+        if let Ok(mem) = crate::MemoryStats::new() {
+            self.add(MetricFamily::gauge(
+                &self.prefixed("memory_total_bytes"),
+                "Total physical memory in bytes",
+                mem.ram.total as f64,
+            ));
+            self.add(MetricFamily::gauge(
+                &self.prefixed("memory_used_bytes"),
+                "Used physical memory in bytes",
+                mem.ram.used as f64,
+            ));
+            self.add(MetricFamily::gauge(
+                &self.prefixed("memory_free_bytes"),
+                "Free physical memory in bytes",
+                mem.ram.free as f64,
+            ));
+            if mem.ram.total > 0 {
+                self.add(MetricFamily::gauge(
+                    &self.prefixed("memory_usage_percent"),
+                    "Memory utilization percentage",
+                    (mem.ram.used as f64 / mem.ram.total as f64) * 100.0,
+                ));
+            }
+        }
+    }
+
+    fn collect_gpu_metrics(&mut self) {
+        if let Ok(gpus) = crate::GpuCollection::auto_detect() {
+            if let Ok(snapshots) = gpus.snapshot_all() {
+                // GPU count
+                self.add(MetricFamily::gauge(
+                    &self.prefixed("gpu_count"),
+                    "Number of detected GPUs",
+                    snapshots.len() as f64,
+                ));
+
+                for (i, info) in snapshots.iter().enumerate() {
+                    let mut base_labels = BTreeMap::new();
+                    base_labels.insert("gpu".into(), i.to_string());
+                    base_labels.insert("name".into(), info.static_info.name.clone());
+                    base_labels.insert("vendor".into(), info.static_info.vendor.to_string());
+
+                    // Utilization
+                    self.add(MetricFamily::gauge_with_labels(
+                        &self.prefixed("gpu_utilization_percent"),
+                        "GPU compute utilization percentage",
+                        info.dynamic_info.utilization as f64,
+                        base_labels.clone(),
+                    ));
+
+                    // Temperature
+                    if let Some(temp) = info.dynamic_info.thermal.temperature {
+                        self.add(MetricFamily::gauge_with_labels(
+                            &self.prefixed("gpu_temperature_celsius"),
+                            "GPU temperature in degrees Celsius",
+                            temp as f64,
+                            base_labels.clone(),
+                        ));
+                    }
+
+                    // Power
+                    if let Some(power) = info.dynamic_info.power.draw {
+                        self.add(MetricFamily::gauge_with_labels(
+                            &self.prefixed("gpu_power_watts"),
+                            "GPU power draw in watts",
+                            power as f64 / 1000.0,
+                            base_labels.clone(),
+                        ));
+                    }
+
+                    // Memory
+                    self.add(MetricFamily::gauge_with_labels(
+                        &self.prefixed("gpu_memory_total_bytes"),
+                        "GPU total memory in bytes",
+                        info.dynamic_info.memory.total as f64,
+                        base_labels.clone(),
+                    ));
+                    self.add(MetricFamily::gauge_with_labels(
+                        &self.prefixed("gpu_memory_used_bytes"),
+                        "GPU used memory in bytes",
+                        info.dynamic_info.memory.used as f64,
+                        base_labels.clone(),
+                    ));
+                    self.add(MetricFamily::gauge_with_labels(
+                        &self.prefixed("gpu_memory_free_bytes"),
+                        "GPU free memory in bytes",
+                        info.dynamic_info.memory.free as f64,
+                        base_labels.clone(),
+                    ));
+
+                    // Clocks
+                    if let Some(graphics) = info.dynamic_info.clocks.graphics {
+                        self.add(MetricFamily::gauge_with_labels(
+                            &self.prefixed("gpu_clock_core_mhz"),
+                            "GPU core clock in MHz",
+                            graphics as f64,
+                            base_labels.clone(),
+                        ));
+                    }
+                    if let Some(mem_clk) = info.dynamic_info.clocks.memory {
+                        self.add(MetricFamily::gauge_with_labels(
+                            &self.prefixed("gpu_clock_memory_mhz"),
+                            "GPU memory clock in MHz",
+                            mem_clk as f64,
+                            base_labels.clone(),
+                        ));
+                    }
+
+                    // Fan
+                    if let Some(fan_speed) = info.dynamic_info.thermal.fan_speed {
+                        self.add(MetricFamily::gauge_with_labels(
+                            &self.prefixed("gpu_fan_speed_percent"),
+                            "GPU fan speed percentage",
+                            fan_speed as f64,
+                            base_labels.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect_disk_metrics(&mut self) {
+        if let Ok(disks) = crate::disk::enumerate_disks() {
+            for disk in &disks {
+                if let Ok(filesystems) = disk.filesystem_info() {
+                    for fs in &filesystems {
+                        let mut labels = BTreeMap::new();
+                        labels.insert("device".into(), disk.name().to_string());
+                        labels.insert("mount".into(), fs.mount_point.to_string_lossy().to_string());
+
+                        self.add(MetricFamily::gauge_with_labels(
+                            &self.prefixed("disk_total_bytes"),
+                            "Total disk capacity in bytes",
+                            fs.total_size as f64,
+                            labels.clone(),
+                        ));
+                        self.add(MetricFamily::gauge_with_labels(
+                            &self.prefixed("disk_used_bytes"),
+                            "Used disk space in bytes",
+                            fs.used_size as f64,
+                            labels.clone(),
+                        ));
+                        self.add(MetricFamily::gauge_with_labels(
+                            &self.prefixed("disk_available_bytes"),
+                            "Available disk space in bytes",
+                            fs.available_size as f64,
+                            labels.clone(),
+                        ));
+                        if fs.total_size > 0 {
+                            self.add(MetricFamily::gauge_with_labels(
+                                &self.prefixed("disk_usage_percent"),
+                                "Disk utilization percentage",
+                                (fs.used_size as f64 / fs.total_size as f64) * 100.0,
+                                labels.clone(),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect_network_metrics(&mut self) {
+        if let Ok(mut monitor) = crate::NetworkMonitor::new() {
+            if let Ok(interfaces) = monitor.interfaces() {
+                for iface in &interfaces {
+                    let mut labels = BTreeMap::new();
+                    labels.insert("interface".into(), iface.name.clone());
+
+                    self.add(MetricFamily::counter_with_labels(
+                        &self.prefixed("network_rx_bytes_total"),
+                        "Total bytes received",
+                        iface.rx_bytes as f64,
+                        labels.clone(),
+                    ));
+
+                    // Use gauges for current rates
+                    let (rx_rate, tx_rate) = monitor.bandwidth_rate(&iface.name, iface);
+                    self.add(MetricFamily::gauge_with_labels(
+                        &self.prefixed("network_rx_bytes_per_sec"),
+                        "Network receive rate in bytes per second",
+                        rx_rate,
+                        labels.clone(),
+                    ));
+                    self.add(MetricFamily::gauge_with_labels(
+                        &self.prefixed("network_tx_bytes_per_sec"),
+                        "Network transmit rate in bytes per second",
+                        tx_rate,
+                        labels.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Add energy accounting counters from a report
+    ///
+    /// Per-process series are limited to the [`MAX_ENERGY_PROCESSES`] largest consumers
+    /// to bound cardinality; containers and systemd units are exported in full.
+    pub fn collect_energy_metrics(&mut self, report: &crate::energy_accounting::EnergyReport) {
+        let family = |name: String, help: &str| MetricFamily {
+            name,
+            help: help.into(),
+            metric_type: MetricType::Counter,
+            samples: Vec::new(),
+        };
+        let add_domains = |fam: &mut MetricFamily,
+                           usage: &crate::energy_accounting::EnergyUsage,
+                           labels: BTreeMap<String, String>| {
+            for (domain, joules) in [("cpu", usage.cpu_joules), ("gpu", usage.gpu_joules)] {
+                let mut l = labels.clone();
+                l.insert("domain".into(), domain.into());
+                fam.add_sample(joules, l);
+            }
+        };
+
+        let mut node = family(
+            self.prefixed("energy_node_joules_total"),
+            "Measured energy since accounting started",
+        );
+        add_domains(&mut node, &report.node, BTreeMap::new());
+        self.add(node);
+
+        let mut unattributed = family(
+            self.prefixed("energy_unattributed_joules_total"),
+            "Energy not attributed to any process",
+        );
+        add_domains(&mut unattributed, &report.unattributed, BTreeMap::new());
+        self.add(unattributed);
+
+        let mut cost = family(
+            self.prefixed("energy_cost_total"),
+            "Cost of measured energy at the configured tariff",
+        );
+        let mut labels = BTreeMap::new();
+        labels.insert("currency".into(), report.currency.clone());
+        cost.add_sample(report.node.cost, labels);
+        self.add(cost);
+
+        let mut co2 = family(
+            self.prefixed("energy_co2_grams_total"),
+            "Emissions of measured energy in grams CO2e at the configured intensity",
+        );
+        co2.add_sample(report.node.co2_grams, BTreeMap::new());
+        self.add(co2);
+
+        let mut processes = family(
+            self.prefixed("energy_process_joules_total"),
+            "Energy attributed to a process",
+        );
+        for p in report.processes.iter().take(MAX_ENERGY_PROCESSES) {
+            let mut labels = BTreeMap::new();
+            labels.insert("pid".into(), p.pid.to_string());
+            labels.insert("name".into(), p.usage.name.clone());
+            if let Some(ref c) = p.container {
+                labels.insert("container".into(), c.clone());
+            }
+            if let Some(ref u) = p.unit {
+                labels.insert("unit".into(), u.clone());
+            }
+            add_domains(&mut processes, &p.usage, labels);
+        }
+        self.add(processes);
+
+        for (metric, label, help, groups) in [
+            (
+                "energy_container_joules_total",
+                "container",
+                "Energy attributed to a container",
+                &report.containers,
+            ),
+            (
+                "energy_unit_joules_total",
+                "unit",
+                "Energy attributed to a systemd unit",
+                &report.units,
+            ),
+        ] {
+            let mut fam = family(self.prefixed(metric), help);
+            for g in groups {
+                let mut labels = BTreeMap::new();
+                labels.insert(label.into(), g.name.clone());
+                add_domains(&mut fam, g, labels);
+            }
+            self.add(fam);
+        }
+    }
+
+    /// Collect whole-node power metrics from a node power model report
+    pub fn collect_node_power_metrics(&mut self, report: &crate::node_power::NodePowerReport) {
+        let gauge = |name: String, help: &str, value: f64| {
+            let mut fam = MetricFamily {
+                name,
+                help: help.into(),
+                metric_type: MetricType::Gauge,
+                samples: Vec::new(),
+            };
+            fam.add_sample(value, BTreeMap::new());
+            fam
+        };
+
+        self.add(gauge(
+            self.prefixed("node_power_watts"),
+            "Node power at the wall (measured or modeled)",
+            report.node_watts,
+        ));
+        self.add(gauge(
+            self.prefixed("node_power_psu_loss_watts"),
+            "Power lost in PSU AC-DC conversion",
+            report.psu_loss_watts,
+        ));
+        self.add(gauge(
+            self.prefixed("node_power_psu_efficiency_ratio"),
+            "PSU efficiency used for reconciliation",
+            report.psu_efficiency,
+        ));
+        if let Some(residual) = report.residual_watts {
+            self.add(gauge(
+                self.prefixed("node_power_residual_watts"),
+                "DC power from the wall reading not explained by components",
+                residual,
+            ));
+        }
+
+        let mut components = MetricFamily {
+            name: self.prefixed("node_power_component_watts"),
+            help: "DC power drawn by a component".into(),
+            metric_type: MetricType::Gauge,
+            samples: Vec::new(),
+        };
+        for c in &report.components {
+            let mut labels = BTreeMap::new();
+            labels.insert("component".into(), c.name.clone());
+            labels.insert("kind".into(), c.kind.to_string());
+            labels.insert("provenance".into(), c.provenance.to_string());
+            components.add_sample(c.watts, labels);
+        }
+        self.add(components);
+    }
+
+    /// Collect hardware counter rates and derived ratios from a perf snapshot
+    pub fn collect_perf_metrics(&mut self, snapshot: &crate::perf::PerfSnapshot) {
+        let family = |name: String, help: &str| MetricFamily {
+            name,
+            help: help.into(),
+            metric_type: MetricType::Gauge,
+            samples: Vec::new(),
+        };
+        let cpu_label = |cpu: &str| {
+            let mut labels = BTreeMap::new();
+            labels.insert("cpu".to_string(), cpu.to_string());
+            labels
+        };
+
+        let mut rates = family(
+            self.prefixed("perf_events_per_second"),
+            "Hardware/software perf events per second summed over all CPUs",
+        );
+        for counter in snapshot.total.counts.keys() {
+            if let Some(rate) = snapshot.rate(*counter) {
+                let mut labels = BTreeMap::new();
+                labels.insert("event".into(), counter.name().to_string());
+                rates.add_sample(rate, labels);
+            }
+        }
+        self.add(rates);
+
+        let mut ipc = family(self.prefixed("perf_ipc"), "Instructions per cycle");
+        if let Some(v) = snapshot.total.ipc() {
+            ipc.add_sample(v, cpu_label("all"));
+        }
+        for c in &snapshot.per_cpu {
+            if let Some(v) = c.values.ipc() {
+                ipc.add_sample(v, cpu_label(&c.cpu.to_string()));
+            }
+        }
+        self.add(ipc);
+
+        let ratios: [(&str, &str, Option<f64>); 3] = [
+            (
+                "perf_llc_miss_ratio",
+                "Last-level cache misses per cache reference",
+                snapshot.total.llc_miss_rate(),
+            ),
+            (
+                "perf_llc_misses_per_kilo_instruction",
+                "Last-level cache misses per thousand instructions",
+                snapshot.total.llc_mpki(),
+            ),
+            (
+                "perf_branch_miss_ratio",
+                "Branch mispredictions per branch instruction",
+                snapshot.total.branch_miss_rate(),
+            ),
+        ];
+        for (name, help, value) in ratios {
+            if let Some(v) = value {
+                let mut fam = family(self.prefixed(name), help);
+                fam.add_sample(v, BTreeMap::new());
+                self.add(fam);
+            }
+        }
+
+        let mut stalls = family(
+            self.prefixed("perf_stalled_cycles_ratio"),
+            "Fraction of cycles stalled, by pipeline stage",
+        );
+        for (stage, value) in [
+            ("frontend", snapshot.total.frontend_stall_rate()),
+            ("backend", snapshot.total.backend_stall_rate()),
+        ] {
+            if let Some(v) = value {
+                let mut labels = BTreeMap::new();
+                labels.insert("stage".into(), stage.to_string());
+                stalls.add_sample(v, labels);
+            }
+        }
+        if !stalls.samples.is_empty() {
+            self.add(stalls);
+        }
+
+        if let Some(bw) = &snapshot.memory_bandwidth {
+            let mut fam = family(
+                self.prefixed("memory_bandwidth_bytes_per_second"),
+                "DRAM traffic measured by uncore memory controller counters",
+            );
+            for (direction, value) in [
+                ("read", bw.read_bytes_per_sec),
+                ("write", bw.write_bytes_per_sec),
+            ] {
+                let mut labels = BTreeMap::new();
+                labels.insert("direction".into(), direction.to_string());
+                fam.add_sample(value, labels);
+            }
+            self.add(fam);
+        }
+
+        let mut unavailable = family(
+            self.prefixed("perf_source_unavailable"),
+            "Perf events or sources that could not be opened (1 = unavailable)",
+        );
+        for entry in &snapshot.unavailable {
+            let source = entry.split(':').next().unwrap_or(entry);
+            let mut labels = BTreeMap::new();
+            labels.insert("source".into(), source.to_string());
+            unavailable.add_sample(1.0, labels);
+        }
+        self.add(unavailable);
+    }
+
+    /// Collect pressure stall metrics, system-wide and per cgroup
+    ///
+    /// Stall totals are exported as counters in seconds so `rate()` gives the
+    /// stalled fraction over any range; avg10 is exported as a gauge.
+    pub fn collect_psi_metrics(&mut self, snapshot: &crate::psi::PsiSnapshot) {
+        let mut totals = MetricFamily {
+            name: self.prefixed("pressure_stalled_seconds_total"),
+            help: "Cumulative time tasks were stalled on a resource".into(),
+            metric_type: MetricType::Counter,
+            samples: Vec::new(),
+        };
+        let mut avg10 = MetricFamily {
+            name: self.prefixed("pressure_avg10_percent"),
+            help: "Percent of the last 10 seconds tasks were stalled on a resource".into(),
+            metric_type: MetricType::Gauge,
+            samples: Vec::new(),
+        };
+        let scopes = std::iter::once(&snapshot.system).chain(&snapshot.cgroups);
+        for scope in scopes {
+            for p in &scope.resources {
+                let lines = [
+                    (crate::psi::StallKind::Some, Some(&p.some)),
+                    (crate::psi::StallKind::Full, p.full.as_ref()),
+                ];
+                for (kind, line) in lines {
+                    let Some(line) = line else { continue };
+                    let mut labels = BTreeMap::new();
+                    labels.insert("resource".into(), p.resource.to_string());
+                    labels.insert("kind".into(), kind.to_string());
+                    if let crate::psi::PressureScope::Cgroup(path) = &scope.scope {
+                        labels.insert("cgroup".into(), path.clone());
+                    }
+                    totals.add_sample(line.total_us as f64 / 1e6, labels.clone());
+                    avg10.add_sample(line.avg10, labels);
+                }
+            }
+        }
+        self.add(totals);
+        self.add(avg10);
+    }
+
+    /// Collect per-device I/O latency, queue and saturation metrics
+    ///
+    /// Values are per-interval `iostat -x` figures; quantiles are windowed
+    /// estimates from I/O-weighted interval awaits.
+    pub fn collect_io_latency_metrics(
+        &mut self,
+        snapshot: &crate::io_scheduler::latency::IoLatencySnapshot,
+    ) {
+        let gauge = |name: &str, help: &str| MetricFamily {
+            name: self.prefixed(name),
+            help: help.into(),
+            metric_type: MetricType::Gauge,
+            samples: Vec::new(),
+        };
+        let mut await_secs = gauge(
+            "disk_await_seconds",
+            "Average I/O latency including queueing over the last interval",
+        );
+        let mut quantiles = gauge(
+            "disk_latency_estimate_seconds",
+            "Windowed I/O latency quantile estimate",
+        );
+        let mut iops = gauge("disk_iops", "Completed I/Os per second");
+        let mut queue = gauge("disk_queue_size", "Average requests queued or in flight");
+        let mut util = gauge(
+            "disk_utilization_percent",
+            "Percent of time the device had I/O in flight",
+        );
+        let mut saturated = gauge(
+            "disk_saturated",
+            "Active saturation condition on the device",
+        );
+        let mut cgroup_lat = gauge(
+            "cgroup_io_avg_latency_seconds",
+            "blk-iolatency measured average latency per cgroup and device",
+        );
+
+        let device_labels = |device: &str| {
+            let mut labels = BTreeMap::new();
+            labels.insert("device".into(), device.to_string());
+            labels
+        };
+        for d in &snapshot.devices {
+            let ops = [
+                ("read", d.read_await_ms, d.reads_per_sec),
+                ("write", d.write_await_ms, d.writes_per_sec),
+            ];
+            for (op, await_ms, rate) in ops {
+                let mut labels = device_labels(&d.device);
+                labels.insert("op".into(), op.into());
+                if let Some(ms) = await_ms {
+                    await_secs.add_sample(ms / 1000.0, labels.clone());
+                }
+                iops.add_sample(rate, labels);
+            }
+            for (q, value) in [("0.5", d.p50_ms), ("0.99", d.p99_ms)] {
+                let Some(ms) = value else { continue };
+                let mut labels = device_labels(&d.device);
+                labels.insert("quantile".into(), q.into());
+                quantiles.add_sample(ms / 1000.0, labels);
+            }
+            queue.add_sample(d.avg_queue_size, device_labels(&d.device));
+            util.add_sample(d.utilization, device_labels(&d.device));
+        }
+        for w in &snapshot.warnings {
+            let mut labels = device_labels(&w.device);
+            labels.insert("kind".into(), w.kind.to_string());
+            saturated.add_sample(1.0, labels);
+        }
+        for c in &snapshot.cgroups {
+            let Some(us) = c.avg_lat_us else { continue };
+            let mut labels = device_labels(&c.device);
+            labels.insert("cgroup".into(), c.cgroup.clone());
+            cgroup_lat.add_sample(us as f64 / 1e6, labels);
+        }
+        for family in [await_secs, quantiles, iops, queue, util, saturated, cgroup_lat] {
+            self.add(family);
+        }
+    }
+
+    /// Collect filesystem fill rates, time-to-full projections and capacity alerts
+    ///
+    /// Projections are omitted for mounts that are not filling.
+    pub fn collect_capacity_metrics(&mut self, snapshot: &crate::capacity::CapacitySnapshot) {
+        use crate::predictive::IssueType;
+
+        let gauge = |name: &str, help: &str| MetricFamily {
+            name: self.prefixed(name),
+            help: help.into(),
+            metric_type: MetricType::Gauge,
+            samples: Vec::new(),
+        };
+        let mut usage = gauge(
+            "filesystem_usage_percent",
+            "Percent of usable filesystem space consumed",
+        );
+        let mut inode_usage = gauge("filesystem_inode_usage_percent", "Percent of inodes used");
+        let mut fill_rate = gauge(
+            "filesystem_fill_rate_bytes_per_hour",
+            "Fitted rate of space consumption (negative when freeing)",
+        );
+        let mut hours_to_full = gauge(
+            "filesystem_hours_to_full",
+            "Projected hours until space or inodes run out at the fitted rate",
+        );
+        let mut confidence = gauge(
+            "filesystem_forecast_confidence",
+            "R squared of the long-term space consumption fit",
+        );
+        let mut alerts = gauge("filesystem_capacity_alert", "Active capacity alert");
+
+        let mount_labels = |mount: &str| {
+            let mut labels = BTreeMap::new();
+            labels.insert("mountpoint".into(), mount.to_string());
+            labels
+        };
+        for f in &snapshot.forecasts {
+            let mut labels = mount_labels(&f.mount_point);
+            labels.insert("fstype".into(), f.fs_type.clone());
+            usage.add_sample(f.usage_percent, labels);
+            if let Some(pct) = f.inode_usage_percent {
+                inode_usage.add_sample(pct, mount_labels(&f.mount_point));
+            }
+            let windows = [
+                ("long", f.bytes.rate_per_hour),
+                ("recent", f.bytes.recent_rate_per_hour),
+            ];
+            for (window, rate) in windows {
+                let Some(rate) = rate else { continue };
+                let mut labels = mount_labels(&f.mount_point);
+                labels.insert("window".into(), window.into());
+                fill_rate.add_sample(rate, labels);
+            }
+            let resources = [
+                ("bytes", f.bytes.earliest_hours()),
+                ("inodes", f.inodes.as_ref().and_then(|p| p.earliest_hours())),
+            ];
+            for (resource, hours) in resources {
+                let Some(hours) = hours else { continue };
+                let mut labels = mount_labels(&f.mount_point);
+                labels.insert("resource".into(), resource.into());
+                hours_to_full.add_sample(hours, labels);
+            }
+            confidence.add_sample(f.bytes.confidence, mount_labels(&f.mount_point));
+        }
+        for a in &snapshot.alerts {
+            let issue = match a.issue_type {
+                IssueType::CapacityExhaustion => "capacity_exhaustion",
+                IssueType::InodeExhaustion => "inode_exhaustion",
+                IssueType::FillRateChange => "fill_rate_change",
+                _ => "other",
+            };
+            let mut labels = mount_labels(&a.component);
+            labels.insert("issue".into(), issue.into());
+            labels.insert("urgency".into(), a.urgency.to_string().to_lowercase());
+            alerts.add_sample(1.0, labels);
+        }
+        for family in [usage, inode_usage, fill_rate, hours_to_full, confidence, alerts] {
+            self.add(family);
+        }
+    }
+
+    /// Collect RDMA port state, link rate, throughput and IB/vendor counters
+    ///
+    /// Raw counters are exported as-is (`port_xmit_data` and `port_rcv_data`
+    /// as bytes) so `rate()` works without the monitor's interval; the
+    /// monitor's own rates are exported as gauges alongside.
+    pub fn collect_rdma_metrics(&mut self, snapshot: &crate::rdma::RdmaSnapshot) {
+        let family = |name: &str, help: &str, metric_type: MetricType| MetricFamily {
+            name: self.prefixed(name),
+            help: help.into(),
+            metric_type,
+            samples: Vec::new(),
+        };
+        let mut info = family(
+            "rdma_device_info",
+            "RDMA device with its PCIe function and NUMA node",
+            MetricType::Gauge,
+        );
+        let mut state = family(
+            "rdma_port_state",
+            "Logical port state (1=down, 2=init, 3=armed, 4=active, 5=active_defer)",
+            MetricType::Gauge,
+        );
+        let mut phys_state = family(
+            "rdma_port_physical_state",
+            "Physical port state (2=polling, 3=disabled, 5=link_up, 6=link_error_recovery)",
+            MetricType::Gauge,
+        );
+        let mut rate = family(
+            "rdma_port_rate_gbps",
+            "Negotiated link rate",
+            MetricType::Gauge,
+        );
+        let mut data = family(
+            "rdma_port_data_bytes_total",
+            "Bytes transferred through the port",
+            MetricType::Counter,
+        );
+        let mut throughput = family(
+            "rdma_port_throughput_bytes_per_second",
+            "Bytes per second over the last sampling interval",
+            MetricType::Gauge,
+        );
+        let mut utilization = family(
+            "rdma_port_utilization_percent",
+            "Busier direction as a percent of the link rate",
+            MetricType::Gauge,
+        );
+        let mut counters = family(
+            "rdma_port_counter_total",
+            "IB port counter (source=port) or driver counter (source=hw)",
+            MetricType::Counter,
+        );
+        let mut alerts = family(
+            "rdma_port_alert",
+            "Active RDMA port alert",
+            MetricType::Gauge,
+        );
+
+        let port_labels = |device: &str, port: u32| {
+            let mut labels = BTreeMap::new();
+            labels.insert("device".into(), device.to_string());
+            labels.insert("port".into(), port.to_string());
+            labels
+        };
+        for dev in &snapshot.devices {
+            let mut labels = BTreeMap::new();
+            labels.insert("device".into(), dev.name.clone());
+            if let Some(pci) = &dev.pci {
+                labels.insert("pci".into(), pci.bdf.clone());
+            }
+            if let Some(node) = dev.numa_node {
+                labels.insert("numa_node".into(), node.to_string());
+            }
+            if let Some(fw) = &dev.fw_ver {
+                labels.insert("fw_ver".into(), fw.clone());
+            }
+            info.add_sample(1.0, labels);
+
+            for port in &dev.ports {
+                let mut labels = port_labels(&dev.name, port.port);
+                labels.insert("link_layer".into(), port.link_layer.to_string());
+                state.add_sample(port.state as u8 as f64, labels);
+                phys_state.add_sample(
+                    port.phys_state as u8 as f64,
+                    port_labels(&dev.name, port.port),
+                );
+                if let Some(r) = &port.rate {
+                    rate.add_sample(r.gbps, port_labels(&dev.name, port.port));
+                }
+                let directions = [
+                    (
+                        "tx",
+                        port.tx_bytes(),
+                        port.rates.as_ref().map(|r| r.tx_bytes_per_sec),
+                    ),
+                    (
+                        "rx",
+                        port.rx_bytes(),
+                        port.rates.as_ref().map(|r| r.rx_bytes_per_sec),
+                    ),
+                ];
+                for (direction, total, per_sec) in directions {
+                    let mut labels = port_labels(&dev.name, port.port);
+                    labels.insert("direction".into(), direction.into());
+                    if let Some(bytes) = total {
+                        data.add_sample(bytes as f64, labels.clone());
+                    }
+                    if let Some(bytes) = per_sec {
+                        throughput.add_sample(bytes, labels);
+                    }
+                }
+                if let Some(pct) = port.rates.as_ref().and_then(|r| r.utilization_pct) {
+                    utilization.add_sample(pct, port_labels(&dev.name, port.port));
+                }
+                let all = [("port", &port.counters), ("hw", &port.hw_counters)];
+                for (source, values) in all {
+                    for (name, value) in values {
+                        if matches!(name.as_str(), "port_xmit_data" | "port_rcv_data") {
+                            continue;
+                        }
+                        let mut labels = port_labels(&dev.name, port.port);
+                        labels.insert("counter".into(), name.clone());
+                        labels.insert("source".into(), source.into());
+                        counters.add_sample(*value as f64, labels);
+                    }
+                }
+            }
+        }
+        for a in &snapshot.alerts {
+            let mut labels = port_labels(&a.device, a.port);
+            labels.insert("kind".into(), a.kind.to_string());
+            alerts.add_sample(1.0, labels);
+        }
+        for family in [
+            info,
+            state,
+            phys_state,
+            rate,
+            data,
+            throughput,
+            utilization,
+            counters,
+            alerts,
+        ] {
+            self.add(family);
+        }
+    }
+
+    /// Collected metric families
+    pub fn families(&self) -> &[MetricFamily] {
+        &self.families
+    }
+
+    /// Export all metrics in Prometheus text exposition format
+    pub fn export(&self) -> String {
+        let mut output = String::with_capacity(4096);
+
+        // Add metadata comment
+        output.push_str(&format!(
+            "# Silicon Monitor v{} Prometheus Metrics\n\n",
+            crate::VERSION
+        ));
+
+        for family in &self.families {
+            output.push_str(&family.format());
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Export metrics and clear collected data
+    pub fn export_and_clear(&mut self) -> String {
+        let output = self.export();
+        self.families.clear();
+        output
+    }
+
+    /// Get the content type for Prometheus exposition format
+    pub fn content_type() -> &'static str {
+        "text/plain; version=0.0.4; charset=utf-8"
+    }
+}
+
+/// Sanitize a string for use as a Prometheus metric name
+fn sanitize_metric_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Escape a label value for Prometheus format
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Format a float value for Prometheus (special handling for NaN, Inf)
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value.is_sign_positive() {
+            "+Inf".to_string()
+        } else {
+            "-Inf".to_string()
+        }
+    } else if value == value.floor() && value.abs() < 1e15 {
+        format!("{:.0}", value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gauge_format() {
+        let family = MetricFamily::gauge("test_metric", "A test metric", 42.5);
+        let output = family.format();
+        assert!(output.contains("# HELP test_metric A test metric"));
+        assert!(output.contains("# TYPE test_metric gauge"));
+        assert!(output.contains("test_metric 42.5"));
+    }
+
+    #[test]
+    fn test_labeled_metric() {
+        let mut labels = BTreeMap::new();
+        labels.insert("gpu".into(), "0".into());
+        labels.insert("name".into(), "RTX 4090".into());
+
+        let family = MetricFamily::gauge_with_labels("gpu_temp", "GPU temperature", 72.0, labels);
+        let output = family.format();
+        assert!(output.contains("gpu=\"0\""));
+        assert!(output.contains("name=\"RTX 4090\""));
+        assert!(output.contains("72"));
+    }
+
+    #[test]
+    fn test_sanitize_metric_name() {
+        assert_eq!(sanitize_metric_name("cpu.usage-percent"), "cpu_usage_percent");
+        assert_eq!(sanitize_metric_name("valid_name"), "valid_name");
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("hello"), "hello");
+        assert_eq!(escape_label_value("hello\"world"), "hello\\\"world");
+        assert_eq!(escape_label_value("line\nnewline"), "line\\nnewline");
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(42.0), "42");
+        assert_eq!(format_value(42.5), "42.5");
+        assert_eq!(format_value(f64::NAN), "NaN");
+        assert_eq!(format_value(f64::INFINITY), "+Inf");
+    }
+
+    #[test]
+    fn test_node_power_metrics() {
+        use crate::node_power::{
+            ComponentKind, ComponentPower, NodePowerInputs, NodePowerModel, WallPower, WallSource,
+        };
+
+        let report = NodePowerModel::default().compute(&NodePowerInputs {
+            measured: vec![ComponentPower::measured(
+                "socket0 package-0",
+                ComponentKind::Cpu,
+                120.0,
+            )],
+            wall: Some(WallPower {
+                watts: 300.0,
+                source: WallSource::Ipmi,
+                psu_output_watts: None,
+            }),
+            ..Default::default()
+        });
+
+        let mut exporter = PrometheusExporter::new("simon");
+        exporter.collect_node_power_metrics(&report);
+        let output = exporter.export();
+        assert!(output.contains("simon_node_power_watts 300"));
+        assert!(output.contains("# TYPE simon_node_power_residual_watts gauge"));
+        assert!(output.contains(
+            "simon_node_power_component_watts{component=\"socket0 package-0\",kind=\"cpu\",provenance=\"measured\"} 120"
+        ));
+    }
+
+    #[test]
+    fn test_perf_metrics() {
+        use crate::perf::{
+            CounterValues, CpuCounters, MemoryBandwidth, PerfAccess, PerfCounter, PerfSnapshot,
+        };
+
+        let mut values = CounterValues::default();
+        values.counts.insert(PerfCounter::Cycles, 2000.0);
+        values.counts.insert(PerfCounter::Instructions, 3000.0);
+        values.counts.insert(PerfCounter::CacheReferences, 100.0);
+        values.counts.insert(PerfCounter::LlcMisses, 25.0);
+        let snapshot = PerfSnapshot {
+            timestamp: 0,
+            elapsed_secs: 2.0,
+            access: PerfAccess::Full,
+            total: values.clone(),
+            per_cpu: vec![CpuCounters { cpu: 3, values }],
+            memory_bandwidth: Some(MemoryBandwidth {
+                read_bytes_per_sec: 1e9,
+                write_bytes_per_sec: 5e8,
+            }),
+            unavailable: vec!["stalled_cycles_frontend: not supported by this PMU".into()],
+        };
+
+        let mut exporter = PrometheusExporter::new("simon");
+        exporter.collect_perf_metrics(&snapshot);
+        let output = exporter.export();
+        assert!(output.contains("simon_perf_events_per_second{event=\"cycles\"} 1000"));
+        assert!(output.contains("simon_perf_ipc{cpu=\"all\"} 1.5"));
+        assert!(output.contains("simon_perf_ipc{cpu=\"3\"} 1.5"));
+        assert!(output.contains("simon_perf_llc_miss_ratio 0.25"));
+        assert!(!output.contains("simon_perf_branch_miss_ratio"));
+        assert!(output.contains(
+            "simon_memory_bandwidth_bytes_per_second{direction=\"read\"} 1000000000"
+        ));
+        assert!(output.contains(
+            "simon_perf_source_unavailable{source=\"stalled_cycles_frontend\"} 1"
+        ));
+    }
+
+    #[test]
+    fn test_psi_metrics() {
+        use crate::psi::{
+            PressureScope, PsiResource, PsiSnapshot, ResourcePressure, ScopePressure,
+        };
+
+        let memory = ResourcePressure::parse(
+            PsiResource::Memory,
+            "some avg10=1.50 avg60=0 avg300=0 total=2500000\nfull avg10=0.25 avg60=0 avg300=0 total=500000\n",
+        )
+        .unwrap();
+        let snapshot = PsiSnapshot {
+            timestamp: 0,
+            system: ScopePressure {
+                scope: PressureScope::System,
+                resources: vec![memory.clone()],
+            },
+            cgroups: vec![ScopePressure {
+                scope: PressureScope::Cgroup("/system.slice/db.service".into()),
+                resources: vec![memory],
+            }],
+        };
+
+        let mut exporter = PrometheusExporter::new("simon");
+        exporter.collect_psi_metrics(&snapshot);
+        let output = exporter.export();
+        assert!(output.contains("# TYPE simon_pressure_stalled_seconds_total counter"));
+        assert!(output.contains(
+            "simon_pressure_stalled_seconds_total{kind=\"some\",resource=\"memory\"} 2.5"
+        ));
+        assert!(output.contains(
+            "simon_pressure_avg10_percent{cgroup=\"/system.slice/db.service\",kind=\"full\",resource=\"memory\"} 0.25"
+        ));
+    }
+
+    #[test]
+    fn test_io_latency_metrics() {
+        use crate::io_scheduler::latency::{
+            CgroupIoLatency, DeviceLatency, IoLatencySnapshot, SaturationKind, SaturationWarning,
+        };
+        use crate::io_scheduler::IoStats;
+
+        let prev = IoStats {
+            reads_completed: 0,
+            reads_merged: 0,
+            sectors_read: 0,
+            read_time_ms: 0,
+            writes_completed: 0,
+            writes_merged: 0,
+            sectors_written: 0,
+            write_time_ms: 0,
+            in_flight: 0,
+            io_time_ms: 0,
+            weighted_io_time_ms: 0,
+            discards_completed: 0,
+        };
+        let now = IoStats {
+            reads_completed: 100,
+            read_time_ms: 2500,
+            io_time_ms: 1000,
+            weighted_io_time_ms: 40_000,
+            ..prev.clone()
+        };
+        let mut device = DeviceLatency::from_delta("sda", &prev, &now, 1.0).unwrap();
+        device.p99_ms = Some(80.0);
+        let snapshot = IoLatencySnapshot {
+            timestamp: 0,
+            interval_secs: 1.0,
+            devices: vec![device],
+            cgroups: vec![CgroupIoLatency {
+                cgroup: "/train.slice".into(),
+                device: "sda".into(),
+                reads_per_sec: 100.0,
+                writes_per_sec: 0.0,
+                read_bytes_per_sec: 0.0,
+                write_bytes_per_sec: 0.0,
+                avg_lat_us: Some(1500),
+                depth: None,
+                window_ms: Some(100),
+            }],
+            warnings: vec![SaturationWarning {
+                device: "sda".into(),
+                kind: SaturationKind::QueueBacklog,
+                message: String::new(),
+            }],
+        };
+
+        let mut exporter = PrometheusExporter::new("simon");
+        exporter.collect_io_latency_metrics(&snapshot);
+        let output = exporter.export();
+        assert!(output.contains("simon_disk_await_seconds{device=\"sda\",op=\"read\"} 0.025"));
+        assert!(!output.contains("simon_disk_await_seconds{device=\"sda\",op=\"write\"}"));
+        assert!(output.contains(
+            "simon_disk_latency_estimate_seconds{device=\"sda\",quantile=\"0.99\"} 0.08"
+        ));
+        assert!(output.contains("simon_disk_queue_size{device=\"sda\"} 40"));
+        assert!(output.contains("simon_disk_utilization_percent{device=\"sda\"} 100"));
+        assert!(output.contains("simon_disk_saturated{device=\"sda\",kind=\"queue_backlog\"} 1"));
+        assert!(output.contains(
+            "simon_cgroup_io_avg_latency_seconds{cgroup=\"/train.slice\",device=\"sda\"} 0.0015"
+        ));
+    }
+
+    #[test]
+    fn test_capacity_metrics() {
+        use crate::capacity::{CapacityForecast, CapacitySnapshot, Projection};
+        use crate::predictive::{IssueType, MaintenanceAlert, Urgency};
+
+        let snapshot = CapacitySnapshot {
+            timestamp: 0,
+            forecasts: vec![CapacityForecast {
+                mount_point: "/ckpt".into(),
+                fs_type: "xfs".into(),
+                total_bytes: 1 << 40,
+                available_bytes: 1 << 38,
+                usage_percent: 75.0,
+                bytes: Projection {
+                    rate_per_hour: Some(1e9),
+                    recent_rate_per_hour: Some(1e11),
+                    hours_to_full: Some(270.0),
+                    recent_hours_to_full: Some(2.5),
+                    confidence: 0.75,
+                    ..Default::default()
+                },
+                inode_usage_percent: None,
+                inodes: None,
+                samples: 100,
+                history_hours: 24.0,
+            }],
+            alerts: vec![MaintenanceAlert {
+                component: "/ckpt".into(),
+                issue_type: IssueType::CapacityExhaustion,
+                urgency: Urgency::Critical,
+                message: String::new(),
+                eta_hours: Some(2.5),
+                degradation_rate: Some(1e11),
+                current_value: 75.0,
+                threshold: 100.0,
+                action: String::new(),
+                confidence: 0.5,
+            }],
+        };
+
+        let mut exporter = PrometheusExporter::new("simon");
+        exporter.collect_capacity_metrics(&snapshot);
+        let output = exporter.export();
+        assert!(output.contains(
+            "simon_filesystem_usage_percent{fstype=\"xfs\",mountpoint=\"/ckpt\"} 75"
+        ));
+        assert!(output.contains(
+            "simon_filesystem_fill_rate_bytes_per_hour{mountpoint=\"/ckpt\",window=\"recent\"} 100000000000"
+        ));
+        assert!(output.contains(
+            "simon_filesystem_hours_to_full{mountpoint=\"/ckpt\",resource=\"bytes\"} 2.5"
+        ));
+        assert!(!output.contains("resource=\"inodes\""));
+        assert!(output.contains(
+            "simon_filesystem_capacity_alert{issue=\"capacity_exhaustion\",mountpoint=\"/ckpt\",urgency=\"critical\"} 1"
+        ));
+    }
+
+    #[test]
+    fn test_rdma_metrics() {
+        use crate::rdma::{
+            LinkLayer, LinkRate, PhysState, PortRates, PortState, RdmaAlert, RdmaAlertKind,
+            RdmaDevice, RdmaPort, RdmaSnapshot,
+        };
+
+        let port = RdmaPort {
+            port: 1,
+            state: PortState::Active,
+            phys_state: PhysState::LinkUp,
+            link_layer: LinkLayer::InfiniBand,
+            rate: LinkRate::parse("400 Gb/sec (4X NDR)"),
+            lid: Some(7),
+            sm_lid: Some(1),
+            gid: None,
+            netdev: None,
+            counters: BTreeMap::from([
+                ("port_xmit_data".to_string(), 1000),
+                ("symbol_error".to_string(), 3),
+            ]),
+            hw_counters: BTreeMap::from([("np_cnp_sent".to_string(), 42)]),
+            rates: Some(PortRates {
+                tx_bytes_per_sec: 5e9,
+                utilization_pct: Some(10.0),
+                ..Default::default()
+            }),
+        };
+        let snapshot = RdmaSnapshot {
+            timestamp: 0,
+            interval_secs: 5.0,
+            devices: vec![RdmaDevice {
+                name: "mlx5_0".into(),
+                node_type: Some("CA".into()),
+                hca_type: None,
+                board_id: None,
+                fw_ver: None,
+                node_guid: None,
+                pci: None,
+                numa_node: Some(1),
+                local_cpus: Vec::new(),
+                ports: vec![port],
+            }],
+            alerts: vec![RdmaAlert {
+                device: "mlx5_0".into(),
+                port: 1,
+                kind: RdmaAlertKind::LinkErrors,
+                message: String::new(),
+                deltas: BTreeMap::new(),
+            }],
+        };
+
+        let mut exporter = PrometheusExporter::new("simon");
+        exporter.collect_rdma_metrics(&snapshot);
+        let output = exporter.export();
+        assert!(output.contains("simon_rdma_device_info{device=\"mlx5_0\",numa_node=\"1\"} 1"));
+        assert!(output.contains(
+            "simon_rdma_port_state{device=\"mlx5_0\",link_layer=\"InfiniBand\",port=\"1\"} 4"
+        ));
+        assert!(output.contains("simon_rdma_port_rate_gbps{device=\"mlx5_0\",port=\"1\"} 400"));
+        assert!(output.contains(
+            "simon_rdma_port_data_bytes_total{device=\"mlx5_0\",direction=\"tx\",port=\"1\"} 4000"
+        ));
+        assert!(!output.contains("data_bytes_total{device=\"mlx5_0\",direction=\"rx\""));
+        assert!(output.contains(
+            "simon_rdma_port_counter_total{counter=\"np_cnp_sent\",device=\"mlx5_0\",port=\"1\",source=\"hw\"} 42"
+        ));
+        assert!(!output.contains("counter=\"port_xmit_data\""));
+        assert!(output.contains(
+            "simon_rdma_port_alert{device=\"mlx5_0\",kind=\"link_errors\",port=\"1\"} 1"
+        ));
+    }
+
+    #[test]
+    fn test_energy_metrics() {
+        use crate::energy_accounting::{
+            EnergyAccountant, EnergyAccountingConfig, EnergySample, ProcessSample,
+        };
+
+        let mut acct = EnergyAccountant::new(EnergyAccountingConfig::default());
+        let mut p = ProcessSample {
+            pid: 42,
+            name: "train".into(),
+            unit: Some("train.service".into()),
+            ..Default::default()
+        };
+        p.gpu_usage.insert(0, 90.0);
+        acct.record(&EnergySample {
+            elapsed_secs: 1.0,
+            cpu_joules: Some(10.0),
+            gpu_joules: std::collections::HashMap::from([(0, 250.0)]),
+            processes: vec![p],
+        });
+
+        let mut exporter = PrometheusExporter::new("simon");
+        exporter.collect_energy_metrics(&acct.report());
+        let output = exporter.export();
+        assert!(output.contains("# TYPE simon_energy_node_joules_total counter"));
+        assert!(output.contains("simon_energy_node_joules_total{domain=\"gpu\"} 250"));
+        assert!(output.contains(
+            "simon_energy_process_joules_total{domain=\"gpu\",name=\"train\",pid=\"42\",unit=\"train.service\"} 250"
+        ));
+        assert!(output.contains("simon_energy_unit_joules_total{domain=\"gpu\",unit=\"train.service\"} 250"));
+        assert!(output.contains("simon_energy_cost_total{currency=\"USD\"}"));
+    }
+
+    #[test]
+    fn test_exporter_collect_and_export() {
+        let mut exporter = PrometheusExporter::new("test");
+        exporter.add(MetricFamily::gauge("test_metric", "Help", 1.0));
+        let output = exporter.export();
+        assert!(output.contains("# HELP test_metric"));
+        assert!(output.contains("test_metric 1"));
+    }
+}
@@ -0,0 +1,946 @@
+//! Pressure Stall Information (PSI)
+//!
+//! PSI reports the share of wall time in which tasks were stalled waiting on
+//! CPU, memory, IO or IRQ handling. Unlike utilization, it measures lost
+//! productivity directly, so it separates "busy" from "saturated" and exposes
+//! noisy neighbours that starve other workloads.
+//!
+//! Each resource reports a `some` line (at least one task stalled) and, except
+//! for system-wide CPU on older kernels, a `full` line (all non-idle tasks
+//! stalled at once), with 10s/60s/300s running averages and a cumulative
+//! `total` in microseconds:
+//!
+//! ```text
+//! some avg10=0.12 avg60=0.08 avg300=0.02 total=123456
+//! full avg10=0.00 avg60=0.00 avg300=0.00 total=4567
+//! ```
+//!
+//! Sources are `/proc/pressure/{cpu,memory,io,irq}` and, per cgroup v2 group,
+//! `/sys/fs/cgroup/<group>/{cpu,memory,io,irq}.pressure`. [`PsiMonitor`] reads
+//! both and derives stall percentages over its own sampling interval from
+//! `total` deltas. [`PsiTriggerSet`] registers kernel triggers ("notify me when
+//! memory `some` stall exceeds 150ms within any 1s window") and waits for them
+//! with `poll(2)`; [`spawn`] turns fired triggers into
+//! [`SystemEvent`](crate::observability::SystemEvent)s.
+//!
+//! ## Platform Support
+//!
+//! - **Linux**: kernel 4.20+ with `CONFIG_PSI` (IRQ pressure since 6.1)
+//! - **Other**: unsupported; readers return empty results
+
+use crate::error::{Result, SimonError};
+use crate::observability::{event_types, EventCategory, EventManager, SystemEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// A resource tracked by PSI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PsiResource {
+    Cpu,
+    Memory,
+    Io,
+    Irq,
+}
+
+impl PsiResource {
+    pub const ALL: [PsiResource; 4] = [Self::Cpu, Self::Memory, Self::Io, Self::Irq];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::Memory => "memory",
+            Self::Io => "io",
+            Self::Irq => "irq",
+        }
+    }
+
+    /// Path of the system-wide pressure file under `root`
+    pub fn system_path(&self, root: &Path) -> PathBuf {
+        root.join("proc/pressure").join(self.name())
+    }
+
+    /// Path of a cgroup's pressure file
+    pub fn cgroup_path(&self, cgroup_dir: &Path) -> PathBuf {
+        cgroup_dir.join(format!("{}.pressure", self.name()))
+    }
+
+    /// Event category for alerts about this resource
+    fn category(&self) -> EventCategory {
+        match self {
+            Self::Cpu | Self::Irq => EventCategory::Cpu,
+            Self::Memory => EventCategory::Memory,
+            Self::Io => EventCategory::Disk,
+        }
+    }
+}
+
+impl std::fmt::Display for PsiResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Which PSI line a value or trigger refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StallKind {
+    /// At least one task stalled
+    Some,
+    /// All non-idle tasks stalled simultaneously
+    Full,
+}
+
+impl std::fmt::Display for StallKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Some => "some",
+            Self::Full => "full",
+        })
+    }
+}
+
+/// One `some` or `full` line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureLine {
+    /// Percent of time stalled over the last 10 seconds
+    pub avg10: f64,
+    /// Percent of time stalled over the last 60 seconds
+    pub avg60: f64,
+    /// Percent of time stalled over the last 300 seconds
+    pub avg300: f64,
+    /// Cumulative stall time in microseconds
+    pub total_us: u64,
+}
+
+impl PressureLine {
+    fn parse(fields: &str) -> Result<Self> {
+        let mut line = Self::default();
+        for part in fields.split_whitespace() {
+            let Some((key, value)) = part.split_once('=') else {
+                continue;
+            };
+            let bad = || SimonError::Parse(format!("invalid PSI field '{}'", part));
+            match key {
+                "avg10" => line.avg10 = value.parse().map_err(|_| bad())?,
+                "avg60" => line.avg60 = value.parse().map_err(|_| bad())?,
+                "avg300" => line.avg300 = value.parse().map_err(|_| bad())?,
+                "total" => line.total_us = value.parse().map_err(|_| bad())?,
+                _ => {}
+            }
+        }
+        Ok(line)
+    }
+}
+
+/// Pressure of one resource in one scope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePressure {
+    pub resource: PsiResource,
+    pub some: PressureLine,
+    /// Absent for system-wide CPU before kernel 5.13
+    pub full: Option<PressureLine>,
+    /// Percent of the last sampling interval with `some` stall, from `total` deltas
+    pub some_interval_pct: Option<f64>,
+    /// Percent of the last sampling interval with `full` stall
+    pub full_interval_pct: Option<f64>,
+}
+
+impl ResourcePressure {
+    /// Parse the contents of a pressure file
+    pub fn parse(resource: PsiResource, text: &str) -> Result<Self> {
+        let mut some = None;
+        let mut full = None;
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("some ") {
+                some = Some(PressureLine::parse(rest)?);
+            } else if let Some(rest) = line.strip_prefix("full ") {
+                full = Some(PressureLine::parse(rest)?);
+            }
+        }
+        // IRQ pressure only has a full line
+        let some = match (some, resource) {
+            (Some(s), _) => s,
+            (None, PsiResource::Irq) if full.is_some() => PressureLine::default(),
+            (None, _) => {
+                return Err(SimonError::Parse(format!(
+                    "{} pressure has no 'some' line",
+                    resource
+                )))
+            }
+        };
+        Ok(Self {
+            resource,
+            some,
+            full,
+            some_interval_pct: None,
+            full_interval_pct: None,
+        })
+    }
+
+    pub fn line(&self, kind: StallKind) -> Option<&PressureLine> {
+        match kind {
+            StallKind::Some => Some(&self.some),
+            StallKind::Full => self.full.as_ref(),
+        }
+    }
+
+    /// The line that best indicates lost productivity: `full` for memory, IO and
+    /// IRQ, `some` for CPU (where `full` is only meaningful inside cgroups)
+    pub fn headline(&self) -> (StallKind, &PressureLine) {
+        match (self.resource, &self.full) {
+            (PsiResource::Cpu, _) | (_, None) => (StallKind::Some, &self.some),
+            (_, Some(full)) => (StallKind::Full, full),
+        }
+    }
+}
+
+/// Where pressure was read from
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PressureScope {
+    System,
+    /// cgroup v2 path relative to the hierarchy root, e.g. "/system.slice/nginx.service"
+    Cgroup(String),
+}
+
+impl std::fmt::Display for PressureScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::System => f.write_str("system"),
+            Self::Cgroup(path) => f.write_str(path),
+        }
+    }
+}
+
+/// All resources of one scope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopePressure {
+    pub scope: PressureScope,
+    pub resources: Vec<ResourcePressure>,
+}
+
+impl ScopePressure {
+    pub fn get(&self, resource: PsiResource) -> Option<&ResourcePressure> {
+        self.resources.iter().find(|r| r.resource == resource)
+    }
+
+    /// Highest headline avg10 across resources
+    pub fn worst_avg10(&self) -> f64 {
+        self.resources
+            .iter()
+            .map(|r| r.headline().1.avg10)
+            .fold(0.0, f64::max)
+    }
+}
+
+/// One sample of system-wide and per-cgroup pressure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PsiSnapshot {
+    /// Unix time of the sample
+    pub timestamp: u64,
+    pub system: ScopePressure,
+    pub cgroups: Vec<ScopePressure>,
+}
+
+impl PsiSnapshot {
+    /// Cgroups with the highest headline avg10 for `resource`, non-zero only
+    pub fn top_cgroups(&self, resource: PsiResource, n: usize) -> Vec<(&ScopePressure, f64)> {
+        let mut ranked: Vec<(&ScopePressure, f64)> = self
+            .cgroups
+            .iter()
+            .filter_map(|c| Some((c, c.get(resource)?.headline().1.avg10)))
+            .filter(|(_, v)| *v > 0.0)
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.truncate(n);
+        ranked
+    }
+}
+
+/// Read every available resource from a directory of pressure files
+fn read_scope(scope: PressureScope, path_for: impl Fn(PsiResource) -> PathBuf) -> ScopePressure {
+    let resources = PsiResource::ALL
+        .iter()
+        .filter_map(|&r| {
+            let text = std::fs::read_to_string(path_for(r)).ok()?;
+            ResourcePressure::parse(r, &text).ok()
+        })
+        .collect();
+    ScopePressure { scope, resources }
+}
+
+/// System-wide pressure under `root` (normally "/")
+pub fn read_system(root: &Path) -> ScopePressure {
+    read_scope(PressureScope::System, |r| r.system_path(root))
+}
+
+/// Pressure of one cgroup, `path` relative to the v2 hierarchy under `root`
+pub fn read_cgroup(root: &Path, path: &str) -> ScopePressure {
+    let dir = cgroup_dir(root, path);
+    read_scope(PressureScope::Cgroup(path.to_string()), |r| {
+        r.cgroup_path(&dir)
+    })
+}
+
+fn cgroup_dir(root: &Path, path: &str) -> PathBuf {
+    root.join("sys/fs/cgroup")
+        .join(path.trim_start_matches('/'))
+}
+
+/// Whether the kernel exposes PSI
+pub fn is_supported(root: &Path) -> bool {
+    PsiResource::Cpu.system_path(root).exists()
+}
+
+/// cgroup v2 groups with pressure files, depth-first up to `max_depth` levels
+fn list_cgroups(root: &Path, max_depth: usize) -> Vec<String> {
+    fn walk(dir: &Path, rel: &str, depth: usize, max_depth: usize, out: &mut Vec<String>) {
+        if depth > max_depth {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut children: Vec<_> = entries
+            .flatten()
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .collect();
+        children.sort_by_key(|e| e.file_name());
+        for entry in children {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = entry.path();
+            let rel_path = format!("{}/{}", rel, name);
+            if PsiResource::Cpu.cgroup_path(&path).exists()
+                || PsiResource::Memory.cgroup_path(&path).exists()
+            {
+                out.push(rel_path.clone());
+            }
+            walk(&path, &rel_path, depth + 1, max_depth, out);
+        }
+    }
+    let mut out = Vec::new();
+    // The root group mirrors /proc/pressure, so only descendants are listed
+    walk(&root.join("sys/fs/cgroup"), "", 1, max_depth, &mut out);
+    out
+}
+
+/// A kernel PSI trigger: notify when `kind` stall of `resource` reaches
+/// `threshold_ms` within any `window_ms` window
+///
+/// The kernel accepts windows from 500ms to 10s. Unprivileged processes
+/// (kernel 6.5+) may only use windows that are multiples of 2s.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PsiTrigger {
+    pub resource: PsiResource,
+    pub kind: StallKind,
+    pub threshold_ms: u64,
+    pub window_ms: u64,
+    /// cgroup path to watch instead of the whole system
+    #[serde(default)]
+    pub cgroup: Option<String>,
+}
+
+impl PsiTrigger {
+    pub fn new(resource: PsiResource, kind: StallKind, threshold_ms: u64, window_ms: u64) -> Self {
+        Self {
+            resource,
+            kind,
+            threshold_ms,
+            window_ms,
+            cgroup: None,
+        }
+    }
+
+    /// Watch a cgroup instead of the whole system
+    pub fn for_cgroup(mut self, path: impl Into<String>) -> Self {
+        self.cgroup = Some(path.into());
+        self
+    }
+
+    /// Check the limits the kernel enforces on trigger registration
+    pub fn validate(&self) -> Result<()> {
+        if !(500..=10_000).contains(&self.window_ms) {
+            return Err(SimonError::InvalidValue(format!(
+                "PSI window {}ms outside 500..=10000ms",
+                self.window_ms
+            )));
+        }
+        if self.threshold_ms == 0 || self.threshold_ms > self.window_ms {
+            return Err(SimonError::InvalidValue(format!(
+                "PSI threshold {}ms must be within (0, {}ms]",
+                self.threshold_ms, self.window_ms
+            )));
+        }
+        Ok(())
+    }
+
+    /// Trigger string written to the pressure file, e.g. "some 150000 1000000"
+    pub fn spec(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.kind,
+            self.threshold_ms * 1000,
+            self.window_ms * 1000
+        )
+    }
+
+    fn path(&self, root: &Path) -> PathBuf {
+        match &self.cgroup {
+            Some(cg) => self.resource.cgroup_path(&cgroup_dir(root, cg)),
+            None => self.resource.system_path(root),
+        }
+    }
+
+    fn scope(&self) -> PressureScope {
+        match &self.cgroup {
+            Some(cg) => PressureScope::Cgroup(cg.clone()),
+            None => PressureScope::System,
+        }
+    }
+
+    /// Stall share of the window that fires the trigger, in percent
+    pub fn threshold_pct(&self) -> f64 {
+        self.threshold_ms as f64 / self.window_ms.max(1) as f64 * 100.0
+    }
+
+    /// Event describing this trigger firing, with the current reading if known
+    pub fn event(&self, current: Option<&ResourcePressure>) -> SystemEvent {
+        let scope = self.scope();
+        let message = format!(
+            "{} {} pressure on {} exceeded {}ms stall per {}ms window",
+            self.resource, self.kind, scope, self.threshold_ms, self.window_ms
+        );
+        let source = match &self.cgroup {
+            Some(cg) => format!("psi:{}:{}", self.resource, cg),
+            None => format!("psi:{}", self.resource),
+        };
+        let event_type = match self.resource {
+            PsiResource::Cpu | PsiResource::Irq => event_types::cpu::PRESSURE_STALL,
+            PsiResource::Memory => event_types::memory::PRESSURE_STALL,
+            PsiResource::Io => event_types::disk::PRESSURE_STALL,
+        };
+        let mut event = match self.kind {
+            StallKind::Some => {
+                SystemEvent::warning(self.resource.category(), event_type, &message, &source)
+            }
+            StallKind::Full => {
+                SystemEvent::critical(self.resource.category(), event_type, &message, &source)
+            }
+        }
+        .with_metadata("resource", self.resource)
+        .with_metadata("kind", self.kind)
+        .with_metadata("threshold_ms", self.threshold_ms)
+        .with_metadata("window_ms", self.window_ms)
+        .with_metadata("scope", scope.to_string());
+        if let Some(line) = current.and_then(|p| p.line(self.kind)) {
+            event = event
+                .with_metadata("avg10", line.avg10)
+                .with_metadata("avg60", line.avg60);
+        }
+        event
+    }
+}
+
+/// Defaults aimed at saturation rather than momentary blips
+fn default_triggers() -> Vec<PsiTrigger> {
+    vec![
+        PsiTrigger::new(PsiResource::Memory, StallKind::Some, 200, 2000),
+        PsiTrigger::new(PsiResource::Memory, StallKind::Full, 100, 2000),
+        PsiTrigger::new(PsiResource::Io, StallKind::Full, 400, 2000),
+        PsiTrigger::new(PsiResource::Cpu, StallKind::Some, 1000, 2000),
+    ]
+}
+
+/// Kernel triggers armed on pressure files
+///
+/// Triggers that cannot be registered (older kernels, missing write access to a
+/// cgroup, unprivileged window limits) are kept in [`PsiTriggerSet::failed`];
+/// [`spawn`] evaluates those in user space from sampled `total` deltas instead.
+pub struct PsiTriggerSet {
+    armed: Vec<(PsiTrigger, File)>,
+    failed: Vec<(PsiTrigger, String)>,
+}
+
+impl PsiTriggerSet {
+    /// Register `triggers` on pressure files under `root`
+    pub fn register(root: &Path, triggers: &[PsiTrigger]) -> Self {
+        let mut armed = Vec::new();
+        let mut failed = Vec::new();
+        for trigger in triggers {
+            match trigger.validate().and_then(|_| arm(root, trigger)) {
+                Ok(file) => armed.push((trigger.clone(), file)),
+                Err(e) => failed.push((trigger.clone(), e.to_string())),
+            }
+        }
+        Self { armed, failed }
+    }
+
+    pub fn armed(&self) -> impl Iterator<Item = &PsiTrigger> {
+        self.armed.iter().map(|(t, _)| t)
+    }
+
+    /// Triggers the kernel rejected, with reasons
+    pub fn failed(&self) -> &[(PsiTrigger, String)] {
+        &self.failed
+    }
+
+    /// Wait up to `timeout` for armed triggers to fire
+    ///
+    /// Triggers whose file reports an error (cgroup removed) are disarmed and
+    /// moved to [`PsiTriggerSet::failed`].
+    #[cfg(target_os = "linux")]
+    pub fn wait(&mut self, timeout: Duration) -> Vec<PsiTrigger> {
+        use std::os::unix::io::AsRawFd;
+        if self.armed.is_empty() {
+            std::thread::sleep(timeout);
+            return Vec::new();
+        }
+        let mut fds: Vec<libc::pollfd> = self
+            .armed
+            .iter()
+            .map(|(_, f)| libc::pollfd {
+                fd: f.as_raw_fd(),
+                events: libc::POLLPRI,
+                revents: 0,
+            })
+            .collect();
+        let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as libc::c_int;
+        // SAFETY: fds is a valid, exclusively borrowed array of initialized pollfds
+        let n = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout_ms) };
+        if n <= 0 {
+            return Vec::new();
+        }
+        let mut fired = Vec::new();
+        let mut dead = Vec::new();
+        for (i, pfd) in fds.iter().enumerate() {
+            if pfd.revents & libc::POLLERR != 0 {
+                dead.push(i);
+            } else if pfd.revents & libc::POLLPRI != 0 {
+                fired.push(self.armed[i].0.clone());
+            }
+        }
+        for i in dead.into_iter().rev() {
+            let (trigger, _) = self.armed.remove(i);
+            self.failed
+                .push((trigger, "pressure file went away".to_string()));
+        }
+        fired
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn wait(&mut self, timeout: Duration) -> Vec<PsiTrigger> {
+        std::thread::sleep(timeout);
+        Vec::new()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn arm(root: &Path, trigger: &PsiTrigger) -> Result<File> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let path = trigger.path(root);
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(&path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                SimonError::PermissionDenied(format!("{}: {}", path.display(), e))
+            }
+            _ => SimonError::Io(e),
+        })?;
+    file.write_all(trigger.spec().as_bytes())?;
+    Ok(file)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn arm(_root: &Path, _trigger: &PsiTrigger) -> Result<File> {
+    Err(SimonError::UnsupportedPlatform(
+        "PSI triggers require Linux".into(),
+    ))
+}
+
+/// Monitor configuration (`[psi]` in the daemon config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PsiConfig {
+    pub enabled: bool,
+    /// Sampling interval in milliseconds
+    pub interval_ms: u64,
+    /// Also read cgroup v2 `*.pressure` files
+    pub cgroups: bool,
+    /// How many levels below the cgroup root to scan
+    pub cgroup_depth: usize,
+    /// Kernel triggers that raise events
+    pub triggers: Vec<PsiTrigger>,
+}
+
+impl Default for PsiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 2000,
+            cgroups: true,
+            cgroup_depth: 3,
+            triggers: default_triggers(),
+        }
+    }
+}
+
+/// Samples system-wide and per-cgroup pressure, deriving interval stall
+/// percentages from `total` deltas
+pub struct PsiMonitor {
+    root: PathBuf,
+    config: PsiConfig,
+    last: HashMap<(PressureScope, PsiResource), (u64, Option<u64>)>,
+    last_at: Option<Instant>,
+}
+
+impl PsiMonitor {
+    /// Monitor the live system
+    pub fn new(config: PsiConfig) -> Result<Self> {
+        Self::with_root("/", config)
+    }
+
+    /// Monitor a filesystem tree rooted at `root` (for tests)
+    pub fn with_root(root: impl Into<PathBuf>, config: PsiConfig) -> Result<Self> {
+        let root = root.into();
+        if !is_supported(&root) {
+            return Err(SimonError::FeatureNotAvailable(
+                "PSI not available (needs Linux 4.20+ with CONFIG_PSI and psi=1)".into(),
+            ));
+        }
+        Ok(Self {
+            root,
+            config,
+            last: HashMap::new(),
+            last_at: None,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Read all scopes and fill in interval percentages against the previous sample
+    pub fn sample(&mut self) -> PsiSnapshot {
+        let now = Instant::now();
+        let elapsed_us = self
+            .last_at
+            .map(|t| now.duration_since(t).as_micros() as f64);
+        self.last_at = Some(now);
+
+        let mut system = read_system(&self.root);
+        self.apply_deltas(&mut system, elapsed_us);
+        let mut cgroups = Vec::new();
+        if self.config.cgroups {
+            for path in list_cgroups(&self.root, self.config.cgroup_depth) {
+                let mut scope = read_cgroup(&self.root, &path);
+                self.apply_deltas(&mut scope, elapsed_us);
+                cgroups.push(scope);
+            }
+        }
+        // Forget cgroups that disappeared
+        let live: std::collections::HashSet<&PressureScope> =
+            cgroups.iter().map(|c| &c.scope).collect();
+        self.last
+            .retain(|(scope, _), _| *scope == PressureScope::System || live.contains(scope));
+
+        PsiSnapshot {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            system,
+            cgroups,
+        }
+    }
+
+    fn apply_deltas(&mut self, scope: &mut ScopePressure, elapsed_us: Option<f64>) {
+        for r in &mut scope.resources {
+            let key = (scope.scope.clone(), r.resource);
+            let totals = (r.some.total_us, r.full.map(|f| f.total_us));
+            if let (Some((prev_some, prev_full)), Some(elapsed)) =
+                (self.last.get(&key), elapsed_us.filter(|e| *e > 0.0))
+            {
+                let pct = |now: u64, prev: u64| {
+                    (now.saturating_sub(prev) as f64 / elapsed * 100.0).clamp(0.0, 100.0)
+                };
+                r.some_interval_pct = Some(pct(totals.0, *prev_some));
+                r.full_interval_pct = match (totals.1, prev_full) {
+                    (Some(now), Some(prev)) => Some(pct(now, *prev)),
+                    _ => None,
+                };
+            }
+            self.last.insert(key, totals);
+        }
+    }
+}
+
+/// Most recent pressure reading, `None` until the first poll completes
+pub type SharedPsiSnapshot = Arc<RwLock<Option<PsiSnapshot>>>;
+
+/// Start sampling and trigger handling on a background thread
+///
+/// Fired kernel triggers are emitted to `events`. Triggers the kernel refused
+/// are evaluated against each sample's interval stall percentage instead, at
+/// most once per window.
+pub fn spawn(config: PsiConfig, events: Arc<EventManager>) -> Result<PsiHandle> {
+    let interval = Duration::from_millis(config.interval_ms.max(500));
+    let mut monitor = PsiMonitor::new(config.clone())?;
+    let mut triggers = PsiTriggerSet::register(monitor.root(), &config.triggers);
+    for (trigger, reason) in triggers.failed() {
+        log::warn!(
+            "PSI trigger '{}' on {} evaluated in user space: {}",
+            trigger.spec(),
+            trigger.scope(),
+            reason
+        );
+    }
+    let snapshot: SharedPsiSnapshot = Arc::new(RwLock::new(Some(monitor.sample())));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let shared = Arc::clone(&snapshot);
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-psi".into())
+        .spawn(move || {
+            let mut next_sample = Instant::now() + interval;
+            let mut last_fired: HashMap<PsiTrigger, Instant> = HashMap::new();
+            while !flag.load(Ordering::SeqCst) {
+                // Short waits keep shutdown responsive
+                let wait = next_sample
+                    .saturating_duration_since(Instant::now())
+                    .min(Duration::from_millis(500));
+                for trigger in triggers.wait(wait) {
+                    let current = read_trigger_scope(monitor.root(), &trigger);
+                    events.emit(trigger.event(current.as_ref()));
+                }
+                if Instant::now() < next_sample {
+                    continue;
+                }
+                next_sample = Instant::now() + interval;
+                let sample = monitor.sample();
+                for (trigger, _) in triggers.failed() {
+                    let window = Duration::from_millis(trigger.window_ms);
+                    if last_fired
+                        .get(trigger)
+                        .is_some_and(|t| t.elapsed() < window)
+                    {
+                        continue;
+                    }
+                    if let Some(current) = emulated_fire(&sample, trigger) {
+                        events.emit(trigger.event(Some(current)));
+                        last_fired.insert(trigger.clone(), Instant::now());
+                    }
+                }
+                if let Ok(mut slot) = shared.write() {
+                    *slot = Some(sample);
+                }
+            }
+        })
+        .map_err(|e| SimonError::Other(format!("Failed to spawn PSI monitor thread: {}", e)))?;
+
+    Ok(PsiHandle {
+        snapshot,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+fn read_trigger_scope(root: &Path, trigger: &PsiTrigger) -> Option<ResourcePressure> {
+    let path = trigger.path(root);
+    let text = std::fs::read_to_string(path).ok()?;
+    ResourcePressure::parse(trigger.resource, &text).ok()
+}
+
+/// The reading that crosses a trigger's threshold in a sample, if any
+fn emulated_fire<'a>(
+    snapshot: &'a PsiSnapshot,
+    trigger: &PsiTrigger,
+) -> Option<&'a ResourcePressure> {
+    let scope = match &trigger.cgroup {
+        None => &snapshot.system,
+        Some(cg) => snapshot
+            .cgroups
+            .iter()
+            .find(|c| matches!(&c.scope, PressureScope::Cgroup(p) if p == cg))?,
+    };
+    let current = scope.get(trigger.resource)?;
+    let pct = match trigger.kind {
+        StallKind::Some => current.some_interval_pct,
+        StallKind::Full => current.full_interval_pct,
+    }?;
+    (pct >= trigger.threshold_pct()).then_some(current)
+}
+
+/// Handle to a running PSI monitor thread
+pub struct PsiHandle {
+    snapshot: SharedPsiSnapshot,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PsiHandle {
+    /// Shared snapshot slot for readers
+    pub fn snapshot(&self) -> SharedPsiSnapshot {
+        Arc::clone(&self.snapshot)
+    }
+
+    /// Stop monitoring and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for PsiHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    fn psi(some_avg10: f64, some_total: u64, full_total: u64) -> String {
+        format!(
+            "some avg10={:.2} avg60=1.00 avg300=0.50 total={}\nfull avg10=0.00 avg60=0.00 avg300=0.00 total={}\n",
+            some_avg10, some_total, full_total
+        )
+    }
+
+    #[test]
+    fn test_parse_pressure() {
+        let p = ResourcePressure::parse(PsiResource::Memory, &psi(12.5, 1000, 200)).unwrap();
+        assert_eq!(p.some.avg10, 12.5);
+        assert_eq!(p.some.avg60, 1.0);
+        assert_eq!(p.some.total_us, 1000);
+        assert_eq!(p.full.unwrap().total_us, 200);
+        assert_eq!(p.headline().0, StallKind::Full);
+
+        // Pre-5.13 system CPU: no full line
+        let cpu = ResourcePressure::parse(
+            PsiResource::Cpu,
+            "some avg10=3.00 avg60=2.00 avg300=1.00 total=42\n",
+        )
+        .unwrap();
+        assert!(cpu.full.is_none());
+        assert_eq!(cpu.headline().1.avg10, 3.0);
+
+        let irq = ResourcePressure::parse(
+            PsiResource::Irq,
+            "full avg10=0.50 avg60=0.10 avg300=0.00 total=99\n",
+        )
+        .unwrap();
+        assert_eq!(irq.full.unwrap().total_us, 99);
+
+        assert!(ResourcePressure::parse(PsiResource::Io, "garbage\n").is_err());
+        assert!(ResourcePressure::parse(PsiResource::Io, "some avg10=x total=1\n").is_err());
+    }
+
+    #[test]
+    fn test_trigger_spec_and_validation() {
+        let t = PsiTrigger::new(PsiResource::Memory, StallKind::Some, 150, 1000);
+        assert_eq!(t.spec(), "some 150000 1000000");
+        assert!(t.validate().is_ok());
+        assert!((t.threshold_pct() - 15.0).abs() < 1e-9);
+        assert!(PsiTrigger::new(PsiResource::Io, StallKind::Full, 10, 100)
+            .validate()
+            .is_err());
+        assert!(
+            PsiTrigger::new(PsiResource::Io, StallKind::Full, 3000, 2000)
+                .validate()
+                .is_err()
+        );
+
+        let event = t.clone().for_cgroup("/system.slice/db.service").event(None);
+        assert_eq!(event.category, EventCategory::Memory);
+        assert_eq!(event.event_type, event_types::memory::PRESSURE_STALL);
+        assert_eq!(event.source, "psi:memory:/system.slice/db.service");
+        let full = PsiTrigger::new(PsiResource::Io, StallKind::Full, 400, 2000).event(None);
+        assert_eq!(full.severity, crate::observability::EventSeverity::Critical);
+    }
+
+    #[test]
+    fn test_monitor_deltas_and_cgroups() {
+        let root = FakeRoot::new("psi-monitor");
+        for r in ["cpu", "memory", "io"] {
+            root.write(&format!("proc/pressure/{}", r), &psi(0.0, 0, 0));
+        }
+        root.write("sys/fs/cgroup/cpu.pressure", &psi(0.0, 0, 0));
+        root.write(
+            "sys/fs/cgroup/system.slice/memory.pressure",
+            &psi(0.0, 0, 0),
+        );
+        root.write(
+            "sys/fs/cgroup/system.slice/db.service/memory.pressure",
+            &psi(0.0, 0, 0),
+        );
+
+        let config = PsiConfig {
+            cgroup_depth: 2,
+            ..Default::default()
+        };
+        let mut monitor = PsiMonitor::with_root(root.path(), config).unwrap();
+        let first = monitor.sample();
+        assert_eq!(first.system.resources.len(), 3);
+        assert!(first
+            .system
+            .get(PsiResource::Memory)
+            .unwrap()
+            .some_interval_pct
+            .is_none());
+        let paths: Vec<String> = first.cgroups.iter().map(|c| c.scope.to_string()).collect();
+        assert_eq!(paths, vec!["/system.slice", "/system.slice/db.service"]);
+
+        // A large stall total guarantees a saturated interval whatever the elapsed time
+        std::thread::sleep(Duration::from_millis(5));
+        root.write("proc/pressure/memory", &psi(40.0, u64::MAX / 2, 0));
+        root.write(
+            "sys/fs/cgroup/system.slice/db.service/memory.pressure",
+            &psi(40.0, u64::MAX / 2, 0),
+        );
+        let second = monitor.sample();
+        let mem = second.system.get(PsiResource::Memory).unwrap();
+        assert_eq!(mem.some_interval_pct, Some(100.0));
+        assert_eq!(mem.full_interval_pct, Some(0.0));
+        assert_eq!(
+            second
+                .system
+                .get(PsiResource::Io)
+                .unwrap()
+                .some_interval_pct,
+            Some(0.0)
+        );
+
+        let top = second.top_cgroups(PsiResource::Memory, 5);
+        assert_eq!(top.len(), 0, "headline is full avg10, which is zero");
+        assert_eq!(
+            second.cgroups[1]
+                .get(PsiResource::Memory)
+                .unwrap()
+                .some
+                .avg10,
+            40.0
+        );
+
+        let trigger = PsiTrigger::new(PsiResource::Memory, StallKind::Some, 150, 1000)
+            .for_cgroup("/system.slice/db.service");
+        assert!(emulated_fire(&second, &trigger).is_some());
+        let full = PsiTrigger::new(PsiResource::Memory, StallKind::Full, 150, 1000);
+        assert!(emulated_fire(&second, &full).is_none());
+    }
+}
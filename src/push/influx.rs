@@ -0,0 +1,239 @@
+//! InfluxDB line protocol sink
+//!
+//! Each sample becomes one line, `measurement[,tag=value...] value=<float>
+//! <timestamp ns>`, with the series name as the measurement and its labels as
+//! tags. Lines are written over HTTP to the v2 (`/api/v2/write`) or v1
+//! (`/write`) API, optionally gzip-compressed, or as UDP datagrams for
+//! Telegraf and InfluxDB 1.x UDP listeners.
+
+use super::{HttpTarget, PushSample, Sink, UdpTarget};
+use crate::error::{Result, SimonError};
+use crate::otlp::transport::ExportError;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::time::Duration;
+
+/// InfluxDB endpoint settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InfluxConfig {
+    /// `http(s)://host:8086/api/v2/write?org=<org>&bucket=<bucket>` (v2),
+    /// `http(s)://host:8086/write?db=<db>` (v1) or `udp://host:8089`
+    pub url: String,
+    /// API token, sent as `Authorization: Token <token>`
+    pub token: Option<String>,
+    /// Extra HTTP request headers
+    pub headers: BTreeMap<String, String>,
+    /// gzip HTTP request bodies
+    pub gzip: bool,
+    /// Lines per HTTP request; larger scrapes are split
+    pub max_lines_per_send: usize,
+    /// Largest UDP datagram payload
+    pub max_packet_bytes: usize,
+}
+
+impl Default for InfluxConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:8086/api/v2/write?org=simon&bucket=simon".into(),
+            token: None,
+            headers: BTreeMap::new(),
+            gzip: true,
+            max_lines_per_send: 5000,
+            max_packet_bytes: 1400,
+        }
+    }
+}
+
+/// Append one line for `sample` to `out`, with `common` tags the sample does
+/// not set itself; non-finite values have no line protocol form and are skipped
+pub fn write_line(
+    out: &mut String,
+    sample: &PushSample,
+    common: &BTreeMap<String, String>,
+    timestamp_ns: i64,
+) {
+    if !sample.value.is_finite() {
+        return;
+    }
+    escape(out, &sample.name, &[',', ' ']);
+    let mut tags: BTreeMap<&str, &str> = common
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    tags.extend(sample.labels.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+    // Sorted tags are what InfluxDB recommends for write performance
+    for (key, value) in tags {
+        if key.is_empty() || value.is_empty() {
+            continue;
+        }
+        out.push(',');
+        escape(out, key, &[',', '=', ' ']);
+        out.push('=');
+        escape(out, value, &[',', '=', ' ']);
+    }
+    let _ = writeln!(out, " value={} {}", sample.value, timestamp_ns);
+}
+
+fn escape(out: &mut String, s: &str, special: &[char]) {
+    for c in s.chars() {
+        // Lines cannot span newlines, escaped or not
+        let c = if c == '\n' || c == '\r' { ' ' } else { c };
+        if special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+/// `host:port` of a `udp://` URL, with the default port filled in
+fn udp_address(url: &str) -> Result<String> {
+    let rest = url.strip_prefix("udp://").unwrap_or(url);
+    let address = rest.split('/').next().unwrap_or_default();
+    if address.is_empty() {
+        return Err(SimonError::Configuration(format!(
+            "InfluxDB URL has no host: {}",
+            url
+        )));
+    }
+    // Bracketed IPv6 literals carry colons of their own
+    let has_port = address.rsplit_once(':').is_some_and(|(host, port)| {
+        !port.is_empty() && (!host.contains(':') || host.ends_with(']'))
+    });
+    Ok(if has_port {
+        address.to_string()
+    } else {
+        format!("{}:8089", address)
+    })
+}
+
+enum Target {
+    Http { target: HttpTarget, gzip: bool },
+    Udp(UdpTarget),
+}
+
+pub(super) struct InfluxSink {
+    target: Target,
+    common: BTreeMap<String, String>,
+    max_lines: usize,
+}
+
+impl InfluxSink {
+    pub(super) fn new(
+        config: &InfluxConfig,
+        common: BTreeMap<String, String>,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let target = if config.url.starts_with("udp://") {
+            let address = udp_address(&config.url)?;
+            Target::Udp(UdpTarget::new(address, config.max_packet_bytes))
+        } else {
+            let mut headers: Vec<(String, String)> = config
+                .headers
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            if let Some(token) = &config.token {
+                headers.push(("authorization".into(), format!("Token {}", token)));
+            }
+            Target::Http {
+                target: HttpTarget::new(&config.url, headers, timeout)?,
+                gzip: config.gzip,
+            }
+        };
+        if config.max_lines_per_send == 0 {
+            return Err(SimonError::Configuration(
+                "influxdb max_lines_per_send must be at least 1".into(),
+            ));
+        }
+        Ok(Self {
+            target,
+            common,
+            max_lines: config.max_lines_per_send,
+        })
+    }
+}
+
+impl Sink for InfluxSink {
+    fn encode(&mut self, samples: &[PushSample], timestamp_ms: i64) -> Vec<Vec<u8>> {
+        let timestamp_ns = timestamp_ms.saturating_mul(1_000_000);
+        samples
+            .chunks(self.max_lines)
+            .map(|chunk| {
+                let mut out = String::new();
+                for sample in chunk {
+                    write_line(&mut out, sample, &self.common, timestamp_ns);
+                }
+                out.into_bytes()
+            })
+            .filter(|record| !record.is_empty())
+            .collect()
+    }
+
+    fn send(&mut self, record: &[u8]) -> std::result::Result<(), ExportError> {
+        let (target, gzip) = match &mut self.target {
+            Target::Udp(udp) => return udp.send_lines(record),
+            Target::Http { target, gzip } => (target, *gzip),
+        };
+        if !gzip {
+            return target.post(
+                "InfluxDB",
+                &[("content-type", "text/plain; charset=utf-8")],
+                record.to_vec(),
+            );
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        let body = encoder
+            .write_all(record)
+            .and_then(|_| encoder.finish())
+            .map_err(|e| ExportError::fatal(format!("gzip: {}", e)))?;
+        target.post(
+            "InfluxDB",
+            &[
+                ("content-type", "text/plain; charset=utf-8"),
+                ("content-encoding", "gzip"),
+            ],
+            body,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prometheus::MetricType;
+
+    #[test]
+    fn test_line_protocol_escaping() {
+        let sample = PushSample {
+            name: "simon disk,io".into(),
+            labels: BTreeMap::from([
+                ("mount".to_string(), "/mnt/my data".to_string()),
+                ("expr".to_string(), "a=b,c".to_string()),
+                ("empty".to_string(), String::new()),
+            ]),
+            value: 12.5,
+            metric_type: MetricType::Gauge,
+        };
+        let common = BTreeMap::from([("host".to_string(), "jetson-7".to_string())]);
+        let mut out = String::new();
+        write_line(&mut out, &sample, &common, 1_700_000_000_000_000_000);
+        assert_eq!(
+            out,
+            "simon\\ disk\\,io,expr=a\\=b\\,c,host=jetson-7,mount=/mnt/my\\ data \
+             value=12.5 1700000000000000000\n"
+        );
+
+        out.clear();
+        let nan = PushSample {
+            value: f64::NAN,
+            ..sample
+        };
+        write_line(&mut out, &nan, &common, 0);
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,767 @@
+//! Push exporters
+//!
+//! For hosts that cannot be scraped, such as edge devices behind NAT, the
+//! [`CollectorRegistry`] data served on `/metrics` is pushed out on an
+//! interval instead, to any combination of:
+//!
+//! - Prometheus remote_write (snappy-compressed protobuf)
+//! - InfluxDB line protocol over HTTP (v1 or v2 API) or UDP
+//! - StatsD / DogStatsD over UDP
+//!
+//! Each scrape is encoded per sink and appended to that sink's [`Wal`] before
+//! anything is sent; the log is then replayed oldest-first until the receiver
+//! stops accepting. Data gathered while the uplink is down, or across a
+//! restart, goes out once it comes back. A batch the receiver rejects outright
+//! (a 4xx other than 429) is dropped rather than retried forever.
+//!
+//! While [`crate::consent::is_offline_mode`] is set, scrapes are still logged
+//! but nothing is sent.
+
+pub mod influx;
+pub mod remote_write;
+pub mod statsd;
+pub mod wal;
+
+pub use influx::InfluxConfig;
+pub use remote_write::{RemoteWriteConfig, TimeSeries};
+pub use statsd::StatsdConfig;
+pub use wal::Wal;
+
+use crate::consent::is_offline_mode;
+use crate::error::{Result, SimonError};
+use crate::observability::SystemIdentity;
+use crate::otlp::transport::ExportError;
+use crate::prometheus::{CollectorRegistry, MetricFamily, MetricType, PrometheusExporter};
+use influx::InfluxSink;
+use remote_write::RemoteWriteSink;
+use serde::{Deserialize, Serialize};
+use statsd::StatsdSink;
+use std::collections::BTreeMap;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Push exporter configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushConfig {
+    /// Start the push exporter with the daemon
+    pub enabled: bool,
+    /// Scrape and push period
+    pub interval_ms: u64,
+    /// Connect and I/O timeout per request
+    pub timeout_ms: u64,
+    /// Directory for the per-sink write-ahead logs (default: `push-wal` in the
+    /// config directory)
+    pub wal_dir: Option<PathBuf>,
+    /// Disk budget per sink; the oldest unsent data is discarded beyond it
+    pub wal_max_bytes: u64,
+    /// Batches replayed per sink per interval, so a long backlog drains in steps
+    pub max_replay_batches: usize,
+    /// Labels added to every series; `instance` defaults to the hostname
+    pub labels: BTreeMap<String, String>,
+    /// Prometheus remote_write sink; unset disables it
+    pub remote_write: Option<RemoteWriteConfig>,
+    /// InfluxDB line protocol sink (HTTP or UDP); unset disables it
+    pub influxdb: Option<InfluxConfig>,
+    /// StatsD / DogStatsD sink; unset disables it
+    pub statsd: Option<StatsdConfig>,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 15_000,
+            timeout_ms: 10_000,
+            wal_dir: None,
+            wal_max_bytes: 64 << 20,
+            max_replay_batches: 100,
+            labels: BTreeMap::new(),
+            remote_write: None,
+            influxdb: None,
+            statsd: None,
+        }
+    }
+}
+
+impl PushConfig {
+    /// Configured WAL directory, or `push-wal` in the config directory
+    pub fn wal_dir(&self) -> Result<PathBuf> {
+        match &self.wal_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(crate::config::Config::default_path()?.join("push-wal")),
+        }
+    }
+}
+
+/// One sample of a scrape, flattened out of its [`MetricFamily`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushSample {
+    /// Full series name, including the family's sample suffix (`_bucket`, `_sum`)
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+    pub metric_type: MetricType,
+}
+
+/// Flatten exporter families into individual samples
+pub fn samples_from_families(families: &[MetricFamily]) -> Vec<PushSample> {
+    families
+        .iter()
+        .flat_map(|family| {
+            family.samples.iter().map(move |sample| PushSample {
+                name: if sample.suffix.is_empty() {
+                    family.name.clone()
+                } else {
+                    format!("{}_{}", family.name, sample.suffix)
+                },
+                labels: sample.labels.clone(),
+                value: sample.value,
+                metric_type: family.metric_type,
+            })
+        })
+        .collect()
+}
+
+/// Per-sink delivery counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SinkStats {
+    pub batches_sent: u64,
+    pub bytes_sent: u64,
+    /// Batches the receiver rejected permanently, then dropped
+    pub batches_rejected: u64,
+    /// Unsent bytes waiting in the WAL
+    pub pending_bytes: u64,
+    /// Unsent bytes discarded to the WAL size limit or to corruption
+    pub dropped_bytes: u64,
+    pub last_error: Option<String>,
+    /// Unix time of the last accepted batch
+    pub last_success: Option<u64>,
+}
+
+/// Push exporter counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PushStats {
+    pub scrapes: u64,
+    pub samples_collected: u64,
+    /// Flushes skipped because offline mode is set
+    pub offline_skips: u64,
+    /// Keyed by sink name: `remote_write`, `influxdb`, `statsd`
+    pub sinks: BTreeMap<String, SinkStats>,
+}
+
+/// A push destination: encodes scrapes into WAL records and delivers them
+trait Sink: Send {
+    /// Encode one scrape into zero or more WAL records
+    fn encode(&mut self, samples: &[PushSample], timestamp_ms: i64) -> Vec<Vec<u8>>;
+
+    /// Deliver one record read back from the WAL
+    fn send(&mut self, record: &[u8]) -> std::result::Result<(), ExportError>;
+}
+
+/// Datagram destination shared by the UDP sinks
+struct UdpTarget {
+    address: String,
+    max_packet: usize,
+    socket: Option<UdpSocket>,
+}
+
+impl UdpTarget {
+    fn new(address: String, max_packet: usize) -> Self {
+        Self {
+            address,
+            max_packet: max_packet.max(64),
+            socket: None,
+        }
+    }
+
+    /// Send newline-separated lines, packing as many into each datagram as fit
+    fn send_lines(&mut self, record: &[u8]) -> std::result::Result<(), ExportError> {
+        let addr = self
+            .address
+            .to_socket_addrs()
+            .map_err(|e| ExportError::retryable(format!("resolve {}: {}", self.address, e)))?
+            .next()
+            .ok_or_else(|| ExportError::retryable(format!("no addresses for {}", self.address)))?;
+        let local = if addr.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = match self.socket.take() {
+            Some(s) if s.local_addr().is_ok_and(|l| l.is_ipv4() == addr.is_ipv4()) => s,
+            _ => UdpSocket::bind(local)
+                .map_err(|e| ExportError::retryable(format!("udp bind: {}", e)))?,
+        };
+        let socket = self.socket.insert(socket);
+        let send = |packet: &[u8]| {
+            socket
+                .send_to(packet, addr)
+                .map(|_| ())
+                .map_err(|e| ExportError::retryable(format!("udp send to {}: {}", addr, e)))
+        };
+
+        let mut packet = Vec::with_capacity(self.max_packet);
+        for line in record.split(|&b| b == b'\n').filter(|l| !l.is_empty()) {
+            if !packet.is_empty() && packet.len() + 1 + line.len() > self.max_packet {
+                send(&packet)?;
+                packet.clear();
+            }
+            if !packet.is_empty() {
+                packet.push(b'\n');
+            }
+            packet.extend_from_slice(line);
+        }
+        if !packet.is_empty() {
+            send(&packet)?;
+        }
+        Ok(())
+    }
+}
+
+/// HTTP(S) destination shared by the remote_write and InfluxDB sinks
+struct HttpTarget {
+    url: String,
+    #[cfg(feature = "remote-backends")]
+    headers: Vec<(String, String)>,
+    #[cfg(feature = "remote-backends")]
+    client: reqwest::blocking::Client,
+}
+
+impl HttpTarget {
+    fn new(url: &str, headers: Vec<(String, String)>, timeout: Duration) -> Result<Self> {
+        #[cfg(feature = "remote-backends")]
+        {
+            match reqwest::Url::parse(url) {
+                Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+                _ => {
+                    return Err(SimonError::Configuration(format!(
+                        "Push URL must be http:// or https://: {}",
+                        url
+                    )))
+                }
+            }
+            let client = reqwest::blocking::Client::builder()
+                .timeout(timeout)
+                .build()
+                .map_err(|e| SimonError::Network(format!("Failed to create HTTP client: {}", e)))?;
+            Ok(Self {
+                url: url.to_string(),
+                headers,
+                client,
+            })
+        }
+
+        #[cfg(not(feature = "remote-backends"))]
+        {
+            let _ = (url, headers, timeout);
+            Err(SimonError::NotImplemented(
+                "HTTP push sinks require 'remote-backends' feature".into(),
+            ))
+        }
+    }
+
+    /// `POST` a record; 429 and 5xx responses are retryable, other non-2xx
+    /// responses are fatal
+    fn post(
+        &self,
+        receiver: &str,
+        headers: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> std::result::Result<(), ExportError> {
+        #[cfg(feature = "remote-backends")]
+        {
+            let mut request = self.client.post(&self.url).body(body);
+            for (name, value) in headers
+                .iter()
+                .copied()
+                .chain(self.headers.iter().map(|(n, v)| (n.as_str(), v.as_str())))
+            {
+                request = request.header(name, value);
+            }
+            let response = request.send().map_err(|e| {
+                let message = format!("POST {}: {}", self.url, e);
+                if e.is_builder() {
+                    ExportError::fatal(message)
+                } else {
+                    ExportError::retryable(message)
+                }
+            })?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+            let detail = response.text().unwrap_or_default();
+            let message = format!(
+                "HTTP {} from {}: {}",
+                status.as_u16(),
+                receiver,
+                detail.trim().chars().take(200).collect::<String>()
+            );
+            Err(if status.as_u16() == 429 || status.is_server_error() {
+                ExportError::retryable(message)
+            } else {
+                ExportError::fatal(message)
+            })
+        }
+
+        #[cfg(not(feature = "remote-backends"))]
+        {
+            let _ = (receiver, headers, body);
+            Err(ExportError::fatal(format!(
+                "cannot send to {} without 'remote-backends' feature",
+                self.url
+            )))
+        }
+    }
+}
+
+struct SinkState {
+    name: &'static str,
+    sink: Box<dyn Sink>,
+    wal: Wal,
+}
+
+/// Scrapes a [`CollectorRegistry`] into per-sink WALs and replays them
+pub struct PushExporter {
+    registry: Arc<CollectorRegistry>,
+    sinks: Vec<SinkState>,
+    max_replay: usize,
+    stats: Arc<Mutex<PushStats>>,
+}
+
+impl PushExporter {
+    /// Set up the configured sinks and open their WALs
+    ///
+    /// Fails if no sink is configured, a URL is invalid or the WAL directory
+    /// cannot be created.
+    pub fn new(config: PushConfig, registry: Arc<CollectorRegistry>) -> Result<Self> {
+        let timeout = Duration::from_millis(config.timeout_ms.max(100));
+        let mut common = config.labels.clone();
+        if !common.contains_key("instance") {
+            common.insert("instance".into(), SystemIdentity::detect().hostname);
+        }
+
+        let mut sinks: Vec<(&'static str, Box<dyn Sink>)> = Vec::new();
+        if let Some(rw) = &config.remote_write {
+            let sink = RemoteWriteSink::new(rw, common.clone(), timeout)?;
+            sinks.push(("remote_write", Box::new(sink)));
+        }
+        if let Some(influx) = &config.influxdb {
+            let sink = InfluxSink::new(influx, common.clone(), timeout)?;
+            sinks.push(("influxdb", Box::new(sink)));
+        }
+        if let Some(statsd) = &config.statsd {
+            sinks.push(("statsd", Box::new(StatsdSink::new(statsd, common.clone()))));
+        }
+        if sinks.is_empty() {
+            return Err(SimonError::Configuration(
+                "push is enabled but no remote_write, influxdb or statsd sink is configured".into(),
+            ));
+        }
+
+        let wal_dir = config.wal_dir()?;
+        let mut stats = PushStats::default();
+        let sinks = sinks
+            .into_iter()
+            .map(|(name, sink)| {
+                let wal = Wal::open(wal_dir.join(name), config.wal_max_bytes)?;
+                stats.sinks.insert(
+                    name.into(),
+                    SinkStats {
+                        pending_bytes: wal.pending_bytes(),
+                        ..Default::default()
+                    },
+                );
+                Ok(SinkState { name, sink, wal })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            registry,
+            sinks,
+            max_replay: config.max_replay_batches.max(1),
+            stats: Arc::new(Mutex::new(stats)),
+        })
+    }
+
+    /// Scrape the registry once and log the result for every sink, returning
+    /// the number of samples
+    pub fn collect(&mut self) -> Result<usize> {
+        let mut exporter = PrometheusExporter::new("simon");
+        self.registry.collect(&mut exporter);
+        let samples = samples_from_families(exporter.families());
+        self.record(&samples, now_millis())?;
+        Ok(samples.len())
+    }
+
+    /// Log `samples`, taken at `timestamp_ms`, for every sink
+    pub fn record(&mut self, samples: &[PushSample], timestamp_ms: i64) -> Result<()> {
+        let mut result = Ok(());
+        for state in &mut self.sinks {
+            for batch in state.sink.encode(samples, timestamp_ms) {
+                if let Err(e) = state.wal.append(&batch) {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        self.update_stats(|s| {
+            s.scrapes += 1;
+            s.samples_collected += samples.len() as u64;
+        });
+        self.refresh_wal_stats();
+        result
+    }
+
+    /// Deliver logged batches oldest-first, at most `max_replay_batches` per sink
+    ///
+    /// A sink stops at its first retryable failure and picks up from there on
+    /// the next flush. Returns whether every WAL is now empty; in offline mode
+    /// nothing is sent and the result is `false`.
+    pub fn flush(&mut self) -> Result<bool> {
+        if is_offline_mode() {
+            self.update_stats(|s| s.offline_skips += 1);
+            return Ok(false);
+        }
+        let mut drained = true;
+        for index in 0..self.sinks.len() {
+            let state = &mut self.sinks[index];
+            let name = state.name;
+            let mut sent = 0;
+            let mut error = None;
+            let mut delivered = Vec::new();
+            let mut rejected = 0;
+            while sent < self.max_replay {
+                let Some(record) = state.wal.peek()? else {
+                    break;
+                };
+                match state.sink.send(&record) {
+                    Ok(()) => {
+                        state.wal.ack()?;
+                        delivered.push(record.len() as u64);
+                        sent += 1;
+                    }
+                    Err(e) if e.retryable => {
+                        log::debug!("Push to {} deferred: {}", name, e.message);
+                        error = Some(e.message);
+                        break;
+                    }
+                    Err(e) => {
+                        log::warn!("{} rejected a batch, dropping it: {}", name, e.message);
+                        state.wal.ack()?;
+                        rejected += 1;
+                        sent += 1;
+                        error = Some(e.message);
+                    }
+                }
+            }
+            drained &= state.wal.pending_bytes() == 0;
+
+            self.update_stats(|s| {
+                let stats = s.sinks.entry(name.into()).or_default();
+                stats.batches_sent += delivered.len() as u64;
+                stats.bytes_sent += delivered.iter().sum::<u64>();
+                stats.batches_rejected += rejected;
+                if !delivered.is_empty() {
+                    stats.last_success = Some(now_millis() as u64 / 1000);
+                }
+                if error.is_some() {
+                    stats.last_error = error;
+                }
+            });
+        }
+        self.refresh_wal_stats();
+        Ok(drained)
+    }
+
+    /// Counters so far
+    pub fn stats(&self) -> PushStats {
+        self.stats.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    fn refresh_wal_stats(&self) {
+        let sizes: Vec<_> = self
+            .sinks
+            .iter()
+            .map(|state| {
+                (
+                    state.name,
+                    state.wal.pending_bytes(),
+                    state.wal.dropped_bytes(),
+                )
+            })
+            .collect();
+        self.update_stats(|s| {
+            for (name, pending, dropped) in sizes {
+                let stats = s.sinks.entry(name.into()).or_default();
+                stats.pending_bytes = pending;
+                stats.dropped_bytes = dropped;
+            }
+        });
+    }
+
+    fn update_stats(&self, f: impl FnOnce(&mut PushStats)) {
+        if let Ok(mut stats) = self.stats.lock() {
+            f(&mut stats);
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Scrape and push on a background thread every `interval_ms`
+///
+/// Batches still unsent when the handle is stopped stay in the WAL and go out
+/// after the next start.
+pub fn spawn(config: PushConfig, registry: Arc<CollectorRegistry>) -> Result<PushHandle> {
+    let interval = Duration::from_millis(config.interval_ms.max(1000));
+    let mut exporter = PushExporter::new(config, registry)?;
+    let stats = Arc::clone(&exporter.stats);
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-push".into())
+        .spawn(move || {
+            while !flag.load(Ordering::SeqCst) {
+                let deadline = Instant::now() + interval;
+                if let Err(e) = exporter.collect() {
+                    log::warn!("Push exporter could not log a scrape: {}", e);
+                }
+                if let Err(e) = exporter.flush() {
+                    log::warn!("Push exporter flush failed: {}", e);
+                }
+                while !flag.load(Ordering::SeqCst) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+            }
+        })
+        .map_err(|e| SimonError::Other(format!("Failed to spawn push exporter: {}", e)))?;
+
+    Ok(PushHandle {
+        stats,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+/// Handle to a running push exporter thread
+pub struct PushHandle {
+    stats: Arc<Mutex<PushStats>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PushHandle {
+    /// Counters so far
+    pub fn stats(&self) -> PushStats {
+        self.stats.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Stop pushing and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for PushHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prometheus::{Collector, CollectorsConfig};
+    use crate::test_util::FakeRoot;
+    #[cfg(feature = "remote-backends")]
+    use std::io::{Read, Write};
+    #[cfg(feature = "remote-backends")]
+    use std::net::TcpListener;
+
+    /// `SIMON_OFFLINE` is process-wide; tests that flush must not overlap
+    static OFFLINE_LOCK: Mutex<()> = Mutex::new(());
+
+    struct Fixed;
+
+    impl Collector for Fixed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn collect(&self, exporter: &mut PrometheusExporter) -> Result<()> {
+            exporter.add(MetricFamily::gauge(
+                &exporter.prefixed("test_value"),
+                "Test value",
+                7.0,
+            ));
+            Ok(())
+        }
+    }
+
+    fn registry() -> Arc<CollectorRegistry> {
+        let registry = CollectorRegistry::new(CollectorsConfig::default());
+        registry.register(Arc::new(Fixed));
+        Arc::new(registry)
+    }
+
+    /// Answer `count` HTTP requests with 204, returning (headers, body) of each
+    #[cfg(feature = "remote-backends")]
+    fn serve(listener: TcpListener, count: usize) -> JoinHandle<Vec<(String, Vec<u8>)>> {
+        std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut raw = Vec::new();
+                let mut buf = [0u8; 4096];
+                let head_end = loop {
+                    let n = stream.read(&mut buf).unwrap();
+                    raw.extend_from_slice(&buf[..n]);
+                    if let Some(pos) = raw.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos;
+                    }
+                };
+                let head = String::from_utf8_lossy(&raw[..head_end]).to_ascii_lowercase();
+                let length: usize = head
+                    .lines()
+                    .find_map(|l| l.strip_prefix("content-length:"))
+                    .and_then(|v| v.trim().parse().ok())
+                    .unwrap_or(0);
+                let mut body = raw[head_end + 4..].to_vec();
+                while body.len() < length {
+                    let n = stream.read(&mut buf).unwrap();
+                    body.extend_from_slice(&buf[..n]);
+                }
+                stream
+                    .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+                    .unwrap();
+                requests.push((head, body));
+            }
+            requests
+        })
+    }
+
+    #[test]
+    fn test_samples_from_families() {
+        let mut family = MetricFamily::counter("simon_rx_bytes_total", "Bytes", 10.0);
+        family.samples[0].suffix = "created".into();
+        let samples = samples_from_families(&[family]);
+        assert_eq!(samples[0].name, "simon_rx_bytes_total_created");
+        assert_eq!(samples[0].metric_type, MetricType::Counter);
+    }
+
+    #[test]
+    #[cfg(feature = "remote-backends")]
+    fn test_remote_write_replays_after_outage_and_restart() {
+        let _guard = OFFLINE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        // Reserve a port, then leave it closed to simulate the receiver being down
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let dir = FakeRoot::new("push-remote-write");
+        let config = PushConfig {
+            wal_dir: Some(dir.path().to_path_buf()),
+            labels: BTreeMap::from([("instance".to_string(), "edge-1".to_string())]),
+            remote_write: Some(RemoteWriteConfig {
+                url: format!("http://{}/api/v1/write", addr),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        {
+            let mut exporter = PushExporter::new(config.clone(), registry()).unwrap();
+            exporter.collect().unwrap();
+            exporter.collect().unwrap();
+            assert!(!exporter.flush().unwrap());
+            let stats = exporter.stats().sinks["remote_write"].clone();
+            assert!(stats.last_error.is_some());
+            assert!(stats.pending_bytes > 0);
+            assert_eq!(stats.batches_sent, 0);
+        }
+
+        // A new process finds the batches in the WAL and sends them once the receiver is up
+        let server = serve(TcpListener::bind(addr).unwrap(), 2);
+        let mut exporter = PushExporter::new(config, registry()).unwrap();
+        assert!(exporter.flush().unwrap());
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        for (head, body) in &requests {
+            assert!(head.starts_with("post /api/v1/write "));
+            assert!(head.contains("content-encoding: snappy"));
+            assert!(head.contains("x-prometheus-remote-write-version: 0.1.0"));
+            let raw = snap::raw::Decoder::new().decompress_vec(body).unwrap();
+            let series = remote_write::decode_write_request(&raw).unwrap();
+            let value = series
+                .iter()
+                .find(|s| s.label("__name__") == Some("simon_test_value"))
+                .unwrap();
+            assert_eq!(value.label("instance"), Some("edge-1"));
+            assert_eq!(value.samples[0].0, 7.0);
+            // Scrape meta-metrics travel with the data
+            assert!(series
+                .iter()
+                .any(|s| s.label("__name__") == Some("simon_scrape_collector_success")));
+        }
+        let stats = exporter.stats().sinks["remote_write"].clone();
+        assert_eq!(stats.batches_sent, 2);
+        assert_eq!(stats.pending_bytes, 0);
+    }
+
+    #[test]
+    fn test_influx_udp_respects_offline_mode() {
+        let _guard = OFFLINE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_millis(300)))
+            .unwrap();
+        let dir = FakeRoot::new("push-influx-udp");
+        let config = PushConfig {
+            wal_dir: Some(dir.path().to_path_buf()),
+            labels: BTreeMap::from([("instance".to_string(), "edge-1".to_string())]),
+            influxdb: Some(InfluxConfig {
+                url: format!("udp://{}", socket.local_addr().unwrap()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut exporter = PushExporter::new(config, registry()).unwrap();
+        let sample = PushSample {
+            name: "simon_gpu_power_watts".into(),
+            labels: BTreeMap::from([("gpu".to_string(), "0".to_string())]),
+            value: 12.5,
+            metric_type: MetricType::Gauge,
+        };
+        exporter.record(&[sample], 1_700_000_000_000).unwrap();
+
+        std::env::set_var("SIMON_OFFLINE", "1");
+        let offline = exporter.flush();
+        std::env::remove_var("SIMON_OFFLINE");
+        assert!(!offline.unwrap());
+        assert_eq!(exporter.stats().offline_skips, 1);
+        let mut buf = [0u8; 2048];
+        assert!(socket.recv_from(&mut buf).is_err());
+
+        assert!(exporter.flush().unwrap());
+        let (n, _) = socket.recv_from(&mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..n]).unwrap(),
+            "simon_gpu_power_watts,gpu=0,instance=edge-1 value=12.5 1700000000000000000"
+        );
+    }
+}
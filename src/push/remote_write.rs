@@ -0,0 +1,247 @@
+//! Prometheus remote_write sink
+//!
+//! Samples are encoded as a `prometheus.WriteRequest` protobuf (remote write
+//! 1.0), snappy-compressed and `POST`ed with the headers receivers such as
+//! Prometheus, Mimir, Thanos Receive and VictoriaMetrics expect. Each series
+//! carries `__name__` plus its labels, sorted by name as the protocol requires.
+
+use super::{HttpTarget, PushSample, Sink};
+use crate::error::{Result, SimonError};
+use crate::otlp::transport::ExportError;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Remote write endpoint settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteWriteConfig {
+    /// Receiver URL (`http://` or `https://`), e.g.
+    /// `https://prometheus:9090/api/v1/write`
+    pub url: String,
+    /// Extra request headers (e.g. `Authorization`, `X-Scope-OrgID`)
+    pub headers: BTreeMap<String, String>,
+    /// Samples per request; larger scrapes are split
+    pub max_samples_per_send: usize,
+}
+
+impl Default for RemoteWriteConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:9090/api/v1/write".into(),
+            headers: BTreeMap::new(),
+            max_samples_per_send: 2000,
+        }
+    }
+}
+
+/// A series decoded from a `WriteRequest`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSeries {
+    /// Label pairs in wire order, `__name__` included
+    pub labels: Vec<(String, String)>,
+    /// `(value, timestamp in milliseconds)`
+    pub samples: Vec<(f64, i64)>,
+}
+
+impl TimeSeries {
+    /// Value of label `name`, if present
+    pub fn label(&self, name: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// `prometheus.WriteRequest` messages from the remote write 1.0 `types.proto`
+mod wire {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct WriteRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub timeseries: Vec<TimeSeries>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TimeSeries {
+        #[prost(message, repeated, tag = "1")]
+        pub labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        pub samples: Vec<Sample>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Label {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub value: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sample {
+        #[prost(double, tag = "1")]
+        pub value: f64,
+        #[prost(int64, tag = "2")]
+        pub timestamp: i64,
+    }
+}
+
+/// Encode samples taken at `timestamp_ms` as an uncompressed `WriteRequest`,
+/// adding `common` labels a sample does not set itself
+pub fn encode_write_request(
+    samples: &[PushSample],
+    common: &BTreeMap<String, String>,
+    timestamp_ms: i64,
+) -> Vec<u8> {
+    let timeseries = samples
+        .iter()
+        .map(|sample| {
+            let mut labels: BTreeMap<&str, &str> = common
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect();
+            labels.extend(sample.labels.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+            labels.insert("__name__", &sample.name);
+            // An empty value means "no such label" in Prometheus
+            labels.retain(|_, v| !v.is_empty());
+
+            wire::TimeSeries {
+                labels: labels
+                    .into_iter()
+                    .map(|(name, value)| wire::Label {
+                        name: name.into(),
+                        value: value.into(),
+                    })
+                    .collect(),
+                samples: vec![wire::Sample {
+                    value: sample.value,
+                    timestamp: timestamp_ms,
+                }],
+            }
+        })
+        .collect();
+    wire::WriteRequest { timeseries }.encode_to_vec()
+}
+
+/// Decode an uncompressed `WriteRequest`
+pub fn decode_write_request(buf: &[u8]) -> Result<Vec<TimeSeries>> {
+    let request = wire::WriteRequest::decode(buf)
+        .map_err(|e| SimonError::Parse(format!("remote_write: {}", e)))?;
+    Ok(request
+        .timeseries
+        .into_iter()
+        .map(|ts| TimeSeries {
+            labels: ts.labels.into_iter().map(|l| (l.name, l.value)).collect(),
+            samples: ts
+                .samples
+                .into_iter()
+                .map(|s| (s.value, s.timestamp))
+                .collect(),
+        })
+        .collect())
+}
+
+pub(super) struct RemoteWriteSink {
+    target: HttpTarget,
+    common: BTreeMap<String, String>,
+    max_samples: usize,
+}
+
+impl RemoteWriteSink {
+    pub(super) fn new(
+        config: &RemoteWriteConfig,
+        common: BTreeMap<String, String>,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let headers = config
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let target = HttpTarget::new(&config.url, headers, timeout)?;
+        if config.max_samples_per_send == 0 {
+            return Err(SimonError::Configuration(
+                "remote_write max_samples_per_send must be at least 1".into(),
+            ));
+        }
+        Ok(Self {
+            target,
+            common,
+            max_samples: config.max_samples_per_send,
+        })
+    }
+}
+
+impl Sink for RemoteWriteSink {
+    fn encode(&mut self, samples: &[PushSample], timestamp_ms: i64) -> Vec<Vec<u8>> {
+        samples
+            .chunks(self.max_samples)
+            .filter_map(|chunk| {
+                let request = encode_write_request(chunk, &self.common, timestamp_ms);
+                match snap::raw::Encoder::new().compress_vec(&request) {
+                    Ok(compressed) => Some(compressed),
+                    Err(e) => {
+                        log::warn!("remote_write: dropping a batch snappy cannot hold: {}", e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn send(&mut self, record: &[u8]) -> std::result::Result<(), ExportError> {
+        // Per the remote write spec, only 5xx and 429 may be retried
+        self.target.post(
+            "remote_write receiver",
+            &[
+                ("content-type", "application/x-protobuf"),
+                ("content-encoding", "snappy"),
+                ("x-prometheus-remote-write-version", "0.1.0"),
+            ],
+            record.to_vec(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prometheus::MetricType;
+
+    #[test]
+    fn test_write_request_round_trip() {
+        let samples = vec![
+            PushSample {
+                name: "simon_gpu_temperature_celsius".into(),
+                labels: BTreeMap::from([("gpu".to_string(), "0".to_string())]),
+                value: 41.5,
+                metric_type: MetricType::Gauge,
+            },
+            PushSample {
+                name: "simon_up".into(),
+                labels: BTreeMap::from([("instance".to_string(), "override".to_string())]),
+                value: f64::NAN,
+                metric_type: MetricType::Gauge,
+            },
+        ];
+        let common = BTreeMap::from([
+            ("instance".to_string(), "jetson-7".to_string()),
+            ("job".to_string(), "simon".to_string()),
+        ]);
+        let encoded = encode_write_request(&samples, &common, 1_700_000_000_123);
+        let series = decode_write_request(&encoded).unwrap();
+        assert_eq!(series.len(), 2);
+
+        let names: Vec<&str> = series[0].labels.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(names, ["__name__", "gpu", "instance", "job"]);
+        assert_eq!(
+            series[0].label("__name__"),
+            Some("simon_gpu_temperature_celsius")
+        );
+        assert_eq!(series[0].samples, [(41.5, 1_700_000_000_123)]);
+        assert_eq!(series[1].label("instance"), Some("override"));
+        assert!(series[1].samples[0].0.is_nan());
+    }
+}
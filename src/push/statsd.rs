@@ -0,0 +1,217 @@
+//! StatsD / DogStatsD sink
+//!
+//! Gauges (and histogram/summary components) are sent as `|g`; Prometheus
+//! counters are cumulative, so they are sent as `|c` with the increase since
+//! the previous scrape. DogStatsD carries labels as `|#key:value` tags and a
+//! `|T<unix seconds>` timestamp so replayed batches land at the right time.
+//! Plain StatsD has neither: label values are appended to the metric name
+//! (`simon_gpu_temperature_celsius.0`).
+
+use super::{PushSample, Sink, UdpTarget};
+use crate::otlp::transport::ExportError;
+use crate::prometheus::MetricType;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+
+/// StatsD server settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsdConfig {
+    /// `host:port` of the StatsD server or Datadog agent
+    pub address: String,
+    /// Use DogStatsD tags and timestamps instead of plain StatsD names
+    pub dogstatsd: bool,
+    /// Largest UDP datagram payload
+    pub max_packet_bytes: usize,
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:8125".into(),
+            dogstatsd: false,
+            max_packet_bytes: 1432,
+        }
+    }
+}
+
+pub(super) struct StatsdSink {
+    udp: UdpTarget,
+    dogstatsd: bool,
+    common: BTreeMap<String, String>,
+    /// Last cumulative value of each counter, keyed by rendered name and tags
+    counters: HashMap<String, f64>,
+}
+
+impl StatsdSink {
+    pub(super) fn new(config: &StatsdConfig, common: BTreeMap<String, String>) -> Self {
+        Self {
+            udp: UdpTarget::new(config.address.clone(), config.max_packet_bytes),
+            dogstatsd: config.dogstatsd,
+            common,
+            counters: HashMap::new(),
+        }
+    }
+
+    /// Metric name plus, for DogStatsD, the `|#tags` suffix
+    fn series(&self, sample: &PushSample) -> (String, String) {
+        let mut labels: BTreeMap<&str, &str> = BTreeMap::new();
+        if self.dogstatsd {
+            labels.extend(self.common.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        }
+        labels.extend(sample.labels.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        labels.retain(|_, v| !v.is_empty());
+
+        let mut name = sanitize(&sample.name, false);
+        if self.dogstatsd {
+            let tags: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}:{}", sanitize(k, false), sanitize(v, true)))
+                .collect();
+            let tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!("|#{}", tags.join(","))
+            };
+            (name, tags)
+        } else {
+            for value in labels.values() {
+                name.push('.');
+                name.push_str(&sanitize(value, false));
+            }
+            (name, String::new())
+        }
+    }
+}
+
+/// Replace characters that would break a StatsD line; tag values may keep `:`
+fn sanitize(s: &str, tag_value: bool) -> String {
+    s.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '.' | '/' => c,
+            ':' if tag_value => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+impl Sink for StatsdSink {
+    fn encode(&mut self, samples: &[PushSample], timestamp_ms: i64) -> Vec<Vec<u8>> {
+        let mut out = String::new();
+        let timestamp = if self.dogstatsd {
+            format!("|T{}", timestamp_ms / 1000)
+        } else {
+            String::new()
+        };
+        for sample in samples {
+            if !sample.value.is_finite() {
+                continue;
+            }
+            let (name, tags) = self.series(sample);
+            if sample.metric_type == MetricType::Counter {
+                let key = format!("{}{}", name, tags);
+                let previous = self.counters.insert(key, sample.value);
+                let Some(previous) = previous else {
+                    continue;
+                };
+                // A drop means the counter restarted from zero
+                let delta = if sample.value >= previous {
+                    sample.value - previous
+                } else {
+                    sample.value
+                };
+                let _ = writeln!(out, "{}:{}|c{}{}", name, delta, tags, timestamp);
+            } else {
+                // A leading sign adjusts a plain StatsD gauge instead of setting it
+                if sample.value < 0.0 && !self.dogstatsd {
+                    let _ = writeln!(out, "{}:0|g", name);
+                }
+                let _ = writeln!(out, "{}:{}|g{}{}", name, sample.value, tags, timestamp);
+            }
+        }
+        if out.is_empty() {
+            Vec::new()
+        } else {
+            vec![out.into_bytes()]
+        }
+    }
+
+    fn send(&mut self, record: &[u8]) -> std::result::Result<(), ExportError> {
+        self.udp.send_lines(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(
+        name: &str,
+        labels: &[(&str, &str)],
+        value: f64,
+        metric_type: MetricType,
+    ) -> PushSample {
+        PushSample {
+            name: name.into(),
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            value,
+            metric_type,
+        }
+    }
+
+    fn encode(sink: &mut StatsdSink, samples: &[PushSample], timestamp_ms: i64) -> String {
+        sink.encode(samples, timestamp_ms)
+            .into_iter()
+            .map(|r| String::from_utf8(r).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_statsd_lines() {
+        let common = BTreeMap::from([("host".to_string(), "jetson-7".to_string())]);
+        let mut plain = StatsdSink::new(&StatsdConfig::default(), common.clone());
+        let scrape = |bytes: f64, temp: f64| {
+            vec![
+                sample(
+                    "simon_gpu_temperature_celsius",
+                    &[("gpu", "0")],
+                    temp,
+                    MetricType::Gauge,
+                ),
+                sample(
+                    "simon_net_rx_bytes_total",
+                    &[("iface", "eth0")],
+                    bytes,
+                    MetricType::Counter,
+                ),
+            ]
+        };
+        // Counters need a previous value before a delta can be sent
+        assert_eq!(
+            encode(&mut plain, &scrape(1000.0, 41.0), 0),
+            "simon_gpu_temperature_celsius.0:41|g\n"
+        );
+        assert_eq!(
+            encode(&mut plain, &scrape(1500.0, -3.5), 0),
+            "simon_gpu_temperature_celsius.0:0|g\n\
+             simon_gpu_temperature_celsius.0:-3.5|g\n\
+             simon_net_rx_bytes_total.eth0:500|c\n"
+        );
+
+        let config = StatsdConfig {
+            dogstatsd: true,
+            ..Default::default()
+        };
+        let mut dog = StatsdSink::new(&config, common);
+        encode(&mut dog, &scrape(1000.0, 41.0), 1_700_000_000_500);
+        assert_eq!(
+            encode(&mut dog, &scrape(200.0, 40.0), 1_700_000_010_500),
+            "simon_gpu_temperature_celsius:40|g|#gpu:0,host:jetson-7|T1700000010\n\
+             simon_net_rx_bytes_total:200|c|#host:jetson-7,iface:eth0|T1700000010\n"
+        );
+    }
+}
@@ -0,0 +1,445 @@
+//! On-disk write-ahead log for push exporters
+//!
+//! Every encoded batch is appended to a segment file before delivery is
+//! attempted and only released once the remote end has accepted it, so data
+//! gathered while the uplink is down, or across a reboot, is replayed in
+//! order when connectivity returns.
+//!
+//! A log is a directory of `<sequence>.wal` segments, each starting with an
+//! 8-byte magic and holding `[length u32 LE][crc32 u32 LE][payload]` records,
+//! plus a `cursor` file naming the oldest unacknowledged record. A torn record
+//! at the tail of the newest segment (power lost mid-write) is truncated on
+//! open; once the log exceeds its size limit the oldest segment is discarded.
+
+use crate::error::{Result, SimonError};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"SIMONWAL";
+const HEADER_LEN: u64 = MAGIC.len() as u64;
+const RECORD_HEADER_LEN: u64 = 8;
+/// Largest record accepted back from disk; anything bigger is corruption
+const MAX_RECORD_LEN: u32 = 64 << 20;
+const CURSOR_FILE: &str = "cursor";
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    seq: u64,
+    /// Bytes on disk, including the magic
+    size: u64,
+}
+
+/// Oldest unacknowledged record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cursor {
+    seq: u64,
+    offset: u64,
+}
+
+/// Append-only record log with a persistent read cursor
+pub struct Wal {
+    dir: PathBuf,
+    max_bytes: u64,
+    segment_bytes: u64,
+    /// Oldest first; segments before the cursor are deleted eagerly
+    segments: VecDeque<Segment>,
+    cursor: Cursor,
+    /// Length of the record returned by the last [`Wal::peek`]
+    peeked: Option<u64>,
+    writer: Option<File>,
+    dropped_bytes: u64,
+}
+
+impl Wal {
+    /// Open or create the log in `dir`, keeping at most about `max_bytes` on disk
+    pub fn open(dir: impl AsRef<Path>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut seqs: Vec<u64> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                name.strip_suffix(".wal")?.parse().ok()
+            })
+            .collect();
+        seqs.sort_unstable();
+
+        let mut segments = VecDeque::new();
+        for seq in seqs {
+            let path = segment_path(&dir, seq);
+            let mut magic = [0u8; 8];
+            let valid = File::open(&path)
+                .and_then(|mut f| f.read_exact(&mut magic))
+                .is_ok()
+                && &magic == MAGIC;
+            if !valid {
+                log::warn!("Discarding unreadable WAL segment {}", path.display());
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            let size = fs::metadata(&path)?.len();
+            segments.push_back(Segment { seq, size });
+        }
+        if let Some(last) = segments.back_mut() {
+            last.size = truncate_torn_tail(&segment_path(&dir, last.seq))?;
+        }
+
+        let first = segments.front().map(|s| s.seq).unwrap_or(1);
+        let cursor = fs::read_to_string(dir.join(CURSOR_FILE))
+            .ok()
+            .and_then(|s| {
+                let (seq, offset) = s.trim().split_once(' ')?;
+                Some(Cursor {
+                    seq: seq.parse().ok()?,
+                    offset: offset.parse().ok()?,
+                })
+            })
+            .filter(|c| {
+                segments
+                    .iter()
+                    .any(|s| s.seq == c.seq && (HEADER_LEN..=s.size).contains(&c.offset))
+            })
+            .unwrap_or(Cursor {
+                seq: first,
+                offset: HEADER_LEN,
+            });
+        // Segments wholly before the cursor were acknowledged but not yet removed
+        while segments.front().is_some_and(|s| s.seq < cursor.seq) {
+            if let Some(old) = segments.pop_front() {
+                let _ = fs::remove_file(segment_path(&dir, old.seq));
+            }
+        }
+
+        Ok(Self {
+            dir,
+            max_bytes,
+            segment_bytes: (max_bytes / 8).clamp(4096, 8 << 20),
+            segments,
+            cursor,
+            peeked: None,
+            writer: None,
+            dropped_bytes: 0,
+        })
+    }
+
+    /// Directory holding the segments
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Append one record and sync it to disk
+    pub fn append(&mut self, payload: &[u8]) -> Result<()> {
+        let record_len = RECORD_HEADER_LEN + payload.len() as u64;
+        let rotate = match self.segments.back() {
+            None => true,
+            Some(last) => last.size > HEADER_LEN && last.size + record_len > self.segment_bytes,
+        };
+        if rotate {
+            self.rotate()?;
+        }
+        let writer = match self.writer.take() {
+            Some(file) => file,
+            None => {
+                let seq = self.segments.back().map(|s| s.seq).unwrap_or(1);
+                OpenOptions::new()
+                    .append(true)
+                    .open(segment_path(&self.dir, seq))?
+            }
+        };
+        let writer = self.writer.insert(writer);
+
+        let mut record = Vec::with_capacity(record_len as usize);
+        record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        record.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+        record.extend_from_slice(payload);
+        writer.write_all(&record)?;
+        writer.sync_data()?;
+        if let Some(last) = self.segments.back_mut() {
+            last.size += record_len;
+        }
+        self.enforce_limit()
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        let seq = self
+            .segments
+            .back()
+            .map(|s| s.seq + 1)
+            .unwrap_or(self.cursor.seq);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(segment_path(&self.dir, seq))?;
+        file.write_all(MAGIC)?;
+        file.sync_data()?;
+        self.segments.push_back(Segment {
+            seq,
+            size: HEADER_LEN,
+        });
+        if self.segments.len() == 1 {
+            self.cursor = Cursor {
+                seq,
+                offset: HEADER_LEN,
+            };
+        }
+        self.writer = None;
+        Ok(())
+    }
+
+    /// Drop the oldest segments until the log fits in `max_bytes`
+    fn enforce_limit(&mut self) -> Result<()> {
+        while self.disk_bytes() > self.max_bytes && self.segments.len() > 1 {
+            let Some(oldest) = self.segments.pop_front() else {
+                break;
+            };
+            // The cursor always sits in the oldest segment
+            let unread = oldest.size.saturating_sub(self.cursor.offset);
+            self.dropped_bytes += unread;
+            log::warn!(
+                "WAL {} over its {} byte limit, discarded {} unsent bytes",
+                self.dir.display(),
+                self.max_bytes,
+                unread
+            );
+            fs::remove_file(segment_path(&self.dir, oldest.seq))?;
+            self.advance_to_next_segment()?;
+        }
+        Ok(())
+    }
+
+    /// Oldest unacknowledged record, if any
+    ///
+    /// The same record is returned until [`Wal::ack`] is called.
+    pub fn peek(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            let Some(segment) = self.segments.front().copied() else {
+                return Ok(None);
+            };
+            let newest = self.segments.len() == 1;
+            if self.cursor.offset >= segment.size {
+                if newest {
+                    return Ok(None);
+                }
+                self.segments.pop_front();
+                fs::remove_file(segment_path(&self.dir, segment.seq))?;
+                self.advance_to_next_segment()?;
+                continue;
+            }
+
+            match read_record(&segment_path(&self.dir, segment.seq), self.cursor.offset)? {
+                Some(payload) => {
+                    self.peeked = Some(payload.len() as u64);
+                    return Ok(Some(payload));
+                }
+                None => {
+                    // Corrupt record: everything after it in the segment is unreachable
+                    let lost = segment.size - self.cursor.offset;
+                    self.dropped_bytes += lost;
+                    log::warn!(
+                        "Corrupt record in WAL segment {} at offset {}, skipping {} bytes",
+                        segment_path(&self.dir, segment.seq).display(),
+                        self.cursor.offset,
+                        lost
+                    );
+                    if newest {
+                        let file = OpenOptions::new()
+                            .write(true)
+                            .open(segment_path(&self.dir, segment.seq))?;
+                        file.set_len(self.cursor.offset)?;
+                        self.writer = None;
+                        if let Some(last) = self.segments.back_mut() {
+                            last.size = self.cursor.offset;
+                        }
+                        return Ok(None);
+                    }
+                    self.cursor.offset = segment.size;
+                }
+            }
+        }
+    }
+
+    /// Release the record returned by the last [`Wal::peek`]
+    pub fn ack(&mut self) -> Result<()> {
+        let Some(len) = self.peeked.take() else {
+            return Ok(());
+        };
+        self.cursor.offset += RECORD_HEADER_LEN + len;
+        self.save_cursor()
+    }
+
+    fn advance_to_next_segment(&mut self) -> Result<()> {
+        self.peeked = None;
+        if let Some(next) = self.segments.front() {
+            self.cursor = Cursor {
+                seq: next.seq,
+                offset: HEADER_LEN,
+            };
+        }
+        self.save_cursor()
+    }
+
+    fn save_cursor(&self) -> Result<()> {
+        let tmp = self.dir.join(format!("{}.tmp", CURSOR_FILE));
+        fs::write(
+            &tmp,
+            format!("{} {}\n", self.cursor.seq, self.cursor.offset),
+        )?;
+        fs::rename(&tmp, self.dir.join(CURSOR_FILE))?;
+        Ok(())
+    }
+
+    fn disk_bytes(&self) -> u64 {
+        self.segments.iter().map(|s| s.size).sum()
+    }
+
+    /// Bytes of records not yet acknowledged, including record headers
+    pub fn pending_bytes(&self) -> u64 {
+        let total: u64 = self
+            .segments
+            .iter()
+            .map(|s| s.size.saturating_sub(HEADER_LEN))
+            .sum();
+        total.saturating_sub(self.cursor.offset.saturating_sub(HEADER_LEN))
+    }
+
+    /// Unsent bytes discarded to the size limit or to corruption since opening
+    pub fn dropped_bytes(&self) -> u64 {
+        self.dropped_bytes
+    }
+}
+
+fn segment_path(dir: &Path, seq: u64) -> PathBuf {
+    dir.join(format!("{:016}.wal", seq))
+}
+
+/// Read the record at `offset`; `None` if it is truncated or fails its checksum
+fn read_record(path: &Path, offset: u64) -> Result<Option<Vec<u8>>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut header = [0u8; RECORD_HEADER_LEN as usize];
+    match file.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let crc = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    if len > MAX_RECORD_LEN {
+        return Ok(None);
+    }
+    let mut payload = vec![0u8; len as usize];
+    match file.read_exact(&mut payload) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    Ok((crc32fast::hash(&payload) == crc).then_some(payload))
+}
+
+/// Cut the segment after its last intact record, returning the new size
+fn truncate_torn_tail(path: &Path) -> Result<u64> {
+    let size = fs::metadata(path)?.len();
+    let mut offset = HEADER_LEN;
+    while offset < size {
+        match read_record(path, offset)? {
+            Some(payload) => offset += RECORD_HEADER_LEN + payload.len() as u64,
+            None => break,
+        }
+    }
+    if offset < size {
+        log::warn!(
+            "Truncating torn WAL tail in {} ({} bytes)",
+            path.display(),
+            size - offset
+        );
+        OpenOptions::new()
+            .write(true)
+            .open(path)
+            .and_then(|f| f.set_len(offset))
+            .map_err(|e| SimonError::Other(format!("truncate {}: {}", path.display(), e)))?;
+    }
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    fn drain(wal: &mut Wal) -> Vec<Vec<u8>> {
+        let mut out = Vec::new();
+        while let Some(record) = wal.peek().unwrap() {
+            out.push(record);
+            wal.ack().unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_replay_survives_reopen() {
+        let dir = FakeRoot::new("wal-reopen");
+        {
+            let mut wal = Wal::open(dir.path(), 1 << 20).unwrap();
+            for i in 0..5u8 {
+                wal.append(&[i; 10]).unwrap();
+            }
+            assert_eq!(wal.peek().unwrap().unwrap(), [0; 10]);
+            wal.ack().unwrap();
+            assert_eq!(wal.pending_bytes(), 4 * 18);
+        }
+        let mut wal = Wal::open(dir.path(), 1 << 20).unwrap();
+        assert_eq!(
+            drain(&mut wal),
+            (1..5u8).map(|i| vec![i; 10]).collect::<Vec<_>>()
+        );
+        assert_eq!(wal.pending_bytes(), 0);
+
+        wal.append(b"after").unwrap();
+        drop(wal);
+        let mut wal = Wal::open(dir.path(), 1 << 20).unwrap();
+        assert_eq!(drain(&mut wal), vec![b"after".to_vec()]);
+    }
+
+    #[test]
+    fn test_rotation_and_size_limit() {
+        let dir = FakeRoot::new("wal-limit");
+        let mut wal = Wal::open(dir.path(), 16 * 1024).unwrap();
+        for i in 0..100u32 {
+            wal.append(&[i as u8; 1000]).unwrap();
+        }
+        assert!(wal.disk_bytes() <= 16 * 1024);
+        assert!(wal.dropped_bytes() > 0);
+        let records = drain(&mut wal);
+        // Only the newest records survive, still in order
+        assert!(!records.is_empty() && records.len() < 100);
+        assert_eq!(records.last().unwrap()[0], 99);
+        assert!(records.windows(2).all(|w| w[1][0] == w[0][0] + 1));
+        // Fully drained segments are removed
+        assert_eq!(wal.segments.len(), 1);
+    }
+
+    #[test]
+    fn test_torn_tail_is_truncated() {
+        let dir = FakeRoot::new("wal-torn");
+        {
+            let mut wal = Wal::open(dir.path(), 1 << 20).unwrap();
+            wal.append(b"one").unwrap();
+            wal.append(b"two").unwrap();
+        }
+        let path = segment_path(dir.path(), 1);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[50, 0, 0, 0, 1, 2, 3, 4, b'x']).unwrap();
+        drop(file);
+
+        let mut wal = Wal::open(dir.path(), 1 << 20).unwrap();
+        wal.append(b"three").unwrap();
+        assert_eq!(
+            drain(&mut wal),
+            vec![b"one".to_vec(), b"two".to_vec(), b"three".to_vec()]
+        );
+    }
+}
@@ -0,0 +1,1098 @@
+//! RDMA fabric monitoring (InfiniBand and RoCE)
+//!
+//! GPU clusters move collective traffic over the RDMA fabric, not the kernel
+//! network stack, so [`crate::network_monitor`] never sees it. This module
+//! reads the `ib_core` sysfs tree at `/sys/class/infiniband/<dev>/ports/<n>`:
+//! port and physical state, link rate and width, LID and GID, the standard
+//! IB port counters in `counters/` and the vendor counters in `hw_counters/`
+//! (for mlx5: ECN-marked packets, CNPs sent and handled, receive buffer
+//! drops, retransmissions).
+//!
+//! [`RdmaMonitor`] keeps the previous reading per port to turn counters into
+//! per-second rates and to raise [`RdmaAlert`]s when a port leaves `ACTIVE`,
+//! the link flaps (`link_downed`) or physical-layer error counters move.
+//! NCCL slowdowns usually show up here before anywhere else: `port_xmit_wait`
+//! climbing under credit starvation, ECN/CNP activity under congestion, or
+//! `symbol_error` on a marginal cable.
+//!
+//! Each device is tied to its PCIe function ([`PcieDevice`]) and NUMA node so
+//! it can be matched with the GPUs and CPUs that share its root complex.
+//!
+//! `port_xmit_data` and `port_rcv_data` count 4-byte words; byte totals and
+//! rates are converted.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::rdma::{RdmaConfig, RdmaMonitor};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = RdmaMonitor::new(RdmaConfig::default())?;
+//! monitor.sample();
+//! std::thread::sleep(std::time::Duration::from_secs(1));
+//! for dev in monitor.sample().devices {
+//!     for port in &dev.ports {
+//!         if let Some(rates) = &port.rates {
+//!             println!("{}/{} {}: tx {:.0} B/s", dev.name, port.port, port.state, rates.tx_bytes_per_sec);
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, SimonError};
+use crate::observability::{event_types, EventCategory, EventManager, SystemEvent};
+use crate::pcie::PcieDevice;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Port counters that only move on link or physical-layer faults
+pub const ERROR_COUNTERS: &[&str] = &[
+    "symbol_error",
+    "link_error_recovery",
+    "link_downed",
+    "port_rcv_errors",
+    "port_rcv_remote_physical_errors",
+    "port_rcv_switch_relay_errors",
+    "local_link_integrity_errors",
+    "excessive_buffer_overrun_errors",
+    "port_xmit_discards",
+    "port_rcv_constraint_errors",
+    "port_xmit_constraint_errors",
+    "VL15_dropped",
+];
+
+/// Logical port state (`ports/<n>/state`, e.g. `4: ACTIVE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortState {
+    Unknown = 0,
+    Down = 1,
+    Init = 2,
+    Armed = 3,
+    Active = 4,
+    ActiveDefer = 5,
+}
+
+impl PortState {
+    pub fn from_sysfs(s: &str) -> Self {
+        match leading_number(s) {
+            Some(1) => Self::Down,
+            Some(2) => Self::Init,
+            Some(3) => Self::Armed,
+            Some(4) => Self::Active,
+            Some(5) => Self::ActiveDefer,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for PortState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "UNKNOWN"),
+            Self::Down => write!(f, "DOWN"),
+            Self::Init => write!(f, "INIT"),
+            Self::Armed => write!(f, "ARMED"),
+            Self::Active => write!(f, "ACTIVE"),
+            Self::ActiveDefer => write!(f, "ACTIVE_DEFER"),
+        }
+    }
+}
+
+/// Physical link state (`ports/<n>/phys_state`, e.g. `5: LinkUp`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhysState {
+    Unknown = 0,
+    Sleep = 1,
+    Polling = 2,
+    Disabled = 3,
+    /// Port configuration training
+    Training = 4,
+    LinkUp = 5,
+    LinkErrorRecovery = 6,
+    PhyTest = 7,
+}
+
+impl PhysState {
+    pub fn from_sysfs(s: &str) -> Self {
+        match leading_number(s) {
+            Some(1) => Self::Sleep,
+            Some(2) => Self::Polling,
+            Some(3) => Self::Disabled,
+            Some(4) => Self::Training,
+            Some(5) => Self::LinkUp,
+            Some(6) => Self::LinkErrorRecovery,
+            Some(7) => Self::PhyTest,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for PhysState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "Unknown"),
+            Self::Sleep => write!(f, "Sleep"),
+            Self::Polling => write!(f, "Polling"),
+            Self::Disabled => write!(f, "Disabled"),
+            Self::Training => write!(f, "PortConfigurationTraining"),
+            Self::LinkUp => write!(f, "LinkUp"),
+            Self::LinkErrorRecovery => write!(f, "LinkErrorRecovery"),
+            Self::PhyTest => write!(f, "PhyTest"),
+        }
+    }
+}
+
+/// Transport under the port (`ports/<n>/link_layer`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkLayer {
+    InfiniBand,
+    /// RoCE
+    Ethernet,
+    Unknown,
+}
+
+impl LinkLayer {
+    pub fn from_sysfs(s: &str) -> Self {
+        match s.trim() {
+            "InfiniBand" => Self::InfiniBand,
+            "Ethernet" => Self::Ethernet,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for LinkLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InfiniBand => write!(f, "InfiniBand"),
+            Self::Ethernet => write!(f, "RoCE"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Negotiated link rate (`ports/<n>/rate`, e.g. `200 Gb/sec (4X HDR)`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkRate {
+    pub gbps: f64,
+    /// Lane count (the `4` in `4X`)
+    pub width: Option<u8>,
+    /// Lane speed name: SDR, DDR, QDR, FDR, EDR, HDR, NDR, XDR
+    pub speed: Option<String>,
+}
+
+impl LinkRate {
+    pub fn parse(s: &str) -> Option<Self> {
+        let (number, rest) = s.trim().split_once(' ')?;
+        let gbps = number.parse().ok()?;
+        let detail = rest
+            .split_once('(')
+            .map(|(_, d)| d.trim_end_matches(')'))
+            .unwrap_or("");
+        let mut parts = detail.split_whitespace();
+        let width = parts
+            .next()
+            .and_then(|w| w.strip_suffix('X'))
+            .and_then(|n| n.parse().ok());
+        Some(Self {
+            gbps,
+            width,
+            speed: parts.next().map(str::to_string),
+        })
+    }
+
+    pub fn bytes_per_sec(&self) -> f64 {
+        self.gbps * 1e9 / 8.0
+    }
+}
+
+impl fmt::Display for LinkRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} Gb/s", self.gbps)?;
+        if let Some(width) = self.width {
+            write!(f, " {}X", width)?;
+        }
+        if let Some(speed) = &self.speed {
+            write!(f, " {}", speed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Per-second rates over the last sampling interval
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PortRates {
+    pub tx_bytes_per_sec: f64,
+    pub rx_bytes_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+    pub rx_packets_per_sec: f64,
+    /// Increments of all [`ERROR_COUNTERS`] per second
+    pub errors_per_sec: f64,
+    /// Ticks with data queued but no flow-control credits to send it
+    pub xmit_wait_per_sec: f64,
+    /// RoCE packets received with ECN congestion marks (`np_ecn_marked_roce_packets`)
+    pub ecn_marked_per_sec: f64,
+    /// Congestion notification packets sent to senders (`np_cnp_sent`)
+    pub cnp_sent_per_sec: f64,
+    /// Congestion notification packets acted on by this sender (`rp_cnp_handled`)
+    pub cnp_handled_per_sec: f64,
+    /// Receive WQE exhaustion drops (`out_of_buffer`)
+    pub out_of_buffer_per_sec: f64,
+    /// Busier direction as a percent of the link rate
+    pub utilization_pct: Option<f64>,
+}
+
+/// One port of an RDMA device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RdmaPort {
+    pub port: u32,
+    pub state: PortState,
+    pub phys_state: PhysState,
+    pub link_layer: LinkLayer,
+    pub rate: Option<LinkRate>,
+    /// Local identifier assigned by the subnet manager (InfiniBand only)
+    pub lid: Option<u16>,
+    pub sm_lid: Option<u16>,
+    /// GID table entry 0
+    pub gid: Option<String>,
+    /// Network interface behind a RoCE port
+    pub netdev: Option<String>,
+    /// Standard IB port counters (`counters/`)
+    pub counters: BTreeMap<String, u64>,
+    /// Driver-specific counters (`hw_counters/`)
+    pub hw_counters: BTreeMap<String, u64>,
+    /// Rates against the previous sample (None on the first)
+    pub rates: Option<PortRates>,
+}
+
+impl RdmaPort {
+    pub fn is_active(&self) -> bool {
+        self.state == PortState::Active
+    }
+
+    /// Bytes transmitted since the counters were last reset
+    pub fn tx_bytes(&self) -> Option<u64> {
+        self.counters.get("port_xmit_data").map(|w| w * 4)
+    }
+
+    /// Bytes received since the counters were last reset
+    pub fn rx_bytes(&self) -> Option<u64> {
+        self.counters.get("port_rcv_data").map(|w| w * 4)
+    }
+
+    /// Sum of all [`ERROR_COUNTERS`]
+    pub fn error_total(&self) -> u64 {
+        ERROR_COUNTERS
+            .iter()
+            .filter_map(|c| self.counters.get(*c))
+            .sum()
+    }
+
+    fn rates_since(&self, prev: &PortReading, secs: f64) -> PortRates {
+        let port = |name: &str| {
+            let cur = self.counters.get(name).copied().unwrap_or(0);
+            let old = prev.counters.get(name).copied().unwrap_or(0);
+            cur.saturating_sub(old) as f64 / secs
+        };
+        let hw = |name: &str| {
+            let cur = self.hw_counters.get(name).copied().unwrap_or(0);
+            let old = prev.hw_counters.get(name).copied().unwrap_or(0);
+            cur.saturating_sub(old) as f64 / secs
+        };
+        let tx_bytes_per_sec = port("port_xmit_data") * 4.0;
+        let rx_bytes_per_sec = port("port_rcv_data") * 4.0;
+        let utilization_pct = self
+            .rate
+            .as_ref()
+            .map(LinkRate::bytes_per_sec)
+            .filter(|b| *b > 0.0)
+            .map(|b| tx_bytes_per_sec.max(rx_bytes_per_sec) / b * 100.0);
+        PortRates {
+            tx_bytes_per_sec,
+            rx_bytes_per_sec,
+            tx_packets_per_sec: port("port_xmit_packets"),
+            rx_packets_per_sec: port("port_rcv_packets"),
+            errors_per_sec: ERROR_COUNTERS.iter().map(|c| port(c)).sum(),
+            xmit_wait_per_sec: port("port_xmit_wait"),
+            ecn_marked_per_sec: hw("np_ecn_marked_roce_packets"),
+            cnp_sent_per_sec: hw("np_cnp_sent"),
+            cnp_handled_per_sec: hw("rp_cnp_handled"),
+            out_of_buffer_per_sec: hw("out_of_buffer"),
+            utilization_pct,
+        }
+    }
+}
+
+/// An RDMA device (HCA or RoCE NIC) and its ports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RdmaDevice {
+    /// Device name, e.g. `mlx5_0`
+    pub name: String,
+    /// Node type, e.g. `CA` for a channel adapter
+    pub node_type: Option<String>,
+    /// Adapter model, e.g. `MT4123`
+    pub hca_type: Option<String>,
+    pub board_id: Option<String>,
+    pub fw_ver: Option<String>,
+    pub node_guid: Option<String>,
+    /// PCIe function behind the device (None for software RDMA such as rxe)
+    pub pci: Option<PcieDevice>,
+    /// NUMA node of the PCIe function
+    pub numa_node: Option<u32>,
+    /// CPUs local to that NUMA node
+    pub local_cpus: Vec<u32>,
+    pub ports: Vec<RdmaPort>,
+}
+
+impl RdmaDevice {
+    pub fn port(&self, port: u32) -> Option<&RdmaPort> {
+        self.ports.iter().find(|p| p.port == port)
+    }
+}
+
+/// What an [`RdmaAlert`] is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RdmaAlertKind {
+    /// Port left `ACTIVE`
+    PortDown,
+    /// `link_downed` increased: the link dropped and retrained
+    LinkFlap,
+    /// Physical-layer or receive error counters increased
+    LinkErrors,
+}
+
+impl fmt::Display for RdmaAlertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PortDown => write!(f, "port_down"),
+            Self::LinkFlap => write!(f, "link_flap"),
+            Self::LinkErrors => write!(f, "link_errors"),
+        }
+    }
+}
+
+/// A fault found in the latest interval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RdmaAlert {
+    pub device: String,
+    pub port: u32,
+    pub kind: RdmaAlertKind,
+    pub message: String,
+    /// Counter increments that triggered the alert
+    pub deltas: BTreeMap<String, u64>,
+}
+
+impl RdmaAlert {
+    pub fn to_system_event(&self) -> SystemEvent {
+        let source = format!("rdma:{}/{}", self.device, self.port);
+        let mut event = match self.kind {
+            RdmaAlertKind::PortDown => SystemEvent::critical(
+                EventCategory::Network,
+                event_types::network::LINK_DOWN,
+                &self.message,
+                &source,
+            ),
+            RdmaAlertKind::LinkFlap => SystemEvent::warning(
+                EventCategory::Network,
+                event_types::network::LINK_DOWN,
+                &self.message,
+                &source,
+            ),
+            RdmaAlertKind::LinkErrors => SystemEvent::warning(
+                EventCategory::Network,
+                event_types::network::HIGH_ERRORS,
+                &self.message,
+                &source,
+            ),
+        }
+        .with_metadata("kind", self.kind.to_string());
+        for (counter, delta) in &self.deltas {
+            event = event.with_metadata(counter, *delta);
+        }
+        event
+    }
+}
+
+/// State of every RDMA port
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RdmaSnapshot {
+    /// Unix time of the sample
+    pub timestamp: u64,
+    pub interval_secs: f64,
+    pub devices: Vec<RdmaDevice>,
+    pub alerts: Vec<RdmaAlert>,
+}
+
+impl RdmaSnapshot {
+    pub fn device(&self, name: &str) -> Option<&RdmaDevice> {
+        self.devices.iter().find(|d| d.name == name)
+    }
+}
+
+/// Monitor configuration (`[rdma]` in the daemon config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RdmaConfig {
+    pub enabled: bool,
+    /// Sampling interval in milliseconds
+    pub interval_ms: u64,
+    /// Devices to watch (empty = all)
+    pub devices: Vec<String>,
+    /// Error counter increments per interval that raise [`RdmaAlertKind::LinkErrors`]
+    pub error_threshold: u64,
+    /// Minimum seconds between events for the same port and condition
+    pub event_cooldown_secs: u64,
+}
+
+impl Default for RdmaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 5000,
+            devices: Vec::new(),
+            error_threshold: 1,
+            event_cooldown_secs: 300,
+        }
+    }
+}
+
+/// Counters and state kept from the previous sample
+struct PortReading {
+    state: PortState,
+    counters: BTreeMap<String, u64>,
+    hw_counters: BTreeMap<String, u64>,
+}
+
+/// Samples `/sys/class/infiniband`, keeping the previous counters per port
+pub struct RdmaMonitor {
+    root: PathBuf,
+    config: RdmaConfig,
+    last: HashMap<(String, u32), PortReading>,
+    last_at: Option<Instant>,
+}
+
+impl RdmaMonitor {
+    /// Monitor the live system
+    pub fn new(config: RdmaConfig) -> Result<Self> {
+        if !cfg!(target_os = "linux") {
+            return Err(SimonError::UnsupportedPlatform(
+                "RDMA monitoring requires Linux sysfs".into(),
+            ));
+        }
+        Self::with_root("/", config)
+    }
+
+    /// Monitor a filesystem tree rooted at `root` (for tests)
+    pub fn with_root(root: impl Into<PathBuf>, config: RdmaConfig) -> Result<Self> {
+        let root = root.into();
+        if !root.join("sys/class/infiniband").is_dir() {
+            return Err(SimonError::FeatureNotAvailable(
+                "RDMA (no /sys/class/infiniband; ib_core not loaded)".into(),
+            ));
+        }
+        Ok(Self {
+            root,
+            config,
+            last: HashMap::new(),
+            last_at: None,
+        })
+    }
+
+    /// Current state and counters without rates
+    pub fn devices(&self) -> Vec<RdmaDevice> {
+        let mut names: Vec<String> = std::fs::read_dir(self.root.join("sys/class/infiniband"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|n| self.config.devices.is_empty() || self.config.devices.contains(n))
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names.iter().map(|n| self.read_device(n)).collect()
+    }
+
+    /// Sample all ports, returning rates and alerts against the previous call
+    pub fn sample(&mut self) -> RdmaSnapshot {
+        let now = Instant::now();
+        let elapsed = self.last_at.map(|t| now.duration_since(t).as_secs_f64());
+        self.last_at = Some(now);
+        self.sample_with_elapsed(elapsed)
+    }
+
+    fn sample_with_elapsed(&mut self, elapsed: Option<f64>) -> RdmaSnapshot {
+        let mut devices = self.devices();
+        let mut alerts = Vec::new();
+        let mut current = HashMap::new();
+        for dev in &mut devices {
+            for port in &mut dev.ports {
+                let key = (dev.name.clone(), port.port);
+                if let Some(prev) = self.last.get(&key) {
+                    if let Some(secs) = elapsed.filter(|s| *s > 0.0) {
+                        port.rates = Some(port.rates_since(prev, secs));
+                    }
+                    alerts.extend(self.check(&dev.name, port, prev));
+                }
+                current.insert(
+                    key,
+                    PortReading {
+                        state: port.state,
+                        counters: port.counters.clone(),
+                        hw_counters: port.hw_counters.clone(),
+                    },
+                );
+            }
+        }
+        self.last = current;
+
+        RdmaSnapshot {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            interval_secs: elapsed.unwrap_or(0.0),
+            devices,
+            alerts,
+        }
+    }
+
+    fn check(&self, device: &str, port: &RdmaPort, prev: &PortReading) -> Vec<RdmaAlert> {
+        let mut alerts = Vec::new();
+        let alert = |kind, message: String, deltas| RdmaAlert {
+            device: device.to_string(),
+            port: port.port,
+            kind,
+            message,
+            deltas,
+        };
+        if prev.state == PortState::Active && !port.is_active() {
+            alerts.push(alert(
+                RdmaAlertKind::PortDown,
+                format!(
+                    "{} port {} went {} (phys {})",
+                    device, port.port, port.state, port.phys_state
+                ),
+                BTreeMap::new(),
+            ));
+        }
+
+        // Counters that went backwards were reset; skip rather than alert
+        let deltas: BTreeMap<String, u64> = ERROR_COUNTERS
+            .iter()
+            .filter_map(|c| {
+                let cur = *port.counters.get(*c)?;
+                let old = *prev.counters.get(*c)?;
+                (cur > old).then(|| (c.to_string(), cur - old))
+            })
+            .collect();
+        if let Some(flaps) = deltas.get("link_downed") {
+            alerts.push(alert(
+                RdmaAlertKind::LinkFlap,
+                format!(
+                    "{} port {} link went down {} time(s)",
+                    device, port.port, flaps
+                ),
+                BTreeMap::from([("link_downed".to_string(), *flaps)]),
+            ));
+        }
+        let errors: BTreeMap<String, u64> = deltas
+            .into_iter()
+            .filter(|(c, _)| c != "link_downed")
+            .collect();
+        let total: u64 = errors.values().sum();
+        if total > 0 && total >= self.config.error_threshold {
+            let detail = errors
+                .iter()
+                .map(|(c, d)| format!("{} +{}", c, d))
+                .collect::<Vec<_>>()
+                .join(", ");
+            alerts.push(alert(
+                RdmaAlertKind::LinkErrors,
+                format!("{} port {} errors: {}", device, port.port, detail),
+                errors,
+            ));
+        }
+        alerts
+    }
+
+    fn read_device(&self, name: &str) -> RdmaDevice {
+        let dir = self.root.join("sys/class/infiniband").join(name);
+        let pci = read_pci(&dir.join("device"));
+        let numa_node = pci.as_ref().and_then(|p| u32::try_from(p.numa_node).ok());
+        let local_cpus = numa_node
+            .and_then(|n| {
+                read_trimmed(
+                    &self
+                        .root
+                        .join(format!("sys/devices/system/node/node{}/cpulist", n)),
+                )
+            })
+            .map(|list| parse_cpu_list(&list))
+            .unwrap_or_default();
+
+        let mut ports: Vec<RdmaPort> = std::fs::read_dir(dir.join("ports"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| {
+                        let port = e.file_name().to_string_lossy().parse().ok()?;
+                        Some(read_port(&e.path(), port))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        ports.sort_by_key(|p| p.port);
+
+        RdmaDevice {
+            name: name.to_string(),
+            node_type: read_trimmed(&dir.join("node_type")).map(|t| {
+                t.split_once(": ")
+                    .map_or(t.as_str(), |(_, n)| n)
+                    .to_string()
+            }),
+            hca_type: read_trimmed(&dir.join("hca_type")),
+            board_id: read_trimmed(&dir.join("board_id")),
+            fw_ver: read_trimmed(&dir.join("fw_ver")),
+            node_guid: read_trimmed(&dir.join("node_guid")),
+            pci,
+            numa_node,
+            local_cpus,
+            ports,
+        }
+    }
+}
+
+fn read_port(dir: &Path, port: u32) -> RdmaPort {
+    let lid = |file: &str| {
+        read_trimmed(&dir.join(file))
+            .and_then(|v| u16::from_str_radix(v.trim_start_matches("0x"), 16).ok())
+            .filter(|l| *l != 0)
+    };
+    let gid = read_trimmed(&dir.join("gids/0")).filter(|g| g.chars().any(|c| c != '0' && c != ':'));
+    RdmaPort {
+        port,
+        state: PortState::from_sysfs(&read_trimmed(&dir.join("state")).unwrap_or_default()),
+        phys_state: PhysState::from_sysfs(
+            &read_trimmed(&dir.join("phys_state")).unwrap_or_default(),
+        ),
+        link_layer: LinkLayer::from_sysfs(
+            &read_trimmed(&dir.join("link_layer")).unwrap_or_default(),
+        ),
+        rate: read_trimmed(&dir.join("rate")).and_then(|r| LinkRate::parse(&r)),
+        lid: lid("lid"),
+        sm_lid: lid("sm_lid"),
+        gid,
+        netdev: read_trimmed(&dir.join("gid_attrs/ndevs/0")).filter(|n| !n.is_empty()),
+        counters: read_counters(&dir.join("counters")),
+        hw_counters: read_counters(&dir.join("hw_counters")),
+        rates: None,
+    }
+}
+
+/// Every numeric file in a counter directory (`lifespan` is a setting, not a counter)
+fn read_counters(dir: &Path) -> BTreeMap<String, u64> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return BTreeMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            if name == "lifespan" {
+                return None;
+            }
+            let value = read_trimmed(&e.path())?.parse().ok()?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// PCIe function at a device's `device` link (None for software RDMA)
+#[cfg(target_os = "linux")]
+fn read_pci(dir: &Path) -> Option<PcieDevice> {
+    dir.join("vendor")
+        .exists()
+        .then(|| PcieDevice::from_sysfs(dir))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_pci(_dir: &Path) -> Option<PcieDevice> {
+    None
+}
+
+/// Kernel CPU list such as `0-15,32-47`
+fn parse_cpu_list(s: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in s.split(',').map(str::trim) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<u32>(), end.parse::<u32>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => cpus.extend(part.parse::<u32>().ok()),
+        }
+    }
+    cpus
+}
+
+fn leading_number(s: &str) -> Option<u32> {
+    s.trim().split(':').next()?.trim().parse().ok()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// Device counters, rates and alerts from the last poll
+pub type SharedRdmaSnapshot = Arc<RwLock<Option<RdmaSnapshot>>>;
+
+/// Start sampling on a background thread, publishing port-down, link-flap and
+/// error-counter alerts to `events` at most once per cooldown for each port
+/// and condition
+pub fn spawn(config: RdmaConfig, events: Arc<EventManager>) -> Result<RdmaHandle> {
+    let interval = Duration::from_millis(config.interval_ms.max(500));
+    let cooldown = Duration::from_secs(config.event_cooldown_secs);
+    let mut monitor = RdmaMonitor::new(config)?;
+    let snapshot: SharedRdmaSnapshot = Arc::new(RwLock::new(Some(monitor.sample())));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let shared = Arc::clone(&snapshot);
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-rdma".into())
+        .spawn(move || {
+            let mut last_event: HashMap<(String, u32, RdmaAlertKind), Instant> = HashMap::new();
+            while !flag.load(Ordering::SeqCst) {
+                let deadline = Instant::now() + interval;
+                while !flag.load(Ordering::SeqCst) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                if flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let sample = monitor.sample();
+                for alert in &sample.alerts {
+                    let key = (alert.device.clone(), alert.port, alert.kind);
+                    if last_event.get(&key).is_some_and(|t| t.elapsed() < cooldown) {
+                        continue;
+                    }
+                    events.emit(alert.to_system_event());
+                    last_event.insert(key, Instant::now());
+                }
+                if let Ok(mut slot) = shared.write() {
+                    *slot = Some(sample);
+                }
+            }
+        })
+        .map_err(|e| SimonError::Other(format!("Failed to spawn RDMA monitor thread: {}", e)))?;
+
+    Ok(RdmaHandle {
+        snapshot,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+/// Handle to a running RDMA monitor thread
+pub struct RdmaHandle {
+    snapshot: SharedRdmaSnapshot,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RdmaHandle {
+    /// Shared snapshot slot for readers
+    pub fn snapshot(&self) -> SharedRdmaSnapshot {
+        Arc::clone(&self.snapshot)
+    }
+
+    /// Stop monitoring and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for RdmaHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    /// ConnectX-6 at 0000:3b:00.0 on NUMA node 1 with one HDR port
+    fn mlx5(root: &FakeRoot) {
+        let pci = "sys/devices/pci0000:3a/0000:3a:00.0/0000:3b:00.0";
+        root.write(&format!("{}/vendor", pci), "0x15b3\n");
+        root.write(&format!("{}/device", pci), "0x101b\n");
+        root.write(&format!("{}/class", pci), "0x020700\n");
+        root.write(&format!("{}/numa_node", pci), "1\n");
+        root.write(&format!("{}/current_link_speed", pci), "16.0 GT/s PCIe\n");
+        root.write(&format!("{}/current_link_width", pci), "16\n");
+        root.write("sys/devices/system/node/node1/cpulist", "16-31,48-63\n");
+
+        let dev = "sys/class/infiniband/mlx5_0";
+        root.write(&format!("{}/node_type", dev), "1: CA\n");
+        root.write(&format!("{}/hca_type", dev), "MT4123\n");
+        root.write(&format!("{}/fw_ver", dev), "20.31.1014\n");
+        root.symlink(pci, &format!("{}/device", dev));
+        port(root, "mlx5_0", 1, "4: ACTIVE", "5: LinkUp", &[]);
+        root.write(
+            "sys/class/infiniband/mlx5_0/ports/1/rate",
+            "200 Gb/sec (4X HDR)\n",
+        );
+        root.write("sys/class/infiniband/mlx5_0/ports/1/lid", "0x1a\n");
+        root.write("sys/class/infiniband/mlx5_0/ports/1/sm_lid", "0x1\n");
+        root.write(
+            "sys/class/infiniband/mlx5_0/ports/1/link_layer",
+            "InfiniBand\n",
+        );
+        root.write(
+            "sys/class/infiniband/mlx5_0/ports/1/gids/0",
+            "fe80:0000:0000:0000:0c42:a103:0065:1234\n",
+        );
+        root.write(
+            "sys/class/infiniband/mlx5_0/ports/1/hw_counters/lifespan",
+            "10\n",
+        );
+    }
+
+    fn port(
+        root: &FakeRoot,
+        dev: &str,
+        port: u32,
+        state: &str,
+        phys: &str,
+        counters: &[(&str, u64)],
+    ) {
+        let dir = format!("sys/class/infiniband/{}/ports/{}", dev, port);
+        root.write(&format!("{}/state", dir), &format!("{}\n", state));
+        root.write(&format!("{}/phys_state", dir), &format!("{}\n", phys));
+        for (name, value) in counters {
+            let sub = if name.starts_with("np_") || name.starts_with("rp_") {
+                "hw_counters"
+            } else {
+                "counters"
+            };
+            root.write(
+                &format!("{}/{}/{}", dir, sub, name),
+                &format!("{}\n", value),
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_sysfs_values() {
+        assert_eq!(PortState::from_sysfs("4: ACTIVE\n"), PortState::Active);
+        assert_eq!(PortState::from_sysfs("1: DOWN"), PortState::Down);
+        assert_eq!(PhysState::from_sysfs("5: LinkUp"), PhysState::LinkUp);
+        assert_eq!(PhysState::from_sysfs("garbage"), PhysState::Unknown);
+        assert_eq!(LinkLayer::from_sysfs("Ethernet\n"), LinkLayer::Ethernet);
+
+        let rate = LinkRate::parse("200 Gb/sec (4X HDR)").unwrap();
+        assert_eq!(rate.gbps, 200.0);
+        assert_eq!(rate.width, Some(4));
+        assert_eq!(rate.speed.as_deref(), Some("HDR"));
+        assert_eq!(rate.to_string(), "200 Gb/s 4X HDR");
+        let rate = LinkRate::parse("2.5 Gb/sec (1X SDR)").unwrap();
+        assert_eq!(rate.bytes_per_sec(), 312_500_000.0);
+        assert_eq!(LinkRate::parse("10 Gb/sec (4X)").unwrap().speed, None);
+        assert!(LinkRate::parse("invalid").is_none());
+
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_read_device_with_pci_and_numa() {
+        let root = FakeRoot::new("rdma-device");
+        mlx5(&root);
+        port(
+            &root,
+            "mlx5_0",
+            1,
+            "4: ACTIVE",
+            "5: LinkUp",
+            &[("symbol_error", 0)],
+        );
+        let monitor = RdmaMonitor::with_root(root.path(), RdmaConfig::default()).unwrap();
+        let devices = monitor.devices();
+        assert_eq!(devices.len(), 1);
+        let dev = &devices[0];
+        assert_eq!(dev.node_type.as_deref(), Some("CA"));
+        assert_eq!(dev.hca_type.as_deref(), Some("MT4123"));
+        let pci = dev.pci.as_ref().unwrap();
+        assert_eq!(pci.bdf, "0000:3b:00.0");
+        assert_eq!(pci.vendor_id, 0x15b3);
+        assert_eq!(pci.current_link_width, 16);
+        assert_eq!(dev.numa_node, Some(1));
+        assert_eq!(dev.local_cpus.len(), 32);
+        assert_eq!(dev.local_cpus[16], 48);
+
+        let port = dev.port(1).unwrap();
+        assert!(port.is_active());
+        assert_eq!(port.link_layer, LinkLayer::InfiniBand);
+        assert_eq!(port.lid, Some(0x1a));
+        assert_eq!(port.sm_lid, Some(1));
+        assert_eq!(
+            port.gid.as_deref(),
+            Some("fe80:0000:0000:0000:0c42:a103:0065:1234")
+        );
+        assert_eq!(port.counters.get("symbol_error"), Some(&0));
+        assert!(port.hw_counters.is_empty());
+        assert!(port.rates.is_none());
+    }
+
+    #[test]
+    fn test_rates_from_counter_deltas() {
+        let root = FakeRoot::new("rdma-rates");
+        mlx5(&root);
+        let counters = |words: u64, ecn: u64| {
+            [
+                ("port_xmit_data", words),
+                ("port_rcv_data", words / 2),
+                ("port_xmit_packets", words / 1024),
+                ("port_xmit_wait", ecn * 10),
+                ("np_ecn_marked_roce_packets", ecn),
+                ("rp_cnp_handled", ecn / 2),
+            ]
+        };
+        port(
+            &root,
+            "mlx5_0",
+            1,
+            "4: ACTIVE",
+            "5: LinkUp",
+            &counters(0, 0),
+        );
+        let mut monitor = RdmaMonitor::with_root(root.path(), RdmaConfig::default()).unwrap();
+        assert!(monitor.sample_with_elapsed(None).devices[0].ports[0]
+            .rates
+            .is_none());
+
+        // 2.5 GB/s transmitted over 2 s = 5 GB/s of a 25 GB/s link
+        port(
+            &root,
+            "mlx5_0",
+            1,
+            "4: ACTIVE",
+            "5: LinkUp",
+            &counters(2_500_000_000, 1000),
+        );
+        let snapshot = monitor.sample_with_elapsed(Some(2.0));
+        let port = &snapshot.devices[0].ports[0];
+        assert_eq!(port.tx_bytes(), Some(10_000_000_000));
+        let rates = port.rates.as_ref().unwrap();
+        assert_eq!(rates.tx_bytes_per_sec, 5e9);
+        assert_eq!(rates.rx_bytes_per_sec, 2.5e9);
+        assert_eq!(rates.utilization_pct, Some(20.0));
+        assert_eq!(rates.xmit_wait_per_sec, 5000.0);
+        assert_eq!(rates.ecn_marked_per_sec, 500.0);
+        assert_eq!(rates.cnp_handled_per_sec, 250.0);
+        assert_eq!(rates.errors_per_sec, 0.0);
+        assert!(snapshot.alerts.is_empty());
+    }
+
+    #[test]
+    fn test_alerts_for_port_down_flaps_and_errors() {
+        let root = FakeRoot::new("rdma-alerts");
+        mlx5(&root);
+        let errors = |n: u64| {
+            [
+                ("symbol_error", n * 3),
+                ("port_rcv_errors", n),
+                ("link_downed", n),
+            ]
+        };
+        port(&root, "mlx5_0", 1, "4: ACTIVE", "5: LinkUp", &errors(0));
+        let mut monitor = RdmaMonitor::with_root(root.path(), RdmaConfig::default()).unwrap();
+        monitor.sample_with_elapsed(None);
+
+        port(&root, "mlx5_0", 1, "1: DOWN", "2: Polling", &errors(1));
+        let alerts = monitor.sample_with_elapsed(Some(1.0)).alerts;
+        let kinds: Vec<RdmaAlertKind> = alerts.iter().map(|a| a.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                RdmaAlertKind::PortDown,
+                RdmaAlertKind::LinkFlap,
+                RdmaAlertKind::LinkErrors
+            ]
+        );
+        assert_eq!(alerts[0].message, "mlx5_0 port 1 went DOWN (phys Polling)");
+        assert_eq!(alerts[2].deltas.get("symbol_error"), Some(&3));
+        assert!(!alerts[2].deltas.contains_key("link_downed"));
+        let event = alerts[0].to_system_event();
+        assert_eq!(event.event_type, event_types::network::LINK_DOWN);
+        assert_eq!(event.source, "rdma:mlx5_0/1");
+
+        // Still down and counters reset: nothing new to report
+        port(&root, "mlx5_0", 1, "1: DOWN", "2: Polling", &errors(0));
+        assert!(monitor.sample_with_elapsed(Some(1.0)).alerts.is_empty());
+    }
+
+    #[test]
+    fn test_device_filter_and_missing_sysfs() {
+        let root = FakeRoot::new("rdma-filter");
+        mlx5(&root);
+        root.write("sys/class/infiniband/rxe0/node_type", "1: CA\n");
+        port(&root, "rxe0", 1, "4: ACTIVE", "5: LinkUp", &[]);
+        root.write("sys/class/infiniband/rxe0/ports/1/link_layer", "Ethernet\n");
+        root.write(
+            "sys/class/infiniband/rxe0/ports/1/gid_attrs/ndevs/0",
+            "eth0\n",
+        );
+        root.write(
+            "sys/class/infiniband/rxe0/ports/1/gids/0",
+            "0000:0000:0000:0000:0000:0000:0000:0000\n",
+        );
+
+        let monitor = RdmaMonitor::with_root(root.path(), RdmaConfig::default()).unwrap();
+        let names: Vec<String> = monitor.devices().into_iter().map(|d| d.name).collect();
+        assert_eq!(names, vec!["mlx5_0", "rxe0"]);
+
+        let config = RdmaConfig {
+            devices: vec!["rxe0".into()],
+            ..Default::default()
+        };
+        let monitor = RdmaMonitor::with_root(root.path(), config).unwrap();
+        let devices = monitor.devices();
+        assert_eq!(devices.len(), 1);
+        let rxe = &devices[0];
+        assert!(rxe.pci.is_none());
+        assert_eq!(rxe.numa_node, None);
+        let port = rxe.port(1).unwrap();
+        assert_eq!(port.link_layer.to_string(), "RoCE");
+        assert_eq!(port.netdev.as_deref(), Some("eth0"));
+        assert_eq!(port.gid, None);
+        assert_eq!(port.lid, None);
+
+        let empty = FakeRoot::new("rdma-none");
+        assert!(matches!(
+            RdmaMonitor::with_root(empty.path(), RdmaConfig::default()),
+            Err(SimonError::FeatureNotAvailable(_))
+        ));
+    }
+}
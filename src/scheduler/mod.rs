@@ -266,69 +266,23 @@ impl SchedulerMonitor {
 
     #[cfg(target_os = "linux")]
     fn read_psi() -> Vec<PressureInfo> {
-        let mut pressures = Vec::new();
-        for resource in &["cpu", "memory", "io"] {
-            let path = format!("/proc/pressure/{}", resource);
-            if let Ok(content) = std::fs::read_to_string(&path) {
-                let mut some_avg10 = 0.0;
-                let mut some_avg60 = 0.0;
-                let mut some_avg300 = 0.0;
-                let mut some_total = 0u64;
-                let mut full_avg10 = None;
-                let mut full_avg60 = None;
-                let mut full_avg300 = None;
-                let mut full_total = None;
-
-                for line in content.lines() {
-                    let is_full = line.starts_with("full");
-                    let is_some = line.starts_with("some");
-                    if !is_full && !is_some {
-                        continue;
-                    }
-
-                    for part in line.split_whitespace().skip(1) {
-                        if let Some((key, val)) = part.split_once('=') {
-                            match key {
-                                "avg10" => {
-                                    if let Ok(v) = val.parse::<f64>() {
-                                        if is_full { full_avg10 = Some(v); } else { some_avg10 = v; }
-                                    }
-                                }
-                                "avg60" => {
-                                    if let Ok(v) = val.parse::<f64>() {
-                                        if is_full { full_avg60 = Some(v); } else { some_avg60 = v; }
-                                    }
-                                }
-                                "avg300" => {
-                                    if let Ok(v) = val.parse::<f64>() {
-                                        if is_full { full_avg300 = Some(v); } else { some_avg300 = v; }
-                                    }
-                                }
-                                "total" => {
-                                    if let Ok(v) = val.parse::<u64>() {
-                                        if is_full { full_total = Some(v); } else { some_total = v; }
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-
-                pressures.push(PressureInfo {
-                    resource: resource.to_string(),
-                    some_avg10,
-                    some_avg60,
-                    some_avg300,
-                    some_total_us: some_total,
-                    full_avg10,
-                    full_avg60,
-                    full_avg300,
-                    full_total_us: full_total,
-                });
-            }
-        }
-        pressures
+        use crate::psi::PsiResource;
+        crate::psi::read_system(std::path::Path::new("/"))
+            .resources
+            .into_iter()
+            .filter(|p| p.resource != PsiResource::Irq)
+            .map(|p| PressureInfo {
+                resource: p.resource.to_string(),
+                some_avg10: p.some.avg10,
+                some_avg60: p.some.avg60,
+                some_avg300: p.some.avg300,
+                some_total_us: p.some.total_us,
+                full_avg10: p.full.map(|f| f.avg10),
+                full_avg60: p.full.map(|f| f.avg60),
+                full_avg300: p.full.map(|f| f.avg300),
+                full_total_us: p.full.map(|f| f.total_us),
+            })
+            .collect()
     }
 
     #[cfg(not(target_os = "linux"))]
@@ -2,7 +2,8 @@
 //!
 //! # Platform Support
 //!
-//! - **Linux**: Reads `/sys/block/*/device/`, `/sys/class/nvme/*/`, `smartctl` output
+//! - **Linux**: Reads `/sys/block/*/device/`, `/sys/class/nvme/*/`, native NVMe log pages
+//!   ([`nvme`]), `smartctl` output
 //! - **Windows**: Uses WMI (`MSFT_PhysicalDisk`, `MSFT_StorageReliabilityCounter`)
 //! - **macOS**: Uses `smartmontools` or `diskutil info`
 //!
@@ -21,8 +22,11 @@
 //! }
 //! ```
 
+pub mod nvme;
+
 use serde::{Deserialize, Serialize};
 use crate::error::SimonError;
+use std::collections::HashMap;
 
 /// Overall disk health assessment
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -118,17 +122,23 @@ pub struct SmartDiskInfo {
 /// Monitor for S.M.A.R.T. disk health
 pub struct SmartMonitor {
     disks: Vec<SmartDiskInfo>,
+    /// Raw NVMe SMART logs read via passthrough, keyed by device path
+    nvme_logs: HashMap<String, nvme::NvmeSmartLog>,
 }
 
 impl SmartMonitor {
     pub fn new() -> Result<Self, SimonError> {
-        let mut monitor = Self { disks: Vec::new() };
+        let mut monitor = Self {
+            disks: Vec::new(),
+            nvme_logs: HashMap::new(),
+        };
         monitor.refresh()?;
         Ok(monitor)
     }
 
     pub fn refresh(&mut self) -> Result<(), SimonError> {
         self.disks.clear();
+        self.nvme_logs.clear();
 
         #[cfg(target_os = "linux")]
         self.refresh_linux();
@@ -142,6 +152,9 @@ impl SmartMonitor {
         // Run inference on all collected disks
         for disk in &mut self.disks {
             Self::infer_health(disk);
+            if let Some(log) = self.nvme_logs.get(&disk.device) {
+                Self::apply_critical_warning(disk, log.critical_warning);
+            }
         }
 
         Ok(())
@@ -159,11 +172,28 @@ impl SmartMonitor {
             .collect()
     }
 
+    /// NVMe SMART/Health log read natively for `device`, including fields the
+    /// generic [`SmartDiskInfo`] lacks (critical warnings, unsafe shutdowns)
+    pub fn nvme_smart_log(&self, device: &str) -> Option<&nvme::NvmeSmartLog> {
+        self.nvme_logs.get(device)
+    }
+
     /// Get the hottest disk temperature.
     pub fn max_temperature(&self) -> u32 {
         self.disks.iter().map(|d| d.temperature_celsius).max().unwrap_or(0)
     }
 
+    /// The controller's own critical warning overrides the inferred score: a
+    /// read-only or degraded device has failed, any other warning is critical.
+    fn apply_critical_warning(disk: &mut SmartDiskInfo, warning: nvme::CriticalWarning) {
+        use nvme::CriticalWarning as W;
+        if warning.contains(W::READ_ONLY) || warning.contains(W::RELIABILITY_DEGRADED) {
+            disk.health = DiskHealth::Failed;
+        } else if warning.any() && disk.health != DiskHealth::Failed {
+            disk.health = DiskHealth::Critical;
+        }
+    }
+
     /// Infer health status and remaining life from raw attributes.
     fn infer_health(disk: &mut SmartDiskInfo) {
         let mut score: f32 = 100.0;
@@ -313,8 +343,12 @@ impl SmartMonitor {
                         estimated_days_remaining: None,
                     };
 
-                    // Try nvme smart-log
-                    if let Ok(output) = std::process::Command::new("nvme")
+                    // Native passthrough first, nvme-cli for unprivileged users
+                    let native = nvme::NvmeDevice::open(&disk.device).and_then(|d| d.smart_log());
+                    if let Ok(log) = native {
+                        log.apply_to(&mut disk);
+                        self.nvme_logs.insert(disk.device.clone(), log);
+                    } else if let Ok(output) = std::process::Command::new("nvme")
                         .args(["smart-log", &format!("/dev/{}n1", ctrl_name), "-o", "json"])
                         .output()
                     {
@@ -673,7 +707,10 @@ Get-PhysicalDisk | ForEach-Object {
 
 impl Default for SmartMonitor {
     fn default() -> Self {
-        Self::new().unwrap_or(Self { disks: Vec::new() })
+        Self::new().unwrap_or(Self {
+            disks: Vec::new(),
+            nvme_logs: HashMap::new(),
+        })
     }
 }
 
@@ -766,6 +803,40 @@ mod tests {
         assert!(matches!(disk.health, DiskHealth::Critical | DiskHealth::Failed));
     }
 
+    #[test]
+    fn test_nvme_critical_warning_overrides_score() {
+        use nvme::CriticalWarning;
+        let mut disk = SmartDiskInfo {
+            device: "/dev/nvme0".into(),
+            model: "Test NVMe".into(),
+            serial: "N1".into(),
+            firmware: "1.0".into(),
+            media_type: DriveMediaType::NVMe,
+            capacity_bytes: 1_000_000_000_000,
+            health: DiskHealth::Unknown,
+            temperature_celsius: 40,
+            power_on_hours: 1000,
+            power_cycle_count: 10,
+            reallocated_sectors: 0,
+            pending_sectors: 0,
+            uncorrectable_errors: 0,
+            wear_leveling_percent: None,
+            total_bytes_written: 0,
+            total_bytes_read: 0,
+            nvme_percentage_used: Some(2),
+            nvme_available_spare: Some(100),
+            attributes: Vec::new(),
+            estimated_life_remaining: None,
+            estimated_days_remaining: None,
+        };
+        SmartMonitor::infer_health(&mut disk);
+        assert_eq!(disk.health, DiskHealth::Good);
+        SmartMonitor::apply_critical_warning(&mut disk, CriticalWarning(CriticalWarning::TEMPERATURE));
+        assert_eq!(disk.health, DiskHealth::Critical);
+        SmartMonitor::apply_critical_warning(&mut disk, CriticalWarning(CriticalWarning::READ_ONLY));
+        assert_eq!(disk.health, DiskHealth::Failed);
+    }
+
     #[test]
     fn test_serialization() {
         let disk = SmartDiskInfo {
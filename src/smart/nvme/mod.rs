@@ -0,0 +1,812 @@
+//! Native NVMe health via admin-command passthrough — no `smartctl` or `nvme-cli`
+//!
+//! Issues Get Log Page, Identify and Device Self-test through
+//! `NVME_IOCTL_ADMIN_CMD` on the controller character device (`/dev/nvme0`)
+//! and decodes the returned pages:
+//!
+//! | Log page | LID  | Decoder                 |
+//! |----------|------|-------------------------|
+//! | Error    | 0x01 | [`NvmeErrorEntry`]      |
+//! | SMART    | 0x02 | [`NvmeSmartLog`]        |
+//! | Firmware | 0x03 | [`NvmeFirmwareLog`]     |
+//! | Self-test| 0x06 | [`NvmeSelfTestLog`]     |
+//!
+//! The decoders are pure functions over byte slices, so captured pages can be
+//! parsed on any platform. Passthrough needs `CAP_SYS_ADMIN` (or root).
+//!
+//! ## Platform Support
+//!
+//! - **Linux**: `NVME_IOCTL_ADMIN_CMD`
+//! - **Other**: decoders only; [`NvmeDevice::open`] returns `UnsupportedPlatform`
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use simonlib::smart::nvme::NvmeDevice;
+//!
+//! let dev = NvmeDevice::open("/dev/nvme0")?;
+//! let log = dev.smart_log()?;
+//! println!(
+//!     "{}°C, {}% used, {} unsafe shutdowns, warnings: {:?}",
+//!     log.temperature_celsius(),
+//!     log.percentage_used,
+//!     log.unsafe_shutdowns,
+//!     log.critical_warning.active()
+//! );
+//! # Ok::<(), simonlib::SimonError>(())
+//! ```
+
+#[cfg(target_os = "linux")]
+mod sys;
+
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Log page identifiers
+pub mod log_id {
+    pub const ERROR: u8 = 0x01;
+    pub const SMART: u8 = 0x02;
+    pub const FIRMWARE_SLOT: u8 = 0x03;
+    pub const SELF_TEST: u8 = 0x06;
+}
+
+const SMART_LOG_LEN: usize = 512;
+const FIRMWARE_LOG_LEN: usize = 512;
+const SELF_TEST_LOG_LEN: usize = 564;
+const ERROR_ENTRY_LEN: usize = 64;
+const SELF_TEST_ENTRY_LEN: usize = 28;
+
+fn le_u16(b: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([b[off], b[off + 1]])
+}
+
+fn le_u32(b: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes(b[off..off + 4].try_into().unwrap())
+}
+
+fn le_u64(b: &[u8], off: usize) -> u64 {
+    u64::from_le_bytes(b[off..off + 8].try_into().unwrap())
+}
+
+/// 128-bit SMART counters, saturated to u64 (2^64 data units is ~9 ZB)
+fn le_u128_sat(b: &[u8], off: usize) -> u64 {
+    let v = u128::from_le_bytes(b[off..off + 16].try_into().unwrap());
+    u64::try_from(v).unwrap_or(u64::MAX)
+}
+
+/// Space-padded ASCII field
+fn ascii(b: &[u8]) -> String {
+    String::from_utf8_lossy(b)
+        .trim_matches(|c: char| c == ' ' || c == '\0')
+        .to_string()
+}
+
+fn need(data: &[u8], len: usize, what: &str) -> Result<()> {
+    if data.len() < len {
+        return Err(SimonError::Parse(format!(
+            "{} is {} bytes, expected {}",
+            what,
+            data.len(),
+            len
+        )));
+    }
+    Ok(())
+}
+
+/// SMART/Health critical warning bits (byte 0 of the SMART log)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CriticalWarning(pub u8);
+
+impl CriticalWarning {
+    pub const SPARE_BELOW_THRESHOLD: u8 = 1 << 0;
+    pub const TEMPERATURE: u8 = 1 << 1;
+    pub const RELIABILITY_DEGRADED: u8 = 1 << 2;
+    pub const READ_ONLY: u8 = 1 << 3;
+    pub const VOLATILE_BACKUP_FAILED: u8 = 1 << 4;
+    pub const PMR_READ_ONLY: u8 = 1 << 5;
+
+    pub fn any(self) -> bool {
+        self.0 != 0
+    }
+
+    pub fn contains(self, bit: u8) -> bool {
+        self.0 & bit != 0
+    }
+
+    /// Names of the set bits
+    pub fn active(self) -> Vec<&'static str> {
+        [
+            (Self::SPARE_BELOW_THRESHOLD, "available_spare"),
+            (Self::TEMPERATURE, "temperature"),
+            (Self::RELIABILITY_DEGRADED, "reliability_degraded"),
+            (Self::READ_ONLY, "read_only"),
+            (Self::VOLATILE_BACKUP_FAILED, "volatile_backup_failed"),
+            (Self::PMR_READ_ONLY, "pmr_read_only"),
+        ]
+        .into_iter()
+        .filter(|(bit, _)| self.contains(*bit))
+        .map(|(_, name)| name)
+        .collect()
+    }
+}
+
+/// SMART / Health Information log page (LID 0x02)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NvmeSmartLog {
+    pub critical_warning: CriticalWarning,
+    /// Composite temperature in Kelvin
+    pub composite_temperature_k: u16,
+    /// Remaining spare capacity, percent
+    pub available_spare: u8,
+    /// Spare percentage at which the spare warning is raised
+    pub available_spare_threshold: u8,
+    /// Vendor estimate of life used, percent (may exceed 100)
+    pub percentage_used: u8,
+    /// Endurance group critical warning summary
+    pub endurance_group_warning: u8,
+    /// Units of 1000 × 512 bytes
+    pub data_units_read: u64,
+    pub data_units_written: u64,
+    pub host_read_commands: u64,
+    pub host_write_commands: u64,
+    /// Minutes the controller was busy with I/O
+    pub controller_busy_minutes: u64,
+    pub power_cycles: u64,
+    pub power_on_hours: u64,
+    pub unsafe_shutdowns: u64,
+    /// Unrecovered data integrity errors
+    pub media_errors: u64,
+    /// Lifetime error log entries
+    pub error_log_entries: u64,
+    /// Minutes above the warning / critical composite temperature thresholds
+    pub warning_temp_minutes: u32,
+    pub critical_temp_minutes: u32,
+    /// Additional sensors in Kelvin (unimplemented sensors omitted)
+    pub temperature_sensors_k: Vec<u16>,
+}
+
+impl NvmeSmartLog {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        need(data, SMART_LOG_LEN, "SMART log")?;
+        Ok(Self {
+            critical_warning: CriticalWarning(data[0]),
+            composite_temperature_k: le_u16(data, 1),
+            available_spare: data[3],
+            available_spare_threshold: data[4],
+            percentage_used: data[5],
+            endurance_group_warning: data[6],
+            data_units_read: le_u128_sat(data, 32),
+            data_units_written: le_u128_sat(data, 48),
+            host_read_commands: le_u128_sat(data, 64),
+            host_write_commands: le_u128_sat(data, 80),
+            controller_busy_minutes: le_u128_sat(data, 96),
+            power_cycles: le_u128_sat(data, 112),
+            power_on_hours: le_u128_sat(data, 128),
+            unsafe_shutdowns: le_u128_sat(data, 144),
+            media_errors: le_u128_sat(data, 160),
+            error_log_entries: le_u128_sat(data, 176),
+            warning_temp_minutes: le_u32(data, 192),
+            critical_temp_minutes: le_u32(data, 196),
+            temperature_sensors_k: (0..8)
+                .map(|i| le_u16(data, 200 + i * 2))
+                .filter(|&k| k != 0)
+                .collect(),
+        })
+    }
+
+    pub fn temperature_celsius(&self) -> i32 {
+        self.composite_temperature_k as i32 - 273
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.data_units_read.saturating_mul(512_000)
+    }
+
+    pub fn bytes_written(&self) -> u64 {
+        self.data_units_written.saturating_mul(512_000)
+    }
+
+    /// Fill the generic SMART fields of a disk from this log
+    pub fn apply_to(&self, disk: &mut super::SmartDiskInfo) {
+        disk.temperature_celsius = self.temperature_celsius().max(0) as u32;
+        disk.power_on_hours = self.power_on_hours;
+        disk.power_cycle_count = self.power_cycles;
+        disk.nvme_percentage_used = Some(self.percentage_used);
+        disk.nvme_available_spare = Some(self.available_spare);
+        disk.uncorrectable_errors = self.media_errors;
+        disk.total_bytes_written = self.bytes_written();
+        disk.total_bytes_read = self.bytes_read();
+    }
+}
+
+/// One Error Information log entry (LID 0x01)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NvmeErrorEntry {
+    /// Unique, incrementing error count (0 marks an empty entry)
+    pub error_count: u64,
+    /// Submission queue (0 = admin queue)
+    pub sqid: u16,
+    pub command_id: u16,
+    /// Status Code Type (0 generic, 1 command specific, 2 media/data integrity, 3 path)
+    pub status_code_type: u8,
+    pub status_code: u8,
+    /// Do Not Retry
+    pub dnr: bool,
+    /// Byte/bit of the command parameter in error (0xFFFF = n/a)
+    pub parameter_error_location: u16,
+    pub lba: u64,
+    pub nsid: u32,
+}
+
+impl NvmeErrorEntry {
+    /// Decode an error log page, newest first, stopping at the first empty entry
+    pub fn parse_log(data: &[u8]) -> Vec<Self> {
+        data.chunks_exact(ERROR_ENTRY_LEN)
+            .map(|e| {
+                // bit 0 is the phase tag; the status field follows
+                let status = le_u16(e, 12) >> 1;
+                Self {
+                    error_count: le_u64(e, 0),
+                    sqid: le_u16(e, 8),
+                    command_id: le_u16(e, 10),
+                    status_code: (status & 0xFF) as u8,
+                    status_code_type: ((status >> 8) & 0x7) as u8,
+                    dnr: status & (1 << 14) != 0,
+                    parameter_error_location: le_u16(e, 14),
+                    lba: le_u64(e, 16),
+                    nsid: le_u32(e, 24),
+                }
+            })
+            .take_while(|e| e.error_count != 0)
+            .collect()
+    }
+
+    /// Human-readable status for common codes
+    pub fn describe(&self) -> String {
+        let known = match (self.status_code_type, self.status_code) {
+            (0, 0x01) => Some("invalid command opcode"),
+            (0, 0x02) => Some("invalid field in command"),
+            (0, 0x04) => Some("data transfer error"),
+            (0, 0x06) => Some("internal error"),
+            (0, 0x07) => Some("command abort requested"),
+            (0, 0x80) => Some("LBA out of range"),
+            (0, 0x81) => Some("capacity exceeded"),
+            (0, 0x82) => Some("namespace not ready"),
+            (1, 0x0B) => Some("invalid log page"),
+            (1, 0x1D) => Some("self-test in progress"),
+            (2, 0x80) => Some("write fault"),
+            (2, 0x81) => Some("unrecovered read error"),
+            (2, 0x82) => Some("end-to-end guard check error"),
+            (2, 0x83) => Some("end-to-end application tag check error"),
+            (2, 0x84) => Some("end-to-end reference tag check error"),
+            (2, 0x85) => Some("compare failure"),
+            (2, 0x86) => Some("access denied"),
+            (2, 0x87) => Some("deallocated or unwritten logical block"),
+            _ => None,
+        };
+        match known {
+            Some(text) => text.to_string(),
+            None => format!(
+                "status type {:#x} code {:#04x}",
+                self.status_code_type, self.status_code
+            ),
+        }
+    }
+
+    /// Media and data integrity errors (SCT 2), as opposed to rejected commands
+    pub fn is_media_error(&self) -> bool {
+        self.status_code_type == 2
+    }
+}
+
+/// Firmware Slot Information log page (LID 0x03)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NvmeFirmwareLog {
+    /// Slot (1-7) the running firmware was loaded from
+    pub active_slot: u8,
+    /// Slot activated at the next controller reset, if pending
+    pub next_slot: Option<u8>,
+    /// Revision in each slot 1-7 (None = empty or unsupported)
+    pub slots: Vec<Option<String>>,
+}
+
+impl NvmeFirmwareLog {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        need(data, FIRMWARE_LOG_LEN, "firmware slot log")?;
+        let afi = data[0];
+        let next = (afi >> 4) & 0x7;
+        Ok(Self {
+            active_slot: afi & 0x7,
+            next_slot: (next != 0).then_some(next),
+            slots: (0..7)
+                .map(|i| {
+                    let rev = &data[8 + i * 8..16 + i * 8];
+                    (rev.iter().any(|&b| b != 0)).then(|| ascii(rev))
+                })
+                .collect(),
+        })
+    }
+
+    /// Revision of the running firmware
+    pub fn active_revision(&self) -> Option<&str> {
+        let idx = (self.active_slot as usize).checked_sub(1)?;
+        self.slots.get(idx)?.as_deref()
+    }
+}
+
+/// Device self-test type (Self-test Code)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestKind {
+    Short,
+    Extended,
+    VendorSpecific,
+    Unknown(u8),
+}
+
+impl SelfTestKind {
+    fn from_code(code: u8) -> Self {
+        match code {
+            0x1 => Self::Short,
+            0x2 => Self::Extended,
+            0xE => Self::VendorSpecific,
+            c => Self::Unknown(c),
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            Self::Short => 0x1,
+            Self::Extended => 0x2,
+            Self::VendorSpecific => 0xE,
+            Self::Unknown(c) => c,
+        }
+    }
+}
+
+/// Outcome of a completed self-test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTestResult {
+    Passed,
+    AbortedByCommand,
+    AbortedByReset,
+    AbortedByNamespaceRemoval,
+    AbortedByFormat,
+    FatalError,
+    /// Failed in an unknown segment
+    Failed,
+    /// Failed in the segment given by [`SelfTestEntry::segment`]
+    SegmentFailed,
+    AbortedUnknown,
+    AbortedBySanitize,
+    Unknown(u8),
+}
+
+impl SelfTestResult {
+    fn from_code(code: u8) -> Self {
+        match code {
+            0x0 => Self::Passed,
+            0x1 => Self::AbortedByCommand,
+            0x2 => Self::AbortedByReset,
+            0x3 => Self::AbortedByNamespaceRemoval,
+            0x4 => Self::AbortedByFormat,
+            0x5 => Self::FatalError,
+            0x6 => Self::Failed,
+            0x7 => Self::SegmentFailed,
+            0x8 => Self::AbortedUnknown,
+            0x9 => Self::AbortedBySanitize,
+            c => Self::Unknown(c),
+        }
+    }
+
+    /// The test found a device fault (aborts are not failures)
+    pub fn is_failure(self) -> bool {
+        matches!(self, Self::FatalError | Self::Failed | Self::SegmentFailed)
+    }
+}
+
+/// One Self-test Result Data Structure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfTestEntry {
+    pub kind: SelfTestKind,
+    pub result: SelfTestResult,
+    /// Segment that failed, for [`SelfTestResult::SegmentFailed`]
+    pub segment: Option<u8>,
+    /// Power-on hours when the test completed
+    pub power_on_hours: u64,
+    pub nsid: Option<u32>,
+    pub failing_lba: Option<u64>,
+    pub status_code_type: Option<u8>,
+    pub status_code: Option<u8>,
+}
+
+/// Device Self-test log page (LID 0x06)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NvmeSelfTestLog {
+    /// Test currently running, if any
+    pub current: Option<SelfTestKind>,
+    /// Progress of the running test, percent
+    pub current_completion: u8,
+    /// Completed tests, newest first (up to 20)
+    pub results: Vec<SelfTestEntry>,
+}
+
+impl NvmeSelfTestLog {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        need(data, SELF_TEST_LOG_LEN, "self-test log")?;
+        let op = data[0] & 0xF;
+        let results = data[4..SELF_TEST_LOG_LEN]
+            .chunks_exact(SELF_TEST_ENTRY_LEN)
+            .take_while(|e| e[0] & 0xF != 0xF)
+            .map(|e| {
+                let valid = e[2];
+                let result = SelfTestResult::from_code(e[0] & 0xF);
+                SelfTestEntry {
+                    kind: SelfTestKind::from_code(e[0] >> 4),
+                    result,
+                    segment: (result == SelfTestResult::SegmentFailed).then_some(e[1]),
+                    power_on_hours: le_u64(e, 4),
+                    nsid: (valid & 0x1 != 0).then(|| le_u32(e, 12)),
+                    failing_lba: (valid & 0x2 != 0).then(|| le_u64(e, 16)),
+                    status_code_type: (valid & 0x4 != 0).then_some(e[24]),
+                    status_code: (valid & 0x8 != 0).then_some(e[25]),
+                }
+            })
+            .collect();
+        Ok(Self {
+            current: (op != 0).then(|| SelfTestKind::from_code(op)),
+            current_completion: data[1] & 0x7F,
+            results,
+        })
+    }
+
+    /// Most recent completed test
+    pub fn latest(&self) -> Option<&SelfTestEntry> {
+        self.results.first()
+    }
+}
+
+/// Identify Controller fields relevant to health monitoring (CNS 0x01)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NvmeIdentify {
+    pub vendor_id: u16,
+    pub serial: String,
+    pub model: String,
+    pub firmware: String,
+    /// Device Self-test command supported (OACS bit 4)
+    pub self_test: bool,
+    /// Firmware slots (1-7)
+    pub firmware_slots: u8,
+    /// Slot 1 is read-only
+    pub firmware_slot1_read_only: bool,
+    /// Error log entries kept by the controller
+    pub error_log_entries: u16,
+    /// Warning / critical composite temperature thresholds in Kelvin (0 = not reported)
+    pub warning_temp_k: u16,
+    pub critical_temp_k: u16,
+}
+
+impl NvmeIdentify {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        need(data, 512, "identify controller")?;
+        let oacs = le_u16(data, 256);
+        let frmw = data[260];
+        Ok(Self {
+            vendor_id: le_u16(data, 0),
+            serial: ascii(&data[4..24]),
+            model: ascii(&data[24..64]),
+            firmware: ascii(&data[64..72]),
+            self_test: oacs & (1 << 4) != 0,
+            firmware_slots: (frmw >> 1) & 0x7,
+            firmware_slot1_read_only: frmw & 0x1 != 0,
+            error_log_entries: data[262] as u16 + 1,
+            warning_temp_k: le_u16(data, 266),
+            critical_temp_k: le_u16(data, 268),
+        })
+    }
+}
+
+/// NVMe controller opened for admin passthrough
+pub struct NvmeDevice {
+    path: PathBuf,
+    #[cfg(target_os = "linux")]
+    file: std::fs::File,
+}
+
+impl NvmeDevice {
+    /// Open a controller (`/dev/nvme0`) or namespace (`/dev/nvme0n1`) device
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        #[cfg(target_os = "linux")]
+        {
+            let file = std::fs::File::open(&path).map_err(|e| map_io(&path, e))?;
+            Ok(Self { path, file })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(SimonError::UnsupportedPlatform(format!(
+                "NVMe passthrough for {} requires Linux",
+                path.display()
+            )))
+        }
+    }
+
+    /// Controller devices under `/sys/class/nvme`
+    pub fn controllers() -> Vec<PathBuf> {
+        let mut out: Vec<PathBuf> = std::fs::read_dir("/sys/class/nvme")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| Path::new("/dev").join(e.file_name()))
+            .collect();
+        out.sort();
+        out
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn identify(&self) -> Result<NvmeIdentify> {
+        let mut buf = [0u8; 4096];
+        self.identify_raw(&mut buf)?;
+        NvmeIdentify::parse(&buf)
+    }
+
+    pub fn smart_log(&self) -> Result<NvmeSmartLog> {
+        NvmeSmartLog::parse(&self.log_page(log_id::SMART, SMART_LOG_LEN)?)
+    }
+
+    /// Up to `max_entries` most recent error log entries
+    pub fn error_log(&self, max_entries: usize) -> Result<Vec<NvmeErrorEntry>> {
+        let entries = max_entries.clamp(1, 256);
+        let page = self.log_page(log_id::ERROR, entries * ERROR_ENTRY_LEN)?;
+        Ok(NvmeErrorEntry::parse_log(&page))
+    }
+
+    pub fn firmware_log(&self) -> Result<NvmeFirmwareLog> {
+        NvmeFirmwareLog::parse(&self.log_page(log_id::FIRMWARE_SLOT, FIRMWARE_LOG_LEN)?)
+    }
+
+    pub fn self_test_log(&self) -> Result<NvmeSelfTestLog> {
+        NvmeSelfTestLog::parse(&self.log_page(log_id::SELF_TEST, SELF_TEST_LOG_LEN)?)
+    }
+
+    /// Start a self-test of the controller and all namespaces; poll
+    /// [`Self::self_test_log`] for progress
+    pub fn start_self_test(&self, kind: SelfTestKind) -> Result<()> {
+        self.self_test(kind.code())
+    }
+
+    pub fn abort_self_test(&self) -> Result<()> {
+        self.self_test(0xF)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn log_page(&self, lid: u8, len: usize) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        sys::get_log_page(&self.file, sys::NSID_ALL, lid, &mut buf)
+            .map_err(|e| map_io(&self.path, e))?;
+        Ok(buf)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn identify_raw(&self, buf: &mut [u8; 4096]) -> Result<()> {
+        sys::identify(&self.file, 0, 0x01, buf).map_err(|e| map_io(&self.path, e))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn self_test(&self, stc: u8) -> Result<()> {
+        sys::device_self_test(&self.file, sys::NSID_ALL, stc).map_err(|e| map_io(&self.path, e))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn log_page(&self, _lid: u8, _len: usize) -> Result<Vec<u8>> {
+        Err(SimonError::UnsupportedPlatform(
+            "NVMe passthrough requires Linux".into(),
+        ))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn identify_raw(&self, _buf: &mut [u8; 4096]) -> Result<()> {
+        Err(SimonError::UnsupportedPlatform(
+            "NVMe passthrough requires Linux".into(),
+        ))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn self_test(&self, _stc: u8) -> Result<()> {
+        Err(SimonError::UnsupportedPlatform(
+            "NVMe passthrough requires Linux".into(),
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn map_io(path: &Path, e: std::io::Error) -> SimonError {
+    match e.kind() {
+        std::io::ErrorKind::PermissionDenied => SimonError::PermissionDenied(format!(
+            "NVMe admin commands on {} require CAP_SYS_ADMIN",
+            path.display()
+        )),
+        std::io::ErrorKind::NotFound => SimonError::DeviceNotFound(path.display().to_string()),
+        _ if e.raw_os_error() == Some(libc::ENOTTY) => {
+            SimonError::FeatureNotAvailable(format!("{} is not an NVMe device", path.display()))
+        }
+        _ => SimonError::Io(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rebuild a page from an `offset: bytes` hex dump (omitted rows are zero)
+    fn hexdump(len: usize, dump: &str) -> Vec<u8> {
+        let mut page = vec![0u8; len];
+        for line in dump.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (off, bytes) = line.split_once(':').unwrap();
+            let off = usize::from_str_radix(off, 16).unwrap();
+            for (i, b) in bytes.split_whitespace().enumerate() {
+                page[off + i] = u8::from_str_radix(b, 16).unwrap();
+            }
+        }
+        page
+    }
+
+    // Captured from a 1 TB consumer drive (`nvme get-log /dev/nvme0 --log-id=2 --log-len=512 -b`)
+    const SMART_LOG: &str = "
+        0000: 00 39 01 64 0a 03 00 00 00 00 00 00 00 00 00 00
+        0020: c3 b2 a1 00 00 00 00 00 00 00 00 00 00 00 00 00
+        0030: cd ab 89 00 00 00 00 00 00 00 00 00 00 00 00 00
+        0040: 78 56 34 12 00 00 00 00 00 00 00 00 00 00 00 00
+        0050: f0 de bc 0a 00 00 00 00 00 00 00 00 00 00 00 00
+        0060: d2 04 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        0070: 65 01 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        0080: 10 27 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        0090: 2a 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        00b0: 07 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        00c0: 05 00 00 00 00 00 00 00 41 01 30 01 00 00 00 00
+    ";
+
+    #[test]
+    fn test_smart_log() {
+        let mut page = hexdump(512, SMART_LOG);
+        let log = NvmeSmartLog::parse(&page).unwrap();
+        assert!(!log.critical_warning.any());
+        assert_eq!(log.temperature_celsius(), 40);
+        assert_eq!(
+            (log.available_spare, log.available_spare_threshold),
+            (100, 10)
+        );
+        assert_eq!(log.percentage_used, 3);
+        assert_eq!(log.data_units_read, 0xA1B2C3);
+        assert_eq!(log.bytes_written(), 0x89ABCD * 512_000);
+        assert_eq!(log.host_read_commands, 0x1234_5678);
+        assert_eq!(log.controller_busy_minutes, 1234);
+        assert_eq!(log.power_cycles, 357);
+        assert_eq!(log.power_on_hours, 10_000);
+        assert_eq!(log.unsafe_shutdowns, 42);
+        assert_eq!(log.media_errors, 0);
+        assert_eq!(log.error_log_entries, 7);
+        assert_eq!(log.warning_temp_minutes, 5);
+        assert_eq!(log.temperature_sensors_k, vec![321, 304]);
+
+        // Spare exhausted and media in read-only mode
+        page[0] = 0x09;
+        let log = NvmeSmartLog::parse(&page).unwrap();
+        assert!(log.critical_warning.contains(CriticalWarning::READ_ONLY));
+        assert_eq!(
+            log.critical_warning.active(),
+            vec!["available_spare", "read_only"]
+        );
+
+        assert!(NvmeSmartLog::parse(&page[..256]).is_err());
+    }
+
+    #[test]
+    fn test_error_log() {
+        let page = hexdump(
+            4 * 64,
+            "
+            0000: 07 00 00 00 00 00 00 00 01 00 42 00 03 85 ff ff
+            0010: 78 56 34 12 00 00 00 00 01 00 00 00 00 00 00 00
+            0040: 06 00 00 00 00 00 00 00 00 00 10 00 04 00 28 00
+            ",
+        );
+        let entries = NvmeErrorEntry::parse_log(&page);
+        assert_eq!(entries.len(), 2);
+
+        let read = &entries[0];
+        assert_eq!((read.error_count, read.sqid, read.command_id), (7, 1, 0x42));
+        assert_eq!((read.status_code_type, read.status_code), (2, 0x81));
+        assert!(read.dnr && read.is_media_error());
+        assert_eq!((read.lba, read.nsid), (0x1234_5678, 1));
+        assert_eq!(read.describe(), "unrecovered read error");
+
+        let admin = &entries[1];
+        assert_eq!(
+            (admin.sqid, admin.status_code_type, admin.status_code),
+            (0, 0, 0x02)
+        );
+        assert!(!admin.dnr && !admin.is_media_error());
+        assert_eq!(admin.parameter_error_location, 0x28);
+        assert_eq!(admin.describe(), "invalid field in command");
+    }
+
+    #[test]
+    fn test_firmware_log() {
+        let page = hexdump(
+            512,
+            "
+            0000: 21 00 00 00 00 00 00 00 31 42 32 51 45 58 4d 37
+            0010: 32 42 32 51 45 58 4d 37 00 00 00 00 00 00 00 00
+            ",
+        );
+        let log = NvmeFirmwareLog::parse(&page).unwrap();
+        assert_eq!((log.active_slot, log.next_slot), (1, Some(2)));
+        assert_eq!(log.active_revision(), Some("1B2QEXM7"));
+        assert_eq!(log.slots[1].as_deref(), Some("2B2QEXM7"));
+        assert_eq!(log.slots.iter().filter(|s| s.is_some()).count(), 2);
+    }
+
+    #[test]
+    fn test_self_test_log() {
+        let page = hexdump(
+            564,
+            "
+            0000: 01 2a 00 00 20 00 00 00 10 27 00 00 00 00 00 00
+            0020: 17 03 0f 00 28 23 00 00 00 00 00 00 01 00 00 00
+            0030: ef cd ab 00 00 00 00 00 02 81 00 00 0f 00 00 00
+            ",
+        );
+        let log = NvmeSelfTestLog::parse(&page).unwrap();
+        assert_eq!(log.current, Some(SelfTestKind::Short));
+        assert_eq!(log.current_completion, 42);
+        assert_eq!(log.results.len(), 2);
+
+        let latest = log.latest().unwrap();
+        assert_eq!(
+            (latest.kind, latest.result),
+            (SelfTestKind::Extended, SelfTestResult::Passed)
+        );
+        assert_eq!(latest.power_on_hours, 10_000);
+        assert_eq!((latest.nsid, latest.failing_lba), (None, None));
+
+        let failed = &log.results[1];
+        assert_eq!(failed.kind, SelfTestKind::Short);
+        assert!(failed.result.is_failure());
+        assert_eq!(failed.segment, Some(3));
+        assert_eq!((failed.nsid, failed.failing_lba), (Some(1), Some(0xABCDEF)));
+        assert_eq!(
+            (failed.status_code_type, failed.status_code),
+            (Some(2), Some(0x81))
+        );
+    }
+
+    #[test]
+    fn test_identify() {
+        let mut page = hexdump(
+            4096,
+            "
+            0000: 4d 14 4d 14
+            0100: 17 00 00 00 06 0e 3f 00 00 00 57 01 60 01
+            ",
+        );
+        page[4..24].copy_from_slice(b"S5GXNX0T123456A     ");
+        page[24..64].copy_from_slice(b"Samsung SSD 980 PRO 1TB                 ");
+        page[64..72].copy_from_slice(b"5B2QGXA7");
+        let id = NvmeIdentify::parse(&page).unwrap();
+        assert_eq!(id.vendor_id, 0x144d);
+        assert_eq!(id.serial, "S5GXNX0T123456A");
+        assert_eq!(id.model, "Samsung SSD 980 PRO 1TB");
+        assert_eq!(id.firmware, "5B2QGXA7");
+        assert!(id.self_test);
+        assert_eq!((id.firmware_slots, id.firmware_slot1_read_only), (3, false));
+        assert_eq!(id.error_log_entries, 64);
+        assert_eq!((id.warning_temp_k, id.critical_temp_k), (343, 352));
+    }
+}
@@ -0,0 +1,119 @@
+//! Raw NVMe admin passthrough (`NVME_IOCTL_ADMIN_CMD`)
+//!
+//! `libc` has no NVMe definitions, so `struct nvme_admin_cmd` from
+//! `<linux/nvme_ioctl.h>` is declared here. The layout has been stable since
+//! the ioctl was introduced.
+
+use std::fs::File;
+use std::io;
+use std::os::unix::io::AsRawFd;
+
+/// `_IOWR('N', 0x41, struct nvme_admin_cmd)`
+const NVME_IOCTL_ADMIN_CMD: libc::c_ulong = 0xC048_4E41;
+
+pub const OPC_GET_LOG_PAGE: u8 = 0x02;
+pub const OPC_IDENTIFY: u8 = 0x06;
+pub const OPC_DEVICE_SELF_TEST: u8 = 0x14;
+
+/// Controller-wide namespace ID
+pub const NSID_ALL: u32 = 0xFFFF_FFFF;
+
+/// `struct nvme_admin_cmd` (a.k.a. `nvme_passthru_cmd`)
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NvmeAdminCmd {
+    pub opcode: u8,
+    pub flags: u8,
+    pub rsvd1: u16,
+    pub nsid: u32,
+    pub cdw2: u32,
+    pub cdw3: u32,
+    pub metadata: u64,
+    pub addr: u64,
+    pub metadata_len: u32,
+    pub data_len: u32,
+    pub cdw10: u32,
+    pub cdw11: u32,
+    pub cdw12: u32,
+    pub cdw13: u32,
+    pub cdw14: u32,
+    pub cdw15: u32,
+    pub timeout_ms: u32,
+    pub result: u32,
+}
+
+/// Submit an admin command. `data`, if given, is the transfer buffer in the
+/// direction implied by the opcode. Returns completion dword 0.
+///
+/// A positive ioctl return is the NVMe status field of a failed command and
+/// is reported as an [`io::ErrorKind::Other`] error with the status in the message.
+pub fn admin_cmd(file: &File, mut cmd: NvmeAdminCmd, data: Option<&mut [u8]>) -> io::Result<u32> {
+    if let Some(buf) = data {
+        cmd.addr = buf.as_mut_ptr() as u64;
+        cmd.data_len = buf.len() as u32;
+    }
+    // SAFETY: cmd is a fully initialized nvme_admin_cmd; addr/data_len describe
+    // a live, exclusively borrowed buffer that outlives the synchronous ioctl.
+    let ret = unsafe { libc::ioctl(file.as_raw_fd(), NVME_IOCTL_ADMIN_CMD as _, &mut cmd) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if ret > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "NVMe command {:#04x} failed with status {:#06x}",
+                cmd.opcode, ret
+            ),
+        ));
+    }
+    Ok(cmd.result)
+}
+
+/// Get Log Page: `len` must be a non-zero multiple of 4
+pub fn get_log_page(file: &File, nsid: u32, lid: u8, buf: &mut [u8]) -> io::Result<()> {
+    let numd = (buf.len() / 4).saturating_sub(1) as u32;
+    let cmd = NvmeAdminCmd {
+        opcode: OPC_GET_LOG_PAGE,
+        nsid,
+        cdw10: lid as u32 | ((numd & 0xFFFF) << 16),
+        cdw11: numd >> 16,
+        ..Default::default()
+    };
+    admin_cmd(file, cmd, Some(buf)).map(|_| ())
+}
+
+/// Identify with the given CNS into a 4096-byte buffer
+pub fn identify(file: &File, nsid: u32, cns: u8, buf: &mut [u8; 4096]) -> io::Result<()> {
+    let cmd = NvmeAdminCmd {
+        opcode: OPC_IDENTIFY,
+        nsid,
+        cdw10: cns as u32,
+        ..Default::default()
+    };
+    admin_cmd(file, cmd, Some(buf)).map(|_| ())
+}
+
+/// Device Self-test with self-test code `stc` (1 short, 2 extended, 0xF abort)
+pub fn device_self_test(file: &File, nsid: u32, stc: u8) -> io::Result<()> {
+    let cmd = NvmeAdminCmd {
+        opcode: OPC_DEVICE_SELF_TEST,
+        nsid,
+        cdw10: stc as u32,
+        ..Default::default()
+    };
+    admin_cmd(file, cmd, None).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_cmd_layout() {
+        assert_eq!(std::mem::size_of::<NvmeAdminCmd>(), 72);
+        // _IOWR: dir=3, size=72, type='N', nr=0x41
+        let ioc = (3 << 30) | (72 << 16) | ((b'N' as libc::c_ulong) << 8) | 0x41;
+        assert_eq!(NVME_IOCTL_ADMIN_CMD, ioc);
+    }
+}
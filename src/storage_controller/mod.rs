@@ -300,19 +300,7 @@ impl StorageControllerMonitor {
                 let base = entry.path();
                 let proc_name = Self::read_trimmed(&base.join("proc_name"));
 
-                let interface = match proc_name.as_str() {
-                    "ahci" => StorageInterface::AHCI,
-                    "mpt3sas" | "mpt2sas" | "megaraid_sas" => StorageInterface::SAS,
-                    "uas" | "usb-storage" => StorageInterface::USB,
-                    "virtio_scsi" => StorageInterface::Virtio,
-                    _ => {
-                        if proc_name.contains("iscsi") {
-                            StorageInterface::ISCSI
-                        } else {
-                            StorageInterface::SCSI
-                        }
-                    }
-                };
+                let interface = StorageInterface::from_scsi_driver(&proc_name);
 
                 let model_name = Self::read_trimmed(&base.join("model_name"));
 
@@ -364,15 +352,7 @@ impl StorageControllerMonitor {
                     _ => RaidStatus::Unknown,
                 };
 
-                let level = match level_str {
-                    "raid0" => RaidLevel::Raid0,
-                    "raid1" => RaidLevel::Raid1,
-                    "raid5" => RaidLevel::Raid5,
-                    "raid6" => RaidLevel::Raid6,
-                    "raid10" => RaidLevel::Raid10,
-                    "linear" => RaidLevel::Linear,
-                    other => RaidLevel::Unknown(other.to_string()),
-                };
+                let level = RaidLevel::from_md_level(level_str);
 
                 // Extract member devices (e.g., "sda1[0]", "sdb1[1]")
                 let members: Vec<String> = parts[4..]
@@ -650,6 +630,35 @@ impl Default for StorageControllerMonitor {
     }
 }
 
+impl StorageInterface {
+    /// Interface behind a SCSI host, from its driver (`scsi_host/*/proc_name`)
+    pub fn from_scsi_driver(proc_name: &str) -> Self {
+        match proc_name {
+            "ahci" => Self::AHCI,
+            "mpt3sas" | "mpt2sas" | "megaraid_sas" => Self::SAS,
+            "uas" | "usb-storage" => Self::USB,
+            "virtio_scsi" => Self::Virtio,
+            _ if proc_name.contains("iscsi") => Self::ISCSI,
+            _ => Self::SCSI,
+        }
+    }
+}
+
+impl RaidLevel {
+    /// Parse an md personality as shown in `/proc/mdstat` or `md/level`
+    pub fn from_md_level(level: &str) -> Self {
+        match level {
+            "raid0" => Self::Raid0,
+            "raid1" => Self::Raid1,
+            "raid5" => Self::Raid5,
+            "raid6" => Self::Raid6,
+            "raid10" => Self::Raid10,
+            "linear" => Self::Linear,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
 impl std::fmt::Display for StorageInterface {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
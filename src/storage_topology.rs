@@ -0,0 +1,1325 @@
+//! Block storage stack: filesystem mounts mapped down to physical disks
+//!
+//! `disk` lists whole disks and `storage_controller` parses `/proc/mdstat`,
+//! but neither says what sits between a mount point and the hardware. This
+//! module builds the block device graph from sysfs and resolves each mount
+//! through it:
+//!
+//! ```text
+//! /data ─ dm-2 (LVM vg0/data) ─ dm-0 (LUKS) ─ nvme0n1p2 ─ nvme0n1 ─ nvme0 @ 0000:01:00.0
+//! /srv  ─ md0 (raid1, degraded) ─ sda1 ─ sda ─ host0 (AHCI)
+//!                               └ sdb1 ─ sdb ─ host0 (AHCI)
+//! ```
+//!
+//! Edges come from `/sys/block/*/slaves` and `holders` plus the
+//! partition/disk nesting. Layers are identified from `dm/uuid` (LVM, LUKS,
+//! multipath, kpartx), `md/` attributes and udev's filesystem probe results in
+//! `/run/udev/data` (LUKS headers, ZFS members). btrfs pools come from
+//! `/sys/fs/btrfs/*/devices`, ZFS pools from udev member labels with state
+//! from `/proc/spl/kstat/zfs/*/state`.
+//!
+//! # Platform Support
+//!
+//! - **Linux**: sysfs, `/proc/self/mountinfo`, udev database
+//! - **Other**: unsupported
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::smart::SmartMonitor;
+//! use simonlib::storage_topology::StorageTopology;
+//!
+//! let topology = StorageTopology::detect().unwrap();
+//! let smart = SmartMonitor::new().unwrap();
+//! if let Some(backing) = topology.backing("/data") {
+//!     for (disk, health) in backing.disk_health(smart.disks()) {
+//!         println!("{} via {:?}: {:?}", disk.name, disk.path, health.map(|h| &h.health));
+//!     }
+//!     for problem in &backing.degraded {
+//!         println!("degraded: {}", problem);
+//!     }
+//! }
+//! ```
+
+use crate::error::{Result, SimonError};
+use crate::smart::SmartDiskInfo;
+use crate::storage_controller::{RaidLevel, StorageInterface};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Physical disk details and the controller it hangs off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhysicalDisk {
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub interface: StorageInterface,
+    /// NVMe controller (`nvme0`) or SCSI host (`host2`)
+    pub controller: Option<String>,
+    /// PCI address of the controller
+    pub pci_address: Option<String>,
+    pub rotational: bool,
+}
+
+/// Linux software RAID state from `/sys/block/md*/md`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MdArray {
+    pub level: RaidLevel,
+    pub raid_disks: u32,
+    /// Members missing from the array
+    pub degraded: u32,
+    /// `clean`, `active`, `inactive`, ...
+    pub array_state: String,
+    /// `idle`, `resync`, `recover`, `check`, ...
+    pub sync_action: Option<String>,
+    /// Progress of the current sync action (0-100)
+    pub sync_progress: Option<f32>,
+    /// Members marked faulty
+    pub faulty: Vec<String>,
+}
+
+impl MdArray {
+    pub fn is_degraded(&self) -> bool {
+        self.degraded > 0 || !self.faulty.is_empty() || self.array_state == "inactive"
+    }
+}
+
+/// What a block device is in the stack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BlockLayer {
+    Disk(PhysicalDisk),
+    Partition {
+        number: Option<u32>,
+    },
+    /// LVM logical volume
+    Lvm {
+        vg: String,
+        lv: String,
+    },
+    /// dm-crypt mapping (`luks_uuid` is None for plain dm-crypt)
+    Crypt {
+        luks_uuid: Option<String>,
+    },
+    Multipath {
+        wwid: Option<String>,
+    },
+    /// Other device-mapper targets (thin pools, VDO, Stratis, ...)
+    DeviceMapper {
+        uuid: Option<String>,
+    },
+    Md(MdArray),
+    Loop {
+        backing_file: Option<String>,
+    },
+    /// ZFS volume
+    Zvol,
+    Other,
+}
+
+/// A node in the block device graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockDevice {
+    /// Kernel name (`nvme0n1p2`, `dm-0`, `md0`)
+    pub name: String,
+    /// `major:minor`
+    pub dev: String,
+    /// Preferred `/dev` path (`/dev/mapper/vg0-data` for device-mapper)
+    pub path: String,
+    pub size_bytes: u64,
+    pub layer: BlockLayer,
+    /// Filesystem or container signature found by udev (`ext4`, `crypto_LUKS`, `zfs_member`)
+    pub fs_type: Option<String>,
+    pub fs_label: Option<String>,
+    pub fs_uuid: Option<String>,
+    /// Devices this one is built on (`slaves`, or the disk of a partition)
+    pub lower: Vec<String>,
+    /// Devices built on this one (`holders`, or a disk's partitions)
+    pub upper: Vec<String>,
+    /// sysfs directory, for reading `stat`
+    #[serde(skip)]
+    sysfs: PathBuf,
+}
+
+impl BlockDevice {
+    pub fn is_disk(&self) -> bool {
+        matches!(self.layer, BlockLayer::Disk(_))
+    }
+
+    pub fn disk(&self) -> Option<&PhysicalDisk> {
+        match &self.layer {
+            BlockLayer::Disk(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// Short description of the layer for display
+    pub fn describe(&self) -> String {
+        match &self.layer {
+            BlockLayer::Disk(d) => match (&d.model, &d.controller) {
+                (Some(model), Some(ctrl)) => format!("{} {} on {}", d.interface, model, ctrl),
+                (Some(model), None) => format!("{} {}", d.interface, model),
+                _ => format!("{} disk", d.interface),
+            },
+            BlockLayer::Partition { number: Some(n) } => format!("partition {}", n),
+            BlockLayer::Partition { number: None } => "partition".into(),
+            BlockLayer::Lvm { vg, lv } => format!("LVM {}/{}", vg, lv),
+            BlockLayer::Crypt { luks_uuid: Some(_) } => "LUKS".into(),
+            BlockLayer::Crypt { luks_uuid: None } => "dm-crypt".into(),
+            BlockLayer::Multipath { .. } => "multipath".into(),
+            BlockLayer::DeviceMapper { .. } => "device-mapper".into(),
+            BlockLayer::Md(md) if md.is_degraded() => format!("md {} (degraded)", md.level),
+            BlockLayer::Md(md) => format!("md {}", md.level),
+            BlockLayer::Loop { .. } => "loop".into(),
+            BlockLayer::Zvol => "zvol".into(),
+            BlockLayer::Other => "block device".into(),
+        }
+    }
+}
+
+/// Multi-device filesystem pool type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolKind {
+    Btrfs,
+    Zfs,
+}
+
+/// btrfs filesystem or ZFS pool spanning several block devices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FsPool {
+    pub kind: PoolKind,
+    /// btrfs label (or UUID when unlabelled) / ZFS pool name
+    pub name: String,
+    pub uuid: Option<String>,
+    pub members: Vec<String>,
+    /// ZFS pool state (`ONLINE`, `DEGRADED`, `FAULTED`, ...)
+    pub state: Option<String>,
+}
+
+impl FsPool {
+    pub fn is_degraded(&self) -> bool {
+        self.state.as_deref().is_some_and(|s| s != "ONLINE")
+    }
+}
+
+/// A mounted filesystem and the block devices directly under it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MountPoint {
+    pub mount_point: String,
+    pub fs_type: String,
+    /// Mount source as shown by `mount`
+    pub source: String,
+    /// Top-level block devices (several for btrfs/ZFS pools)
+    pub devices: Vec<String>,
+    /// Pool name for btrfs/ZFS
+    pub pool: Option<String>,
+}
+
+/// Everything backing one mount point, top-down
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MountBacking {
+    pub mount: MountPoint,
+    /// Every block device between the filesystem and the hardware
+    pub stack: Vec<BlockDevice>,
+    /// Physical disks at the bottom of the stack
+    pub disks: Vec<BlockDevice>,
+    pub pool: Option<FsPool>,
+    /// Degraded RAID arrays and pools in the stack
+    pub degraded: Vec<String>,
+}
+
+impl MountBacking {
+    /// Pair each physical disk with its SMART data, matched by serial or device name
+    pub fn disk_health<'a>(
+        &self,
+        smart: &'a [SmartDiskInfo],
+    ) -> Vec<(&BlockDevice, Option<&'a SmartDiskInfo>)> {
+        self.disks
+            .iter()
+            .map(|disk| (disk, smart_for_disk(disk, smart)))
+            .collect()
+    }
+
+    /// No degraded arrays and no disk with a SMART warning or worse
+    pub fn is_healthy(&self, smart: &[SmartDiskInfo]) -> bool {
+        use crate::smart::DiskHealth;
+        self.degraded.is_empty()
+            && self.disk_health(smart).iter().all(|(_, s)| {
+                !s.is_some_and(|s| {
+                    matches!(
+                        s.health,
+                        DiskHealth::Warning | DiskHealth::Critical | DiskHealth::Failed
+                    )
+                })
+            })
+    }
+}
+
+/// SMART entry for a disk: by serial, then by `/dev` name (SMART lists NVMe by controller)
+pub fn smart_for_disk<'a>(
+    disk: &BlockDevice,
+    smart: &'a [SmartDiskInfo],
+) -> Option<&'a SmartDiskInfo> {
+    let info = disk.disk()?;
+    let by_serial = info.serial.as_deref().and_then(|serial| {
+        smart
+            .iter()
+            .find(|s| !s.serial.is_empty() && s.serial.trim() == serial)
+    });
+    by_serial.or_else(|| {
+        smart.iter().find(|s| {
+            let dev = s.device.trim_start_matches("/dev/");
+            dev == disk.name || Some(dev) == info.controller.as_deref()
+        })
+    })
+}
+
+/// Cumulative I/O counters from a block device's `stat` file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IoCounters {
+    pub read_ios: u64,
+    pub read_bytes: u64,
+    pub write_ios: u64,
+    pub write_bytes: u64,
+    pub in_flight: u64,
+    /// Milliseconds the device had I/O in flight
+    pub io_ticks_ms: u64,
+}
+
+impl IoCounters {
+    pub fn parse(text: &str) -> Option<Self> {
+        let f: Vec<u64> = text
+            .split_whitespace()
+            .map(|v| v.parse().unwrap_or(0))
+            .collect();
+        if f.len() < 11 {
+            return None;
+        }
+        Some(Self {
+            read_ios: f[0],
+            read_bytes: f[2] * 512,
+            write_ios: f[4],
+            write_bytes: f[6] * 512,
+            in_flight: f[8],
+            io_ticks_ms: f[9],
+        })
+    }
+
+    /// Rates over `secs` since `prev`
+    pub fn rates_since(&self, prev: &Self, secs: f64) -> IoRates {
+        if secs <= 0.0 {
+            return IoRates::default();
+        }
+        let per_sec = |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+        IoRates {
+            read_bytes_per_sec: per_sec(self.read_bytes, prev.read_bytes),
+            write_bytes_per_sec: per_sec(self.write_bytes, prev.write_bytes),
+            read_iops: per_sec(self.read_ios, prev.read_ios),
+            write_iops: per_sec(self.write_ios, prev.write_ios),
+            utilization: (per_sec(self.io_ticks_ms, prev.io_ticks_ms) / 10.0).min(100.0),
+        }
+    }
+}
+
+/// I/O rates derived from two [`IoCounters`] samples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct IoRates {
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    /// Percent of time with I/O in flight
+    pub utilization: f64,
+}
+
+/// Block device graph, filesystem pools and mounts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageTopology {
+    pub devices: Vec<BlockDevice>,
+    pub pools: Vec<FsPool>,
+    pub mounts: Vec<MountPoint>,
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+impl StorageTopology {
+    /// Read the live system's storage stack
+    pub fn detect() -> Result<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            Self::from_root("/")
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(SimonError::UnsupportedPlatform(
+                "Storage topology is only implemented on Linux".into(),
+            ))
+        }
+    }
+
+    /// Read the storage stack from `/sys`, `/proc` and `/run/udev` under `root`
+    pub fn from_root(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let sys_block = root.join("sys/block");
+        let entries = std::fs::read_dir(&sys_block)?;
+
+        let mut dirs: Vec<(String, PathBuf, Option<String>)> = Vec::new();
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with("ram") {
+                continue;
+            }
+            let dir = entry.path();
+            if let Ok(children) = std::fs::read_dir(&dir) {
+                for child in children.flatten() {
+                    if child.path().join("partition").exists() {
+                        let part = child.file_name().to_string_lossy().to_string();
+                        dirs.push((part, child.path(), Some(name.clone())));
+                    }
+                }
+            }
+            dirs.push((name, dir, None));
+        }
+
+        let mut devices = Vec::new();
+        for (name, dir, parent) in dirs {
+            if let Some(device) = read_device(root, &name, &dir, parent) {
+                devices.push(device);
+            }
+        }
+        if devices.is_empty() {
+            return Err(SimonError::FeatureNotAvailable(
+                "no block devices in sysfs".into(),
+            ));
+        }
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut topology = Self {
+            devices,
+            pools: Vec::new(),
+            mounts: Vec::new(),
+            index: HashMap::new(),
+        };
+        topology.reindex();
+        topology.link_partitions();
+        topology.pools = read_pools(root, &topology.devices);
+        topology.mounts = topology.read_mounts(root);
+        Ok(topology)
+    }
+
+    fn reindex(&mut self) {
+        self.index = self
+            .devices
+            .iter()
+            .enumerate()
+            .map(|(i, d)| (d.name.clone(), i))
+            .collect();
+    }
+
+    /// Partitions sit on their disk: add the edge both ways
+    fn link_partitions(&mut self) {
+        let edges: Vec<(String, String)> = self
+            .devices
+            .iter()
+            .filter(|d| matches!(d.layer, BlockLayer::Partition { .. }))
+            .flat_map(|d| d.lower.iter().map(move |l| (d.name.clone(), l.clone())))
+            .collect();
+        for (part, disk) in edges {
+            if let Some(&i) = self.index.get(&disk) {
+                if !self.devices[i].upper.contains(&part) {
+                    self.devices[i].upper.push(part);
+                    self.devices[i].upper.sort();
+                }
+            }
+        }
+    }
+
+    pub fn device(&self, name: &str) -> Option<&BlockDevice> {
+        self.index.get(name).map(|&i| &self.devices[i])
+    }
+
+    /// Find a device by kernel name or `/dev` path (`/dev/mapper/*` included)
+    pub fn resolve(&self, path: &str) -> Option<&BlockDevice> {
+        let name = path.strip_prefix("/dev/").unwrap_or(path);
+        self.device(name).or_else(|| {
+            self.devices
+                .iter()
+                .find(|d| d.path == path || d.path == format!("/dev/{}", name))
+        })
+    }
+
+    /// Every device below `name`, top-down (depth first, no repeats)
+    pub fn stack(&self, name: &str) -> Vec<&BlockDevice> {
+        let mut out = Vec::new();
+        let mut seen = HashSet::new();
+        self.walk_down(name, &mut seen, &mut out);
+        out
+    }
+
+    fn walk_down<'a>(
+        &'a self,
+        name: &str,
+        seen: &mut HashSet<String>,
+        out: &mut Vec<&'a BlockDevice>,
+    ) {
+        let Some(device) = self.device(name) else {
+            return;
+        };
+        if !seen.insert(device.name.clone()) {
+            return;
+        }
+        out.push(device);
+        for lower in &device.lower {
+            self.walk_down(lower, seen, out);
+        }
+    }
+
+    /// Physical disks backing `name`
+    pub fn physical_disks(&self, name: &str) -> Vec<&BlockDevice> {
+        self.stack(name)
+            .into_iter()
+            .filter(|d| d.is_disk())
+            .collect()
+    }
+
+    /// Every device built on top of `name` (what breaks if this disk fails)
+    pub fn dependents(&self, name: &str) -> Vec<&BlockDevice> {
+        let mut out = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = vec![name.to_string()];
+        while let Some(next) = queue.pop() {
+            let Some(device) = self.device(&next) else {
+                continue;
+            };
+            for upper in &device.upper {
+                if seen.insert(upper.clone()) {
+                    if let Some(d) = self.device(upper) {
+                        out.push(d);
+                    }
+                    queue.push(upper.clone());
+                }
+            }
+        }
+        out
+    }
+
+    /// Mount points that depend on a device (e.g. a failing disk)
+    pub fn mounts_on(&self, name: &str) -> Vec<&MountPoint> {
+        let mut affected: HashSet<&str> = self
+            .dependents(name)
+            .iter()
+            .map(|d| d.name.as_str())
+            .collect();
+        affected.insert(name);
+        self.mounts
+            .iter()
+            .filter(|m| m.devices.iter().any(|d| affected.contains(d.as_str())))
+            .collect()
+    }
+
+    pub fn pool(&self, name: &str) -> Option<&FsPool> {
+        self.pools.iter().find(|p| p.name == name)
+    }
+
+    /// The mount containing `path` (longest matching mount point)
+    pub fn mount_for(&self, path: impl AsRef<Path>) -> Option<&MountPoint> {
+        let path = path.as_ref();
+        self.mounts
+            .iter()
+            .filter(|m| path.starts_with(&m.mount_point))
+            .max_by_key(|m| m.mount_point.len())
+    }
+
+    /// Resolve the filesystem holding `path` down to physical disks
+    pub fn backing(&self, path: impl AsRef<Path>) -> Option<MountBacking> {
+        let mount = self.mount_for(path)?;
+        let mut stack: Vec<&BlockDevice> = Vec::new();
+        let mut seen = HashSet::new();
+        for top in &mount.devices {
+            self.walk_down(top, &mut seen, &mut stack);
+        }
+        let pool = mount.pool.as_deref().and_then(|p| self.pool(p)).cloned();
+
+        let mut degraded: Vec<String> = stack
+            .iter()
+            .filter_map(|d| match &d.layer {
+                BlockLayer::Md(md) if md.is_degraded() => Some(format!(
+                    "{} ({}) is {}: {}/{} members{}",
+                    d.name,
+                    md.level,
+                    md.array_state,
+                    md.raid_disks.saturating_sub(md.degraded),
+                    md.raid_disks,
+                    if md.faulty.is_empty() {
+                        String::new()
+                    } else {
+                        format!(", faulty {}", md.faulty.join(", "))
+                    }
+                )),
+                _ => None,
+            })
+            .collect();
+        if let Some(p) = pool.as_ref().filter(|p| p.is_degraded()) {
+            degraded.push(format!(
+                "ZFS pool {} is {}",
+                p.name,
+                p.state.as_deref().unwrap_or("unknown")
+            ));
+        }
+
+        Some(MountBacking {
+            mount: mount.clone(),
+            disks: stack
+                .iter()
+                .filter(|d| d.is_disk())
+                .map(|d| (*d).clone())
+                .collect(),
+            stack: stack.into_iter().cloned().collect(),
+            pool,
+            degraded,
+        })
+    }
+
+    /// Backing for every mount that resolves to block devices
+    pub fn all_backings(&self) -> Vec<MountBacking> {
+        self.mounts
+            .iter()
+            .filter_map(|m| self.backing(&m.mount_point))
+            .collect()
+    }
+
+    /// Current I/O counters of a device
+    pub fn io_counters(&self, name: &str) -> Option<IoCounters> {
+        let device = self.device(name)?;
+        IoCounters::parse(&std::fs::read_to_string(device.sysfs.join("stat")).ok()?)
+    }
+
+    /// I/O counters for a mount, summed over its top-level devices
+    ///
+    /// Reading the top of the stack counts each filesystem I/O once, before
+    /// RAID mirroring or LUKS/LVM remapping multiplies it.
+    pub fn mount_io_counters(&self, mount_point: &str) -> Option<IoCounters> {
+        let mount = self.mounts.iter().find(|m| m.mount_point == mount_point)?;
+        let mut total: Option<IoCounters> = None;
+        for c in mount.devices.iter().filter_map(|d| self.io_counters(d)) {
+            let t = total.get_or_insert_with(IoCounters::default);
+            t.read_ios += c.read_ios;
+            t.read_bytes += c.read_bytes;
+            t.write_ios += c.write_ios;
+            t.write_bytes += c.write_bytes;
+            t.in_flight += c.in_flight;
+            t.io_ticks_ms = t.io_ticks_ms.max(c.io_ticks_ms);
+        }
+        total
+    }
+
+    fn read_mounts(&self, root: &Path) -> Vec<MountPoint> {
+        let Ok(text) = std::fs::read_to_string(root.join("proc/self/mountinfo")) else {
+            return Vec::new();
+        };
+        let by_dev: HashMap<&str, &str> = self
+            .devices
+            .iter()
+            .map(|d| (d.dev.as_str(), d.name.as_str()))
+            .collect();
+
+        let mut mounts = Vec::new();
+        for line in text.lines() {
+            let Some((left, right)) = line.split_once(" - ") else {
+                continue;
+            };
+            let left: Vec<&str> = left.split(' ').collect();
+            let right: Vec<&str> = right.split(' ').collect();
+            if left.len() < 5 || right.len() < 2 {
+                continue;
+            }
+            let mount_point = unescape_mount(left[4]);
+            let fs_type = right[0].to_string();
+            let source = unescape_mount(right[1]);
+
+            let (devices, pool) = match fs_type.as_str() {
+                "zfs" => {
+                    let pool = source.split('/').next().unwrap_or_default();
+                    match self
+                        .pools
+                        .iter()
+                        .find(|p| p.kind == PoolKind::Zfs && p.name == pool)
+                    {
+                        Some(p) => (p.members.clone(), Some(p.name.clone())),
+                        None => continue,
+                    }
+                }
+                "btrfs" => {
+                    // mountinfo shows an anonymous dev for btrfs; go by source
+                    let Some(dev) = self.resolve(&source) else {
+                        continue;
+                    };
+                    match self
+                        .pools
+                        .iter()
+                        .find(|p| p.kind == PoolKind::Btrfs && p.members.contains(&dev.name))
+                    {
+                        Some(p) => (p.members.clone(), Some(p.name.clone())),
+                        None => (vec![dev.name.clone()], None),
+                    }
+                }
+                _ => match by_dev
+                    .get(left[2])
+                    .map(|n| n.to_string())
+                    .or_else(|| self.resolve(&source).map(|d| d.name.clone()))
+                {
+                    Some(name) => (vec![name], None),
+                    None => continue,
+                },
+            };
+            mounts.push(MountPoint {
+                mount_point,
+                fs_type,
+                source,
+                devices,
+                pool,
+            });
+        }
+        mounts
+    }
+}
+
+/// udev database entry (`E:` properties) for a block device
+fn read_udev(root: &Path, dev: &str) -> HashMap<String, String> {
+    std::fs::read_to_string(root.join(format!("run/udev/data/b{}", dev)))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.strip_prefix("E:")?.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn list_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn read_device(root: &Path, name: &str, dir: &Path, parent: Option<String>) -> Option<BlockDevice> {
+    let dev = read_trimmed(&dir.join("dev"))?;
+    let size_bytes = read_trimmed(&dir.join("size"))
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0)
+        * 512;
+    // Unattached loop devices
+    if name.starts_with("loop") && size_bytes == 0 {
+        return None;
+    }
+    let udev = read_udev(root, &dev);
+    let mut lower = list_names(&dir.join("slaves"));
+    let upper = list_names(&dir.join("holders"));
+    let mut path = format!("/dev/{}", name);
+
+    let layer = if let Some(disk) = parent {
+        lower.push(disk);
+        BlockLayer::Partition {
+            number: read_trimmed(&dir.join("partition")).and_then(|p| p.parse().ok()),
+        }
+    } else if name.starts_with("dm-") {
+        let dm_name = read_trimmed(&dir.join("dm/name"));
+        let uuid = read_trimmed(&dir.join("dm/uuid"));
+        if let Some(n) = &dm_name {
+            path = format!("/dev/mapper/{}", n);
+        }
+        dm_layer(dm_name.as_deref().unwrap_or(name), uuid)
+    } else if dir.join("md").is_dir() {
+        BlockLayer::Md(read_md(dir))
+    } else if name.starts_with("loop") {
+        BlockLayer::Loop {
+            backing_file: read_trimmed(&dir.join("loop/backing_file")),
+        }
+    } else if name.starts_with("zd") {
+        BlockLayer::Zvol
+    } else if dir.join("device").exists() {
+        BlockLayer::Disk(read_physical(root, name, dir, &udev))
+    } else {
+        BlockLayer::Other
+    };
+
+    Some(BlockDevice {
+        name: name.to_string(),
+        dev,
+        path,
+        size_bytes,
+        layer,
+        fs_type: udev.get("ID_FS_TYPE").cloned(),
+        fs_label: udev.get("ID_FS_LABEL").cloned(),
+        fs_uuid: udev.get("ID_FS_UUID").cloned(),
+        lower,
+        upper,
+        sysfs: dir.to_path_buf(),
+    })
+}
+
+/// Classify a device-mapper device by its UUID prefix
+fn dm_layer(dm_name: &str, uuid: Option<String>) -> BlockLayer {
+    let Some(uuid) = uuid else {
+        return BlockLayer::DeviceMapper { uuid: None };
+    };
+    if uuid.starts_with("LVM-") {
+        let (vg, lv) = split_lvm_name(dm_name);
+        BlockLayer::Lvm { vg, lv }
+    } else if let Some(rest) = uuid.strip_prefix("CRYPT-") {
+        // CRYPT-LUKS2-<uuid without dashes>-<name>, CRYPT-PLAIN-<name>
+        let mut parts = rest.splitn(3, '-');
+        let luks_uuid = match (parts.next(), parts.next()) {
+            (Some(t), Some(id)) if t.starts_with("LUKS") => Some(id.to_string()),
+            _ => None,
+        };
+        BlockLayer::Crypt { luks_uuid }
+    } else if let Some(wwid) = uuid.strip_prefix("mpath-") {
+        BlockLayer::Multipath {
+            wwid: Some(wwid.to_string()),
+        }
+    } else if uuid.starts_with("part") && uuid.contains('-') {
+        // kpartx partition on top of a multipath device
+        let number = uuid[4..].split('-').next().and_then(|n| n.parse().ok());
+        BlockLayer::Partition { number }
+    } else {
+        BlockLayer::DeviceMapper { uuid: Some(uuid) }
+    }
+}
+
+/// Split a dm name into VG and LV, undoing LVM's `-` → `--` escaping
+pub fn split_lvm_name(dm_name: &str) -> (String, String) {
+    let chars: Vec<char> = dm_name.chars().collect();
+    let mut vg = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '-' {
+            if chars.get(i + 1) == Some(&'-') {
+                vg.push('-');
+                i += 2;
+                continue;
+            }
+            let lv: String = chars[i + 1..].iter().collect();
+            return (vg, lv.replace("--", "-"));
+        }
+        vg.push(chars[i]);
+        i += 1;
+    }
+    (vg, String::new())
+}
+
+fn read_md(dir: &Path) -> MdArray {
+    let md = dir.join("md");
+    let read_u32 = |f: &str| {
+        read_trimmed(&md.join(f))
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0)
+    };
+    let sync_action = read_trimmed(&md.join("sync_action"));
+    // "done / total" in sectors, or "none"
+    let sync_progress = read_trimmed(&md.join("sync_completed")).and_then(|s| {
+        let (done, total) = s.split_once('/')?;
+        let done: f64 = done.trim().parse().ok()?;
+        let total: f64 = total.trim().parse().ok()?;
+        (total > 0.0).then(|| (done / total * 100.0) as f32)
+    });
+    let faulty = list_names(&md)
+        .into_iter()
+        .filter_map(|entry| {
+            let member = entry.strip_prefix("dev-")?;
+            read_trimmed(&md.join(&entry).join("state"))
+                .filter(|s| s.split(',').any(|f| f == "faulty"))
+                .map(|_| member.to_string())
+        })
+        .collect();
+    MdArray {
+        level: RaidLevel::from_md_level(&read_trimmed(&md.join("level")).unwrap_or_default()),
+        raid_disks: read_u32("raid_disks"),
+        degraded: read_u32("degraded"),
+        array_state: read_trimmed(&md.join("array_state")).unwrap_or_default(),
+        sync_action: sync_action.filter(|a| a != "idle"),
+        sync_progress,
+        faulty,
+    }
+}
+
+fn read_physical(
+    root: &Path,
+    name: &str,
+    dir: &Path,
+    udev: &HashMap<String, String>,
+) -> PhysicalDisk {
+    let device = dir.join("device");
+    // /sys/devices/pci0000:00/0000:00:17.0/ata1/host0/target0:0:0/0:0:0:0/block/sda
+    let components: Vec<String> = std::fs::canonicalize(dir)
+        .ok()
+        .and_then(|real| {
+            let base = std::fs::canonicalize(root.join("sys/devices")).ok()?;
+            real.strip_prefix(base).ok().map(|p| {
+                p.components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect()
+            })
+        })
+        .unwrap_or_default();
+
+    let pci_address = components.iter().rev().find(|c| is_pci_address(c)).cloned();
+    let nvme_ctrl = components.iter().rev().find(|c| {
+        c.strip_prefix("nvme")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|ch| ch.is_ascii_digit()))
+    });
+    let scsi_host = components.iter().rev().find(|c| {
+        c.strip_prefix("host")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|ch| ch.is_ascii_digit()))
+    });
+
+    let (interface, controller) = if name.starts_with("nvme") {
+        (StorageInterface::NVMe, nvme_ctrl.cloned())
+    } else if name.starts_with("vd") {
+        (StorageInterface::Virtio, None)
+    } else if components.iter().any(|c| c.starts_with("usb")) {
+        (StorageInterface::USB, scsi_host.cloned())
+    } else if let Some(host) = scsi_host {
+        let driver = read_trimmed(&root.join(format!("sys/class/scsi_host/{}/proc_name", host)));
+        let iface = match driver {
+            Some(d) => StorageInterface::from_scsi_driver(&d),
+            None if components.iter().any(|c| c.starts_with("ata")) => StorageInterface::AHCI,
+            None => StorageInterface::SCSI,
+        };
+        (iface, Some(host.clone()))
+    } else {
+        (StorageInterface::Unknown, None)
+    };
+
+    PhysicalDisk {
+        model: read_trimmed(&device.join("model")).or_else(|| udev.get("ID_MODEL").cloned()),
+        serial: read_trimmed(&device.join("serial"))
+            .or_else(|| udev.get("ID_SERIAL_SHORT").cloned()),
+        interface,
+        controller,
+        pci_address,
+        rotational: read_trimmed(&dir.join("queue/rotational")).as_deref() == Some("1"),
+    }
+}
+
+fn is_pci_address(s: &str) -> bool {
+    // dddd:bb:dd.f
+    let b = s.as_bytes();
+    b.len() == 12
+        && b[4] == b':'
+        && b[7] == b':'
+        && b[10] == b'.'
+        && s.chars()
+            .enumerate()
+            .all(|(i, c)| matches!(i, 4 | 7 | 10) || c.is_ascii_hexdigit())
+}
+
+fn read_pools(root: &Path, devices: &[BlockDevice]) -> Vec<FsPool> {
+    let mut pools = Vec::new();
+
+    let btrfs = root.join("sys/fs/btrfs");
+    for uuid in list_names(&btrfs) {
+        let members = list_names(&btrfs.join(&uuid).join("devices"));
+        if members.is_empty() {
+            continue;
+        }
+        let label = read_trimmed(&btrfs.join(&uuid).join("label"));
+        pools.push(FsPool {
+            kind: PoolKind::Btrfs,
+            name: label.unwrap_or_else(|| uuid.clone()),
+            uuid: Some(uuid),
+            members,
+            state: None,
+        });
+    }
+
+    let mut zfs: BTreeMap<String, (Option<String>, Vec<String>)> = BTreeMap::new();
+    for d in devices
+        .iter()
+        .filter(|d| d.fs_type.as_deref() == Some("zfs_member"))
+    {
+        if let Some(pool) = &d.fs_label {
+            let entry = zfs.entry(pool.clone()).or_default();
+            entry.0 = entry.0.take().or_else(|| d.fs_uuid.clone());
+            entry.1.push(d.name.clone());
+        }
+    }
+    for (name, (uuid, members)) in zfs {
+        let state = read_trimmed(&root.join(format!("proc/spl/kstat/zfs/{}/state", name)));
+        pools.push(FsPool {
+            kind: PoolKind::Zfs,
+            name,
+            uuid,
+            members,
+            state,
+        });
+    }
+    pools
+}
+
+/// Undo the octal escaping of spaces, tabs and backslashes in mountinfo
+fn unescape_mount(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(pos) = rest.find('\\') {
+        out.push_str(&rest[..pos]);
+        let code = rest.get(pos + 1..pos + 4);
+        match code.and_then(|c| u8::from_str_radix(c, 8).ok()) {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::FakeRoot;
+
+    /// Block device at `sys/devices/<parent>/block/<name>`, linked from /sys/block
+    fn block(fs: &FakeRoot, parent: &str, name: &str, dev: &str, sectors: u64) -> String {
+        let dir = format!("sys/devices/{}/block/{}", parent, name);
+        fs.write(&format!("{}/dev", dir), dev);
+        fs.write(&format!("{}/size", dir), &sectors.to_string());
+        fs.write(&format!("{}/stat", dir), "0 0 0 0 0 0 0 0 0 0 0");
+        fs.symlink(&dir, &format!("sys/block/{}", name));
+        dir
+    }
+
+    fn disk(fs: &FakeRoot, parent: &str, name: &str, dev: &str, model: &str) -> String {
+        let dir = block(fs, parent, name, dev, 1 << 30);
+        fs.write(&format!("{}/device/model", dir), model);
+        fs.write(&format!("{}/queue/rotational", dir), "0");
+        dir
+    }
+
+    fn partition(fs: &FakeRoot, disk_dir: &str, name: &str, number: u32, dev: &str) -> String {
+        let dir = format!("{}/{}", disk_dir, name);
+        fs.write(&format!("{}/partition", dir), &number.to_string());
+        fs.write(&format!("{}/dev", dir), dev);
+        fs.write(&format!("{}/size", dir), "1000");
+        dir
+    }
+
+    /// Record `upper` as built on `lower` in both directions
+    fn stack(fs: &FakeRoot, lower_dir: &str, lower: &str, upper_dir: &str, upper: &str) {
+        fs.write(&format!("{}/holders/{}", lower_dir, upper), "");
+        fs.write(&format!("{}/slaves/{}", upper_dir, lower), "");
+    }
+
+    fn udev(fs: &FakeRoot, dev: &str, props: &[(&str, &str)]) {
+        let text: String = props
+            .iter()
+            .map(|(k, v)| format!("E:{}={}\n", k, v))
+            .collect();
+        fs.write(&format!("run/udev/data/b{}", dev), &text);
+    }
+
+    /// NVMe → LUKS → LVM (/ and /data), SATA raid1 (/srv) with a faulty
+    /// member, a two-disk btrfs (/bulk) and a ZFS mirror (/tank/media)
+    fn fixture() -> FakeRoot {
+        let fs = FakeRoot::new("storage-topology");
+        fs.write("sys/class/scsi_host/host0/proc_name", "ahci");
+
+        let nvme = disk(
+            &fs,
+            "pci0000:00/0000:00:01.0/0000:01:00.0/nvme/nvme0",
+            "nvme0n1",
+            "259:0",
+            "Samsung SSD 990 PRO",
+        );
+        fs.write(&format!("{}/device/serial", nvme), "S6Z1NJ0W123456");
+        partition(&fs, &nvme, "nvme0n1p1", 1, "259:1");
+        let p2 = partition(&fs, &nvme, "nvme0n1p2", 2, "259:2");
+        udev(&fs, "259:2", &[("ID_FS_TYPE", "crypto_LUKS")]);
+
+        let crypt = block(&fs, "virtual", "dm-0", "253:0", 900);
+        fs.write(&format!("{}/dm/name", crypt), "luks-0f3c");
+        fs.write(
+            &format!("{}/dm/uuid", crypt),
+            "CRYPT-LUKS2-0f3c9a1be2d84b7c8a4f5e6d7c8b9a01-luks-0f3c",
+        );
+        stack(&fs, &p2, "nvme0n1p2", &crypt, "dm-0");
+        for (name, dev, dm) in [
+            ("dm-1", "253:1", "vg0-root"),
+            ("dm-2", "253:2", "vg0-data--set"),
+        ] {
+            let lv = block(&fs, "virtual", name, dev, 400);
+            fs.write(&format!("{}/dm/name", lv), dm);
+            fs.write(&format!("{}/dm/uuid", lv), "LVM-abcdef");
+            stack(&fs, &crypt, "dm-0", &lv, name);
+        }
+
+        let ahci = "pci0000:00/0000:00:17.0/ata1/host0/target0:0:0";
+        let sda = disk(
+            &fs,
+            &format!("{}/0:0:0:0", ahci),
+            "sda",
+            "8:0",
+            "WDC WD40EFRX",
+        );
+        udev(&fs, "8:0", &[("ID_SERIAL_SHORT", "WD-WCC4E1234567")]);
+        let sdb = disk(
+            &fs,
+            &format!("{}/0:0:1:0", ahci),
+            "sdb",
+            "8:16",
+            "WDC WD40EFRX",
+        );
+        let sda1 = partition(&fs, &sda, "sda1", 1, "8:1");
+        let sdb1 = partition(&fs, &sdb, "sdb1", 1, "8:17");
+        let md = block(&fs, "virtual", "md0", "9:0", 1000);
+        fs.write(&format!("{}/md/level", md), "raid1");
+        fs.write(&format!("{}/md/raid_disks", md), "2");
+        fs.write(&format!("{}/md/degraded", md), "1");
+        fs.write(&format!("{}/md/array_state", md), "clean");
+        fs.write(&format!("{}/md/sync_action", md), "idle");
+        fs.write(&format!("{}/md/sync_completed", md), "none");
+        fs.write(&format!("{}/md/dev-sda1/state", md), "in_sync");
+        fs.write(&format!("{}/md/dev-sdb1/state", md), "faulty,write_error");
+        stack(&fs, &sda1, "sda1", &md, "md0");
+        stack(&fs, &sdb1, "sdb1", &md, "md0");
+
+        disk(&fs, &format!("{}/0:0:2:0", ahci), "sdc", "8:32", "ST8000");
+        disk(&fs, &format!("{}/0:0:3:0", ahci), "sdd", "8:48", "ST8000");
+        fs.write("sys/fs/btrfs/7e1b/label", "bulk");
+        fs.write("sys/fs/btrfs/7e1b/devices/sdc", "");
+        fs.write("sys/fs/btrfs/7e1b/devices/sdd", "");
+
+        for (target, name, ddev, pdev) in [(4, "sde", "8:64", "8:65"), (5, "sdf", "8:80", "8:81")] {
+            let dir = disk(
+                &fs,
+                &format!("{}/0:0:{}:0", ahci, target),
+                name,
+                ddev,
+                "HGST",
+            );
+            partition(&fs, &dir, &format!("{}1", name), 1, pdev);
+            udev(
+                &fs,
+                pdev,
+                &[
+                    ("ID_FS_TYPE", "zfs_member"),
+                    ("ID_FS_LABEL", "tank"),
+                    ("ID_FS_UUID", "1234"),
+                ],
+            );
+        }
+        fs.write("proc/spl/kstat/zfs/tank/state", "DEGRADED\n");
+
+        fs.write(
+            "proc/self/mountinfo",
+            "22 1 253:1 / / rw,relatime shared:1 - ext4 /dev/mapper/vg0-root rw\n\
+             23 22 259:1 / /boot/efi rw shared:2 - vfat /dev/nvme0n1p1 rw\n\
+             24 22 253:2 / /data rw shared:3 - xfs /dev/mapper/vg0-data--set rw\n\
+             25 22 9:0 / /srv rw shared:4 - ext4 /dev/md0 rw\n\
+             26 22 0:45 / /bulk rw shared:5 - btrfs /dev/sdc rw,space_cache=v2\n\
+             27 22 0:46 / /tank/media rw shared:6 - zfs tank/media rw,xattr\n\
+             28 22 0:22 / /proc rw shared:7 - proc proc rw\n\
+             29 22 253:2 /exports /srv/my\\040share rw shared:3 - xfs /dev/mapper/vg0-data--set rw\n",
+        );
+        fs
+    }
+
+    #[test]
+    fn test_parse_helpers() {
+        assert_eq!(split_lvm_name("vg0-root"), ("vg0".into(), "root".into()));
+        assert_eq!(
+            split_lvm_name("my--vg-data--set"),
+            ("my-vg".into(), "data-set".into())
+        );
+        assert_eq!(unescape_mount("/srv/my\\040share"), "/srv/my share");
+        assert!(is_pci_address("0000:01:00.0"));
+        assert!(!is_pci_address("target0:0:0"));
+
+        let c = IoCounters::parse("100 0 2048 5 50 0 4096 9 1 500 600").unwrap();
+        assert_eq!(
+            (c.read_bytes, c.write_bytes, c.in_flight),
+            (2048 * 512, 4096 * 512, 1)
+        );
+        let later = IoCounters {
+            read_bytes: c.read_bytes + 1_000_000,
+            io_ticks_ms: c.io_ticks_ms + 250,
+            ..c
+        };
+        let rates = later.rates_since(&c, 0.5);
+        assert_eq!(rates.read_bytes_per_sec, 2_000_000.0);
+        assert_eq!(rates.utilization, 50.0);
+    }
+
+    #[test]
+    fn test_layers_and_controllers() {
+        let fs = fixture();
+        let topo = StorageTopology::from_root(fs.path()).unwrap();
+
+        let nvme = topo.device("nvme0n1").unwrap().disk().unwrap().clone();
+        assert_eq!(nvme.interface, StorageInterface::NVMe);
+        assert_eq!(nvme.controller.as_deref(), Some("nvme0"));
+        assert_eq!(nvme.pci_address.as_deref(), Some("0000:01:00.0"));
+        assert_eq!(nvme.serial.as_deref(), Some("S6Z1NJ0W123456"));
+
+        let sda = topo.device("sda").unwrap().disk().unwrap();
+        assert_eq!(sda.interface, StorageInterface::AHCI);
+        assert_eq!(sda.controller.as_deref(), Some("host0"));
+        assert_eq!(sda.serial.as_deref(), Some("WD-WCC4E1234567"));
+
+        assert_eq!(
+            topo.device("dm-2").unwrap().layer,
+            BlockLayer::Lvm {
+                vg: "vg0".into(),
+                lv: "data-set".into()
+            }
+        );
+        assert_eq!(
+            topo.resolve("/dev/mapper/vg0-data--set").unwrap().name,
+            "dm-2"
+        );
+        assert!(matches!(
+            &topo.device("dm-0").unwrap().layer,
+            BlockLayer::Crypt { luks_uuid: Some(id) } if id.starts_with("0f3c9a1b")
+        ));
+        let BlockLayer::Md(md) = &topo.device("md0").unwrap().layer else {
+            panic!("md0 not an md array");
+        };
+        assert_eq!(md.level, RaidLevel::Raid1);
+        assert_eq!(md.faulty, vec!["sdb1".to_string()]);
+        assert!(md.is_degraded() && md.sync_action.is_none());
+
+        assert_eq!(
+            topo.device("nvme0n1").unwrap().upper,
+            vec!["nvme0n1p1", "nvme0n1p2"]
+        );
+        let zfs = topo.pool("tank").unwrap();
+        assert_eq!(zfs.members, vec!["sde1", "sdf1"]);
+        assert!(zfs.is_degraded());
+        assert_eq!(topo.pool("bulk").unwrap().members, vec!["sdc", "sdd"]);
+    }
+
+    #[test]
+    fn test_mount_backing() {
+        let fs = fixture();
+        let topo = StorageTopology::from_root(fs.path()).unwrap();
+        // Pseudo filesystems have no block devices
+        assert_eq!(topo.mount_for("/proc/self").unwrap().mount_point, "/");
+
+        let data = topo.backing("/data/models/checkpoint.pt").unwrap();
+        assert_eq!(data.mount.mount_point, "/data");
+        let stack: Vec<&str> = data.stack.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(stack, vec!["dm-2", "dm-0", "nvme0n1p2", "nvme0n1"]);
+        assert_eq!(data.disks.len(), 1);
+        assert!(data.degraded.is_empty());
+
+        let srv = topo.backing("/srv/www").unwrap();
+        assert_eq!(
+            srv.disks
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["sda", "sdb"]
+        );
+        assert_eq!(srv.degraded.len(), 1);
+        assert!(
+            srv.degraded[0].contains("faulty sdb1"),
+            "{}",
+            srv.degraded[0]
+        );
+        // Bind mount with an escaped space resolves to the same LV
+        assert_eq!(
+            topo.backing("/srv/my share/x").unwrap().stack[0].name,
+            "dm-2"
+        );
+
+        let bulk = topo.backing("/bulk").unwrap();
+        assert_eq!(bulk.mount.pool.as_deref(), Some("bulk"));
+        assert_eq!(bulk.disks.len(), 2);
+
+        let media = topo.backing("/tank/media").unwrap();
+        assert_eq!(
+            media
+                .disks
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["sde", "sdf"]
+        );
+        assert_eq!(
+            media.degraded,
+            vec!["ZFS pool tank is DEGRADED".to_string()]
+        );
+
+        // Reverse lookup: losing the NVMe takes out /, /boot/efi, /data and the bind mount
+        let affected: Vec<&str> = topo
+            .mounts_on("nvme0n1")
+            .iter()
+            .map(|m| m.mount_point.as_str())
+            .collect();
+        assert_eq!(affected, vec!["/", "/boot/efi", "/data", "/srv/my share"]);
+        assert!(topo.mount_io_counters("/data").is_some());
+    }
+
+    #[test]
+    fn test_smart_matching() {
+        use crate::smart::{DiskHealth, DriveMediaType};
+        let fs = fixture();
+        let topo = StorageTopology::from_root(fs.path()).unwrap();
+        let smart = |device: &str, serial: &str, health: DiskHealth| SmartDiskInfo {
+            device: device.into(),
+            model: String::new(),
+            serial: serial.into(),
+            firmware: String::new(),
+            media_type: DriveMediaType::Unknown,
+            capacity_bytes: 0,
+            health,
+            temperature_celsius: 40,
+            power_on_hours: 0,
+            power_cycle_count: 0,
+            reallocated_sectors: 0,
+            pending_sectors: 0,
+            uncorrectable_errors: 0,
+            wear_leveling_percent: None,
+            total_bytes_written: 0,
+            total_bytes_read: 0,
+            nvme_percentage_used: None,
+            nvme_available_spare: None,
+            attributes: Vec::new(),
+            estimated_life_remaining: None,
+            estimated_days_remaining: None,
+        };
+        // SMART lists NVMe by controller; SATA matched by serial despite a different name
+        let disks = vec![
+            smart("/dev/nvme0", "", DiskHealth::Good),
+            smart("/dev/sdx", "WD-WCC4E1234567", DiskHealth::Critical),
+        ];
+
+        let data = topo.backing("/data").unwrap();
+        let health = data.disk_health(&disks);
+        assert_eq!(health[0].1.unwrap().device, "/dev/nvme0");
+        assert!(data.is_healthy(&disks));
+
+        let srv = topo.backing("/srv").unwrap();
+        let health = srv.disk_health(&disks);
+        assert_eq!(health[0].1.unwrap().health, DiskHealth::Critical);
+        assert!(health[1].1.is_none());
+        assert!(!srv.is_healthy(&disks));
+    }
+}
@@ -0,0 +1,75 @@
+//! Shared test fixtures
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Temporary directory standing in for `/`, `/sys` or `/proc`, removed on drop
+///
+/// Each fixture gets its own directory, so tests running in parallel never share
+/// files even when they pass the same tag.
+pub(crate) struct FakeRoot(PathBuf);
+
+impl FakeRoot {
+    /// Create an empty tree named after `tag`, the process and a per-process counter
+    pub(crate) fn new(tag: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "simon-{}-{}-{}",
+            tag,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        Self(root)
+    }
+
+    /// Root of the tree
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Absolute path of `rel` inside the tree
+    pub(crate) fn join(&self, rel: &str) -> PathBuf {
+        self.0.join(rel)
+    }
+
+    /// Write `rel`, creating parent directories as needed
+    pub(crate) fn write(&self, rel: &str, contents: &str) {
+        let path = self.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// Append to an existing file
+    pub(crate) fn append(&self, rel: &str, contents: &str) {
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(self.join(rel))
+            .unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+    }
+
+    /// Contents of `rel` with surrounding whitespace trimmed
+    pub(crate) fn read(&self, rel: &str) -> String {
+        std::fs::read_to_string(self.join(rel))
+            .unwrap()
+            .trim()
+            .to_string()
+    }
+
+    /// Symlink `link` to `target`, both relative to the root
+    #[cfg(unix)]
+    pub(crate) fn symlink(&self, target: &str, link: &str) {
+        let link = self.join(link);
+        std::fs::create_dir_all(link.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(self.join(target), link).unwrap();
+    }
+}
+
+impl Drop for FakeRoot {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -133,6 +133,34 @@ pub struct SystemSnapshot {
     pub processes: Vec<ProcessSnapshot>,
 }
 
+/// Space and inode usage of one mounted filesystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilesystemUsage {
+    /// Mount point
+    pub mount_point: String,
+    /// Filesystem type
+    pub fs_type: String,
+    /// Total size in bytes
+    pub total_bytes: u64,
+    /// Used bytes
+    pub used_bytes: u64,
+    /// Bytes available to unprivileged users
+    pub available_bytes: u64,
+    /// Total inodes (0 if the filesystem has no fixed inode table)
+    pub total_inodes: u64,
+    /// Used inodes
+    pub used_inodes: u64,
+}
+
+/// Filesystem usage of all mounts at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilesystemSnapshot {
+    /// Timestamp (Unix milliseconds)
+    pub timestamp: u64,
+    /// Per-mount usage
+    pub filesystems: Vec<FilesystemUsage>,
+}
+
 /// Database header stored at the beginning of the file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DatabaseHeader {
@@ -178,6 +206,7 @@ impl Default for DatabaseHeader {
 enum RecordType {
     System = 1,
     Process = 2,
+    Filesystem = 3,
 }
 
 /// Time-Series Database for recording metrics
@@ -317,6 +346,17 @@ impl TimeSeriesDb {
         Ok(())
     }
 
+    /// Record a filesystem usage snapshot
+    pub fn record_filesystems(&mut self, snapshot: &FilesystemSnapshot) -> Result<()> {
+        let data = bincode::serialize(snapshot).map_err(|e| {
+            SimonError::Other(format!("Failed to serialize filesystem snapshot: {}", e))
+        })?;
+
+        self.write_record(RecordType::Filesystem, &data, snapshot.timestamp)?;
+
+        Ok(())
+    }
+
     /// Write a record to the database
     fn write_record(&mut self, record_type: RecordType, data: &[u8], timestamp: u64) -> Result<()> {
         // Record format: [type: 1 byte][length: 4 bytes][data: N bytes]
@@ -405,10 +445,16 @@ impl TimeSeriesDb {
         // Read all records
         let records = self.read_all_system_snapshots()?;
         let records_len = records.len();
+        let filesystems = self.read_filesystem_snapshots()?;
+        let filesystems_len = filesystems.len();
 
-        // Keep the most recent 50%
+        // Keep the most recent 50% of each record type
         let keep_count = records_len / 2;
         let to_keep: Vec<_> = records.into_iter().skip(records_len - keep_count).collect();
+        let fs_to_keep: Vec<_> = filesystems
+            .into_iter()
+            .skip(filesystems_len - filesystems_len / 2)
+            .collect();
 
         // Close and recreate
         self.file = None;
@@ -420,6 +466,9 @@ impl TimeSeriesDb {
         for snapshot in to_keep {
             self.record_system(&snapshot)?;
         }
+        for snapshot in fs_to_keep {
+            self.record_filesystems(&snapshot)?;
+        }
 
         log::info!(
             "Rotation complete, kept {} records",
@@ -431,12 +480,43 @@ impl TimeSeriesDb {
 
     /// Read all system snapshots from the database
     pub fn read_all_system_snapshots(&mut self) -> Result<Vec<SystemSnapshot>> {
+        Ok(self
+            .read_records(RecordType::System)?
+            .iter()
+            .filter_map(|data| bincode::deserialize::<SystemSnapshot>(data).ok())
+            .collect())
+    }
+
+    /// Read all filesystem usage snapshots from the database
+    pub fn read_filesystem_snapshots(&mut self) -> Result<Vec<FilesystemSnapshot>> {
+        Ok(self
+            .read_records(RecordType::Filesystem)?
+            .iter()
+            .filter_map(|data| bincode::deserialize::<FilesystemSnapshot>(data).ok())
+            .collect())
+    }
+
+    /// Query filesystem snapshots within a time range
+    pub fn query_filesystem_range(
+        &mut self,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<Vec<FilesystemSnapshot>> {
+        let all = self.read_filesystem_snapshots()?;
+        Ok(all
+            .into_iter()
+            .filter(|s| s.timestamp >= start_time && s.timestamp <= end_time)
+            .collect())
+    }
+
+    /// Raw payloads of every record of one type, oldest first
+    fn read_records(&mut self, record_type: RecordType) -> Result<Vec<Vec<u8>>> {
         let file = self
             .file
             .as_mut()
             .ok_or_else(|| SimonError::Other("Database not open".to_string()))?;
 
-        let mut snapshots = Vec::new();
+        let mut records = Vec::new();
 
         // Seek to start of data
         file.seek(SeekFrom::Start(HEADER_SIZE))
@@ -465,15 +545,12 @@ impl TimeSeriesDb {
 
             offset += 1 + 4 + len as u64;
 
-            // Parse if system record
-            if type_byte[0] == RecordType::System as u8 {
-                if let Ok(snapshot) = bincode::deserialize::<SystemSnapshot>(&data) {
-                    snapshots.push(snapshot);
-                }
+            if type_byte[0] == record_type as u8 {
+                records.push(data);
             }
         }
 
-        Ok(snapshots)
+        Ok(records)
     }
 
     /// Query snapshots within a time range
@@ -634,6 +711,11 @@ impl MetricsRecorder {
         self.db.query_range(start, end)
     }
 
+    /// Record filesystem usage alongside system snapshots
+    pub fn record_filesystems(&mut self, snapshot: FilesystemSnapshot) -> Result<()> {
+        self.db.record_filesystems(&snapshot)
+    }
+
     /// Close the recorder
     pub fn close(&mut self) -> Result<()> {
         self.recording = false;
@@ -644,6 +726,7 @@ impl MetricsRecorder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::FakeRoot;
 
     #[test]
     fn test_parse_size() {
@@ -656,6 +739,36 @@ mod tests {
         assert_eq!(parse_size(" 100 MB ").unwrap(), 100 * 1024 * 1024);
     }
 
+    #[test]
+    fn test_filesystem_records() {
+        let root = FakeRoot::new("tsdb-fs");
+        let path = root.join("metrics.db");
+        let mut db = TimeSeriesDb::new(&path, 0).unwrap();
+        for i in 0..3u64 {
+            db.record_filesystems(&FilesystemSnapshot {
+                timestamp: 1_000 * (i + 1),
+                filesystems: vec![FilesystemUsage {
+                    mount_point: "/data".into(),
+                    fs_type: "xfs".into(),
+                    total_bytes: 1 << 40,
+                    used_bytes: i << 30,
+                    available_bytes: (1 << 40) - (i << 30),
+                    total_inodes: 1_000_000,
+                    used_inodes: 100 * i,
+                }],
+            })
+            .unwrap();
+        }
+
+        assert!(db.read_all_system_snapshots().unwrap().is_empty());
+        let all = db.read_filesystem_snapshots().unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2].filesystems[0].used_bytes, 2 << 30);
+        assert_eq!(db.query_filesystem_range(1_500, 3_000).unwrap().len(), 2);
+
+        db.close().unwrap();
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(500), "500 B");
@@ -1,9 +1,18 @@
 //! Application state management
 
 use crate::agent::{Agent, AgentConfig, AgentResponse};
+use crate::cpu_topology::{CoreMetrics, TopologySampler};
+use crate::energy_accounting::{
+    EnergyAccountant, EnergyAccountingConfig, EnergyCollector, EnergyReport,
+};
 use crate::gpu::traits::Device;
+use crate::io_scheduler::latency::{IoLatencyConfig, IoLatencyMonitor, IoLatencySnapshot};
+use crate::net_accounting::{NetAccountant, NetTrafficSnapshot};
 use crate::network_monitor::NetworkMonitor;
+use crate::perf::{PerfConfig, PerfMonitor, PerfSnapshot};
+use crate::psi::{PsiConfig, PsiMonitor, PsiSnapshot};
 use crate::silicon::NpuInfo;
+use crate::wireless::{WirelessConfig, WirelessMonitor, WirelessSnapshot};
 use crate::{ProcessMonitor, ProcessMonitorInfo, SiliconMonitor};
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
@@ -574,6 +583,38 @@ pub struct App {
     pub peripheral_cache: PeripheralCache,
     /// Last time peripheral cache was refreshed
     peripheral_cache_last_refresh: Instant,
+    /// Energy sampler fed from the process list and GPU power readings
+    energy_collector: EnergyCollector,
+    /// Per-process energy attribution accumulated since startup
+    energy_accountant: EnergyAccountant,
+    /// Latest energy attribution report
+    pub energy_report: Option<EnergyReport>,
+    /// CPU topology sampler (None where topology detection is unsupported)
+    pub cpu_topology: Option<TopologySampler>,
+    /// Per-CPU utilization, frequency and temperature from the topology sampler
+    pub core_metrics: Vec<CoreMetrics>,
+    /// System-wide hardware counters (None when perf_event access is denied)
+    perf_monitor: Option<PerfMonitor>,
+    /// Latest IPC / miss rate / memory bandwidth sample
+    pub perf_snapshot: Option<PerfSnapshot>,
+    /// Why perf counters are unavailable, shown in place of the metrics
+    pub perf_status: Option<String>,
+    /// Pressure stall sampler (None where PSI is unavailable)
+    psi_monitor: Option<PsiMonitor>,
+    /// Latest system-wide and per-cgroup pressure
+    pub psi_snapshot: Option<PsiSnapshot>,
+    /// Per-device await/queue sampler (None where /proc/diskstats is unavailable)
+    io_latency_monitor: Option<IoLatencyMonitor>,
+    /// Latest per-device latency, queue size and saturation warnings
+    pub io_latency_snapshot: Option<IoLatencySnapshot>,
+    /// Per-process TCP traffic accountant (None where sock_diag is unavailable)
+    net_accountant: Option<NetAccountant>,
+    /// Latest per-connection and per-process network rates
+    pub net_traffic: Option<NetTrafficSnapshot>,
+    /// WiFi link sampler (None where nl80211 is unavailable)
+    wireless_monitor: Option<WirelessMonitor>,
+    /// Latest WiFi links and recent roams
+    pub wireless: Option<WirelessSnapshot>,
 }
 
 /// Background initialization state
@@ -840,6 +881,15 @@ impl App {
             let _ = proc_tx.send(ProcessMonitor::new().ok());
         });
 
+        // Totals only; the CPU tab shows one line of derived metrics
+        let (perf_monitor, perf_status) = match PerfMonitor::new(PerfConfig {
+            per_cpu: false,
+            ..Default::default()
+        }) {
+            Ok(monitor) => (Some(monitor), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
         let mut app = Self {
             selected_tab: 0,
             tabs: vec![
@@ -851,6 +901,7 @@ impl App {
                 "System",
                 "Peripherals",
                 "Agent",
+                "Connections",
             ],
             cpu_history: VecDeque::with_capacity(MAX_HISTORY),
             memory_history: VecDeque::with_capacity(MAX_HISTORY),
@@ -893,6 +944,31 @@ impl App {
             selected_theme_idx: 0,
             peripheral_cache: PeripheralCache::default(),
             peripheral_cache_last_refresh: Instant::now() - Duration::from_secs(60), // force initial refresh
+            energy_collector: EnergyCollector::new(&EnergyAccountingConfig::default()),
+            energy_accountant: EnergyAccountant::new(EnergyAccountingConfig::default()),
+            energy_report: None,
+            cpu_topology: TopologySampler::new().ok(),
+            core_metrics: Vec::new(),
+            perf_monitor,
+            perf_snapshot: None,
+            perf_status,
+            // Triggers are the daemon's job; the TUI only samples
+            psi_monitor: PsiMonitor::new(PsiConfig {
+                triggers: Vec::new(),
+                ..Default::default()
+            })
+            .ok(),
+            psi_snapshot: None,
+            io_latency_monitor: IoLatencyMonitor::new(IoLatencyConfig {
+                cgroups: false,
+                ..Default::default()
+            })
+            .ok(),
+            io_latency_snapshot: None,
+            net_accountant: cfg!(target_os = "linux").then(NetAccountant::new),
+            net_traffic: None,
+            wireless_monitor: WirelessMonitor::new(WirelessConfig::default()).ok(),
+            wireless: None,
         };
 
         // Do initial fast update for immediate data (CPU, Memory are fast)
@@ -1024,6 +1100,12 @@ impl App {
     /// Fast updates - CPU, GPU, Memory, Network (called every 500ms)
     pub fn update_fast(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.update_cpu()?;
+        if let Some(ref mut sampler) = self.cpu_topology {
+            self.core_metrics = sampler.sample();
+        }
+        if let Some(ref mut perf) = self.perf_monitor {
+            self.perf_snapshot = Some(perf.sample());
+        }
         self.update_memory()?;
         self.update_gpu()?;
         self.update_network()?;
@@ -1034,6 +1116,16 @@ impl App {
     /// Slow updates - System, Disks, Processes (called every 2s)
     pub fn update_slow(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.update_system()?;
+        if let Some(ref mut psi) = self.psi_monitor {
+            self.psi_snapshot = Some(psi.sample());
+        }
+        if let Some(ref mut latency) = self.io_latency_monitor {
+            self.io_latency_snapshot = Some(latency.sample());
+        }
+        if let Some(ref mut wireless) = self.wireless_monitor {
+            wireless.sample();
+            self.wireless = Some(wireless.snapshot());
+        }
         self.update_disks()?;
         self.update_processes()?;
         // Refresh peripherals every 10 seconds (they're expensive due to subprocess calls)
@@ -1697,10 +1789,36 @@ impl App {
             self.processes = monitor.processes().unwrap_or_default();
             // Rebuild cached process order for efficient scrolling
             self.rebuild_cached_process_order();
+            self.update_energy();
         }
+        self.update_net_traffic();
         Ok(())
     }
 
+    /// Refresh per-process network rates alongside the process list
+    fn update_net_traffic(&mut self) {
+        if let Some(ref mut accountant) = self.net_accountant {
+            match accountant.refresh() {
+                Ok(snapshot) => self.net_traffic = Some(snapshot),
+                Err(_) => self.net_accountant = None,
+            }
+        }
+    }
+
+    /// Attribute energy since the last process refresh
+    fn update_energy(&mut self) {
+        let gpu_watts: Vec<Option<f64>> = self
+            .gpu_devices
+            .iter()
+            .map(|d| d.power().ok().map(|p| p.current as f64))
+            .collect();
+        let sample = self
+            .energy_collector
+            .sample_from(&self.processes, &gpu_watts);
+        self.energy_accountant.record(&sample);
+        self.energy_report = Some(self.energy_accountant.report());
+    }
+
     /// Rebuild the cached process order based on current display mode
     /// This is called when processes are updated or display mode changes
     fn rebuild_cached_process_order(&mut self) {
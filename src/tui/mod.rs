@@ -143,6 +143,7 @@ fn run_app<B: Backend>(
                                     KeyCode::Char('6') => app.set_tab(5),
                                     KeyCode::Char('7') => app.set_tab(6),
                                     KeyCode::Char('8') => app.set_tab(7),
+                                    KeyCode::Char('9') => app.set_tab(8),
                                     KeyCode::Left => app.previous_tab(),
                                     KeyCode::Right => app.next_tab(),
                                     KeyCode::Up => app.select_process_up(),
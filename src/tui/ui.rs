@@ -124,6 +124,7 @@ pub fn draw(f: &mut Frame, app: &App) {
         5 => draw_system_tab(f, app, chunks[1]),
         6 => draw_peripherals(f, app, chunks[1]),
         7 => draw_agent(f, app, chunks[1]),
+        8 => draw_connections_tab(f, app, chunks[1]),
         _ => {}
     }
 
@@ -275,10 +276,170 @@ fn draw_processes_tab(f: &mut Frame, app: &App, area: Rect) {
     draw_nvtop_processes(f, app, area);
 }
 
+/// Per-core lines grouped by package, die, L3 domain and core type, with one bar
+/// per SMT thread. None until the topology sampler has produced metrics.
+fn cpu_topology_lines(app: &App) -> Option<Vec<Line<'static>>> {
+    let sampler = app.cpu_topology.as_ref()?;
+    if app.core_metrics.is_empty() {
+        return None;
+    }
+    let topo = sampler.topology();
+    let metrics: std::collections::HashMap<u32, &crate::cpu_topology::CoreMetrics> =
+        app.core_metrics.iter().map(|m| (m.cpu, m)).collect();
+    let loads = topo.domain_loads(&app.core_metrics);
+    let header = Style::default()
+        .fg(glances_colors::TITLE)
+        .add_modifier(Modifier::BOLD);
+
+    let mut lines = Vec::new();
+    for pkg in topo.tree() {
+        let numa = if pkg.numa_nodes.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " (NUMA {})",
+                pkg.numa_nodes
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        };
+        lines.push(Line::from(Span::styled(
+            format!("Package {}{}", pkg.id, numa),
+            header,
+        )));
+        for die in &pkg.dies {
+            for l3 in &die.l3_groups {
+                let mut spans = vec![Span::styled(
+                    format!(" Die {} · {}", die.id, topo.l3_label(l3.l3_domain)),
+                    Style::default().fg(glances_colors::TITLE),
+                )];
+                if let Some(load) = loads
+                    .iter()
+                    .find(|l| l.package == pkg.id && l.die == die.id && l.l3_domain == l3.l3_domain)
+                {
+                    spans.push(Span::styled(
+                        format!("  avg {:>3.0}%", load.utilization),
+                        Style::default()
+                            .fg(threshold_color(load.utilization))
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                    spans.push(Span::raw(format!("  max {:>3.0}%", load.max_utilization)));
+                    if let Some(mhz) = load.frequency_mhz {
+                        spans.push(Span::raw(format!("  {} MHz", mhz)));
+                    }
+                    if let Some(t) = load.temperature {
+                        spans.push(Span::raw(format!("  {:.0}°C", t)));
+                    }
+                }
+                lines.push(Line::from(spans));
+
+                for group in &l3.groups {
+                    for core in &group.cores {
+                        let mut spans = vec![Span::styled(
+                            format!("   {} {:>3} ", group.core_type, core.core_id),
+                            Style::default().fg(Color::White),
+                        )];
+                        for &cpu in &core.threads {
+                            let usage = metrics.get(&cpu).map(|m| m.utilization).unwrap_or(0.0);
+                            let bar_width: usize = 10;
+                            let filled = (usage / 100.0 * bar_width as f32) as usize;
+                            let bar = "█".repeat(filled.min(bar_width))
+                                + &"░".repeat(bar_width.saturating_sub(filled));
+                            spans.push(Span::styled(
+                                format!(" cpu{:<3}", cpu),
+                                Style::default().fg(Color::DarkGray),
+                            ));
+                            spans.push(Span::styled(
+                                bar,
+                                Style::default().fg(threshold_color(usage)),
+                            ));
+                            spans.push(Span::styled(
+                                format!(" {:>3.0}%", usage),
+                                Style::default().fg(threshold_color(usage)),
+                            ));
+                        }
+                        if let Some(m) = core.threads.first().and_then(|c| metrics.get(c)) {
+                            if let Some(mhz) = m.frequency_mhz {
+                                spans.push(Span::raw(format!("  {:>4} MHz", mhz)));
+                            }
+                            if let Some(t) = m.temperature {
+                                spans.push(Span::raw(format!("  {:.0}°C", t)));
+                            }
+                        }
+                        lines.push(Line::from(spans));
+                    }
+                }
+            }
+        }
+    }
+    Some(lines)
+}
+
+/// One line of hardware counter metrics (IPC, LLC/branch miss rates, stalls,
+/// DRAM bandwidth), or why counters are unavailable
+fn perf_line(app: &App) -> Line<'static> {
+    use crate::perf::PerfCounter;
+    let label = Span::styled("Perf: ", Style::default().fg(glances_colors::TITLE));
+    let Some(snapshot) = &app.perf_snapshot else {
+        let reason = app
+            .perf_status
+            .clone()
+            .unwrap_or_else(|| "sampling...".to_string());
+        return Line::from(vec![
+            label,
+            Span::styled(reason, Style::default().fg(Color::DarkGray)),
+        ]);
+    };
+    let pct = |v: Option<f64>| match v {
+        Some(v) => format!("{:.1}%", v * 100.0),
+        None => "n/a".to_string(),
+    };
+    let total = &snapshot.total;
+    let mut spans = vec![label];
+    match total.ipc() {
+        Some(ipc) => spans.push(Span::styled(
+            format!("IPC {:.2}", ipc),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        None => spans.push(Span::raw("IPC n/a")),
+    }
+    spans.push(Span::raw(format!(
+        " │ LLC miss {} │ Branch miss {}",
+        pct(total.llc_miss_rate()),
+        pct(total.branch_miss_rate()),
+    )));
+    if let Some(be) = total.backend_stall_rate() {
+        spans.push(Span::raw(format!(
+            " │ Stalls FE {} BE {:.1}%",
+            pct(total.frontend_stall_rate()),
+            be * 100.0
+        )));
+    }
+    if let Some(cs) = snapshot.rate(PerfCounter::ContextSwitches) {
+        spans.push(Span::raw(format!(" │ {:.0} ctx/s", cs)));
+    }
+    if let Some(bw) = &snapshot.memory_bandwidth {
+        spans.push(Span::raw(format!(
+            " │ DRAM R {:.1} W {:.1} GB/s",
+            bw.read_bytes_per_sec / 1e9,
+            bw.write_bytes_per_sec / 1e9
+        )));
+    }
+    Line::from(spans)
+}
+
 /// CPU tab: detailed CPU info with per-core breakdown and history
 fn draw_cpu_tab(f: &mut Frame, app: &App, area: Rect) {
-    let core_count = app.cpu_info.per_core_usage.len();
-    let core_display_height = (core_count.min(32) + 3) as u16; // +3 for header line + border
+    let topology_lines = cpu_topology_lines(app);
+    let core_count = match topology_lines {
+        Some(ref lines) => lines.len(),
+        None => app.cpu_info.per_core_usage.len().min(32),
+    };
+    let core_display_height = (core_count + 4) as u16; // +4 for header and perf lines + border
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -303,33 +464,39 @@ fn draw_cpu_tab(f: &mut Frame, app: &App, area: Rect) {
             app.cpu_info.temperature.unwrap_or(0.0),
         )),
     ])];
+    info_lines.push(perf_line(app));
 
-    let per_core_lines: Vec<Line> = app
-        .cpu_info
-        .per_core_usage
-        .iter()
-        .enumerate()
-        .take(32)
-        .map(|(i, &usage)| {
-            let bar_width: usize = 30;
-            let filled = (usage / 100.0 * bar_width as f32) as usize;
-            let bar: String = "█".repeat(filled) + &"░".repeat(bar_width.saturating_sub(filled));
-            Line::from(vec![
-                Span::styled(
-                    format!("Core {:>2}: ", i),
-                    Style::default().fg(Color::White),
-                ),
-                Span::styled(bar, Style::default().fg(threshold_color(usage))),
-                Span::styled(
-                    format!(" {:>5.1}%", usage),
-                    Style::default()
-                        .fg(threshold_color(usage))
-                        .add_modifier(Modifier::BOLD),
-                ),
-            ])
-        })
-        .collect();
-    info_lines.extend(per_core_lines);
+    if let Some(lines) = topology_lines {
+        info_lines.extend(lines);
+    } else {
+        let per_core_lines: Vec<Line> = app
+            .cpu_info
+            .per_core_usage
+            .iter()
+            .enumerate()
+            .take(32)
+            .map(|(i, &usage)| {
+                let bar_width: usize = 30;
+                let filled = (usage / 100.0 * bar_width as f32) as usize;
+                let bar: String =
+                    "█".repeat(filled) + &"░".repeat(bar_width.saturating_sub(filled));
+                Line::from(vec![
+                    Span::styled(
+                        format!("Core {:>2}: ", i),
+                        Style::default().fg(Color::White),
+                    ),
+                    Span::styled(bar, Style::default().fg(threshold_color(usage))),
+                    Span::styled(
+                        format!(" {:>5.1}%", usage),
+                        Style::default()
+                            .fg(threshold_color(usage))
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ])
+            })
+            .collect();
+        info_lines.extend(per_core_lines);
+    }
 
     let info = Paragraph::new(info_lines)
         .block(Block::default().borders(Borders::ALL).title("CPU Details"))
@@ -415,11 +582,13 @@ fn draw_memory_tab(f: &mut Frame, app: &App, area: Rect) {
         .constraints([
             Constraint::Length(3),  // Memory bar gauge
             Constraint::Length(10), // Detailed memory info
+            Constraint::Length(7),  // Pressure stall information
             Constraint::Min(5),     // Memory history sparkline
         ])
         .split(area);
 
     draw_memory_bar(f, app, chunks[0]);
+    draw_pressure_panel(f, app, chunks[2]);
 
     // Detailed memory info with visual bars
     let used_gb = app.memory_info.used as f64 / (1024.0 * 1024.0 * 1024.0);
@@ -516,8 +685,346 @@ fn draw_memory_tab(f: &mut Frame, app: &App, area: Rect) {
             )
             .data(&mem_data)
             .style(Style::default().fg(threshold_color(mem_pct as f32)));
-        f.render_widget(sparkline, chunks[2]);
+        f.render_widget(sparkline, chunks[3]);
+    }
+}
+
+/// Pressure stall per resource (avg10/60/300 for some and full) and the
+/// cgroups stalling the most
+fn draw_pressure_panel(f: &mut Frame, app: &App, area: Rect) {
+    use crate::psi::PsiResource;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Pressure Stall (avg10 / avg60 / avg300)");
+    let Some(ref snapshot) = app.psi_snapshot else {
+        let text = if cfg!(target_os = "linux") {
+            "PSI unavailable (needs CONFIG_PSI)"
+        } else {
+            "PSI requires Linux"
+        };
+        let empty = Paragraph::new(text)
+            .block(block)
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let fmt_line = |line: &crate::psi::PressureLine| {
+        format!(
+            "{:>5.1}% {:>5.1}% {:>5.1}%",
+            line.avg10, line.avg60, line.avg300
+        )
+    };
+    let mut lines = Vec::new();
+    // Stall percentages are scaled onto the utilization color bands so "some"
+    // turns red near 45% and "full" near 18%
+    for p in &snapshot.system.resources {
+        let (full, full_avg10) = match p.full {
+            Some(ref full) => (fmt_line(full), full.avg10),
+            None => ("    -".to_string(), 0.0),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<7}", p.resource.name()),
+                Style::default().fg(glances_colors::TITLE),
+            ),
+            Span::raw("some "),
+            Span::styled(
+                fmt_line(&p.some),
+                Style::default().fg(threshold_color((p.some.avg10 * 2.0) as f32)),
+            ),
+            Span::raw("  full "),
+            Span::styled(
+                full,
+                Style::default().fg(threshold_color((full_avg10 * 5.0) as f32)),
+            ),
+        ]));
+    }
+
+    let top: Vec<String> = [PsiResource::Memory, PsiResource::Io, PsiResource::Cpu]
+        .iter()
+        .filter_map(|&r| {
+            let (cg, v) = snapshot.top_cgroups(r, 1).into_iter().next()?;
+            Some(format!("{} {} {:.1}%", r, cg.scope, v))
+        })
+        .collect();
+    lines.push(Line::from(vec![
+        Span::styled("Top: ", Style::default().fg(glances_colors::TITLE)),
+        Span::raw(if top.is_empty() {
+            "no cgroup under pressure".to_string()
+        } else {
+            top.join(" │ ")
+        }),
+    ]));
+
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::White));
+    f.render_widget(panel, area);
+}
+
+/// Energy attributed since startup: node total, then the top consumers
+/// (systemd units and containers first, falling back to processes)
+fn draw_energy_panel(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title("Energy");
+    let Some(ref report) = app.energy_report else {
+        let empty = Paragraph::new("Collecting...")
+            .block(block)
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled("Node: ", Style::default().fg(glances_colors::TITLE)),
+        Span::raw(format!(
+            "{:.4} kWh  {:.4} {}  {:.1} gCO2",
+            report.node.kwh, report.node.cost, report.currency, report.node.co2_grams
+        )),
+    ])];
+    if !report.cpu_measured {
+        lines.push(Line::from(Span::styled(
+            "CPU energy unavailable (no RAPL)",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let max_rows = (area.height as usize).saturating_sub(2 + lines.len());
+    let mut consumers: Vec<(&str, f64)> = report
+        .units
+        .iter()
+        .chain(report.containers.iter())
+        .map(|u| (u.name.as_str(), u.total_joules))
+        .collect();
+    if consumers.is_empty() {
+        consumers = report
+            .processes
+            .iter()
+            .map(|p| (p.usage.name.as_str(), p.usage.total_joules))
+            .collect();
+    }
+    consumers.sort_by(|a, b| b.1.total_cmp(&a.1));
+    for (name, joules) in consumers.into_iter().take(max_rows) {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<24.24} ", name),
+                Style::default().fg(glances_colors::TITLE),
+            ),
+            Span::raw(format!("{:>10.1} J", joules)),
+        ]));
+    }
+
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::White));
+    f.render_widget(panel, area);
+}
+
+/// Per-device await, windowed p99, queue size and utilization (slowest
+/// first), with any saturation warnings on the last line
+fn draw_disk_latency_panel(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Disk Latency (await / p99 / aqu-sz / util)");
+    let snapshot = match app.io_latency_snapshot {
+        Some(ref s) if !s.devices.is_empty() => s,
+        _ => {
+            let text = if cfg!(target_os = "linux") {
+                "Collecting..."
+            } else {
+                "I/O latency requires Linux"
+            };
+            let empty = Paragraph::new(text)
+                .block(block)
+                .style(Style::default().fg(Color::DarkGray));
+            f.render_widget(empty, area);
+            return;
+        }
+    };
+
+    let fmt_ms = |ms: Option<f64>| match ms {
+        Some(ms) if ms >= 100.0 => format!("{:>5.0}ms", ms),
+        Some(ms) => format!("{:>5.1}ms", ms),
+        None => "     -".to_string(),
+    };
+    let warning_rows = usize::from(!snapshot.warnings.is_empty());
+    let max_rows = (area.height as usize).saturating_sub(2 + warning_rows);
+    let mut lines = Vec::new();
+    for d in snapshot.slowest().into_iter().take(max_rows) {
+        let saturated = snapshot.warnings.iter().any(|w| w.device == d.device);
+        let await_color = if saturated {
+            glances_colors::CRITICAL
+        } else {
+            Color::White
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<10.10} ", d.device),
+                Style::default().fg(glances_colors::TITLE),
+            ),
+            Span::styled(fmt_ms(d.await_ms), Style::default().fg(await_color)),
+            Span::raw(format!(" {} {:>6.1} ", fmt_ms(d.p99_ms), d.avg_queue_size)),
+            Span::styled(
+                format!("{:>3.0}%", d.utilization),
+                Style::default().fg(threshold_color(d.utilization as f32)),
+            ),
+        ]));
+    }
+    if !snapshot.warnings.is_empty() {
+        let kinds: Vec<String> = snapshot
+            .warnings
+            .iter()
+            .map(|w| format!("{} {}", w.device, w.kind))
+            .collect();
+        lines.push(Line::from(Span::styled(
+            format!("⚠ {}", kinds.join(", ")),
+            Style::default().fg(glances_colors::CRITICAL),
+        )));
+    }
+
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::White));
+    f.render_widget(panel, area);
+}
+
+/// WiFi links: association, signal/noise, bitrates, retries and the last roam
+fn draw_wireless_panel(f: &mut Frame, app: &App, area: Rect) {
+    use crate::wireless::SignalQuality;
+
+    let block = Block::default().borders(Borders::ALL).title("WiFi");
+    let Some(ref snapshot) = app.wireless else {
+        f.render_widget(block, area);
+        return;
+    };
+
+    let quality_color = |quality: Option<SignalQuality>| match quality {
+        Some(SignalQuality::Excellent) => glances_colors::OK,
+        Some(SignalQuality::Good) => glances_colors::CAREFUL,
+        Some(SignalQuality::Fair) => glances_colors::WARNING,
+        Some(SignalQuality::Poor) => glances_colors::CRITICAL,
+        None => Color::DarkGray,
+    };
+    let label =
+        |text: &str| Span::styled(text.to_string(), Style::default().fg(glances_colors::TITLE));
+    let mut lines = Vec::new();
+    for link in &snapshot.links {
+        let Some(ref station) = link.station else {
+            lines.push(Line::from(vec![
+                label(&format!("{}: ", link.interface)),
+                Span::styled(
+                    format!("not associated ({})", link.mode.as_deref().unwrap_or("?")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+            continue;
+        };
+
+        let mut channel = Vec::new();
+        if let Some(ch) = link.channel {
+            channel.push(format!("ch {}", ch));
+        }
+        if let Some(band) = link.band {
+            channel.push(band.to_string());
+        }
+        if let Some(width) = link.channel_width_mhz {
+            channel.push(format!("{} MHz", width));
+        }
+        lines.push(Line::from(vec![
+            label(&format!("{}: ", link.interface)),
+            Span::styled(
+                link.ssid.clone().unwrap_or_else(|| "<hidden>".into()),
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    " {} │ {}",
+                    link.bssid.as_deref().unwrap_or("?"),
+                    channel.join(" ")
+                ),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+
+        let mut signal = vec![
+            Span::raw("  Signal "),
+            Span::styled(
+                link.signal_dbm()
+                    .map(|s| format!("{} dBm", s))
+                    .unwrap_or_else(|| "-".into()),
+                Style::default().fg(quality_color(link.quality())),
+            ),
+        ];
+        if let Some(noise) = link.noise_dbm {
+            signal.push(Span::raw(format!(" │ Noise {} dBm", noise)));
+        }
+        if let Some(snr) = link.snr_db() {
+            signal.push(Span::raw(format!(" │ SNR {} dB", snr)));
+        }
+        if let Some(busy) = link.channel_busy_pct {
+            signal.push(Span::raw(" │ Busy "));
+            signal.push(Span::styled(
+                format!("{:.0}%", busy),
+                Style::default().fg(threshold_color(busy as f32)),
+            ));
+        }
+        lines.push(Line::from(signal));
+
+        for (dir, rate) in [("TX", &station.tx_bitrate), ("RX", &station.rx_bitrate)] {
+            if let Some(rate) = rate {
+                lines.push(Line::from(format!("  {} {}", dir, rate)));
+            }
+        }
+
+        let retry_color = match link.retry_rate_pct {
+            Some(r) if r >= 30.0 => glances_colors::CRITICAL,
+            Some(r) if r >= 10.0 => glances_colors::WARNING,
+            Some(_) => glances_colors::OK,
+            None => Color::DarkGray,
+        };
+        let lost = link.beacon_loss_delta.unwrap_or(0);
+        lines.push(Line::from(vec![
+            Span::raw("  Retries "),
+            Span::styled(
+                link.retry_rate_pct
+                    .map(|r| format!("{:.1}%", r))
+                    .unwrap_or_else(|| "-".into()),
+                Style::default().fg(retry_color),
+            ),
+            Span::raw(format!(
+                " │ Failed {} │ Beacon loss ",
+                link.tx_failed_delta.unwrap_or(0)
+            )),
+            Span::styled(
+                lost.to_string(),
+                Style::default().fg(if lost > 0 {
+                    glances_colors::CRITICAL
+                } else {
+                    Color::White
+                }),
+            ),
+        ]));
     }
+
+    if let Some(roam) = snapshot.roams.last() {
+        let age = snapshot.timestamp.saturating_sub(roam.timestamp);
+        let age = match age {
+            0..=59 => format!("{}s", age),
+            60..=3599 => format!("{}m", age / 60),
+            _ => format!("{}h", age / 3600),
+        };
+        lines.push(Line::from(Span::styled(
+            format!("↔ {} ago: {}", age, roam),
+            Style::default().fg(glances_colors::WARNING),
+        )));
+    }
+
+    let panel = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(Color::White));
+    f.render_widget(panel, area);
 }
 
 /// System tab: system info, disk details, and network details
@@ -577,7 +1084,12 @@ fn draw_system_tab(f: &mut Frame, app: &App, area: Rect) {
                 .title("System Information"),
         )
         .style(Style::default().fg(Color::White));
-    f.render_widget(sys_info, chunks[0]);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(chunks[0]);
+    f.render_widget(sys_info, top[0]);
+    draw_energy_panel(f, app, top[1]);
 
     // Disk bar
     draw_disk_bar(f, app, chunks[1]);
@@ -623,7 +1135,12 @@ fn draw_system_tab(f: &mut Frame, app: &App, area: Rect) {
     let disks = List::new(disk_items)
         .block(Block::default().borders(Borders::ALL).title("Disks"))
         .style(Style::default().fg(Color::White));
-    f.render_widget(disks, chunks[2]);
+    let disk_row = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(chunks[2]);
+    f.render_widget(disks, disk_row[0]);
+    draw_disk_latency_panel(f, app, disk_row[1]);
 
     // Network bar
     draw_network_bar(f, app, chunks[3]);
@@ -671,7 +1188,16 @@ fn draw_system_tab(f: &mut Frame, app: &App, area: Rect) {
                 .title("Network Interfaces"),
         )
         .style(Style::default().fg(Color::White));
-    f.render_widget(net_list, chunks[4]);
+    if app.wireless.as_ref().is_some_and(|w| !w.links.is_empty()) {
+        let net_row = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(chunks[4]);
+        f.render_widget(net_list, net_row[0]);
+        draw_wireless_panel(f, app, net_row[1]);
+    } else {
+        f.render_widget(net_list, chunks[4]);
+    }
 }
 
 /// Draw all accelerators (GPUs, NPUs, FPGAs, etc.) with detailed metrics
@@ -1348,6 +1874,31 @@ fn draw_network_graph(f: &mut Frame, _app: &App, area: Rect) {
     f.render_widget(net_placeholder, inner_chunks[1]);
 }
 
+/// Per-process network rate cell (received/sent per second)
+fn process_net_cell(app: &App, pid: u32) -> Span<'static> {
+    match app.net_traffic.as_ref().and_then(|n| n.process(pid)) {
+        Some(t) if t.rx_bps + t.tx_bps >= 1.0 => {
+            let total = t.rx_bps + t.tx_bps;
+            let color = if total > 100.0 * 1024.0 * 1024.0 {
+                glances_colors::WARNING
+            } else if total > 1024.0 * 1024.0 {
+                glances_colors::CAREFUL
+            } else {
+                glances_colors::OK
+            };
+            Span::styled(
+                format!(
+                    "{:>6}/{:<6}",
+                    auto_unit(t.rx_bps as u64),
+                    auto_unit(t.tx_bps as u64)
+                ),
+                Style::default().fg(color),
+            )
+        }
+        _ => Span::styled("     -", Style::default().fg(Color::DarkGray)),
+    }
+}
+
 /// Draw GPU processes table (nvtop style)
 fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
     let mode_name = app.process_mode_name();
@@ -1452,6 +2003,12 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                             .fg(glances_colors::TITLE)
                             .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
                     ),
+                    Span::styled(
+                        "NET RX/TX",
+                        Style::default()
+                            .fg(glances_colors::TITLE)
+                            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    ),
                 ])
                 .bottom_margin(1)
             } else {
@@ -1504,6 +2061,12 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                             .fg(glances_colors::TITLE)
                             .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
                     ),
+                    Span::styled(
+                        "NET RX/TX",
+                        Style::default()
+                            .fg(glances_colors::TITLE)
+                            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    ),
                 ])
                 .bottom_margin(1)
             };
@@ -1658,6 +2221,7 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                                     glances_colors::INACTIVE
                                 }),
                             ),
+                            process_net_cell(app, p.pid),
                         ])
                         .style(if is_selected {
                             highlight_style
@@ -1734,6 +2298,7 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                                 },
                                 Style::default().fg(io_color),
                             ),
+                            process_net_cell(app, p.pid),
                         ])
                         .style(if is_selected {
                             highlight_style
@@ -1758,6 +2323,7 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                     Constraint::Length(6), // GM% (GPU mem percentage)
                     Constraint::Length(6), // E/D (enc/dec combined)
                     Constraint::Length(2), // TY (type)
+                    Constraint::Length(13), // NET RX/TX (per second)
                 ]
             } else {
                 vec![
@@ -1769,6 +2335,7 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                     Constraint::Length(7), // MEM
                     Constraint::Length(5), // THR (threads)
                     Constraint::Length(7), // I/O
+                    Constraint::Length(13), // NET RX/TX (per second)
                 ]
             };
 
@@ -2012,6 +2579,178 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(table, area);
 }
 
+/// Connections tab: nethogs-style per-process and per-connection TCP rates
+fn draw_connections_tab(f: &mut Frame, app: &App, area: Rect) {
+    let Some(ref traffic) = app.net_traffic else {
+        let text = if cfg!(target_os = "linux") {
+            "Collecting..."
+        } else {
+            "Per-process network accounting requires Linux (sock_diag)"
+        };
+        let empty = Paragraph::new(text)
+            .block(Block::default().borders(Borders::ALL).title("Connections"))
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+
+    let header_style = Style::default()
+        .fg(glances_colors::TITLE)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let rate = |bps: f64| {
+        if bps >= 1.0 {
+            format!("{:>7}/s", auto_unit(bps as u64))
+        } else {
+            "        -".to_string()
+        }
+    };
+    let rate_color = |bps: f64| {
+        if bps > 100.0 * 1024.0 * 1024.0 {
+            glances_colors::WARNING
+        } else if bps > 1024.0 * 1024.0 {
+            glances_colors::CAREFUL
+        } else if bps >= 1.0 {
+            glances_colors::OK
+        } else {
+            Color::DarkGray
+        }
+    };
+
+    // Per-process totals
+    let header = Row::new(
+        ["PID", "PROGRAM", "CONTAINER / CGROUP", "CONN", "RECEIVED", "SENT"]
+            .map(|h| Span::styled(h, header_style)),
+    )
+    .bottom_margin(1);
+    let max_rows = (chunks[0].height as usize).saturating_sub(4);
+    let rows: Vec<Row> = traffic
+        .processes
+        .iter()
+        .take(max_rows)
+        .map(|p| {
+            let group = match (&p.container_id, p.runtime) {
+                (Some(id), Some(runtime)) => format!("{} {}", runtime, id),
+                (Some(id), None) => id.clone(),
+                _ => p.cgroup.clone().unwrap_or_else(|| "-".into()),
+            };
+            Row::new(vec![
+                Span::styled(format!("{:>7}", p.pid), Style::default().fg(Color::White)),
+                Span::styled(p.name.clone(), Style::default().fg(Color::White)),
+                Span::styled(group, Style::default().fg(Color::DarkGray)),
+                Span::raw(format!("{:>4}", p.connections)),
+                Span::styled(rate(p.rx_bps), Style::default().fg(rate_color(p.rx_bps))),
+                Span::styled(rate(p.tx_bps), Style::default().fg(rate_color(p.tx_bps))),
+            ])
+        })
+        .collect();
+    let (total_rx, total_tx) = traffic.total_bps();
+    let mut title = format!(
+        "Processes (TCP) ↓{} ↑{}",
+        rate(total_rx).trim(),
+        rate(total_tx).trim()
+    );
+    if traffic.unattributed_rx_bps + traffic.unattributed_tx_bps >= 1.0 {
+        title.push_str(&format!(
+            " | unattributed ↓{} ↑{}",
+            rate(traffic.unattributed_rx_bps).trim(),
+            rate(traffic.unattributed_tx_bps).trim()
+        ));
+    }
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(16),
+            Constraint::Min(20),
+            Constraint::Length(5),
+            Constraint::Length(10),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header)
+    .block(Block::default().borders(Borders::ALL).title(title))
+    .column_spacing(1);
+    f.render_widget(table, chunks[0]);
+
+    // Per-connection detail
+    let header = Row::new(
+        [
+            "LOCAL",
+            "REMOTE",
+            "STATE",
+            "PID",
+            "RECEIVED",
+            "SENT",
+            "TOTAL RX/TX",
+            "RTT",
+            "RETR",
+        ]
+        .map(|h| Span::styled(h, header_style)),
+    )
+    .bottom_margin(1);
+    let max_rows = (chunks[1].height as usize).saturating_sub(4);
+    let rows: Vec<Row> = traffic
+        .connections
+        .iter()
+        .take(max_rows)
+        .map(|c| {
+            Row::new(vec![
+                Span::raw(c.local.to_string()),
+                Span::raw(c.remote.to_string()),
+                Span::styled(c.state.to_string(), Style::default().fg(Color::DarkGray)),
+                Span::raw(
+                    c.pid
+                        .map(|p| format!("{:>7}", p))
+                        .unwrap_or_else(|| "      -".into()),
+                ),
+                Span::styled(rate(c.rx_bps), Style::default().fg(rate_color(c.rx_bps))),
+                Span::styled(rate(c.tx_bps), Style::default().fg(rate_color(c.tx_bps))),
+                Span::raw(format!(
+                    "{:>6}/{:<6}",
+                    auto_unit(c.rx_bytes),
+                    auto_unit(c.tx_bytes)
+                )),
+                Span::raw(format!("{:>6.1}ms", c.rtt_us as f64 / 1000.0)),
+                Span::styled(
+                    format!("{:>5}", c.total_retrans),
+                    Style::default().fg(if c.total_retrans > 0 {
+                        glances_colors::WARNING
+                    } else {
+                        Color::DarkGray
+                    }),
+                ),
+            ])
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(22),
+            Constraint::Min(22),
+            Constraint::Length(11),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(13),
+            Constraint::Length(8),
+            Constraint::Length(5),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Connections ({})", traffic.connections.len())),
+    )
+    .column_spacing(1);
+    f.render_widget(table, chunks[1]);
+}
+
 /// Draw footer with tab-aware controls
 fn draw_tab_footer(f: &mut Frame, app: &App, area: Rect) {
     // Show status message if active
@@ -2062,7 +2801,7 @@ fn draw_tab_footer(f: &mut Frame, app: &App, area: Rect) {
         ),
         Span::raw(" Navigate  "),
         Span::styled(
-            "1-8",
+            "1-9",
             Style::default()
                 .fg(glances_colors::TITLE)
                 .add_modifier(Modifier::BOLD),
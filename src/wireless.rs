@@ -0,0 +1,1570 @@
+//! Wireless link quality via nl80211
+//!
+//! [`crate::network_monitor`] sees a WiFi interface as just another set of
+//! byte counters. This module asks the kernel's cfg80211 layer over generic
+//! netlink for what `iw dev <if> link/station dump/survey dump` print: SSID
+//! and BSSID, frequency, channel and width, signal strength and noise, the
+//! current transmit and receive bitrates with MCS index and spatial streams,
+//! transmit retries and failures, and beacon loss.
+//!
+//! [`WirelessMonitor`] keeps the previous reading per interface to turn the
+//! cumulative counters into per-interval rates (retries per 100 packets,
+//! beacons lost, channel busy time) and to detect roaming: a BSSID change
+//! between two samples becomes a [`RoamEvent`], which [`spawn`] publishes as
+//! a [`SystemEvent`].
+//!
+//! Message layouts (`<linux/netlink.h>`, `<linux/genetlink.h>`,
+//! `<linux/nl80211.h>`) are declared here and parsed from byte buffers so
+//! they can be tested on any platform; only the `sys` module talks to the
+//! kernel. None of the queries need privileges.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simonlib::wireless::{WirelessConfig, WirelessMonitor};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = WirelessMonitor::new(WirelessConfig::default())?;
+//! let report = monitor.check("wlan0")?;
+//! if let Some(signal) = report.link.signal_dbm() {
+//!     println!("{:?} via {:?}: {} dBm", report.link.ssid, report.link.bssid, signal);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, SimonError};
+use crate::observability::{event_types, EventCategory, EventManager, SystemEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_ACK: u16 = 0x4;
+const NLM_F_DUMP: u16 = 0x300;
+const NLMSG_HDRLEN: usize = 16;
+const GENL_HDRLEN: usize = 4;
+/// Clears `NLA_F_NESTED` and `NLA_F_NET_BYTEORDER` from attribute types
+const NLA_TYPE_MASK: u16 = 0x3fff;
+
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_GET_STATION: u8 = 17;
+const NL80211_CMD_GET_SURVEY: u8 = 50;
+
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_IFTYPE: u16 = 5;
+const NL80211_ATTR_MAC: u16 = 6;
+const NL80211_ATTR_STA_INFO: u16 = 21;
+const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+const NL80211_ATTR_SSID: u16 = 52;
+const NL80211_ATTR_SURVEY_INFO: u16 = 84;
+/// Transmit power in mBm (1/100 dBm)
+const NL80211_ATTR_WIPHY_TX_POWER_LEVEL: u16 = 98;
+const NL80211_ATTR_CHANNEL_WIDTH: u16 = 159;
+const NL80211_ATTR_CENTER_FREQ1: u16 = 160;
+
+/// `NL80211_IFTYPE_STATION` and `NL80211_IFTYPE_P2P_CLIENT`: the only modes
+/// whose station dump is the access point
+const IFTYPE_STATION: u32 = 2;
+const IFTYPE_P2P_CLIENT: u32 = 8;
+
+// Nested in NL80211_ATTR_STA_INFO (enum nl80211_sta_info)
+const STA_INFO_INACTIVE_TIME: u16 = 1;
+const STA_INFO_RX_BYTES: u16 = 2;
+const STA_INFO_TX_BYTES: u16 = 3;
+const STA_INFO_SIGNAL: u16 = 7;
+const STA_INFO_TX_BITRATE: u16 = 8;
+const STA_INFO_RX_PACKETS: u16 = 9;
+const STA_INFO_TX_PACKETS: u16 = 10;
+const STA_INFO_TX_RETRIES: u16 = 11;
+const STA_INFO_TX_FAILED: u16 = 12;
+const STA_INFO_SIGNAL_AVG: u16 = 13;
+const STA_INFO_RX_BITRATE: u16 = 14;
+const STA_INFO_CONNECTED_TIME: u16 = 16;
+const STA_INFO_BEACON_LOSS: u16 = 18;
+const STA_INFO_RX_BYTES64: u16 = 23;
+const STA_INFO_TX_BYTES64: u16 = 24;
+const STA_INFO_EXPECTED_THROUGHPUT: u16 = 27;
+const STA_INFO_RX_DROP_MISC: u16 = 28;
+const STA_INFO_BEACON_RX: u16 = 29;
+const STA_INFO_BEACON_SIGNAL_AVG: u16 = 30;
+
+// Nested in STA_INFO_TX_BITRATE / STA_INFO_RX_BITRATE (enum nl80211_rate_info)
+const RATE_INFO_BITRATE: u16 = 1;
+const RATE_INFO_MCS: u16 = 2;
+const RATE_INFO_40_MHZ_WIDTH: u16 = 3;
+const RATE_INFO_SHORT_GI: u16 = 4;
+const RATE_INFO_BITRATE32: u16 = 5;
+const RATE_INFO_VHT_MCS: u16 = 6;
+const RATE_INFO_VHT_NSS: u16 = 7;
+const RATE_INFO_80_MHZ_WIDTH: u16 = 8;
+const RATE_INFO_80P80_MHZ_WIDTH: u16 = 9;
+const RATE_INFO_160_MHZ_WIDTH: u16 = 10;
+const RATE_INFO_10_MHZ_WIDTH: u16 = 11;
+const RATE_INFO_5_MHZ_WIDTH: u16 = 12;
+const RATE_INFO_HE_MCS: u16 = 13;
+const RATE_INFO_HE_NSS: u16 = 14;
+const RATE_INFO_320_MHZ_WIDTH: u16 = 18;
+const RATE_INFO_EHT_MCS: u16 = 19;
+const RATE_INFO_EHT_NSS: u16 = 20;
+
+// Nested in NL80211_ATTR_SURVEY_INFO (enum nl80211_survey_info)
+const SURVEY_INFO_FREQUENCY: u16 = 1;
+const SURVEY_INFO_NOISE: u16 = 2;
+const SURVEY_INFO_IN_USE: u16 = 3;
+const SURVEY_INFO_TIME: u16 = 4;
+const SURVEY_INFO_TIME_BUSY: u16 = 5;
+
+/// Frequency band of a channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WifiBand {
+    #[serde(rename = "2.4GHz")]
+    Band2_4GHz,
+    #[serde(rename = "5GHz")]
+    Band5GHz,
+    #[serde(rename = "6GHz")]
+    Band6GHz,
+    #[serde(rename = "60GHz")]
+    Band60GHz,
+}
+
+impl WifiBand {
+    /// Band containing a center frequency
+    pub fn from_frequency(mhz: u32) -> Option<Self> {
+        match mhz {
+            2400..=2500 => Some(Self::Band2_4GHz),
+            4900..=5899 => Some(Self::Band5GHz),
+            5925..=7125 => Some(Self::Band6GHz),
+            57000..=71000 => Some(Self::Band60GHz),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WifiBand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Band2_4GHz => "2.4 GHz",
+            Self::Band5GHz => "5 GHz",
+            Self::Band6GHz => "6 GHz",
+            Self::Band60GHz => "60 GHz",
+        })
+    }
+}
+
+/// IEEE 802.11 channel number of a center frequency
+pub fn frequency_to_channel(mhz: u32) -> Option<u32> {
+    match mhz {
+        2484 => Some(14),
+        2412..=2472 => Some((mhz - 2407) / 5),
+        4910..=4980 => Some((mhz - 4000) / 5),
+        5150..=5895 => Some((mhz - 5000) / 5),
+        5935 => Some(2),
+        5955..=7115 => Some((mhz - 5950) / 5),
+        58320..=70200 => Some((mhz - 56160) / 2160),
+        _ => None,
+    }
+}
+
+/// Channel width in MHz from `enum nl80211_chan_width`; 80+80 counts as 160
+fn channel_width_mhz(width: u32) -> Option<u32> {
+    match width {
+        0 | 1 => Some(20),
+        2 => Some(40),
+        3 => Some(80),
+        4 | 5 => Some(160),
+        6 => Some(5),
+        7 => Some(10),
+        13 => Some(320),
+        _ => None,
+    }
+}
+
+/// `iw`-style name of an `enum nl80211_iftype`
+fn iftype_name(iftype: u32) -> &'static str {
+    match iftype {
+        1 => "IBSS",
+        2 => "managed",
+        3 => "AP",
+        4 => "AP/VLAN",
+        5 => "WDS",
+        6 => "monitor",
+        7 => "mesh point",
+        8 => "P2P-client",
+        9 => "P2P-GO",
+        10 => "P2P-device",
+        11 => "OCB",
+        12 => "NAN",
+        _ => "unknown",
+    }
+}
+
+/// 802.11 PHY generation a bitrate was sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PhyMode {
+    /// 802.11a/b/g rates without MCS
+    Legacy,
+    /// 802.11n (WiFi 4)
+    Ht,
+    /// 802.11ac (WiFi 5)
+    Vht,
+    /// 802.11ax (WiFi 6/6E)
+    He,
+    /// 802.11be (WiFi 7)
+    Eht,
+}
+
+impl fmt::Display for PhyMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Legacy => "legacy",
+            Self::Ht => "HT",
+            Self::Vht => "VHT",
+            Self::He => "HE",
+            Self::Eht => "EHT",
+        })
+    }
+}
+
+/// Rate of the last frame sent or received, as the driver reports it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bitrate {
+    /// Rate in Mbit/s
+    pub mbps: f64,
+    pub mode: PhyMode,
+    /// MCS index; HT indices encode the stream count (MCS 15 = 2 streams)
+    pub mcs: Option<u8>,
+    /// Spatial streams
+    pub nss: Option<u8>,
+    pub width_mhz: u32,
+    /// 400 ns guard interval (HT/VHT)
+    pub short_gi: bool,
+}
+
+impl fmt::Display for Bitrate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1} Mb/s", self.mbps)?;
+        if self.mode != PhyMode::Legacy {
+            write!(f, " {}", self.mode)?;
+            if let Some(mcs) = self.mcs {
+                write!(f, " MCS {}", mcs)?;
+            }
+            if let Some(nss) = self.nss {
+                write!(f, " NSS {}", nss)?;
+            }
+        }
+        write!(f, " {} MHz", self.width_mhz)?;
+        if self.short_gi {
+            f.write_str(" SGI")?;
+        }
+        Ok(())
+    }
+}
+
+/// Counters and rates of the access point as seen from this station
+///
+/// Counters are cumulative since association.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StationInfo {
+    /// Signal of the last received frame
+    pub signal_dbm: Option<i8>,
+    /// Running average of the signal
+    pub signal_avg_dbm: Option<i8>,
+    /// Average signal of beacons only
+    pub beacon_signal_avg_dbm: Option<i8>,
+    pub tx_bitrate: Option<Bitrate>,
+    pub rx_bitrate: Option<Bitrate>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u32,
+    pub tx_packets: u32,
+    /// Transmit retries (one frame can be retried several times)
+    pub tx_retries: Option<u32>,
+    /// Frames given up on after the retry limit
+    pub tx_failed: Option<u32>,
+    /// Times the driver detected loss of the AP's beacons
+    pub beacon_loss: Option<u32>,
+    pub beacon_rx: Option<u64>,
+    /// Received frames dropped for reasons other than duplicates or decryption
+    pub rx_drop_misc: Option<u64>,
+    pub connected_secs: Option<u32>,
+    pub inactive_ms: Option<u32>,
+    /// Driver's estimate of achievable throughput
+    pub expected_throughput_kbps: Option<u32>,
+}
+
+/// Coarse signal rating used for display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignalQuality {
+    Poor,
+    Fair,
+    Good,
+    Excellent,
+}
+
+impl SignalQuality {
+    /// Rating of a signal level: -50 dBm and above is excellent, below -70
+    /// dBm throughput and reliability usually suffer
+    pub fn from_dbm(dbm: i8) -> Self {
+        match dbm {
+            -50..=i8::MAX => Self::Excellent,
+            -60..=-51 => Self::Good,
+            -70..=-61 => Self::Fair,
+            _ => Self::Poor,
+        }
+    }
+}
+
+impl fmt::Display for SignalQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Poor => "poor",
+            Self::Fair => "fair",
+            Self::Good => "good",
+            Self::Excellent => "excellent",
+        })
+    }
+}
+
+/// Current state of one wireless interface
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WirelessLink {
+    pub interface: String,
+    pub ifindex: u32,
+    /// Interface mode, e.g. "managed", "AP", "monitor"
+    pub mode: Option<String>,
+    pub mac_address: Option<String>,
+    pub ssid: Option<String>,
+    /// Access point the station is associated with
+    pub bssid: Option<String>,
+    pub frequency_mhz: Option<u32>,
+    pub channel: Option<u32>,
+    pub band: Option<WifiBand>,
+    pub channel_width_mhz: Option<u32>,
+    /// Center of the whole (possibly bonded) channel
+    pub center_frequency_mhz: Option<u32>,
+    pub tx_power_dbm: Option<f64>,
+    /// Noise floor of the channel in use
+    pub noise_dbm: Option<i8>,
+    /// Share of time the channel was busy over the last interval (or since
+    /// the driver reset its survey when there is no previous sample)
+    pub channel_busy_pct: Option<f64>,
+    /// None while not associated
+    pub station: Option<StationInfo>,
+    /// Transmit retries per 100 transmitted packets over the last interval
+    pub retry_rate_pct: Option<f64>,
+    /// Frames given up on over the last interval
+    pub tx_failed_delta: Option<u32>,
+    /// Beacon loss detections over the last interval
+    pub beacon_loss_delta: Option<u32>,
+}
+
+impl WirelessLink {
+    fn new(interface: &str, ifindex: u32) -> Self {
+        Self {
+            interface: interface.to_string(),
+            ifindex,
+            ..Default::default()
+        }
+    }
+
+    /// Associated with an access point
+    pub fn is_connected(&self) -> bool {
+        self.station.is_some()
+    }
+
+    /// Averaged signal if the driver provides it, else the last frame's
+    pub fn signal_dbm(&self) -> Option<i8> {
+        let station = self.station.as_ref()?;
+        station.signal_avg_dbm.or(station.signal_dbm)
+    }
+
+    /// Signal-to-noise ratio in dB
+    pub fn snr_db(&self) -> Option<i16> {
+        Some(self.signal_dbm()? as i16 - self.noise_dbm? as i16)
+    }
+
+    pub fn quality(&self) -> Option<SignalQuality> {
+        self.signal_dbm().map(SignalQuality::from_dbm)
+    }
+}
+
+/// What changed about the association between two samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoamKind {
+    /// Moved from one BSSID to another
+    Roamed,
+    /// Associated after being disconnected
+    Associated,
+    /// Lost the association
+    Disassociated,
+}
+
+/// A BSSID change on one interface
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoamEvent {
+    pub interface: String,
+    pub kind: RoamKind,
+    pub ssid: Option<String>,
+    pub from_bssid: Option<String>,
+    pub to_bssid: Option<String>,
+    pub from_signal_dbm: Option<i8>,
+    pub to_signal_dbm: Option<i8>,
+    pub from_frequency_mhz: Option<u32>,
+    pub to_frequency_mhz: Option<u32>,
+    /// Unix time in seconds
+    pub timestamp: u64,
+}
+
+impl RoamEvent {
+    /// Compare two readings of the same interface
+    pub fn detect(previous: &WirelessLink, current: &WirelessLink) -> Option<Self> {
+        let kind = match (&previous.bssid, &current.bssid) {
+            (Some(a), Some(b)) if a != b => RoamKind::Roamed,
+            (None, Some(_)) => RoamKind::Associated,
+            (Some(_), None) => RoamKind::Disassociated,
+            _ => return None,
+        };
+        Some(Self {
+            interface: current.interface.clone(),
+            kind,
+            ssid: current.ssid.clone().or_else(|| previous.ssid.clone()),
+            from_bssid: previous.bssid.clone(),
+            to_bssid: current.bssid.clone(),
+            from_signal_dbm: previous.signal_dbm(),
+            to_signal_dbm: current.signal_dbm(),
+            from_frequency_mhz: previous.frequency_mhz,
+            to_frequency_mhz: current.frequency_mhz,
+            timestamp: unix_now(),
+        })
+    }
+
+    pub fn to_system_event(&self) -> SystemEvent {
+        let source = format!("wifi:{}", self.interface);
+        let message = self.to_string();
+        let event = match self.kind {
+            RoamKind::Disassociated => SystemEvent::warning(
+                EventCategory::Network,
+                event_types::network::WIFI_ROAM,
+                &message,
+                &source,
+            ),
+            RoamKind::Roamed | RoamKind::Associated => SystemEvent::info(
+                EventCategory::Network,
+                event_types::network::WIFI_ROAM,
+                &message,
+                &source,
+            ),
+        };
+        let mut event = event
+            .with_metadata("interface", &self.interface)
+            .with_metadata("kind", self.kind)
+            .with_metadata("ssid", &self.ssid)
+            .with_metadata("from_bssid", &self.from_bssid)
+            .with_metadata("to_bssid", &self.to_bssid)
+            .with_metadata("from_signal_dbm", self.from_signal_dbm)
+            .with_metadata("to_signal_dbm", self.to_signal_dbm);
+        event.timestamp = self.timestamp;
+        event
+    }
+}
+
+impl fmt::Display for RoamEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bssid = |b: &Option<String>| b.clone().unwrap_or_else(|| "?".into());
+        let signal = |s: Option<i8>| s.map(|s| format!(" ({} dBm)", s)).unwrap_or_default();
+        let ssid = self
+            .ssid
+            .as_ref()
+            .map(|s| format!(" on '{}'", s))
+            .unwrap_or_default();
+        match self.kind {
+            RoamKind::Roamed => write!(
+                f,
+                "{} roamed{} from {}{} to {}{}",
+                self.interface,
+                ssid,
+                bssid(&self.from_bssid),
+                signal(self.from_signal_dbm),
+                bssid(&self.to_bssid),
+                signal(self.to_signal_dbm)
+            ),
+            RoamKind::Associated => write!(
+                f,
+                "{} associated with {}{}{}",
+                self.interface,
+                bssid(&self.to_bssid),
+                ssid,
+                signal(self.to_signal_dbm)
+            ),
+            RoamKind::Disassociated => write!(
+                f,
+                "{} lost association with {}{}{}",
+                self.interface,
+                bssid(&self.from_bssid),
+                ssid,
+                signal(self.from_signal_dbm)
+            ),
+        }
+    }
+}
+
+/// One reading of an interface and the roam it revealed, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WirelessReport {
+    pub link: WirelessLink,
+    pub roam: Option<RoamEvent>,
+}
+
+/// Latest links and recent roams
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WirelessSnapshot {
+    /// Unix time in seconds
+    pub timestamp: u64,
+    pub links: Vec<WirelessLink>,
+    /// Oldest first
+    pub roams: Vec<RoamEvent>,
+}
+
+/// Monitor configuration (`[wireless]` in the daemon config)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WirelessConfig {
+    pub enabled: bool,
+    /// Sampling interval in milliseconds
+    pub interval_ms: u64,
+    /// Interfaces to watch; empty means every wireless interface
+    pub interfaces: Vec<String>,
+    /// Roams kept in the snapshot
+    pub history: usize,
+}
+
+impl Default for WirelessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 2000,
+            interfaces: Vec::new(),
+            history: 32,
+        }
+    }
+}
+
+/// Channel survey counters for the channel in use
+#[derive(Debug, Clone, Copy, Default)]
+struct ChannelSurvey {
+    frequency_mhz: u32,
+    in_use: bool,
+    noise_dbm: Option<i8>,
+    active_ms: Option<u64>,
+    busy_ms: Option<u64>,
+}
+
+struct Previous {
+    at: Instant,
+    link: WirelessLink,
+    survey: Option<ChannelSurvey>,
+}
+
+/// Samples wireless interfaces over one nl80211 socket, keeping the previous
+/// reading of each to derive interval rates and roams
+pub struct WirelessMonitor {
+    config: WirelessConfig,
+    #[cfg(target_os = "linux")]
+    socket: sys::GenlSocket,
+    previous: HashMap<String, Previous>,
+    links: Vec<WirelessLink>,
+    roams: VecDeque<RoamEvent>,
+}
+
+impl WirelessMonitor {
+    /// Open an nl80211 socket; fails where cfg80211 is not loaded
+    #[cfg(target_os = "linux")]
+    pub fn new(config: WirelessConfig) -> Result<Self> {
+        let socket = sys::GenlSocket::open("nl80211").map_err(|e| match e.raw_os_error() {
+            Some(libc::ENOENT) => {
+                SimonError::FeatureNotAvailable("nl80211 (cfg80211 not loaded)".to_string())
+            }
+            _ => e.into(),
+        })?;
+        Ok(Self {
+            config,
+            socket,
+            previous: HashMap::new(),
+            links: Vec::new(),
+            roams: VecDeque::new(),
+        })
+    }
+
+    /// Open an nl80211 socket; fails where cfg80211 is not loaded
+    #[cfg(not(target_os = "linux"))]
+    pub fn new(_config: WirelessConfig) -> Result<Self> {
+        Err(SimonError::UnsupportedPlatform(
+            "nl80211 is only available on Linux".to_string(),
+        ))
+    }
+
+    /// Read `name` and compare with the previous reading of the same interface
+    pub fn check(&mut self, name: &str) -> Result<WirelessReport> {
+        let (mut link, survey) = self.query(name)?;
+        let previous = self.previous.get(name);
+        apply_deltas(&mut link, survey.as_ref(), previous);
+        let roam = previous.and_then(|p| RoamEvent::detect(&p.link, &link));
+        self.previous.insert(
+            name.to_string(),
+            Previous {
+                at: Instant::now(),
+                link: link.clone(),
+                survey,
+            },
+        );
+        Ok(WirelessReport { link, roam })
+    }
+
+    /// Read every configured interface, returning the roams found
+    ///
+    /// Interfaces that vanish or fail to answer are skipped; the latest links
+    /// are available from [`WirelessMonitor::snapshot`].
+    pub fn sample(&mut self) -> Vec<RoamEvent> {
+        let names = if self.config.interfaces.is_empty() {
+            wireless_interfaces()
+        } else {
+            self.config.interfaces.clone()
+        };
+        let mut links = Vec::new();
+        let mut roams = Vec::new();
+        for name in names {
+            match self.check(&name) {
+                Ok(report) => {
+                    links.push(report.link);
+                    roams.extend(report.roam);
+                }
+                Err(e) => log::debug!("nl80211 query for {} failed: {}", name, e),
+            }
+        }
+        self.previous
+            .retain(|name, _| links.iter().any(|l| &l.interface == name));
+        self.links = links;
+        for roam in &roams {
+            self.roams.push_back(roam.clone());
+        }
+        while self.roams.len() > self.config.history {
+            self.roams.pop_front();
+        }
+        roams
+    }
+
+    /// Links from the latest [`WirelessMonitor::sample`] and recent roams
+    pub fn snapshot(&self) -> WirelessSnapshot {
+        WirelessSnapshot {
+            timestamp: unix_now(),
+            links: self.links.clone(),
+            roams: self.roams.iter().cloned().collect(),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn query(&mut self, name: &str) -> Result<(WirelessLink, Option<ChannelSurvey>)> {
+        let ifindex = sys::ifindex(name).ok_or_else(|| {
+            SimonError::DeviceNotFound(format!("Network interface '{}' not found", name))
+        })?;
+        let mut link = WirelessLink::new(name, ifindex);
+        let replies = self
+            .socket
+            .nl80211(NL80211_CMD_GET_INTERFACE, false, ifindex)
+            .map_err(|e| match e.raw_os_error() {
+                Some(libc::ENODEV) | Some(libc::EOPNOTSUPP) | Some(libc::EINVAL) => {
+                    SimonError::FeatureNotAvailable(format!(
+                        "'{}' is not a wireless interface",
+                        name
+                    ))
+                }
+                _ => e.into(),
+            })?;
+        let mut iftype = None;
+        for reply in &replies {
+            iftype = parse_interface(reply, &mut link).or(iftype);
+        }
+
+        if matches!(iftype, Some(IFTYPE_STATION) | Some(IFTYPE_P2P_CLIENT)) {
+            let stations = self
+                .socket
+                .nl80211(NL80211_CMD_GET_STATION, true, ifindex)?;
+            if let Some((bssid, station)) = stations.iter().find_map(|m| parse_station(m)) {
+                link.bssid = Some(bssid);
+                link.station = Some(station);
+            }
+        }
+
+        // Not every driver implements surveys
+        let survey = self
+            .socket
+            .nl80211(NL80211_CMD_GET_SURVEY, true, ifindex)
+            .ok()
+            .and_then(|replies| {
+                let surveys: Vec<ChannelSurvey> =
+                    replies.iter().filter_map(|m| parse_survey(m)).collect();
+                surveys
+                    .iter()
+                    .find(|s| s.in_use)
+                    .or_else(|| {
+                        surveys
+                            .iter()
+                            .find(|s| Some(s.frequency_mhz) == link.frequency_mhz)
+                    })
+                    .copied()
+            });
+        link.noise_dbm = survey.and_then(|s| s.noise_dbm);
+        Ok((link, survey))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn query(&mut self, _name: &str) -> Result<(WirelessLink, Option<ChannelSurvey>)> {
+        Err(SimonError::UnsupportedPlatform(
+            "nl80211 is only available on Linux".to_string(),
+        ))
+    }
+}
+
+/// Fill the interval fields of `link` from the previous reading
+fn apply_deltas(
+    link: &mut WirelessLink,
+    survey: Option<&ChannelSurvey>,
+    previous: Option<&Previous>,
+) {
+    let busy_pct =
+        |active: u64, busy: u64| (active > 0).then(|| busy as f64 / active as f64 * 100.0);
+    link.channel_busy_pct = survey.and_then(|s| {
+        let (active, busy) = (s.active_ms?, s.busy_ms?);
+        match previous.and_then(|p| p.survey) {
+            Some(prev) if prev.frequency_mhz == s.frequency_mhz => {
+                match (prev.active_ms, prev.busy_ms) {
+                    (Some(pa), Some(pb)) if active > pa && busy >= pb => {
+                        busy_pct(active - pa, busy - pb)
+                    }
+                    _ => busy_pct(active, busy),
+                }
+            }
+            _ => busy_pct(active, busy),
+        }
+    });
+
+    let Some(previous) = previous else {
+        return;
+    };
+    // Counters restart on every association
+    if previous.link.bssid != link.bssid || previous.at.elapsed() > Duration::from_secs(3600) {
+        return;
+    }
+    let (Some(now), Some(before)) = (&link.station, &previous.link.station) else {
+        return;
+    };
+    if let (Some(retries), Some(prev_retries)) = (now.tx_retries, before.tx_retries) {
+        let packets = now.tx_packets.wrapping_sub(before.tx_packets);
+        if packets > 0 {
+            link.retry_rate_pct =
+                Some(retries.wrapping_sub(prev_retries) as f64 / packets as f64 * 100.0);
+        }
+    }
+    if let (Some(failed), Some(prev_failed)) = (now.tx_failed, before.tx_failed) {
+        link.tx_failed_delta = Some(failed.wrapping_sub(prev_failed));
+    }
+    if let (Some(lost), Some(prev_lost)) = (now.beacon_loss, before.beacon_loss) {
+        link.beacon_loss_delta = Some(lost.wrapping_sub(prev_lost));
+    }
+}
+
+/// Whether the kernel exposes `name` as a cfg80211 device
+pub fn is_wireless(name: &str) -> bool {
+    let base = Path::new("/sys/class/net").join(name);
+    base.join("wireless").exists() || base.join("phy80211").exists()
+}
+
+/// Names of all wireless interfaces, sorted
+pub fn wireless_interfaces() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir("/sys/class/net")
+        .map(|dir| {
+            dir.flatten()
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter(|name| is_wireless(name))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn u16_at(buf: &[u8], off: usize) -> u16 {
+    u16::from_ne_bytes([buf[off], buf[off + 1]])
+}
+
+fn u32_at(buf: &[u8], off: usize) -> u32 {
+    u32::from_ne_bytes(buf[off..off + 4].try_into().unwrap())
+}
+
+fn align4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn attr_u8(payload: &[u8]) -> Option<u8> {
+    payload.first().copied()
+}
+
+fn attr_u16(payload: &[u8]) -> Option<u16> {
+    (payload.len() >= 2).then(|| u16_at(payload, 0))
+}
+
+fn attr_u32(payload: &[u8]) -> Option<u32> {
+    (payload.len() >= 4).then(|| u32_at(payload, 0))
+}
+
+fn attr_u64(payload: &[u8]) -> Option<u64> {
+    (payload.len() >= 8).then(|| u64::from_ne_bytes(payload[..8].try_into().unwrap()))
+}
+
+fn attr_dbm(payload: &[u8]) -> Option<i8> {
+    attr_u8(payload).map(|v| v as i8)
+}
+
+fn format_mac(payload: &[u8]) -> Option<String> {
+    (payload.len() == 6).then(|| {
+        payload
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(":")
+    })
+}
+
+/// Iterate the netlink attributes in `buf` as (type, payload)
+fn attrs(buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> + '_ {
+    let mut off = 0;
+    std::iter::from_fn(move || {
+        if off + 4 > buf.len() {
+            return None;
+        }
+        let len = u16_at(buf, off) as usize;
+        let kind = u16_at(buf, off + 2) & NLA_TYPE_MASK;
+        if len < 4 || off + len > buf.len() {
+            return None;
+        }
+        let payload = &buf[off + 4..off + len];
+        off += align4(len);
+        Some((kind, payload))
+    })
+}
+
+/// Append one attribute, padded to 4 bytes
+fn push_attr(buf: &mut Vec<u8>, kind: u16, payload: &[u8]) {
+    buf.extend_from_slice(&((4 + payload.len()) as u16).to_ne_bytes());
+    buf.extend_from_slice(&kind.to_ne_bytes());
+    buf.extend_from_slice(payload);
+    buf.resize(align4(buf.len()), 0);
+}
+
+/// Generic netlink request: netlink header, genl header, attributes
+fn build_request(
+    family: u16,
+    flags: u16,
+    seq: u32,
+    cmd: u8,
+    version: u8,
+    attributes: &[(u16, &[u8])],
+) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(64);
+    buf.extend_from_slice(&0u32.to_ne_bytes()); // length, patched below
+    buf.extend_from_slice(&family.to_ne_bytes());
+    buf.extend_from_slice(&(NLM_F_REQUEST | flags).to_ne_bytes());
+    buf.extend_from_slice(&seq.to_ne_bytes());
+    buf.extend_from_slice(&0u32.to_ne_bytes()); // pid: kernel assigns
+    buf.extend_from_slice(&[cmd, version, 0, 0]);
+    for (kind, payload) in attributes {
+        push_attr(&mut buf, *kind, payload);
+    }
+    let len = buf.len() as u32;
+    buf[..4].copy_from_slice(&len.to_ne_bytes());
+    buf
+}
+
+/// Parse one `recv` worth of replies to request `seq`, appending each
+/// message's attributes (after the genl header) to `out`
+///
+/// Returns true once the request is complete: `NLMSG_DONE` ends a dump and
+/// an acknowledgement ends a single request. A netlink error reply is
+/// returned as the corresponding OS error.
+fn parse_replies(buf: &[u8], seq: u32, out: &mut Vec<Vec<u8>>) -> io::Result<bool> {
+    let mut off = 0;
+    while off + NLMSG_HDRLEN <= buf.len() {
+        let len = u32_at(buf, off) as usize;
+        let kind = u16_at(buf, off + 4);
+        let msg_seq = u32_at(buf, off + 8);
+        if len < NLMSG_HDRLEN || off + len > buf.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "truncated netlink message",
+            ));
+        }
+        let payload = &buf[off + NLMSG_HDRLEN..off + len];
+        off += align4(len);
+        // Stale replies to an earlier, abandoned request
+        if msg_seq != seq {
+            continue;
+        }
+        match kind {
+            NLMSG_DONE => return Ok(true),
+            NLMSG_ERROR => {
+                let errno = if payload.len() >= 4 {
+                    i32::from_ne_bytes(payload[..4].try_into().unwrap())
+                } else {
+                    0
+                };
+                if errno != 0 {
+                    return Err(io::Error::from_raw_os_error(-errno));
+                }
+                return Ok(true);
+            }
+            _ if payload.len() >= GENL_HDRLEN => out.push(payload[GENL_HDRLEN..].to_vec()),
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+fn parse_family_id(attributes: &[u8]) -> Option<u16> {
+    attrs(attributes)
+        .find(|(kind, _)| *kind == CTRL_ATTR_FAMILY_ID)
+        .and_then(|(_, p)| attr_u16(p))
+}
+
+/// Fill `link` from an `NL80211_CMD_GET_INTERFACE` reply, returning the
+/// interface type
+fn parse_interface(attributes: &[u8], link: &mut WirelessLink) -> Option<u32> {
+    let mut iftype = None;
+    for (kind, payload) in attrs(attributes) {
+        match kind {
+            NL80211_ATTR_IFTYPE => {
+                iftype = attr_u32(payload);
+                link.mode = iftype.map(|t| iftype_name(t).to_string());
+            }
+            NL80211_ATTR_MAC => link.mac_address = format_mac(payload),
+            NL80211_ATTR_SSID => {
+                link.ssid =
+                    Some(String::from_utf8_lossy(payload).into_owned()).filter(|s| !s.is_empty())
+            }
+            NL80211_ATTR_WIPHY_FREQ => {
+                link.frequency_mhz = attr_u32(payload);
+                link.channel = link.frequency_mhz.and_then(frequency_to_channel);
+                link.band = link.frequency_mhz.and_then(WifiBand::from_frequency);
+            }
+            NL80211_ATTR_CHANNEL_WIDTH => {
+                link.channel_width_mhz = attr_u32(payload).and_then(channel_width_mhz)
+            }
+            NL80211_ATTR_CENTER_FREQ1 => link.center_frequency_mhz = attr_u32(payload),
+            NL80211_ATTR_WIPHY_TX_POWER_LEVEL => {
+                link.tx_power_dbm = attr_u32(payload).map(|mbm| mbm as i32 as f64 / 100.0)
+            }
+            _ => {}
+        }
+    }
+    iftype
+}
+
+/// Peer address and info from an `NL80211_CMD_GET_STATION` reply
+fn parse_station(attributes: &[u8]) -> Option<(String, StationInfo)> {
+    let mut mac = None;
+    let mut info = None;
+    for (kind, payload) in attrs(attributes) {
+        match kind {
+            NL80211_ATTR_MAC => mac = format_mac(payload),
+            NL80211_ATTR_STA_INFO => info = Some(parse_sta_info(payload)),
+            _ => {}
+        }
+    }
+    Some((mac?, info?))
+}
+
+fn parse_sta_info(nested: &[u8]) -> StationInfo {
+    let mut info = StationInfo::default();
+    let (mut rx32, mut tx32, mut rx64, mut tx64) = (None, None, None, None);
+    for (kind, p) in attrs(nested) {
+        match kind {
+            STA_INFO_INACTIVE_TIME => info.inactive_ms = attr_u32(p),
+            STA_INFO_RX_BYTES => rx32 = attr_u32(p),
+            STA_INFO_TX_BYTES => tx32 = attr_u32(p),
+            STA_INFO_RX_BYTES64 => rx64 = attr_u64(p),
+            STA_INFO_TX_BYTES64 => tx64 = attr_u64(p),
+            STA_INFO_SIGNAL => info.signal_dbm = attr_dbm(p),
+            STA_INFO_SIGNAL_AVG => info.signal_avg_dbm = attr_dbm(p),
+            STA_INFO_BEACON_SIGNAL_AVG => info.beacon_signal_avg_dbm = attr_dbm(p),
+            STA_INFO_TX_BITRATE => info.tx_bitrate = parse_bitrate(p),
+            STA_INFO_RX_BITRATE => info.rx_bitrate = parse_bitrate(p),
+            STA_INFO_RX_PACKETS => info.rx_packets = attr_u32(p).unwrap_or(0),
+            STA_INFO_TX_PACKETS => info.tx_packets = attr_u32(p).unwrap_or(0),
+            STA_INFO_TX_RETRIES => info.tx_retries = attr_u32(p),
+            STA_INFO_TX_FAILED => info.tx_failed = attr_u32(p),
+            STA_INFO_BEACON_LOSS => info.beacon_loss = attr_u32(p),
+            STA_INFO_BEACON_RX => info.beacon_rx = attr_u64(p),
+            STA_INFO_RX_DROP_MISC => info.rx_drop_misc = attr_u64(p),
+            STA_INFO_CONNECTED_TIME => info.connected_secs = attr_u32(p),
+            STA_INFO_EXPECTED_THROUGHPUT => info.expected_throughput_kbps = attr_u32(p),
+            _ => {}
+        }
+    }
+    info.rx_bytes = rx64.or(rx32.map(u64::from)).unwrap_or(0);
+    info.tx_bytes = tx64.or(tx32.map(u64::from)).unwrap_or(0);
+    info
+}
+
+/// Decode a nested `nl80211_rate_info`; None when no rate is reported
+fn parse_bitrate(nested: &[u8]) -> Option<Bitrate> {
+    let mut rate = Bitrate {
+        mbps: 0.0,
+        mode: PhyMode::Legacy,
+        mcs: None,
+        nss: None,
+        width_mhz: 20,
+        short_gi: false,
+    };
+    let (mut units16, mut units32) = (None, None);
+    for (kind, p) in attrs(nested) {
+        match kind {
+            RATE_INFO_BITRATE => units16 = attr_u16(p),
+            RATE_INFO_BITRATE32 => units32 = attr_u32(p),
+            RATE_INFO_MCS => {
+                rate.mode = PhyMode::Ht;
+                rate.mcs = attr_u8(p);
+            }
+            RATE_INFO_VHT_MCS => {
+                rate.mode = PhyMode::Vht;
+                rate.mcs = attr_u8(p);
+            }
+            RATE_INFO_HE_MCS => {
+                rate.mode = PhyMode::He;
+                rate.mcs = attr_u8(p);
+            }
+            RATE_INFO_EHT_MCS => {
+                rate.mode = PhyMode::Eht;
+                rate.mcs = attr_u8(p);
+            }
+            RATE_INFO_VHT_NSS | RATE_INFO_HE_NSS | RATE_INFO_EHT_NSS => rate.nss = attr_u8(p),
+            RATE_INFO_5_MHZ_WIDTH => rate.width_mhz = 5,
+            RATE_INFO_10_MHZ_WIDTH => rate.width_mhz = 10,
+            RATE_INFO_40_MHZ_WIDTH => rate.width_mhz = 40,
+            RATE_INFO_80_MHZ_WIDTH => rate.width_mhz = 80,
+            RATE_INFO_80P80_MHZ_WIDTH | RATE_INFO_160_MHZ_WIDTH => rate.width_mhz = 160,
+            RATE_INFO_320_MHZ_WIDTH => rate.width_mhz = 320,
+            RATE_INFO_SHORT_GI => rate.short_gi = true,
+            _ => {}
+        }
+    }
+    if rate.mode == PhyMode::Ht && rate.nss.is_none() {
+        rate.nss = rate.mcs.map(|mcs| mcs / 8 + 1);
+    }
+    // Units of 100 kbit/s; the 16-bit field saturates above 6.5 Gbit/s
+    let units = units32.or(units16.map(u32::from)).filter(|&u| u > 0)?;
+    rate.mbps = units as f64 / 10.0;
+    Some(rate)
+}
+
+/// One channel of an `NL80211_CMD_GET_SURVEY` dump
+fn parse_survey(attributes: &[u8]) -> Option<ChannelSurvey> {
+    let (_, nested) = attrs(attributes).find(|(kind, _)| *kind == NL80211_ATTR_SURVEY_INFO)?;
+    let mut survey = ChannelSurvey::default();
+    for (kind, p) in attrs(nested) {
+        match kind {
+            SURVEY_INFO_FREQUENCY => survey.frequency_mhz = attr_u32(p)?,
+            SURVEY_INFO_NOISE => survey.noise_dbm = attr_dbm(p),
+            SURVEY_INFO_IN_USE => survey.in_use = true,
+            SURVEY_INFO_TIME => survey.active_ms = attr_u64(p),
+            SURVEY_INFO_TIME_BUSY => survey.busy_ms = attr_u64(p),
+            _ => {}
+        }
+    }
+    (survey.frequency_mhz > 0).then_some(survey)
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use super::*;
+    use std::ffi::CString;
+    use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+
+    /// Generic netlink socket bound to one resolved family
+    pub struct GenlSocket {
+        fd: OwnedFd,
+        family: u16,
+        seq: u32,
+    }
+
+    impl GenlSocket {
+        /// Open a socket and resolve `name` through the genl controller;
+        /// ENOENT if the family is not registered
+        pub fn open(name: &str) -> io::Result<Self> {
+            // SAFETY: plain socket(2) call; the returned descriptor is owned below.
+            let fd = unsafe {
+                libc::socket(
+                    libc::AF_NETLINK,
+                    libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                    libc::NETLINK_GENERIC,
+                )
+            };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: fd is a freshly created, valid descriptor not owned elsewhere.
+            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+            // A wedged driver must not hang the caller
+            let timeout = libc::timeval {
+                tv_sec: 2,
+                tv_usec: 0,
+            };
+            // SAFETY: timeout is a valid timeval for the length passed.
+            unsafe {
+                libc::setsockopt(
+                    fd.as_raw_fd(),
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVTIMEO,
+                    &timeout as *const libc::timeval as *const libc::c_void,
+                    std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+                );
+            }
+            let mut socket = Self {
+                fd,
+                family: GENL_ID_CTRL,
+                seq: 0,
+            };
+            let mut family_name = name.as_bytes().to_vec();
+            family_name.push(0);
+            let replies = socket.transact(
+                GENL_ID_CTRL,
+                CTRL_CMD_GETFAMILY,
+                1,
+                false,
+                &[(CTRL_ATTR_FAMILY_NAME, &family_name)],
+            )?;
+            socket.family = replies
+                .iter()
+                .find_map(|r| parse_family_id(r))
+                .ok_or_else(|| io::Error::from_raw_os_error(libc::ENOENT))?;
+            Ok(socket)
+        }
+
+        /// Run an nl80211 command against one interface
+        pub fn nl80211(&mut self, cmd: u8, dump: bool, ifindex: u32) -> io::Result<Vec<Vec<u8>>> {
+            let index = ifindex.to_ne_bytes();
+            self.transact(self.family, cmd, 0, dump, &[(NL80211_ATTR_IFINDEX, &index)])
+        }
+
+        fn transact(
+            &mut self,
+            family: u16,
+            cmd: u8,
+            version: u8,
+            dump: bool,
+            attributes: &[(u16, &[u8])],
+        ) -> io::Result<Vec<Vec<u8>>> {
+            self.seq = self.seq.wrapping_add(1);
+            let flags = if dump { NLM_F_DUMP } else { NLM_F_ACK };
+            let request = build_request(family, flags, self.seq, cmd, version, attributes);
+            // SAFETY: sockaddr_nl is plain data; all-zero addresses the kernel.
+            let mut kernel: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+            kernel.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+            // SAFETY: request and kernel are valid for the lengths passed.
+            let sent = unsafe {
+                libc::sendto(
+                    self.fd.as_raw_fd(),
+                    request.as_ptr() as *const libc::c_void,
+                    request.len(),
+                    0,
+                    &kernel as *const libc::sockaddr_nl as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+                )
+            };
+            if sent < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut replies = Vec::new();
+            let mut buf = vec![0u8; 64 * 1024];
+            loop {
+                // SAFETY: buf is valid for writes of buf.len() bytes.
+                let n = unsafe {
+                    libc::recv(
+                        self.fd.as_raw_fd(),
+                        buf.as_mut_ptr() as *mut libc::c_void,
+                        buf.len(),
+                        0,
+                    )
+                };
+                if n < 0 {
+                    let err = io::Error::last_os_error();
+                    if err.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    return Err(err);
+                }
+                if n == 0 || parse_replies(&buf[..n as usize], self.seq, &mut replies)? {
+                    return Ok(replies);
+                }
+            }
+        }
+    }
+
+    pub fn ifindex(name: &str) -> Option<u32> {
+        let name = CString::new(name).ok()?;
+        // SAFETY: name is a valid NUL-terminated string.
+        let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        (index != 0).then_some(index)
+    }
+}
+
+/// Links and roam history from the last scan, `None` before the first one
+pub type SharedWirelessSnapshot = Arc<RwLock<Option<WirelessSnapshot>>>;
+
+/// Start sampling on a background thread, emitting every roam to `events`
+pub fn spawn(config: WirelessConfig, events: Arc<EventManager>) -> Result<WirelessHandle> {
+    let interval = Duration::from_millis(config.interval_ms.max(250));
+    let mut monitor = WirelessMonitor::new(config)?;
+    monitor.sample();
+    let snapshot: SharedWirelessSnapshot = Arc::new(RwLock::new(Some(monitor.snapshot())));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let shared = Arc::clone(&snapshot);
+    let flag = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("simon-wireless".into())
+        .spawn(move || {
+            while !flag.load(Ordering::SeqCst) {
+                let deadline = Instant::now() + interval;
+                while !flag.load(Ordering::SeqCst) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(100).min(interval));
+                }
+                if flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                for roam in monitor.sample() {
+                    events.emit(roam.to_system_event());
+                }
+                if let Ok(mut slot) = shared.write() {
+                    *slot = Some(monitor.snapshot());
+                }
+            }
+        })
+        .map_err(|e| SimonError::Other(format!("Failed to spawn wireless monitor thread: {}", e)))?;
+
+    Ok(WirelessHandle {
+        snapshot,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+/// Handle to a running wireless monitor thread
+pub struct WirelessHandle {
+    snapshot: SharedWirelessSnapshot,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WirelessHandle {
+    /// Shared snapshot slot for readers
+    pub fn snapshot(&self) -> SharedWirelessSnapshot {
+        Arc::clone(&self.snapshot)
+    }
+
+    /// Stop monitoring and wait for the thread to exit
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for WirelessHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attr(kind: u16, payload: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        push_attr(&mut buf, kind, payload);
+        buf
+    }
+
+    fn nested(kind: u16, children: &[Vec<u8>]) -> Vec<u8> {
+        attr(kind, &children.concat())
+    }
+
+    const AP: [u8; 6] = [0x02, 0x11, 0x22, 0x33, 0x44, 0x55];
+
+    fn station_reply(retries: u32, tx_packets: u32, beacon_loss: u32) -> Vec<u8> {
+        let info = nested(
+            NL80211_ATTR_STA_INFO,
+            &[
+                attr(STA_INFO_SIGNAL, &[(-61i8) as u8]),
+                attr(STA_INFO_SIGNAL_AVG, &[(-58i8) as u8]),
+                attr(STA_INFO_RX_BYTES, &7u32.to_ne_bytes()),
+                attr(STA_INFO_RX_BYTES64, &5_000_000_000u64.to_ne_bytes()),
+                attr(STA_INFO_TX_PACKETS, &tx_packets.to_ne_bytes()),
+                attr(STA_INFO_TX_RETRIES, &retries.to_ne_bytes()),
+                attr(STA_INFO_TX_FAILED, &3u32.to_ne_bytes()),
+                attr(STA_INFO_BEACON_LOSS, &beacon_loss.to_ne_bytes()),
+                nested(
+                    STA_INFO_TX_BITRATE,
+                    &[
+                        attr(RATE_INFO_BITRATE, &8667u16.to_ne_bytes()),
+                        attr(RATE_INFO_BITRATE32, &8667u32.to_ne_bytes()),
+                        attr(RATE_INFO_VHT_MCS, &[9]),
+                        attr(RATE_INFO_VHT_NSS, &[2]),
+                        attr(RATE_INFO_80_MHZ_WIDTH, &[]),
+                        attr(RATE_INFO_SHORT_GI, &[]),
+                    ],
+                ),
+                nested(
+                    STA_INFO_RX_BITRATE,
+                    &[
+                        attr(RATE_INFO_BITRATE, &1300u16.to_ne_bytes()),
+                        attr(RATE_INFO_MCS, &[15]),
+                        attr(RATE_INFO_40_MHZ_WIDTH, &[]),
+                    ],
+                ),
+            ],
+        );
+        [
+            attr(NL80211_ATTR_MAC, &AP),
+            attr(NL80211_ATTR_IFINDEX, &3u32.to_ne_bytes()),
+            info,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_channels_and_bands() {
+        assert_eq!(frequency_to_channel(2412), Some(1));
+        assert_eq!(frequency_to_channel(2484), Some(14));
+        assert_eq!(frequency_to_channel(5180), Some(36));
+        assert_eq!(frequency_to_channel(5825), Some(165));
+        assert_eq!(frequency_to_channel(5955), Some(1));
+        assert_eq!(frequency_to_channel(6415), Some(93));
+        assert_eq!(frequency_to_channel(60480), Some(2));
+        assert_eq!(frequency_to_channel(3000), None);
+        assert_eq!(WifiBand::from_frequency(2437), Some(WifiBand::Band2_4GHz));
+        assert_eq!(WifiBand::from_frequency(5500), Some(WifiBand::Band5GHz));
+        assert_eq!(WifiBand::from_frequency(6115), Some(WifiBand::Band6GHz));
+        assert_eq!(SignalQuality::from_dbm(-45), SignalQuality::Excellent);
+        assert_eq!(SignalQuality::from_dbm(-60), SignalQuality::Good);
+        assert_eq!(SignalQuality::from_dbm(-75), SignalQuality::Poor);
+    }
+
+    #[test]
+    fn test_parse_interface() {
+        let reply = [
+            attr(NL80211_ATTR_IFINDEX, &3u32.to_ne_bytes()),
+            attr(NL80211_ATTR_IFTYPE, &IFTYPE_STATION.to_ne_bytes()),
+            attr(NL80211_ATTR_MAC, &[0xa4, 0xb1, 0xc1, 0x00, 0x00, 0x01]),
+            attr(NL80211_ATTR_SSID, b"lab-5g"),
+            attr(NL80211_ATTR_WIPHY_FREQ, &5180u32.to_ne_bytes()),
+            attr(NL80211_ATTR_CHANNEL_WIDTH, &3u32.to_ne_bytes()),
+            attr(NL80211_ATTR_CENTER_FREQ1, &5210u32.to_ne_bytes()),
+            attr(NL80211_ATTR_WIPHY_TX_POWER_LEVEL, &2200u32.to_ne_bytes()),
+        ]
+        .concat();
+        let mut link = WirelessLink::new("wlan0", 3);
+        assert_eq!(parse_interface(&reply, &mut link), Some(IFTYPE_STATION));
+        assert_eq!(link.mode.as_deref(), Some("managed"));
+        assert_eq!(link.mac_address.as_deref(), Some("a4:b1:c1:00:00:01"));
+        assert_eq!(link.ssid.as_deref(), Some("lab-5g"));
+        assert_eq!(link.channel, Some(36));
+        assert_eq!(link.band, Some(WifiBand::Band5GHz));
+        assert_eq!(link.channel_width_mhz, Some(80));
+        assert_eq!(link.center_frequency_mhz, Some(5210));
+        assert_eq!(link.tx_power_dbm, Some(22.0));
+    }
+
+    #[test]
+    fn test_parse_station_and_bitrates() {
+        let (bssid, info) = parse_station(&station_reply(40, 1000, 0)).unwrap();
+        assert_eq!(bssid, "02:11:22:33:44:55");
+        assert_eq!(info.signal_dbm, Some(-61));
+        assert_eq!(info.signal_avg_dbm, Some(-58));
+        // 64-bit counter wins over the wrapped 32-bit one
+        assert_eq!(info.rx_bytes, 5_000_000_000);
+        assert_eq!(info.tx_packets, 1000);
+        assert_eq!(info.tx_failed, Some(3));
+
+        let tx = info.tx_bitrate.unwrap();
+        assert_eq!(tx.mode, PhyMode::Vht);
+        assert_eq!((tx.mcs, tx.nss, tx.width_mhz), (Some(9), Some(2), 80));
+        assert!(tx.short_gi);
+        assert_eq!(tx.to_string(), "866.7 Mb/s VHT MCS 9 NSS 2 80 MHz SGI");
+
+        // HT: stream count is encoded in the MCS index
+        let rx = info.rx_bitrate.unwrap();
+        assert_eq!(rx.mode, PhyMode::Ht);
+        assert_eq!((rx.mcs, rx.nss, rx.width_mhz), (Some(15), Some(2), 40));
+        assert_eq!(rx.mbps, 130.0);
+
+        let survey = parse_survey(&nested(
+            NL80211_ATTR_SURVEY_INFO,
+            &[
+                attr(SURVEY_INFO_FREQUENCY, &5180u32.to_ne_bytes()),
+                attr(SURVEY_INFO_NOISE, &[(-92i8) as u8]),
+                attr(SURVEY_INFO_IN_USE, &[]),
+                attr(SURVEY_INFO_TIME, &1000u64.to_ne_bytes()),
+                attr(SURVEY_INFO_TIME_BUSY, &250u64.to_ne_bytes()),
+            ],
+        ))
+        .unwrap();
+        assert!(survey.in_use);
+        assert_eq!(survey.noise_dbm, Some(-92));
+    }
+
+    #[test]
+    fn test_replies_and_errors() {
+        let message = |kind: u16, seq: u32, payload: &[u8]| {
+            let mut buf = Vec::new();
+            buf.extend_from_slice(&((NLMSG_HDRLEN + payload.len()) as u32).to_ne_bytes());
+            buf.extend_from_slice(&kind.to_ne_bytes());
+            buf.extend_from_slice(&0u16.to_ne_bytes());
+            buf.extend_from_slice(&seq.to_ne_bytes());
+            buf.extend_from_slice(&0u32.to_ne_bytes());
+            buf.extend_from_slice(payload);
+            buf.resize(align4(buf.len()), 0);
+            buf
+        };
+        let genl =
+            |attributes: &[u8]| [&[NL80211_CMD_GET_STATION, 1, 0, 0][..], attributes].concat();
+
+        let mut out = Vec::new();
+        let dump = [
+            message(
+                30,
+                6,
+                &genl(&attr(NL80211_ATTR_IFINDEX, &1u32.to_ne_bytes())),
+            ),
+            message(
+                30,
+                7,
+                &genl(&attr(NL80211_ATTR_IFINDEX, &3u32.to_ne_bytes())),
+            ),
+            message(NLMSG_DONE, 7, &0i32.to_ne_bytes()),
+        ]
+        .concat();
+        assert!(parse_replies(&dump, 7, &mut out).unwrap());
+        // The stale reply with sequence 6 is skipped
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0], attr(NL80211_ATTR_IFINDEX, &3u32.to_ne_bytes()));
+
+        let enodev = message(NLMSG_ERROR, 8, &(-libc::ENODEV).to_ne_bytes());
+        let err = parse_replies(&enodev, 8, &mut out).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENODEV));
+
+        let ack = message(NLMSG_ERROR, 9, &0i32.to_ne_bytes());
+        assert!(parse_replies(&ack, 9, &mut out).unwrap());
+
+        let request = build_request(
+            GENL_ID_CTRL,
+            NLM_F_ACK,
+            1,
+            CTRL_CMD_GETFAMILY,
+            1,
+            &[(CTRL_ATTR_FAMILY_NAME, b"nl80211\0")],
+        );
+        assert_eq!(u32_at(&request, 0) as usize, request.len());
+        assert_eq!(request.len(), NLMSG_HDRLEN + GENL_HDRLEN + 12);
+        assert_eq!(
+            attrs(&request[NLMSG_HDRLEN + GENL_HDRLEN..]).next(),
+            Some((CTRL_ATTR_FAMILY_NAME, &b"nl80211\0"[..]))
+        );
+    }
+
+    #[test]
+    fn test_interval_rates_and_roams() {
+        let link = |bssid: Option<&str>, reply: Vec<u8>| {
+            let mut link = WirelessLink::new("wlan0", 3);
+            link.ssid = Some("lab".into());
+            if let Some(bssid) = bssid {
+                link.bssid = Some(bssid.into());
+                link.station = parse_station(&reply).map(|(_, s)| s);
+            }
+            link
+        };
+        let survey = |active: u64, busy: u64| ChannelSurvey {
+            frequency_mhz: 5180,
+            in_use: true,
+            noise_dbm: Some(-92),
+            active_ms: Some(active),
+            busy_ms: Some(busy),
+        };
+
+        let first = link(Some("02:11:22:33:44:55"), station_reply(40, 1000, 1));
+        let previous = Previous {
+            at: Instant::now(),
+            link: first.clone(),
+            survey: Some(survey(1000, 100)),
+        };
+        let mut second = link(Some("02:11:22:33:44:55"), station_reply(90, 1200, 3));
+        second.noise_dbm = Some(-92);
+        apply_deltas(&mut second, Some(&survey(2000, 600)), Some(&previous));
+        assert_eq!(second.retry_rate_pct, Some(25.0));
+        assert_eq!(second.beacon_loss_delta, Some(2));
+        assert_eq!(second.tx_failed_delta, Some(0));
+        assert_eq!(second.channel_busy_pct, Some(50.0));
+        assert_eq!(second.snr_db(), Some(34));
+        assert!(RoamEvent::detect(&first, &second).is_none());
+
+        // Counters of a new association are not compared with the old one
+        let mut roamed = link(Some("02:aa:bb:cc:dd:ee"), station_reply(5, 10, 0));
+        apply_deltas(&mut roamed, None, Some(&previous));
+        assert_eq!(roamed.retry_rate_pct, None);
+        let roam = RoamEvent::detect(&second, &roamed).unwrap();
+        assert_eq!(roam.kind, RoamKind::Roamed);
+        assert_eq!(roam.from_bssid.as_deref(), Some("02:11:22:33:44:55"));
+        assert_eq!(roam.to_bssid.as_deref(), Some("02:aa:bb:cc:dd:ee"));
+        assert_eq!(
+            roam.to_string(),
+            "wlan0 roamed on 'lab' from 02:11:22:33:44:55 (-58 dBm) to 02:aa:bb:cc:dd:ee (-58 dBm)"
+        );
+        let event = roam.to_system_event();
+        assert_eq!(event.event_type, event_types::network::WIFI_ROAM);
+
+        let gone = link(None, Vec::new());
+        assert_eq!(
+            RoamEvent::detect(&roamed, &gone).map(|r| r.kind),
+            Some(RoamKind::Disassociated)
+        );
+        assert_eq!(
+            RoamEvent::detect(&gone, &roamed).map(|r| r.kind),
+            Some(RoamKind::Associated)
+        );
+    }
+}